@@ -0,0 +1,127 @@
+//! Reference MCP server for trying out clients and for the CLI tests.
+//!
+//! Serves, over stdio:
+//! - an `echo` tool that returns its `message` argument,
+//! - a static `echo://readme` resource,
+//! - a `greeting` prompt taking a `name` argument.
+//!
+//! Point a client at it with e.g. `fastmcp inspect fastmcp-echo-server`.
+
+use std::collections::HashMap;
+
+use fastmcp_core::{McpContext, McpError, McpResult};
+use fastmcp_protocol::{
+    Content, Prompt, PromptArgument, PromptMessage, Resource, ResourceContent, Role, Tool,
+};
+use fastmcp_server::{PromptHandler, ResourceHandler, Server, ToolHandler};
+
+const README_URI: &str = "echo://readme";
+const README: &str = "fastmcp-echo-server: call the `echo` tool with a `message`.";
+
+/// Returns its `message` argument unchanged.
+struct EchoTool;
+
+impl ToolHandler for EchoTool {
+    fn definition(&self) -> Tool {
+        Tool {
+            name: "echo".to_string(),
+            description: Some("Echo the message back".to_string()),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "message": {"type": "string", "description": "Text to echo"}
+                },
+                "required": ["message"]
+            }),
+            output_schema: None,
+            icon: None,
+            version: None,
+            tags: vec![],
+            annotations: None,
+        }
+    }
+
+    fn call(&self, _ctx: &McpContext, arguments: serde_json::Value) -> McpResult<Vec<Content>> {
+        let message = arguments
+            .get("message")
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| McpError::invalid_params("missing string argument 'message'"))?;
+        Ok(vec![Content::Text {
+            text: message.to_string(),
+        }])
+    }
+}
+
+/// Fixed text describing this server.
+struct ReadmeResource;
+
+impl ResourceHandler for ReadmeResource {
+    fn definition(&self) -> Resource {
+        Resource {
+            uri: README_URI.to_string(),
+            name: "Readme".to_string(),
+            description: Some("What this server does".to_string()),
+            mime_type: Some("text/plain".to_string()),
+            icon: None,
+            version: None,
+            tags: vec![],
+        }
+    }
+
+    fn read(&self, _ctx: &McpContext) -> McpResult<Vec<ResourceContent>> {
+        Ok(vec![ResourceContent {
+            uri: README_URI.to_string(),
+            mime_type: Some("text/plain".to_string()),
+            text: Some(README.to_string()),
+            blob: None,
+            etag: None,
+            last_modified: None,
+        }])
+    }
+}
+
+/// Asks the model to greet `name`.
+struct GreetingPrompt;
+
+impl PromptHandler for GreetingPrompt {
+    fn definition(&self) -> Prompt {
+        Prompt {
+            name: "greeting".to_string(),
+            description: Some("Generate a friendly greeting".to_string()),
+            arguments: vec![PromptArgument {
+                name: "name".to_string(),
+                description: Some("Who to greet".to_string()),
+                required: true,
+            }],
+            icon: None,
+            version: None,
+            tags: vec![],
+        }
+    }
+
+    fn get(
+        &self,
+        _ctx: &McpContext,
+        arguments: HashMap<String, String>,
+    ) -> McpResult<Vec<PromptMessage>> {
+        let name = arguments
+            .get("name")
+            .ok_or_else(|| McpError::invalid_params("missing argument 'name'"))?;
+        Ok(vec![PromptMessage {
+            role: Role::User,
+            content: Content::Text {
+                text: format!("Please greet {name} in a friendly way."),
+            },
+        }])
+    }
+}
+
+fn main() {
+    Server::new("fastmcp-echo-server", env!("CARGO_PKG_VERSION"))
+        .tool(EchoTool)
+        .resource(ReadmeResource)
+        .prompt(GreetingPrompt)
+        .without_banner()
+        .build()
+        .run_stdio();
+}
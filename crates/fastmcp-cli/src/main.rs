@@ -5,13 +5,15 @@
 //! - `inspect` - Inspect a server's capabilities
 //! - `install` - Install server config for Claude Desktop etc.
 //! - `tasks` - Manage background tasks on MCP servers
+//! - `logs` - Run a server and follow its filtered log output
+//! - `pipe` - Send one request (or batch) from stdin to a server
 
 #![forbid(unsafe_code)]
 
 use std::collections::HashMap;
 use std::env;
-use std::io::{self, Write};
-use std::path::PathBuf;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
 use std::process::{Command, ExitCode, Stdio};
 
 use clap::{Parser, Subcommand};
@@ -20,7 +22,7 @@ use serde::{Deserialize, Serialize};
 use fastmcp_client::Client;
 use fastmcp_console::rich_rust::prelude::*;
 use fastmcp_core::McpResult;
-use fastmcp_protocol::TaskStatus;
+use fastmcp_protocol::{RegistryDocument, TaskStatus};
 
 /// FastMCP CLI - Run, inspect, and install MCP servers.
 #[derive(Parser)]
@@ -53,6 +55,10 @@ enum Commands {
         /// Environment variables (KEY=VALUE format).
         #[arg(long, short = 'e')]
         env: Vec<String>,
+
+        /// Load environment variables from a dotenv-style file (`-e` wins).
+        #[arg(long)]
+        env_file: Option<PathBuf>,
     },
 
     /// Inspect an MCP server's capabilities.
@@ -74,6 +80,10 @@ enum Commands {
         /// Output file (default: stdout).
         #[arg(long, short = 'o')]
         output: Option<PathBuf>,
+
+        /// Show tool parameters and prompt arguments in text output.
+        #[arg(long, short = 's')]
+        schemas: bool,
     },
 
     /// Install server configuration into Claude Desktop or other clients.
@@ -94,6 +104,17 @@ enum Commands {
         #[arg(long, short = 't', default_value = "claude")]
         target: InstallTarget,
 
+        /// Environment variables for the server (KEY=VALUE format).
+        ///
+        /// `${VAR}` in a value is replaced with VAR from the current
+        /// environment at install time.
+        #[arg(long, short = 'e')]
+        env: Vec<String>,
+
+        /// Keep `${VAR}` references in env values literally instead of expanding them.
+        #[arg(long)]
+        no_expand: bool,
+
         /// Just print the config, don't modify any files.
         #[arg(long)]
         dry_run: bool,
@@ -146,6 +167,50 @@ enum Commands {
         json: bool,
     },
 
+    /// Run a server and follow its log output.
+    ///
+    /// Spawns the server like `run` (stdin/stdout stay connected), parses each
+    /// stderr line as a JSON or rich/plain log record, and re-emits on stderr
+    /// the records at or above the given level.
+    ///
+    /// Example: fastmcp logs --level warn --target fastmcp::server ./my-server
+    Logs {
+        /// Server command or path.
+        server: String,
+
+        /// Arguments to pass to the server.
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+
+        /// Minimum level to show (error, warn, info, debug, trace).
+        #[arg(long, short = 'l', default_value = "info")]
+        level: LogsLevel,
+
+        /// Only show records whose target contains this substring.
+        #[arg(long, short = 't')]
+        target: Option<String>,
+
+        /// Re-emit records as JSON lines.
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Send a single JSON-RPC request from stdin to a server.
+    ///
+    /// Spawns the server, performs the initialize handshake, reads one JSON
+    /// object (or an array for a batch) from stdin, and prints the response
+    /// (or array of responses) to stdout. Notifications produce no output.
+    ///
+    /// Example: echo '{"jsonrpc":"2.0","id":1,"method":"tools/list"}' | fastmcp pipe ./my-server
+    Pipe {
+        /// Server command or path.
+        server: String,
+
+        /// Arguments to pass to the server.
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
     /// Run server in development mode with hot reloading.
     ///
     /// Watches source files and automatically rebuilds and restarts the server on changes.
@@ -189,6 +254,10 @@ enum Commands {
         #[arg(long, short = 'e')]
         env: Vec<String>,
 
+        /// Load environment variables from a dotenv-style file (`-e` wins).
+        #[arg(long)]
+        env_file: Option<PathBuf>,
+
         /// Show detailed output.
         #[arg(long, short = 'v')]
         verbose: bool,
@@ -415,6 +484,45 @@ impl std::str::FromStr for InstallTarget {
     }
 }
 
+/// Log level filter for the logs command, ordered from most to least severe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum LogsLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogsLevel {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "ERROR",
+            Self::Warn => "WARN",
+            Self::Info => "INFO",
+            Self::Debug => "DEBUG",
+            Self::Trace => "TRACE",
+        }
+    }
+}
+
+impl std::str::FromStr for LogsLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "error" => Ok(Self::Error),
+            "warn" | "warning" => Ok(Self::Warn),
+            "info" => Ok(Self::Info),
+            "debug" => Ok(Self::Debug),
+            "trace" => Ok(Self::Trace),
+            _ => Err(format!(
+                "Unknown level: {s}. Expected: error, warn, info, debug, trace"
+            )),
+        }
+    }
+}
+
 fn main() -> ExitCode {
     let cli = Cli::parse();
 
@@ -424,20 +532,24 @@ fn main() -> ExitCode {
             args,
             cwd,
             env,
-        } => cmd_run(&server, &args, cwd.as_deref(), &env),
+            env_file,
+        } => cmd_run(&server, &args, cwd.as_deref(), &env, env_file.as_deref()),
         Commands::Inspect {
             server,
             args,
             format,
             output,
-        } => cmd_inspect(&server, &args, format, output.as_deref()),
+            schemas,
+        } => cmd_inspect(&server, &args, format, output.as_deref(), schemas),
         Commands::Install {
             name,
             server,
             args,
             target,
+            env,
+            no_expand,
             dry_run,
-        } => cmd_install(&name, &server, &args, target, dry_run),
+        } => cmd_install(&name, &server, &args, &env, !no_expand, target, dry_run),
         Commands::List {
             target,
             config,
@@ -451,6 +563,22 @@ fn main() -> ExitCode {
             verbose,
             json,
         } => cmd_test(&server, &args, timeout, verbose, json),
+        Commands::Logs {
+            server,
+            args,
+            level,
+            target,
+            json,
+        } => cmd_logs(
+            &server,
+            &args,
+            &LogFilter {
+                level,
+                target,
+                json,
+            },
+        ),
+        Commands::Pipe { server, args } => cmd_pipe(&server, &args),
         Commands::Dev {
             target,
             host,
@@ -462,6 +590,7 @@ fn main() -> ExitCode {
             debounce,
             clear,
             env,
+            env_file,
             verbose,
         } => cmd_dev(DevConfig {
             target,
@@ -474,6 +603,7 @@ fn main() -> ExitCode {
             debounce_ms: debounce,
             clear,
             env,
+            env_file,
             verbose,
         }),
         Commands::Tasks { action } => cmd_tasks(action),
@@ -494,7 +624,10 @@ fn cmd_run(
     args: &[String],
     cwd: Option<&std::path::Path>,
     env_vars: &[String],
+    env_file: Option<&Path>,
 ) -> McpResult<()> {
+    let env = resolve_child_env(env_file, env_vars)?;
+
     let mut cmd = Command::new(server);
     cmd.args(args)
         .stdin(Stdio::inherit())
@@ -505,18 +638,237 @@ fn cmd_run(
         cmd.current_dir(dir);
     }
 
-    // Parse and set environment variables
+    cmd.envs(&env);
+
+    let mut child = cmd.spawn().map_err(|e| {
+        fastmcp_core::McpError::internal_error(format!("Failed to start server: {e}"))
+    })?;
+
+    let status = child.wait().map_err(|e| {
+        fastmcp_core::McpError::internal_error(format!("Failed to wait for server: {e}"))
+    })?;
+
+    if !status.success() {
+        if let Some(code) = status.code() {
+            return Err(fastmcp_core::McpError::internal_error(format!(
+                "Server exited with code {code}"
+            )));
+        }
+        return Err(fastmcp_core::McpError::internal_error(
+            "Server terminated by signal",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Builds the extra environment for a server process: variables from
+/// `env_file`, then `-e KEY=VALUE` flags, which override the file.
+fn resolve_child_env(
+    env_file: Option<&Path>,
+    env_vars: &[String],
+) -> McpResult<HashMap<String, String>> {
+    let mut env = HashMap::new();
+    if let Some(path) = env_file {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            fastmcp_core::McpError::invalid_params(format!(
+                "Failed to read env file {}: {e}",
+                path.display()
+            ))
+        })?;
+        let entries = parse_env_file(&content).map_err(|e| {
+            fastmcp_core::McpError::invalid_params(format!("{}: {e}", path.display()))
+        })?;
+        env.extend(entries);
+    }
+
     for env_var in env_vars {
         if let Some((key, value)) = env_var.split_once('=') {
-            cmd.env(key, value);
+            env.insert(key.to_string(), value.to_string());
         } else {
             eprintln!("Warning: Invalid env var format (expected KEY=VALUE): {env_var}");
         }
     }
+    Ok(env)
+}
 
-    let mut child = cmd.spawn().map_err(|e| {
-        fastmcp_core::McpError::internal_error(format!("Failed to start server: {e}"))
+/// Parses a dotenv-style file into `(key, value)` pairs in file order.
+///
+/// Each non-blank line that is not a `#` comment holds `KEY=VALUE`, optionally
+/// prefixed with `export`. Values may be double-quoted (with `\n`, `\t`,
+/// `\"` and `\\` escapes) or single-quoted (taken literally); an unquoted
+/// value ends at a ` #` comment. Errors name the offending line.
+fn parse_env_file(content: &str) -> Result<Vec<(String, String)>, String> {
+    let mut entries = Vec::new();
+    for (index, raw) in content.lines().enumerate() {
+        let line_no = index + 1;
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("line {line_no}: expected KEY=VALUE"));
+        };
+        let key = key.trim();
+        let valid_key = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_key {
+            return Err(format!("line {line_no}: invalid variable name '{key}'"));
+        }
+        let value = parse_env_value(value).map_err(|e| format!("line {line_no}: {e}"))?;
+        entries.push((key.to_string(), value));
+    }
+    Ok(entries)
+}
+
+/// Parses the value part of one env file line.
+fn parse_env_value(raw: &str) -> Result<String, String> {
+    let value = raw.trim_start();
+    let (parsed, rest) = match value.chars().next() {
+        Some('"') => {
+            let mut parsed = String::new();
+            let mut chars = value[1..].char_indices();
+            let mut end = None;
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    '"' => {
+                        end = Some(i + 2);
+                        break;
+                    }
+                    '\\' => match chars.next() {
+                        Some((_, 'n')) => parsed.push('\n'),
+                        Some((_, 't')) => parsed.push('\t'),
+                        Some((_, escaped)) => parsed.push(escaped),
+                        None => break,
+                    },
+                    c => parsed.push(c),
+                }
+            }
+            let end = end.ok_or("unterminated double-quoted value")?;
+            (parsed, &value[end..])
+        }
+        Some('\'') => {
+            let len = value[1..]
+                .find('\'')
+                .ok_or("unterminated single-quoted value")?;
+            (value[1..=len].to_string(), &value[len + 2..])
+        }
+        _ => {
+            let end = raw
+                .match_indices('#')
+                .find(|(i, _)| raw[..*i].ends_with(char::is_whitespace))
+                .map_or(raw.len(), |(i, _)| i);
+            return Ok(raw[..end].trim().to_string());
+        }
+    };
+
+    let rest = rest.trim_start();
+    if rest.is_empty() || rest.starts_with('#') {
+        Ok(parsed)
+    } else {
+        Err(format!("unexpected text after quoted value: '{rest}'"))
+    }
+}
+
+/// Which log records the logs command passes through, and how.
+#[derive(Debug, Clone)]
+struct LogFilter {
+    level: LogsLevel,
+    target: Option<String>,
+    json: bool,
+}
+
+/// A log record parsed from one line of server stderr.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct LogRecord {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timestamp: Option<String>,
+    level: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<String>,
+    message: String,
+}
+
+/// Pipe command: send one request or batch read from stdin and print the response.
+fn cmd_pipe(server: &str, args: &[String]) -> McpResult<()> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input).map_err(|e| {
+        fastmcp_core::McpError::internal_error(format!("Failed to read stdin: {e}"))
+    })?;
+    // Parse before spawning so malformed input never starts the server
+    let (requests, batch) = parse_pipe_input(&input)?;
+
+    let args_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+    let mut client = fastmcp_client::ClientBuilder::new().connect_stdio(server, &args_refs)?;
+
+    let mut responses = Vec::with_capacity(requests.len());
+    for request in requests {
+        if let Some(response) = client.send_raw(request)? {
+            responses.push(response);
+        }
+    }
+    client.close();
+
+    // Like JSON-RPC itself, stay silent when nothing warrants a response
+    let output = match (batch, responses.as_slice()) {
+        (_, []) => return Ok(()),
+        (false, [response]) => serde_json::to_string(response),
+        _ => serde_json::to_string(&responses),
+    }
+    .map_err(|e| {
+        fastmcp_core::McpError::internal_error(format!("JSON serialization error: {e}"))
     })?;
+    println!("{output}");
+
+    Ok(())
+}
+
+/// Parses `pipe` input into requests.
+///
+/// Returns the requests and whether the input was a batch (a JSON array).
+fn parse_pipe_input(input: &str) -> McpResult<(Vec<fastmcp_protocol::JsonRpcRequest>, bool)> {
+    let invalid = |e: serde_json::Error| {
+        fastmcp_core::McpError::invalid_params(format!("Invalid JSON-RPC request on stdin: {e}"))
+    };
+
+    let value: serde_json::Value = serde_json::from_str(input).map_err(invalid)?;
+    match value {
+        serde_json::Value::Array(items) => {
+            if items.is_empty() {
+                return Err(fastmcp_core::McpError::invalid_params(
+                    "Empty batch on stdin",
+                ));
+            }
+            let requests = items
+                .into_iter()
+                .map(serde_json::from_value)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(invalid)?;
+            Ok((requests, true))
+        }
+        value => Ok((vec![serde_json::from_value(value).map_err(invalid)?], false)),
+    }
+}
+
+/// Logs command: run a server and follow its filtered stderr.
+fn cmd_logs(server: &str, args: &[String], filter: &LogFilter) -> McpResult<()> {
+    let mut child = Command::new(server)
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            fastmcp_core::McpError::internal_error(format!("Failed to start server: {e}"))
+        })?;
+
+    if let Some(stderr) = child.stderr.take() {
+        // Runs until the server closes stderr, i.e. exits
+        follow_logs(BufReader::new(stderr), &mut io::stderr().lock(), filter).map_err(|e| {
+            fastmcp_core::McpError::internal_error(format!("Failed to read server logs: {e}"))
+        })?;
+    }
 
     let status = child.wait().map_err(|e| {
         fastmcp_core::McpError::internal_error(format!("Failed to wait for server: {e}"))
@@ -536,6 +888,116 @@ fn cmd_run(
     Ok(())
 }
 
+/// Copies matching log lines from `reader` to `writer` until end of input.
+///
+/// Lines that don't parse as a record (panic messages, multi-line payloads)
+/// follow the decision made for the record before them, and pass through if
+/// no record has been seen yet. JSON output drops them.
+fn follow_logs(
+    reader: impl BufRead,
+    writer: &mut impl Write,
+    filter: &LogFilter,
+) -> io::Result<()> {
+    let mut show_continuation = true;
+    for line in reader.lines() {
+        let line = line?;
+        match parse_log_line(&line) {
+            Some(record) => {
+                show_continuation = log_record_matches(&record, filter);
+                if !show_continuation {
+                    continue;
+                }
+                if filter.json {
+                    let json = serde_json::to_string(&record).map_err(io::Error::other)?;
+                    writeln!(writer, "{json}")?;
+                } else {
+                    writeln!(writer, "{line}")?;
+                }
+            }
+            None if show_continuation && !filter.json => writeln!(writer, "{line}")?,
+            None => {}
+        }
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+fn log_record_matches(record: &LogRecord, filter: &LogFilter) -> bool {
+    let level_ok = record
+        .level
+        .parse::<LogsLevel>()
+        .is_ok_and(|level| level <= filter.level);
+    let target_ok = filter.target.as_deref().is_none_or(|wanted| {
+        record
+            .target
+            .as_deref()
+            .is_some_and(|target| target.contains(wanted))
+    });
+    level_ok && target_ok
+}
+
+/// Parses a JSON log object or a rich/plain logger line.
+///
+/// Plain lines look like `[12:00:00] [INFO ] fastmcp::server message`, with
+/// the timestamp and target optional.
+fn parse_log_line(line: &str) -> Option<LogRecord> {
+    let line = console::strip_ansi_codes(line);
+    let line = line.trim();
+
+    if line.starts_with('{') {
+        let value: serde_json::Value = serde_json::from_str(line).ok()?;
+        let field = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| value.get(*name).and_then(serde_json::Value::as_str))
+                .map(str::to_string)
+        };
+        let level = field(&["level", "lvl", "severity"])?
+            .parse::<LogsLevel>()
+            .ok()?;
+        return Some(LogRecord {
+            timestamp: field(&["timestamp", "ts", "time"]),
+            level: level.as_str(),
+            target: field(&["target", "logger"]),
+            message: field(&["message", "msg"]).unwrap_or_default(),
+        });
+    }
+
+    let mut rest = line;
+    let mut timestamp = None;
+    let mut level = None;
+    // Up to two leading bracketed tokens: an optional timestamp, then the level
+    for _ in 0..2 {
+        let Some(inner_end) = rest.strip_prefix('[').and_then(|r| r.find(']')) else {
+            break;
+        };
+        let token = rest[1..=inner_end].trim();
+        rest = rest[inner_end + 2..].trim_start();
+        match token.parse::<LogsLevel>() {
+            Ok(parsed) => {
+                level = Some(parsed);
+                break;
+            }
+            Err(_) => timestamp = Some(token.to_string()),
+        }
+    }
+    let level = level?;
+
+    let (target, message) = match rest.split_once(' ') {
+        Some((first, message)) if first.contains("::") => {
+            (Some(first.to_string()), message.to_string())
+        }
+        _ => (None, rest.to_string()),
+    };
+
+    Some(LogRecord {
+        timestamp,
+        level: level.as_str(),
+        target,
+        message,
+    })
+}
+
 /// Server entry for list output.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ServerEntry {
@@ -838,18 +1300,40 @@ fn load_servers_from_path(
     Ok(())
 }
 
+/// Project-local config files, relative to the project root, with their source labels.
+const PROJECT_LOCAL_CONFIGS: &[(&str, &str)] = &[
+    ("mcp.json", "Project (mcp.json)"),
+    ("mcp.toml", "Project (mcp.toml)"),
+    (".vscode/mcp.json", "VS Code (.vscode/mcp.json)"),
+    (".mcp.json", "Claude Code (.mcp.json)"),
+];
+
 /// Load servers from project-local config files.
 fn load_project_local_servers(servers: &mut Vec<ServerEntry>) {
-    // Check for ./mcp.json
-    let mcp_json = PathBuf::from("./mcp.json");
-    if mcp_json.exists() {
-        let _ = load_servers_from_path(&mcp_json, "Project (mcp.json)", servers);
-    }
+    load_project_local_servers_in(Path::new("."), servers);
+}
+
+/// Load servers from the project-local config files under `root`.
+///
+/// A server already listed with the same name and command (from another
+/// file or a client config) is skipped, so the first occurrence wins.
+fn load_project_local_servers_in(root: &Path, servers: &mut Vec<ServerEntry>) {
+    for (relative, source) in PROJECT_LOCAL_CONFIGS {
+        let path = root.join(relative);
+        if !path.exists() {
+            continue;
+        }
 
-    // Check for ./mcp.toml
-    let mcp_toml = PathBuf::from("./mcp.toml");
-    if mcp_toml.exists() {
-        let _ = load_servers_from_path(&mcp_toml, "Project (mcp.toml)", servers);
+        let mut found = Vec::new();
+        let _ = load_servers_from_path(&path, source, &mut found);
+        for entry in found {
+            let duplicate = servers
+                .iter()
+                .any(|s| s.name == entry.name && s.command == entry.command);
+            if !duplicate {
+                servers.push(entry);
+            }
+        }
     }
 }
 
@@ -912,8 +1396,28 @@ fn cmd_test(
     }
     results.push(init_result);
 
-    // Test 2: List tools
+    // Test 2: Ping
+    let ping_result = run_test("ping", || {
+        let latency = client.ping()?;
+        Ok(format!(
+            "{:.2}ms round trip",
+            latency.as_secs_f64() * 1000.0
+        ))
+    });
+    if !json_output {
+        print_test_result(&ping_result, verbose);
+    }
+    results.push(ping_result);
+
+    // Tests 3-5 only exercise capabilities the server advertised, since
+    // the list methods of the others are answered with method-not-found.
+    let capabilities = client.server_capabilities().clone();
+
+    // Test 3: List tools
     let tools_result = run_test("list_tools", || {
+        if capabilities.tools.is_none() {
+            return Ok("not advertised".to_string());
+        }
         let tools = client.list_tools()?;
         Ok(format!("{} tools", tools.len()))
     });
@@ -922,8 +1426,11 @@ fn cmd_test(
     }
     results.push(tools_result);
 
-    // Test 3: List resources
+    // Test 4: List resources
     let resources_result = run_test("list_resources", || {
+        if capabilities.resources.is_none() {
+            return Ok("not advertised".to_string());
+        }
         let resources = client.list_resources()?;
         Ok(format!("{} resources", resources.len()))
     });
@@ -932,8 +1439,11 @@ fn cmd_test(
     }
     results.push(resources_result);
 
-    // Test 4: List prompts
+    // Test 5: List prompts
     let prompts_result = run_test("list_prompts", || {
+        if capabilities.prompts.is_none() {
+            return Ok("not advertised".to_string());
+        }
         let prompts = client.list_prompts()?;
         Ok(format!("{} prompts", prompts.len()))
     });
@@ -1048,6 +1558,7 @@ struct DevConfig {
     debounce_ms: u64,
     clear: bool,
     env: Vec<String>,
+    env_file: Option<PathBuf>,
     verbose: bool,
 }
 
@@ -1061,15 +1572,7 @@ fn cmd_dev(config: DevConfig) -> McpResult<()> {
 
     let term = Term::stdout();
 
-    // Parse environment variables
-    let env_vars: HashMap<String, String> = config
-        .env
-        .iter()
-        .filter_map(|s| {
-            let mut parts = s.splitn(2, '=');
-            Some((parts.next()?.to_string(), parts.next()?.to_string()))
-        })
-        .collect();
+    let env_vars = resolve_child_env(config.env_file.as_deref(), &config.env)?;
 
     // Determine if this is a Cargo project
     let target_path = PathBuf::from(&config.target);
@@ -1328,47 +1831,48 @@ fn cmd_tasks(action: TasksAction) -> McpResult<()> {
             status,
             limit,
             json,
-        } => {
-            let args_refs: Vec<&str> = args.iter().map(String::as_str).collect();
-            let mut client = Client::stdio(&server, &args_refs)?;
-            let result = cmd_tasks_list(&mut client, status, limit, json);
-            client.close();
-            result
-        }
+        } => with_server(&server, &args, |client| {
+            cmd_tasks_list(client, status, limit, json)
+        }),
         TasksAction::Show {
             server,
             task_id,
             args,
             json,
-        } => {
-            let args_refs: Vec<&str> = args.iter().map(String::as_str).collect();
-            let mut client = Client::stdio(&server, &args_refs)?;
-            let result = cmd_tasks_show(&mut client, &task_id, json);
-            client.close();
-            result
-        }
+        } => with_server(&server, &args, |client| {
+            cmd_tasks_show(client, &task_id, json)
+        }),
         TasksAction::Cancel {
             server,
             task_id,
             args,
             reason,
-        } => {
-            let args_refs: Vec<&str> = args.iter().map(String::as_str).collect();
-            let mut client = Client::stdio(&server, &args_refs)?;
-            let result = cmd_tasks_cancel(&mut client, &task_id, reason.as_deref());
-            client.close();
-            result
-        }
+        } => with_server(&server, &args, |client| {
+            cmd_tasks_cancel(client, &task_id, reason.as_deref())
+        }),
         TasksAction::Stats { server, args, json } => {
-            let args_refs: Vec<&str> = args.iter().map(String::as_str).collect();
-            let mut client = Client::stdio(&server, &args_refs)?;
-            let result = cmd_tasks_stats(&mut client, json);
-            client.close();
-            result
+            with_server(&server, &args, |client| cmd_tasks_stats(client, json))
         }
     }
 }
 
+/// Spawns `server` with `args`, runs `f` over that one connection, and
+/// shuts the server down again.
+///
+/// Every call `f` makes goes to the same subprocess, so a subcommand that
+/// needs several requests (like paging through `tasks/list`) spawns once.
+fn with_server<T>(
+    server: &str,
+    args: &[String],
+    f: impl FnOnce(&mut Client) -> McpResult<T>,
+) -> McpResult<T> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let mut client = Client::stdio(server, &args)?;
+    let result = f(&mut client);
+    client.close();
+    result
+}
+
 /// List tasks with optional status filter.
 fn cmd_tasks_list(
     client: &mut Client,
@@ -1510,29 +2014,64 @@ fn cmd_tasks_cancel(client: &mut Client, task_id: &str, reason: Option<&str>) ->
     Ok(())
 }
 
-/// Show task queue statistics.
-fn cmd_tasks_stats(client: &mut Client, json_output: bool) -> McpResult<()> {
-    // Get all tasks to compute statistics
-    let all_tasks = client.list_tasks(None, None)?;
+/// Task counts by status.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct TaskStats {
+    pending: usize,
+    running: usize,
+    completed: usize,
+    failed: usize,
+    cancelled: usize,
+}
 
-    let mut pending = 0;
-    let mut running = 0;
-    let mut completed = 0;
-    let mut failed = 0;
-    let mut cancelled = 0;
+impl TaskStats {
+    fn record(&mut self, status: TaskStatus) {
+        match status {
+            TaskStatus::Pending => self.pending += 1,
+            TaskStatus::Running => self.running += 1,
+            TaskStatus::Completed => self.completed += 1,
+            TaskStatus::Failed => self.failed += 1,
+            TaskStatus::Cancelled => self.cancelled += 1,
+        }
+    }
+
+    fn total(&self) -> usize {
+        self.active() + self.completed + self.failed + self.cancelled
+    }
 
-    for task in &all_tasks.tasks {
-        match task.status {
-            TaskStatus::Pending => pending += 1,
-            TaskStatus::Running => running += 1,
-            TaskStatus::Completed => completed += 1,
-            TaskStatus::Failed => failed += 1,
-            TaskStatus::Cancelled => cancelled += 1,
+    fn active(&self) -> usize {
+        self.pending + self.running
+    }
+}
+
+/// Counts every task on a server, following the list across pages.
+fn collect_task_stats(client: &mut Client) -> McpResult<TaskStats> {
+    let mut stats = TaskStats::default();
+    let mut cursor = None;
+    loop {
+        let page = client.list_tasks(None, cursor.as_deref())?;
+        for task in &page.tasks {
+            stats.record(task.status);
+        }
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => return Ok(stats),
         }
     }
+}
 
-    let total = all_tasks.tasks.len();
-    let active = pending + running;
+/// Show task queue statistics.
+fn cmd_tasks_stats(client: &mut Client, json_output: bool) -> McpResult<()> {
+    let stats = collect_task_stats(client)?;
+    let TaskStats {
+        pending,
+        running,
+        completed,
+        failed,
+        cancelled,
+    } = stats;
+    let total = stats.total();
+    let active = stats.active();
 
     if json_output {
         let stats = serde_json::json!({
@@ -1555,8 +2094,8 @@ fn cmd_tasks_stats(client: &mut Client, json_output: bool) -> McpResult<()> {
     println!("=====================");
     println!("Total:     {total}");
     println!("Active:    {active}");
-    println!("  Pending:   {pending}");
     println!("  Running:   {running}");
+    println!("  Queued:    {pending}");
     println!("Completed: {completed}");
     println!("Failed:    {failed}");
     println!("Cancelled: {cancelled}");
@@ -1591,6 +2130,7 @@ fn cmd_inspect(
     args: &[String],
     format: InspectFormat,
     output: Option<&std::path::Path>,
+    schemas: bool,
 ) -> McpResult<()> {
     let args_refs: Vec<&str> = args.iter().map(String::as_str).collect();
 
@@ -1638,8 +2178,9 @@ fn cmd_inspect(
             &resources,
             &resource_templates,
             &prompts,
+            schemas,
         ),
-        InspectFormat::Json | InspectFormat::Mcp => format_inspect_json(
+        InspectFormat::Json => format_inspect_json(
             &server_info,
             &capabilities,
             &tools,
@@ -1647,6 +2188,14 @@ fn cmd_inspect(
             &resource_templates,
             &prompts,
         )?,
+        InspectFormat::Mcp => format_inspect_mcp(&RegistryDocument {
+            server: server_info,
+            capabilities,
+            tools,
+            resources,
+            resource_templates,
+            prompts,
+        })?,
     };
 
     // Write output
@@ -1669,6 +2218,7 @@ fn format_inspect_text(
     resources: &[fastmcp_protocol::Resource],
     resource_templates: &[fastmcp_protocol::ResourceTemplate],
     prompts: &[fastmcp_protocol::Prompt],
+    schemas: bool,
 ) -> String {
     let mut out = String::new();
 
@@ -1692,6 +2242,9 @@ fn format_inspect_text(
                 out.push_str(&format!(": {desc}"));
             }
             out.push('\n');
+            if schemas {
+                format_schema_properties(&mut out, &tool.input_schema, 6);
+            }
         }
         out.push('\n');
     }
@@ -1731,12 +2284,76 @@ fn format_inspect_text(
                 out.push_str(&format!(": {desc}"));
             }
             out.push('\n');
+            if schemas {
+                for argument in &prompt.arguments {
+                    out.push_str(&format!("      {}", argument.name));
+                    if argument.required {
+                        out.push_str(" (required)");
+                    }
+                    if let Some(desc) = &argument.description {
+                        out.push_str(&format!(" - {desc}"));
+                    }
+                    out.push('\n');
+                }
+            }
         }
     }
 
     out
 }
 
+/// Renders the properties of an object schema as indented `name: type` lines.
+///
+/// Nested object properties are rendered one level deeper.
+fn format_schema_properties(out: &mut String, schema: &serde_json::Value, indent: usize) {
+    let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) else {
+        return;
+    };
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(|r| r.as_array())
+        .map(|r| r.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+
+    for (name, property) in properties {
+        out.push_str(&format!(
+            "{:indent$}{name}: {}",
+            "",
+            schema_type_name(property)
+        ));
+        if required.contains(&name.as_str()) {
+            out.push_str(" (required)");
+        }
+        if let Some(desc) = property.get("description").and_then(|d| d.as_str()) {
+            out.push_str(&format!(" - {desc}"));
+        }
+        out.push('\n');
+        format_schema_properties(out, property, indent + 2);
+    }
+}
+
+/// Returns a short type description for a schema, e.g. `string`,
+/// `array<integer>`, `string | null`, or `enum("a", "b")`.
+fn schema_type_name(schema: &serde_json::Value) -> String {
+    if let Some(values) = schema.get("enum").and_then(|e| e.as_array()) {
+        let values: Vec<String> = values.iter().map(ToString::to_string).collect();
+        return format!("enum({})", values.join(", "));
+    }
+    match schema.get("type") {
+        Some(serde_json::Value::String(ty)) if ty == "array" => match schema.get("items") {
+            Some(items) if items.is_object() => format!("array<{}>", schema_type_name(items)),
+            _ => "array".to_string(),
+        },
+        Some(serde_json::Value::String(ty)) => ty.clone(),
+        Some(serde_json::Value::Array(types)) => types
+            .iter()
+            .filter_map(|t| t.as_str())
+            .collect::<Vec<_>>()
+            .join(" | "),
+        _ => "any".to_string(),
+    }
+}
+
 #[derive(Serialize)]
 struct InspectOutput {
     server: ServerInfoOutput,
@@ -1803,15 +2420,25 @@ fn format_inspect_json(
     })
 }
 
+/// Emits the registry document, the same shape as `Server::registry_document()`.
+fn format_inspect_mcp(document: &RegistryDocument) -> McpResult<String> {
+    serde_json::to_string_pretty(document).map_err(|e| {
+        fastmcp_core::McpError::internal_error(format!("JSON serialization error: {e}"))
+    })
+}
+
 /// Install command: Generate configuration for MCP clients.
 fn cmd_install(
     name: &str,
     server: &str,
     args: &[String],
+    env_vars: &[String],
+    expand: bool,
     target: InstallTarget,
     dry_run: bool,
 ) -> McpResult<()> {
-    let config = generate_server_config(name, server, args);
+    let env = parse_install_env(env_vars, expand, |var| env::var(var).ok())?;
+    let config = generate_server_config(name, server, args, env);
 
     match target {
         InstallTarget::Claude => install_claude_desktop(&config, dry_run),
@@ -1823,26 +2450,90 @@ fn cmd_install(
 #[derive(Serialize, Deserialize)]
 struct McpServerConfig {
     command: String,
+    #[serde(default)]
     args: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     env: Option<HashMap<String, String>>,
 }
 
-fn generate_server_config(name: &str, server: &str, args: &[String]) -> (String, McpServerConfig) {
+fn generate_server_config(
+    name: &str,
+    server: &str,
+    args: &[String],
+    env: Option<HashMap<String, String>>,
+) -> (String, McpServerConfig) {
     (
         name.to_string(),
         McpServerConfig {
             command: server.to_string(),
             args: args.to_vec(),
-            env: None,
+            env,
         },
     )
 }
 
-fn install_claude_desktop(config: &(String, McpServerConfig), dry_run: bool) -> McpResult<()> {
-    let config_path = get_claude_desktop_config_path()?;
+/// Parses `install --env KEY=VALUE` entries into the config's `env` map.
+///
+/// With `expand`, each `${VAR}` in a value is replaced using `lookup`.
+/// Returns `None` when no entries were given, so configs without env vars
+/// stay unchanged.
+fn parse_install_env(
+    env_vars: &[String],
+    expand: bool,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> McpResult<Option<HashMap<String, String>>> {
+    if env_vars.is_empty() {
+        return Ok(None);
+    }
 
-    let mut servers = HashMap::new();
+    let mut env = HashMap::with_capacity(env_vars.len());
+    for env_var in env_vars {
+        let Some((key, value)) = env_var.split_once('=').filter(|(key, _)| !key.is_empty()) else {
+            return Err(fastmcp_core::McpError::invalid_params(format!(
+                "Invalid env var format (expected KEY=VALUE): {env_var}"
+            )));
+        };
+        let value = if expand {
+            expand_env_references(value, &lookup)?
+        } else {
+            value.to_string()
+        };
+        env.insert(key.to_string(), value);
+    }
+    Ok(Some(env))
+}
+
+/// Replaces `${VAR}` references in `value` with values from `lookup`.
+///
+/// A `$` not followed by a complete `${...}` reference is kept as is.
+fn expand_env_references(
+    value: &str,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> McpResult<String> {
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start + 2..].find('}') else {
+            break;
+        };
+        let var = &rest[start + 2..start + 2 + len];
+        let resolved = lookup(var).ok_or_else(|| {
+            fastmcp_core::McpError::invalid_params(format!(
+                "Environment variable '{var}' is not set (use --no-expand to keep '${{{var}}}' literally)"
+            ))
+        })?;
+        expanded.push_str(&rest[..start]);
+        expanded.push_str(&resolved);
+        rest = &rest[start + 2 + len + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+fn install_claude_desktop(config: &(String, McpServerConfig), dry_run: bool) -> McpResult<()> {
+    let config_path = get_claude_desktop_config_path()?;
+
+    let mut servers = HashMap::new();
     servers.insert(config.0.clone(), &config.1);
 
     let config_snippet = serde_json::json!({
@@ -2094,11 +2785,13 @@ mod tests {
                     args,
                     cwd,
                     env,
+                    env_file,
                 } => {
                     assert_eq!(server, "./my-server");
                     assert!(args.is_empty());
                     assert!(cwd.is_none());
                     assert!(env.is_empty());
+                    assert!(env_file.is_none());
                 }
                 _ => panic!("Expected Run command"),
             }
@@ -2150,6 +2843,27 @@ mod tests {
             }
         }
 
+        #[test]
+        fn test_run_and_dev_accept_env_file() {
+            let cli =
+                Cli::try_parse_from(["fastmcp", "run", "--env-file", ".env", "./server"]).unwrap();
+            match cli.command {
+                Commands::Run { env_file, .. } => {
+                    assert_eq!(env_file, Some(PathBuf::from(".env")));
+                }
+                _ => panic!("Expected Run command"),
+            }
+
+            let cli =
+                Cli::try_parse_from(["fastmcp", "dev", "--env-file", "dev.env", "."]).unwrap();
+            match cli.command {
+                Commands::Dev { env_file, .. } => {
+                    assert_eq!(env_file, Some(PathBuf::from("dev.env")));
+                }
+                _ => panic!("Expected Dev command"),
+            }
+        }
+
         #[test]
         fn test_inspect_command_basic() {
             let cli = Cli::try_parse_from(["fastmcp", "inspect", "./server"]).unwrap();
@@ -2204,6 +2918,15 @@ mod tests {
             }
         }
 
+        #[test]
+        fn test_inspect_command_with_schemas() {
+            let cli = Cli::try_parse_from(["fastmcp", "inspect", "-s", "./server"]).unwrap();
+            match cli.command {
+                Commands::Inspect { schemas, .. } => assert!(schemas),
+                _ => panic!("Expected Inspect command"),
+            }
+        }
+
         #[test]
         fn test_install_command_basic() {
             let cli = Cli::try_parse_from(["fastmcp", "install", "my-server", "./server"]).unwrap();
@@ -2243,6 +2966,41 @@ mod tests {
             }
         }
 
+        #[test]
+        fn test_install_command_with_env() {
+            let cli = Cli::try_parse_from([
+                "fastmcp",
+                "install",
+                "-e",
+                "TOKEN=${API_TOKEN}",
+                "--env",
+                "DEBUG=1",
+                "--no-expand",
+                "my-server",
+                "./server",
+            ])
+            .unwrap();
+            match cli.command {
+                Commands::Install { env, no_expand, .. } => {
+                    assert_eq!(env, vec!["TOKEN=${API_TOKEN}", "DEBUG=1"]);
+                    assert!(no_expand);
+                }
+                _ => panic!("Expected Install command"),
+            }
+        }
+
+        #[test]
+        fn test_pipe_command() {
+            let cli = Cli::try_parse_from(["fastmcp", "pipe", "./server", "--port", "9"]).unwrap();
+            match cli.command {
+                Commands::Pipe { server, args } => {
+                    assert_eq!(server, "./server");
+                    assert_eq!(args, vec!["--port", "9"]);
+                }
+                _ => panic!("Expected Pipe command"),
+            }
+        }
+
         #[test]
         fn test_install_command_dry_run() {
             let cli =
@@ -2772,6 +3530,7 @@ mod tests {
                 "my-server",
                 "/path/to/server",
                 &["--config".to_string(), "config.json".to_string()],
+                None,
             );
 
             assert_eq!(name, "my-server");
@@ -2779,6 +3538,91 @@ mod tests {
             assert_eq!(config.args, vec!["--config", "config.json"]);
             assert!(config.env.is_none());
         }
+
+        fn lookup(var: &str) -> Option<String> {
+            match var {
+                "API_TOKEN" => Some("s3cret".to_string()),
+                "HOME" => Some("/home/me".to_string()),
+                _ => None,
+            }
+        }
+
+        #[test]
+        fn test_install_env_none_when_empty() {
+            assert!(parse_install_env(&[], true, lookup).unwrap().is_none());
+        }
+
+        #[test]
+        fn test_install_env_literal_values() {
+            let env = parse_install_env(
+                &["DEBUG=1".to_string(), "URL=http://x?a=b".to_string()],
+                true,
+                lookup,
+            )
+            .unwrap()
+            .unwrap();
+
+            assert_eq!(env["DEBUG"], "1");
+            assert_eq!(env["URL"], "http://x?a=b");
+        }
+
+        #[test]
+        fn test_install_env_expands_references() {
+            let env = parse_install_env(
+                &[
+                    "TOKEN=${API_TOKEN}".to_string(),
+                    "CACHE=${HOME}/.cache/${API_TOKEN}".to_string(),
+                    "PRICE=$5".to_string(),
+                ],
+                true,
+                lookup,
+            )
+            .unwrap()
+            .unwrap();
+
+            assert_eq!(env["TOKEN"], "s3cret");
+            assert_eq!(env["CACHE"], "/home/me/.cache/s3cret");
+            assert_eq!(env["PRICE"], "$5");
+        }
+
+        #[test]
+        fn test_install_env_unset_reference_fails() {
+            let err =
+                parse_install_env(&["TOKEN=${MISSING}".to_string()], true, lookup).unwrap_err();
+            assert!(err.message.contains("MISSING"));
+            assert!(err.message.contains("--no-expand"));
+        }
+
+        #[test]
+        fn test_install_env_no_expand_keeps_references() {
+            let env = parse_install_env(
+                &[
+                    "TOKEN=${API_TOKEN}".to_string(),
+                    "OTHER=${MISSING}".to_string(),
+                ],
+                false,
+                lookup,
+            )
+            .unwrap()
+            .unwrap();
+
+            assert_eq!(env["TOKEN"], "${API_TOKEN}");
+            assert_eq!(env["OTHER"], "${MISSING}");
+        }
+
+        #[test]
+        fn test_install_env_rejects_malformed_entry() {
+            assert!(parse_install_env(&["NOVALUE".to_string()], true, lookup).is_err());
+            assert!(parse_install_env(&["=value".to_string()], true, lookup).is_err());
+        }
+
+        #[test]
+        fn test_install_config_serializes_env() {
+            let env = parse_install_env(&["TOKEN=${API_TOKEN}".to_string()], true, lookup).unwrap();
+            let (_, config) = generate_server_config("srv", "./server", &[], env);
+            let value = serde_json::to_value(&config).unwrap();
+            assert_eq!(value["env"]["TOKEN"], "s3cret");
+        }
     }
 
     // ============================================================================
@@ -2999,6 +3843,7 @@ mod tests {
                 },
                 logging: None,
                 tasks: None,
+                experimental: None,
             }
         }
 
@@ -3043,7 +3888,8 @@ mod tests {
             let server_info = make_test_server_info();
             let capabilities = make_test_capabilities(true, true, true);
 
-            let output = format_inspect_text(&server_info, &capabilities, &[], &[], &[], &[]);
+            let output =
+                format_inspect_text(&server_info, &capabilities, &[], &[], &[], &[], false);
 
             assert!(output.contains("test-server"));
             assert!(output.contains("v1.0.0"));
@@ -3059,7 +3905,8 @@ mod tests {
 
             let tools = vec![make_test_tool("my_tool", Some("A test tool"))];
 
-            let output = format_inspect_text(&server_info, &capabilities, &tools, &[], &[], &[]);
+            let output =
+                format_inspect_text(&server_info, &capabilities, &tools, &[], &[], &[], false);
 
             assert!(output.contains("Tools (1)"));
             assert!(output.contains("my_tool"));
@@ -3073,8 +3920,15 @@ mod tests {
 
             let resources = vec![make_test_resource("file:///test.txt", "test file")];
 
-            let output =
-                format_inspect_text(&server_info, &capabilities, &[], &resources, &[], &[]);
+            let output = format_inspect_text(
+                &server_info,
+                &capabilities,
+                &[],
+                &resources,
+                &[],
+                &[],
+                false,
+            );
 
             assert!(output.contains("Resources (1)"));
             assert!(output.contains("file:///test.txt"));
@@ -3088,13 +3942,100 @@ mod tests {
 
             let prompts = vec![make_test_prompt("greeting", Some("A greeting prompt"))];
 
-            let output = format_inspect_text(&server_info, &capabilities, &[], &[], &[], &prompts);
+            let output =
+                format_inspect_text(&server_info, &capabilities, &[], &[], &[], &prompts, false);
 
             assert!(output.contains("Prompts (1)"));
             assert!(output.contains("greeting"));
             assert!(output.contains("A greeting prompt"));
         }
 
+        fn make_greet_tool() -> fastmcp_protocol::Tool {
+            let mut tool = make_test_tool("greet", Some("Greets someone"));
+            tool.input_schema = serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string", "description": "Who to greet"},
+                    "tags": {"type": "array", "items": {"type": "string"}},
+                    "style": {"enum": ["formal", "casual"]},
+                    "options": {
+                        "type": "object",
+                        "properties": {"shout": {"type": "boolean"}},
+                        "required": ["shout"]
+                    }
+                },
+                "required": ["name"]
+            });
+            tool
+        }
+
+        #[test]
+        fn test_format_inspect_text_schemas_hidden_by_default() {
+            let server_info = make_test_server_info();
+            let capabilities = make_test_capabilities(true, false, false);
+
+            let output = format_inspect_text(
+                &server_info,
+                &capabilities,
+                &[make_greet_tool()],
+                &[],
+                &[],
+                &[],
+                false,
+            );
+
+            assert!(output.contains("greet: Greets someone"));
+            assert!(!output.contains("name: string"));
+        }
+
+        #[test]
+        fn test_format_inspect_text_with_tool_schemas() {
+            let server_info = make_test_server_info();
+            let capabilities = make_test_capabilities(true, false, false);
+
+            let output = format_inspect_text(
+                &server_info,
+                &capabilities,
+                &[make_greet_tool()],
+                &[],
+                &[],
+                &[],
+                true,
+            );
+
+            assert!(output.contains("      name: string (required) - Who to greet\n"));
+            assert!(output.contains("      tags: array<string>\n"));
+            assert!(output.contains("      style: enum(\"formal\", \"casual\")\n"));
+            assert!(output.contains("      options: object\n"));
+            assert!(output.contains("        shout: boolean (required)\n"));
+        }
+
+        #[test]
+        fn test_format_inspect_text_with_prompt_arguments() {
+            let server_info = make_test_server_info();
+            let capabilities = make_test_capabilities(false, false, true);
+
+            let mut prompt = make_test_prompt("review", None);
+            prompt.arguments = vec![
+                fastmcp_protocol::PromptArgument {
+                    name: "code".to_string(),
+                    description: Some("Code to review".to_string()),
+                    required: true,
+                },
+                fastmcp_protocol::PromptArgument {
+                    name: "focus".to_string(),
+                    description: None,
+                    required: false,
+                },
+            ];
+
+            let output =
+                format_inspect_text(&server_info, &capabilities, &[], &[], &[], &[prompt], true);
+
+            assert!(output.contains("      code (required) - Code to review\n"));
+            assert!(output.contains("      focus\n"));
+        }
+
         #[test]
         fn test_format_inspect_json_basic() {
             let server_info = make_test_server_info();
@@ -3132,6 +4073,35 @@ mod tests {
             assert!(json.contains("calculator"));
             assert!(json.contains("Performs calculations"));
         }
+
+        #[test]
+        fn test_format_inspect_mcp_emits_registry_document() {
+            let mut tool = make_test_tool("calculator", Some("Performs calculations"));
+            tool.input_schema = serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "expression": { "type": "string" }
+                }
+            });
+            let document = RegistryDocument {
+                server: make_test_server_info(),
+                capabilities: make_test_capabilities(true, false, false),
+                tools: vec![tool],
+                resources: Vec::new(),
+                resource_templates: Vec::new(),
+                prompts: Vec::new(),
+            };
+
+            let json = format_inspect_mcp(&document).unwrap();
+            let parsed: RegistryDocument = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(parsed.server.name, "test-server");
+            assert_eq!(parsed.tools.len(), 1);
+            assert_eq!(
+                parsed.tools[0].input_schema["properties"]["expression"]["type"],
+                "string"
+            );
+        }
     }
 
     // ============================================================================
@@ -3170,6 +4140,107 @@ mod tests {
         }
     }
 
+    // ============================================================================
+    // Project-Local Config Tests
+    // ============================================================================
+
+    mod project_local_configs {
+        use super::*;
+        use tempfile::tempdir;
+
+        fn write(dir: &Path, relative: &str, content: &str) {
+            let path = dir.join(relative);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        fn sources(servers: &[ServerEntry]) -> Vec<(&str, &str)> {
+            servers
+                .iter()
+                .map(|s| (s.name.as_str(), s.source.as_str()))
+                .collect()
+        }
+
+        #[test]
+        fn test_vscode_config_nests_servers() {
+            let dir = tempdir().unwrap();
+            write(
+                dir.path(),
+                ".vscode/mcp.json",
+                r#"{"servers": {"files": {"type": "stdio", "command": "mcp-files"}}}"#,
+            );
+
+            let mut servers = Vec::new();
+            load_project_local_servers_in(dir.path(), &mut servers);
+
+            assert_eq!(sources(&servers), [("files", "VS Code (.vscode/mcp.json)")]);
+            assert_eq!(servers[0].command, "mcp-files");
+        }
+
+        #[test]
+        fn test_claude_code_config() {
+            let dir = tempdir().unwrap();
+            write(
+                dir.path(),
+                ".mcp.json",
+                r#"{"mcpServers": {"git": {"command": "mcp-git", "args": ["--repo", "."]}}}"#,
+            );
+
+            let mut servers = Vec::new();
+            load_project_local_servers_in(dir.path(), &mut servers);
+
+            assert_eq!(sources(&servers), [("git", "Claude Code (.mcp.json)")]);
+            assert_eq!(servers[0].args, ["--repo", "."]);
+        }
+
+        #[test]
+        fn test_merges_and_dedupes_by_name_and_command() {
+            let dir = tempdir().unwrap();
+            write(
+                dir.path(),
+                "mcp.json",
+                r#"{"mcpServers": {"git": {"command": "mcp-git"}}}"#,
+            );
+            write(
+                dir.path(),
+                "mcp.toml",
+                "[servers.notes]\ncommand = \"mcp-notes\"\n",
+            );
+            write(
+                dir.path(),
+                ".vscode/mcp.json",
+                r#"{"servers": {"git": {"command": "mcp-git"}, "files": {"command": "mcp-files"}}}"#,
+            );
+            // Same name, different command: kept as a separate entry
+            write(
+                dir.path(),
+                ".mcp.json",
+                r#"{"mcpServers": {"files": {"command": "mcp-files"}, "notes": {"command": "notes-v2"}}}"#,
+            );
+
+            let mut servers = Vec::new();
+            load_project_local_servers_in(dir.path(), &mut servers);
+
+            assert_eq!(
+                sources(&servers),
+                [
+                    ("git", "Project (mcp.json)"),
+                    ("notes", "Project (mcp.toml)"),
+                    ("files", "VS Code (.vscode/mcp.json)"),
+                    ("notes", "Claude Code (.mcp.json)"),
+                ]
+            );
+        }
+
+        #[test]
+        fn test_missing_files_load_nothing() {
+            let dir = tempdir().unwrap();
+            let mut servers = Vec::new();
+            load_project_local_servers_in(dir.path(), &mut servers);
+            assert!(servers.is_empty());
+        }
+    }
+
     // ============================================================================
     // Error Case Tests
     // ============================================================================
@@ -3239,6 +4310,166 @@ mod tests {
         }
     }
 
+    // ============================================================================
+    // Log Following Tests
+    // ============================================================================
+
+    mod pipe_input {
+        use super::*;
+
+        #[test]
+        fn single_request() {
+            let (requests, batch) =
+                parse_pipe_input(r#"{"jsonrpc":"2.0","id":7,"method":"tools/list"}"#).unwrap();
+            assert!(!batch);
+            assert_eq!(requests.len(), 1);
+            assert_eq!(requests[0].method, "tools/list");
+            assert_eq!(requests[0].id, Some(fastmcp_protocol::RequestId::Number(7)));
+        }
+
+        #[test]
+        fn batch_with_notification() {
+            let input = r#"[
+                {"jsonrpc":"2.0","id":"a","method":"ping"},
+                {"jsonrpc":"2.0","method":"notifications/cancelled","params":{"requestId":1}}
+            ]"#;
+            let (requests, batch) = parse_pipe_input(input).unwrap();
+            assert!(batch);
+            assert_eq!(requests.len(), 2);
+            assert!(requests[1].id.is_none());
+        }
+
+        #[test]
+        fn rejects_invalid_input() {
+            assert!(parse_pipe_input("").is_err());
+            assert!(parse_pipe_input("not json").is_err());
+            assert!(parse_pipe_input("[]").is_err());
+            assert!(parse_pipe_input(r#"{"id":1}"#).is_err());
+        }
+    }
+
+    mod log_following {
+        use super::*;
+
+        /// Stderr of a server logging at mixed levels, in plain and JSON form.
+        const MIXED_LEVEL_FIXTURE: &str = "\
+[12:00:00] [ERROR] fastmcp::server disk full
+[12:00:01] [WARN ] fastmcp::transport slow client
+[12:00:02] [INFO ] fastmcp::server listening
+[DEBUG] fastmcp::handler tool called
+\u{1b}[33m[WARN ]\u{1b}[0m app::db pool exhausted
+{\"level\":\"warn\",\"target\":\"fastmcp::server\",\"message\":\"json warning\"}
+{\"level\":\"trace\",\"target\":\"fastmcp::server\",\"message\":\"json trace\"}
+";
+
+        fn filter(level: LogsLevel, target: Option<&str>, json: bool) -> LogFilter {
+            LogFilter {
+                level,
+                target: target.map(str::to_string),
+                json,
+            }
+        }
+
+        fn follow(input: &str, filter: &LogFilter) -> Vec<String> {
+            let mut out = Vec::new();
+            follow_logs(input.as_bytes(), &mut out, filter).unwrap();
+            String::from_utf8(out)
+                .unwrap()
+                .lines()
+                .map(str::to_string)
+                .collect()
+        }
+
+        #[test]
+        fn test_logs_command_parsing() {
+            let cli = Cli::try_parse_from([
+                "fastmcp", "logs", "--level", "warn", "--target", "server", "--json", "./srv",
+                "--flag",
+            ])
+            .unwrap();
+            match cli.command {
+                Commands::Logs {
+                    server,
+                    args,
+                    level,
+                    target,
+                    json,
+                } => {
+                    assert_eq!(server, "./srv");
+                    assert_eq!(args, vec!["--flag"]);
+                    assert_eq!(level, LogsLevel::Warn);
+                    assert_eq!(target.as_deref(), Some("server"));
+                    assert!(json);
+                }
+                _ => panic!("Expected Logs command"),
+            }
+        }
+
+        #[test]
+        fn test_parse_plain_and_json_lines() {
+            let record = parse_log_line("[12:00:00] [INFO ] fastmcp::server listening").unwrap();
+            assert_eq!(record.timestamp.as_deref(), Some("12:00:00"));
+            assert_eq!(record.level, "INFO");
+            assert_eq!(record.target.as_deref(), Some("fastmcp::server"));
+            assert_eq!(record.message, "listening");
+
+            let record = parse_log_line(r#"{"level":"ERROR","msg":"boom"}"#).unwrap();
+            assert_eq!(record.level, "ERROR");
+            assert_eq!(record.target, None);
+            assert_eq!(record.message, "boom");
+
+            assert!(parse_log_line("thread 'main' panicked").is_none());
+            assert!(parse_log_line("[not a level] text").is_none());
+        }
+
+        #[test]
+        fn test_only_selected_levels_pass_through() {
+            let lines = follow(MIXED_LEVEL_FIXTURE, &filter(LogsLevel::Warn, None, false));
+            assert_eq!(lines.len(), 4, "{lines:?}");
+            assert!(lines[0].contains("disk full"));
+            assert!(lines[1].contains("slow client"));
+            assert!(lines[2].contains("pool exhausted"));
+            assert!(lines[3].contains("json warning"));
+
+            let lines = follow(MIXED_LEVEL_FIXTURE, &filter(LogsLevel::Trace, None, false));
+            assert_eq!(lines.len(), 7);
+        }
+
+        #[test]
+        fn test_target_filter_and_json_output() {
+            let lines = follow(
+                MIXED_LEVEL_FIXTURE,
+                &filter(LogsLevel::Info, Some("fastmcp::server"), true),
+            );
+            let records: Vec<serde_json::Value> = lines
+                .iter()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect();
+            let messages: Vec<&str> = records
+                .iter()
+                .map(|r| r["message"].as_str().unwrap())
+                .collect();
+            assert_eq!(messages, ["disk full", "listening", "json warning"]);
+            assert_eq!(records[0]["level"], "ERROR");
+            assert_eq!(records[0]["timestamp"], "12:00:00");
+        }
+
+        #[test]
+        fn test_continuation_lines_follow_their_record() {
+            let input = "\
+[ERROR] app::worker failed
+  caused by: timeout
+[DEBUG] app::worker retrying
+  attempt 2
+";
+            let lines = follow(input, &filter(LogsLevel::Info, None, false));
+            assert_eq!(
+                lines,
+                ["[ERROR] app::worker failed", "  caused by: timeout"]
+            );
+        }
+    }
+
     // ============================================================================
     // Integration-style Tests (without actual server)
     // ============================================================================
@@ -3281,4 +4512,143 @@ mod tests {
             assert!(result.details.is_none());
         }
     }
+
+    // ============================================================================
+    // Task Stats Tests
+    // ============================================================================
+
+    mod task_stats {
+        use super::*;
+
+        #[test]
+        fn test_task_stats_counts_by_status() {
+            let mut stats = TaskStats::default();
+            for status in [
+                TaskStatus::Pending,
+                TaskStatus::Running,
+                TaskStatus::Running,
+                TaskStatus::Completed,
+                TaskStatus::Failed,
+                TaskStatus::Cancelled,
+            ] {
+                stats.record(status);
+            }
+
+            assert_eq!(
+                stats,
+                TaskStats {
+                    pending: 1,
+                    running: 2,
+                    completed: 1,
+                    failed: 1,
+                    cancelled: 1,
+                }
+            );
+            assert_eq!(stats.total(), 6);
+            assert_eq!(stats.active(), 3);
+        }
+    }
+
+    // ============================================================================
+    // Env File Tests
+    // ============================================================================
+
+    mod env_file {
+        use super::*;
+
+        fn pairs(entries: &[(&str, &str)]) -> Vec<(String, String)> {
+            entries
+                .iter()
+                .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                .collect()
+        }
+
+        #[test]
+        fn test_parse_env_file_well_formed() {
+            let content =
+                "DATABASE_URL=postgres://localhost/db\nPORT=8080\nexport API_KEY=abc123\n";
+
+            let entries = parse_env_file(content).unwrap();
+
+            assert_eq!(
+                entries,
+                pairs(&[
+                    ("DATABASE_URL", "postgres://localhost/db"),
+                    ("PORT", "8080"),
+                    ("API_KEY", "abc123"),
+                ])
+            );
+        }
+
+        #[test]
+        fn test_parse_env_file_comments_and_quotes() {
+            let content = r#"
+# Server settings
+NAME="My Server"  # trailing comment
+GREETING="line one\nsay \"hi\""
+LITERAL='no $expansion \n here'
+URL=http://example.com/#anchor
+EMPTY=
+COMMENTED= # nothing here
+"#;
+
+            let entries = parse_env_file(content).unwrap();
+
+            assert_eq!(
+                entries,
+                pairs(&[
+                    ("NAME", "My Server"),
+                    ("GREETING", "line one\nsay \"hi\""),
+                    ("LITERAL", "no $expansion \\n here"),
+                    ("URL", "http://example.com/#anchor"),
+                    ("EMPTY", ""),
+                    ("COMMENTED", ""),
+                ])
+            );
+        }
+
+        #[test]
+        fn test_parse_env_file_reports_line_numbers() {
+            let err = parse_env_file("# header\nOK=1\nnot a pair\n").unwrap_err();
+            assert!(err.starts_with("line 3:"), "{err}");
+
+            let err = parse_env_file("A=1\nB=\"unterminated\n").unwrap_err();
+            assert!(err.starts_with("line 2:"), "{err}");
+            assert!(err.contains("unterminated"), "{err}");
+
+            let err = parse_env_file("1BAD=x").unwrap_err();
+            assert!(err.starts_with("line 1:"), "{err}");
+
+            let err = parse_env_file("A='quoted' trailing").unwrap_err();
+            assert!(err.contains("unexpected text"), "{err}");
+        }
+
+        #[test]
+        fn test_env_flags_override_env_file() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join(".env");
+            std::fs::write(&path, "FOO=from-file\nBAR=kept\n").unwrap();
+
+            let env = resolve_child_env(Some(&path), &["FOO=from-flag".to_string()]).unwrap();
+
+            assert_eq!(env.get("FOO").map(String::as_str), Some("from-flag"));
+            assert_eq!(env.get("BAR").map(String::as_str), Some("kept"));
+            assert_eq!(env.len(), 2);
+        }
+
+        #[test]
+        fn test_env_file_errors_name_the_file() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join(".env");
+            std::fs::write(&path, "GOOD=1\nBROKEN\n").unwrap();
+
+            let message = resolve_child_env(Some(&path), &[]).unwrap_err().to_string();
+
+            assert!(message.contains(&path.display().to_string()), "{message}");
+            assert!(message.contains("line 2"), "{message}");
+
+            let missing = dir.path().join("missing.env");
+            assert!(resolve_child_env(Some(&missing), &[]).is_err());
+        }
+    }
 }
@@ -21,6 +21,28 @@ fn run_cli(args: &[&str]) -> Output {
         .expect("Failed to execute CLI binary")
 }
 
+/// Helper to run the CLI with `input` on stdin and capture output.
+#[cfg(unix)]
+fn run_cli_with_stdin(args: &[&str], input: &str) -> Output {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(get_binary_path())
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute CLI binary");
+    child
+        .stdin
+        .take()
+        .expect("stdin piped")
+        .write_all(input.as_bytes())
+        .expect("Failed to write stdin");
+    child.wait_with_output().expect("Failed to wait for CLI")
+}
+
 /// Helper to get stdout as string.
 fn stdout_str(output: &Output) -> String {
     String::from_utf8_lossy(&output.stdout).to_string()
@@ -74,6 +96,7 @@ fn e2e_cli_inspect_help() {
     assert!(stdout.contains("Inspect"));
     assert!(stdout.contains("--format"));
     assert!(stdout.contains("--output"));
+    assert!(stdout.contains("--schemas"));
 }
 
 #[test]
@@ -427,3 +450,281 @@ fn e2e_cli_test_json_option() {
     let stdout = stdout_str(&output);
     assert!(stdout.contains("--json"), "Should support --json output");
 }
+
+// =============================================================================
+// Tasks Command Tests
+// =============================================================================
+
+/// A stand-in stdio server with tasks: logs each spawn to the file named by
+/// its first argument, then serves `tasks/list` as two pages of tasks.
+#[cfg(unix)]
+const PAGED_TASKS_SERVER: &str = r#"
+echo spawned >> "$1"
+task() {
+  printf '{"id":"%s","taskType":"index","status":"%s","createdAt":"2024-01-01T00:00:00Z"}' "$1" "$2"
+}
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  [ -z "$id" ] && continue
+  case "$line" in
+    *'"initialize"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"protocolVersion":"2024-11-05","capabilities":{"tasks":{}},"serverInfo":{"name":"tasks","version":"1.0.0"}}}\n' "$id" ;;
+    *'"cursor":"page-2"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"tasks":[%s,%s]}}\n' "$id" "$(task t3 completed)" "$(task t4 failed)" ;;
+    *'"tasks/list"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"tasks":[%s,%s],"nextCursor":"page-2"}}\n' "$id" "$(task t1 running)" "$(task t2 running)" ;;
+    *)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{}}\n' "$id" ;;
+  esac
+done
+"#;
+
+#[cfg(unix)]
+#[test]
+fn e2e_cli_tasks_stats_pages_over_one_spawn() {
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let spawn_log = dir.path().join("spawns.log");
+    let spawn_log = spawn_log.to_str().expect("UTF-8 temp path");
+
+    let output = run_cli(&[
+        "tasks",
+        "stats",
+        "--json",
+        "sh",
+        "--",
+        "-c",
+        PAGED_TASKS_SERVER,
+        "sh",
+        spawn_log,
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr_str(&output));
+    let stats: serde_json::Value = serde_json::from_str(&stdout_str(&output)).expect("stats JSON");
+    assert_eq!(stats["total"], 4);
+    assert_eq!(stats["running"], 2);
+    assert_eq!(stats["completed"], 1);
+    assert_eq!(stats["failed"], 1);
+
+    let spawns = std::fs::read_to_string(spawn_log).expect("read spawn log");
+    assert_eq!(spawns.lines().count(), 1, "{spawns}");
+}
+
+// =============================================================================
+// Echo Server Fixture Tests
+// =============================================================================
+
+/// Path to the reference echo server built alongside the CLI.
+#[cfg(feature = "echo-server")]
+fn echo_server_path() -> &'static str {
+    env!("CARGO_BIN_EXE_fastmcp-echo-server")
+}
+
+#[cfg(feature = "echo-server")]
+#[test]
+fn e2e_cli_inspect_echo_server_text() {
+    let output = run_cli(&["inspect", echo_server_path()]);
+
+    assert!(output.status.success(), "stderr: {}", stderr_str(&output));
+    let stdout = stdout_str(&output);
+    assert!(stdout.contains("Server: fastmcp-echo-server v"), "{stdout}");
+    assert!(stdout.contains("Tools (1):"), "{stdout}");
+    assert!(
+        stdout.contains("  - echo: Echo the message back"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("  - echo://readme (Readme)"), "{stdout}");
+    assert!(stdout.contains("greeting"), "{stdout}");
+}
+
+#[cfg(feature = "echo-server")]
+#[test]
+fn e2e_cli_inspect_echo_server_json() {
+    let output = run_cli(&["inspect", "--format", "json", echo_server_path()]);
+
+    assert!(output.status.success(), "stderr: {}", stderr_str(&output));
+    let report: serde_json::Value =
+        serde_json::from_str(&stdout_str(&output)).expect("inspect JSON");
+    assert_eq!(report["server"]["name"], "fastmcp-echo-server");
+    assert_eq!(report["capabilities"]["tools"], true);
+    assert_eq!(report["tools"].as_array().map(Vec::len), Some(1));
+    assert_eq!(report["tools"][0]["name"], "echo");
+    assert_eq!(report["resources"][0]["uri"], "echo://readme");
+    assert_eq!(report["prompts"][0]["name"], "greeting");
+    assert_eq!(report["prompts"][0]["arguments"][0]["name"], "name");
+}
+
+#[cfg(feature = "echo-server")]
+#[test]
+fn e2e_cli_test_echo_server_passes() {
+    let output = run_cli(&["test", "--json", echo_server_path()]);
+
+    assert!(output.status.success(), "stderr: {}", stderr_str(&output));
+    let report: serde_json::Value =
+        serde_json::from_str(&stdout_str(&output)).expect("test report JSON");
+    assert_eq!(report["success"], true);
+
+    let details: Vec<(&str, &str)> = report["tests"]
+        .as_array()
+        .expect("tests array")
+        .iter()
+        .map(|test| {
+            assert_eq!(test["success"], true, "{test}");
+            (
+                test["name"].as_str().unwrap_or_default(),
+                test["details"].as_str().unwrap_or_default(),
+            )
+        })
+        .collect();
+    assert!(details.contains(&("list_tools", "1 tools")), "{details:?}");
+    assert!(
+        details.contains(&("list_resources", "1 resources")),
+        "{details:?}"
+    );
+    assert!(
+        details.contains(&("list_prompts", "1 prompts")),
+        "{details:?}"
+    );
+}
+
+#[cfg(feature = "echo-server")]
+#[test]
+fn e2e_cli_test_echo_server_text() {
+    let output = run_cli(&["test", echo_server_path()]);
+
+    assert!(output.status.success(), "stderr: {}", stderr_str(&output));
+    let stdout = stdout_str(&output);
+    assert!(stdout.contains("✓ ping"), "{stdout}");
+    assert!(stdout.contains("All tests passed!"), "{stdout}");
+}
+
+/// A stand-in stdio server that advertises tools but never answers
+/// `tools/list`; everything else with an id gets an empty result.
+#[cfg(unix)]
+const STALLING_TOOLS_SERVER: &str = r#"
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  [ -z "$id" ] && continue
+  case "$line" in
+    *'"initialize"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"protocolVersion":"2024-11-05","capabilities":{"tools":{}},"serverInfo":{"name":"stall","version":"1.0.0"}}}\n' "$id" ;;
+    *'"tools/list"'*) ;;
+    *)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{}}\n' "$id" ;;
+  esac
+done
+"#;
+
+#[cfg(unix)]
+#[test]
+fn e2e_cli_test_stalled_list_times_out() {
+    let start = std::time::Instant::now();
+    let output = run_cli(&[
+        "test",
+        "--json",
+        "--timeout",
+        "1",
+        "sh",
+        "-c",
+        STALLING_TOOLS_SERVER,
+    ]);
+    let elapsed = start.elapsed();
+
+    assert!(!output.status.success());
+    assert!(elapsed < std::time::Duration::from_secs(10), "{elapsed:?}");
+    let report: serde_json::Value =
+        serde_json::from_str(&stdout_str(&output)).expect("test report JSON");
+    assert_eq!(report["success"], false);
+
+    let tests = report["tests"].as_array().expect("tests array");
+    let ping = tests.iter().find(|t| t["name"] == "ping").expect("ping");
+    assert_eq!(ping["success"], true, "{ping}");
+    let list_tools = tests
+        .iter()
+        .find(|t| t["name"] == "list_tools")
+        .expect("list_tools");
+    assert_eq!(list_tools["success"], false, "{list_tools}");
+    let error = list_tools["error"].as_str().unwrap_or_default();
+    assert!(error.contains("timed out after 1000ms"), "{error}");
+}
+
+// =============================================================================
+// Logs Command Tests
+// =============================================================================
+
+#[cfg(unix)]
+#[test]
+fn e2e_cli_logs_filters_server_stderr_by_level() {
+    // A stand-in "server" that logs at mixed levels and exits cleanly
+    let script = "echo '[ERROR] fastmcp::server boom' >&2; \
+                  echo '[INFO ] fastmcp::server ready' >&2; \
+                  echo '[WARN ] fastmcp::server slow' >&2; \
+                  echo '[DEBUG] fastmcp::server noise' >&2";
+    let output = run_cli(&["logs", "--level", "warn", "sh", "-c", script]);
+
+    assert!(output.status.success(), "stderr: {}", stderr_str(&output));
+    let stderr = stderr_str(&output);
+    assert!(stderr.contains("boom"), "{stderr}");
+    assert!(stderr.contains("slow"), "{stderr}");
+    assert!(!stderr.contains("ready"), "{stderr}");
+    assert!(!stderr.contains("noise"), "{stderr}");
+}
+
+#[cfg(unix)]
+#[test]
+fn e2e_cli_logs_reports_server_failure() {
+    let output = run_cli(&["logs", "sh", "-c", "exit 3"]);
+
+    assert!(!output.status.success());
+    assert!(stderr_str(&output).contains("Server exited with code 3"));
+}
+
+// =============================================================================
+// Pipe Command Tests
+// =============================================================================
+
+/// A stand-in stdio server: answers `initialize`, swallows `initialized`,
+/// then answers the piped request (sent with id 7) with a tool list.
+#[cfg(unix)]
+const FAKE_TOOLS_SERVER: &str = r#"read -r _
+echo '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2024-11-05","capabilities":{"tools":{}},"serverInfo":{"name":"fake","version":"1.0.0"}}}'
+read -r _
+read -r _
+echo '{"jsonrpc":"2.0","id":7,"result":{"tools":[{"name":"echo","inputSchema":{"type":"object"}}]}}'
+read -r _"#;
+
+#[cfg(unix)]
+#[test]
+fn e2e_cli_pipe_tools_list() {
+    let output = run_cli_with_stdin(
+        &["pipe", "sh", "-c", FAKE_TOOLS_SERVER],
+        r#"{"jsonrpc":"2.0","id":7,"method":"tools/list"}"#,
+    );
+
+    assert!(output.status.success(), "stderr: {}", stderr_str(&output));
+    let response: serde_json::Value =
+        serde_json::from_str(stdout_str(&output).trim()).expect("stdout is one JSON response");
+    assert_eq!(response["jsonrpc"], "2.0");
+    assert_eq!(response["id"], 7);
+    assert_eq!(response["result"]["tools"][0]["name"], "echo");
+    assert!(response.get("error").is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn e2e_cli_pipe_notification_prints_nothing() {
+    let output = run_cli_with_stdin(
+        &["pipe", "sh", "-c", FAKE_TOOLS_SERVER],
+        r#"{"jsonrpc":"2.0","method":"notifications/cancelled","params":{"requestId":3}}"#,
+    );
+
+    assert!(output.status.success(), "stderr: {}", stderr_str(&output));
+    assert!(stdout_str(&output).trim().is_empty());
+}
+
+#[test]
+fn e2e_cli_pipe_rejects_empty_input() {
+    // `output()` gives the CLI an empty stdin, which is not a request
+    let output = run_cli(&["pipe", "./nonexistent-server"]);
+
+    assert!(!output.status.success());
+}
@@ -21,6 +21,7 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::process::{Child, Command, Stdio};
+use std::time::Duration;
 
 /// Guard that kills and waits for a child process when dropped.
 /// Call `disarm()` to prevent cleanup (e.g., when ownership transfers to Client).
@@ -53,9 +54,10 @@ use fastmcp_protocol::{
     ClientCapabilities, ClientInfo, InitializeParams, InitializeResult, JsonRpcMessage,
     JsonRpcRequest, PROTOCOL_VERSION,
 };
-use fastmcp_transport::{StdioTransport, Transport};
+use fastmcp_transport::{FrameMode, Transport};
 
-use crate::{Client, ClientSession};
+use crate::reader::{self, ClientTransport, ReadDeadline};
+use crate::{Client, ClientSession, RetryPolicy};
 
 /// Builder for configuring an MCP client.
 ///
@@ -71,6 +73,8 @@ pub struct ClientBuilder {
     max_retries: u32,
     /// Delay between retries in milliseconds.
     retry_delay_ms: u64,
+    /// Retry policy for idempotent requests.
+    request_retry: Option<RetryPolicy>,
     /// Working directory for subprocess.
     working_dir: Option<PathBuf>,
     /// Environment variables to set for subprocess.
@@ -81,6 +85,8 @@ pub struct ClientBuilder {
     capabilities: ClientCapabilities,
     /// Whether to defer initialization until first use.
     auto_initialize: bool,
+    /// How messages are delimited on the subprocess pipes.
+    frame_mode: FrameMode,
 }
 
 impl ClientBuilder {
@@ -93,6 +99,7 @@ impl ClientBuilder {
     /// - Retry delay: 1 second
     /// - Inherit environment: true
     /// - Auto-initialize: false (initialize immediately on connect)
+    /// - Framing: NDJSON
     #[must_use]
     pub fn new() -> Self {
         Self {
@@ -103,11 +110,13 @@ impl ClientBuilder {
             timeout_ms: 30_000,
             max_retries: 0,
             retry_delay_ms: 1_000,
+            request_retry: None,
             working_dir: None,
             env_vars: HashMap::new(),
             inherit_env: true,
             capabilities: ClientCapabilities::default(),
             auto_initialize: false,
+            frame_mode: FrameMode::Ndjson,
         }
     }
 
@@ -152,6 +161,28 @@ impl ClientBuilder {
         self
     }
 
+    /// Retries idempotent requests that fail with a transient transport error.
+    ///
+    /// Listing, reading, `ping`, and [`Client::call_tool_idempotent`] are
+    /// re-sent under a fresh request id up to `max_attempts` times in total,
+    /// waiting an exponential backoff with jitter starting at `base_backoff`.
+    /// Other requests, including plain [`Client::call_tool`], are never
+    /// retried. Unlike [`max_retries`](Self::max_retries), this applies after
+    /// the connection is established.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let client = ClientBuilder::new()
+    ///     .retry(3, Duration::from_millis(200))
+    ///     .connect_stdio("uvx", &["my-server"])?;
+    /// ```
+    #[must_use]
+    pub fn retry(mut self, max_attempts: u32, base_backoff: Duration) -> Self {
+        self.request_retry = Some(RetryPolicy::new(max_attempts, base_backoff));
+        self
+    }
+
     /// Sets the working directory for the subprocess.
     ///
     /// If not set, the subprocess inherits the current working directory.
@@ -228,10 +259,21 @@ impl ClientBuilder {
         self
     }
 
+    /// Sets how messages are delimited on the subprocess pipes.
+    ///
+    /// Use [`FrameMode::ContentLength`] for servers that speak LSP-style
+    /// `Content-Length` frames. Default is [`FrameMode::Ndjson`].
+    #[must_use]
+    pub fn frame_mode(mut self, frame_mode: FrameMode) -> Self {
+        self.frame_mode = frame_mode;
+        self
+    }
+
     /// Connects to a server via stdio subprocess.
     ///
     /// Spawns the specified command as a subprocess and communicates via
-    /// stdin/stdout using JSON-RPC over NDJSON framing.
+    /// stdin/stdout using JSON-RPC over NDJSON framing (see
+    /// [`frame_mode`](Self::frame_mode)).
     ///
     /// # Arguments
     ///
@@ -312,14 +354,15 @@ impl ClientBuilder {
             .ok_or_else(|| McpError::internal_error("Failed to get subprocess stdout"))?;
 
         // Create transport
-        let transport = StdioTransport::new(stdout, stdin);
+        let (transport, read_deadline) = reader::client_transport(stdout, stdin);
+        let transport = transport.with_frame_mode(self.frame_mode);
 
         if self.auto_initialize {
             // Create uninitialized client - initialization will happen on first use
-            Ok(self.create_uninitialized_client(child, transport, cx))
+            Ok(self.create_uninitialized_client(child, transport, read_deadline, cx))
         } else {
             // Perform initialization immediately
-            self.initialize_client(child, transport, cx)
+            self.initialize_client(child, transport, read_deadline, cx)
         }
     }
 
@@ -327,7 +370,8 @@ impl ClientBuilder {
     fn create_uninitialized_client(
         &self,
         child: Child,
-        transport: StdioTransport<std::process::ChildStdout, std::process::ChildStdin>,
+        transport: ClientTransport,
+        read_deadline: ReadDeadline,
         cx: &Cx,
     ) -> Client {
         // Create a placeholder session - will be updated on first use
@@ -342,14 +386,23 @@ impl ClientBuilder {
             String::new(),
         );
 
-        Client::from_parts_uninitialized(child, transport, cx.clone(), session, self.timeout_ms)
+        Client::from_parts_uninitialized(
+            child,
+            transport,
+            read_deadline,
+            cx.clone(),
+            session,
+            self.timeout_ms,
+            self.request_retry,
+        )
     }
 
     /// Performs the initialization handshake and creates the client.
     fn initialize_client(
         &self,
         child: Child,
-        mut transport: StdioTransport<std::process::ChildStdout, std::process::ChildStdin>,
+        mut transport: ClientTransport,
+        read_deadline: ReadDeadline,
         cx: &Cx,
     ) -> McpResult<Client> {
         // Guard ensures child process is killed if initialization fails.
@@ -375,7 +428,12 @@ impl ClientBuilder {
             .send(cx, &JsonRpcMessage::Request(init_request))
             .map_err(|e| McpError::internal_error(format!("Failed to send initialize: {e}")))?;
 
-        // Receive initialize response
+        // Receive initialize response, within the request timeout
+        if self.timeout_ms > 0 {
+            read_deadline.set(Some(
+                std::time::Instant::now() + std::time::Duration::from_millis(self.timeout_ms),
+            ));
+        }
         let response = loop {
             let msg = transport.recv(cx).map_err(|e| {
                 McpError::internal_error(format!("Failed to receive response: {e}"))
@@ -388,6 +446,7 @@ impl ClientBuilder {
                 }
             }
         };
+        read_deadline.set(None);
 
         // Check for error
         if let Some(error) = response.error {
@@ -431,9 +490,11 @@ impl ClientBuilder {
         Ok(Client::from_parts(
             child_guard.disarm(),
             transport,
+            read_deadline,
             cx.clone(),
             session,
             self.timeout_ms,
+            self.request_retry,
         ))
     }
 }
@@ -459,6 +520,16 @@ mod tests {
         assert!(builder.working_dir.is_none());
         assert!(builder.env_vars.is_empty());
         assert!(!builder.auto_initialize);
+        assert!(builder.request_retry.is_none());
+    }
+
+    #[test]
+    fn test_builder_request_retry() {
+        let builder = ClientBuilder::new().retry(3, Duration::from_millis(200));
+        assert_eq!(
+            builder.request_retry,
+            Some(RetryPolicy::new(3, Duration::from_millis(200)))
+        );
     }
 
     #[test]
@@ -514,12 +585,20 @@ mod tests {
         assert!(!builder.auto_initialize);
     }
 
+    #[test]
+    fn test_builder_frame_mode() {
+        assert_eq!(ClientBuilder::new().frame_mode, FrameMode::Ndjson);
+        let builder = ClientBuilder::new().frame_mode(FrameMode::ContentLength);
+        assert_eq!(builder.frame_mode, FrameMode::ContentLength);
+    }
+
     #[test]
     fn test_builder_capabilities() {
         let caps = ClientCapabilities {
             sampling: Some(fastmcp_protocol::SamplingCapability {}),
             elicitation: None,
             roots: None,
+            experimental: None,
         };
         let builder = ClientBuilder::new().capabilities(caps);
         assert!(builder.capabilities.sampling.is_some());
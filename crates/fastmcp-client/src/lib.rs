@@ -5,6 +5,7 @@
 //! - Tool invocation
 //! - Resource reading
 //! - Prompt fetching
+//! - Server notifications
 //!
 //! # Example
 //!
@@ -25,12 +26,17 @@
 
 mod builder;
 pub mod mcp_config;
+mod notification;
+mod reader;
+mod retry;
 mod session;
 
 pub use builder::ClientBuilder;
+pub use notification::{Notification, NotificationHandler};
+pub use retry::RetryPolicy;
 pub use session::ClientSession;
 
-use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::process::{Child, Command, Stdio};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 
@@ -53,7 +59,9 @@ use fastmcp_protocol::{
 ///
 /// The callback receives the progress value, optional total, and optional message.
 pub type ProgressCallback<'a> = &'a mut dyn FnMut(f64, Option<f64>, Option<&str>);
-use fastmcp_transport::{StdioTransport, Transport, TransportError};
+use fastmcp_transport::{Transport, TransportError};
+use notification::NotificationSink;
+use reader::{ClientTransport, ReadDeadline};
 
 #[derive(Debug, serde::Deserialize)]
 struct ClientProgressParams {
@@ -64,6 +72,9 @@ struct ClientProgressParams {
     message: Option<String>,
 }
 
+/// Times a paginated listing is started over after `cursor_invalidated`.
+const MAX_LIST_ATTEMPTS: usize = 3;
+
 fn method_not_found_response(request: &JsonRpcRequest) -> Option<JsonRpcMessage> {
     let id = request.id.clone()?;
     let error = McpError::method_not_found(&request.method);
@@ -79,7 +90,9 @@ pub struct Client {
     /// The subprocess running the MCP server.
     child: Child,
     /// Transport for communication.
-    transport: StdioTransport<ChildStdout, ChildStdin>,
+    transport: ClientTransport,
+    /// Deadline for reads from the server, set per request.
+    read_deadline: ReadDeadline,
     /// Capability context for cancellation.
     cx: Cx,
     /// Session state after initialization.
@@ -88,11 +101,15 @@ pub struct Client {
     next_id: AtomicU64,
     /// Request timeout in milliseconds (0 = no timeout).
     timeout_ms: u64,
+    /// Retry policy for idempotent requests (None = never retry).
+    retry: Option<RetryPolicy>,
     /// Whether auto-initialization is enabled (for documentation/debugging).
     #[allow(dead_code)]
     auto_initialize: bool,
     /// Whether the client has been initialized.
     initialized: AtomicBool,
+    /// Notifications received from the server.
+    notifications: NotificationSink,
 }
 
 impl Client {
@@ -132,7 +149,7 @@ impl Client {
             .ok_or_else(|| McpError::internal_error("Failed to get subprocess stdout"))?;
 
         // Create transport
-        let transport = StdioTransport::new(stdout, stdin);
+        let (transport, read_deadline) = reader::client_transport(stdout, stdin);
 
         // Create client info
         let client_info = ClientInfo {
@@ -145,6 +162,7 @@ impl Client {
         let mut client = Self {
             child,
             transport,
+            read_deadline,
             cx,
             session: ClientSession::new(
                 client_info.clone(),
@@ -158,8 +176,10 @@ impl Client {
             ),
             next_id: AtomicU64::new(1),
             timeout_ms: 30_000, // Default 30 second timeout
+            retry: None,
             auto_initialize: false,
             initialized: AtomicBool::new(false),
+            notifications: NotificationSink::default(),
         };
 
         // Perform initialization handshake
@@ -194,20 +214,25 @@ impl Client {
     /// This is an internal constructor used by the builder.
     pub(crate) fn from_parts(
         child: Child,
-        transport: StdioTransport<ChildStdout, ChildStdin>,
+        transport: ClientTransport,
+        read_deadline: ReadDeadline,
         cx: Cx,
         session: ClientSession,
         timeout_ms: u64,
+        retry: Option<RetryPolicy>,
     ) -> Self {
         Self {
             child,
             transport,
+            read_deadline,
             cx,
             session,
             next_id: AtomicU64::new(2), // Start at 2 since initialize used 1
             timeout_ms,
+            retry,
             auto_initialize: false,
             initialized: AtomicBool::new(true), // Already initialized by builder
+            notifications: NotificationSink::default(),
         }
     }
 
@@ -216,20 +241,25 @@ impl Client {
     /// This is an internal constructor used by the builder when auto_initialize is enabled.
     pub(crate) fn from_parts_uninitialized(
         child: Child,
-        transport: StdioTransport<ChildStdout, ChildStdin>,
+        transport: ClientTransport,
+        read_deadline: ReadDeadline,
         cx: Cx,
         session: ClientSession,
         timeout_ms: u64,
+        retry: Option<RetryPolicy>,
     ) -> Self {
         Self {
             child,
             transport,
+            read_deadline,
             cx,
             session,
             next_id: AtomicU64::new(1), // Start at 1 since initialize hasn't happened
             timeout_ms,
+            retry,
             auto_initialize: true,
             initialized: AtomicBool::new(false),
+            notifications: NotificationSink::default(),
         }
     }
 
@@ -303,31 +333,34 @@ impl Client {
     }
 
     /// Sends a request and waits for response.
+    ///
+    /// Idempotent methods are retried under the client's retry policy.
     fn send_request<P: serde::Serialize, R: serde::de::DeserializeOwned>(
         &mut self,
         method: &str,
         params: P,
     ) -> McpResult<R> {
-        let id = self.next_request_id();
+        self.send_request_with_retry(method, params, retry::is_idempotent_method(method))
+    }
+
+    /// Sends a request, retrying transient transport failures if `idempotent`.
+    fn send_request_with_retry<P: serde::Serialize, R: serde::de::DeserializeOwned>(
+        &mut self,
+        method: &str,
+        params: P,
+        idempotent: bool,
+    ) -> McpResult<R> {
         let params_value = serde_json::to_value(params)
             .map_err(|e| McpError::internal_error(format!("Failed to serialize params: {e}")))?;
 
-        #[allow(clippy::cast_possible_wrap)]
-        let (request_id, request) = {
-            let id_i64 = id as i64;
-            (
-                RequestId::Number(id_i64),
-                JsonRpcRequest::new(method, Some(params_value), id_i64),
-            )
-        };
-
-        // Send request
-        self.transport
-            .send(&self.cx, &JsonRpcMessage::Request(request))
-            .map_err(transport_error_to_mcp)?;
-
-        // Receive response with ID validation
-        let response = self.recv_response(&request_id)?;
+        let policy = if idempotent { self.retry } else { None };
+        let mut timed_out = false;
+        let response = retry::run_with_retry(policy.as_ref(), || {
+            let result = self.exchange(method, &params_value);
+            timed_out = matches!(result, Err(TransportError::Timeout));
+            result
+        })
+        .map_err(|e| if timed_out { self.timeout_error() } else { e })?;
 
         // Check for error response
         if let Some(error) = response.error {
@@ -346,6 +379,30 @@ impl Client {
             .map_err(|e| McpError::internal_error(format!("Failed to deserialize response: {e}")))
     }
 
+    /// Sends one request under a fresh id and waits for its response.
+    fn exchange(
+        &mut self,
+        method: &str,
+        params: &serde_json::Value,
+    ) -> Result<JsonRpcResponse, TransportError> {
+        let id = self.next_request_id();
+
+        #[allow(clippy::cast_possible_wrap)]
+        let (request_id, request) = {
+            let id_i64 = id as i64;
+            (
+                RequestId::Number(id_i64),
+                JsonRpcRequest::new(method, Some(params.clone()), id_i64),
+            )
+        };
+
+        self.transport
+            .send(&self.cx, &JsonRpcMessage::Request(request))?;
+
+        // Receive response with ID validation
+        self.recv_response(&request_id)
+    }
+
     /// Sends a notification (no response expected).
     fn send_notification<P: serde::Serialize>(&mut self, method: &str, params: P) -> McpResult<()> {
         let params_value = serde_json::to_value(params)
@@ -366,6 +423,30 @@ impl Client {
         Ok(())
     }
 
+    /// Sends a caller-built JSON-RPC request and returns the raw response.
+    ///
+    /// The request goes out with its own id, and error responses are returned
+    /// as-is instead of being converted to [`McpError`]. Notifications
+    /// (requests without an id) are sent without waiting and return `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request cannot be sent or no response arrives.
+    pub fn send_raw(&mut self, request: JsonRpcRequest) -> McpResult<Option<JsonRpcResponse>> {
+        let id = request.id.clone();
+        self.transport
+            .send(&self.cx, &JsonRpcMessage::Request(request))
+            .map_err(transport_error_to_mcp)?;
+
+        match id {
+            Some(id) => self
+                .recv_response(&id)
+                .map(Some)
+                .map_err(transport_error_to_mcp),
+            None => Ok(None),
+        }
+    }
+
     /// Sends a cancellation notification for a previously issued request.
     ///
     /// Set `await_cleanup` to request that the server wait for any cleanup
@@ -388,11 +469,56 @@ impl Client {
         self.send_notification("notifications/cancelled", params)
     }
 
+    /// Returns the notifications received from the server since the last
+    /// call, in arrival order.
+    ///
+    /// Notifications that arrive while a request is in flight are buffered
+    /// rather than dropped, so they can be read once the call returns. Use
+    /// [`Notification::progress_token`] to match progress to the call that
+    /// asked for it. Progress delivered to a
+    /// [`call_tool_with_progress`](Self::call_tool_with_progress) callback is
+    /// not buffered again.
+    pub fn notifications(&mut self) -> impl Iterator<Item = Notification> + '_ {
+        self.notifications.drain()
+    }
+
+    /// Registers a handler called for each notification as it arrives.
+    ///
+    /// The handler runs while the client waits for a response, so it sees
+    /// progress live during long calls. Notifications handed to the handler
+    /// are not buffered for [`notifications`](Self::notifications).
+    pub fn on_notification<F>(&mut self, handler: F)
+    where
+        F: FnMut(&Notification) + Send + 'static,
+    {
+        self.notifications.set_handler(Box::new(handler));
+    }
+
+    /// Handles a request or notification sent by the server.
+    ///
+    /// Notifications are logged if they are log messages and passed on to the
+    /// notification sink. Requests are answered with "method not found".
+    fn handle_server_request(&mut self, request: JsonRpcRequest) -> Result<(), TransportError> {
+        if let Some(response) = method_not_found_response(&request) {
+            return self.transport.send(&self.cx, &response);
+        }
+
+        if request.method == "notifications/message" {
+            if let Some(params) = request.params.as_ref() {
+                if let Ok(message) = serde_json::from_value::<LogMessageParams>(params.clone()) {
+                    self.emit_log_message(message);
+                }
+            }
+        }
+        self.notifications.push(Notification::from(request));
+        Ok(())
+    }
+
     /// Receives a response from the transport, validating the response ID.
     fn recv_response(
         &mut self,
         expected_id: &RequestId,
-    ) -> McpResult<fastmcp_protocol::JsonRpcResponse> {
+    ) -> Result<JsonRpcResponse, TransportError> {
         // Calculate deadline if timeout is configured
         let deadline = if self.timeout_ms > 0 {
             Some(Instant::now() + Duration::from_millis(self.timeout_ms))
@@ -404,14 +530,11 @@ impl Client {
             // Check timeout before each recv attempt
             if let Some(deadline) = deadline {
                 if Instant::now() >= deadline {
-                    return Err(McpError::internal_error("Request timed out"));
+                    return Err(TransportError::Timeout);
                 }
             }
 
-            let message = self
-                .transport
-                .recv(&self.cx)
-                .map_err(transport_error_to_mcp)?;
+            let message = self.recv_until(deadline)?;
 
             match message {
                 JsonRpcMessage::Response(response) => {
@@ -427,26 +550,29 @@ impl Client {
                 }
                 JsonRpcMessage::Request(request) => {
                     // Server sending a request to client (e.g., notification)
-                    if request.method == "notifications/message" {
-                        if let Some(params) = request.params.as_ref() {
-                            if let Ok(message) =
-                                serde_json::from_value::<LogMessageParams>(params.clone())
-                            {
-                                self.emit_log_message(message);
-                            }
-                        }
-                    }
-
-                    if let Some(response) = method_not_found_response(&request) {
-                        self.transport
-                            .send(&self.cx, &response)
-                            .map_err(transport_error_to_mcp)?;
-                    }
+                    self.handle_server_request(request)?;
                 }
             }
         }
     }
 
+    /// Receives the next message, failing with [`TransportError::Timeout`]
+    /// if none arrives before `deadline`.
+    fn recv_until(&mut self, deadline: Option<Instant>) -> Result<JsonRpcMessage, TransportError> {
+        self.read_deadline.set(deadline);
+        match self.transport.recv(&self.cx) {
+            Err(TransportError::Io(e)) if e.kind() == std::io::ErrorKind::TimedOut => {
+                Err(TransportError::Timeout)
+            }
+            other => other,
+        }
+    }
+
+    /// Returns the error reported when a request exceeds the timeout.
+    fn timeout_error(&self) -> McpError {
+        McpError::internal_error(format!("Request timed out after {}ms", self.timeout_ms))
+    }
+
     /// Performs the initialization handshake.
     fn initialize(
         &mut self,
@@ -462,16 +588,74 @@ impl Client {
         self.send_request("initialize", params)
     }
 
-    /// Lists available tools.
+    /// Sends a `ping` and returns the measured round-trip time.
+    ///
+    /// `ping` does not depend on any capability, so this doubles as a
+    /// liveness check.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or times out.
+    pub fn ping(&mut self) -> McpResult<Duration> {
+        let start = Instant::now();
+        let _: serde_json::Value = self.send_request("ping", serde_json::json!({}))?;
+        Ok(start.elapsed())
+    }
+
+    /// Lists available tools, following `nextCursor` across pages.
     ///
     /// # Errors
     ///
     /// Returns an error if the request fails.
     pub fn list_tools(&mut self) -> McpResult<Vec<Tool>> {
         self.ensure_initialized()?;
-        let params = ListToolsParams::default();
-        let result: ListToolsResult = self.send_request("tools/list", params)?;
-        Ok(result.tools)
+        self.list_all(
+            "tools/list",
+            |cursor| ListToolsParams {
+                cursor,
+                ..ListToolsParams::default()
+            },
+            |result: ListToolsResult| (result.tools, result.next_cursor),
+        )
+    }
+
+    /// Collects every page of a paginated `*/list` method.
+    ///
+    /// If the server reports that the listing changed mid-way
+    /// (`cursor_invalidated`), the listing is restarted from the first page,
+    /// up to [`MAX_LIST_ATTEMPTS`] times.
+    fn list_all<P, R, T>(
+        &mut self,
+        method: &str,
+        params: impl Fn(Option<String>) -> P,
+        page: impl Fn(R) -> (Vec<T>, Option<String>),
+    ) -> McpResult<Vec<T>>
+    where
+        P: serde::Serialize,
+        R: serde::de::DeserializeOwned,
+    {
+        let mut attempt = 1;
+        'restart: loop {
+            let mut items = Vec::new();
+            let mut cursor = None;
+            loop {
+                match self.send_request(method, params(cursor.take())) {
+                    Ok(result) => {
+                        let (mut entries, next_cursor) = page(result);
+                        items.append(&mut entries);
+                        match next_cursor {
+                            Some(next) => cursor = Some(next),
+                            None => return Ok(items),
+                        }
+                    }
+                    Err(e) if e.is_cursor_invalidated() && attempt < MAX_LIST_ATTEMPTS => {
+                        attempt += 1;
+                        continue 'restart;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        }
     }
 
     /// Calls a tool with the given arguments.
@@ -483,6 +667,32 @@ impl Client {
         &mut self,
         name: &str,
         arguments: serde_json::Value,
+    ) -> McpResult<Vec<Content>> {
+        self.call_tool_inner(name, arguments, false)
+    }
+
+    /// Calls a tool that the caller knows is safe to run more than once.
+    ///
+    /// Unlike [`call_tool`](Self::call_tool), this request is retried on
+    /// transient transport failures under the client's retry policy, which
+    /// may execute the tool again on the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tool call fails.
+    pub fn call_tool_idempotent(
+        &mut self,
+        name: &str,
+        arguments: serde_json::Value,
+    ) -> McpResult<Vec<Content>> {
+        self.call_tool_inner(name, arguments, true)
+    }
+
+    fn call_tool_inner(
+        &mut self,
+        name: &str,
+        arguments: serde_json::Value,
+        idempotent: bool,
     ) -> McpResult<Vec<Content>> {
         self.ensure_initialized()?;
         let params = CallToolParams {
@@ -490,7 +700,8 @@ impl Client {
             arguments: Some(arguments),
             meta: None,
         };
-        let result: CallToolResult = self.send_request("tools/call", params)?;
+        let result: CallToolResult =
+            self.send_request_with_retry("tools/call", params, idempotent)?;
 
         if result.is_error {
             // Extract error message from content if available
@@ -539,6 +750,7 @@ impl Client {
             arguments: Some(arguments),
             meta: Some(RequestMeta {
                 progress_token: Some(progress_marker.clone()),
+                result_format: None,
             }),
         };
 
@@ -623,49 +835,37 @@ impl Client {
             // Check timeout before each recv attempt
             if let Some(deadline) = deadline {
                 if Instant::now() >= deadline {
-                    return Err(McpError::internal_error("Request timed out"));
+                    return Err(self.timeout_error());
                 }
             }
 
-            let message = self
-                .transport
-                .recv(&self.cx)
-                .map_err(transport_error_to_mcp)?;
+            let message = match self.recv_until(deadline) {
+                Ok(message) => message,
+                Err(TransportError::Timeout) => return Err(self.timeout_error()),
+                Err(e) => return Err(transport_error_to_mcp(e)),
+            };
 
             match message {
                 JsonRpcMessage::Response(response) => return Ok(response),
                 JsonRpcMessage::Request(request) => {
-                    // Check if this is a progress notification
+                    // Progress for our marker goes to the callback
                     if request.method == "notifications/progress" {
-                        if let Some(params) = request.params.as_ref() {
-                            if let Ok(progress) =
-                                serde_json::from_value::<ClientProgressParams>(params.clone())
-                            {
-                                // Only handle progress for our expected marker
-                                if progress.marker == *expected_marker {
-                                    on_progress(
-                                        progress.progress,
-                                        progress.total,
-                                        progress.message.as_deref(),
-                                    );
-                                }
-                            }
-                        }
-                    } else if request.method == "notifications/message" {
-                        if let Some(params) = request.params.as_ref() {
-                            if let Ok(message) =
-                                serde_json::from_value::<LogMessageParams>(params.clone())
-                            {
-                                self.emit_log_message(message);
+                        if let Some(progress) = request.params.as_ref().and_then(|params| {
+                            serde_json::from_value::<ClientProgressParams>(params.clone()).ok()
+                        }) {
+                            if progress.marker == *expected_marker {
+                                on_progress(
+                                    progress.progress,
+                                    progress.total,
+                                    progress.message.as_deref(),
+                                );
+                                continue;
                             }
                         }
                     }
 
-                    if let Some(response) = method_not_found_response(&request) {
-                        self.transport
-                            .send(&self.cx, &response)
-                            .map_err(transport_error_to_mcp)?;
-                    }
+                    self.handle_server_request(request)
+                        .map_err(transport_error_to_mcp)?;
                     // Continue waiting for actual response
                 }
             }
@@ -689,16 +889,21 @@ impl Client {
         log::log!(target: target, level, "{text}");
     }
 
-    /// Lists available resources.
+    /// Lists available resources, following `nextCursor` across pages.
     ///
     /// # Errors
     ///
     /// Returns an error if the request fails.
     pub fn list_resources(&mut self) -> McpResult<Vec<Resource>> {
         self.ensure_initialized()?;
-        let params = ListResourcesParams::default();
-        let result: ListResourcesResult = self.send_request("resources/list", params)?;
-        Ok(result.resources)
+        self.list_all(
+            "resources/list",
+            |cursor| ListResourcesParams {
+                cursor,
+                ..ListResourcesParams::default()
+            },
+            |result: ListResourcesResult| (result.resources, result.next_cursor),
+        )
     }
 
     /// Lists available resource templates.
@@ -735,22 +940,30 @@ impl Client {
         self.ensure_initialized()?;
         let params = ReadResourceParams {
             uri: uri.to_string(),
+            range: None,
+            if_none_match: None,
+            if_modified_since: None,
             meta: None,
         };
         let result: ReadResourceResult = self.send_request("resources/read", params)?;
         Ok(result.contents)
     }
 
-    /// Lists available prompts.
+    /// Lists available prompts, following `nextCursor` across pages.
     ///
     /// # Errors
     ///
     /// Returns an error if the request fails.
     pub fn list_prompts(&mut self) -> McpResult<Vec<Prompt>> {
         self.ensure_initialized()?;
-        let params = ListPromptsParams::default();
-        let result: ListPromptsResult = self.send_request("prompts/list", params)?;
-        Ok(result.prompts)
+        self.list_all(
+            "prompts/list",
+            |cursor| ListPromptsParams {
+                cursor,
+                ..ListPromptsParams::default()
+            },
+            |result: ListPromptsResult| (result.prompts, result.next_cursor),
+        )
     }
 
     /// Gets a prompt with the given arguments.
@@ -1140,4 +1353,104 @@ mod tests {
         assert!(params.total.is_none());
         assert!(params.message.is_none());
     }
+
+    // ========================================
+    // ping tests
+    // ========================================
+
+    /// A shell server that answers `initialize` and replies `{}` to anything
+    /// else carrying an id.
+    #[cfg(unix)]
+    const FIXTURE_SERVER: &str = r#"
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  [ -z "$id" ] && continue
+  case "$line" in
+    *'"initialize"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"protocolVersion":"2024-11-05","capabilities":{},"serverInfo":{"name":"fixture","version":"1.0"}}}\n' "$id" ;;
+    *)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{}}\n' "$id" ;;
+  esac
+done
+"#;
+
+    #[cfg(unix)]
+    #[test]
+    fn ping_measures_round_trip() {
+        let mut client = Client::stdio("sh", &["-c", FIXTURE_SERVER]).expect("connect");
+        let latency = client.ping().expect("ping");
+        assert!(latency > Duration::ZERO);
+        assert!(latency < Duration::from_secs(5), "latency {latency:?}");
+        client.close();
+    }
+
+    // ========================================
+    // notification tests
+    // ========================================
+
+    /// Like [`FIXTURE_SERVER`], but sends two progress notifications, keyed
+    /// by the request id, before answering `tools/call`.
+    #[cfg(unix)]
+    const PROGRESS_SERVER: &str = r#"
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  [ -z "$id" ] && continue
+  case "$line" in
+    *'"initialize"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"protocolVersion":"2024-11-05","capabilities":{},"serverInfo":{"name":"fixture","version":"1.0"}}}\n' "$id" ;;
+    *'"tools/call"'*)
+      printf '{"jsonrpc":"2.0","method":"notifications/progress","params":{"progressToken":%s,"progress":1,"total":2}}\n' "$id"
+      printf '{"jsonrpc":"2.0","method":"notifications/progress","params":{"progressToken":%s,"progress":2,"total":2}}\n' "$id"
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"content":[{"type":"text","text":"done"}]}}\n' "$id" ;;
+    *)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{}}\n' "$id" ;;
+  esac
+done
+"#;
+
+    #[cfg(unix)]
+    #[test]
+    fn notifications_received_during_call_are_buffered() {
+        let mut client = Client::stdio("sh", &["-c", PROGRESS_SERVER]).expect("connect");
+        let content = client
+            .call_tool("slow", serde_json::json!({}))
+            .expect("call tool");
+        assert!(matches!(&content[..], [Content::Text { text }] if text == "done"));
+
+        let notifications: Vec<Notification> = client.notifications().collect();
+        let progress: Vec<f64> = notifications
+            .iter()
+            .filter_map(Notification::progress)
+            .map(|params| params.progress)
+            .collect();
+        assert_eq!(progress, [1.0, 2.0]);
+        let tokens: Vec<_> = notifications
+            .iter()
+            .filter_map(Notification::progress_token)
+            .collect();
+        assert_eq!(tokens[0], tokens[1]);
+        assert_eq!(client.notifications().count(), 0);
+        client.close();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn notification_handler_sees_progress_live() {
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut client = Client::stdio("sh", &["-c", PROGRESS_SERVER]).expect("connect");
+        let sink = std::sync::Arc::clone(&seen);
+        client.on_notification(move |notification| {
+            sink.lock().unwrap().push(notification.method.clone());
+        });
+
+        client
+            .call_tool("slow", serde_json::json!({}))
+            .expect("call tool");
+        assert_eq!(
+            *seen.lock().unwrap(),
+            ["notifications/progress", "notifications/progress"]
+        );
+        assert_eq!(client.notifications().count(), 0);
+        client.close();
+    }
 }
@@ -48,11 +48,10 @@
 use std::collections::HashMap;
 use std::env;
 use std::path::{Path, PathBuf};
-use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::process::{Child, Command, Stdio};
 
 use asupersync::Cx;
 use fastmcp_core::{McpError, McpResult};
-use fastmcp_transport::StdioTransport;
 use serde::{Deserialize, Serialize};
 
 use crate::{Client, ClientSession};
@@ -357,7 +356,7 @@ fn spawn_client_from_config(
     })?;
 
     // Create transport
-    let transport = StdioTransport::new(stdout, stdin);
+    let (transport, read_deadline) = crate::reader::client_transport(stdout, stdin);
 
     // Create client info
     let client_info = ClientInfo {
@@ -367,14 +366,22 @@ fn spawn_client_from_config(
     let client_capabilities = ClientCapabilities::default();
 
     // Create client and initialize
-    create_and_initialize_client(child, transport, cx, client_info, client_capabilities)
-        .map_err(|e| ConfigError::SpawnError(format!("Initialization failed: {e}")))
+    create_and_initialize_client(
+        child,
+        transport,
+        read_deadline,
+        cx,
+        client_info,
+        client_capabilities,
+    )
+    .map_err(|e| ConfigError::SpawnError(format!("Initialization failed: {e}")))
 }
 
 /// Creates a client and performs initialization handshake.
 fn create_and_initialize_client(
     child: Child,
-    mut transport: StdioTransport<ChildStdout, ChildStdin>,
+    mut transport: crate::reader::ClientTransport,
+    read_deadline: crate::reader::ReadDeadline,
     cx: Cx,
     client_info: ClientInfo,
     client_capabilities: ClientCapabilities,
@@ -446,7 +453,15 @@ fn create_and_initialize_client(
     );
 
     // Return client
-    Ok(Client::from_parts(child, transport, cx, session, 30_000))
+    Ok(Client::from_parts(
+        child,
+        transport,
+        read_deadline,
+        cx,
+        session,
+        30_000,
+        None,
+    ))
 }
 
 // ============================================================================
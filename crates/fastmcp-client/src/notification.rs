@@ -0,0 +1,87 @@
+//! Server-pushed notifications.
+//!
+//! Servers send notifications (progress, `resources/updated`, list changes,
+//! log messages) at any time, including while the client waits for the
+//! response to a call. The client buffers them as they arrive so the response
+//! still goes to its caller; read them back with
+//! [`Client::notifications`](crate::Client::notifications), or register a
+//! handler with [`Client::on_notification`](crate::Client::on_notification)
+//! to see them live.
+
+use std::collections::VecDeque;
+
+use fastmcp_protocol::{JsonRpcRequest, ProgressParams, ProgressToken};
+use serde_json::Value;
+
+/// Most notifications buffered before the oldest are dropped.
+const MAX_BUFFERED: usize = 1024;
+
+/// Handler invoked for each notification as it arrives.
+pub type NotificationHandler = Box<dyn FnMut(&Notification) + Send>;
+
+/// A notification received from the server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    /// Notification method, e.g. `notifications/progress`.
+    pub method: String,
+    /// Notification params, if any.
+    pub params: Option<Value>,
+}
+
+impl Notification {
+    /// Returns the params of a `notifications/progress` notification.
+    #[must_use]
+    pub fn progress(&self) -> Option<ProgressParams> {
+        if self.method != "notifications/progress" {
+            return None;
+        }
+        serde_json::from_value(self.params.clone()?).ok()
+    }
+
+    /// Returns the progress token, correlating progress with the request
+    /// that asked for it.
+    #[must_use]
+    pub fn progress_token(&self) -> Option<ProgressToken> {
+        self.progress().map(|progress| progress.progress_token)
+    }
+}
+
+impl From<JsonRpcRequest> for Notification {
+    fn from(request: JsonRpcRequest) -> Self {
+        Self {
+            method: request.method,
+            params: request.params,
+        }
+    }
+}
+
+/// Where received notifications go.
+#[derive(Default)]
+pub(crate) struct NotificationSink {
+    buffer: VecDeque<Notification>,
+    handler: Option<NotificationHandler>,
+}
+
+impl NotificationSink {
+    /// Hands a notification to the handler, or buffers it if none is set.
+    pub(crate) fn push(&mut self, notification: Notification) {
+        if let Some(handler) = self.handler.as_mut() {
+            handler(&notification);
+            return;
+        }
+        if self.buffer.len() == MAX_BUFFERED {
+            self.buffer.pop_front();
+        }
+        self.buffer.push_back(notification);
+    }
+
+    /// Sets the handler, replacing any previous one.
+    pub(crate) fn set_handler(&mut self, handler: NotificationHandler) {
+        self.handler = Some(handler);
+    }
+
+    /// Removes and returns buffered notifications in arrival order.
+    pub(crate) fn drain(&mut self) -> std::collections::vec_deque::Drain<'_, Notification> {
+        self.buffer.drain(..)
+    }
+}
@@ -0,0 +1,128 @@
+//! Reading server output with a deadline.
+//!
+//! Reads from a subprocess pipe block until the server writes, so a server
+//! that stalls would hang the client past its request timeout. The
+//! [`DeadlineReader`] moves the blocking reads onto a background thread and
+//! waits for their output only until the current [`ReadDeadline`].
+
+use std::io::{self, Read};
+use std::process::{ChildStdin, ChildStdout};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Instant;
+
+use fastmcp_transport::StdioTransport;
+
+/// Bytes requested from the pipe per read.
+const CHUNK_SIZE: usize = 8192;
+
+/// Transport used by [`Client`](crate::Client) for a subprocess server.
+pub(crate) type ClientTransport = StdioTransport<DeadlineReader, ChildStdin>;
+
+/// Creates a transport for a subprocess and the handle setting its read
+/// deadline.
+pub(crate) fn client_transport(
+    stdout: ChildStdout,
+    stdin: ChildStdin,
+) -> (ClientTransport, ReadDeadline) {
+    let reader = DeadlineReader::new(stdout);
+    let deadline = reader.deadline();
+    (StdioTransport::new(reader, stdin), deadline)
+}
+
+/// Shared deadline for reads through a [`DeadlineReader`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ReadDeadline(Arc<Mutex<Option<Instant>>>);
+
+impl ReadDeadline {
+    /// Sets the deadline for subsequent reads, or clears it with `None`.
+    pub(crate) fn set(&self, deadline: Option<Instant>) {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = deadline;
+    }
+
+    fn get(&self) -> Option<Instant> {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A reader that fails with [`io::ErrorKind::TimedOut`] once its deadline
+/// passes without data.
+///
+/// The inner reader is drained by a background thread, which exits at end of
+/// input or when the `DeadlineReader` is dropped and the next read returns.
+pub(crate) struct DeadlineReader {
+    chunks: Receiver<io::Result<Vec<u8>>>,
+    pending: Vec<u8>,
+    pos: usize,
+    deadline: ReadDeadline,
+}
+
+impl DeadlineReader {
+    /// Starts reading `inner` on a background thread.
+    pub(crate) fn new<R: Read + Send + 'static>(mut inner: R) -> Self {
+        let (tx, chunks) = mpsc::channel();
+        std::thread::spawn(move || {
+            loop {
+                let mut chunk = vec![0; CHUNK_SIZE];
+                let result = match inner.read(&mut chunk) {
+                    Ok(n) => {
+                        chunk.truncate(n);
+                        Ok(chunk)
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => Err(e),
+                };
+                let done = !matches!(&result, Ok(chunk) if !chunk.is_empty());
+                if tx.send(result).is_err() || done {
+                    break;
+                }
+            }
+        });
+        Self {
+            chunks,
+            pending: Vec::new(),
+            pos: 0,
+            deadline: ReadDeadline::default(),
+        }
+    }
+
+    /// Returns the handle controlling this reader's deadline.
+    pub(crate) fn deadline(&self) -> ReadDeadline {
+        self.deadline.clone()
+    }
+}
+
+impl Read for DeadlineReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.pending.len() {
+            let received = match self.deadline.get() {
+                Some(deadline) => self
+                    .chunks
+                    .recv_timeout(deadline.saturating_duration_since(Instant::now())),
+                None => self
+                    .chunks
+                    .recv()
+                    .map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            match received {
+                Ok(chunk) => {
+                    self.pending = chunk?;
+                    self.pos = 0;
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        "no data before read deadline",
+                    ));
+                }
+                // The reader thread has finished: end of input
+                Err(RecvTimeoutError::Disconnected) => return Ok(0),
+            }
+        }
+
+        let n = buf.len().min(self.pending.len() - self.pos);
+        buf[..n].copy_from_slice(&self.pending[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
@@ -0,0 +1,266 @@
+//! Request retries for transient transport failures.
+//!
+//! Only idempotent requests are retried: re-sending a `tools/call` that the
+//! server already executed could repeat its side effects, so tool calls are
+//! retried only through [`Client::call_tool_idempotent`](crate::Client::call_tool_idempotent).
+
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::time::Duration;
+
+use fastmcp_core::McpResult;
+use fastmcp_transport::TransportError;
+
+use crate::transport_error_to_mcp;
+
+/// Methods that are safe to re-send after a transport failure.
+const IDEMPOTENT_METHODS: &[&str] = &[
+    "ping",
+    "tools/list",
+    "resources/list",
+    "resources/templates/list",
+    "resources/read",
+    "prompts/list",
+    "prompts/get",
+    "tasks/list",
+    "tasks/get",
+];
+
+/// Largest exponent used for backoff, so delays cannot overflow.
+const MAX_BACKOFF_SHIFT: u32 = 16;
+
+/// Retry policy for idempotent requests.
+///
+/// Each retry waits an exponentially growing delay: half of
+/// `base_backoff * 2^(retry - 1)` plus a random jitter of up to the other
+/// half, so clients that failed together do not retry in lockstep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first one.
+    pub max_attempts: u32,
+    /// Delay before the first retry (before jitter).
+    pub base_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Creates a retry policy.
+    #[must_use]
+    pub fn new(max_attempts: u32, base_backoff: Duration) -> Self {
+        Self {
+            max_attempts,
+            base_backoff,
+        }
+    }
+
+    /// Returns the delay before retry number `retry` (starting at 1).
+    #[must_use]
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let shift = retry.saturating_sub(1).min(MAX_BACKOFF_SHIFT);
+        let delay = self.base_backoff.saturating_mul(1 << shift);
+        let half = delay / 2;
+        half + jitter(delay - half)
+    }
+}
+
+/// Returns whether `method` is safe to retry.
+pub(crate) fn is_idempotent_method(method: &str) -> bool {
+    IDEMPOTENT_METHODS.contains(&method)
+}
+
+/// Returns whether a transport error is worth retrying.
+///
+/// I/O errors and timeouts may be transient; a closed transport, a
+/// cancellation, or a malformed message will not go away by re-sending.
+pub(crate) fn is_retryable(error: &TransportError) -> bool {
+    matches!(error, TransportError::Io(_) | TransportError::Timeout)
+}
+
+/// Runs `attempt` until it succeeds, fails with a non-retryable error, or the
+/// policy runs out of attempts. Without a policy, `attempt` runs once.
+///
+/// The last error is returned once attempts are exhausted.
+pub(crate) fn run_with_retry<T>(
+    policy: Option<&RetryPolicy>,
+    mut attempt: impl FnMut() -> Result<T, TransportError>,
+) -> McpResult<T> {
+    let max_attempts = policy.map_or(1, |p| p.max_attempts.max(1));
+    let mut attempts = 1;
+    loop {
+        match attempt() {
+            Ok(value) => return Ok(value),
+            Err(e) if attempts < max_attempts && is_retryable(&e) => {
+                let delay = policy.map_or(Duration::ZERO, |p| p.backoff(attempts));
+                log::debug!(
+                    target: "fastmcp::client",
+                    "Attempt {attempts}/{max_attempts} failed ({e}), retrying in {delay:?}"
+                );
+                std::thread::sleep(delay);
+                attempts += 1;
+            }
+            Err(e) => return Err(transport_error_to_mcp(e)),
+        }
+    }
+}
+
+/// Returns a random duration in `0..=max`.
+fn jitter(max: Duration) -> Duration {
+    let nanos = u64::try_from(max.as_nanos()).unwrap_or(u64::MAX);
+    if nanos == 0 {
+        return Duration::ZERO;
+    }
+    let random = RandomState::new().hash_one(0u8);
+    Duration::from_nanos(random % nanos.saturating_add(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use asupersync::Cx;
+    use fastmcp_core::McpErrorCode;
+    use fastmcp_protocol::{JsonRpcMessage, JsonRpcRequest, JsonRpcResponse, RequestId};
+    use fastmcp_transport::Transport;
+
+    /// A transport whose first `failures` sends fail with an I/O error.
+    ///
+    /// Successful sends are answered with an empty result for the same id.
+    struct FlakyTransport {
+        failures: u32,
+        error: fn() -> TransportError,
+        sent_ids: Vec<RequestId>,
+        pending: Option<RequestId>,
+    }
+
+    impl FlakyTransport {
+        fn new(failures: u32) -> Self {
+            Self {
+                failures,
+                error: || TransportError::Io(std::io::ErrorKind::BrokenPipe.into()),
+                sent_ids: Vec::new(),
+                pending: None,
+            }
+        }
+    }
+
+    impl Transport for FlakyTransport {
+        fn send(&mut self, _cx: &Cx, message: &JsonRpcMessage) -> Result<(), TransportError> {
+            let JsonRpcMessage::Request(request) = message else {
+                return Ok(());
+            };
+            let id = request.id.clone().expect("request id");
+            self.sent_ids.push(id.clone());
+            if self.failures > 0 {
+                self.failures -= 1;
+                return Err((self.error)());
+            }
+            self.pending = Some(id);
+            Ok(())
+        }
+
+        fn recv(&mut self, _cx: &Cx) -> Result<JsonRpcMessage, TransportError> {
+            let id = self.pending.take().ok_or(TransportError::Closed)?;
+            Ok(JsonRpcMessage::Response(JsonRpcResponse::success(
+                id,
+                serde_json::json!({}),
+            )))
+        }
+
+        fn close(&mut self) -> Result<(), TransportError> {
+            Ok(())
+        }
+    }
+
+    /// Sends `method` with a fresh id per attempt, as the client does.
+    fn request(
+        transport: &mut FlakyTransport,
+        next_id: &mut i64,
+        method: &str,
+    ) -> Result<JsonRpcMessage, TransportError> {
+        let cx = Cx::for_testing();
+        *next_id += 1;
+        let request = JsonRpcRequest::new(method, None, *next_id);
+        transport.send(&cx, &JsonRpcMessage::Request(request))?;
+        transport.recv(&cx)
+    }
+
+    fn fast_policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy::new(max_attempts, Duration::from_millis(1))
+    }
+
+    #[test]
+    fn retries_until_success_with_fresh_ids() {
+        let mut transport = FlakyTransport::new(2);
+        let mut next_id = 0;
+
+        let result = run_with_retry(Some(&fast_policy(3)), || {
+            request(&mut transport, &mut next_id, "tools/list")
+        });
+
+        assert!(matches!(result, Ok(JsonRpcMessage::Response(_))));
+        assert_eq!(
+            transport.sent_ids,
+            [
+                RequestId::Number(1),
+                RequestId::Number(2),
+                RequestId::Number(3)
+            ]
+        );
+    }
+
+    #[test]
+    fn surfaces_last_error_after_exhausting_attempts() {
+        let mut transport = FlakyTransport::new(5);
+        let mut next_id = 0;
+
+        let err = run_with_retry(Some(&fast_policy(3)), || {
+            request(&mut transport, &mut next_id, "tools/list")
+        })
+        .unwrap_err();
+
+        assert_eq!(err.code, McpErrorCode::InternalError);
+        assert!(err.message.contains("I/O error"), "{}", err.message);
+        assert_eq!(transport.sent_ids.len(), 3);
+    }
+
+    #[test]
+    fn no_policy_means_single_attempt() {
+        let mut transport = FlakyTransport::new(2);
+        let mut next_id = 0;
+
+        let result = run_with_retry(None, || request(&mut transport, &mut next_id, "tools/call"));
+
+        assert!(result.is_err());
+        assert_eq!(transport.sent_ids.len(), 1);
+    }
+
+    #[test]
+    fn non_retryable_errors_fail_immediately() {
+        let mut transport = FlakyTransport::new(2);
+        transport.error = || TransportError::Closed;
+        let mut next_id = 0;
+
+        let result = run_with_retry(Some(&fast_policy(3)), || {
+            request(&mut transport, &mut next_id, "tools/list")
+        });
+
+        assert!(result.is_err());
+        assert_eq!(transport.sent_ids.len(), 1);
+    }
+
+    #[test]
+    fn idempotent_methods() {
+        assert!(is_idempotent_method("ping"));
+        assert!(is_idempotent_method("resources/read"));
+        assert!(!is_idempotent_method("tools/call"));
+        assert!(!is_idempotent_method("tasks/submit"));
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_within_jitter_bounds() {
+        let policy = RetryPolicy::new(5, Duration::from_millis(100));
+        for (retry, full) in [(1, 100), (2, 200), (3, 400)] {
+            let delay = policy.backoff(retry);
+            assert!(delay >= Duration::from_millis(full / 2), "{delay:?}");
+            assert!(delay <= Duration::from_millis(full), "{delay:?}");
+        }
+    }
+}
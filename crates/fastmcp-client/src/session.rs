@@ -94,6 +94,7 @@ mod tests {
                 }),
                 logging: None,
                 tasks: None,
+                experimental: None,
             },
             "2024-11-05".to_string(),
         )
@@ -153,6 +154,7 @@ mod tests {
                 sampling: Some(fastmcp_protocol::SamplingCapability {}),
                 elicitation: None,
                 roots: None,
+                experimental: None,
             },
             ServerInfo {
                 name: "srv".to_string(),
@@ -1,13 +1,14 @@
 //! Startup banner for FastMCP servers.
 //!
 //! Displays a banner when the server starts, showing server info,
-//! capabilities, and ready status.
+//! build info, capabilities, and ready status.
 
 use crate::console::FastMcpConsole;
 use crate::theme::FastMcpTheme;
 use rich_rust::r#box::ROUNDED;
 use rich_rust::markup;
 use rich_rust::prelude::*;
+use serde::Serialize;
 
 /// ASCII art logo for FastMCP.
 const LOGO_FULL: &str = r"
@@ -40,6 +41,74 @@ const LOGO_COMPACT: &str = r"
 /// Minimal logo fallback.
 const LOGO_MINIMAL: &str = "FastMCP Rust";
 
+/// Which build of a server is running.
+///
+/// Every field is optional; unset fields are left out of the banner line and
+/// of serialized output.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildInfo {
+    /// Git commit the binary was built from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_sha: Option<String>,
+    /// When the binary was built.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build_time: Option<String>,
+    /// Version of the Rust compiler used for the build.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rust_version: Option<String>,
+}
+
+impl BuildInfo {
+    /// Reads `VERGEN_GIT_SHA`, `VERGEN_BUILD_TIMESTAMP`, and
+    /// `VERGEN_RUSTC_SEMVER` from the process environment.
+    ///
+    /// Returns `None` if none of them are set. `vergen` emits the same names
+    /// as compile-time variables; to bake those into the binary instead, fill
+    /// the fields from `option_env!`:
+    ///
+    /// ```ignore
+    /// let info = BuildInfo {
+    ///     git_sha: option_env!("VERGEN_GIT_SHA").map(Into::into),
+    ///     build_time: option_env!("VERGEN_BUILD_TIMESTAMP").map(Into::into),
+    ///     rust_version: option_env!("VERGEN_RUSTC_SEMVER").map(Into::into),
+    /// };
+    /// ```
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let var = |name| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let info = Self {
+            git_sha: var("VERGEN_GIT_SHA"),
+            build_time: var("VERGEN_BUILD_TIMESTAMP"),
+            rust_version: var("VERGEN_RUSTC_SEMVER"),
+        };
+        (!info.is_empty()).then_some(info)
+    }
+
+    /// Returns whether no field is set.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.git_sha.is_none() && self.build_time.is_none() && self.rust_version.is_none()
+    }
+
+    /// Returns the set fields as one line, e.g.
+    /// `git 1a2b3c4, built 2026-01-01T00:00:00Z, rustc 1.85.0`.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(sha) = &self.git_sha {
+            parts.push(format!("git {sha}"));
+        }
+        if let Some(time) = &self.build_time {
+            parts.push(format!("built {time}"));
+        }
+        if let Some(version) = &self.rust_version {
+            parts.push(format!("rustc {version}"));
+        }
+        parts.join(", ")
+    }
+}
+
 /// Builder for the startup banner.
 pub struct StartupBanner {
     /// Server name (from ServerInfo)
@@ -48,6 +117,8 @@ pub struct StartupBanner {
     version: String,
     /// Optional description/instructions
     description: Option<String>,
+    /// Optional build provenance
+    build_info: Option<BuildInfo>,
     /// Number of registered tools
     tools_count: usize,
     /// Number of registered resources
@@ -68,6 +139,7 @@ impl StartupBanner {
             server_name: server_name.into(),
             version: version.into(),
             description: None,
+            build_info: None,
             tools_count: 0,
             resources_count: 0,
             prompts_count: 0,
@@ -83,6 +155,15 @@ impl StartupBanner {
         self
     }
 
+    /// Set the build info, shown as a `Build:` line.
+    ///
+    /// Ignored if no field is set.
+    #[must_use]
+    pub fn build_info(mut self, info: BuildInfo) -> Self {
+        self.build_info = Some(info).filter(|info| !info.is_empty());
+        self
+    }
+
     /// Set the number of tools.
     #[must_use]
     pub fn tools(mut self, count: usize) -> Self {
@@ -164,6 +245,14 @@ impl StartupBanner {
             content.push_str(&format!("\n[{}]{}[/]", color_hex(&theme.text_dim), desc));
         }
 
+        if let Some(info) = &self.build_info {
+            content.push_str(&format!(
+                "\n[{}]Build: {}[/]",
+                color_hex(&theme.text_muted),
+                info.summary()
+            ));
+        }
+
         content.push_str(&format!(
             "\n[{}]High-performance Model Context Protocol framework[/]",
             color_hex(&theme.text_dim)
@@ -226,6 +315,9 @@ impl StartupBanner {
         if let Some(desc) = &self.description {
             eprintln!("  {desc}");
         }
+        if let Some(info) = &self.build_info {
+            eprintln!("  Build: {}", info.summary());
+        }
         eprintln!("  Tools: {}", self.tools_count);
         eprintln!("  Resources: {}", self.resources_count);
         eprintln!("  Prompts: {}", self.prompts_count);
@@ -303,3 +395,47 @@ fn lerp(a: u8, b: u8, t: f64) -> u8 {
     let b = b as f64;
     (a + (b - a) * t).round() as u8
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestConsole;
+
+    fn sample_build_info() -> BuildInfo {
+        BuildInfo {
+            git_sha: Some("1a2b3c4".to_string()),
+            build_time: None,
+            rust_version: Some("1.85.0".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_banner_renders_build_line() {
+        let console = TestConsole::new();
+        StartupBanner::new("demo", "1.0.0")
+            .build_info(sample_build_info())
+            .no_logo()
+            .render(console.console());
+        console.assert_contains("Build: git 1a2b3c4, rustc 1.85.0");
+    }
+
+    #[test]
+    fn test_banner_omits_build_line_without_info() {
+        let console = TestConsole::new();
+        StartupBanner::new("demo", "1.0.0")
+            .build_info(BuildInfo::default())
+            .no_logo()
+            .render(console.console());
+        console.assert_contains("demo");
+        console.assert_not_contains("Build:");
+    }
+
+    #[test]
+    fn test_build_info_serializes_set_fields() {
+        let value = serde_json::to_value(sample_build_info()).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({"gitSha": "1a2b3c4", "rustVersion": "1.85.0"})
+        );
+    }
+}
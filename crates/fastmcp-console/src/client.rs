@@ -269,6 +269,7 @@ mod tests {
             sampling: Some(fastmcp_protocol::SamplingCapability {}),
             elicitation: None,
             roots: Some(RootsCapability { list_changed: true }),
+            experimental: None,
         }
     }
 
@@ -347,6 +348,7 @@ mod tests {
             sampling: Some(fastmcp_protocol::SamplingCapability {}),
             elicitation: None,
             roots: None,
+            experimental: None,
         };
         assert_eq!(renderer.format_capabilities(&caps), "sampling");
     }
@@ -360,6 +362,7 @@ mod tests {
             roots: Some(RootsCapability {
                 list_changed: false,
             }),
+            experimental: None,
         };
         assert_eq!(renderer.format_capabilities(&caps), "roots");
     }
@@ -21,6 +21,8 @@ pub struct RequestResponseRenderer {
     pub truncate_at: usize,
     /// Whether to show timing information when available.
     pub show_timing: bool,
+    /// Keys whose values are masked before params/results are printed.
+    pub redact_keys: Vec<String>,
 }
 
 impl RequestResponseRenderer {
@@ -34,6 +36,7 @@ impl RequestResponseRenderer {
             show_result: true,
             truncate_at: 200,
             show_timing: true,
+            redact_keys: crate::redact::default_redact_keys(),
         }
     }
 
@@ -160,8 +163,7 @@ impl RequestResponseRenderer {
         value: &serde_json::Value,
         console: &FastMcpConsole,
     ) {
-        let json_str = serde_json::to_string_pretty(value).unwrap_or_default();
-        let preview = self.truncate_string(&json_str);
+        let preview = self.json_preview(value);
         let dim_color = self.dim_color();
 
         console.print(&format!("  [{}]{}:[/]", dim_color, label));
@@ -181,7 +183,7 @@ impl RequestResponseRenderer {
             console.print(&format!(
                 "  [{}]Data: {}[/]",
                 self.dim_color(),
-                self.truncate_string(&data.to_string())
+                self.truncate_string(&self.redact(data).to_string())
             ));
         }
     }
@@ -248,6 +250,15 @@ impl RequestResponseRenderer {
         }
     }
 
+    fn redact(&self, value: &serde_json::Value) -> serde_json::Value {
+        crate::redact::redact_json(value, &self.redact_keys)
+    }
+
+    fn json_preview(&self, value: &serde_json::Value) -> String {
+        let json_str = serde_json::to_string_pretty(&self.redact(value)).unwrap_or_default();
+        self.truncate_string(&json_str)
+    }
+
     fn truncate_string(&self, s: &str) -> String {
         let len = s.chars().count();
         if len <= self.truncate_at {
@@ -333,8 +344,7 @@ impl RequestResponseRenderer {
         value: &serde_json::Value,
         console: &FastMcpConsole,
     ) {
-        let json_str = serde_json::to_string_pretty(value).unwrap_or_default();
-        let preview = self.truncate_string(&json_str);
+        let preview = self.json_preview(value);
         console.print_plain(&format!("  {}:", label));
         for line in preview.lines() {
             console.print_plain(&format!("    {}", line));
@@ -346,7 +356,7 @@ impl RequestResponseRenderer {
         if let Some(data) = &error.data {
             console.print_plain(&format!(
                 "  Data: {}",
-                self.truncate_string(&data.to_string())
+                self.truncate_string(&self.redact(data).to_string())
             ));
         }
     }
@@ -408,4 +418,26 @@ mod tests {
         assert!(output.contains("resources/list"));
         assert!(output.contains("OK"));
     }
+
+    #[test]
+    fn test_render_request_redacts_secret_params() {
+        let renderer = RequestResponseRenderer::new(DisplayContext::new_agent());
+        let console = TestConsole::new();
+        let request = JsonRpcRequest::new(
+            "tools/call",
+            Some(serde_json::json!({
+                "name": "search",
+                "arguments": {"apiKey": "sk-live-123", "query": "rust"}
+            })),
+            3i64,
+        );
+
+        renderer.render_request(&request, console.console());
+
+        let output = console.output_string();
+        assert!(output.contains(r#""apiKey": "***""#));
+        assert!(!output.contains("sk-live-123"));
+        assert!(output.contains(r#""query": "rust""#));
+        assert!(output.contains(r#""name": "search""#));
+    }
 }
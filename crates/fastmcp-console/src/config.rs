@@ -29,6 +29,8 @@ pub struct ConsoleConfig {
     pub show_capabilities: bool,
     /// Banner display style
     pub banner_style: BannerStyle,
+    /// Write a JSON `ready` line to stderr once startup succeeds
+    pub emit_ready_event: bool,
 
     // Logging
     /// Log level filter
@@ -65,6 +67,11 @@ pub struct ConsoleConfig {
     pub max_json_depth: usize,
     /// Truncate long strings at this length
     pub truncate_at: usize,
+
+    // Redaction
+    /// Keys whose values are replaced with `***` in logged JSON
+    /// (case-insensitive, `_`/`-` ignored)
+    pub redact_keys: Vec<String>,
 }
 
 /// Style variants for the startup banner
@@ -120,6 +127,7 @@ impl Default for ConsoleConfig {
             show_banner: true,
             show_capabilities: true,
             banner_style: BannerStyle::Full,
+            emit_ready_event: false,
             log_level: None,
             log_timestamps: true,
             log_targets: true,
@@ -134,6 +142,7 @@ impl Default for ConsoleConfig {
             max_table_rows: 100,
             max_json_depth: 5,
             truncate_at: 200,
+            redact_keys: crate::redact::default_redact_keys(),
         }
     }
 }
@@ -256,6 +265,17 @@ impl ConsoleConfig {
         self
     }
 
+    /// Emit a machine-readable `ready` line once the server has started
+    ///
+    /// The line is a single JSON object on stderr, e.g.
+    /// `{"event":"ready","name":"demo","version":"1.0","tools":2,...,"transport":"stdio"}`,
+    /// so supervisors can wait for it instead of scraping the banner.
+    #[must_use]
+    pub fn emit_ready_event(mut self, emit: bool) -> Self {
+        self.emit_ready_event = emit;
+        self
+    }
+
     /// Set the log level
     #[must_use]
     pub fn with_log_level(mut self, level: log::Level) -> Self {
@@ -321,6 +341,24 @@ impl ConsoleConfig {
         self
     }
 
+    /// Replace the set of keys redacted in logged JSON
+    #[must_use]
+    pub fn with_redact_keys<I, S>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.redact_keys = keys.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Add a key to the redaction list
+    #[must_use]
+    pub fn redact_key(mut self, key: impl Into<String>) -> Self {
+        self.redact_keys.push(key.into());
+        self
+    }
+
     // ─────────────────────────────────────────────────
     // Accessor Methods
     // ─────────────────────────────────────────────────
@@ -364,9 +402,19 @@ mod tests {
         assert!(config.show_banner);
         assert!(config.show_capabilities);
         assert_eq!(config.banner_style, BannerStyle::Full);
+        assert!(!config.emit_ready_event);
         assert!(config.log_timestamps);
         assert!(!config.force_plain);
         assert_eq!(config.max_table_rows, 100);
+        assert!(config.redact_keys.iter().any(|k| k == "*api_key"));
+    }
+
+    #[test]
+    fn test_redact_keys_builder() {
+        let config = ConsoleConfig::new()
+            .with_redact_keys(["session_id"])
+            .redact_key("cookie");
+        assert_eq!(config.redact_keys, vec!["session_id", "cookie"]);
     }
 
     #[test]
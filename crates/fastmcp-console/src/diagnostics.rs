@@ -1,5 +1,6 @@
 //! Error/warning formatting
 
+use crate::config::ConsoleConfig;
 use crate::console::FastMcpConsole;
 use crate::theme::FastMcpTheme;
 use fastmcp_core::{McpError, McpErrorCode};
@@ -10,6 +11,7 @@ pub struct RichErrorRenderer {
     show_suggestions: bool,
     show_backtrace: bool,
     show_error_code: bool,
+    redact_keys: Vec<String>,
 }
 
 impl Default for RichErrorRenderer {
@@ -18,6 +20,7 @@ impl Default for RichErrorRenderer {
             show_suggestions: true,
             show_backtrace: std::env::var("RUST_BACKTRACE").is_ok(),
             show_error_code: true,
+            redact_keys: crate::redact::default_redact_keys(),
         }
     }
 }
@@ -27,6 +30,24 @@ impl RichErrorRenderer {
         Self::default()
     }
 
+    /// Create a renderer following the error and redaction settings of `config`
+    #[must_use]
+    pub fn from_config(config: &ConsoleConfig) -> Self {
+        Self {
+            show_suggestions: config.show_suggestions,
+            show_backtrace: config.show_backtrace,
+            show_error_code: config.show_error_codes,
+            redact_keys: config.redact_keys.clone(),
+        }
+    }
+
+    /// Set the keys whose values are masked in error context data
+    #[must_use]
+    pub fn with_redact_keys(mut self, keys: Vec<String>) -> Self {
+        self.redact_keys = keys;
+        self
+    }
+
     /// Render an error with full context
     pub fn render(&self, error: &McpError, console: &FastMcpConsole) {
         if !console.is_rich() {
@@ -105,7 +126,8 @@ impl RichErrorRenderer {
 
         // Add data context if present
         let content = if let Some(data) = &error.data {
-            if let Ok(pretty) = serde_json::to_string_pretty(data) {
+            let data = crate::redact::redact_json(data, &self.redact_keys);
+            if let Ok(pretty) = serde_json::to_string_pretty(&data) {
                 format!("{}\n\n[dim]Context:[/]\n{}", content, pretty)
             } else {
                 content
@@ -159,6 +181,7 @@ impl RichErrorRenderer {
             error.message
         ));
         if let Some(data) = &error.data {
+            let data = crate::redact::redact_json(data, &self.redact_keys);
             console.print_plain(&format!("Context: {:?}", data));
         }
     }
@@ -31,6 +31,7 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 
 use fastmcp_core::McpError;
 
+use crate::config::ConsoleConfig;
 use crate::console::FastMcpConsole;
 use crate::diagnostics::RichErrorRenderer;
 
@@ -80,6 +81,23 @@ impl<'a> ErrorBoundary<'a> {
         }
     }
 
+    /// Renders errors following the error and redaction settings of `config`.
+    ///
+    /// Without this the boundary uses the default settings, including the
+    /// default redaction keys.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let config = ConsoleConfig::from_env().redact_key("session_id");
+    /// let boundary = ErrorBoundary::new(console()).with_config(&config);
+    /// ```
+    #[must_use]
+    pub fn with_config(mut self, config: &ConsoleConfig) -> Self {
+        self.renderer = RichErrorRenderer::from_config(config);
+        self
+    }
+
     /// Configures the boundary to exit the process on error.
     ///
     /// When `exit` is `true`, any error will cause the process to exit
@@ -14,6 +14,7 @@ pub mod diagnostics; // Error formatting
 pub mod error; // Error boundary wrapper
 pub mod handlers; // Unified handler registry display
 pub mod logging; // Rich log formatter (RichLogFormatter, RichLogger)
+pub mod redact; // Secret redaction for logged JSON
 pub mod stats; // Runtime metrics
 pub mod tables; // Info tables
 pub mod testing; // Test utilities
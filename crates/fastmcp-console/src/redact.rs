@@ -0,0 +1,222 @@
+//! Secret redaction for logged JSON payloads.
+//!
+//! Traffic logging and error diagnostics can print request params and error
+//! data verbatim. This module masks values whose key looks like a secret
+//! (API keys, tokens, passwords) before anything reaches the console.
+
+use serde_json::Value;
+
+/// Placeholder written in place of redacted values.
+pub const REDACTED: &str = "***";
+
+/// Keys redacted by default.
+///
+/// The `*` prefix makes an entry a suffix pattern, so `client_secret` and
+/// `x-api-key` are caught alongside the bare names. Token keys are listed
+/// one by one: `*token` would also hide protocol fields such as
+/// `progressToken` and pagination tokens.
+pub const DEFAULT_REDACT_KEYS: &[&str] = &[
+    "*authorization",
+    "token",
+    "access_token",
+    "refresh_token",
+    "id_token",
+    "auth_token",
+    "bearer_token",
+    "session_token",
+    "api_token",
+    "*password",
+    "*secret",
+    "*api_key",
+];
+
+/// Returns the default redaction key list as owned strings.
+#[must_use]
+pub fn default_redact_keys() -> Vec<String> {
+    DEFAULT_REDACT_KEYS
+        .iter()
+        .map(|k| (*k).to_string())
+        .collect()
+}
+
+/// Returns a copy of `value` with every object entry whose key matches one of
+/// `keys` replaced by [`REDACTED`].
+///
+/// Matching is case-insensitive and ignores `_` and `-`, so `api_key` also
+/// matches `apiKey` and `API-KEY`. A leading `*` matches any prefix and a
+/// trailing `*` any suffix: `*token` matches `refresh_token`, `token*`
+/// matches `tokens`, and `*token*` matches both. Nested objects and arrays
+/// are walked recursively.
+#[must_use]
+pub fn redact_json(value: &Value, keys: &[String]) -> Value {
+    if keys.is_empty() {
+        return value.clone();
+    }
+    let patterns: Vec<KeyPattern> = keys.iter().map(|k| KeyPattern::parse(k)).collect();
+    redact_with(value, &patterns)
+}
+
+/// Returns true if `key` matches one of the redaction `keys`.
+#[must_use]
+pub fn is_redacted_key(key: &str, keys: &[String]) -> bool {
+    let key = normalize_key(key);
+    keys.iter().any(|k| KeyPattern::parse(k).matches(&key))
+}
+
+/// A normalized redaction key with optional `*` wildcards at either end.
+struct KeyPattern {
+    needle: String,
+    any_prefix: bool,
+    any_suffix: bool,
+}
+
+impl KeyPattern {
+    fn parse(pattern: &str) -> Self {
+        let any_prefix = pattern.starts_with('*');
+        let any_suffix = pattern.len() > 1 && pattern.ends_with('*');
+        let trimmed = pattern.trim_start_matches('*').trim_end_matches('*');
+        Self {
+            needle: normalize_key(trimmed),
+            any_prefix,
+            any_suffix,
+        }
+    }
+
+    /// Matches an already-normalized key.
+    fn matches(&self, key: &str) -> bool {
+        if self.needle.is_empty() {
+            return false;
+        }
+        match (self.any_prefix, self.any_suffix) {
+            (false, false) => key == self.needle,
+            (true, false) => key.ends_with(&self.needle),
+            (false, true) => key.starts_with(&self.needle),
+            (true, true) => key.contains(&self.needle),
+        }
+    }
+}
+
+fn redact_with(value: &Value, patterns: &[KeyPattern]) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| {
+                    let key = normalize_key(k);
+                    if patterns.iter().any(|p| p.matches(&key)) {
+                        (k.clone(), Value::String(REDACTED.to_string()))
+                    } else {
+                        (k.clone(), redact_with(v, patterns))
+                    }
+                })
+                .collect(),
+        ),
+        Value::Array(items) => {
+            Value::Array(items.iter().map(|v| redact_with(v, patterns)).collect())
+        }
+        other => other.clone(),
+    }
+}
+
+fn normalize_key(key: &str) -> String {
+    key.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redacts_case_insensitively() {
+        let value = json!({"Authorization": "Bearer abc", "apiKey": "k", "name": "n"});
+        let redacted = redact_json(&value, &default_redact_keys());
+        assert_eq!(redacted["Authorization"], "***");
+        assert_eq!(redacted["apiKey"], "***");
+        assert_eq!(redacted["name"], "n");
+    }
+
+    #[test]
+    fn test_redacts_nested_values() {
+        let value = json!({
+            "arguments": {"items": [{"password": "hunter2", "user": "bob"}]},
+            "_meta": {"token": {"nested": true}}
+        });
+        let redacted = redact_json(&value, &default_redact_keys());
+        assert_eq!(redacted["arguments"]["items"][0]["password"], "***");
+        assert_eq!(redacted["arguments"]["items"][0]["user"], "bob");
+        assert_eq!(redacted["_meta"]["token"], "***");
+    }
+
+    #[test]
+    fn test_empty_keys_is_noop() {
+        let value = json!({"password": "x"});
+        assert_eq!(redact_json(&value, &[]), value);
+    }
+
+    #[test]
+    fn test_is_redacted_key() {
+        let keys = default_redact_keys();
+        assert!(is_redacted_key("API-KEY", &keys));
+        assert!(!is_redacted_key("apikeys", &keys));
+    }
+
+    #[test]
+    fn test_default_keys_match_compound_names() {
+        let value = json!({
+            "access_token": "a",
+            "refreshToken": "r",
+            "client_secret": "s",
+            "x-api-key": "k",
+            "db_password": "p",
+            "Proxy-Authorization": "Basic xyz",
+            "maxTokens": 100,
+            "tokenizer": "cl100k"
+        });
+        let redacted = redact_json(&value, &default_redact_keys());
+        for key in [
+            "access_token",
+            "refreshToken",
+            "client_secret",
+            "x-api-key",
+            "db_password",
+            "Proxy-Authorization",
+        ] {
+            assert_eq!(redacted[key], "***", "{key} should be redacted");
+        }
+        assert_eq!(redacted["maxTokens"], 100);
+        assert_eq!(redacted["tokenizer"], "cl100k");
+    }
+
+    #[test]
+    fn test_default_keys_leave_protocol_tokens_alone() {
+        let value = json!({
+            "_meta": {"progressToken": 7},
+            "nextPageToken": "page-2",
+            "id_token": "jwt"
+        });
+        let redacted = redact_json(&value, &default_redact_keys());
+        assert_eq!(redacted["_meta"]["progressToken"], 7);
+        assert_eq!(redacted["nextPageToken"], "page-2");
+        assert_eq!(redacted["id_token"], "***");
+    }
+
+    #[test]
+    fn test_wildcard_patterns() {
+        let exact = vec!["session".to_string()];
+        assert!(is_redacted_key("Session", &exact));
+        assert!(!is_redacted_key("session_id", &exact));
+
+        let prefix = vec!["session*".to_string()];
+        assert!(is_redacted_key("session_id", &prefix));
+        assert!(!is_redacted_key("user_session", &prefix));
+
+        let both = vec!["*session*".to_string()];
+        assert!(is_redacted_key("user_session_id", &both));
+
+        let bare = vec!["*".to_string()];
+        assert!(!is_redacted_key("anything", &bare));
+    }
+}
@@ -2,6 +2,8 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 
+use fastmcp_core::McpErrorCode;
+
 mod renderer;
 
 pub use renderer::StatsRenderer;
@@ -18,6 +20,8 @@ struct ServerStatsInner {
     total_requests: AtomicU64,
     successful_requests: AtomicU64,
     failed_requests: AtomicU64,
+    client_errors: AtomicU64,
+    server_errors: AtomicU64,
     cancelled_requests: AtomicU64,
     tool_calls: AtomicU64,
     resource_reads: AtomicU64,
@@ -30,6 +34,7 @@ struct ServerStatsInner {
     total_connections: AtomicU64,
     bytes_received: AtomicU64,
     bytes_sent: AtomicU64,
+    tool_cache_hits: AtomicU64,
 }
 
 impl Default for ServerStats {
@@ -48,6 +53,8 @@ impl ServerStats {
                 total_requests: AtomicU64::new(0),
                 successful_requests: AtomicU64::new(0),
                 failed_requests: AtomicU64::new(0),
+                client_errors: AtomicU64::new(0),
+                server_errors: AtomicU64::new(0),
                 cancelled_requests: AtomicU64::new(0),
                 tool_calls: AtomicU64::new(0),
                 resource_reads: AtomicU64::new(0),
@@ -60,6 +67,7 @@ impl ServerStats {
                 total_connections: AtomicU64::new(0),
                 bytes_received: AtomicU64::new(0),
                 bytes_sent: AtomicU64::new(0),
+                tool_cache_hits: AtomicU64::new(0),
             }),
         }
     }
@@ -76,6 +84,25 @@ impl ServerStats {
         }
     }
 
+    /// Record a failed request, bucketed by error code.
+    ///
+    /// Client mistakes (invalid params, unknown method, missing resource, ...)
+    /// count towards `client_errors`, everything else towards `server_errors`.
+    /// [`McpErrorCode::RequestCancelled`] is recorded as a cancellation.
+    pub fn record_error(&self, method: &str, latency: Duration, code: McpErrorCode) {
+        if code == McpErrorCode::RequestCancelled {
+            self.record_cancelled(method, latency);
+            return;
+        }
+        self.record_request(method, latency, false);
+        let bucket = if code.is_client_error() {
+            &self.inner.client_errors
+        } else {
+            &self.inner.server_errors
+        };
+        bucket.fetch_add(1, Ordering::Relaxed);
+    }
+
     /// Record a cancelled request.
     pub fn record_cancelled(&self, method: &str, latency: Duration) {
         self.record_request_base(method, latency);
@@ -123,6 +150,11 @@ impl ServerStats {
         self.inner.bytes_sent.fetch_add(bytes, Ordering::Relaxed);
     }
 
+    /// Record a `tools/call` answered from the tool result cache.
+    pub fn record_tool_cache_hit(&self) {
+        self.inner.tool_cache_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
     /// Get a point-in-time snapshot of all counters.
     #[must_use]
     pub fn snapshot(&self) -> StatsSnapshot {
@@ -136,6 +168,8 @@ impl ServerStats {
             total_requests: total,
             successful_requests: self.inner.successful_requests.load(Ordering::Relaxed),
             failed_requests: self.inner.failed_requests.load(Ordering::Relaxed),
+            client_errors: self.inner.client_errors.load(Ordering::Relaxed),
+            server_errors: self.inner.server_errors.load(Ordering::Relaxed),
             cancelled_requests: self.inner.cancelled_requests.load(Ordering::Relaxed),
             tool_calls: self.inner.tool_calls.load(Ordering::Relaxed),
             resource_reads: self.inner.resource_reads.load(Ordering::Relaxed),
@@ -155,6 +189,7 @@ impl ServerStats {
             total_connections: self.inner.total_connections.load(Ordering::Relaxed),
             bytes_received: self.inner.bytes_received.load(Ordering::Relaxed),
             bytes_sent: self.inner.bytes_sent.load(Ordering::Relaxed),
+            tool_cache_hits: self.inner.tool_cache_hits.load(Ordering::Relaxed),
         }
     }
 
@@ -193,6 +228,10 @@ pub struct StatsSnapshot {
     pub total_requests: u64,
     pub successful_requests: u64,
     pub failed_requests: u64,
+    /// Failures caused by the request (invalid params, unknown method, ...).
+    pub client_errors: u64,
+    /// Failures caused by the server (internal errors, tool failures, ...).
+    pub server_errors: u64,
     pub cancelled_requests: u64,
     pub tool_calls: u64,
     pub resource_reads: u64,
@@ -205,6 +244,7 @@ pub struct StatsSnapshot {
     pub total_connections: u64,
     pub bytes_received: u64,
     pub bytes_sent: u64,
+    pub tool_cache_hits: u64,
 }
 
 #[cfg(test)]
@@ -233,6 +273,33 @@ mod tests {
         assert_eq!(snap.min_latency, Duration::from_millis(5));
     }
 
+    #[test]
+    fn test_record_error_buckets() {
+        let stats = ServerStats::new();
+        stats.record_error(
+            "tools/call",
+            Duration::from_millis(1),
+            McpErrorCode::InvalidParams,
+        );
+        stats.record_error(
+            "tools/call",
+            Duration::from_millis(1),
+            McpErrorCode::InternalError,
+        );
+        stats.record_error(
+            "tools/call",
+            Duration::from_millis(1),
+            McpErrorCode::RequestCancelled,
+        );
+
+        let snap = stats.snapshot();
+        assert_eq!(snap.total_requests, 3);
+        assert_eq!(snap.failed_requests, 2);
+        assert_eq!(snap.client_errors, 1);
+        assert_eq!(snap.server_errors, 1);
+        assert_eq!(snap.cancelled_requests, 1);
+    }
+
     #[test]
     fn test_snapshot_latency() {
         let stats = ServerStats::new();
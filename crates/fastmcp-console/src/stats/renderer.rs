@@ -90,6 +90,14 @@ impl StatsRenderer {
             Cell::new("Failed Requests"),
             Cell::new(stats.failed_requests.to_string()),
         ]));
+        table.add_row(Row::new(vec![
+            Cell::new("Client Errors"),
+            Cell::new(stats.client_errors.to_string()),
+        ]));
+        table.add_row(Row::new(vec![
+            Cell::new("Server Errors"),
+            Cell::new(stats.server_errors.to_string()),
+        ]));
         table.add_row(Row::new(vec![
             Cell::new("Cancelled Requests"),
             Cell::new(stats.cancelled_requests.to_string()),
@@ -185,6 +193,8 @@ impl StatsRenderer {
                 self.format_percentage(stats.successful_requests, stats.total_requests),
             ),
             ("Failed Requests", stats.failed_requests.to_string()),
+            ("Client Errors", stats.client_errors.to_string()),
+            ("Server Errors", stats.server_errors.to_string()),
             ("Cancelled Requests", stats.cancelled_requests.to_string()),
             ("Tool Calls", stats.tool_calls.to_string()),
             ("Resource Reads", stats.resource_reads.to_string()),
@@ -216,10 +226,12 @@ impl StatsRenderer {
         console.print("=== Server Statistics ===");
         console.print(&format!("Uptime: {}", self.format_duration(stats.uptime)));
         console.print(&format!(
-            "Requests: {} total, {} successful, {} failed, {} cancelled",
+            "Requests: {} total, {} successful, {} failed ({} client, {} server), {} cancelled",
             stats.total_requests,
             stats.successful_requests,
             stats.failed_requests,
+            stats.client_errors,
+            stats.server_errors,
             stats.cancelled_requests
         ));
         console.print(&format!(
@@ -319,6 +331,8 @@ mod tests {
             total_requests: 100,
             successful_requests: 90,
             failed_requests: 8,
+            client_errors: 5,
+            server_errors: 3,
             cancelled_requests: 2,
             tool_calls: 70,
             resource_reads: 20,
@@ -331,6 +345,7 @@ mod tests {
             total_connections: 5,
             bytes_received: 1024,
             bytes_sent: 2048,
+            tool_cache_hits: 0,
         }
     }
 
@@ -369,6 +384,8 @@ mod tests {
         renderer.render_table(&stats, console.console());
         console.assert_contains("Total Requests");
         console.assert_contains("Success Rate");
+        console.assert_contains("Client Errors");
+        console.assert_contains("Server Errors");
     }
 
     #[test]
@@ -17,7 +17,7 @@ use fastmcp_console::stats::{ServerStats, StatsRenderer};
 use fastmcp_console::tables::{PromptTableRenderer, ResourceTableRenderer, ToolTableRenderer};
 use fastmcp_console::testing::TestConsole;
 use fastmcp_console::theme::theme;
-use fastmcp_core::McpError;
+use fastmcp_core::{McpError, McpErrorCode};
 use fastmcp_protocol::{ClientCapabilities, ClientInfo, RootsCapability};
 
 // ============================================================================
@@ -65,6 +65,41 @@ fn test_error_renderer_uses_console_correctly() {
     tc.assert_contains("test error message");
 }
 
+#[test]
+fn test_error_renderer_redacts_secret_context() {
+    let tc = TestConsole::new();
+    let renderer = RichErrorRenderer::new();
+
+    let error = McpError::with_data(
+        McpErrorCode::InternalError,
+        "upstream rejected request",
+        serde_json::json!({"password": "hunter2", "user": "bob"}),
+    );
+    renderer.render(&error, tc.console());
+
+    tc.assert_contains("***");
+    tc.assert_contains("bob");
+    assert!(!tc.output_string().contains("hunter2"));
+}
+
+#[test]
+fn test_error_boundary_redacts_configured_keys() {
+    let tc = TestConsole::new();
+    let config = ConsoleConfig::new().with_redact_keys(["session_id"]);
+    let boundary = ErrorBoundary::new(tc.console()).with_config(&config);
+
+    let error = McpError::with_data(
+        McpErrorCode::InternalError,
+        "session rejected",
+        serde_json::json!({"session_id": "s-123", "password": "hunter2"}),
+    );
+    let _: Option<()> = boundary.wrap(Err(error));
+
+    assert!(!tc.output_string().contains("s-123"));
+    // Only the configured keys are redacted
+    tc.assert_contains("hunter2");
+}
+
 #[test]
 fn test_stats_renderer_uses_console_correctly() {
     let tc = TestConsole::new();
@@ -397,6 +432,7 @@ fn test_client_info_with_capabilities() {
         sampling: Some(fastmcp_protocol::SamplingCapability {}),
         elicitation: None,
         roots: Some(RootsCapability { list_changed: true }),
+        experimental: None,
     };
 
     renderer.render_connected_with_caps(&client, &caps, tc.console());
@@ -4,8 +4,12 @@
 //! request context. It is transport-agnostic and can be populated by
 //! server-side authentication providers.
 
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use serde::{Deserialize, Serialize};
 
+use crate::McpError;
+
 /// Session state key used to store authentication context.
 pub const AUTH_STATE_KEY: &str = "fastmcp.auth";
 
@@ -82,4 +86,108 @@ impl AuthContext {
             ..Self::default()
         }
     }
+
+    /// Returns when the context expires, from a numeric `exp` claim
+    /// (seconds since the Unix epoch).
+    #[must_use]
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        let exp = self.claims.as_ref()?.get("exp")?.as_u64()?;
+        UNIX_EPOCH.checked_add(Duration::from_secs(exp))
+    }
+}
+
+/// Key under which an [`AuthChallenge`] is stored in an error's `data` object.
+pub const AUTH_CHALLENGE_KEY: &str = "wwwAuthenticate";
+
+/// Bearer challenge telling a client where to authenticate.
+///
+/// Servers attach this to authentication failures so HTTP transports can
+/// answer `401 Unauthorized` with a `WWW-Authenticate` header (RFC 6750),
+/// including the `resource_metadata` URL MCP clients use to discover the
+/// authorization server (RFC 9728).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthChallenge {
+    /// Protection space the credentials apply to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub realm: Option<String>,
+    /// URL of the protected resource metadata document.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_metadata: Option<String>,
+    /// OAuth error code (e.g., `invalid_token`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Human-readable explanation of the error.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_description: Option<String>,
+}
+
+impl AuthChallenge {
+    /// Renders the challenge as a `WWW-Authenticate` header value.
+    ///
+    /// Parameter values are emitted as quoted strings with quotes and
+    /// backslashes escaped and control characters dropped.
+    #[must_use]
+    pub fn header_value(&self) -> String {
+        let params: Vec<String> = [
+            ("realm", &self.realm),
+            ("resource_metadata", &self.resource_metadata),
+            ("error", &self.error),
+            ("error_description", &self.error_description),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| {
+            value
+                .as_deref()
+                .map(|value| format!("{name}=\"{}\"", quote_param(value)))
+        })
+        .collect();
+
+        if params.is_empty() {
+            "Bearer".to_string()
+        } else {
+            format!("Bearer {}", params.join(", "))
+        }
+    }
+
+    /// Returns `error` with this challenge stored under [`AUTH_CHALLENGE_KEY`]
+    /// in its `data`.
+    ///
+    /// Existing object data is kept; any other data value is moved under
+    /// `detail`.
+    #[must_use]
+    pub fn attach(&self, mut error: McpError) -> McpError {
+        let challenge = serde_json::to_value(self).unwrap_or_default();
+        let mut data = match error.data.take() {
+            Some(serde_json::Value::Object(map)) => map,
+            Some(other) => {
+                let mut map = serde_json::Map::new();
+                map.insert("detail".to_string(), other);
+                map
+            }
+            None => serde_json::Map::new(),
+        };
+        data.insert(AUTH_CHALLENGE_KEY.to_string(), challenge);
+        error.data = Some(serde_json::Value::Object(data));
+        error
+    }
+
+    /// Extracts a challenge previously stored by [`attach`](Self::attach)
+    /// from an error's `data`.
+    #[must_use]
+    pub fn from_error_data(data: Option<&serde_json::Value>) -> Option<Self> {
+        let challenge = data?.get(AUTH_CHALLENGE_KEY)?;
+        serde_json::from_value(challenge.clone()).ok()
+    }
+}
+
+fn quote_param(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len());
+    for ch in value.chars().filter(|ch| !ch.is_control()) {
+        if ch == '"' || ch == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(ch);
+    }
+    quoted
 }
@@ -17,8 +17,11 @@
 //!
 //! # Cancel-Correctness
 //!
-//! All combinators properly drain cancelled/losing futures to ensure
-//! structured concurrency invariants are maintained. No orphan tasks.
+//! Child futures are polled concurrently within the calling task, so they
+//! can never outlive the combinator call. Losing or outstanding futures are
+//! dropped before the combinator returns, and if the request's [`Cx`] is
+//! cancelled (or its budget is exhausted) every outstanding child is dropped
+//! and the combinator fails with `RequestCancelled`. No orphan tasks.
 //!
 //! # Example
 //!
@@ -26,7 +29,7 @@
 //! use fastmcp_core::combinator::{join_all, race, quorum, first_ok};
 //!
 //! // Wait for all to complete
-//! let results = join_all(ctx.cx(), vec![fut1, fut2, fut3]).await;
+//! let results = join_all(ctx.cx(), vec![fut1, fut2, fut3]).await?;
 //!
 //! // Return first to complete
 //! let winner = race(ctx.cx(), vec![fut1, fut2, fut3]).await?;
@@ -40,7 +43,8 @@
 
 use std::future::Future;
 use std::pin::Pin;
-use std::time::Duration;
+use std::task::Poll;
+use std::time::{Duration, Instant};
 
 use asupersync::Cx;
 
@@ -53,6 +57,69 @@ use crate::error::{McpError, McpErrorCode, McpResult};
 /// A boxed, pinned, sendable future for use with combinators.
 pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 
+/// Returns true if the request owning `cx` should stop.
+fn cancel_requested(cx: &Cx) -> bool {
+    cx.is_cancel_requested() || cx.budget().is_exhausted()
+}
+
+/// Polls `futures` concurrently until all complete or `on_ready` stops early.
+///
+/// Every wakeup polls each outstanding future in input order and hands
+/// completed values to `on_ready` along with their index. Returning `Some`
+/// from `on_ready` finishes the combinator immediately. Outstanding futures
+/// are dropped before this returns, including when `cx` is cancelled.
+///
+/// Cancelling `cx` does not wake the task, so while children are pending
+/// this re-polls every [`CANCEL_CHECK_INTERVAL`](crate::runtime::CANCEL_CHECK_INTERVAL).
+/// Fails with an internal error if the timer behind those re-polls is
+/// unavailable.
+async fn drive<'a, T, R>(
+    cx: &Cx,
+    futures: Vec<BoxFuture<'a, T>>,
+    mut on_ready: impl FnMut(usize, T) -> Option<R>,
+) -> McpResult<Option<R>> {
+    let mut slots: Vec<Option<BoxFuture<'a, T>>> = futures.into_iter().map(Some).collect();
+    let mut remaining = slots.len();
+
+    std::future::poll_fn(move |task_cx| {
+        if cancel_requested(cx) {
+            slots.clear();
+            return Poll::Ready(Err(McpError::request_cancelled()));
+        }
+
+        for (index, slot) in slots.iter_mut().enumerate() {
+            let Some(fut) = slot else {
+                continue;
+            };
+            if let Poll::Ready(value) = fut.as_mut().poll(task_cx) {
+                *slot = None;
+                remaining -= 1;
+                if let Some(result) = on_ready(index, value) {
+                    slots.clear();
+                    return Poll::Ready(Ok(Some(result)));
+                }
+            }
+        }
+
+        if remaining == 0 {
+            Poll::Ready(Ok(None))
+        } else if cancel_requested(cx) {
+            // A child may have requested cancellation during this round
+            slots.clear();
+            Poll::Ready(Err(McpError::request_cancelled()))
+        } else if let Err(e) = crate::runtime::wake_at(
+            Instant::now() + crate::runtime::CANCEL_CHECK_INTERVAL,
+            task_cx.waker().clone(),
+        ) {
+            slots.clear();
+            Poll::Ready(Err(e))
+        } else {
+            Poll::Pending
+        }
+    })
+    .await
+}
+
 // ============================================================================
 // Join Combinator
 // ============================================================================
@@ -64,11 +131,13 @@ pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 ///
 /// # Cancel-Correctness
 ///
-/// In Phase 1+, if any future is cancelled or panics, the combinator
-/// will still await all remaining futures to completion before returning.
+/// Futures are polled concurrently. If `cx` is cancelled before all of
+/// them complete, the outstanding futures are dropped and the call fails.
+///
+/// # Errors
 ///
-/// **Phase 0 note:** Currently uses sequential execution, so futures run
-/// one at a time and panics propagate immediately.
+/// Returns a `RequestCancelled` error if the request is cancelled or its
+/// budget is exhausted before every future completes.
 ///
 /// # Example
 ///
@@ -78,16 +147,19 @@ pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 ///     Box::pin(fetch_user(2)),
 ///     Box::pin(fetch_user(3)),
 /// ];
-/// let users = join_all(ctx.cx(), futures).await;
+/// let users = join_all(ctx.cx(), futures).await?;
 /// ```
-pub async fn join_all<T: Send + 'static>(_cx: &Cx, futures: Vec<BoxFuture<'_, T>>) -> Vec<T> {
-    // Phase 0: Sequential execution (single-threaded runtime)
-    // Phase 1+: Will use proper concurrent polling
-    let mut results = Vec::with_capacity(futures.len());
-    for fut in futures {
-        results.push(fut.await);
-    }
-    results
+pub async fn join_all<T: Send + 'static>(
+    cx: &Cx,
+    futures: Vec<BoxFuture<'_, T>>,
+) -> McpResult<Vec<T>> {
+    let mut results: Vec<Option<T>> = (0..futures.len()).map(|_| None).collect();
+    drive(cx, futures, |index, value| {
+        results[index] = Some(value);
+        None::<()>
+    })
+    .await?;
+    Ok(results.into_iter().flatten().collect())
 }
 
 /// Waits for all futures to complete, returning Results.
@@ -103,13 +175,13 @@ pub async fn join_all<T: Send + 'static>(_cx: &Cx, futures: Vec<BoxFuture<'_, T>
 ///     Box::pin(async { Err(McpError::internal_error("failed")) }),
 ///     Box::pin(async { Ok::<_, McpError>(3) }),
 /// ];
-/// let results = join_all_results(ctx.cx(), futures).await;
+/// let results = join_all_results(ctx.cx(), futures).await?;
 /// // results = [Ok(1), Err(...), Ok(3)]
 /// ```
 pub async fn join_all_results<T: Send + 'static>(
     cx: &Cx,
     futures: Vec<BoxFuture<'_, McpResult<T>>>,
-) -> Vec<McpResult<T>> {
+) -> McpResult<Vec<McpResult<T>>> {
     join_all(cx, futures).await
 }
 
@@ -124,15 +196,14 @@ pub async fn join_all_results<T: Send + 'static>(
 ///
 /// # Cancel-Correctness
 ///
-/// In Phase 1+, losing futures are properly cancelled and awaited to
-/// ensure no orphan tasks remain.
-///
-/// **Phase 0 note:** Currently uses sequential execution, so only the
-/// first future runs and there are no losers to cancel.
+/// Futures are polled concurrently; once one completes, the losers are
+/// dropped before returning. Ties within a single poll round go to the
+/// earliest future in input order.
 ///
 /// # Errors
 ///
-/// Returns an error if no futures are provided.
+/// Returns an error if no futures are provided, or `RequestCancelled` if
+/// the request is cancelled before any future completes.
 ///
 /// # Note
 ///
@@ -149,17 +220,17 @@ pub async fn join_all_results<T: Send + 'static>(
 /// ];
 /// let result = race(ctx.cx(), futures).await?;
 /// ```
-pub async fn race<T: Send + 'static>(_cx: &Cx, futures: Vec<BoxFuture<'_, T>>) -> McpResult<T> {
-    // Phase 0: Sequential execution - first future wins
-    // Phase 1+: Will use proper concurrent polling with cancellation
-    let mut iter = futures.into_iter();
-    match iter.next() {
-        Some(fut) => Ok(fut.await),
-        None => Err(McpError::new(
+pub async fn race<T: Send + 'static>(cx: &Cx, futures: Vec<BoxFuture<'_, T>>) -> McpResult<T> {
+    if futures.is_empty() {
+        return Err(McpError::new(
             McpErrorCode::InvalidParams,
             "race requires at least one future",
-        )),
+        ));
     }
+
+    drive(cx, futures, |_, value| Some(value))
+        .await?
+        .ok_or_else(|| McpError::internal_error("race finished without a winner"))
 }
 
 /// Races multiple futures with a timeout.
@@ -232,11 +303,8 @@ impl<T> QuorumResult<T> {
 ///
 /// # Cancel-Correctness
 ///
-/// In Phase 1+, once quorum is reached (or impossible), remaining futures
-/// are cancelled and drained. No orphan tasks.
-///
-/// **Phase 0 note:** Currently uses sequential execution, so remaining
-/// futures simply aren't started rather than being cancelled.
+/// Futures are polled concurrently. Once quorum is reached (or becomes
+/// impossible), remaining futures are dropped. No orphan tasks.
 ///
 /// # Special Cases
 ///
@@ -260,7 +328,7 @@ impl<T> QuorumResult<T> {
 /// }
 /// ```
 pub async fn quorum<T: Send + 'static>(
-    _cx: &Cx,
+    cx: &Cx,
     required: usize,
     futures: Vec<BoxFuture<'_, McpResult<T>>>,
 ) -> McpResult<QuorumResult<T>> {
@@ -283,28 +351,19 @@ pub async fn quorum<T: Send + 'static>(
         });
     }
 
-    // Phase 0: Sequential execution with early exit
-    // Phase 1+: Will use proper concurrent polling with cancellation
     let mut successes = Vec::with_capacity(required);
     let mut failures = 0;
     let max_allowed_failures = total - required;
 
-    for fut in futures {
-        // Check if quorum is already met
-        if successes.len() >= required {
-            break;
-        }
-
-        // Check if quorum is still possible
-        if failures > max_allowed_failures {
-            break;
-        }
-
-        match fut.await {
+    drive(cx, futures, |_, result| {
+        match result {
             Ok(value) => successes.push(value),
             Err(_) => failures += 1,
         }
-    }
+        // Stop once quorum is met or can no longer be reached
+        (successes.len() >= required || failures > max_allowed_failures).then_some(())
+    })
+    .await?;
 
     let quorum_met = successes.len() >= required;
 
@@ -352,9 +411,9 @@ pub async fn quorum_timeout<T: Send + 'static>(
 
 /// Races futures and returns the first successful result.
 ///
-/// This function takes futures that return `McpResult<T>` and tries them
-/// in sequence (Phase 0) or concurrently (Phase 1+), returning the first
-/// `Ok` value. If all futures return `Err`, the last error is returned.
+/// This function takes futures that return `McpResult<T>` and polls them
+/// concurrently, returning the first `Ok` value. If all futures return
+/// `Err`, the last error to complete is returned.
 ///
 /// Use this for fallback patterns where you want to try multiple sources
 /// and take the first success.
@@ -370,7 +429,7 @@ pub async fn quorum_timeout<T: Send + 'static>(
 /// let result = first_ok(ctx.cx(), futures).await?;
 /// ```
 pub async fn first_ok<T: Send + 'static>(
-    _cx: &Cx,
+    cx: &Cx,
     futures: Vec<BoxFuture<'_, McpResult<T>>>,
 ) -> McpResult<T> {
     if futures.is_empty() {
@@ -380,15 +439,19 @@ pub async fn first_ok<T: Send + 'static>(
         ));
     }
 
-    // Phase 0: Sequential fallback execution
-    // Phase 1+: Will use concurrent polling with first-success semantics
     let mut last_error = None;
 
-    for fut in futures {
-        match fut.await {
-            Ok(value) => return Ok(value),
-            Err(e) => last_error = Some(e),
+    let winner = drive(cx, futures, |_, result| match result {
+        Ok(value) => Some(value),
+        Err(e) => {
+            last_error = Some(e);
+            None
         }
+    })
+    .await?;
+
+    if let Some(value) = winner {
+        return Ok(value);
     }
 
     Err(last_error
@@ -412,7 +475,7 @@ mod tests {
     fn test_join_all_empty() {
         let cx = make_cx();
         let futures: Vec<BoxFuture<'_, i32>> = vec![];
-        let results = block_on(join_all(&cx, futures));
+        let results = block_on(join_all(&cx, futures)).unwrap();
         assert!(results.is_empty());
     }
 
@@ -420,7 +483,7 @@ mod tests {
     fn test_join_all_single() {
         let cx = make_cx();
         let futures: Vec<BoxFuture<'_, i32>> = vec![Box::pin(async { 42 })];
-        let results = block_on(join_all(&cx, futures));
+        let results = block_on(join_all(&cx, futures)).unwrap();
         assert_eq!(results, vec![42]);
     }
 
@@ -432,10 +495,138 @@ mod tests {
             Box::pin(async { 2 }),
             Box::pin(async { 3 }),
         ];
-        let results = block_on(join_all(&cx, futures));
+        let results = block_on(join_all(&cx, futures)).unwrap();
         assert_eq!(results, vec![1, 2, 3]);
     }
 
+    /// Yields to the executor `remaining` times before completing.
+    struct YieldN {
+        remaining: usize,
+    }
+
+    impl Future for YieldN {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, task_cx: &mut std::task::Context<'_>) -> Poll<()> {
+            if self.remaining == 0 {
+                return Poll::Ready(());
+            }
+            self.remaining -= 1;
+            task_cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    fn yield_n(remaining: usize) -> YieldN {
+        YieldN { remaining }
+    }
+
+    #[test]
+    fn test_join_all_runs_futures_concurrently() {
+        let cx = make_cx();
+        let order = std::sync::Mutex::new(Vec::new());
+        let futures: Vec<BoxFuture<'_, i32>> = vec![
+            Box::pin(async {
+                yield_n(3).await;
+                order.lock().unwrap().push(1);
+                1
+            }),
+            Box::pin(async {
+                order.lock().unwrap().push(2);
+                2
+            }),
+        ];
+        let results = block_on(join_all(&cx, futures)).unwrap();
+
+        // Results keep input order even though the second finished first
+        assert_eq!(results, vec![1, 2]);
+        assert_eq!(*order.lock().unwrap(), vec![2, 1]);
+    }
+
+    #[test]
+    fn test_race_returns_first_to_complete() {
+        let cx = make_cx();
+        let futures: Vec<BoxFuture<'_, &str>> = vec![
+            Box::pin(async {
+                yield_n(5).await;
+                "slow"
+            }),
+            Box::pin(async {
+                yield_n(1).await;
+                "fast"
+            }),
+        ];
+        let result = block_on(race(&cx, futures));
+        assert_eq!(result.unwrap(), "fast");
+    }
+
+    /// Sets a flag when dropped, to observe that a child was cancelled.
+    struct DropFlag(std::sync::Arc<std::sync::atomic::AtomicBool>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_join_all_cancellation_drops_outstanding_children() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let cx = make_cx();
+        let dropped = Arc::new(AtomicBool::new(false));
+        let completed = Arc::new(AtomicBool::new(false));
+
+        let guard = DropFlag(dropped.clone());
+        let completed_flag = completed.clone();
+        let canceller = cx.clone();
+        let futures: Vec<BoxFuture<'_, ()>> = vec![
+            Box::pin(async move {
+                let _guard = guard;
+                yield_n(usize::MAX).await;
+                completed_flag.store(true, Ordering::SeqCst);
+            }),
+            Box::pin(async move {
+                canceller.set_cancel_requested(true);
+            }),
+        ];
+
+        let result = block_on(join_all(&cx, futures));
+        let err = result.unwrap_err();
+        assert_eq!(err.code, McpErrorCode::RequestCancelled);
+        assert!(dropped.load(Ordering::SeqCst));
+        assert!(!completed.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_join_all_cancelled_while_children_are_parked() {
+        let cx = make_cx();
+        let canceller = cx.clone();
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            canceller.set_cancel_requested(true);
+        });
+
+        // Neither child ever wakes the task; only the cancel check can end this
+        let futures: Vec<BoxFuture<'_, ()>> = vec![
+            Box::pin(std::future::pending()),
+            Box::pin(std::future::pending()),
+        ];
+        let result = block_on(join_all(&cx, futures));
+        handle.join().unwrap();
+        assert_eq!(result.unwrap_err().code, McpErrorCode::RequestCancelled);
+    }
+
+    #[test]
+    fn test_race_on_cancelled_context_fails() {
+        let cx = make_cx();
+        cx.set_cancel_requested(true);
+        let futures: Vec<BoxFuture<'_, i32>> = vec![Box::pin(async { 1 })];
+        let result = block_on(race(&cx, futures));
+        assert_eq!(result.unwrap_err().code, McpErrorCode::RequestCancelled);
+    }
+
     #[test]
     fn test_race_empty() {
         let cx = make_cx();
@@ -5,11 +5,15 @@
 
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::Poll;
+use std::time::{Duration, Instant};
 
 use asupersync::types::CancelReason;
-use asupersync::{Budget, Cx, Outcome, RegionId, TaskId};
+use asupersync::{Budget, CancelKind, Cx, Outcome, RegionId, TaskId};
 
+use crate::logging::{Level, debug, targets, warn};
 use crate::{AUTH_STATE_KEY, AuthContext, SessionState};
 
 // ============================================================================
@@ -31,6 +35,58 @@ pub trait NotificationSender: Send + Sync {
     fn send_progress(&self, progress: f64, total: Option<f64>, message: Option<&str>);
 }
 
+// ============================================================================
+// Content Stream Sender
+// ============================================================================
+
+/// Trait for delivering partial tool output while the tool is still running.
+///
+/// The server installs an implementation for each `tools/call`. Clients that
+/// opted into streaming receive every chunk as a notification; for other
+/// clients the chunks are buffered and prepended to the final result.
+pub trait ContentStreamSender: Send + Sync {
+    /// Sends one chunk of content.
+    fn send_content(&self, content: Vec<ToolContentItem>);
+}
+
+// ============================================================================
+// Log Sender
+// ============================================================================
+
+/// Trait for forwarding handler log messages to the client.
+///
+/// The server installs an implementation when it advertises the `logging`
+/// capability and the client has picked a level with `logging/setLevel`.
+/// Implementations drop messages below that level.
+pub trait LogSender: Send + Sync {
+    /// Sends one log message, optionally naming the logger it came from.
+    fn send_log(&self, level: Level, logger: Option<&str>, data: serde_json::Value);
+}
+
+// ============================================================================
+// Missing Capability Policy
+// ============================================================================
+
+/// What context helpers that need a client capability do when it is missing.
+///
+/// Applies to helpers that reach the client:
+///
+/// - [`McpContext::log`]: errors, or drops the message.
+/// - [`McpContext::elicit`] and friends: error, or return
+///   [`ElicitationResponse::cancel`] as if the user dismissed the prompt.
+/// - [`McpContext::sample`] and friends: always error. There is no
+///   completion to stand in for the one the client would have produced.
+///
+/// No-ops are logged at debug level.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MissingCapabilityPolicy {
+    /// Return an error from the helper.
+    #[default]
+    Error,
+    /// Skip the call where a stand-in result makes sense.
+    SilentNoOp,
+}
+
 // ============================================================================
 // Sampling Sender
 // ============================================================================
@@ -865,6 +921,17 @@ impl std::fmt::Debug for ProgressReporter {
     }
 }
 
+/// What is left of a request's budget, as seen by a handler.
+///
+/// Returned by [`McpContext::budget_remaining`]. `None` means unbounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetRemaining {
+    /// Time left before the request deadline.
+    pub deadline: Option<Duration>,
+    /// Polls left before the poll quota runs out.
+    pub polls: Option<u64>,
+}
+
 /// MCP context that wraps asupersync's capability context.
 ///
 /// `McpContext` provides access to:
@@ -928,6 +995,60 @@ pub struct McpContext {
     client_capabilities: Option<ClientCapabilityInfo>,
     /// Server capability information.
     server_capabilities: Option<ServerCapabilityInfo>,
+    /// JSON-RPC method being served (e.g. `tools/call`).
+    method: Option<String>,
+    /// Original JSON-RPC request ID, rendered as a string.
+    request_id_display: Option<String>,
+    /// Optional sink for streamed tool content.
+    content_stream: Option<Arc<dyn ContentStreamSender>>,
+    /// Optional sink for log messages to the client.
+    log_sender: Option<Arc<dyn LogSender>>,
+    /// What client-facing helpers do when the client lacks a capability.
+    missing_capability_policy: MissingCapabilityPolicy,
+    /// `_meta` entries for the result, shared between clones.
+    result_meta: Arc<Mutex<serde_json::Map<String, serde_json::Value>>>,
+    /// When the context was created, i.e. when the request started.
+    created_at: Instant,
+    /// Handler timeout tighter than the budget deadline, if any.
+    timeout: Option<Arc<HandlerTimeout>>,
+}
+
+/// A handler timeout armed on the runtime timer.
+///
+/// Dropping the last context clone holding it disarms the pending cancel,
+/// so a finished request's `Cx` is not cancelled after the fact.
+struct HandlerTimeout {
+    at: Instant,
+    disarmed: Arc<AtomicBool>,
+}
+
+impl HandlerTimeout {
+    /// Arms a cancel of `cx` with [`CancelKind::Timeout`] at `at`.
+    fn arm(cx: &Cx, at: Instant) -> Self {
+        let disarmed = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&disarmed);
+        let cx = cx.clone();
+        let armed = crate::runtime::run_at(at, move || {
+            if !flag.load(Ordering::Acquire) && !cx.is_cancel_requested() {
+                cx.cancel_with(CancelKind::Timeout, None);
+            }
+        });
+        if let Err(e) = armed {
+            // Still enforced at checkpoints through `timed_out`
+            warn!(
+                target: targets::HANDLER,
+                "Handler timeout will only be seen at checkpoints: {}",
+                e
+            );
+        }
+        Self { at, disarmed }
+    }
+}
+
+impl Drop for HandlerTimeout {
+    fn drop(&mut self) {
+        self.disarmed.store(true, Ordering::Release);
+    }
 }
 
 impl std::fmt::Debug for McpContext {
@@ -945,6 +1066,11 @@ impl std::fmt::Debug for McpContext {
             .field("tool_call_depth", &self.tool_call_depth)
             .field("client_capabilities", &self.client_capabilities)
             .field("server_capabilities", &self.server_capabilities)
+            .field("method", &self.method)
+            .field("request_id_display", &self.request_id_display)
+            .field("content_stream", &self.content_stream.is_some())
+            .field("log_sender", &self.log_sender.is_some())
+            .field("missing_capability_policy", &self.missing_capability_policy)
             .finish()
     }
 }
@@ -969,6 +1095,14 @@ impl McpContext {
             tool_call_depth: 0,
             client_capabilities: None,
             server_capabilities: None,
+            method: None,
+            request_id_display: None,
+            content_stream: None,
+            log_sender: None,
+            missing_capability_policy: MissingCapabilityPolicy::Error,
+            result_meta: Arc::default(),
+            created_at: Instant::now(),
+            timeout: None,
         }
     }
 
@@ -990,6 +1124,14 @@ impl McpContext {
             tool_call_depth: 0,
             client_capabilities: None,
             server_capabilities: None,
+            method: None,
+            request_id_display: None,
+            content_stream: None,
+            log_sender: None,
+            missing_capability_policy: MissingCapabilityPolicy::Error,
+            result_meta: Arc::default(),
+            created_at: Instant::now(),
+            timeout: None,
         }
     }
 
@@ -1012,6 +1154,14 @@ impl McpContext {
             tool_call_depth: 0,
             client_capabilities: None,
             server_capabilities: None,
+            method: None,
+            request_id_display: None,
+            content_stream: None,
+            log_sender: None,
+            missing_capability_policy: MissingCapabilityPolicy::Error,
+            result_meta: Arc::default(),
+            created_at: Instant::now(),
+            timeout: None,
         }
     }
 
@@ -1036,6 +1186,14 @@ impl McpContext {
             tool_call_depth: 0,
             client_capabilities: None,
             server_capabilities: None,
+            method: None,
+            request_id_display: None,
+            content_stream: None,
+            log_sender: None,
+            missing_capability_policy: MissingCapabilityPolicy::Error,
+            result_meta: Arc::default(),
+            created_at: Instant::now(),
+            timeout: None,
         }
     }
 
@@ -1059,6 +1217,55 @@ impl McpContext {
         self
     }
 
+    /// Sets the content stream for this context.
+    ///
+    /// This enables [`stream_content`](Self::stream_content) for tool handlers.
+    #[must_use]
+    pub fn with_content_stream(mut self, sender: Arc<dyn ContentStreamSender>) -> Self {
+        self.content_stream = Some(sender);
+        self
+    }
+
+    /// Sets the log sender for this context.
+    ///
+    /// This enables [`log`](Self::log) to reach the client.
+    #[must_use]
+    pub fn with_log_sender(mut self, sender: Arc<dyn LogSender>) -> Self {
+        self.log_sender = Some(sender);
+        self
+    }
+
+    /// Sets what client-facing helpers do when the client lacks a capability.
+    #[must_use]
+    pub fn with_missing_capability_policy(mut self, policy: MissingCapabilityPolicy) -> Self {
+        self.missing_capability_policy = policy;
+        self
+    }
+
+    /// Limits the handler to `timeout`, when that is less than what is left
+    /// of the request budget.
+    ///
+    /// When the timeout passes, the runtime timer cancels the underlying
+    /// [`Cx`] with [`CancelKind::Timeout`], so the handler is stopped at its
+    /// next await on cancel-aware work as well as at checkpoints, and
+    /// [`budget_remaining`](Self::budget_remaining) counts down to it instead
+    /// of the budget deadline.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        let left = self.budget_remaining().deadline;
+        if left.is_none_or(|left| timeout < left) {
+            let at = Instant::now() + timeout;
+            self.timeout = Some(Arc::new(HandlerTimeout::arm(&self.cx, at)));
+        }
+        self
+    }
+
+    /// Returns what client-facing helpers do when the client lacks a capability.
+    #[must_use]
+    pub fn missing_capability_policy(&self) -> MissingCapabilityPolicy {
+        self.missing_capability_policy
+    }
+
     /// Sets the resource reader for this context.
     ///
     /// This enables the `read_resource()` methods to read resources from
@@ -1119,6 +1326,23 @@ impl McpContext {
         self
     }
 
+    /// Sets the JSON-RPC method this context is serving.
+    #[must_use]
+    pub fn with_method(mut self, method: impl Into<String>) -> Self {
+        self.method = Some(method.into());
+        self
+    }
+
+    /// Sets the original JSON-RPC request ID, as sent by the client.
+    ///
+    /// Unlike [`request_id`](Self::request_id), this preserves string IDs
+    /// verbatim so they can be used as correlation IDs in logs.
+    #[must_use]
+    pub fn with_request_id_display(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id_display = Some(request_id.into());
+        self
+    }
+
     /// Returns whether progress reporting is enabled for this context.
     #[must_use]
     pub fn has_progress_reporter(&self) -> bool {
@@ -1181,6 +1405,141 @@ impl McpContext {
         }
     }
 
+    /// Returns whether this context can stream tool content.
+    #[must_use]
+    pub fn can_stream_content(&self) -> bool {
+        self.content_stream.is_some()
+    }
+
+    /// Sends part of a tool's output before the tool returns.
+    ///
+    /// Clients that opted into streaming receive the chunk immediately;
+    /// everyone else sees it at the front of the final `tools/call` result.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no content stream is attached (the context was
+    /// not created for a `tools/call`).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// async fn export(ctx: &McpContext) -> McpResult<Vec<Content>> {
+    ///     for chunk in encode_blob_in_chunks()? {
+    ///         ctx.stream_content(vec![ToolContentItem::text(chunk)])?;
+    ///     }
+    ///     Ok(vec![Content::text("export complete")])
+    /// }
+    /// ```
+    pub fn stream_content(&self, content: Vec<ToolContentItem>) -> crate::McpResult<()> {
+        let Some(ref sender) = self.content_stream else {
+            return Err(crate::McpError::internal_error(
+                "Content streaming is not available in this context",
+            ));
+        };
+        sender.send_content(content);
+        Ok(())
+    }
+
+    /// Sends a log message to the client as `notifications/message`.
+    ///
+    /// Messages below the level the client set with `logging/setLevel` are
+    /// dropped. MCP has no trace level, so [`Level::Trace`] is sent as debug.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server does not advertise the `logging`
+    /// capability or the client has not set a level, unless the
+    /// [`MissingCapabilityPolicy`] is `SilentNoOp`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// ctx.log(Level::Info, json!({"table": table, "rows": rows.len()}))?;
+    /// ```
+    pub fn log(&self, level: Level, data: impl Into<serde_json::Value>) -> crate::McpResult<()> {
+        self.send_log(level, None, data.into())
+    }
+
+    /// Like [`log`](Self::log), but names the logger the message came from.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`log`](Self::log).
+    pub fn log_with_logger(
+        &self,
+        level: Level,
+        logger: &str,
+        data: impl Into<serde_json::Value>,
+    ) -> crate::McpResult<()> {
+        self.send_log(level, Some(logger), data.into())
+    }
+
+    fn send_log(
+        &self,
+        level: Level,
+        logger: Option<&str>,
+        data: serde_json::Value,
+    ) -> crate::McpResult<()> {
+        let Some(ref sender) = self.log_sender else {
+            return self
+                .missing_capability("Logging not available: client has not enabled logging");
+        };
+        sender.send_log(level, logger, data);
+        Ok(())
+    }
+
+    /// Applies the [`MissingCapabilityPolicy`] to a helper whose capability
+    /// is missing, returning `Ok` if the helper should skip its work.
+    fn missing_capability(&self, message: &str) -> crate::McpResult<()> {
+        match self.missing_capability_policy {
+            MissingCapabilityPolicy::Error => Err(crate::McpError::new(
+                crate::McpErrorCode::InvalidRequest,
+                message,
+            )),
+            MissingCapabilityPolicy::SilentNoOp => {
+                debug!(target: targets::HANDLER, "{}; skipping", message);
+                Ok(())
+            }
+        }
+    }
+
+    /// Adds an entry to the result's `_meta` object.
+    ///
+    /// Use it for metadata about how the result was produced (timing, model
+    /// used, upstream cache status) that clients and proxies may consume
+    /// without parsing the content. Setting a key again replaces its value.
+    /// Currently only `tools/call` results carry the entries.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// ctx.set_result_meta("model", json!("gpt-4o"));
+    /// ctx.set_result_meta("upstreamMs", json!(elapsed.as_millis()));
+    /// ```
+    pub fn set_result_meta(&self, key: impl Into<String>, value: serde_json::Value) {
+        self.result_meta
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(key.into(), value);
+    }
+
+    /// Removes and returns the entries added with
+    /// [`set_result_meta`](Self::set_result_meta), or `None` if there are
+    /// none.
+    ///
+    /// Called by the server when it builds the result.
+    #[must_use]
+    pub fn take_result_meta(&self) -> Option<serde_json::Value> {
+        let meta = std::mem::take(
+            &mut *self
+                .result_meta
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+        (!meta.is_empty()).then_some(serde_json::Value::Object(meta))
+    }
+
     /// Returns the unique request identifier.
     ///
     /// This corresponds to the JSON-RPC request ID and is useful for
@@ -1190,6 +1549,21 @@ impl McpContext {
         self.request_id
     }
 
+    /// Returns the original JSON-RPC request ID, if known.
+    ///
+    /// String IDs are returned unchanged and numeric IDs in decimal form.
+    /// Returns `None` for contexts created outside request dispatch.
+    #[must_use]
+    pub fn request_id_display(&self) -> Option<&str> {
+        self.request_id_display.as_deref()
+    }
+
+    /// Returns the JSON-RPC method being served (e.g. `tools/call`), if known.
+    #[must_use]
+    pub fn method(&self) -> Option<&str> {
+        self.method.as_deref()
+    }
+
     /// Returns the underlying region ID from asupersync.
     ///
     /// The region represents the request's lifecycle scope - all spawned
@@ -1216,13 +1590,81 @@ impl McpContext {
         self.cx.budget()
     }
 
+    /// Returns how much of the request budget is left.
+    ///
+    /// Lets handlers pick a cheaper code path when little time remains, e.g.
+    /// skip an expensive refinement step. The deadline is measured from when
+    /// this context was created, or is the handler timeout when that is
+    /// tighter; poll quotas are reported as-is. Cheap enough to call in a
+    /// loop.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let thorough = ctx
+    ///     .budget_remaining()
+    ///     .deadline
+    ///     .is_none_or(|left| left > Duration::from_secs(5));
+    /// ```
+    #[must_use]
+    pub fn budget_remaining(&self) -> BudgetRemaining {
+        let budget = self.cx.budget();
+        let deadline = match &self.timeout {
+            // Only set when tighter than the budget deadline
+            Some(timeout) => Some(timeout.at.saturating_duration_since(Instant::now())),
+            None => budget.deadline.map(|deadline| {
+                Duration::from_nanos(deadline.as_nanos()).saturating_sub(self.created_at.elapsed())
+            }),
+        };
+        BudgetRemaining {
+            deadline,
+            polls: (budget.poll_quota != u32::MAX).then_some(u64::from(budget.poll_quota)),
+        }
+    }
+
     /// Checks if cancellation has been requested.
     ///
     /// This includes client disconnection, timeout, or explicit cancellation.
     /// Handlers should check this periodically and exit early if true.
     #[must_use]
     pub fn is_cancelled(&self) -> bool {
-        self.cx.is_cancel_requested() || self.cx.budget().is_exhausted()
+        self.cx.is_cancel_requested() || self.cx.budget().is_exhausted() || self.timed_out()
+    }
+
+    /// Returns whether the timeout set by [`with_timeout`](Self::with_timeout) has passed.
+    fn timed_out(&self) -> bool {
+        self.timeout
+            .as_ref()
+            .is_some_and(|timeout| Instant::now() >= timeout.at)
+    }
+
+    /// Returns why the request was cancelled, or `None` if it has not been.
+    ///
+    /// The server cancels with [`CancelKind::User`] for
+    /// `notifications/cancelled`, [`CancelKind::Timeout`] when a tool exceeds
+    /// its handler timeout, and [`CancelKind::Shutdown`] when the server stops.
+    /// An exhausted request budget reports [`CancelKind::Deadline`].
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// if ctx.cancel_reason() == Some(CancelKind::Shutdown) {
+    ///     checkpoint_progress_for_resume()?;
+    /// }
+    /// ```
+    #[must_use]
+    pub fn cancel_reason(&self) -> Option<CancelKind> {
+        if let Some(reason) = self.cx.cancel_reason() {
+            return Some(reason.kind);
+        }
+        if self.timed_out() {
+            return Some(CancelKind::Timeout);
+        }
+        if self.cx.budget().is_exhausted() {
+            return Some(CancelKind::Deadline);
+        }
+        // Cancelled without a recorded reason
+        self.cx.is_cancel_requested().then_some(CancelKind::User)
     }
 
     /// Cooperative cancellation checkpoint.
@@ -1248,7 +1690,7 @@ impl McpContext {
     /// ```
     pub fn checkpoint(&self) -> Result<(), CancelledError> {
         self.cx.checkpoint().map_err(|_| CancelledError)?;
-        if self.cx.budget().is_exhausted() {
+        if self.cx.budget().is_exhausted() || self.timed_out() {
             return Err(CancelledError);
         }
         Ok(())
@@ -1276,6 +1718,52 @@ impl McpContext {
         self.cx.masked(f)
     }
 
+    /// Waits for `duration` without blocking the thread running the handler.
+    ///
+    /// Unlike `std::thread::sleep`, the wait ends early when the request or
+    /// task is cancelled (noticed within a few milliseconds), and it never
+    /// runs past the budget deadline.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `RequestCancelled` error if cancellation is requested
+    /// before the wait ends, or if the budget deadline falls within it.
+    /// Returns an internal error if the runtime timer is unavailable.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// while !job.is_done()? {
+    ///     ctx.sleep(Duration::from_secs(5)).await?;
+    /// }
+    /// ```
+    pub async fn sleep(&self, duration: Duration) -> crate::McpResult<()> {
+        let until = Instant::now() + duration;
+        std::future::poll_fn(|task_cx| {
+            if self.is_cancelled() {
+                return Poll::Ready(Err(crate::McpError::request_cancelled()));
+            }
+            let now = Instant::now();
+            if now >= until {
+                return Poll::Ready(Ok(()));
+            }
+
+            // Cancellation does not wake us, so check back periodically.
+            let mut wake = until.min(now + crate::runtime::CANCEL_CHECK_INTERVAL);
+            if let Some(left) = self.budget_remaining().deadline {
+                if left.is_zero() {
+                    return Poll::Ready(Err(crate::McpError::request_cancelled()));
+                }
+                wake = wake.min(now + left);
+            }
+            match crate::runtime::wake_at(wake, task_cx.waker().clone()) {
+                Ok(()) => Poll::Pending,
+                Err(e) => Poll::Ready(Err(e)),
+            }
+        })
+        .await
+    }
+
     /// Records a trace event for this request.
     ///
     /// Events are associated with the request's trace context and can be
@@ -1657,7 +2145,8 @@ impl McpContext {
     /// # Errors
     ///
     /// Returns an error if:
-    /// - The client doesn't support sampling
+    /// - The client doesn't support sampling, whatever the
+    ///   [`MissingCapabilityPolicy`]
     /// - The sampling request fails
     ///
     /// # Example
@@ -1706,6 +2195,40 @@ impl McpContext {
         self.elicitation_sender.is_some()
     }
 
+    /// Asks the user for structured input via `elicitation/create`.
+    ///
+    /// Sends `message` with `requested_schema` (a flat JSON Schema object)
+    /// and waits for the user's answer. Declining or cancelling is not an
+    /// error: branch on [`ElicitationResponse::action`] and read submitted
+    /// values from `content` when the user accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the client did not advertise the `elicitation`
+    /// capability, or if the request itself fails.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let schema = serde_json::json!({
+    ///     "type": "object",
+    ///     "properties": { "confirm": { "type": "boolean" } },
+    ///     "required": ["confirm"]
+    /// });
+    /// match ctx.elicit("Delete 12 files?", schema).await?.action {
+    ///     ElicitationAction::Accept => { /* read response.content */ }
+    ///     ElicitationAction::Decline | ElicitationAction::Cancel => { /* skip */ }
+    /// }
+    /// ```
+    pub async fn elicit(
+        &self,
+        message: impl Into<String>,
+        requested_schema: serde_json::Value,
+    ) -> crate::McpResult<ElicitationResponse> {
+        self.elicit_with_request(ElicitationRequest::form(message, requested_schema))
+            .await
+    }
+
     /// Requests user input via a form.
     ///
     /// This presents a form to the user with fields defined by the JSON schema.
@@ -1801,21 +2324,25 @@ impl McpContext {
     ///
     /// * `request` - The full elicitation request parameters
     ///
+    /// If the client doesn't support elicitation and the
+    /// [`MissingCapabilityPolicy`] is `SilentNoOp`, returns
+    /// [`ElicitationResponse::cancel`] without asking.
+    ///
     /// # Errors
     ///
     /// Returns an error if:
-    /// - The client doesn't support elicitation
+    /// - The client doesn't support elicitation, under the default policy
     /// - The elicitation request fails
     pub async fn elicit_with_request(
         &self,
         request: ElicitationRequest,
     ) -> crate::McpResult<ElicitationResponse> {
-        let sender = self.elicitation_sender.as_ref().ok_or_else(|| {
-            crate::McpError::new(
-                crate::McpErrorCode::InvalidRequest,
+        let Some(sender) = self.elicitation_sender.as_ref() else {
+            self.missing_capability(
                 "Elicitation not available: client does not support elicitation capability",
-            )
-        })?;
+            )?;
+            return Ok(ElicitationResponse::cancel());
+        };
 
         sender.elicit(request).await
     }
@@ -2122,8 +2649,10 @@ impl McpContext {
 
     /// Waits for all futures to complete and returns their results.
     ///
-    /// This is the N-of-N combinator: all futures must complete before
-    /// returning. Results are returned in the same order as input futures.
+    /// This is the N-of-N combinator: all futures are polled concurrently
+    /// and must complete before returning. Results are returned in the same
+    /// order as input futures. If the request is cancelled first, the
+    /// outstanding futures are dropped and an error is returned.
     ///
     /// # Example
     ///
@@ -2133,12 +2662,12 @@ impl McpContext {
     ///     Box::pin(fetch_user(2)),
     ///     Box::pin(fetch_user(3)),
     /// ];
-    /// let users = ctx.join_all(futures).await;
+    /// let users = ctx.join_all(futures).await?;
     /// ```
     pub async fn join_all<T: Send + 'static>(
         &self,
         futures: Vec<crate::combinator::BoxFuture<'_, T>>,
-    ) -> Vec<T> {
+    ) -> crate::McpResult<Vec<T>> {
         crate::combinator::join_all(&self.cx, futures).await
     }
 
@@ -2302,6 +2831,20 @@ mod tests {
         assert!(ctx.checkpoint().is_err());
     }
 
+    #[test]
+    fn test_mcp_context_cancel_reason() {
+        let ctx = McpContext::new(Cx::for_testing(), 1);
+        assert_eq!(ctx.cancel_reason(), None);
+
+        let cx = Cx::for_testing();
+        cx.cancel_with(CancelKind::Shutdown, None);
+        let ctx = McpContext::new(cx, 1);
+        assert_eq!(ctx.cancel_reason(), Some(CancelKind::Shutdown));
+
+        let ctx = McpContext::new(Cx::for_testing_with_budget(Budget::ZERO), 1);
+        assert_eq!(ctx.cancel_reason(), Some(CancelKind::Deadline));
+    }
+
     #[test]
     fn test_mcp_context_masked_section() {
         let cx = Cx::for_testing();
@@ -2312,6 +2855,62 @@ mod tests {
         assert_eq!(result, 42);
     }
 
+    #[test]
+    fn test_result_meta_shared_between_clones_and_taken_once() {
+        let ctx = McpContext::new(Cx::for_testing(), 1);
+        assert!(ctx.take_result_meta().is_none());
+
+        ctx.clone()
+            .set_result_meta("cached", serde_json::json!(false));
+        assert_eq!(
+            ctx.take_result_meta(),
+            Some(serde_json::json!({"cached": false}))
+        );
+        assert!(ctx.take_result_meta().is_none());
+    }
+
+    #[test]
+    fn test_missing_capability_policy_per_helper() {
+        let strict = McpContext::new(Cx::for_testing(), 1);
+        assert_eq!(
+            strict.missing_capability_policy(),
+            MissingCapabilityPolicy::Error
+        );
+        assert!(crate::block_on(strict.elicit("Continue?", serde_json::json!({}))).is_err());
+
+        let lenient = McpContext::new(Cx::for_testing(), 1)
+            .with_missing_capability_policy(MissingCapabilityPolicy::SilentNoOp);
+        let response = crate::block_on(lenient.elicit("Continue?", serde_json::json!({}))).unwrap();
+        assert_eq!(response.action, ElicitationAction::Cancel);
+        // Sampling has no stand-in result, so it errors regardless
+        assert!(crate::block_on(lenient.sample("Hello", 10)).is_err());
+    }
+
+    #[test]
+    fn test_mcp_context_sleep_waits() {
+        let ctx = McpContext::new(Cx::for_testing(), 1);
+
+        let started = Instant::now();
+        assert!(crate::block_on(ctx.sleep(Duration::from_millis(30))).is_ok());
+        assert!(started.elapsed() >= Duration::from_millis(30));
+    }
+
+    #[test]
+    fn test_mcp_context_sleep_cancelled_mid_sleep() {
+        let cx = Cx::for_testing();
+        let ctx = McpContext::new(cx.clone(), 1);
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            cx.set_cancel_requested(true);
+        });
+
+        let started = Instant::now();
+        let err = crate::block_on(ctx.sleep(Duration::from_secs(30))).unwrap_err();
+        canceller.join().unwrap();
+        assert_eq!(err.code, crate::McpErrorCode::RequestCancelled);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
     #[test]
     fn test_mcp_context_budget() {
         let cx = Cx::for_testing();
@@ -2323,6 +2922,83 @@ mod tests {
         assert!(!budget.is_exhausted());
     }
 
+    #[test]
+    fn test_mcp_context_budget_remaining() {
+        let ctx = McpContext::new(Cx::for_testing_with_budget(Budget::INFINITE), 1);
+        assert_eq!(
+            ctx.budget_remaining(),
+            BudgetRemaining {
+                deadline: None,
+                polls: None,
+            }
+        );
+
+        let ctx = McpContext::new(
+            Cx::for_testing_with_budget(Budget::with_deadline_secs(60)),
+            1,
+        );
+        let left = ctx.budget_remaining().deadline.expect("deadline");
+        assert!(left <= Duration::from_secs(60));
+        assert!(left > Duration::from_secs(50));
+
+        let ctx = McpContext::new(
+            Cx::for_testing_with_budget(Budget::unlimited().with_poll_quota(3)),
+            1,
+        );
+        assert_eq!(ctx.budget_remaining().polls, Some(3));
+    }
+
+    #[test]
+    fn test_mcp_context_with_timeout() {
+        let ctx = McpContext::new(
+            Cx::for_testing_with_budget(Budget::with_deadline_secs(60)),
+            1,
+        )
+        .with_timeout(Duration::from_millis(20));
+        let left = ctx.budget_remaining().deadline.expect("deadline");
+        assert!(left <= Duration::from_millis(20));
+        assert!(!ctx.is_cancelled());
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(ctx.budget_remaining().deadline, Some(Duration::ZERO));
+        assert!(ctx.is_cancelled());
+        assert!(ctx.checkpoint().is_err());
+        assert_eq!(ctx.cancel_reason(), Some(CancelKind::Timeout));
+
+        // A timeout beyond the budget deadline leaves the budget in charge
+        let ctx = McpContext::new(
+            Cx::for_testing_with_budget(Budget::with_deadline_secs(1)),
+            1,
+        )
+        .with_timeout(Duration::from_secs(60));
+        let left = ctx.budget_remaining().deadline.expect("deadline");
+        assert!(left <= Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_mcp_context_timeout_cancels_cx() {
+        let ctx = McpContext::new(Cx::for_testing(), 1).with_timeout(Duration::from_millis(20));
+        assert!(!ctx.cx().is_cancel_requested());
+
+        // The handler never checkpoints; the timer cancels the Cx itself
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(ctx.cx().is_cancel_requested());
+        assert_eq!(
+            ctx.cx().cancel_reason().map(|reason| reason.kind),
+            Some(CancelKind::Timeout)
+        );
+    }
+
+    #[test]
+    fn test_mcp_context_dropped_before_timeout_leaves_cx_alone() {
+        let cx = Cx::for_testing();
+        let ctx = McpContext::new(cx.clone(), 1).with_timeout(Duration::from_millis(20));
+        drop(ctx);
+
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(!cx.is_cancel_requested());
+    }
+
     #[test]
     fn test_cancelled_error_display() {
         let err = CancelledError;
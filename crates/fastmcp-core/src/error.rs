@@ -8,6 +8,10 @@ use serde::{Deserialize, Serialize};
 /// Standard MCP/JSON-RPC error codes.
 ///
 /// These follow the JSON-RPC 2.0 specification and MCP protocol.
+///
+/// JSON-RPC reserves -32768 to -32000 for protocol and server errors; every
+/// other `i32` is free for application-defined codes (see
+/// [`McpError::with_code`]).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(into = "i32", from = "i32")]
 pub enum McpErrorCode {
@@ -72,6 +76,60 @@ impl From<i32> for McpErrorCode {
     }
 }
 
+/// Lowest code in the range JSON-RPC reserves for protocol errors.
+const RESERVED_CODE_MIN: i64 = -32768;
+
+/// Highest code in the range JSON-RPC reserves for protocol errors.
+const RESERVED_CODE_MAX: i64 = -32000;
+
+impl McpErrorCode {
+    /// Returns an application-defined code.
+    ///
+    /// Returns `None` if `code` lies in the reserved range -32768 to -32000
+    /// or does not fit in an `i32`.
+    #[must_use]
+    pub fn application(code: i64) -> Option<Self> {
+        if (RESERVED_CODE_MIN..=RESERVED_CODE_MAX).contains(&code) {
+            return None;
+        }
+        i32::try_from(code).ok().map(McpErrorCode::Custom)
+    }
+
+    /// Returns whether this is an application-defined code, i.e. a
+    /// [`Custom`](Self::Custom) code outside the reserved range.
+    #[must_use]
+    pub fn is_application(&self) -> bool {
+        match self {
+            McpErrorCode::Custom(code) => {
+                !(RESERVED_CODE_MIN..=RESERVED_CODE_MAX).contains(&i64::from(*code))
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns whether this code describes a mistake in the request rather
+    /// than a server fault.
+    ///
+    /// Returns `false` for [`McpErrorCode::RequestCancelled`], which is
+    /// neither.
+    #[must_use]
+    pub fn is_client_error(&self) -> bool {
+        matches!(
+            self,
+            McpErrorCode::ParseError
+                | McpErrorCode::InvalidRequest
+                | McpErrorCode::MethodNotFound
+                | McpErrorCode::InvalidParams
+                | McpErrorCode::ResourceNotFound
+                | McpErrorCode::ResourceForbidden
+                | McpErrorCode::PromptNotFound
+        )
+    }
+}
+
+/// `data.reason` of a [`McpError::cursor_invalidated`] error.
+const CURSOR_INVALIDATED: &str = "cursor_invalidated";
+
 /// An MCP error response.
 ///
 /// This maps directly to the JSON-RPC error object and can be serialized
@@ -112,6 +170,40 @@ impl McpError {
         }
     }
 
+    /// Creates an error with an application-defined code.
+    ///
+    /// Use this for domain errors clients should branch on, such as a quota
+    /// or a conflict. Any `i32` outside the JSON-RPC reserved range
+    /// -32768 to -32000 is allowed, and is sent to the client unchanged;
+    /// a tool returning such an error gets a JSON-RPC error response rather
+    /// than an `isError` result.
+    ///
+    /// A reserved or out-of-range `code` is a programming error. It is
+    /// replaced by an internal error naming the rejected code, so handlers
+    /// cannot pass themselves off as protocol errors like `MethodNotFound`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fastmcp_core::{McpError, McpErrorCode};
+    ///
+    /// let err = McpError::with_code(4001, "Quota exceeded");
+    /// assert_eq!(err.code, McpErrorCode::Custom(4001));
+    ///
+    /// let reserved = McpError::with_code(-32601, "Not really missing");
+    /// assert_eq!(reserved.code, McpErrorCode::InternalError);
+    /// ```
+    #[must_use]
+    pub fn with_code(code: i64, message: impl Into<String>) -> Self {
+        match McpErrorCode::application(code) {
+            Some(code) => Self::new(code, message),
+            None => Self::internal_error(format!(
+                "Invalid application error code {code}: codes from {RESERVED_CODE_MIN} to \
+                 {RESERVED_CODE_MAX} are reserved"
+            )),
+        }
+    }
+
     /// Creates a parse error.
     #[must_use]
     pub fn parse_error(message: impl Into<String>) -> Self {
@@ -166,6 +258,31 @@ impl McpError {
         Self::new(McpErrorCode::RequestCancelled, "Request was cancelled")
     }
 
+    /// Creates a cursor invalidated error.
+    ///
+    /// Returned by paginated `*/list` methods when the listing changed after
+    /// the cursor was issued. Clients should restart from the first page.
+    #[must_use]
+    pub fn cursor_invalidated() -> Self {
+        Self::with_data(
+            McpErrorCode::InvalidParams,
+            "Cursor invalidated: the list changed, restart from the first page",
+            serde_json::json!({ "reason": CURSOR_INVALIDATED }),
+        )
+    }
+
+    /// Returns whether this is a [`cursor_invalidated`](Self::cursor_invalidated) error.
+    #[must_use]
+    pub fn is_cursor_invalidated(&self) -> bool {
+        self.code == McpErrorCode::InvalidParams
+            && self
+                .data
+                .as_ref()
+                .and_then(|data| data.get("reason"))
+                .and_then(serde_json::Value::as_str)
+                == Some(CURSOR_INVALIDATED)
+    }
+
     /// Returns a masked version of this error for client responses.
     ///
     /// When masking is enabled, internal error details are hidden to prevent
@@ -423,6 +540,43 @@ mod tests {
         assert!(matches!(from_int, McpErrorCode::Custom(-99999)));
     }
 
+    #[test]
+    fn test_application_error_codes() {
+        assert_eq!(
+            McpErrorCode::application(4001),
+            Some(McpErrorCode::Custom(4001))
+        );
+        assert_eq!(
+            McpErrorCode::application(-31999),
+            Some(McpErrorCode::Custom(-31999))
+        );
+        assert_eq!(
+            McpErrorCode::application(-32769),
+            Some(McpErrorCode::Custom(-32769))
+        );
+        assert_eq!(McpErrorCode::application(-32000), None);
+        assert_eq!(McpErrorCode::application(-32601), None);
+        assert_eq!(McpErrorCode::application(-32768), None);
+        assert_eq!(McpErrorCode::application(i64::from(i32::MAX) + 1), None);
+
+        assert!(McpErrorCode::Custom(4001).is_application());
+        assert!(!McpErrorCode::Custom(-32005).is_application());
+        assert!(!McpErrorCode::InternalError.is_application());
+    }
+
+    #[test]
+    fn test_with_code_rejects_reserved_codes() {
+        let err = McpError::with_code(4001, "Quota exceeded");
+        assert_eq!(i32::from(err.code), 4001);
+        assert_eq!(err.message, "Quota exceeded");
+
+        for reserved in [-32700, -32601, -32000] {
+            let err = McpError::with_code(reserved, "Spoofed");
+            assert_eq!(err.code, McpErrorCode::InternalError);
+            assert!(err.message.contains(&reserved.to_string()));
+        }
+    }
+
     // ========================================
     // McpError tests
     // ========================================
@@ -35,15 +35,15 @@ pub mod logging;
 pub mod runtime;
 mod state;
 
-pub use auth::{AUTH_STATE_KEY, AccessToken, AuthContext};
+pub use auth::{AUTH_CHALLENGE_KEY, AUTH_STATE_KEY, AccessToken, AuthChallenge, AuthContext};
 pub use context::{
-    CancelledError, ClientCapabilityInfo, ElicitationAction, ElicitationMode, ElicitationRequest,
-    ElicitationResponse, ElicitationSender, IntoOutcome, MAX_RESOURCE_READ_DEPTH,
-    MAX_TOOL_CALL_DEPTH, McpContext, NoOpElicitationSender, NoOpNotificationSender,
-    NoOpSamplingSender, NotificationSender, ProgressReporter, ResourceContentItem,
-    ResourceReadResult, ResourceReader, SamplingRequest, SamplingRequestMessage, SamplingResponse,
-    SamplingRole, SamplingSender, SamplingStopReason, ServerCapabilityInfo, ToolCallResult,
-    ToolCaller, ToolContentItem,
+    BudgetRemaining, CancelledError, ClientCapabilityInfo, ContentStreamSender, ElicitationAction,
+    ElicitationMode, ElicitationRequest, ElicitationResponse, ElicitationSender, IntoOutcome,
+    LogSender, MAX_RESOURCE_READ_DEPTH, MAX_TOOL_CALL_DEPTH, McpContext, MissingCapabilityPolicy,
+    NoOpElicitationSender, NoOpNotificationSender, NoOpSamplingSender, NotificationSender,
+    ProgressReporter, ResourceContentItem, ResourceReadResult, ResourceReader, SamplingRequest,
+    SamplingRequestMessage, SamplingResponse, SamplingRole, SamplingSender, SamplingStopReason,
+    ServerCapabilityInfo, ToolCallResult, ToolCaller, ToolContentItem,
 };
 pub use duration::{ParseDurationError, parse_duration};
 pub use error::{
@@ -53,4 +53,6 @@ pub use runtime::block_on;
 pub use state::{DISABLED_PROMPTS_KEY, DISABLED_RESOURCES_KEY, DISABLED_TOOLS_KEY, SessionState};
 
 // Re-export key asupersync types for convenience
-pub use asupersync::{Budget, Cx, LabConfig, LabRuntime, Outcome, RegionId, Scope, TaskId};
+pub use asupersync::{
+    Budget, CancelKind, Cx, LabConfig, LabRuntime, Outcome, RegionId, Scope, TaskId,
+};
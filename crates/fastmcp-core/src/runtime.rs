@@ -1,13 +1,24 @@
 //! Minimal runtime helpers for FastMCP.
 //!
 //! This module provides a small `block_on` utility used by macros to
-//! execute async handlers in a sync context without adding new deps.
+//! execute async handlers in a sync context without adding new deps, and
+//! the timer behind [`McpContext::sleep`](crate::McpContext::sleep) and
+//! handler timeouts.
 
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 use std::future::Future;
-use std::sync::OnceLock;
+use std::sync::{Condvar, Mutex, OnceLock, PoisonError};
+use std::task::Waker;
+use std::time::{Duration, Instant};
 
 use asupersync::runtime::RuntimeBuilder;
 
+use crate::{McpError, McpResult};
+
+/// How often waits that cancellation cannot wake check for it.
+pub(crate) const CANCEL_CHECK_INTERVAL: Duration = Duration::from_millis(10);
+
 static RUNTIME: OnceLock<asupersync::runtime::Runtime> = OnceLock::new();
 
 /// Blocks the current thread on the provided future.
@@ -22,3 +33,135 @@ pub fn block_on<F: Future>(future: F) -> F::Output {
 
     runtime.block_on(future)
 }
+
+/// Pending timer actions, earliest first.
+struct Timer {
+    wakeups: Mutex<BinaryHeap<Reverse<Wakeup>>>,
+    changed: Condvar,
+}
+
+/// An action to run once `at` has passed.
+struct Wakeup {
+    at: Instant,
+    seq: u64,
+    action: Box<dyn FnOnce() + Send>,
+}
+
+impl PartialEq for Wakeup {
+    fn eq(&self, other: &Self) -> bool {
+        (self.at, self.seq) == (other.at, other.seq)
+    }
+}
+
+impl Eq for Wakeup {}
+
+impl PartialOrd for Wakeup {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Wakeup {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.at, self.seq).cmp(&(other.at, other.seq))
+    }
+}
+
+/// The timer, or `None` if its thread could not be started.
+static TIMER: OnceLock<Option<Timer>> = OnceLock::new();
+
+/// Runs `action` on the timer thread once `at` has passed.
+///
+/// Actions are served by one lazily started background thread, so futures
+/// can wait on time without blocking the runtime thread that polls them.
+/// Actions must be quick; they delay every action due after them.
+///
+/// # Errors
+///
+/// Returns an internal error if the timer thread could not be started.
+pub(crate) fn run_at(at: Instant, action: impl FnOnce() + Send + 'static) -> McpResult<()> {
+    static SEQ: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+    let timer = TIMER.get_or_init(|| {
+        let spawned = std::thread::Builder::new()
+            .name("fastmcp-timer".to_string())
+            .spawn(run_timer);
+        match spawned {
+            Ok(_) => Some(Timer {
+                wakeups: Mutex::new(BinaryHeap::new()),
+                changed: Condvar::new(),
+            }),
+            Err(e) => {
+                crate::logging::error!(
+                    target: crate::logging::targets::SERVER,
+                    "Failed to start the timer thread: {}",
+                    e
+                );
+                None
+            }
+        }
+    });
+    let Some(timer) = timer else {
+        return Err(McpError::internal_error("timer thread unavailable"));
+    };
+    let seq = SEQ.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    timer
+        .wakeups
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(Reverse(Wakeup {
+            at,
+            seq,
+            action: Box::new(action),
+        }));
+    timer.changed.notify_one();
+    Ok(())
+}
+
+/// Wakes `waker` once `at` has passed.
+///
+/// # Errors
+///
+/// Returns an internal error if the timer thread could not be started.
+pub(crate) fn wake_at(at: Instant, waker: Waker) -> McpResult<()> {
+    run_at(at, move || waker.wake())
+}
+
+fn run_timer() {
+    let timer = loop {
+        match TIMER.get() {
+            Some(Some(timer)) => break timer,
+            _ => std::thread::yield_now(),
+        }
+    };
+    let mut wakeups = timer.wakeups.lock().unwrap_or_else(PoisonError::into_inner);
+    loop {
+        let now = Instant::now();
+        let mut due = Vec::new();
+        while wakeups.peek().is_some_and(|next| next.0.at <= now) {
+            if let Some(Reverse(wakeup)) = wakeups.pop() {
+                due.push(wakeup.action);
+            }
+        }
+        if !due.is_empty() {
+            drop(wakeups);
+            due.into_iter().for_each(|action| action());
+            wakeups = timer.wakeups.lock().unwrap_or_else(PoisonError::into_inner);
+            continue;
+        }
+
+        wakeups = match wakeups.peek().map(|next| next.0.at) {
+            Some(at) => {
+                timer
+                    .changed
+                    .wait_timeout(wakeups, at.saturating_duration_since(now))
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .0
+            }
+            None => timer
+                .changed
+                .wait(wakeups)
+                .unwrap_or_else(PoisonError::into_inner),
+        };
+    }
+}
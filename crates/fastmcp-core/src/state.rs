@@ -3,6 +3,8 @@
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
+use crate::logging::{targets, warn};
+
 /// Thread-safe session state container for per-session key-value storage.
 ///
 /// This allows handlers to store and retrieve state that persists across
@@ -121,6 +123,49 @@ impl SessionState {
             guard.clear();
         }
     }
+
+    /// Returns a snapshot of all entries as a JSON object.
+    ///
+    /// Useful for debugging, or for persisting a session and restoring it later
+    /// with [`import`](Self::import). The snapshot is taken under the lock, so
+    /// concurrent writers never produce a partial view. Values are stored as
+    /// JSON, so every entry is included, internal `fastmcp.*` keys such as the
+    /// stored [`AuthContext`](crate::AuthContext) too: treat the snapshot as
+    /// sensitive.
+    #[must_use]
+    pub fn export(&self) -> serde_json::Value {
+        let Ok(guard) = self.inner.lock() else {
+            warn!(target: targets::SESSION, "Session state lock poisoned; exporting empty snapshot");
+            return serde_json::Value::Object(serde_json::Map::new());
+        };
+        serde_json::Value::Object(
+            guard
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        )
+    }
+
+    /// Restores entries from a snapshot produced by [`export`](Self::export).
+    ///
+    /// Entries are merged into the current state, overwriting existing keys.
+    /// Returns the number of entries imported; a snapshot that is not a JSON
+    /// object is skipped with a warning.
+    pub fn import(&self, snapshot: serde_json::Value) -> usize {
+        let serde_json::Value::Object(entries) = snapshot else {
+            warn!(
+                target: targets::SESSION,
+                "Ignoring session state snapshot: expected a JSON object"
+            );
+            return 0;
+        };
+        let Ok(mut guard) = self.inner.lock() else {
+            return 0;
+        };
+        let count = entries.len();
+        guard.extend(entries);
+        count
+    }
 }
 
 // ============================================================================
@@ -308,6 +353,66 @@ mod tests {
         assert!(state.contains("key2"));
     }
 
+    #[test]
+    fn test_session_state_export_import_roundtrip() {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Cursor {
+            page: u32,
+            filter: Option<String>,
+        }
+
+        let state = SessionState::new();
+        state.set("name", "Alice");
+        state.set("count", 42_u64);
+        state.set("tags", vec!["a", "b"]);
+        state.set(
+            "cursor",
+            Cursor {
+                page: 3,
+                filter: Some("open".to_string()),
+            },
+        );
+
+        let snapshot = state.export();
+        // The snapshot is plain JSON and survives a text round-trip
+        let snapshot: serde_json::Value =
+            serde_json::from_str(&snapshot.to_string()).expect("reparse");
+
+        let restored = SessionState::new();
+        assert_eq!(restored.import(snapshot), 4);
+        assert_eq!(restored.get::<String>("name").as_deref(), Some("Alice"));
+        assert_eq!(restored.get::<u64>("count"), Some(42));
+        assert_eq!(
+            restored.get::<Vec<String>>("tags"),
+            Some(vec!["a".to_string(), "b".to_string()])
+        );
+        assert_eq!(
+            restored.get::<Cursor>("cursor"),
+            Some(Cursor {
+                page: 3,
+                filter: Some("open".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_session_state_import_merges_and_rejects_non_objects() {
+        let state = SessionState::new();
+        state.set("keep", 1);
+        state.set("overwrite", 1);
+
+        assert_eq!(
+            state.import(serde_json::json!({"overwrite": 2, "new": 3})),
+            2
+        );
+        assert_eq!(state.get::<i32>("keep"), Some(1));
+        assert_eq!(state.get::<i32>("overwrite"), Some(2));
+        assert_eq!(state.get::<i32>("new"), Some(3));
+
+        assert_eq!(state.import(serde_json::json!([1, 2, 3])), 0);
+        assert_eq!(state.len(), 3);
+    }
+
     // ========================================================================
     // Dynamic Enable/Disable Tests
     // ========================================================================
@@ -0,0 +1,86 @@
+//! Canonical JSON serialization.
+//!
+//! Canonical output has object keys sorted (recursively, by byte order) and
+//! no insignificant whitespace, so two logically equal messages always
+//! serialize to the same bytes. Servers use it for cache keys and, when asked,
+//! on the wire so responses can be diffed or hashed.
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Serializes a JSON value with object keys sorted, recursively.
+///
+/// Two logically equal values always produce the same string, regardless of
+/// the key order they were built or received with.
+#[must_use]
+pub fn canonical_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+/// Serializes any value to canonical JSON bytes.
+///
+/// Struct fields are sorted like object keys, so the field order of the Rust
+/// type does not leak into the output.
+///
+/// # Errors
+///
+/// Returns an error if `value` cannot be represented as JSON.
+pub fn to_canonical_vec<T: Serialize + ?Sized>(value: &T) -> serde_json::Result<Vec<u8>> {
+    Ok(canonical_json(&serde_json::to_value(value)?).into_bytes())
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut fields: Vec<_> = map.iter().collect();
+            fields.sort_by(|a, b| a.0.cmp(b.0));
+            out.push('{');
+            for (i, (key, field)) in fields.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(field, out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{JsonRpcResponse, RequestId};
+
+    #[test]
+    fn canonical_json_sorts_nested_keys() {
+        let value = serde_json::json!({"b": [{"z": 1, "a": 2}], "a": {"d": null, "c": "x"}});
+        assert_eq!(
+            canonical_json(&value),
+            r#"{"a":{"c":"x","d":null},"b":[{"a":2,"z":1}]}"#
+        );
+    }
+
+    #[test]
+    fn to_canonical_vec_sorts_struct_fields() {
+        let response = JsonRpcResponse::success(RequestId::Number(1), serde_json::json!({}));
+        let default = serde_json::to_string(&response).unwrap();
+        let canonical = String::from_utf8(to_canonical_vec(&response).unwrap()).unwrap();
+        assert!(default.starts_with(r#"{"jsonrpc""#));
+        assert_eq!(canonical, r#"{"id":1,"jsonrpc":"2.0","result":{}}"#);
+    }
+}
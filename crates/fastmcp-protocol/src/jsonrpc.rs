@@ -30,7 +30,10 @@ where
 }
 
 /// JSON-RPC request ID.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+///
+/// IDs order numbers before strings, numbers numerically and strings
+/// lexicographically.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
 #[serde(untagged)]
 pub enum RequestId {
     /// Integer ID.
@@ -39,6 +42,29 @@ pub enum RequestId {
     String(String),
 }
 
+impl<'de> Deserialize<'de> for RequestId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        // Decode through `Value` instead of `#[serde(untagged)]`: untagged
+        // enums cannot read numbers buffered under serde_json's
+        // `arbitrary_precision` feature, which `Value` understands.
+        match Value::deserialize(deserializer)? {
+            Value::Number(n) => n
+                .as_i64()
+                .map(RequestId::Number)
+                .ok_or_else(|| D::Error::custom(format!("request id out of range: {n}"))),
+            Value::String(s) => Ok(RequestId::String(s)),
+            other => Err(D::Error::custom(format!(
+                "request id must be a number or string, got {other}"
+            ))),
+        }
+    }
+}
+
 impl From<i64> for RequestId {
     fn from(id: i64) -> Self {
         RequestId::Number(id)
@@ -137,6 +163,16 @@ impl From<fastmcp_core::McpError> for JsonRpcError {
     }
 }
 
+impl From<JsonRpcError> for fastmcp_core::McpError {
+    fn from(err: JsonRpcError) -> Self {
+        Self {
+            code: err.code.into(),
+            message: err.message,
+            data: err.data,
+        }
+    }
+}
+
 /// JSON-RPC 2.0 response.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JsonRpcResponse {
@@ -231,6 +267,24 @@ mod tests {
         assert_eq!(id, RequestId::String("abc".to_string()));
     }
 
+    #[test]
+    fn request_id_rejects_non_integer_values() {
+        assert!(serde_json::from_value::<RequestId>(json!(true)).is_err());
+        assert!(serde_json::from_value::<RequestId>(json!(1.5)).is_err());
+        assert!(serde_json::from_value::<RequestId>(json!(u64::MAX)).is_err());
+    }
+
+    #[test]
+    fn request_id_inside_untagged_message() {
+        let msg: JsonRpcMessage =
+            serde_json::from_str(r#"{"jsonrpc":"2.0","id":5,"method":"ping"}"#)
+                .expect("deserialize");
+        match msg {
+            JsonRpcMessage::Request(req) => assert_eq!(req.id, Some(RequestId::Number(5))),
+            JsonRpcMessage::Response(_) => panic!("expected request"),
+        }
+    }
+
     #[test]
     fn request_id_from_i64() {
         let id: RequestId = 7i64.into();
@@ -362,6 +416,19 @@ mod tests {
         assert!(value.get("data").is_none());
     }
 
+    #[test]
+    fn jsonrpc_error_roundtrips_application_code() {
+        let original = fastmcp_core::McpError::with_code(4001, "Quota exceeded");
+        let wire = serde_json::to_value(JsonRpcError::from(original)).expect("serialize");
+        assert_eq!(wire["code"], 4001);
+
+        let parsed: JsonRpcError = serde_json::from_value(wire).expect("deserialize");
+        let error = fastmcp_core::McpError::from(parsed);
+        assert_eq!(error.code, fastmcp_core::McpErrorCode::Custom(4001));
+        assert!(error.code.is_application());
+        assert_eq!(error.message, "Quota exceeded");
+    }
+
     #[test]
     fn jsonrpc_error_with_data() {
         let error = JsonRpcError {
@@ -22,14 +22,16 @@
 #![forbid(unsafe_code)]
 #![allow(dead_code)]
 
+mod canonical;
 mod jsonrpc;
 mod messages;
 pub mod schema;
 mod types;
 
+pub use canonical::{canonical_json, to_canonical_vec};
 pub use jsonrpc::{
     JSONRPC_VERSION, JsonRpcError, JsonRpcMessage, JsonRpcRequest, JsonRpcResponse, RequestId,
 };
 pub use messages::*;
-pub use schema::{ValidationError, ValidationResult, validate, validate_strict};
+pub use schema::{ValidationError, ValidationResult, coerce_to_schema, validate, validate_strict};
 pub use types::*;
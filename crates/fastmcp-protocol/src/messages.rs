@@ -59,6 +59,26 @@ pub struct RequestMeta {
     /// Progress token for receiving progress notifications.
     #[serde(rename = "progressToken", skip_serializing_if = "Option::is_none")]
     pub progress_token: Option<ProgressToken>,
+    /// Preferred representation of a `tools/call` result.
+    #[serde(
+        rename = "resultFormat",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub result_format: Option<ResultFormat>,
+}
+
+/// How a client wants structured tool results represented.
+///
+/// A tool returns structured content when its only content item is text
+/// holding a JSON object or array.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResultFormat {
+    /// Pretty-printed JSON text, for text-only clients.
+    Text,
+    /// The JSON value in `structuredContent`, alongside the text content.
+    Json,
 }
 
 // ============================================================================
@@ -98,6 +118,17 @@ pub struct InitializeResult {
 // Tools
 // ============================================================================
 
+/// How `includeTags` matches a component's tags.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagMatch {
+    /// The component must have every listed tag.
+    #[default]
+    All,
+    /// The component must have at least one listed tag.
+    Any,
+}
+
 /// tools/list request params.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ListToolsParams {
@@ -118,6 +149,9 @@ pub struct ListToolsParams {
         skip_serializing_if = "Option::is_none"
     )]
     pub exclude_tags: Option<Vec<String>>,
+    /// How `include_tags` matches; defaults to [`TagMatch::All`].
+    #[serde(rename = "tagMatch", default, skip_serializing_if = "Option::is_none")]
+    pub tag_match: Option<TagMatch>,
 }
 
 /// tools/list response result.
@@ -155,6 +189,18 @@ pub struct CallToolResult {
         skip_serializing_if = "std::ops::Not::not"
     )]
     pub is_error: bool,
+    /// Structured result, set when the client asked for
+    /// [`ResultFormat::Json`].
+    #[serde(
+        rename = "structuredContent",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub structured_content: Option<serde_json::Value>,
+    /// Result metadata set by the handler, or by the server (e.g.
+    /// `{"cached": true}` for a cache hit).
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<serde_json::Value>,
 }
 
 // ============================================================================
@@ -228,16 +274,102 @@ pub struct ListResourceTemplatesResult {
 pub struct ReadResourceParams {
     /// Resource URI to read.
     pub uri: String,
+    /// Byte range to read instead of the whole resource.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range: Option<ResourceRange>,
+    /// ETags the client already holds, comma-separated (`*` matches any).
+    ///
+    /// If every returned content item carries one of these ETags, the server
+    /// answers with an empty result flagged `notModified`.
+    #[serde(
+        rename = "ifNoneMatch",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub if_none_match: Option<String>,
+    /// RFC 3339 time of the client's copy.
+    ///
+    /// If every returned content item was last modified at or before this
+    /// time, the server answers not modified. Ignored when `if_none_match`
+    /// is set.
+    #[serde(
+        rename = "ifModifiedSince",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub if_modified_since: Option<String>,
     /// Request metadata (progress token, etc.).
     #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
     pub meta: Option<RequestMeta>,
 }
 
+/// Byte range for a partial `resources/read`.
+///
+/// Offsets refer to the raw bytes of the resource (UTF-8 bytes for text,
+/// decoded bytes for blobs).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceRange {
+    /// Byte offset to start reading from.
+    pub offset: u64,
+    /// Maximum number of bytes to read (`None` = read to the end).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length: Option<u64>,
+}
+
+impl ResourceRange {
+    /// Creates a range starting at `offset` with an optional `length`.
+    #[must_use]
+    pub fn new(offset: u64, length: Option<u64>) -> Self {
+        Self { offset, length }
+    }
+
+    /// Resolves this range against a resource of `total` bytes.
+    ///
+    /// Returns the `(start, end)` byte positions, clamped to `total`.
+    #[must_use]
+    pub fn resolve(&self, total: u64) -> (u64, u64) {
+        let start = self.offset.min(total);
+        let end = match self.length {
+            Some(len) => start.saturating_add(len).min(total),
+            None => total,
+        };
+        (start, end)
+    }
+}
+
 /// resources/read response result.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReadResourceResult {
     /// Resource contents.
     pub contents: Vec<ResourceContent>,
+    /// Set when a conditional read matched: the client's copy is current and
+    /// `contents` is empty.
+    #[serde(
+        rename = "notModified",
+        default,
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub not_modified: bool,
+}
+
+impl ReadResourceResult {
+    /// Creates a result carrying the given contents.
+    #[must_use]
+    pub fn new(contents: Vec<ResourceContent>) -> Self {
+        Self {
+            contents,
+            not_modified: false,
+        }
+    }
+
+    /// Creates the empty result for a conditional read whose content is unchanged.
+    #[must_use]
+    pub fn unchanged() -> Self {
+        Self {
+            contents: Vec::new(),
+            not_modified: true,
+        }
+    }
 }
 
 /// resources/subscribe request params.
@@ -423,6 +555,23 @@ pub struct ResourceUpdatedNotificationParams {
     pub uri: String,
 }
 
+/// Tool content chunk notification params (`notifications/tools/content`).
+///
+/// Sent from server to client while a `tools/call` is still running, to
+/// deliver part of the result early. Only sent to clients that advertise
+/// the [`STREAMING_CONTENT_CAPABILITY`](crate::STREAMING_CONTENT_CAPABILITY).
+/// The final `tools/call` response follows the last chunk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolContentChunkParams {
+    /// ID of the `tools/call` request this chunk belongs to.
+    #[serde(rename = "requestId")]
+    pub request_id: RequestId,
+    /// Zero-based position of this chunk in the stream.
+    pub sequence: u64,
+    /// Content items carried by this chunk.
+    pub content: Vec<Content>,
+}
+
 /// Log message notification params.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LogMessageParams {
@@ -1127,11 +1276,27 @@ mod tests {
     fn request_meta_with_token() {
         let meta = RequestMeta {
             progress_token: Some(ProgressToken::String("pt-1".to_string())),
+            result_format: None,
         };
         let value = serde_json::to_value(&meta).expect("serialize");
         assert_eq!(value["progressToken"], "pt-1");
     }
 
+    #[test]
+    fn request_meta_result_format() {
+        let meta: RequestMeta =
+            serde_json::from_value(serde_json::json!({"resultFormat": "json"})).expect("parse");
+        assert_eq!(meta.result_format, Some(ResultFormat::Json));
+        assert!(meta.progress_token.is_none());
+
+        let meta = RequestMeta {
+            progress_token: None,
+            result_format: Some(ResultFormat::Text),
+        };
+        let value = serde_json::to_value(&meta).expect("serialize");
+        assert_eq!(value, serde_json::json!({"resultFormat": "text"}));
+    }
+
     // ========================================================================
     // Initialize Tests
     // ========================================================================
@@ -1213,6 +1378,7 @@ mod tests {
             cursor: Some("next-page".to_string()),
             include_tags: None,
             exclude_tags: None,
+            tag_match: None,
         };
         let value = serde_json::to_value(&params).expect("serialize");
         assert_eq!(value["cursor"], "next-page");
@@ -1224,10 +1390,12 @@ mod tests {
             cursor: None,
             include_tags: Some(vec!["api".to_string(), "v2".to_string()]),
             exclude_tags: Some(vec!["deprecated".to_string()]),
+            tag_match: Some(TagMatch::Any),
         };
         let value = serde_json::to_value(&params).expect("serialize");
         assert_eq!(value["includeTags"], serde_json::json!(["api", "v2"]));
         assert_eq!(value["excludeTags"], serde_json::json!(["deprecated"]));
+        assert_eq!(value["tagMatch"], "any");
     }
 
     // ========================================================================
@@ -1254,6 +1422,7 @@ mod tests {
             arguments: Some(serde_json::json!({"a": 1, "b": 2})),
             meta: Some(RequestMeta {
                 progress_token: Some(ProgressToken::Number(100)),
+                result_format: None,
             }),
         };
         let value = serde_json::to_value(&params).expect("serialize");
@@ -1273,12 +1442,31 @@ mod tests {
                 text: "42".to_string(),
             }],
             is_error: false,
+            structured_content: None,
+            meta: None,
         };
         let value = serde_json::to_value(&result).expect("serialize");
         assert_eq!(value["content"][0]["type"], "text");
         assert_eq!(value["content"][0]["text"], "42");
-        // is_error=false should be omitted
+        // is_error=false and absent meta should be omitted
         assert!(value.get("isError").is_none());
+        assert!(value.get("_meta").is_none());
+    }
+
+    #[test]
+    fn call_tool_result_meta_roundtrip() {
+        let result = CallToolResult {
+            content: vec![],
+            is_error: false,
+            structured_content: None,
+            meta: Some(serde_json::json!({"cached": true})),
+        };
+        let value = serde_json::to_value(&result).expect("serialize");
+        assert_eq!(value["_meta"]["cached"], true);
+        assert!(value.get("meta").is_none());
+
+        let parsed: CallToolResult = serde_json::from_value(value).expect("deserialize");
+        assert_eq!(parsed.meta, Some(serde_json::json!({"cached": true})));
     }
 
     #[test]
@@ -1288,6 +1476,8 @@ mod tests {
                 text: "Something went wrong".to_string(),
             }],
             is_error: true,
+            structured_content: None,
+            meta: None,
         };
         let value = serde_json::to_value(&result).expect("serialize");
         assert_eq!(value["isError"], true);
@@ -1323,19 +1513,27 @@ mod tests {
     fn read_resource_params_serialization() {
         let params = ReadResourceParams {
             uri: "file://config.json".to_string(),
+            range: None,
+            if_none_match: None,
+            if_modified_since: None,
             meta: None,
         };
         let value = serde_json::to_value(&params).expect("serialize");
         assert_eq!(value["uri"], "file://config.json");
         assert!(value.get("_meta").is_none());
+        assert!(value.get("range").is_none());
     }
 
     #[test]
     fn read_resource_params_with_meta() {
         let params = ReadResourceParams {
             uri: "file://data.csv".to_string(),
+            range: None,
+            if_none_match: None,
+            if_modified_since: None,
             meta: Some(RequestMeta {
                 progress_token: Some(ProgressToken::String("pt-read".to_string())),
+                result_format: None,
             }),
         };
         let value = serde_json::to_value(&params).expect("serialize");
@@ -1343,6 +1541,28 @@ mod tests {
         assert_eq!(value["_meta"]["progressToken"], "pt-read");
     }
 
+    #[test]
+    fn read_resource_params_with_range() {
+        let json = serde_json::json!({
+            "uri": "file://big.log",
+            "range": {"offset": 1024, "length": 512}
+        });
+        let params: ReadResourceParams = serde_json::from_value(json).expect("deserialize");
+        assert_eq!(params.range, Some(ResourceRange::new(1024, Some(512))));
+
+        let value = serde_json::to_value(&params).expect("serialize");
+        assert_eq!(value["range"]["offset"], 1024);
+        assert_eq!(value["range"]["length"], 512);
+    }
+
+    #[test]
+    fn resource_range_resolve_clamps_to_total() {
+        assert_eq!(ResourceRange::new(2, Some(3)).resolve(10), (2, 5));
+        assert_eq!(ResourceRange::new(8, Some(5)).resolve(10), (8, 10));
+        assert_eq!(ResourceRange::new(4, None).resolve(10), (4, 10));
+        assert_eq!(ResourceRange::new(20, Some(5)).resolve(10), (10, 10));
+    }
+
     // ========================================================================
     // ReadResourceResult Tests
     // ========================================================================
@@ -1355,6 +1575,8 @@ mod tests {
                 mime_type: Some("text/plain".to_string()),
                 text: Some("Hello!".to_string()),
                 blob: None,
+                etag: None,
+                last_modified: None,
             }],
         };
         let value = serde_json::to_value(&result).expect("serialize");
@@ -1672,6 +1894,7 @@ mod tests {
                 icon: None,
                 version: None,
                 tags: vec![],
+                parameters: vec![],
             }],
         };
 
@@ -2217,6 +2440,7 @@ mod tests {
             icon: None,
             version: None,
             tags: vec![],
+            parameters: vec![],
         };
         let json = serde_json::to_value(&template).expect("serialize");
         assert!(json.get("version").is_none());
@@ -2230,6 +2454,7 @@ mod tests {
             icon: None,
             version: Some("3.0.0".to_string()),
             tags: vec![],
+            parameters: vec![],
         };
         let json = serde_json::to_value(&template).expect("serialize");
         assert_eq!(json["version"], "3.0.0");
@@ -2389,6 +2614,7 @@ mod tests {
             icon: None,
             version: None,
             tags: vec![],
+            parameters: vec![],
         };
         let json = serde_json::to_value(&template).expect("serialize");
         assert!(
@@ -2405,6 +2631,7 @@ mod tests {
             icon: None,
             version: None,
             tags: vec!["filesystem".to_string()],
+            parameters: vec![],
         };
         let json = serde_json::to_value(&template).expect("serialize");
         assert_eq!(json["tags"], serde_json::json!(["filesystem"]));
@@ -2507,10 +2734,11 @@ mod tests {
         };
         let json = serde_json::to_value(&tool).expect("serialize");
         let annotations = json.get("annotations").expect("annotations field");
-        assert_eq!(annotations["destructive"], true);
-        assert_eq!(annotations["idempotent"], false);
-        assert_eq!(annotations["readOnly"], false);
+        assert_eq!(annotations["destructiveHint"], true);
+        assert_eq!(annotations["idempotentHint"], false);
+        assert_eq!(annotations["readOnlyHint"], false);
         assert!(annotations.get("openWorldHint").is_none());
+        assert!(annotations.get("title").is_none());
 
         // Tool with read_only annotation
         let tool = Tool {
@@ -2525,8 +2753,8 @@ mod tests {
         };
         let json = serde_json::to_value(&tool).expect("serialize");
         let annotations = json.get("annotations").expect("annotations field");
-        assert_eq!(annotations["readOnly"], true);
-        assert!(annotations.get("destructive").is_none());
+        assert_eq!(annotations["readOnlyHint"], true);
+        assert!(annotations.get("destructiveHint").is_none());
     }
 
     #[test]
@@ -2546,21 +2774,20 @@ mod tests {
             "name": "delete_tool",
             "inputSchema": {"type": "object"},
             "annotations": {
-                "destructive": true,
-                "idempotent": false,
-                "readOnly": false,
-                "openWorldHint": "May delete any file"
+                "title": "Delete Tool",
+                "destructiveHint": true,
+                "idempotentHint": false,
+                "readOnlyHint": false,
+                "openWorldHint": false
             }
         });
         let tool: Tool = serde_json::from_value(json).expect("deserialize");
         let annotations = tool.annotations.expect("annotations present");
-        assert_eq!(annotations.destructive, Some(true));
-        assert_eq!(annotations.idempotent, Some(false));
-        assert_eq!(annotations.read_only, Some(false));
-        assert_eq!(
-            annotations.open_world_hint,
-            Some("May delete any file".to_string())
-        );
+        assert_eq!(annotations.title.as_deref(), Some("Delete Tool"));
+        assert_eq!(annotations.destructive_hint, Some(true));
+        assert_eq!(annotations.idempotent_hint, Some(false));
+        assert_eq!(annotations.read_only_hint, Some(false));
+        assert_eq!(annotations.open_world_hint, Some(false));
     }
 
     #[test]
@@ -2571,16 +2798,20 @@ mod tests {
             .destructive(true)
             .idempotent(true)
             .read_only(false)
-            .open_world_hint("Handles unknown inputs gracefully");
-
-        assert_eq!(annotations.destructive, Some(true));
-        assert_eq!(annotations.idempotent, Some(true));
-        assert_eq!(annotations.read_only, Some(false));
-        assert_eq!(
-            annotations.open_world_hint,
-            Some("Handles unknown inputs gracefully".to_string())
-        );
+            .open_world(true)
+            .title("Sync Files");
+
+        assert_eq!(annotations.destructive_hint, Some(true));
+        assert_eq!(annotations.idempotent_hint, Some(true));
+        assert_eq!(annotations.read_only_hint, Some(false));
+        assert_eq!(annotations.open_world_hint, Some(true));
+        assert_eq!(annotations.title.as_deref(), Some("Sync Files"));
         assert!(!annotations.is_empty());
+        assert!(annotations.is_destructive());
+
+        // readOnlyHint overrides destructiveHint; unset destructiveHint defaults to true
+        assert!(!ToolAnnotations::new().read_only(true).is_destructive());
+        assert!(ToolAnnotations::new().is_destructive());
 
         // Empty annotations
         let empty = ToolAnnotations::new();
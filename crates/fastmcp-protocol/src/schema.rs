@@ -19,6 +19,13 @@ use std::fmt;
 pub struct ValidationError {
     /// Path to the invalid value (e.g., `root.foo.bar` or `root[0]`).
     pub path: String,
+    /// RFC 6901 JSON pointer to the invalid value (e.g., `/foo/bar` or `/0`).
+    ///
+    /// For `required` failures this points at the missing property rather
+    /// than the object that lacks it.
+    pub pointer: String,
+    /// Schema keyword that failed (e.g., `required`, `type`, `maxLength`).
+    pub keyword: &'static str,
     /// Description of what went wrong.
     pub message: String,
 }
@@ -69,7 +76,7 @@ pub type ValidationResult = Result<(), Vec<ValidationError>>;
 /// ```
 pub fn validate(schema: &Value, value: &Value) -> ValidationResult {
     let mut errors = Vec::new();
-    validate_internal(schema, value, "root", &mut errors);
+    validate_internal(schema, value, "root", "", &mut errors);
 
     if errors.is_empty() {
         Ok(())
@@ -120,6 +127,250 @@ pub fn validate_strict(schema: &Value, value: &Value) -> ValidationResult {
     validate(&strict_schema, value)
 }
 
+/// Checks that `schema` is itself a well-formed schema.
+///
+/// Every subschema (under `properties`, `additionalProperties`, `items` and
+/// `prefixItems`) is validated with [`validate_strict`] against a meta-schema
+/// describing the keywords this module understands, so a malformed schema
+/// can be caught when a tool is registered rather than when it is called.
+/// Keywords outside that set, such as `description` or `format`, are
+/// allowed. Error paths and pointers refer to locations inside `schema`.
+///
+/// # Example
+///
+/// ```
+/// use fastmcp_protocol::schema::check_schema;
+/// use serde_json::json;
+///
+/// assert!(check_schema(&json!({"type": "object"})).is_ok());
+///
+/// let errors = check_schema(&json!({"type": 123})).unwrap_err();
+/// assert_eq!(errors[0].pointer, "/type");
+/// ```
+pub fn check_schema(schema: &Value) -> ValidationResult {
+    let meta = meta_schema();
+    let mut errors = Vec::new();
+    check_subschema(&meta, schema, "root", "", &mut errors);
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Names accepted by the `type` keyword.
+const TYPE_NAMES: [&str; 7] = [
+    "string", "number", "integer", "boolean", "object", "array", "null",
+];
+
+/// Meta-schema for a single schema node, without recursing into subschemas.
+fn meta_schema() -> Value {
+    let subschema = serde_json::json!({"type": ["object", "boolean"]});
+    let count = serde_json::json!({"type": "integer", "minimum": 0});
+    let number = serde_json::json!({"type": "number"});
+    serde_json::json!({
+        "type": ["object", "boolean"],
+        "additionalProperties": true,
+        "properties": {
+            "type": {"type": ["string", "array"], "items": {"enum": TYPE_NAMES}},
+            "properties": {"type": "object", "additionalProperties": subschema},
+            "additionalProperties": subschema,
+            "items": {"type": ["object", "boolean", "array"], "items": subschema},
+            "prefixItems": {"type": "array", "items": subschema},
+            "required": {"type": "array", "items": {"type": "string"}},
+            "enum": {"type": "array"},
+            "minLength": count,
+            "maxLength": count,
+            "minItems": count,
+            "maxItems": count,
+            "minProperties": count,
+            "maxProperties": count,
+            "uniqueItems": {"type": "boolean"},
+            "minimum": number,
+            "maximum": number,
+            "exclusiveMinimum": number,
+            "exclusiveMaximum": number,
+            "multipleOf": {"type": "number", "exclusiveMinimum": 0}
+        }
+    })
+}
+
+/// Checks one schema node against the meta-schema, then its subschemas.
+fn check_subschema(
+    meta: &Value,
+    schema: &Value,
+    path: &str,
+    pointer: &str,
+    errors: &mut Vec<ValidationError>,
+) {
+    if let Err(found) = validate_strict(meta, schema) {
+        errors.extend(found.into_iter().map(|e| ValidationError {
+            path: e.path.replacen("root", path, 1),
+            pointer: format!("{pointer}{}", e.pointer),
+            ..e
+        }));
+    }
+
+    let Some(obj) = schema.as_object() else {
+        return;
+    };
+
+    if let Some(Value::String(name)) = obj.get("type") {
+        if !TYPE_NAMES.contains(&name.as_str()) {
+            errors.push(ValidationError {
+                path: format!("{path}.type"),
+                pointer: format!("{pointer}/type"),
+                keyword: "enum",
+                message: format!("unknown type '{name}'"),
+            });
+        }
+    }
+
+    if let Some(Value::Object(props)) = obj.get("properties") {
+        for (name, sub) in props {
+            check_subschema(
+                meta,
+                sub,
+                &format!("{path}.properties.{name}"),
+                &format!("{pointer}/properties/{}", escape_pointer_token(name)),
+                errors,
+            );
+        }
+    }
+
+    if let Some(sub) = obj.get("additionalProperties") {
+        check_subschema(
+            meta,
+            sub,
+            &format!("{path}.additionalProperties"),
+            &format!("{pointer}/additionalProperties"),
+            errors,
+        );
+    }
+
+    for key in ["items", "prefixItems"] {
+        match obj.get(key) {
+            Some(Value::Array(subs)) => {
+                for (i, sub) in subs.iter().enumerate() {
+                    check_subschema(
+                        meta,
+                        sub,
+                        &format!("{path}.{key}[{i}]"),
+                        &format!("{pointer}/{key}/{i}"),
+                        errors,
+                    );
+                }
+            }
+            Some(sub) => check_subschema(
+                meta,
+                sub,
+                &format!("{path}.{key}"),
+                &format!("{pointer}/{key}"),
+                errors,
+            ),
+            None => {}
+        }
+    }
+}
+
+/// Coerces common scalar mismatches in `value` toward the types declared in `schema`.
+///
+/// This runs before validation for clients that send, for example, `"5"` where
+/// the schema expects an integer. Only unambiguous conversions are attempted:
+///
+/// - numeric strings to `integer` / `number`
+/// - `"true"` / `"false"` to `boolean`
+/// - a single non-array value to a one-element array
+///
+/// Schemas with union types (`"type": [...]`) are left alone, as are values
+/// that cannot be converted cleanly; those still fail validation afterwards.
+///
+/// # Example
+///
+/// ```
+/// use fastmcp_protocol::schema::coerce_to_schema;
+/// use serde_json::json;
+///
+/// let schema = json!({
+///     "type": "object",
+///     "properties": { "count": { "type": "integer" } }
+/// });
+///
+/// let coerced = coerce_to_schema(&schema, json!({ "count": "5" }));
+/// assert_eq!(coerced, json!({ "count": 5 }));
+/// ```
+#[must_use]
+pub fn coerce_to_schema(schema: &Value, value: Value) -> Value {
+    let Some(schema_obj) = schema.as_object() else {
+        return value;
+    };
+    let Some(type_name) = schema_obj.get("type").and_then(Value::as_str) else {
+        return value;
+    };
+
+    match (type_name, value) {
+        ("integer", Value::String(s)) => coerce_integer(&s).unwrap_or(Value::String(s)),
+        ("number", Value::String(s)) => coerce_number(&s).unwrap_or(Value::String(s)),
+        ("boolean", Value::String(s)) => match s.as_str() {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            _ => Value::String(s),
+        },
+        ("object", Value::Object(mut obj)) => {
+            if let Some(Value::Object(props)) = schema_obj.get("properties") {
+                for (key, prop_schema) in props {
+                    if let Some(prop) = obj.remove(key) {
+                        obj.insert(key.clone(), coerce_to_schema(prop_schema, prop));
+                    }
+                }
+            }
+            Value::Object(obj)
+        }
+        ("array", value) => {
+            let items = match value {
+                Value::Array(items) => items,
+                Value::Null => return Value::Null,
+                single => vec![single],
+            };
+            match schema_obj.get("items") {
+                Some(items_schema) if items_schema.is_object() => Value::Array(
+                    items
+                        .into_iter()
+                        .map(|item| coerce_to_schema(items_schema, item))
+                        .collect(),
+                ),
+                _ => Value::Array(items),
+            }
+        }
+        (_, value) => value,
+    }
+}
+
+/// Parses an integer string, rejecting anything with surrounding text or whitespace.
+fn coerce_integer(s: &str) -> Option<Value> {
+    if let Ok(n) = s.parse::<i64>() {
+        return Some(Value::from(n));
+    }
+    s.parse::<u64>().ok().map(Value::from)
+}
+
+/// Parses a finite numeric string, preferring an integer representation.
+fn coerce_number(s: &str) -> Option<Value> {
+    if let Some(n) = coerce_integer(s) {
+        return Some(n);
+    }
+    // Only accept plain decimal/exponent notation, not "inf" or "NaN".
+    if !s
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E'))
+    {
+        return None;
+    }
+    let n = s.parse::<f64>().ok().filter(|n| n.is_finite())?;
+    serde_json::Number::from_f64(n).map(Value::Number)
+}
+
 /// Recursively adds `additionalProperties: false` to all object schemas.
 fn make_strict_schema(schema: &Value) -> Value {
     match schema {
@@ -179,13 +430,26 @@ fn make_strict_schema(schema: &Value) -> Value {
     }
 }
 
+/// Escapes a property name for use as a JSON pointer reference token.
+fn escape_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
 /// Internal recursive validation function.
-fn validate_internal(schema: &Value, value: &Value, path: &str, errors: &mut Vec<ValidationError>) {
+fn validate_internal(
+    schema: &Value,
+    value: &Value,
+    path: &str,
+    pointer: &str,
+    errors: &mut Vec<ValidationError>,
+) {
     // Handle boolean schemas (true = accept all, false = reject all)
     if let Some(b) = schema.as_bool() {
         if !b {
             errors.push(ValidationError {
                 path: path.to_string(),
+                pointer: pointer.to_string(),
+                keyword: "false",
                 message: "schema rejects all values".to_string(),
             });
         }
@@ -207,6 +471,8 @@ fn validate_internal(schema: &Value, value: &Value, path: &str, errors: &mut Vec
                 .unwrap_or_else(|| "unknown".to_string());
             errors.push(ValidationError {
                 path: path.to_string(),
+                pointer: pointer.to_string(),
+                keyword: "type",
                 message: format!("expected type {expected}, got {}", json_type_name(value)),
             });
             return; // Type mismatch, skip further validation
@@ -219,6 +485,8 @@ fn validate_internal(schema: &Value, value: &Value, path: &str, errors: &mut Vec
             if !enum_arr.contains(value) {
                 errors.push(ValidationError {
                     path: path.to_string(),
+                    pointer: pointer.to_string(),
+                    keyword: "enum",
                     message: format!("value must be one of: {enum_arr:?}"),
                 });
             }
@@ -230,6 +498,8 @@ fn validate_internal(schema: &Value, value: &Value, path: &str, errors: &mut Vec
         if value != const_val {
             errors.push(ValidationError {
                 path: path.to_string(),
+                pointer: pointer.to_string(),
+                keyword: "const",
                 message: format!("value must equal {const_val}"),
             });
         }
@@ -238,16 +508,16 @@ fn validate_internal(schema: &Value, value: &Value, path: &str, errors: &mut Vec
     // Type-specific validation
     match value {
         Value::Object(obj) => {
-            validate_object(schema_obj, obj, path, errors);
+            validate_object(schema_obj, obj, path, pointer, errors);
         }
         Value::Array(arr) => {
-            validate_array(schema_obj, arr, path, errors);
+            validate_array(schema_obj, arr, path, pointer, errors);
         }
         Value::String(s) => {
-            validate_string(schema_obj, s, path, errors);
+            validate_string(schema_obj, s, path, pointer, errors);
         }
         Value::Number(n) => {
-            validate_number(schema_obj, n, path, errors);
+            validate_number(schema_obj, n, path, pointer, errors);
         }
         _ => {}
     }
@@ -270,7 +540,7 @@ fn matches_type(type_name: &str, value: &Value) -> bool {
     match type_name {
         "string" => value.is_string(),
         "number" => value.is_number(),
-        "integer" => value.is_i64() || value.is_u64(),
+        "integer" => value.as_number().is_some_and(is_integer),
         "boolean" => value.is_boolean(),
         "object" => value.is_object(),
         "array" => value.is_array(),
@@ -279,13 +549,26 @@ fn matches_type(type_name: &str, value: &Value) -> bool {
     }
 }
 
+/// Checks if a number is an integer.
+///
+/// Beyond `i64`/`u64` this only holds with `serde_json`'s arbitrary precision
+/// feature, where a number keeps its original digits.
+fn is_integer(n: &serde_json::Number) -> bool {
+    if n.is_i64() || n.is_u64() {
+        return true;
+    }
+    let text = n.to_string();
+    let digits = text.strip_prefix('-').unwrap_or(&text);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
 /// Returns the JSON type name for a value.
 fn json_type_name(value: &Value) -> &'static str {
     match value {
         Value::Null => "null",
         Value::Bool(_) => "boolean",
         Value::Number(n) => {
-            if n.is_i64() || n.is_u64() {
+            if is_integer(n) {
                 "integer"
             } else {
                 "number"
@@ -302,6 +585,7 @@ fn validate_object(
     schema: &serde_json::Map<String, Value>,
     obj: &serde_json::Map<String, Value>,
     path: &str,
+    pointer: &str,
     errors: &mut Vec<ValidationError>,
 ) {
     // Check required fields
@@ -311,6 +595,8 @@ fn validate_object(
                 if !obj.contains_key(req_name) {
                     errors.push(ValidationError {
                         path: path.to_string(),
+                        pointer: format!("{pointer}/{}", escape_pointer_token(req_name)),
+                        keyword: "required",
                         message: format!("missing required field: {req_name}"),
                     });
                 }
@@ -323,7 +609,8 @@ fn validate_object(
         for (key, value) in obj {
             if let Some(prop_schema) = properties.get(key) {
                 let prop_path = format!("{path}.{key}");
-                validate_internal(prop_schema, value, &prop_path, errors);
+                let prop_pointer = format!("{pointer}/{}", escape_pointer_token(key));
+                validate_internal(prop_schema, value, &prop_path, &prop_pointer, errors);
             }
         }
     }
@@ -341,12 +628,15 @@ fn validate_object(
                     Value::Bool(false) => {
                         errors.push(ValidationError {
                             path: path.to_string(),
+                            pointer: pointer.to_string(),
+                            keyword: "additionalProperties",
                             message: format!("additional property not allowed: {key}"),
                         });
                     }
                     Value::Object(_) => {
                         let prop_path = format!("{path}.{key}");
-                        validate_internal(additional, value, &prop_path, errors);
+                        let prop_pointer = format!("{pointer}/{}", escape_pointer_token(key));
+                        validate_internal(additional, value, &prop_path, &prop_pointer, errors);
                     }
                     _ => {}
                 }
@@ -362,6 +652,8 @@ fn validate_object(
         if (obj.len() as u64) < min {
             errors.push(ValidationError {
                 path: path.to_string(),
+                pointer: pointer.to_string(),
+                keyword: "minProperties",
                 message: format!("object must have at least {min} properties"),
             });
         }
@@ -373,6 +665,8 @@ fn validate_object(
         if (obj.len() as u64) > max {
             errors.push(ValidationError {
                 path: path.to_string(),
+                pointer: pointer.to_string(),
+                keyword: "maxProperties",
                 message: format!("object must have at most {max} properties"),
             });
         }
@@ -384,6 +678,7 @@ fn validate_array(
     schema: &serde_json::Map<String, Value>,
     arr: &[Value],
     path: &str,
+    pointer: &str,
     errors: &mut Vec<ValidationError>,
 ) {
     // Validate prefixItems (tuple validation)
@@ -393,7 +688,8 @@ fn validate_array(
         for (i, item_schema) in prefix_items.iter().enumerate() {
             if let Some(item) = arr.get(i) {
                 let item_path = format!("{path}[{i}]");
-                validate_internal(item_schema, item, &item_path, errors);
+                let item_pointer = format!("{pointer}/{i}");
+                validate_internal(item_schema, item, &item_path, &item_pointer, errors);
             }
         }
     }
@@ -406,7 +702,8 @@ fn validate_array(
                 for (i, item_schema) in items_arr.iter().enumerate() {
                     if let Some(item) = arr.get(i) {
                         let item_path = format!("{path}[{i}]");
-                        validate_internal(item_schema, item, &item_path, errors);
+                        let item_pointer = format!("{pointer}/{i}");
+                        validate_internal(item_schema, item, &item_path, &item_pointer, errors);
                     }
                 }
                 // In older drafts, 'additionalItems' controls the rest. We skip that for simplicity unless needed.
@@ -415,7 +712,8 @@ fn validate_array(
             // Validate items starting from where prefixItems left off
             for (i, item) in arr.iter().enumerate().skip(prefix_len) {
                 let item_path = format!("{path}[{i}]");
-                validate_internal(items_schema, item, &item_path, errors);
+                let item_pointer = format!("{pointer}/{i}");
+                validate_internal(items_schema, item, &item_path, &item_pointer, errors);
             }
         }
     }
@@ -425,6 +723,8 @@ fn validate_array(
         if (arr.len() as u64) < min {
             errors.push(ValidationError {
                 path: path.to_string(),
+                pointer: pointer.to_string(),
+                keyword: "minItems",
                 message: format!("array must have at least {min} items"),
             });
         }
@@ -433,6 +733,8 @@ fn validate_array(
         if (arr.len() as u64) > max {
             errors.push(ValidationError {
                 path: path.to_string(),
+                pointer: pointer.to_string(),
+                keyword: "maxItems",
                 message: format!("array must have at most {max} items"),
             });
         }
@@ -454,6 +756,8 @@ fn validate_array(
             if !seen.insert(key) {
                 errors.push(ValidationError {
                     path: format!("{path}[{i}]"),
+                    pointer: format!("{pointer}/{i}"),
+                    keyword: "uniqueItems",
                     message: "duplicate item in array".to_string(),
                 });
             }
@@ -466,6 +770,7 @@ fn validate_string(
     schema: &serde_json::Map<String, Value>,
     s: &str,
     path: &str,
+    pointer: &str,
     errors: &mut Vec<ValidationError>,
 ) {
     // Check minLength/maxLength
@@ -474,6 +779,8 @@ fn validate_string(
         if (len as u64) < min {
             errors.push(ValidationError {
                 path: path.to_string(),
+                pointer: pointer.to_string(),
+                keyword: "minLength",
                 message: format!("string must be at least {min} characters"),
             });
         }
@@ -482,6 +789,8 @@ fn validate_string(
         if (len as u64) > max {
             errors.push(ValidationError {
                 path: path.to_string(),
+                pointer: pointer.to_string(),
+                keyword: "maxLength",
                 message: format!("string must be at most {max} characters"),
             });
         }
@@ -496,6 +805,7 @@ fn validate_number(
     schema: &serde_json::Map<String, Value>,
     n: &serde_json::Number,
     path: &str,
+    pointer: &str,
     errors: &mut Vec<ValidationError>,
 ) {
     let val = n.as_f64().unwrap_or(0.0);
@@ -505,6 +815,8 @@ fn validate_number(
         if val < min {
             errors.push(ValidationError {
                 path: path.to_string(),
+                pointer: pointer.to_string(),
+                keyword: "minimum",
                 message: format!("value must be >= {min}"),
             });
         }
@@ -513,6 +825,8 @@ fn validate_number(
         if val > max {
             errors.push(ValidationError {
                 path: path.to_string(),
+                pointer: pointer.to_string(),
+                keyword: "maximum",
                 message: format!("value must be <= {max}"),
             });
         }
@@ -526,6 +840,8 @@ fn validate_number(
         if val <= min {
             errors.push(ValidationError {
                 path: path.to_string(),
+                pointer: pointer.to_string(),
+                keyword: "exclusiveMinimum",
                 message: format!("value must be > {min}"),
             });
         }
@@ -537,6 +853,8 @@ fn validate_number(
         if val >= max {
             errors.push(ValidationError {
                 path: path.to_string(),
+                pointer: pointer.to_string(),
+                keyword: "exclusiveMaximum",
                 message: format!("value must be < {max}"),
             });
         }
@@ -547,6 +865,8 @@ fn validate_number(
         if multiple != 0.0 && (val % multiple).abs() > f64::EPSILON {
             errors.push(ValidationError {
                 path: path.to_string(),
+                pointer: pointer.to_string(),
+                keyword: "multipleOf",
                 message: format!("value must be a multiple of {multiple}"),
             });
         }
@@ -558,6 +878,96 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn test_check_schema_accepts_well_formed_schemas() {
+        let schema = json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "description": "Search parameters",
+            "properties": {
+                "query": {"type": "string", "minLength": 1, "format": "text"},
+                "tags": {"type": "array", "items": {"type": "string"}},
+                "limit": {"type": ["integer", "null"], "minimum": 1},
+                "point": {"prefixItems": [{"type": "number"}, {"type": "number"}]}
+            },
+            "required": ["query"],
+            "additionalProperties": false
+        });
+        assert!(check_schema(&schema).is_ok());
+        assert!(check_schema(&json!(true)).is_ok());
+    }
+
+    #[test]
+    fn test_check_schema_reports_malformed_keywords() {
+        let errors = check_schema(&json!({"type": 123})).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].pointer, "/type");
+        assert_eq!(errors[0].keyword, "type");
+
+        let errors = check_schema(&json!({
+            "type": "object",
+            "properties": {
+                "a/b": {"type": "strng"},
+                "n": {"type": "integer", "maxLength": -1}
+            },
+            "required": "a/b"
+        }))
+        .unwrap_err();
+        let pointers: Vec<&str> = errors.iter().map(|e| e.pointer.as_str()).collect();
+        assert!(pointers.contains(&"/required"), "{pointers:?}");
+        assert!(pointers.contains(&"/properties/a~1b/type"), "{pointers:?}");
+        assert!(
+            pointers.contains(&"/properties/n/maxLength"),
+            "{pointers:?}"
+        );
+    }
+
+    #[test]
+    fn test_coerce_scalars() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "count": {"type": "integer"},
+                "ratio": {"type": "number"},
+                "enabled": {"type": "boolean"},
+                "tags": {"type": "array", "items": {"type": "string"}},
+                "name": {"type": "string"}
+            }
+        });
+        let coerced = coerce_to_schema(
+            &schema,
+            json!({"count": "5", "ratio": "0.5", "enabled": "true", "tags": "a", "name": "42"}),
+        );
+        assert_eq!(
+            coerced,
+            json!({"count": 5, "ratio": 0.5, "enabled": true, "tags": ["a"], "name": "42"})
+        );
+        assert!(validate(&schema, &coerced).is_ok());
+    }
+
+    #[test]
+    fn test_coerce_leaves_ambiguous_values() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "count": {"type": "integer"},
+                "ratio": {"type": "number"},
+                "enabled": {"type": "boolean"},
+                "either": {"type": ["integer", "string"]}
+            }
+        });
+        let input = json!({"count": "5.5", "ratio": "NaN", "enabled": "yes", "either": "7"});
+        let coerced = coerce_to_schema(&schema, input.clone());
+        assert_eq!(coerced, input);
+        assert!(validate(&schema, &coerced).is_err());
+
+        // Whitespace is not trimmed
+        assert_eq!(
+            coerce_to_schema(&json!({"type": "integer"}), json!(" 5")),
+            json!(" 5")
+        );
+    }
+
     #[test]
     fn test_type_validation_string() {
         let schema = json!({"type": "string"});
@@ -812,6 +1222,35 @@ mod tests {
         assert_eq!(errors[0].path, "root.items[1]");
     }
 
+    #[test]
+    fn test_error_pointer_and_keyword() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "items": {"type": "array", "items": {"type": "integer"}},
+                "a/b": {"type": "string", "maxLength": 2}
+            },
+            "required": ["items", "na~me"]
+        });
+
+        let errors = validate(&schema, &json!({"items": [1, "two"], "a/b": "long"})).unwrap_err();
+        let found: Vec<(&str, &str)> = errors
+            .iter()
+            .map(|e| (e.pointer.as_str(), e.keyword))
+            .collect();
+        assert_eq!(found.len(), 3);
+        assert!(found.contains(&("/na~0me", "required")));
+        assert!(found.contains(&("/items/1", "type")));
+        assert!(found.contains(&("/a~1b", "maxLength")));
+    }
+
+    #[test]
+    fn test_root_error_pointer_is_empty() {
+        let errors = validate(&json!({"type": "string"}), &json!(1)).unwrap_err();
+        assert_eq!(errors[0].pointer, "");
+        assert_eq!(errors[0].keyword, "type");
+    }
+
     // ========================================================================
     // Strict Validation Tests
     // ========================================================================
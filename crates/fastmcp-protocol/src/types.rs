@@ -25,13 +25,21 @@ pub struct ServerCapabilities {
     /// Background tasks capability (Docket/SEP-1686).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tasks: Option<TasksCapability>,
+    /// Non-standard capabilities, keyed by feature name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub experimental: Option<std::collections::HashMap<String, serde_json::Value>>,
 }
 
 /// Tool capabilities.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ToolsCapability {
     /// Whether the server supports tool list changes.
-    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    #[serde(
+        rename = "listChanged",
+        alias = "list_changed",
+        default,
+        skip_serializing_if = "std::ops::Not::not"
+    )]
     pub list_changed: bool,
 }
 
@@ -42,7 +50,12 @@ pub struct ResourcesCapability {
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub subscribe: bool,
     /// Whether the server supports resource list changes.
-    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    #[serde(
+        rename = "listChanged",
+        alias = "list_changed",
+        default,
+        skip_serializing_if = "std::ops::Not::not"
+    )]
     pub list_changed: bool,
 }
 
@@ -50,7 +63,12 @@ pub struct ResourcesCapability {
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PromptsCapability {
     /// Whether the server supports prompt list changes.
-    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    #[serde(
+        rename = "listChanged",
+        alias = "list_changed",
+        default,
+        skip_serializing_if = "std::ops::Not::not"
+    )]
     pub list_changed: bool,
 }
 
@@ -70,6 +88,25 @@ pub struct ClientCapabilities {
     /// Roots capability (filesystem roots).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub roots: Option<RootsCapability>,
+    /// Non-standard capabilities, keyed by feature name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub experimental: Option<std::collections::HashMap<String, serde_json::Value>>,
+}
+
+/// Experimental client capability key for streamed tool content.
+///
+/// A client that sets `experimental.streamingContent` during initialize
+/// receives `notifications/tools/content` chunks while a tool runs.
+pub const STREAMING_CONTENT_CAPABILITY: &str = "streamingContent";
+
+impl ClientCapabilities {
+    /// Returns true if the client opted into streamed tool content.
+    #[must_use]
+    pub fn supports_streaming_content(&self) -> bool {
+        self.experimental
+            .as_ref()
+            .is_some_and(|exp| exp.contains_key(STREAMING_CONTENT_CAPABILITY))
+    }
 }
 
 /// Sampling capability.
@@ -284,25 +321,29 @@ impl Icon {
 /// Tool annotations for additional metadata.
 ///
 /// These annotations provide hints about tool behavior to help clients
-/// make informed decisions about tool usage.
+/// make informed decisions about tool usage (e.g. confirming with the user
+/// before running a destructive tool). Field names follow the MCP spec.
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ToolAnnotations {
-    /// Whether the tool may cause destructive side effects.
-    /// True means the tool modifies external state (e.g., deleting files).
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub destructive: Option<bool>,
-    /// Whether the tool is idempotent (safe to retry without side effects).
-    /// True means calling the tool multiple times has the same effect as calling it once.
+    /// Human-readable title for the tool.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub idempotent: Option<bool>,
+    pub title: Option<String>,
     /// Whether the tool is read-only (has no side effects).
     /// True means the tool only reads data without modifying anything.
-    #[serde(rename = "readOnly", skip_serializing_if = "Option::is_none")]
-    pub read_only: Option<bool>,
-    /// Hint about the tool's behavior with unknown inputs.
-    /// Can be used to indicate how the tool handles inputs not explicitly defined.
+    #[serde(rename = "readOnlyHint", skip_serializing_if = "Option::is_none")]
+    pub read_only_hint: Option<bool>,
+    /// Whether the tool may cause destructive side effects.
+    /// True means the tool may delete or overwrite external state.
+    #[serde(rename = "destructiveHint", skip_serializing_if = "Option::is_none")]
+    pub destructive_hint: Option<bool>,
+    /// Whether the tool is idempotent (safe to retry without side effects).
+    /// True means calling the tool multiple times has the same effect as calling it once.
+    #[serde(rename = "idempotentHint", skip_serializing_if = "Option::is_none")]
+    pub idempotent_hint: Option<bool>,
+    /// Whether the tool interacts with an open world of external entities
+    /// (e.g. the web) rather than a closed, well-known domain.
     #[serde(rename = "openWorldHint", skip_serializing_if = "Option::is_none")]
-    pub open_world_hint: Option<String>,
+    pub open_world_hint: Option<bool>,
 }
 
 impl ToolAnnotations {
@@ -312,40 +353,60 @@ impl ToolAnnotations {
         Self::default()
     }
 
-    /// Sets the destructive annotation.
+    /// Sets the human-readable title.
+    #[must_use]
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets the `readOnlyHint` annotation.
+    #[must_use]
+    pub fn read_only(mut self, value: bool) -> Self {
+        self.read_only_hint = Some(value);
+        self
+    }
+
+    /// Sets the `destructiveHint` annotation.
     #[must_use]
     pub fn destructive(mut self, value: bool) -> Self {
-        self.destructive = Some(value);
+        self.destructive_hint = Some(value);
         self
     }
 
-    /// Sets the idempotent annotation.
+    /// Sets the `idempotentHint` annotation.
     #[must_use]
     pub fn idempotent(mut self, value: bool) -> Self {
-        self.idempotent = Some(value);
+        self.idempotent_hint = Some(value);
         self
     }
 
-    /// Sets the read_only annotation.
+    /// Sets the `openWorldHint` annotation.
     #[must_use]
-    pub fn read_only(mut self, value: bool) -> Self {
-        self.read_only = Some(value);
+    pub fn open_world(mut self, value: bool) -> Self {
+        self.open_world_hint = Some(value);
         self
     }
 
-    /// Sets the open_world_hint annotation.
+    /// Returns true if the tool is declared destructive.
+    ///
+    /// Per the spec, `destructiveHint` only applies to tools that are not
+    /// read-only, and an unset hint defaults to `true`.
     #[must_use]
-    pub fn open_world_hint(mut self, hint: impl Into<String>) -> Self {
-        self.open_world_hint = Some(hint.into());
-        self
+    pub fn is_destructive(&self) -> bool {
+        if self.read_only_hint == Some(true) {
+            return false;
+        }
+        self.destructive_hint.unwrap_or(true)
     }
 
-    /// Returns true if any annotation is set.
+    /// Returns true if no annotation is set.
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.destructive.is_none()
-            && self.idempotent.is_none()
-            && self.read_only.is_none()
+        self.title.is_none()
+            && self.read_only_hint.is_none()
+            && self.destructive_hint.is_none()
+            && self.idempotent_hint.is_none()
             && self.open_world_hint.is_none()
     }
 }
@@ -425,6 +486,27 @@ pub struct ResourceTemplate {
     /// Tags for filtering and organization.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
+    /// Parameters of the URI template, for clients building input forms.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<TemplateParameter>,
+}
+
+/// Resource template parameter definition.
+///
+/// Describes one `{placeholder}` of a [`ResourceTemplate`]'s URI template.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateParameter {
+    /// Placeholder name, as written in the URI template.
+    pub name: String,
+    /// Parameter description.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Whether the parameter is required.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub required: bool,
+    /// Value type, e.g. `string` or `int` for a `{name:int}` placeholder.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub param_type: Option<String>,
 }
 
 /// Prompt definition.
@@ -487,6 +569,10 @@ pub enum Content {
 }
 
 /// Resource content in a message.
+///
+/// Content read from a resource sets exactly one of `text` or `blob`; binary
+/// data goes in `blob` as standard base64 (see [`binary`](Self::binary)).
+/// Only a [`reference`](Self::reference) embedded in a prompt sets neither.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResourceContent {
     /// Resource URI.
@@ -500,6 +586,54 @@ pub struct ResourceContent {
     /// Binary content (if blob, base64).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub blob: Option<String>,
+    /// Opaque version tag; changes whenever the content changes.
+    ///
+    /// Clients echo it back in [`ReadResourceParams::if_none_match`](crate::ReadResourceParams::if_none_match)
+    /// to skip re-downloading unchanged content.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    /// When the content last changed (RFC 3339).
+    #[serde(
+        rename = "lastModified",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub last_modified: Option<String>,
+}
+
+impl ResourceContent {
+    /// Creates a reference to the resource at `uri`, with no content.
+    ///
+    /// A prompt message carrying a reference has the resource read and
+    /// embedded by the server when the prompt is fetched.
+    #[must_use]
+    pub fn reference(uri: impl Into<String>) -> Self {
+        Self {
+            uri: uri.into(),
+            mime_type: None,
+            text: None,
+            blob: None,
+            etag: None,
+            last_modified: None,
+        }
+    }
+
+    /// Creates binary content, base64-encoding `bytes` into `blob`.
+    #[must_use]
+    pub fn binary(uri: impl Into<String>, mime_type: impl Into<String>, bytes: &[u8]) -> Self {
+        Self {
+            mime_type: Some(mime_type.into()),
+            blob: Some(base64_encode(bytes)),
+            ..Self::reference(uri)
+        }
+    }
+
+    /// Returns whether this is a [`reference`](Self::reference) with no
+    /// content.
+    #[must_use]
+    pub fn is_reference(&self) -> bool {
+        self.text.is_none() && self.blob.is_none()
+    }
 }
 
 /// Role in prompt messages.
@@ -765,6 +899,66 @@ pub enum StopReason {
     MaxTokens,
 }
 
+// ============================================================================
+// Registry Document
+// ============================================================================
+
+/// Machine-readable dump of everything a server exposes.
+///
+/// Aggregates the server identity, its capabilities, and the full definitions
+/// (schemas, annotations, tags) of every tool, resource, resource template and
+/// prompt. This is the canonical artifact for code generation and for diffing
+/// a server's surface between versions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryDocument {
+    /// Server identity.
+    pub server: ServerInfo,
+    /// Capabilities advertised during initialization.
+    pub capabilities: ServerCapabilities,
+    /// Registered tools.
+    pub tools: Vec<Tool>,
+    /// Registered static resources.
+    pub resources: Vec<Resource>,
+    /// Registered resource templates.
+    #[serde(rename = "resourceTemplates")]
+    pub resource_templates: Vec<ResourceTemplate>,
+    /// Registered prompts.
+    pub prompts: Vec<Prompt>,
+}
+
+/// Standard base64 alphabet.
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes bytes to padded standard base64.
+fn base64_encode(data: &[u8]) -> String {
+    let mut result = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let b0 = chunk[0] as usize;
+        let b1 = chunk.get(1).copied().unwrap_or(0) as usize;
+        let b2 = chunk.get(2).copied().unwrap_or(0) as usize;
+
+        let combined = (b0 << 16) | (b1 << 8) | b2;
+
+        result.push(BASE64_CHARS[(combined >> 18) & 0x3F] as char);
+        result.push(BASE64_CHARS[(combined >> 12) & 0x3F] as char);
+
+        if chunk.len() > 1 {
+            result.push(BASE64_CHARS[(combined >> 6) & 0x3F] as char);
+        } else {
+            result.push('=');
+        }
+
+        if chunk.len() > 2 {
+            result.push(BASE64_CHARS[combined & 0x3F] as char);
+        } else {
+            result.push('=');
+        }
+    }
+
+    result
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -797,14 +991,19 @@ mod tests {
             prompts: Some(PromptsCapability { list_changed: true }),
             logging: Some(LoggingCapability {}),
             tasks: Some(TasksCapability { list_changed: true }),
+            experimental: Some(std::collections::HashMap::from([(
+                "x-search".to_string(),
+                json!({"version": 1}),
+            )])),
         };
         let value = serde_json::to_value(&caps).expect("serialize");
-        assert_eq!(value["tools"]["list_changed"], true);
+        assert_eq!(value["tools"]["listChanged"], true);
         assert_eq!(value["resources"]["subscribe"], true);
-        assert_eq!(value["resources"]["list_changed"], true);
-        assert_eq!(value["prompts"]["list_changed"], true);
+        assert_eq!(value["resources"]["listChanged"], true);
+        assert_eq!(value["prompts"]["listChanged"], true);
         assert!(value.get("logging").is_some());
         assert_eq!(value["tasks"]["listChanged"], true);
+        assert_eq!(value["experimental"]["x-search"]["version"], 1);
     }
 
     #[test]
@@ -832,6 +1031,7 @@ mod tests {
             prompts: None,
             logging: Some(LoggingCapability {}),
             tasks: None,
+            experimental: None,
         };
         let json_str = serde_json::to_string(&caps).expect("serialize");
         let deserialized: ServerCapabilities =
@@ -854,14 +1054,19 @@ mod tests {
         let cap = ToolsCapability::default();
         let value = serde_json::to_value(&cap).expect("serialize");
         // list_changed defaults to false and should be omitted
-        assert!(value.get("list_changed").is_none());
+        assert_eq!(value, json!({}));
     }
 
     #[test]
     fn tools_capability_list_changed() {
         let cap = ToolsCapability { list_changed: true };
         let value = serde_json::to_value(&cap).expect("serialize");
-        assert_eq!(value["list_changed"], true);
+        assert_eq!(value, json!({"listChanged": true}));
+
+        // The snake_case spelling older servers sent is still understood
+        let legacy: ToolsCapability =
+            serde_json::from_value(json!({"list_changed": true})).expect("deserialize");
+        assert!(legacy.list_changed);
     }
 
     // ========================================================================
@@ -872,8 +1077,7 @@ mod tests {
     fn resources_capability_default() {
         let cap = ResourcesCapability::default();
         let value = serde_json::to_value(&cap).expect("serialize");
-        assert!(value.get("subscribe").is_none());
-        assert!(value.get("list_changed").is_none());
+        assert_eq!(value, json!({}));
     }
 
     #[test]
@@ -884,7 +1088,7 @@ mod tests {
         };
         let value = serde_json::to_value(&cap).expect("serialize");
         assert_eq!(value["subscribe"], true);
-        assert_eq!(value["list_changed"], true);
+        assert_eq!(value["listChanged"], true);
     }
 
     // ========================================================================
@@ -904,6 +1108,7 @@ mod tests {
             sampling: Some(SamplingCapability {}),
             elicitation: Some(ElicitationCapability::both()),
             roots: Some(RootsCapability { list_changed: true }),
+            experimental: None,
         };
         let value = serde_json::to_value(&caps).expect("serialize");
         assert!(value.get("sampling").is_some());
@@ -919,6 +1124,7 @@ mod tests {
             roots: Some(RootsCapability {
                 list_changed: false,
             }),
+            experimental: None,
         };
         let json_str = serde_json::to_string(&caps).expect("serialize");
         let deserialized: ClientCapabilities =
@@ -928,6 +1134,15 @@ mod tests {
         assert!(deserialized.roots.is_some());
     }
 
+    #[test]
+    fn client_capabilities_streaming_content_flag() {
+        let caps: ClientCapabilities =
+            serde_json::from_value(serde_json::json!({"experimental": {"streamingContent": {}}}))
+                .expect("deserialize");
+        assert!(caps.supports_streaming_content());
+        assert!(!ClientCapabilities::default().supports_streaming_content());
+    }
+
     // ========================================================================
     // ElicitationCapability Tests
     // ========================================================================
@@ -1088,6 +1303,8 @@ mod tests {
                 mime_type: Some("application/json".to_string()),
                 text: Some("{\"key\": \"value\"}".to_string()),
                 blob: None,
+                etag: None,
+                last_modified: None,
             },
         };
         let value = serde_json::to_value(&content).expect("serialize");
@@ -1132,6 +1349,8 @@ mod tests {
             mime_type: Some("text/markdown".to_string()),
             text: Some("# Hello".to_string()),
             blob: None,
+            etag: None,
+            last_modified: None,
         };
         let value = serde_json::to_value(&rc).expect("serialize");
         assert_eq!(value["uri"], "file://readme.md");
@@ -1140,6 +1359,20 @@ mod tests {
         assert!(value.get("blob").is_none());
     }
 
+    #[test]
+    fn resource_content_binary_encodes_base64() {
+        let rc = ResourceContent::binary("file://data.bin", "application/octet-stream", b"fo");
+        assert_eq!(rc.blob.as_deref(), Some("Zm8="));
+        assert!(rc.text.is_none());
+        assert_eq!(rc.mime_type.as_deref(), Some("application/octet-stream"));
+        assert!(!rc.is_reference());
+
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(&[0xFF, 0xFE, 0x00, 0x01]), "//4AAQ==");
+    }
+
     #[test]
     fn resource_content_blob_serialization() {
         let rc = ResourceContent {
@@ -1147,6 +1380,8 @@ mod tests {
             mime_type: Some("image/png".to_string()),
             text: None,
             blob: Some("base64data".to_string()),
+            etag: None,
+            last_modified: None,
         };
         let value = serde_json::to_value(&rc).expect("serialize");
         assert_eq!(value["uri"], "file://image.png");
@@ -1161,6 +1396,8 @@ mod tests {
             mime_type: None,
             text: None,
             blob: None,
+            etag: None,
+            last_modified: None,
         };
         let value = serde_json::to_value(&rc).expect("serialize");
         assert_eq!(value["uri"], "file://test");
@@ -1308,8 +1545,8 @@ mod tests {
         assert_eq!(value["icon"]["src"], "https://example.com/icon.png");
         assert_eq!(value["version"], "2.1.0");
         assert_eq!(value["tags"], json!(["math", "compute"]));
-        assert_eq!(value["annotations"]["readOnly"], true);
-        assert_eq!(value["annotations"]["idempotent"], true);
+        assert_eq!(value["annotations"]["readOnlyHint"], true);
+        assert_eq!(value["annotations"]["idempotentHint"], true);
     }
 
     #[test]
@@ -1321,13 +1558,13 @@ mod tests {
             "outputSchema": {"type": "string"},
             "version": "1.0.0",
             "tags": ["greeting"],
-            "annotations": {"readOnly": true}
+            "annotations": {"readOnlyHint": true}
         });
         let tool: Tool = serde_json::from_value(json.clone()).expect("deserialize");
         assert_eq!(tool.name, "greet");
         assert_eq!(tool.version, Some("1.0.0".to_string()));
         assert_eq!(tool.tags, vec!["greeting"]);
-        assert!(tool.annotations.as_ref().unwrap().read_only.unwrap());
+        assert!(tool.annotations.as_ref().unwrap().read_only_hint.unwrap());
         let re_serialized = serde_json::to_value(&tool).expect("re-serialize");
         assert_eq!(re_serialized["name"], json["name"]);
     }
@@ -1384,12 +1621,75 @@ mod tests {
             icon: None,
             version: None,
             tags: vec![],
+            parameters: vec![],
         };
         let value = serde_json::to_value(&template).expect("serialize");
         assert_eq!(value["uriTemplate"], "file://{path}");
         assert_eq!(value["name"], "File Reader");
         assert_eq!(value["description"], "Read any file");
         assert_eq!(value["mimeType"], "text/plain");
+        // No parameters declared, so the field is omitted
+        assert!(value.get("parameters").is_none());
+    }
+
+    #[test]
+    fn resource_template_parameters_serialization() {
+        let template = ResourceTemplate {
+            uri_template: "db://{table}/{id:int}".to_string(),
+            name: "Row".to_string(),
+            description: None,
+            mime_type: None,
+            icon: None,
+            version: None,
+            tags: vec![],
+            parameters: vec![
+                TemplateParameter {
+                    name: "table".to_string(),
+                    description: Some("Table name".to_string()),
+                    required: true,
+                    param_type: None,
+                },
+                TemplateParameter {
+                    name: "id".to_string(),
+                    description: None,
+                    required: false,
+                    param_type: Some("int".to_string()),
+                },
+            ],
+        };
+        let value = serde_json::to_value(&template).expect("serialize");
+        assert_eq!(
+            value["parameters"],
+            json!([
+                {"name": "table", "description": "Table name", "required": true},
+                {"name": "id", "type": "int"}
+            ])
+        );
+    }
+
+    #[test]
+    fn resource_template_parameters_round_trip() {
+        let json = json!({
+            "uriTemplate": "weather://{city}",
+            "name": "Weather",
+            "parameters": [
+                {"name": "city", "description": "City name", "required": true, "type": "string"}
+            ]
+        });
+        let template: ResourceTemplate = serde_json::from_value(json.clone()).expect("deserialize");
+        assert_eq!(template.parameters.len(), 1);
+        let param = &template.parameters[0];
+        assert_eq!(param.name, "city");
+        assert_eq!(param.description.as_deref(), Some("City name"));
+        assert!(param.required);
+        assert_eq!(param.param_type.as_deref(), Some("string"));
+        assert_eq!(serde_json::to_value(&template).expect("serialize"), json);
+
+        // Templates from servers without parameter metadata still parse
+        let template: ResourceTemplate =
+            serde_json::from_value(json!({"uriTemplate": "x://{y}", "name": "X"}))
+                .expect("deserialize");
+        assert!(template.parameters.is_empty());
     }
 
     // ========================================================================
@@ -0,0 +1,644 @@
+//! Network accept loops for the WebSocket and SSE transports.
+//!
+//! [`Server::run_ws_accept`] and [`Server::run_sse_accept`] bind a TCP
+//! listener and serve every client on its own thread with its own
+//! [`Session`](crate::Session) and notification sender, sharing the router,
+//! stats, and lifecycle hooks the way [`Server::run_multi`] does. At most
+//! [`max_connections`](crate::ServerBuilder::max_connections) clients are
+//! served at once; the rest are answered with `503 Service Unavailable`.
+//!
+//! # SSE endpoints
+//!
+//! The SSE loop follows the MCP HTTP+SSE transport: a client opens its event
+//! stream with `GET /sse`, receives an `endpoint` event naming
+//! `/messages?session=<id>`, and POSTs its JSON-RPC messages there. Each POST
+//! is answered with `202 Accepted`; responses arrive on the event stream.
+//! POSTed responses to server-initiated requests (sampling, elicitation,
+//! roots) go straight to the waiting request. Only event streams count
+//! towards the connection limit.
+//!
+//! When an authorization server is configured, the loop also serves
+//! [`Server::protected_resource_metadata`] at
+//! `GET /.well-known/oauth-protected-resource`, using the configured
+//! [`resource_url`](crate::ServerBuilder::resource_url) or else the request's
+//! `Host` header as the resource.
+
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use asupersync::Cx;
+use fastmcp_core::logging::{debug, error, info, targets, warn};
+use fastmcp_protocol::{JsonRpcMessage, JsonRpcRequest};
+use fastmcp_transport::compression::{self, CompressedWriter};
+use fastmcp_transport::http::{HttpMethod, HttpRequest, HttpResponse, HttpStatus, HttpTransport};
+use fastmcp_transport::sse::SseServerTransport;
+use fastmcp_transport::websocket::WsTransport;
+use fastmcp_transport::{Transport, TransportError};
+
+use crate::oauth::PROTECTED_RESOURCE_METADATA_PATH;
+use crate::{
+    Server, ServerRunError, SharedTransport, ShutdownReason, create_transport_notification_sender,
+    exit_with,
+};
+
+/// Path of the SSE event stream.
+const SSE_STREAM_PATH: &str = "/sse";
+
+/// Path clients POST their messages to, with a `session` query parameter.
+const SSE_MESSAGES_PATH: &str = "/messages";
+
+/// How often an idle accept loop checks for cancellation.
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long the accept loop waits on a client it is turning away, so it
+/// cannot be stalled by a client that never sends its request.
+const REJECT_READ_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Event stream senders by session id, so POSTs can reach their session.
+type SseSessions = Mutex<HashMap<String, Sender<JsonRpcRequest>>>;
+
+impl Server {
+    /// Serves WebSocket clients on `addr` with a testing Cx.
+    ///
+    /// See [`run_ws_accept_with_cx`](Self::run_ws_accept_with_cx).
+    pub fn run_ws_accept(self, addr: impl ToSocketAddrs) -> ! {
+        let cx = Cx::for_testing();
+        self.run_ws_accept_with_cx(&cx, addr)
+    }
+
+    /// Serves WebSocket clients on `addr` with a provided Cx.
+    ///
+    /// Every accepted connection is upgraded with
+    /// [`WsTransportBuilder::accept`](fastmcp_transport::websocket::WsTransportBuilder::accept)
+    /// and served with its own [`Session`](crate::Session). The startup hook
+    /// runs once before the first client is accepted. Once `cx` is cancelled
+    /// the listener stops accepting, open connections are closed, and the
+    /// shutdown hook runs once.
+    pub fn run_ws_accept_with_cx(self, cx: &Cx, addr: impl ToSocketAddrs) -> ! {
+        exit_with(self.try_run_ws_accept_with_cx(cx, addr))
+    }
+
+    /// Serves WebSocket clients on `addr` with a testing Cx, without exiting
+    /// the process.
+    ///
+    /// See [`try_run_ws_accept_with_cx`](Self::try_run_ws_accept_with_cx).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the listener cannot be bound, the startup hook
+    /// fails, or the accept loop stops abnormally.
+    pub fn try_run_ws_accept(self, addr: impl ToSocketAddrs) -> Result<(), ServerRunError> {
+        let cx = Cx::for_testing();
+        self.try_run_ws_accept_with_cx(&cx, addr)
+    }
+
+    /// Serves WebSocket clients on `addr` with a provided Cx, without
+    /// exiting the process.
+    ///
+    /// Like [`run_ws_accept_with_cx`](Self::run_ws_accept_with_cx), but
+    /// returns `Ok` once `cx` is cancelled and the shutdown hook has run, and
+    /// a [`ServerRunError`] instead of exiting on failure.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the listener cannot be bound, the startup hook
+    /// fails, or the accept loop stops abnormally.
+    pub fn try_run_ws_accept_with_cx(
+        self,
+        cx: &Cx,
+        addr: impl ToSocketAddrs,
+    ) -> Result<(), ServerRunError> {
+        self.init_rich_logging();
+        let listener = TcpListener::bind(addr).map_err(ServerRunError::Bind)?;
+        self.install_signal_handler(cx);
+        self.start("websocket")?;
+
+        let reason = self.serve_ws_listener(cx, &listener);
+        self.shutdown(reason)
+    }
+
+    /// Serves MCP-over-SSE clients on `addr` with a testing Cx.
+    ///
+    /// See [`run_sse_accept_with_cx`](Self::run_sse_accept_with_cx).
+    pub fn run_sse_accept(self, addr: impl ToSocketAddrs) -> ! {
+        let cx = Cx::for_testing();
+        self.run_sse_accept_with_cx(&cx, addr)
+    }
+
+    /// Serves MCP-over-SSE clients on `addr` with a provided Cx.
+    ///
+    /// Each `GET /sse` event stream is served with its own
+    /// [`Session`](crate::Session). Its `endpoint` event names
+    /// `/messages?session=<id>`, where the client POSTs its JSON-RPC
+    /// messages; each POST is answered with `202 Accepted` and the response
+    /// arrives on the event stream. Startup and shutdown behave as in
+    /// [`run_ws_accept_with_cx`](Self::run_ws_accept_with_cx).
+    pub fn run_sse_accept_with_cx(self, cx: &Cx, addr: impl ToSocketAddrs) -> ! {
+        exit_with(self.try_run_sse_accept_with_cx(cx, addr))
+    }
+
+    /// Serves MCP-over-SSE clients on `addr` with a testing Cx, without
+    /// exiting the process.
+    ///
+    /// See [`try_run_sse_accept_with_cx`](Self::try_run_sse_accept_with_cx).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the listener cannot be bound, the startup hook
+    /// fails, or the accept loop stops abnormally.
+    pub fn try_run_sse_accept(self, addr: impl ToSocketAddrs) -> Result<(), ServerRunError> {
+        let cx = Cx::for_testing();
+        self.try_run_sse_accept_with_cx(&cx, addr)
+    }
+
+    /// Serves MCP-over-SSE clients on `addr` with a provided Cx, without
+    /// exiting the process.
+    ///
+    /// The SSE counterpart of
+    /// [`try_run_ws_accept_with_cx`](Self::try_run_ws_accept_with_cx).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the listener cannot be bound, the startup hook
+    /// fails, or the accept loop stops abnormally.
+    pub fn try_run_sse_accept_with_cx(
+        self,
+        cx: &Cx,
+        addr: impl ToSocketAddrs,
+    ) -> Result<(), ServerRunError> {
+        self.init_rich_logging();
+        let listener = TcpListener::bind(addr).map_err(ServerRunError::Bind)?;
+        self.install_signal_handler(cx);
+        self.start("sse")?;
+
+        let reason = self.serve_sse_listener(cx, &listener);
+        self.shutdown(reason)
+    }
+
+    /// Accepts WebSocket clients until `cx` is cancelled.
+    pub(crate) fn serve_ws_listener(&self, cx: &Cx, listener: &TcpListener) -> ShutdownReason {
+        let slots = ConnectionSlots::new(self.max_connections);
+        accept_loop(cx, listener, |stream| {
+            let Some(slot) = slots.try_acquire() else {
+                turn_away(&stream);
+                return None;
+            };
+            Some(move |cx: &Cx| {
+                let _slot = slot;
+                let reader = match stream.try_clone() {
+                    Ok(reader) => reader,
+                    Err(e) => {
+                        warn!(target: targets::TRANSPORT, "Failed to clone connection: {}", e);
+                        return;
+                    }
+                };
+                match WsTransport::builder().accept(reader, stream) {
+                    Ok(transport) => self.serve_client(cx, transport),
+                    Err(e) => {
+                        debug!(target: targets::TRANSPORT, "WebSocket upgrade failed: {}", e);
+                    }
+                }
+            })
+        })
+    }
+
+    /// Accepts SSE event streams and message POSTs until `cx` is cancelled.
+    pub(crate) fn serve_sse_listener(&self, cx: &Cx, listener: &TcpListener) -> ShutdownReason {
+        let slots = &ConnectionSlots::new(self.max_connections);
+        let sessions = &SseSessions::default();
+        accept_loop(cx, listener, |stream| {
+            // Message POSTs are still served at capacity, so only turn away
+            // what is visibly a new event stream.
+            if slots.is_full() && opens_event_stream(&stream) {
+                turn_away(&stream);
+                return None;
+            }
+            Some(move |cx: &Cx| self.serve_sse_request(cx, stream, slots, sessions))
+        })
+    }
+
+    /// Serves one HTTP request made to the SSE listener.
+    fn serve_sse_request(
+        &self,
+        cx: &Cx,
+        stream: TcpStream,
+        slots: &ConnectionSlots,
+        sessions: &SseSessions,
+    ) {
+        let request = match HttpTransport::new(&stream, &stream).read_request() {
+            Ok(request) => request,
+            Err(e) => {
+                debug!(target: targets::TRANSPORT, "Failed to read HTTP request: {}", e);
+                return;
+            }
+        };
+        let (path, query) = request
+            .path
+            .split_once('?')
+            .unwrap_or((request.path.as_str(), ""));
+
+        match (request.method, path) {
+            (HttpMethod::Get, SSE_STREAM_PATH) => {
+                // Another stream may have taken the last slot since this
+                // connection was accepted.
+                let Some(_slot) = slots.try_acquire() else {
+                    reject_over_capacity(&stream);
+                    return;
+                };
+                self.serve_event_stream(cx, stream, &request, sessions);
+            }
+            (HttpMethod::Post, SSE_MESSAGES_PATH) => {
+                self.post_message(&stream, &request, query, sessions);
+            }
+            (HttpMethod::Get, PROTECTED_RESOURCE_METADATA_PATH) => {
+                self.serve_resource_metadata(&stream, &request);
+            }
+            (_, SSE_STREAM_PATH | SSE_MESSAGES_PATH) => {
+                respond(&stream, HttpStatus::METHOD_NOT_ALLOWED);
+            }
+            _ => respond(&stream, HttpStatus::NOT_FOUND),
+        }
+    }
+
+    /// Hands a POSTed JSON-RPC message to its session.
+    ///
+    /// Requests go to the session's event stream. Responses answer a
+    /// server-initiated request, whose caller may be blocking the session's
+    /// message loop, so they go straight to the pending request instead.
+    fn post_message(
+        &self,
+        stream: &TcpStream,
+        request: &HttpRequest,
+        query: &str,
+        sessions: &SseSessions,
+    ) {
+        let sender = query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("session="))
+            .and_then(|id| lock(sessions).get(id).cloned());
+        let Some(sender) = sender else {
+            respond(stream, HttpStatus::NOT_FOUND);
+            return;
+        };
+
+        let status = match self.inbound_codec().parse_frame(&request.body) {
+            Ok(JsonRpcMessage::Request(message)) if sender.send(message).is_ok() => {
+                HttpStatus::ACCEPTED
+            }
+            Ok(JsonRpcMessage::Request(_)) => HttpStatus::NOT_FOUND,
+            Ok(JsonRpcMessage::Response(response)) => {
+                if !self.pending_requests.route_response(&response) {
+                    debug!(target: targets::SERVER, "Received unexpected response: {:?}", response.id);
+                }
+                HttpStatus::ACCEPTED
+            }
+            Err(e) => {
+                debug!(target: targets::TRANSPORT, "Invalid JSON-RPC message in POST: {}", e);
+                HttpStatus::BAD_REQUEST
+            }
+        };
+        respond(stream, status);
+    }
+
+    /// Answers a protected resource metadata request.
+    fn serve_resource_metadata(&self, stream: &TcpStream, request: &HttpRequest) {
+        if self.protected_resource.authorization_servers.is_empty() {
+            respond(stream, HttpStatus::NOT_FOUND);
+            return;
+        }
+        let resource = self
+            .protected_resource
+            .resource_url
+            .clone()
+            .or_else(|| request.header("host").map(|host| format!("http://{host}")));
+        let Some(resource) = resource else {
+            respond(stream, HttpStatus::BAD_REQUEST);
+            return;
+        };
+
+        let mut response = HttpResponse::ok()
+            .with_json(&self.protected_resource_metadata(&resource))
+            .with_header("connection", "close");
+        if let Some(config) = &self.compression {
+            response = response.compressed(request.header("accept-encoding"), config);
+        }
+        if let Err(e) = HttpTransport::new(stream, stream).write_response(&response) {
+            debug!(target: targets::TRANSPORT, "Failed to write HTTP response: {}", e);
+        }
+    }
+
+    /// Serves one SSE event stream until the client hangs up or `cx` is
+    /// cancelled.
+    ///
+    /// The stream is compressed if compression is configured and the
+    /// client's `Accept-Encoding` allows it.
+    fn serve_event_stream(
+        &self,
+        cx: &Cx,
+        stream: TcpStream,
+        request: &HttpRequest,
+        sessions: &SseSessions,
+    ) {
+        let (watcher, closer) = match (stream.try_clone(), stream.try_clone()) {
+            (Ok(watcher), Ok(closer)) => (watcher, closer),
+            (Err(e), _) | (_, Err(e)) => {
+                warn!(target: targets::TRANSPORT, "Failed to clone connection: {}", e);
+                return;
+            }
+        };
+
+        let session_id = new_session_id();
+        let (sender, receiver) = mpsc::channel();
+        lock(sessions).insert(session_id.clone(), sender);
+
+        let encoding = self.compression.and_then(|_| {
+            request
+                .header("accept-encoding")
+                .and_then(compression::negotiate)
+        });
+        let level = self.compression.map_or(0, |config| config.level);
+        let content_encoding = encoding
+            .map(|encoding| {
+                format!(
+                    "content-encoding: {}\r\nvary: accept-encoding\r\n",
+                    encoding.as_str()
+                )
+            })
+            .unwrap_or_default();
+        let headers = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ncache-control: no-cache\r\nconnection: keep-alive\r\n{content_encoding}\r\n"
+        );
+        let mut transport = SseServerTransport::new(
+            CompressedWriter::new(stream, encoding, level),
+            receiver.into_iter(),
+            format!("{SSE_MESSAGES_PATH}?session={session_id}"),
+        );
+        let opened = (&closer)
+            .write_all(headers.as_bytes())
+            .map_err(TransportError::from)
+            .and_then(|()| transport.send_endpoint(cx));
+
+        std::thread::scope(|scope| {
+            // The client never writes to its event stream, so a read only
+            // returns once it hangs up (or the stream is shut down below).
+            // Dropping the sender then ends the session's message loop.
+            scope.spawn(|| {
+                let mut watcher = watcher;
+                let mut buf = [0u8; 64];
+                while matches!(watcher.read(&mut buf), Ok(n) if n > 0) {}
+                lock(sessions).remove(&session_id);
+            });
+
+            match opened {
+                Ok(()) => self.serve_client(cx, transport),
+                Err(e) => {
+                    debug!(target: targets::TRANSPORT, "Failed to open event stream: {}", e);
+                }
+            }
+            lock(sessions).remove(&session_id);
+            let _ = closer.shutdown(Shutdown::Both);
+        });
+    }
+
+    /// Serves one accepted client with its own session until it disconnects.
+    fn serve_client<T>(&self, cx: &Cx, transport: T)
+    where
+        T: Transport + Send + 'static,
+    {
+        let shared = SharedTransport::new(transport);
+        let notification_sender = create_transport_notification_sender(shared.clone());
+        let shared_recv = shared.clone();
+        self.serve_connection(
+            cx,
+            move |cx| closed_on_disconnect(shared_recv.recv(cx)),
+            move |cx, message| shared.send(cx, message),
+            notification_sender,
+        );
+    }
+}
+
+/// Accepts connections until `cx` is cancelled.
+///
+/// `admit` runs on the accepting thread. It either answers a connection
+/// itself and returns `None`, as when turning a client away, or returns the
+/// work of serving it, which runs on its own thread.
+///
+/// On cancellation every open connection is shut down so blocked reads
+/// return, and the loop waits for the connection threads to finish.
+fn accept_loop<A, S>(cx: &Cx, listener: &TcpListener, admit: A) -> ShutdownReason
+where
+    A: Fn(TcpStream) -> Option<S>,
+    S: FnOnce(&Cx) + Send,
+{
+    // Poll instead of blocking in accept() so cancellation is observed.
+    if let Err(e) = listener.set_nonblocking(true) {
+        error!(target: targets::SERVER, "Failed to configure listener: {}", e);
+        return ShutdownReason::Error(format!("Failed to configure listener: {e}"));
+    }
+    if let Ok(addr) = listener.local_addr() {
+        info!(target: targets::SERVER, "Accepting connections on {}", addr);
+    }
+
+    let open = OpenStreams::default();
+    std::thread::scope(|scope| {
+        loop {
+            if cx.is_cancel_requested() {
+                info!(target: targets::SERVER, "Cancellation requested, closing listener");
+                break;
+            }
+
+            match listener.accept() {
+                Ok((stream, peer)) => {
+                    debug!(target: targets::TRANSPORT, "Accepted connection from {}", peer);
+                    if let Err(e) = stream.set_nonblocking(false) {
+                        warn!(target: targets::TRANSPORT, "Failed to configure connection: {}", e);
+                        continue;
+                    }
+                    let id = open.track(&stream);
+                    let Some(serve) = admit(stream) else {
+                        open.untrack(id);
+                        continue;
+                    };
+                    let cx = cx.clone();
+                    let open = &open;
+                    scope.spawn(move || {
+                        serve(&cx);
+                        open.untrack(id);
+                    });
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    std::thread::sleep(ACCEPT_POLL_INTERVAL);
+                }
+                Err(e) => {
+                    warn!(target: targets::TRANSPORT, "Failed to accept connection: {}", e);
+                    std::thread::sleep(ACCEPT_POLL_INTERVAL);
+                }
+            }
+        }
+
+        open.shutdown_all();
+    });
+    ShutdownReason::Cancelled
+}
+
+/// Counts clients holding a session, up to an optional limit.
+struct ConnectionSlots {
+    active: AtomicUsize,
+    limit: Option<usize>,
+}
+
+impl ConnectionSlots {
+    fn new(limit: Option<usize>) -> Self {
+        Self {
+            active: AtomicUsize::new(0),
+            limit,
+        }
+    }
+
+    /// Returns whether every slot is taken.
+    fn is_full(&self) -> bool {
+        self.limit
+            .is_some_and(|limit| self.active.load(Ordering::Acquire) >= limit)
+    }
+
+    /// Takes a slot, or returns `None` if the limit is reached.
+    fn try_acquire(&self) -> Option<ConnectionSlot<'_>> {
+        self.active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |active| {
+                match self.limit {
+                    Some(limit) if active >= limit => None,
+                    _ => Some(active + 1),
+                }
+            })
+            .ok()
+            .map(|_| ConnectionSlot { slots: self })
+    }
+}
+
+/// A taken connection slot, released on drop.
+struct ConnectionSlot<'a> {
+    slots: &'a ConnectionSlots,
+}
+
+impl Drop for ConnectionSlot<'_> {
+    fn drop(&mut self) {
+        self.slots.active.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Handles to open connections, so shutdown can unblock their readers.
+#[derive(Default)]
+struct OpenStreams {
+    next_id: AtomicU64,
+    streams: Mutex<HashMap<u64, TcpStream>>,
+}
+
+impl OpenStreams {
+    fn track(&self, stream: &TcpStream) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        match stream.try_clone() {
+            Ok(handle) => {
+                lock(&self.streams).insert(id, handle);
+            }
+            Err(e) => {
+                warn!(target: targets::TRANSPORT, "Connection will not be closed on shutdown: {}", e);
+            }
+        }
+        id
+    }
+
+    fn untrack(&self, id: u64) {
+        lock(&self.streams).remove(&id);
+    }
+
+    fn shutdown_all(&self) {
+        for stream in lock(&self.streams).values() {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+/// Returns whether the request waiting on `stream` opens an event stream,
+/// peeking at it without consuming anything.
+///
+/// A request line that has not fully arrived within
+/// [`REJECT_READ_TIMEOUT`] counts as something else.
+fn opens_event_stream(stream: &TcpStream) -> bool {
+    let prefix = format!("GET {SSE_STREAM_PATH}");
+    let mut buf = [0u8; 16];
+    let _ = stream.set_read_timeout(Some(REJECT_READ_TIMEOUT));
+    let peeked = stream.peek(&mut buf);
+    let _ = stream.set_read_timeout(None);
+    match peeked {
+        Ok(n) => {
+            buf[..n].starts_with(prefix.as_bytes())
+                && matches!(buf.get(prefix.len()), Some(b' ' | b'?'))
+        }
+        Err(_) => false,
+    }
+}
+
+/// Turns away a client over the connection limit from the accepting
+/// thread.
+///
+/// The request is read first so the client sees the 503 rather than a
+/// reset, but for no longer than [`REJECT_READ_TIMEOUT`].
+fn turn_away(stream: &TcpStream) {
+    let _ = stream.set_read_timeout(Some(REJECT_READ_TIMEOUT));
+    let _ = HttpTransport::new(stream, stream).read_request();
+    reject_over_capacity(stream);
+}
+
+/// Answers a client over the connection limit.
+fn reject_over_capacity(stream: &TcpStream) {
+    warn!(target: targets::SERVER, "Connection limit reached, rejecting client");
+    respond(stream, HttpStatus::SERVICE_UNAVAILABLE);
+}
+
+/// Writes an empty response that closes the connection.
+fn respond(stream: &TcpStream, status: HttpStatus) {
+    let response = HttpResponse::new(status).with_header("connection", "close");
+    if let Err(e) = HttpTransport::new(stream, stream).write_response(&response) {
+        debug!(target: targets::TRANSPORT, "Failed to write HTTP response: {}", e);
+    }
+}
+
+/// Treats a peer hanging up mid-read as a closed connection.
+fn closed_on_disconnect(
+    result: Result<JsonRpcMessage, TransportError>,
+) -> Result<JsonRpcMessage, TransportError> {
+    match result {
+        Err(TransportError::Io(e))
+            if matches!(
+                e.kind(),
+                ErrorKind::UnexpectedEof
+                    | ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+                    | ErrorKind::BrokenPipe
+            ) =>
+        {
+            Err(TransportError::Closed)
+        }
+        other => other,
+    }
+}
+
+/// Returns a hard-to-guess id for a new event stream.
+fn new_session_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    format!("{:016x}", hasher.finish())
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,112 @@
+//! Per-request audit trail.
+//!
+//! Stats aggregate and logs are written for humans; an [`AuditSink`] instead
+//! receives one structured [`AuditEvent`] per completed request, carrying the
+//! authenticated subject, so deployments can keep an append-only record of who
+//! called what. [`FileAuditSink`] writes the events as JSON lines.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use fastmcp_core::logging::{targets, warn};
+use fastmcp_protocol::RequestId;
+use serde::{Serialize, Serializer};
+
+/// Receives an [`AuditEvent`] for every completed request.
+///
+/// Register one with
+/// [`ServerBuilder::audit_sink`](crate::ServerBuilder::audit_sink). `record`
+/// runs on the request path, so slow sinks delay responses.
+pub trait AuditSink: Send + Sync {
+    /// Records one completed request.
+    fn record(&self, event: AuditEvent);
+}
+
+/// A completed request, as passed to an [`AuditSink`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEvent {
+    /// When the request completed (RFC 3339).
+    pub timestamp: String,
+    /// Authenticated subject, if the request was authenticated and the
+    /// provider identified one.
+    pub subject: Option<String>,
+    /// JSON-RPC method.
+    pub method: String,
+    /// JSON-RPC request ID.
+    pub request_id: RequestId,
+    /// Whether the request succeeded.
+    pub outcome: AuditOutcome,
+    /// Time spent handling the request (serialized as `latencyMs`).
+    #[serde(rename = "latencyMs", serialize_with = "serialize_millis")]
+    pub latency: Duration,
+}
+
+/// Result of an audited request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum AuditOutcome {
+    /// The request succeeded.
+    Success,
+    /// The request failed.
+    Error {
+        /// JSON-RPC error code.
+        code: i32,
+        /// Error message, before any masking applied to the response.
+        message: String,
+    },
+}
+
+fn serialize_millis<S: Serializer>(latency: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(latency.as_secs_f64() * 1000.0)
+}
+
+/// Appends audit events to a file, one JSON object per line.
+///
+/// # Example
+///
+/// ```ignore
+/// let sink = FileAuditSink::open("/var/log/mcp/audit.jsonl")?;
+/// let server = Server::new("api", "1.0")
+///     .audit_sink(Arc::new(sink))
+///     .build();
+/// ```
+#[derive(Debug)]
+pub struct FileAuditSink {
+    file: Mutex<File>,
+}
+
+impl FileAuditSink {
+    /// Opens `path` for appending, creating it if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl AuditSink for FileAuditSink {
+    fn record(&self, event: AuditEvent) {
+        let mut line = match serde_json::to_vec(&event) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!(target: targets::SERVER, "Failed to serialize audit event: {}", e);
+                return;
+            }
+        };
+        line.push(b'\n');
+        // One write per event keeps lines whole in append mode
+        let mut file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Err(e) = file.write_all(&line).and_then(|()| file.flush()) {
+            warn!(target: targets::SERVER, "Failed to write audit event: {}", e);
+        }
+    }
+}
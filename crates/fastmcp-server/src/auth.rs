@@ -108,6 +108,38 @@ pub trait AuthProvider: Send + Sync {
     ///
     /// Return `Ok(AuthContext)` to allow, or an `Err(McpError)` to deny.
     fn authenticate(&self, ctx: &McpContext, request: AuthRequest<'_>) -> McpResult<AuthContext>;
+
+    /// Whether the server may reuse a successful result for later requests.
+    ///
+    /// Defaults to [`AuthCachePolicy::PerSession`]. Providers that must see
+    /// every request (for example, to honor token revocation) should return
+    /// [`AuthCachePolicy::Never`].
+    fn cache_policy(&self) -> AuthCachePolicy {
+        AuthCachePolicy::PerSession
+    }
+}
+
+/// How the server caches [`AuthContext`]s resolved by an [`AuthProvider`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AuthCachePolicy {
+    /// Reuse the context for the rest of the session while the client keeps
+    /// presenting the same access token and its `exp` claim (if any) has not
+    /// passed. Requests without a token are always authenticated.
+    #[default]
+    PerSession,
+    /// Run the provider on every request.
+    Never,
+}
+
+/// Authorization servers and scopes a server advertises to clients.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ProtectedResource {
+    /// Authorization servers that issue accepted tokens.
+    pub(crate) authorization_servers: Vec<String>,
+    /// Public URL of this server, if known.
+    pub(crate) resource_url: Option<String>,
+    /// Scopes listed in the protected resource metadata.
+    pub(crate) scopes_supported: Vec<String>,
 }
 
 /// Token verifier interface used by token-based auth providers.
@@ -126,6 +158,7 @@ pub trait TokenVerifier: Send + Sync {
 pub struct TokenAuthProvider {
     verifier: Arc<dyn TokenVerifier>,
     missing_token_error: McpError,
+    cache_policy: AuthCachePolicy,
 }
 
 impl TokenAuthProvider {
@@ -135,6 +168,7 @@ impl TokenAuthProvider {
         Self {
             verifier: Arc::new(verifier),
             missing_token_error: auth_error("Missing access token"),
+            cache_policy: AuthCachePolicy::default(),
         }
     }
 
@@ -144,6 +178,13 @@ impl TokenAuthProvider {
         self.missing_token_error = error;
         self
     }
+
+    /// Overrides the cache policy reported to the server.
+    #[must_use]
+    pub fn with_cache_policy(mut self, policy: AuthCachePolicy) -> Self {
+        self.cache_policy = policy;
+        self
+    }
 }
 
 impl AuthProvider for TokenAuthProvider {
@@ -153,6 +194,10 @@ impl AuthProvider for TokenAuthProvider {
             .ok_or_else(|| self.missing_token_error.clone())?;
         self.verifier.verify(ctx, request, &access)
     }
+
+    fn cache_policy(&self) -> AuthCachePolicy {
+        self.cache_policy
+    }
 }
 
 /// Static token verifier backed by an in-memory token map.
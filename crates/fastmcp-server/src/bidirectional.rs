@@ -3,7 +3,7 @@
 //! This module provides the infrastructure for server-initiated requests to clients,
 //! such as:
 //! - `sampling/createMessage` - Request LLM completion from the client
-//! - `elicitation/elicit` - Request user input from the client
+//! - `elicitation/create` - Request user input from the client
 //! - `roots/list` - Request filesystem roots from the client
 //!
 //! # Architecture
@@ -33,9 +33,9 @@
 //! ).await?;
 //! ```
 
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, PoisonError};
 
 use asupersync::Cx;
 use fastmcp_core::{
@@ -43,12 +43,20 @@ use fastmcp_core::{
     McpError, McpErrorCode, McpResult, SamplingRequest, SamplingResponse, SamplingRole,
     SamplingSender, SamplingStopReason,
 };
-use fastmcp_protocol::{JsonRpcError, JsonRpcMessage, JsonRpcRequest, JsonRpcResponse, RequestId};
+use fastmcp_protocol::{
+    ClientCapabilities, JsonRpcError, JsonRpcMessage, JsonRpcRequest, JsonRpcResponse, RequestId,
+};
 
 // ============================================================================
 // Pending Request Tracking
 // ============================================================================
 
+/// Default prefix for ids of server-to-client requests.
+///
+/// Server-initiated ids are strings like `srv-1`, `srv-2`, ... so they cannot
+/// collide with the numeric ids most clients use for their own requests.
+pub const DEFAULT_REQUEST_ID_PREFIX: &str = "srv-";
+
 /// A oneshot channel for receiving a response.
 type ResponseSender = std::sync::mpsc::Sender<Result<serde_json::Value, JsonRpcError>>;
 type ResponseReceiver = std::sync::mpsc::Receiver<Result<serde_json::Value, JsonRpcError>>;
@@ -59,34 +67,47 @@ type ResponseReceiver = std::sync::mpsc::Receiver<Result<serde_json::Value, Json
 /// here. When a response arrives, the dispatcher routes it to the correct sender.
 #[derive(Debug)]
 pub struct PendingRequests {
-    /// Map from request ID to response sender.
-    pending: Mutex<HashMap<RequestId, ResponseSender>>,
+    /// Map from request ID to response sender, ordered so `cancel_all`
+    /// fails requests in the same order on every run.
+    pending: Mutex<BTreeMap<RequestId, ResponseSender>>,
     /// Counter for generating unique request IDs.
     next_id: AtomicU64,
+    /// Prefix prepended to every generated request ID.
+    id_prefix: String,
 }
 
 impl PendingRequests {
     /// Creates a new pending request tracker.
+    ///
+    /// Generated IDs use [`DEFAULT_REQUEST_ID_PREFIX`].
     #[must_use]
     pub fn new() -> Self {
+        Self::with_id_prefix(DEFAULT_REQUEST_ID_PREFIX)
+    }
+
+    /// Creates a pending request tracker whose IDs start with `prefix`.
+    ///
+    /// Use this when a client's own ID scheme could produce strings like
+    /// `srv-1`.
+    #[must_use]
+    pub fn with_id_prefix(prefix: impl Into<String>) -> Self {
         Self {
-            pending: Mutex::new(HashMap::new()),
-            // Start at a high number to avoid collision with client request IDs
-            next_id: AtomicU64::new(1_000_000),
+            pending: Mutex::new(BTreeMap::new()),
+            next_id: AtomicU64::new(1),
+            id_prefix: prefix.into(),
         }
     }
 
-    /// Generates a new unique request ID.
-    #[allow(clippy::cast_possible_wrap)]
+    /// Generates a new unique request ID, e.g. `srv-1`.
     pub fn next_request_id(&self) -> RequestId {
         let id = self.next_id.fetch_add(1, Ordering::SeqCst);
-        RequestId::Number(id as i64)
+        RequestId::String(format!("{}{id}", self.id_prefix))
     }
 
     /// Registers a pending request and returns a receiver for the response.
     pub fn register(&self, id: RequestId) -> ResponseReceiver {
         let (tx, rx) = std::sync::mpsc::channel();
-        let mut pending = self.pending.lock().unwrap();
+        let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
         pending.insert(id, tx);
         rx
     }
@@ -100,7 +121,7 @@ impl PendingRequests {
         };
 
         let sender = {
-            let mut pending = self.pending.lock().unwrap();
+            let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
             pending.remove(id)
         };
 
@@ -120,14 +141,14 @@ impl PendingRequests {
 
     /// Removes a pending request (e.g., on timeout or cancellation).
     pub fn remove(&self, id: &RequestId) {
-        let mut pending = self.pending.lock().unwrap();
+        let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
         pending.remove(id);
     }
 
     /// Cancels all pending requests with a connection closed error.
     pub fn cancel_all(&self) {
-        let mut pending = self.pending.lock().unwrap();
-        for (_, sender) in pending.drain() {
+        let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+        for (_, sender) in std::mem::take(&mut *pending) {
             let _ = sender.send(Err(JsonRpcError {
                 code: McpErrorCode::InternalError.into(),
                 message: "Connection closed".to_string(),
@@ -160,12 +181,52 @@ pub struct RequestSender {
     pending: Arc<PendingRequests>,
     /// Transport send callback.
     send_fn: TransportSendFn,
+    /// Capabilities the client advertised at initialize, if known.
+    client_capabilities: Option<Arc<ClientCapabilities>>,
 }
 
 impl RequestSender {
     /// Creates a new request sender.
     pub fn new(pending: Arc<PendingRequests>, send_fn: TransportSendFn) -> Self {
-        Self { pending, send_fn }
+        Self {
+            pending,
+            send_fn,
+            client_capabilities: None,
+        }
+    }
+
+    /// Restricts this sender to requests the client advertised support for.
+    ///
+    /// A client that did not declare e.g. `sampling` at initialize will never
+    /// answer `sampling/createMessage`, so such requests fail immediately
+    /// instead of waiting forever for a response.
+    #[must_use]
+    pub fn with_client_capabilities(mut self, capabilities: ClientCapabilities) -> Self {
+        self.client_capabilities = Some(Arc::new(capabilities));
+        self
+    }
+
+    /// Fails if the client did not advertise the capability `method` needs.
+    fn check_client_capability(&self, method: &str) -> McpResult<()> {
+        let Some(caps) = &self.client_capabilities else {
+            return Ok(());
+        };
+        let (capability, supported) = match method {
+            "sampling/createMessage" => ("sampling", caps.sampling.is_some()),
+            "elicitation/create" => ("elicitation", caps.elicitation.is_some()),
+            "roots/list" => ("roots", caps.roots.is_some()),
+            _ => return Ok(()),
+        };
+        if supported {
+            Ok(())
+        } else {
+            Err(McpError::new(
+                McpErrorCode::InvalidRequest,
+                format!(
+                    "Cannot send '{method}': client did not advertise the '{capability}' capability"
+                ),
+            ))
+        }
     }
 
     /// Sends a request to the client and waits for a response.
@@ -173,6 +234,7 @@ impl RequestSender {
     /// # Errors
     ///
     /// Returns an error if:
+    /// - The client did not advertise the capability the method requires
     /// - The transport send fails
     /// - The request times out (based on budget)
     /// - The client returns an error response
@@ -183,6 +245,8 @@ impl RequestSender {
         method: &str,
         params: serde_json::Value,
     ) -> McpResult<T> {
+        self.check_client_capability(method)?;
+
         let id = self.pending.next_request_id();
         let receiver = self.pending.register(id.clone());
 
@@ -358,7 +422,7 @@ impl ElicitationSender for TransportElicitationSender {
 
             let result: fastmcp_protocol::ElicitResult =
                 self.sender
-                    .send_request(&cx, "elicitation/elicit", params_value)?;
+                    .send_request(&cx, "elicitation/create", params_value)?;
 
             // Convert HashMap<String, ElicitContentValue> to HashMap<String, serde_json::Value>
             let content = result.content.map(|content_map| {
@@ -496,6 +560,125 @@ mod tests {
         assert!(result2.is_err());
     }
 
+    #[test]
+    fn test_request_ids_are_prefixed_and_unique() {
+        let pending = PendingRequests::new();
+        assert_eq!(pending.next_request_id(), RequestId::from("srv-1"));
+        assert_eq!(pending.next_request_id(), RequestId::from("srv-2"));
+
+        let custom = PendingRequests::with_id_prefix("fastmcp-server:");
+        assert_eq!(
+            custom.next_request_id(),
+            RequestId::from("fastmcp-server:1")
+        );
+    }
+
+    #[test]
+    fn test_concurrent_requests_route_to_their_waiters() {
+        let pending = Arc::new(PendingRequests::new());
+        let (outbound_tx, outbound_rx) = std::sync::mpsc::channel::<JsonRpcRequest>();
+        let outbound_tx = Mutex::new(outbound_tx);
+        let send_fn: TransportSendFn = Arc::new(move |message| {
+            if let JsonRpcMessage::Request(request) = message {
+                outbound_tx.lock().unwrap().send(request.clone()).unwrap();
+            }
+            Ok(())
+        });
+        let sender = RequestSender::new(Arc::clone(&pending), send_fn);
+
+        let waiters: Vec<_> = (0..8)
+            .map(|n| {
+                let sender = sender.clone();
+                std::thread::spawn(move || {
+                    let value: serde_json::Value = sender
+                        .send_request(
+                            &Cx::for_testing(),
+                            "roots/list",
+                            serde_json::json!({ "n": n }),
+                        )
+                        .unwrap();
+                    (n, value)
+                })
+            })
+            .collect();
+
+        // Play the client: collect every request, then answer in reverse order.
+        let mut requests: Vec<JsonRpcRequest> =
+            (0..8).map(|_| outbound_rx.recv().unwrap()).collect();
+        requests.reverse();
+        for request in requests {
+            let id = request.id.expect("request id");
+            assert!(
+                matches!(&id, RequestId::String(s) if s.starts_with(DEFAULT_REQUEST_ID_PREFIX)),
+                "unexpected id {id}"
+            );
+            let echo = request.params.expect("params")["n"].clone();
+            assert!(pending.route_response(&JsonRpcResponse::success(
+                id,
+                serde_json::json!({ "echo": echo }),
+            )));
+        }
+
+        for waiter in waiters {
+            let (n, value) = waiter.join().unwrap();
+            assert_eq!(value, serde_json::json!({ "echo": n }));
+        }
+    }
+
+    /// A request sender whose transport records sent methods and never answers.
+    fn recording_sender() -> (RequestSender, Arc<Mutex<Vec<String>>>) {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let recorder = Arc::clone(&sent);
+        let send_fn: TransportSendFn = Arc::new(move |message| {
+            if let JsonRpcMessage::Request(request) = message {
+                recorder.lock().unwrap().push(request.method.clone());
+            }
+            Err("transport closed".to_string())
+        });
+        (
+            RequestSender::new(Arc::new(PendingRequests::new()), send_fn),
+            sent,
+        )
+    }
+
+    #[test]
+    fn test_unadvertised_capability_fails_without_sending() {
+        let (sender, sent) = recording_sender();
+        let sender = sender.with_client_capabilities(ClientCapabilities::default());
+
+        let err = TransportRootsProvider::new(sender.clone())
+            .list_roots()
+            .unwrap_err();
+        assert_eq!(err.code, McpErrorCode::InvalidRequest);
+        assert!(err.message.contains("'roots'"), "{}", err.message);
+
+        let err = sender
+            .send_request::<serde_json::Value>(
+                &Cx::for_testing(),
+                "sampling/createMessage",
+                serde_json::json!({}),
+            )
+            .unwrap_err();
+        assert!(err.message.contains("'sampling'"), "{}", err.message);
+        assert!(sent.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_advertised_capability_is_sent() {
+        let (sender, sent) = recording_sender();
+        let sender = sender.with_client_capabilities(ClientCapabilities {
+            roots: Some(fastmcp_protocol::RootsCapability::default()),
+            ..ClientCapabilities::default()
+        });
+
+        // The request reaches the transport (which then fails)
+        let err = TransportRootsProvider::new(sender)
+            .list_roots()
+            .unwrap_err();
+        assert!(err.message.contains("Failed to send"), "{}", err.message);
+        assert_eq!(*sent.lock().unwrap(), ["roots/list"]);
+    }
+
     #[test]
     fn test_route_unknown_response() {
         let pending = PendingRequests::new();
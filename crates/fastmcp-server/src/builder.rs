@@ -1,54 +1,133 @@
 //! Server builder for configuring MCP servers.
 
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
 
+use fastmcp_console::banner::BuildInfo;
 use fastmcp_console::config::{BannerStyle, ConsoleConfig, TrafficVerbosity};
 use fastmcp_console::stats::ServerStats;
+use fastmcp_core::{McpContext, McpError, McpResult, MissingCapabilityPolicy};
 use fastmcp_protocol::{
-    LoggingCapability, PromptsCapability, ResourceTemplate, ResourcesCapability,
-    ServerCapabilities, ServerInfo, TasksCapability, ToolsCapability,
+    ClientCapabilities, ClientInfo, LoggingCapability, PromptsCapability, ResourceTemplate,
+    ResourcesCapability, ServerCapabilities, ServerInfo, TasksCapability, ToolsCapability, schema,
 };
+use fastmcp_transport::compression::CompressionConfig;
 use log::{Level, LevelFilter};
+use serde_json::Value;
 
+use crate::auth::ProtectedResource;
+use crate::health;
 use crate::proxy::{ProxyPromptHandler, ProxyResourceHandler, ProxyToolHandler};
+use crate::rate_limiting::SubjectRateLimiter;
 use crate::tasks::SharedTaskManager;
 use crate::{
-    AuthProvider, DuplicateBehavior, LifespanHooks, LoggingConfig, PromptHandler, ProxyCatalog,
-    ProxyClient, ResourceHandler, Router, Server, ToolHandler,
+    AuditSink, AuthProvider, Clock, CustomMethodHandler, DEFAULT_MAX_CONTENT_ITEMS,
+    DEFAULT_MAX_RESULT_BYTES, DuplicateBehavior, FallbackToolHandler, FlushPolicy,
+    InflightOverflow, InstructionsFn, LifespanHooks, LoggingConfig, PromptHandler, ProxyCatalog,
+    ProxyClient, ResourceHandler, Router, Server, ShutdownReason, SystemClock, ToolHandler,
 };
 
 /// Default request timeout in seconds.
 const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 30;
 
+/// Default nesting limit for request params.
+const DEFAULT_MAX_JSON_DEPTH: usize = 128;
+
+/// Default size limit for incoming messages, in bytes (16 MiB).
+const DEFAULT_MAX_JSON_SIZE: usize = 16 * 1024 * 1024;
+
 /// Builder for configuring an MCP server.
 pub struct ServerBuilder {
     info: ServerInfo,
     capabilities: ServerCapabilities,
     router: Router,
     instructions: Option<String>,
+    /// Computes instructions per session.
+    instructions_fn: Option<InstructionsFn>,
     /// Request timeout in seconds (0 = no timeout).
     request_timeout_secs: u64,
     /// Whether to enable statistics collection.
     stats_enabled: bool,
+    /// Time source for server uptime.
+    clock: Arc<dyn Clock>,
     /// Whether to mask internal error details in responses.
     mask_error_details: bool,
     /// Logging configuration.
     logging: LoggingConfig,
     /// Console configuration for rich output.
     console_config: ConsoleConfig,
+    /// Build provenance for the banner, ready event and health reports.
+    build_info: Option<BuildInfo>,
     /// Lifecycle hooks for startup/shutdown.
     lifespan: LifespanHooks,
     /// Optional authentication provider.
     auth_provider: Option<Arc<dyn AuthProvider>>,
+    /// Authorization servers and scopes advertised to clients.
+    protected_resource: ProtectedResource,
     /// Registered middleware.
     middleware: Vec<Box<dyn crate::Middleware>>,
     /// Optional task manager for background tasks (Docket/SEP-1686).
     task_manager: Option<SharedTaskManager>,
     /// Behavior when registering duplicate component names.
     on_duplicate: DuplicateBehavior,
+    /// Whether any duplicate registration fails `try_build`.
+    strict_registration: bool,
+    /// Components registered under a name or URI already taken.
+    duplicates: Vec<String>,
     /// Whether to use strict input validation (reject extra properties).
     strict_input_validation: bool,
+    /// Whether to coerce scalar argument mismatches before validation.
+    coerce_arguments: bool,
+    /// Whether `build` checks that tool input schemas are well-formed.
+    validate_tool_schemas: bool,
+    /// Whether lossless number handling was requested.
+    arbitrary_precision: bool,
+    /// Maximum size in bytes of a single `resources/read` response.
+    max_resource_bytes: Option<usize>,
+    /// Entries per `*/list` page.
+    list_page_size: Option<usize>,
+    /// Maximum number of content items in a `tools/call` result.
+    max_content_items: usize,
+    /// Maximum serialized size of a `tools/call` result's content.
+    max_result_bytes: usize,
+    /// What handler context helpers do when the client lacks a capability.
+    missing_capability_policy: MissingCapabilityPolicy,
+    /// Close the connection after this many consecutive unparseable frames.
+    max_consecutive_parse_errors: Option<u32>,
+    /// Receives an audit event for every completed request.
+    audit_sink: Option<Arc<dyn AuditSink>>,
+    /// Maximum nesting depth of request params.
+    max_json_depth: usize,
+    /// Maximum size of an incoming message, in bytes.
+    max_json_size: usize,
+    /// Maximum number of clients served at once by the accept loops.
+    max_connections: Option<usize>,
+    /// Maximum number of concurrently active requests.
+    max_inflight_requests: Option<usize>,
+    /// What to do with requests over the in-flight cap.
+    inflight_overflow: InflightOverflow,
+    /// Whether `run_*` installs SIGINT/SIGTERM handlers.
+    handle_signals: bool,
+    /// Handlers for custom JSON-RPC methods.
+    custom_methods: HashMap<String, CustomMethodHandler>,
+    /// Methods rejected with `method_not_found`.
+    disabled_methods: HashSet<String>,
+    /// Unknown notifications with these prefixes are ignored at trace level.
+    ignored_notification_prefixes: Vec<String>,
+    /// Per-subject request rate limit.
+    rate_limiter: Option<SubjectRateLimiter>,
+    /// Prefix for ids of server-to-client requests.
+    request_id_prefix: String,
+    /// Whether stdio output is written as canonical JSON.
+    canonical_responses: bool,
+    /// When stdio notifications are written out.
+    notification_flush: FlushPolicy,
+    /// HTTP/SSE response compression (None = off).
+    compression: Option<CompressionConfig>,
+    /// Whether `listChanged` is advertised for tools, resources, and prompts.
+    list_changed_notifications: bool,
 }
 
 impl ServerBuilder {
@@ -72,17 +151,48 @@ impl ServerBuilder {
             },
             router: Router::new(),
             instructions: None,
+            instructions_fn: None,
             request_timeout_secs: DEFAULT_REQUEST_TIMEOUT_SECS,
             stats_enabled: true,
+            clock: Arc::new(SystemClock),
             mask_error_details: false, // Disabled by default for development
             logging: LoggingConfig::from_env(),
             console_config: ConsoleConfig::from_env(),
+            build_info: None,
             lifespan: LifespanHooks::default(),
             auth_provider: None,
+            protected_resource: ProtectedResource::default(),
             middleware: Vec::new(),
             task_manager: None,
             on_duplicate: DuplicateBehavior::default(),
+            strict_registration: false,
+            duplicates: Vec::new(),
             strict_input_validation: false,
+            coerce_arguments: false,
+            validate_tool_schemas: true,
+            arbitrary_precision: false,
+            max_resource_bytes: None,
+            list_page_size: None,
+            max_content_items: DEFAULT_MAX_CONTENT_ITEMS,
+            max_result_bytes: DEFAULT_MAX_RESULT_BYTES,
+            missing_capability_policy: MissingCapabilityPolicy::Error,
+            max_consecutive_parse_errors: None,
+            audit_sink: None,
+            max_json_depth: DEFAULT_MAX_JSON_DEPTH,
+            max_json_size: DEFAULT_MAX_JSON_SIZE,
+            max_connections: None,
+            max_inflight_requests: None,
+            inflight_overflow: InflightOverflow::default(),
+            handle_signals: true,
+            custom_methods: HashMap::new(),
+            disabled_methods: HashSet::new(),
+            ignored_notification_prefixes: vec!["notifications/".to_string()],
+            rate_limiter: None,
+            request_id_prefix: crate::bidirectional::DEFAULT_REQUEST_ID_PREFIX.to_string(),
+            canonical_responses: false,
+            notification_flush: FlushPolicy::Always,
+            compression: None,
+            list_changed_notifications: false,
         }
     }
 
@@ -111,6 +221,280 @@ impl ServerBuilder {
         self
     }
 
+    /// Makes duplicate registrations fail the build.
+    ///
+    /// With `strict` set, [`try_build`](Self::try_build) returns an error
+    /// listing every tool, resource or prompt registered under a name (or,
+    /// for resources, a URI) that was already taken, whatever the
+    /// [`on_duplicate`](Self::on_duplicate) behavior. Otherwise duplicates
+    /// are resolved by that behavior and the build goes ahead.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let result = Server::new("demo", "1.0")
+    ///     .strict_registration(true)
+    ///     .tool(handler1)
+    ///     .tool(handler2)  // Same name
+    ///     .try_build();
+    /// assert!(result.is_err());
+    /// ```
+    #[must_use]
+    pub fn strict_registration(mut self, strict: bool) -> Self {
+        self.strict_registration = strict;
+        self
+    }
+
+    /// Caches successful results of the named tool for `ttl`.
+    ///
+    /// Repeated calls with the same arguments within the TTL return the
+    /// cached result without invoking the handler. Arguments are compared
+    /// with object keys sorted, so key order does not cause misses. Error
+    /// results are never cached. Use this only for idempotent tools.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Server::new("demo", "1.0")
+    ///     .tool(FetchDataTool)
+    ///     .cache_tool("fetch_data", Duration::from_secs(60))
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn cache_tool(mut self, name: impl Into<String>, ttl: Duration) -> Self {
+        self.router.set_tool_cache_ttl(name, ttl);
+        self
+    }
+
+    /// Caps the number of requests the server handles at once.
+    ///
+    /// When `limit` requests are already active, a new `tools/call` or
+    /// `resources/read` is handled according to
+    /// [`inflight_overflow`](Self::inflight_overflow): rejected immediately by
+    /// default, or queued for a bounded time. Overflowing requests fail with
+    /// a `RequestCancelled` "server busy" error. Unlimited by default.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Server::new("demo", "1.0")
+    ///     .max_inflight_requests(16)
+    ///     .inflight_overflow(InflightOverflow::Queue(Duration::from_millis(500)))
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn max_inflight_requests(mut self, limit: usize) -> Self {
+        self.max_inflight_requests = Some(limit);
+        self
+    }
+
+    /// Sets how requests over [`max_inflight_requests`](Self::max_inflight_requests)
+    /// are handled.
+    #[must_use]
+    pub fn inflight_overflow(mut self, overflow: InflightOverflow) -> Self {
+        self.inflight_overflow = overflow;
+        self
+    }
+
+    /// Limits each authenticated subject to `per_subject_rps` requests per
+    /// second, with bursts up to `burst`.
+    ///
+    /// Subjects are taken from the [`AuthContext`](fastmcp_core::AuthContext)
+    /// returned by the auth provider; unauthenticated traffic shares a single
+    /// bucket. Requests over the limit fail with a `RequestCancelled` "rate
+    /// limited" error carrying a `retryAfter` hint (in seconds) in its data.
+    /// Methods exempt from authentication (`initialize`, `ping`, ...) are not
+    /// limited.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Server::new("demo", "1.0")
+    ///     .auth_provider(provider)
+    ///     .rate_limit(5.0, 10)
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn rate_limit(mut self, per_subject_rps: f64, burst: usize) -> Self {
+        self.rate_limiter = Some(SubjectRateLimiter::new(per_subject_rps, burst));
+        self
+    }
+
+    /// Sends an [`AuditEvent`](crate::AuditEvent) to `sink` for every
+    /// completed request.
+    ///
+    /// Each event carries the method, request ID, outcome, latency, and the
+    /// subject returned by the auth provider (`None` for methods exempt from
+    /// authentication or when no provider is configured). Notifications are
+    /// not audited.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Server::new("demo", "1.0")
+    ///     .auth_provider(provider)
+    ///     .audit_sink(Arc::new(FileAuditSink::open("audit.jsonl")?))
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit_sink = Some(sink);
+        self
+    }
+
+    /// Sets the prefix for ids of requests the server sends to the client
+    /// (`sampling/createMessage`, `roots/list`, `elicitation/create`).
+    ///
+    /// Ids default to `srv-1`, `srv-2`, ... Change the prefix if a client's own
+    /// id scheme could produce the same strings; responses are matched to
+    /// waiting handlers by id.
+    #[must_use]
+    pub fn request_id_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.request_id_prefix = prefix.into();
+        self
+    }
+
+    /// Enables or disables the built-in signal handler.
+    ///
+    /// By default the `run_*` entry points install a SIGINT/SIGTERM handler
+    /// (Ctrl-C on Windows) that cancels the server's `Cx` with
+    /// `CancelKind::Shutdown`, so the shutdown hook runs before the process
+    /// exits. Embedders that manage signals themselves should pass `false`
+    /// and cancel the `Cx` given to `run_*_with_cx` instead.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Server::new("embedded", "1.0")
+    ///     .handle_signals(false)
+    ///     .build()
+    ///     .run_stdio_with_cx(&cx);
+    /// ```
+    #[must_use]
+    pub fn handle_signals(mut self, enabled: bool) -> Self {
+        self.handle_signals = enabled;
+        self
+    }
+
+    /// Writes messages on stdio as canonical JSON: object keys sorted and no
+    /// insignificant whitespace.
+    ///
+    /// Logically equal responses then produce identical bytes, which makes
+    /// output diffable and hashable (e.g. for golden-file tests). Off by
+    /// default because it costs an extra conversion per message. Custom
+    /// transports can opt in with [`Codec::set_canonical`](fastmcp_transport::Codec::set_canonical).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Server::new("golden", "1.0")
+    ///     .canonical_responses(true)
+    ///     .build()
+    ///     .run_stdio();
+    /// ```
+    #[must_use]
+    pub fn canonical_responses(mut self, enabled: bool) -> Self {
+        self.canonical_responses = enabled;
+        self
+    }
+
+    /// Sets when notifications sent on stdio are written out (default
+    /// [`FlushPolicy::Always`]).
+    ///
+    /// [`FlushPolicy::Batched`] coalesces bursts, such as a tool reporting
+    /// progress thousands of times, into fewer writes while bounding how long
+    /// a notification waits. Buffered notifications are always written before
+    /// the next response, so they never arrive after it.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Server::new("indexer", "1.0")
+    ///     .notification_flush_policy(FlushPolicy::Batched {
+    ///         max_delay: Duration::from_millis(50),
+    ///         max_pending: 64,
+    ///     })
+    ///     .build()
+    ///     .run_stdio();
+    /// ```
+    #[must_use]
+    pub fn notification_flush_policy(mut self, policy: FlushPolicy) -> Self {
+        self.notification_flush = policy;
+        self
+    }
+
+    /// Advertises `listChanged` for tools, resources, and prompts.
+    ///
+    /// When enabled, a handler that enables or disables a component for its
+    /// session (e.g. [`McpContext::disable_tool`](fastmcp_core::McpContext::disable_tool))
+    /// makes the server send the matching `notifications/tools/list_changed`,
+    /// `notifications/resources/list_changed`, or
+    /// `notifications/prompts/list_changed` once the request completes.
+    /// Off by default, in which case no list-changed notification is ever
+    /// sent.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Server::new("adaptive", "1.0")
+    ///     .tool(UnlockAdminTools)
+    ///     .list_changed_notifications(true)
+    ///     .build()
+    ///     .run_stdio();
+    /// ```
+    #[must_use]
+    pub fn list_changed_notifications(mut self, enabled: bool) -> Self {
+        self.list_changed_notifications = enabled;
+        self
+    }
+
+    /// Compresses HTTP and SSE responses for clients that send
+    /// `Accept-Encoding: gzip` (or `deflate`).
+    ///
+    /// Response bodies of at least `min_size` bytes are compressed; an SSE
+    /// event stream is compressed as a whole, flushing after every event.
+    /// Compressed request bodies are decompressed whether or not this is set.
+    /// Stdio is never compressed.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Server::new("remote", "1.0")
+    ///     .compression(CompressionConfig { min_size: 1024, level: 6 })
+    ///     .build()
+    ///     .run_sse_accept("0.0.0.0:8080");
+    /// ```
+    #[must_use]
+    pub fn compression(mut self, config: CompressionConfig) -> Self {
+        self.compression = Some(config);
+        self
+    }
+
+    /// Sets the method prefixes of notifications the server may silently ignore.
+    ///
+    /// Notifications cannot be answered, so an unknown notification is never
+    /// an error for the client. Those matching one of these prefixes are
+    /// logged at trace level and any others at debug level; unknown request
+    /// methods still get a method-not-found response. The default is
+    /// `["notifications/"]`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Server::new("api", "1.0")
+    ///     .ignored_notification_prefixes(["notifications/", "x-vendor/"])
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn ignored_notification_prefixes<I, S>(mut self, prefixes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.ignored_notification_prefixes = prefixes.into_iter().map(Into::into).collect();
+        self
+    }
+
     /// Sets an authentication provider.
     #[must_use]
     pub fn auth_provider<P: AuthProvider + 'static>(mut self, provider: P) -> Self {
@@ -118,6 +502,55 @@ impl ServerBuilder {
         self
     }
 
+    /// Adds an authorization server clients can obtain tokens from.
+    ///
+    /// Configured servers are listed in
+    /// [`Server::protected_resource_metadata`], and auth provider errors then
+    /// carry an [`AuthChallenge`](fastmcp_core::AuthChallenge) with the server
+    /// name as `realm`, which HTTP transports render as a `WWW-Authenticate`
+    /// header on a `401` response. Call more than once to list several.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Server::new("files", "1.0.0")
+    ///     .auth_provider(provider)
+    ///     .authorization_server_url("https://auth.example.com")
+    ///     .resource_url("https://mcp.example.com/mcp")
+    ///     .scopes_supported(["files:read", "files:write"])
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn authorization_server_url(mut self, url: impl Into<String>) -> Self {
+        self.protected_resource
+            .authorization_servers
+            .push(url.into());
+        self
+    }
+
+    /// Sets the public URL clients use to reach this server.
+    ///
+    /// `WWW-Authenticate` challenges then name its protected resource metadata
+    /// document (see [`protected_resource_metadata_url`](crate::oauth::protected_resource_metadata_url))
+    /// as `resource_metadata`, which MCP clients fetch to discover the
+    /// authorization servers.
+    #[must_use]
+    pub fn resource_url(mut self, url: impl Into<String>) -> Self {
+        self.protected_resource.resource_url = Some(url.into());
+        self
+    }
+
+    /// Sets the scopes advertised in [`Server::protected_resource_metadata`].
+    #[must_use]
+    pub fn scopes_supported<I, S>(mut self, scopes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.protected_resource.scopes_supported = scopes.into_iter().map(Into::into).collect();
+        self
+    }
+
     /// Disables statistics collection.
     ///
     /// Use this for performance-critical scenarios where the overhead
@@ -130,6 +563,17 @@ impl ServerBuilder {
         self
     }
 
+    /// Sets the time source used for [`Server::uptime`] and for expiring
+    /// cached session auth.
+    ///
+    /// Defaults to [`SystemClock`]. Tests can supply a [`TestClock`](crate::TestClock)
+    /// to advance time without sleeping.
+    #[must_use]
+    pub fn clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
     /// Sets the request timeout in seconds.
     ///
     /// Set to 0 to disable timeout enforcement.
@@ -140,6 +584,78 @@ impl ServerBuilder {
         self
     }
 
+    /// Closes the connection after `limit` consecutive frames fail to parse.
+    ///
+    /// Every invalid frame is answered with a parse error (-32700) when its
+    /// request id can be recovered, and logged otherwise. A successfully
+    /// parsed message resets the count. By default the server never closes
+    /// the connection because of parse errors.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let server = Server::new("api", "1.0")
+    ///     .max_consecutive_parse_errors(5)
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn max_consecutive_parse_errors(mut self, limit: u32) -> Self {
+        self.max_consecutive_parse_errors = Some(limit);
+        self
+    }
+
+    /// Sets how deeply request params may nest (default 128).
+    ///
+    /// The transports the server builds reject deeper messages before
+    /// parsing them, answering with a parse error when the request id can be
+    /// recovered. The top-level params object counts as depth 1.
+    #[must_use]
+    pub fn max_json_depth(mut self, depth: usize) -> Self {
+        self.max_json_depth = depth;
+        self
+    }
+
+    /// Sets the largest incoming message accepted, in bytes (default 16 MiB).
+    ///
+    /// The transports the server builds reject larger messages before
+    /// parsing them.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let server = Server::new("api", "1.0")
+    ///     .max_json_depth(32)
+    ///     .max_json_size(1024 * 1024)
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn max_json_size(mut self, bytes: usize) -> Self {
+        self.max_json_size = bytes;
+        self
+    }
+
+    /// Limits how many clients the network accept loops serve at once.
+    ///
+    /// Applies to [`Server::run_ws_accept`](crate::Server::run_ws_accept)
+    /// and [`Server::run_sse_accept`](crate::Server::run_sse_accept). A
+    /// client connecting while `limit` others are connected is answered with
+    /// `503 Service Unavailable` instead of getting a session. By default the
+    /// number of connections is unlimited.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Server::new("api", "1.0")
+    ///     .max_connections(64)
+    ///     .build()
+    ///     .run_ws_accept("0.0.0.0:8080");
+    /// ```
+    #[must_use]
+    pub fn max_connections(mut self, limit: usize) -> Self {
+        self.max_connections = Some(limit);
+        self
+    }
+
     /// Enables or disables error detail masking.
     ///
     /// When enabled, internal error details are hidden from client responses:
@@ -246,6 +762,160 @@ impl ServerBuilder {
         self.strict_input_validation
     }
 
+    /// Enables or disables the tool input schema check at build time.
+    ///
+    /// When enabled (default), [`build`](Self::build) checks every tool's
+    /// `input_schema` with [`schema::check_schema`](fastmcp_protocol::schema::check_schema)
+    /// so a malformed schema fails at startup instead of on the first call.
+    /// Disable it to skip the check for servers with many tools, or for
+    /// proxies of servers whose schemas you don't control.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let server = Server::new("api", "1.0")
+    ///     .validate_tool_schemas(false)
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn validate_tool_schemas(mut self, enabled: bool) -> Self {
+        self.validate_tool_schemas = enabled;
+        self
+    }
+
+    /// Enables or disables schema-guided argument coercion.
+    ///
+    /// When enabled, tool arguments are coerced toward the tool's input schema
+    /// before validation: numeric strings become numbers, `"true"`/`"false"`
+    /// become booleans, and a single value becomes a one-element array where
+    /// the schema expects an array. Values that cannot be converted
+    /// unambiguously are left as-is and still fail validation.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let server = Server::new("api", "1.0")
+    ///     .coerce_arguments(true)  // Accept "5" for an integer parameter
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn coerce_arguments(mut self, enabled: bool) -> Self {
+        self.coerce_arguments = enabled;
+        self
+    }
+
+    /// Requires lossless handling of large integers and high-precision decimals.
+    ///
+    /// By default `serde_json` stores numbers as `i64`, `u64` or `f64`, so an
+    /// integer beyond `u64::MAX` or a decimal with more than ~17 significant
+    /// digits is silently rounded on its way into a tool and back out. The
+    /// `arbitrary-precision` cargo feature switches `serde_json` to keep every
+    /// number as its original decimal text, so such values round-trip intact
+    /// and integers of any size satisfy `"type": "integer"`.
+    ///
+    /// Because `serde_json` features are unified at compile time, this option
+    /// cannot turn the feature on by itself: it documents the requirement and
+    /// logs a warning at [`build`](Self::build) when the feature is missing.
+    ///
+    /// **Performance:** numbers are parsed lazily and kept as strings, so
+    /// number-heavy payloads parse and serialize noticeably slower and
+    /// `Value::as_f64` and friends re-parse on every call. Only enable it when
+    /// you need exact numbers.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Cargo.toml: fastmcp = { version = "...", features = ["arbitrary-precision"] }
+    /// let server = Server::new("ledger", "1.0")
+    ///     .arbitrary_precision(true)
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn arbitrary_precision(mut self, enabled: bool) -> Self {
+        self.arbitrary_precision = enabled;
+        self
+    }
+
+    /// Sets the maximum size in bytes of a `resources/read` response.
+    ///
+    /// Reads that exceed the limit fail with an error rather than producing
+    /// an oversized message. Clients can still fetch large resources in
+    /// pieces by passing a `range` in `resources/read`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let server = Server::new("files", "1.0")
+    ///     .max_resource_bytes(1024 * 1024) // 1 MB per read
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn max_resource_bytes(mut self, bytes: usize) -> Self {
+        self.max_resource_bytes = Some(bytes);
+        self
+    }
+
+    /// Caps the number of content items in a `tools/call` result.
+    ///
+    /// Longer results are cut to fit and end with a
+    /// [`TRUNCATION_MARKER`](crate::TRUNCATION_MARKER) text item; their
+    /// `_meta.truncation` notes the truncation. Defaults to
+    /// [`DEFAULT_MAX_CONTENT_ITEMS`]; values below 1 are raised to 1 to
+    /// leave room for the marker.
+    #[must_use]
+    pub fn max_content_items(mut self, max: usize) -> Self {
+        self.max_content_items = max.max(1);
+        self
+    }
+
+    /// Caps the serialized size in bytes of a `tools/call` result's content.
+    ///
+    /// Larger results are cut to fit, as with
+    /// [`max_content_items`](Self::max_content_items), instead of being sent
+    /// as an oversized frame. Defaults to [`DEFAULT_MAX_RESULT_BYTES`];
+    /// values too small to hold the marker alone are raised to its size.
+    #[must_use]
+    pub fn max_result_bytes(mut self, max: usize) -> Self {
+        self.max_result_bytes = max.max(crate::router::min_result_bytes());
+        self
+    }
+
+    /// Paginates `tools/list`, `resources/list` and `prompts/list`.
+    ///
+    /// Responses carry at most `page_size` entries and a `nextCursor` for the
+    /// rest. If the listing changes between pages, the next page fails with
+    /// a `cursor_invalidated` error (see [`McpError::cursor_invalidated`])
+    /// instead of skipping or repeating entries; clients restart from the
+    /// first page.
+    #[must_use]
+    pub fn list_page_size(mut self, page_size: usize) -> Self {
+        self.list_page_size = Some(page_size);
+        self
+    }
+
+    /// Sets what handler context helpers do when the client lacks the
+    /// capability they need.
+    ///
+    /// With [`MissingCapabilityPolicy::Error`] (the default) `ctx.log` and
+    /// `ctx.elicit` fail; with [`MissingCapabilityPolicy::SilentNoOp`] logs
+    /// are dropped and elicitations return a cancelled response, each noted
+    /// at debug level. `ctx.sample` errors under either policy.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let server = Server::new("telemetry", "1.0")
+    ///     .missing_capability_policy(MissingCapabilityPolicy::SilentNoOp)
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn missing_capability_policy(mut self, policy: MissingCapabilityPolicy) -> Self {
+        self.missing_capability_policy = policy;
+        self
+    }
+
     /// Registers a middleware.
     #[must_use]
     pub fn middleware<M: crate::Middleware + 'static>(mut self, middleware: M) -> Self {
@@ -260,6 +930,10 @@ impl ServerBuilder {
     /// an error will be logged and the tool will not be registered.
     #[must_use]
     pub fn tool<H: ToolHandler + 'static>(mut self, handler: H) -> Self {
+        let name = handler.definition().name;
+        if self.router.get_tool(&name).is_some() {
+            self.duplicates.push(format!("tool '{name}'"));
+        }
         if let Err(e) = self
             .router
             .add_tool_with_behavior(handler, self.on_duplicate)
@@ -271,6 +945,20 @@ impl ServerBuilder {
         self
     }
 
+    /// Sets a catch-all handler for `tools/call` requests naming no
+    /// registered tool.
+    ///
+    /// The handler receives the requested name and arguments, so a gateway
+    /// can forward unknown tools to a backend instead of answering
+    /// method-not-found. Registered tools still take precedence. See
+    /// [`FallbackToolHandler`] for how validation and listing work.
+    #[must_use]
+    pub fn fallback_tool<H: FallbackToolHandler + 'static>(mut self, handler: H) -> Self {
+        self.router.set_fallback_tool(handler);
+        self.capabilities.tools = Some(ToolsCapability::default());
+        self
+    }
+
     /// Registers a resource handler.
     ///
     /// Duplicate handling is controlled by [`on_duplicate`](Self::on_duplicate).
@@ -278,6 +966,19 @@ impl ServerBuilder {
     /// an error will be logged and the resource will not be registered.
     #[must_use]
     pub fn resource<H: ResourceHandler + 'static>(mut self, handler: H) -> Self {
+        let taken = match handler.template() {
+            Some(template) => self
+                .router
+                .get_resource_template(&template.uri_template)
+                .map(|_| template.uri_template),
+            None => {
+                let uri = handler.definition().uri;
+                self.router.get_resource(&uri).map(|_| uri)
+            }
+        };
+        if let Some(uri) = taken {
+            self.duplicates.push(format!("resource '{uri}'"));
+        }
         if let Err(e) = self
             .router
             .add_resource_with_behavior(handler, self.on_duplicate)
@@ -304,6 +1005,10 @@ impl ServerBuilder {
     /// an error will be logged and the prompt will not be registered.
     #[must_use]
     pub fn prompt<H: PromptHandler + 'static>(mut self, handler: H) -> Self {
+        let name = handler.definition().name;
+        if self.router.get_prompt(&name).is_some() {
+            self.duplicates.push(format!("prompt '{name}'"));
+        }
         if let Err(e) = self
             .router
             .add_prompt_with_behavior(handler, self.on_duplicate)
@@ -676,6 +1381,86 @@ impl ServerBuilder {
         self
     }
 
+    /// Declares an experimental capability in the initialize result.
+    ///
+    /// Use this to advertise protocol extensions, such as a custom method
+    /// namespace registered with [`custom_method`](Self::custom_method).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Server::new("demo", "1.0")
+    ///     .experimental_capability("x-search", json!({"version": 1}))
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn experimental_capability(mut self, key: impl Into<String>, value: Value) -> Self {
+        self.capabilities
+            .experimental
+            .get_or_insert_with(HashMap::new)
+            .insert(key.into(), value);
+        self
+    }
+
+    /// Registers a handler for a custom JSON-RPC method.
+    ///
+    /// Custom methods are consulted only after the built-in MCP methods, so
+    /// they cannot shadow them. Prefix names with `x-` or a vendor namespace
+    /// to avoid clashing with future protocol methods.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Server::new("demo", "1.0")
+    ///     .experimental_capability("x-search", json!({}))
+    ///     .custom_method("x-search/query", |_ctx, params| {
+    ///         Ok(json!({"hits": [], "query": params}))
+    ///     })
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn custom_method<F>(mut self, method: impl Into<String>, handler: F) -> Self
+    where
+        F: Fn(&McpContext, Option<Value>) -> McpResult<Value> + Send + Sync + 'static,
+    {
+        self.custom_methods.insert(method.into(), Box::new(handler));
+        self
+    }
+
+    /// Disables a built-in (or custom) method.
+    ///
+    /// Requests for a disabled method are answered with `method_not_found`,
+    /// and capabilities that only exist to serve it are not advertised:
+    /// disabling `logging/setLevel` drops the `logging` capability, and
+    /// disabling `resources/subscribe` clears `resources.subscribe`.
+    ///
+    /// Lifecycle methods (`initialize`, `notifications/initialized`, `ping`)
+    /// cannot be disabled; attempts are logged and ignored.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Server::new("locked-down", "1.0")
+    ///     .disable_method("logging/setLevel")
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn disable_method(mut self, method: impl Into<String>) -> Self {
+        let method = method.into();
+        if matches!(
+            method.as_str(),
+            "initialize" | "initialized" | "notifications/initialized" | "ping"
+        ) {
+            log::warn!(
+                target: "fastmcp::server",
+                "Ignoring disable_method({method}): lifecycle methods cannot be disabled"
+            );
+            return self;
+        }
+        self.disabled_methods.insert(method);
+        self
+    }
+
     /// Sets custom server instructions.
     #[must_use]
     pub fn instructions(mut self, instructions: impl Into<String>) -> Self {
@@ -683,6 +1468,34 @@ impl ServerBuilder {
         self
     }
 
+    /// Computes instructions for each session from the client's `initialize`.
+    ///
+    /// Called once per session with the client's info and capabilities; the
+    /// result is kept for the rest of the session. Returning `None` falls
+    /// back to [`instructions`](Self::instructions).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Server::new("adaptive", "1.0")
+    ///     .instructions("Use the tools to answer questions.")
+    ///     .instructions_fn(|client, _caps| {
+    ///         client
+    ///             .name
+    ///             .starts_with("legacy-")
+    ///             .then(|| "Call `search` before `fetch`.".to_string())
+    ///     })
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn instructions_fn<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&ClientInfo, &ClientCapabilities) -> Option<String> + Send + Sync + 'static,
+    {
+        self.instructions_fn = Some(Box::new(f));
+        self
+    }
+
     /// Sets the log level.
     ///
     /// Default is read from `FASTMCP_LOG` environment variable, or `INFO` if not set.
@@ -769,6 +1582,24 @@ impl ServerBuilder {
         self
     }
 
+    /// Sets which build of the server is running.
+    ///
+    /// Shown as a `Build:` line in the startup banner and included as
+    /// `build` in the `ready` event and in [`HealthReport`](crate::HealthReport)s.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let server = Server::new("api", "1.0")
+    ///     .with_build_info(BuildInfo::from_env().unwrap_or_default())
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn with_build_info(mut self, info: BuildInfo) -> Self {
+        self.build_info = Some(info).filter(|info| !info.is_empty());
+        self
+    }
+
     /// Enables request/response traffic logging.
     ///
     /// Controls the verbosity of traffic logging:
@@ -861,6 +1692,9 @@ impl ServerBuilder {
     /// Shutdown hooks are run on a best-effort basis. If the process is
     /// forcefully terminated, hooks may not run.
     ///
+    /// Use [`on_shutdown_with_reason`](Self::on_shutdown_with_reason) if the
+    /// cleanup depends on why the server is stopping.
+    ///
     /// # Example
     ///
     /// ```ignore
@@ -871,9 +1705,32 @@ impl ServerBuilder {
     ///     .run_stdio();
     /// ```
     #[must_use]
-    pub fn on_shutdown<F>(mut self, hook: F) -> Self
+    pub fn on_shutdown<F>(self, hook: F) -> Self
     where
         F: FnOnce() + Send + 'static,
+    {
+        self.on_shutdown_with_reason(move |_| hook())
+    }
+
+    /// Registers a shutdown hook that receives the [`ShutdownReason`].
+    ///
+    /// Replaces any hook set with [`on_shutdown`](Self::on_shutdown).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Server::new("demo", "1.0.0")
+    ///     .on_shutdown_with_reason(|reason| {
+    ///         if let ShutdownReason::Error(message) = reason {
+    ///             eprintln!("Flushing state after error: {message}");
+    ///         }
+    ///     })
+    ///     .run_stdio();
+    /// ```
+    #[must_use]
+    pub fn on_shutdown_with_reason<F>(mut self, hook: F) -> Self
+    where
+        F: FnOnce(&ShutdownReason) + Send + 'static,
     {
         self.lifespan.on_shutdown = Some(Box::new(hook));
         self
@@ -906,32 +1763,133 @@ impl ServerBuilder {
     }
 
     /// Builds the server.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a tool has a malformed input schema, or a component was
+    /// registered twice under [`strict_registration`](Self::strict_registration);
+    /// see [`try_build`](Self::try_build) and
+    /// [`validate_tool_schemas`](Self::validate_tool_schemas).
     #[must_use]
-    pub fn build(mut self) -> Server {
+    pub fn build(self) -> Server {
+        self.try_build().unwrap_or_else(|e| panic!("{}", e.message))
+    }
+
+    /// Builds the server, reporting a malformed tool input schema as an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an invalid-params error naming the first tool whose
+    /// `input_schema` is not a well-formed schema, or an invalid-request
+    /// error listing duplicate registrations under
+    /// [`strict_registration`](Self::strict_registration).
+    pub fn try_build(mut self) -> McpResult<Server> {
+        if self.strict_registration && !self.duplicates.is_empty() {
+            return Err(McpError::invalid_request(format!(
+                "Duplicate registrations: {}",
+                self.duplicates.join(", ")
+            )));
+        }
+
+        if self.validate_tool_schemas {
+            for tool in self.router.tools() {
+                if let Err(errors) = schema::check_schema(&tool.input_schema) {
+                    let details: Vec<String> = errors.iter().map(ToString::to_string).collect();
+                    return Err(McpError::invalid_params(format!(
+                        "Tool '{}' has an invalid input_schema: {}",
+                        tool.name,
+                        details.join("; ")
+                    )));
+                }
+            }
+        }
+
+        if self.arbitrary_precision && !cfg!(feature = "arbitrary-precision") {
+            log::warn!(
+                target: "fastmcp::server",
+                "arbitrary_precision(true) requires the `arbitrary-precision` feature; \
+                 large numbers will lose precision"
+            );
+        }
+
         // Configure router with strict input validation setting
         self.router
             .set_strict_input_validation(self.strict_input_validation);
+        self.router.set_coerce_arguments(self.coerce_arguments);
+        self.router.set_max_resource_bytes(self.max_resource_bytes);
+        self.router.set_list_page_size(self.list_page_size);
+        self.router.set_max_content_items(self.max_content_items);
+        self.router.set_max_result_bytes(self.max_result_bytes);
+        self.router
+            .set_missing_capability_policy(self.missing_capability_policy);
+
+        if self.disabled_methods.contains("logging/setLevel") {
+            self.capabilities.logging = None;
+        }
+        if self.disabled_methods.contains("resources/subscribe") {
+            if let Some(resources) = self.capabilities.resources.as_mut() {
+                resources.subscribe = false;
+            }
+        }
+        if let Some(tools) = self.capabilities.tools.as_mut() {
+            tools.list_changed = self.list_changed_notifications;
+        }
+        if let Some(resources) = self.capabilities.resources.as_mut() {
+            resources.list_changed = self.list_changed_notifications;
+        }
+        if let Some(prompts) = self.capabilities.prompts.as_mut() {
+            prompts.list_changed = self.list_changed_notifications;
+        }
+
+        let stats = if self.stats_enabled {
+            Some(ServerStats::new())
+        } else {
+            None
+        };
+        self.router.set_stats(stats.clone());
 
-        Server {
+        Ok(Server {
             info: self.info,
             capabilities: self.capabilities,
             router: self.router,
             instructions: self.instructions,
+            instructions_fn: self.instructions_fn,
             request_timeout_secs: self.request_timeout_secs,
-            stats: if self.stats_enabled {
-                Some(ServerStats::new())
-            } else {
-                None
-            },
+            stats,
             mask_error_details: self.mask_error_details,
             logging: self.logging,
             console_config: self.console_config,
+            build_info: self.build_info,
             lifespan: Mutex::new(Some(self.lifespan)),
             auth_provider: self.auth_provider,
+            protected_resource: self.protected_resource,
             middleware: Arc::new(self.middleware),
-            active_requests: Mutex::new(HashMap::new()),
+            active_requests: Mutex::new(BTreeMap::new()),
             task_manager: self.task_manager,
-            pending_requests: std::sync::Arc::new(crate::bidirectional::PendingRequests::new()),
-        }
+            pending_requests: std::sync::Arc::new(
+                crate::bidirectional::PendingRequests::with_id_prefix(self.request_id_prefix),
+            ),
+            max_consecutive_parse_errors: self.max_consecutive_parse_errors,
+            audit_sink: self.audit_sink,
+            max_json_depth: self.max_json_depth,
+            max_json_size: self.max_json_size,
+            max_connections: self.max_connections,
+            max_inflight_requests: self.max_inflight_requests,
+            inflight_overflow: self.inflight_overflow,
+            inflight_released: Condvar::new(),
+            handle_signals: self.handle_signals,
+            custom_methods: self.custom_methods,
+            disabled_methods: self.disabled_methods,
+            ignored_notification_prefixes: self.ignored_notification_prefixes,
+            rate_limiter: self.rate_limiter,
+            health: health::HealthTracker::new(),
+            started_at: Mutex::new(self.clock.now_instant()),
+            clock: self.clock,
+            size_estimate_warned: AtomicBool::new(false),
+            ready_emitted: AtomicBool::new(false),
+            canonical_responses: self.canonical_responses,
+            notification_flush: self.notification_flush,
+            compression: self.compression,
+        })
     }
 }
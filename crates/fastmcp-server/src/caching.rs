@@ -34,7 +34,7 @@ use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
 use fastmcp_core::{McpContext, McpError, McpResult};
-use fastmcp_protocol::JsonRpcRequest;
+use fastmcp_protocol::{CallToolResult, JsonRpcRequest, canonical_json};
 
 use crate::{Middleware, MiddlewareDecision};
 
@@ -96,10 +96,8 @@ fn hash_json_value(value: &serde_json::Value) -> u64 {
 
     let mut hasher = DefaultHasher::new();
 
-    // Convert to canonical JSON string for consistent hashing
-    // This handles key ordering in objects
-    let json_str = serde_json::to_string(value).unwrap_or_default();
-    json_str.hash(&mut hasher);
+    // Hash the canonical form so key order in objects does not matter
+    canonical_json(value).hash(&mut hasher);
 
     hasher.finish()
 }
@@ -684,6 +682,80 @@ impl Middleware for ResponseCachingMiddleware {
     }
 }
 
+// ============================================================================
+// Tool Result Cache
+// ============================================================================
+
+/// Per-tool cache of successful `tools/call` results.
+///
+/// Unlike [`ResponseCachingMiddleware`], which caches raw responses for any
+/// method, this cache is configured per tool with
+/// [`ServerBuilder::cache_tool`](crate::ServerBuilder::cache_tool) and lives in
+/// the router, so a hit skips argument handling and the handler entirely.
+/// Entries are keyed by tool name and canonicalized arguments, so the order
+/// of object keys in a request does not matter. Error results are never cached.
+#[derive(Debug, Default)]
+pub(crate) struct ToolResultCache {
+    /// TTL for each cached tool; tools not listed here are never cached.
+    ttls: HashMap<String, Duration>,
+    /// Cached results with their expiry, keyed by (tool name, canonical arguments).
+    entries: Mutex<HashMap<(String, String), (CallToolResult, Instant)>>,
+}
+
+impl ToolResultCache {
+    /// Enables caching for `tool` with the given TTL.
+    pub(crate) fn set_ttl(&mut self, tool: impl Into<String>, ttl: Duration) {
+        self.ttls.insert(tool.into(), ttl);
+    }
+
+    /// Returns true if results of `tool` are cached.
+    pub(crate) fn caches(&self, tool: &str) -> bool {
+        self.ttls.contains_key(tool)
+    }
+
+    /// Returns the cached result for this call, if present and unexpired.
+    pub(crate) fn get(&self, tool: &str, arguments: &serde_json::Value) -> Option<CallToolResult> {
+        if !self.caches(tool) {
+            return None;
+        }
+        let key = (tool.to_string(), canonical_json(arguments));
+        let mut entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        match entries.get(&key) {
+            Some((result, expires_at)) if Instant::now() < *expires_at => Some(result.clone()),
+            Some(_) => {
+                entries.remove(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Stores a successful result for this call. Error results are ignored.
+    pub(crate) fn insert(
+        &self,
+        tool: &str,
+        arguments: &serde_json::Value,
+        result: &CallToolResult,
+    ) {
+        if result.is_error {
+            return;
+        }
+        let Some(ttl) = self.ttls.get(tool) else {
+            return;
+        };
+        let key = (tool.to_string(), canonical_json(arguments));
+        let mut entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        entries.retain(|_, (_, expires_at)| Instant::now() < *expires_at);
+        entries.insert(key, (result.clone(), Instant::now() + *ttl));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1001,4 +1073,15 @@ mod tests {
 
         assert!((stats.hit_rate() - 75.0).abs() < 0.001);
     }
+
+    #[test]
+    fn test_canonical_json_ignores_key_order() {
+        let a = serde_json::json!({"b": 1, "a": {"y": [1, {"d": 2, "c": 3}], "x": null}});
+        let b = serde_json::json!({"a": {"x": null, "y": [1, {"c": 3, "d": 2}]}, "b": 1});
+        assert_eq!(canonical_json(&a), canonical_json(&b));
+        assert_eq!(
+            canonical_json(&a),
+            r#"{"a":{"x":null,"y":[1,{"c":3,"d":2}]},"b":1}"#
+        );
+    }
 }
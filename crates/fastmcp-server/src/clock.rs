@@ -0,0 +1,90 @@
+//! Time source for uptime and expiry checks.
+//!
+//! [`Server::uptime`](crate::Server::uptime), OAuth token and code expiry
+//! ([`OAuthServer`](crate::oauth::OAuthServer), and through it
+//! [`OidcProvider`](crate::oidc::OidcProvider)), cached session auth expiry, and
+//! [`TaskManager`](crate::TaskManager) timestamps read the current time through
+//! a [`Clock`]. Tests swap in a [`TestClock`] and advance it instead of
+//! sleeping.
+
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Source of the current time.
+pub trait Clock: Send + Sync {
+    /// Returns the current monotonic instant.
+    fn now_instant(&self) -> Instant;
+
+    /// Returns the current wall-clock time.
+    fn now_system(&self) -> SystemTime;
+}
+
+/// Clock backed by [`Instant::now`] and [`SystemTime::now`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_instant(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn now_system(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Clock that only moves when [`advance`](Self::advance) is called.
+///
+/// Clones share the same time, so a test can keep one handle and give
+/// another to the component under test.
+///
+/// # Example
+///
+/// ```ignore
+/// let clock = TestClock::new();
+/// let oauth = OAuthServer::with_defaults().with_clock(clock.clone());
+/// clock.advance(Duration::from_secs(3600));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TestClock {
+    start_instant: Instant,
+    start_system: SystemTime,
+    elapsed: Arc<Mutex<Duration>>,
+}
+
+impl TestClock {
+    /// Creates a clock frozen at the current time.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            start_instant: Instant::now(),
+            start_system: SystemTime::now(),
+            elapsed: Arc::new(Mutex::new(Duration::ZERO)),
+        }
+    }
+
+    /// Moves this clock and all its clones forward by `by`.
+    pub fn advance(&self, by: Duration) {
+        *self.elapsed.lock().unwrap_or_else(PoisonError::into_inner) += by;
+    }
+
+    fn elapsed(&self) -> Duration {
+        *self.elapsed.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Default for TestClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for TestClock {
+    fn now_instant(&self) -> Instant {
+        self.start_instant + self.elapsed()
+    }
+
+    fn now_system(&self) -> SystemTime {
+        self.start_system + self.elapsed()
+    }
+}
@@ -13,14 +13,18 @@ use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 
+use fastmcp_core::logging::Level;
 use fastmcp_core::{
-    McpContext, McpOutcome, McpResult, NotificationSender, Outcome, ProgressReporter, SessionState,
+    ContentStreamSender, LogSender, McpContext, McpError, McpOutcome, McpResult,
+    NotificationSender, Outcome, ProgressReporter, SessionState, ToolContentItem,
 };
 use fastmcp_protocol::{
-    Content, Icon, JsonRpcRequest, ProgressParams, ProgressToken, Prompt, PromptMessage, Resource,
-    ResourceContent, ResourceTemplate, Tool, ToolAnnotations,
+    Content, Icon, JsonRpcRequest, LogLevel, LogMessageParams, ProgressParams, ProgressToken,
+    Prompt, PromptMessage, RequestId, Resource, ResourceContent, ResourceRange, ResourceTemplate,
+    Tool, ToolAnnotations, ToolContentChunkParams,
 };
 
 // ============================================================================
@@ -96,6 +100,171 @@ where
     }
 }
 
+// ============================================================================
+// Tool Content Stream Sender
+// ============================================================================
+
+/// A content stream that forwards tool output chunks as notifications.
+///
+/// Each chunk becomes a `notifications/tools/content` notification tagged
+/// with the id of the `tools/call` request and a running sequence number.
+pub struct ToolContentNotificationSender<F>
+where
+    F: Fn(JsonRpcRequest) + Send + Sync,
+{
+    /// ID of the `tools/call` request being streamed.
+    request_id: RequestId,
+    /// Sequence number of the next chunk.
+    sequence: AtomicU64,
+    /// Callback to send notifications.
+    send_fn: F,
+}
+
+impl<F> ToolContentNotificationSender<F>
+where
+    F: Fn(JsonRpcRequest) + Send + Sync,
+{
+    /// Creates a new content stream for the given request.
+    pub fn new(request_id: RequestId, send_fn: F) -> Self {
+        Self {
+            request_id,
+            sequence: AtomicU64::new(0),
+            send_fn,
+        }
+    }
+}
+
+impl<F> ContentStreamSender for ToolContentNotificationSender<F>
+where
+    F: Fn(JsonRpcRequest) + Send + Sync,
+{
+    fn send_content(&self, content: Vec<ToolContentItem>) {
+        let params = ToolContentChunkParams {
+            request_id: self.request_id.clone(),
+            sequence: self.sequence.fetch_add(1, Ordering::Relaxed),
+            content: content.into_iter().map(content_from_item).collect(),
+        };
+        let notification = JsonRpcRequest::notification(
+            "notifications/tools/content",
+            Some(serde_json::to_value(&params).unwrap_or_default()),
+        );
+
+        (self.send_fn)(notification);
+    }
+}
+
+impl<F> std::fmt::Debug for ToolContentNotificationSender<F>
+where
+    F: Fn(JsonRpcRequest) + Send + Sync,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ToolContentNotificationSender")
+            .field("request_id", &self.request_id)
+            .field("sequence", &self.sequence.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+// ============================================================================
+// Log Message Sender
+// ============================================================================
+
+/// A log sender that forwards handler log messages as notifications.
+///
+/// Each message at or above the session's `logging/setLevel` level becomes a
+/// `notifications/message` notification; quieter messages are dropped.
+pub struct LogNotificationSender<F>
+where
+    F: Fn(JsonRpcRequest) + Send + Sync,
+{
+    /// Lowest level the client asked to receive.
+    min_level: LogLevel,
+    /// Callback to send notifications.
+    send_fn: F,
+}
+
+impl<F> LogNotificationSender<F>
+where
+    F: Fn(JsonRpcRequest) + Send + Sync,
+{
+    /// Creates a new log sender passing messages at `min_level` and above.
+    pub fn new(min_level: LogLevel, send_fn: F) -> Self {
+        Self { min_level, send_fn }
+    }
+}
+
+impl<F> LogSender for LogNotificationSender<F>
+where
+    F: Fn(JsonRpcRequest) + Send + Sync,
+{
+    fn send_log(&self, level: Level, logger: Option<&str>, data: serde_json::Value) {
+        let level = match level {
+            Level::Error => LogLevel::Error,
+            Level::Warn => LogLevel::Warning,
+            Level::Info => LogLevel::Info,
+            Level::Debug | Level::Trace => LogLevel::Debug,
+        };
+        if log_level_rank(level) < log_level_rank(self.min_level) {
+            return;
+        }
+
+        let params = LogMessageParams {
+            level,
+            logger: logger.map(str::to_string),
+            data,
+        };
+        let notification = JsonRpcRequest::notification(
+            "notifications/message",
+            Some(serde_json::to_value(&params).unwrap_or_default()),
+        );
+
+        (self.send_fn)(notification);
+    }
+}
+
+impl<F> std::fmt::Debug for LogNotificationSender<F>
+where
+    F: Fn(JsonRpcRequest) + Send + Sync,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LogNotificationSender")
+            .field("min_level", &self.min_level)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Orders log levels from least (debug) to most (error) severe.
+pub(crate) fn log_level_rank(level: LogLevel) -> u8 {
+    match level {
+        LogLevel::Debug => 1,
+        LogLevel::Info => 2,
+        LogLevel::Warning => 3,
+        LogLevel::Error => 4,
+    }
+}
+
+/// Converts a core content item into protocol content.
+pub(crate) fn content_from_item(item: ToolContentItem) -> Content {
+    match item {
+        ToolContentItem::Text { text } => Content::Text { text },
+        ToolContentItem::Image { data, mime_type } => Content::Image { data, mime_type },
+        ToolContentItem::Resource {
+            uri,
+            mime_type,
+            text,
+        } => Content::Resource {
+            resource: ResourceContent {
+                uri,
+                mime_type,
+                text,
+                blob: None,
+                etag: None,
+                last_modified: None,
+            },
+        },
+    }
+}
+
 /// Configuration for bidirectional senders to attach to context.
 #[derive(Clone, Default)]
 pub struct BidirectionalSenders {
@@ -103,6 +272,8 @@ pub struct BidirectionalSenders {
     pub sampling: Option<Arc<dyn fastmcp_core::SamplingSender>>,
     /// Optional elicitation sender for user input requests.
     pub elicitation: Option<Arc<dyn fastmcp_core::ElicitationSender>>,
+    /// Optional sender for log messages to the client.
+    pub log: Option<Arc<dyn LogSender>>,
 }
 
 impl BidirectionalSenders {
@@ -125,6 +296,13 @@ impl BidirectionalSenders {
         self.elicitation = Some(sender);
         self
     }
+
+    /// Sets the log sender.
+    #[must_use]
+    pub fn with_log(mut self, sender: Arc<dyn LogSender>) -> Self {
+        self.log = Some(sender);
+        self
+    }
 }
 
 impl std::fmt::Debug for BidirectionalSenders {
@@ -132,6 +310,7 @@ impl std::fmt::Debug for BidirectionalSenders {
         f.debug_struct("BidirectionalSenders")
             .field("sampling", &self.sampling.is_some())
             .field("elicitation", &self.elicitation.is_some())
+            .field("log", &self.log.is_some())
             .finish()
     }
 }
@@ -183,6 +362,9 @@ where
         if let Some(ref elicitation) = senders.elicitation {
             ctx = ctx.with_elicitation(elicitation.clone());
         }
+        if let Some(ref log) = senders.log {
+            ctx = ctx.with_log_sender(log.clone());
+        }
     }
 
     ctx
@@ -242,8 +424,8 @@ pub trait ToolHandler: Send + Sync {
     /// Returns the tool's annotations providing behavioral hints.
     ///
     /// Default implementation returns `None`. Override to provide annotations
-    /// like `destructive`, `idempotent`, `read_only`, or `open_world_hint`.
-    /// Note: Annotations can also be set directly in `definition()`.
+    /// like `readOnlyHint`, `destructiveHint`, `idempotentHint`, or `openWorldHint`.
+    /// Annotations set directly in `definition()` take precedence.
     fn annotations(&self) -> Option<&ToolAnnotations> {
         None
     }
@@ -262,8 +444,12 @@ pub trait ToolHandler: Send + Sync {
     /// Default implementation returns `None`, meaning the server's default
     /// timeout applies. Override to specify a per-handler timeout.
     ///
-    /// When set, creates a child budget with the specified timeout that
-    /// overrides the server's default timeout for this handler.
+    /// The effective limit is the tighter of this timeout and the time left
+    /// in the request budget. When the tool's own timeout is tighter, the call is
+    /// cancelled once it has run that long and fails with a "Tool timeout"
+    /// error instead of consuming the rest of the request budget. The request's
+    /// `Cx` is cancelled at that point, so the handler stops at its next
+    /// cancel-aware await (such as `ctx.sleep()`) as well as at `checkpoint()`.
     fn timeout(&self) -> Option<Duration> {
         None
     }
@@ -299,6 +485,62 @@ pub trait ToolHandler: Send + Sync {
     }
 }
 
+/// Catch-all handler for `tools/call` requests naming no registered tool.
+///
+/// Gateways and proxies use this to forward unknown tool names to a backend
+/// instead of answering method-not-found. Register one with
+/// [`ServerBuilder::fallback_tool`](crate::ServerBuilder::fallback_tool).
+///
+/// Arguments are validated only against a schema returned by
+/// [`input_schema`](Self::input_schema), and the fallback's tools appear in
+/// `tools/list` only if it enumerates them with
+/// [`list_tools`](Self::list_tools). Results go through the same path as
+/// registered tools, so errors, result formats, and cancellation behave the
+/// same.
+pub trait FallbackToolHandler: Send + Sync {
+    /// Returns the input schema for tool `name`, if known.
+    ///
+    /// Default implementation returns `None`, so arguments are passed
+    /// through unvalidated.
+    fn input_schema(&self, name: &str) -> Option<serde_json::Value> {
+        let _ = name;
+        None
+    }
+
+    /// Returns the tools to include in `tools/list`.
+    ///
+    /// Default implementation returns none. Tools with the same name as a
+    /// registered tool are left out.
+    fn list_tools(&self) -> Vec<Tool> {
+        Vec::new()
+    }
+
+    /// Calls tool `name` synchronously with the given arguments.
+    fn call(
+        &self,
+        ctx: &McpContext,
+        name: &str,
+        arguments: serde_json::Value,
+    ) -> McpResult<Vec<Content>>;
+
+    /// Calls tool `name` asynchronously with the given arguments.
+    ///
+    /// The default implementation delegates to the sync `call()` method.
+    fn call_async<'a>(
+        &'a self,
+        ctx: &'a McpContext,
+        name: &'a str,
+        arguments: serde_json::Value,
+    ) -> BoxFuture<'a, McpOutcome<Vec<Content>>> {
+        Box::pin(async move {
+            match self.call(ctx, name, arguments) {
+                Ok(v) => Outcome::Ok(v),
+                Err(e) => Outcome::Err(e),
+            }
+        })
+    }
+}
+
 /// Handler for a resource.
 ///
 /// This trait is typically implemented via the `#[resource]` macro.
@@ -318,10 +560,35 @@ pub trait ResourceHandler: Send + Sync {
     fn definition(&self) -> Resource;
 
     /// Returns the resource template definition, if this resource uses a URI template.
+    ///
+    /// Placeholders may declare a type, e.g. `item://{id:int}`. Supported types
+    /// are `string` (the default for `{name}`), `int`, `float` and `bool`; the
+    /// router rejects URIs whose values don't parse with `invalid_params`.
     fn template(&self) -> Option<ResourceTemplate> {
         None
     }
 
+    /// Lists the concrete resources this template currently produces.
+    ///
+    /// Only consulted for handlers with a [`template`](Self::template). The
+    /// returned resources are included in `resources/list` (and paginated
+    /// with it). Return `None` (the default) when the instances can't
+    /// feasibly be enumerated.
+    fn expand(&self) -> Option<Vec<Resource>> {
+        None
+    }
+
+    /// Returns the size in bytes of the resource at `uri`, if it is known
+    /// without reading the content.
+    ///
+    /// The router uses this to enforce its maximum resource size before
+    /// reading, so an oversized resource is never loaded into memory. Return
+    /// `None` (the default) when the size is unknown; the limit is then
+    /// checked against the content after the read.
+    fn content_len(&self, _uri: &str, _params: &UriParams) -> Option<u64> {
+        None
+    }
+
     /// Returns the resource's icon, if any.
     ///
     /// Default implementation returns `None`. Override to provide an icon.
@@ -413,6 +680,64 @@ pub trait ResourceHandler: Send + Sync {
             }
         })
     }
+
+    /// Reads a byte range of the resource.
+    ///
+    /// The router calls this instead of `read_async_with_uri()` when the
+    /// `resources/read` request carries a `range`.
+    ///
+    /// The default implementation reads the whole resource and slices its text
+    /// content; binary content is rejected. Override this to read only the
+    /// requested bytes (e.g., by seeking in a file).
+    fn read_with_range<'a>(
+        &'a self,
+        ctx: &'a McpContext,
+        uri: &'a str,
+        params: &'a UriParams,
+        range: ResourceRange,
+    ) -> BoxFuture<'a, McpOutcome<Vec<ResourceContent>>> {
+        Box::pin(async move {
+            match self.read_async_with_uri(ctx, uri, params).await {
+                Outcome::Ok(contents) => match slice_text_contents(contents, range) {
+                    Ok(v) => Outcome::Ok(v),
+                    Err(e) => Outcome::Err(e),
+                },
+                other => other,
+            }
+        })
+    }
+}
+
+/// Applies a byte range to text resource contents.
+///
+/// Ranges that split a UTF-8 character are rejected, as is blob content,
+/// which cannot be sliced without decoding it first.
+fn slice_text_contents(
+    contents: Vec<ResourceContent>,
+    range: ResourceRange,
+) -> McpResult<Vec<ResourceContent>> {
+    contents
+        .into_iter()
+        .map(|mut content| {
+            if content.blob.is_some() {
+                return Err(McpError::invalid_params(format!(
+                    "Resource '{}' is binary and does not support range reads",
+                    content.uri
+                )));
+            }
+            if let Some(text) = content.text.take() {
+                let (start, end) = range.resolve(text.len() as u64);
+                let (start, end) = (start as usize, end as usize);
+                if !text.is_char_boundary(start) || !text.is_char_boundary(end) {
+                    return Err(McpError::invalid_params(
+                        "Range does not fall on UTF-8 character boundaries",
+                    ));
+                }
+                content.text = Some(text[start..end].to_string());
+            }
+            Ok(content)
+        })
+        .collect()
 }
 
 /// Handler for a prompt.
@@ -506,6 +831,9 @@ pub type BoxedResourceHandler = Box<dyn ResourceHandler>;
 /// A boxed prompt handler.
 pub type BoxedPromptHandler = Box<dyn PromptHandler>;
 
+/// A boxed fallback tool handler.
+pub type BoxedFallbackToolHandler = Box<dyn FallbackToolHandler>;
+
 // ============================================================================
 // Mounted Handler Wrappers
 // ============================================================================
@@ -644,6 +972,20 @@ impl ResourceHandler for MountedResourceHandler {
     ) -> BoxFuture<'a, McpOutcome<Vec<ResourceContent>>> {
         self.inner.read_async(ctx)
     }
+
+    fn read_with_range<'a>(
+        &'a self,
+        ctx: &'a McpContext,
+        uri: &'a str,
+        params: &'a UriParams,
+        range: ResourceRange,
+    ) -> BoxFuture<'a, McpOutcome<Vec<ResourceContent>>> {
+        self.inner.read_with_range(ctx, uri, params, range)
+    }
+
+    fn content_len(&self, uri: &str, params: &UriParams) -> Option<u64> {
+        self.inner.content_len(uri, params)
+    }
 }
 
 /// A wrapper for a prompt handler that overrides its name.
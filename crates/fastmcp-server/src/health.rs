@@ -0,0 +1,282 @@
+//! Health reporting for orchestrator readiness probes.
+//!
+//! `ping` only proves the message loop is alive. The `health/check` method
+//! (and [`Server::health`](crate::Server::health)) returns a [`HealthReport`]
+//! with uptime, initialization state, load, and a [`HealthStatus`] derived from
+//! the error rate over the most recent requests, so liveness and readiness
+//! probes can be told apart. The last few failures are kept as
+//! [`RecentError`]s (see [`Server::recent_errors`](crate::Server::recent_errors))
+//! so operators can see what went wrong without scraping logs.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+use fastmcp_console::banner::BuildInfo;
+use serde::Serialize;
+
+/// Number of recent request outcomes used to compute the error rate.
+const RECENT_WINDOW: usize = 100;
+
+/// Minimum number of recent outcomes before the server can report degraded.
+const MIN_SAMPLES: usize = 5;
+
+/// Error rate at or above which the server reports degraded.
+const DEGRADED_ERROR_RATE: f64 = 0.5;
+
+/// Number of failed requests kept for [`RecentError`] diagnostics.
+pub(crate) const RECENT_ERRORS_CAPACITY: usize = 16;
+
+/// Overall health classification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    /// Recent requests are mostly succeeding.
+    Ok,
+    /// A large share of recent requests failed.
+    Degraded,
+}
+
+/// Snapshot returned by `health/check` and [`Server::health`](crate::Server::health).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    /// Health derived from the recent error rate.
+    pub status: HealthStatus,
+    /// Seconds since the server started running (see
+    /// [`Server::uptime`](crate::Server::uptime)).
+    pub uptime_secs: f64,
+    /// Whether the probing session has completed `initialize`. From
+    /// [`Server::health`](crate::Server::health), whether any session has.
+    pub initialized: bool,
+    /// Requests currently being handled, excluding the health probe itself.
+    pub active_requests: usize,
+    /// Requests handled since startup, excluding health probes.
+    pub total_requests: u64,
+    /// Fraction of the last requests (up to 100) that failed.
+    pub recent_error_rate: f64,
+    /// Message of the most recent failed request, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// The most recent failures (up to 16), oldest first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub recent_errors: Vec<RecentError>,
+    /// Which build is running, if set with
+    /// [`ServerBuilder::with_build_info`](crate::ServerBuilder::with_build_info).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build: Option<BuildInfo>,
+}
+
+/// A failed request, as kept by [`Server::recent_errors`](crate::Server::recent_errors).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentError {
+    /// JSON-RPC method of the failed request.
+    pub method: String,
+    /// JSON-RPC error code.
+    pub code: i32,
+    /// Error message, masked like the response if error masking is on.
+    pub message: String,
+    /// When the failure was recorded (RFC 3339).
+    pub timestamp: String,
+}
+
+/// Records request outcomes for [`HealthReport`]s.
+#[derive(Debug)]
+pub(crate) struct HealthTracker {
+    any_initialized: AtomicBool,
+    total_requests: AtomicU64,
+    recent: Mutex<RecentOutcomes>,
+}
+
+#[derive(Debug, Default)]
+struct RecentOutcomes {
+    /// `true` for each failed request, oldest first.
+    failures: VecDeque<bool>,
+    last_error: Option<String>,
+    /// Most recent failures, oldest first.
+    errors: VecDeque<RecentError>,
+}
+
+impl HealthTracker {
+    pub(crate) fn new() -> Self {
+        Self {
+            any_initialized: AtomicBool::new(false),
+            total_requests: AtomicU64::new(0),
+            recent: Mutex::new(RecentOutcomes::default()),
+        }
+    }
+
+    /// Notes that a session completed `initialize`.
+    pub(crate) fn mark_initialized(&self) {
+        self.any_initialized.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether any session has completed `initialize`.
+    pub(crate) fn any_session_initialized(&self) -> bool {
+        self.any_initialized.load(Ordering::Relaxed)
+    }
+
+    /// Records the outcome of one handled request.
+    pub(crate) fn record(&self, error: Option<&str>) {
+        self.total_requests.fetch_add(1, Ordering::Relaxed);
+        let mut recent = self
+            .recent
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if recent.failures.len() == RECENT_WINDOW {
+            recent.failures.pop_front();
+        }
+        recent.failures.push_back(error.is_some());
+        if let Some(message) = error {
+            recent.last_error = Some(message.to_string());
+        }
+    }
+
+    /// Keeps `error` among the recent errors, evicting the oldest when full.
+    pub(crate) fn record_error(&self, error: RecentError) {
+        let mut recent = self
+            .recent
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if recent.errors.len() == RECENT_ERRORS_CAPACITY {
+            recent.errors.pop_front();
+        }
+        recent.errors.push_back(error);
+    }
+
+    /// Returns the recent errors, oldest first.
+    pub(crate) fn recent_errors(&self) -> Vec<RecentError> {
+        self.recent
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .errors
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Builds a report from the recorded outcomes.
+    pub(crate) fn report(
+        &self,
+        initialized: bool,
+        active_requests: usize,
+        uptime: Duration,
+        build: Option<BuildInfo>,
+    ) -> HealthReport {
+        let recent = self
+            .recent
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let samples = recent.failures.len();
+        let failures = recent.failures.iter().filter(|failed| **failed).count();
+        #[allow(clippy::cast_precision_loss)]
+        let recent_error_rate = if samples == 0 {
+            0.0
+        } else {
+            failures as f64 / samples as f64
+        };
+        let status = if samples >= MIN_SAMPLES && recent_error_rate >= DEGRADED_ERROR_RATE {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Ok
+        };
+
+        HealthReport {
+            status,
+            uptime_secs: uptime.as_secs_f64(),
+            initialized,
+            active_requests,
+            total_requests: self.total_requests.load(Ordering::Relaxed),
+            recent_error_rate,
+            last_error: recent.last_error.clone(),
+            recent_errors: recent.errors.iter().cloned().collect(),
+            build,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_tracker_is_ok() {
+        let report = HealthTracker::new().report(false, 0, Duration::ZERO, None);
+        assert_eq!(report.status, HealthStatus::Ok);
+        assert_eq!(report.total_requests, 0);
+        assert!(report.recent_error_rate.abs() < f64::EPSILON);
+        assert!(report.last_error.is_none());
+    }
+
+    #[test]
+    fn few_failures_do_not_degrade() {
+        let tracker = HealthTracker::new();
+        tracker.record(Some("boom"));
+        let report = tracker.report(true, 0, Duration::ZERO, None);
+        assert_eq!(report.status, HealthStatus::Ok);
+        assert_eq!(report.last_error.as_deref(), Some("boom"));
+    }
+
+    #[test]
+    fn high_error_rate_degrades_and_recovers() {
+        let tracker = HealthTracker::new();
+        for _ in 0..MIN_SAMPLES {
+            tracker.record(Some("boom"));
+        }
+        assert_eq!(
+            tracker.report(true, 0, Duration::ZERO, None).status,
+            HealthStatus::Degraded
+        );
+
+        for _ in 0..RECENT_WINDOW {
+            tracker.record(None);
+        }
+        let report = tracker.report(true, 0, Duration::ZERO, None);
+        assert_eq!(report.status, HealthStatus::Ok);
+        assert_eq!(report.total_requests, (MIN_SAMPLES + RECENT_WINDOW) as u64);
+    }
+
+    fn recent_error(n: usize) -> RecentError {
+        RecentError {
+            method: format!("method/{n}"),
+            code: -32603,
+            message: format!("boom {n}"),
+            timestamp: "2026-01-01T00:00:00+00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn recent_errors_keep_the_newest_in_order() {
+        let tracker = HealthTracker::new();
+        for n in 0..RECENT_ERRORS_CAPACITY + 3 {
+            tracker.record_error(recent_error(n));
+        }
+
+        let errors = tracker.recent_errors();
+        assert_eq!(errors.len(), RECENT_ERRORS_CAPACITY);
+        assert_eq!(errors[0], recent_error(3));
+        assert_eq!(
+            errors.last(),
+            Some(&recent_error(RECENT_ERRORS_CAPACITY + 2))
+        );
+        assert_eq!(
+            tracker.report(true, 0, Duration::ZERO, None).recent_errors,
+            errors
+        );
+    }
+
+    #[test]
+    fn report_serializes_camel_case() {
+        let value =
+            serde_json::to_value(HealthTracker::new().report(true, 2, Duration::ZERO, None))
+                .unwrap();
+        assert_eq!(value["status"], "ok");
+        assert_eq!(value["activeRequests"], 2);
+        assert_eq!(value["initialized"], true);
+        assert!(value.get("lastError").is_none());
+        assert!(value.get("recentErrors").is_none());
+        assert!(value.get("build").is_none());
+    }
+}
@@ -26,44 +26,62 @@
 #![forbid(unsafe_code)]
 #![allow(dead_code)]
 
+mod accept;
+mod audit;
 mod auth;
 pub mod bidirectional;
 mod builder;
 pub mod caching;
+mod clock;
 pub mod docket;
 mod handler;
+mod health;
 mod middleware;
+mod notification_writer;
 pub mod oauth;
 pub mod oidc;
 pub mod providers;
 mod proxy;
 pub mod rate_limiting;
 mod router;
+#[cfg(feature = "tower")]
+mod service;
 mod session;
+mod stdin_reader;
 mod tasks;
+pub mod testing;
 pub mod transform;
 
 #[cfg(test)]
 mod tests;
 
+pub use audit::{AuditEvent, AuditOutcome, AuditSink, FileAuditSink};
 #[cfg(feature = "jwt")]
 pub use auth::JwtTokenVerifier;
 pub use auth::{
-    AllowAllAuthProvider, AuthProvider, AuthRequest, StaticTokenVerifier, TokenAuthProvider,
-    TokenVerifier,
+    AllowAllAuthProvider, AuthCachePolicy, AuthProvider, AuthRequest, StaticTokenVerifier,
+    TokenAuthProvider, TokenVerifier,
 };
 pub use builder::ServerBuilder;
+pub use clock::{Clock, SystemClock, TestClock};
+pub use fastmcp_console::banner::BuildInfo;
 pub use fastmcp_console::config::{BannerStyle, ConsoleConfig, TrafficVerbosity};
 pub use fastmcp_console::stats::{ServerStats, StatsSnapshot};
 pub use handler::{
-    BidirectionalSenders, BoxFuture, ProgressNotificationSender, PromptHandler, ResourceHandler,
+    BidirectionalSenders, BoxFuture, FallbackToolHandler, LogNotificationSender,
+    ProgressNotificationSender, PromptHandler, ResourceHandler, ToolContentNotificationSender,
     ToolHandler, create_context_with_progress, create_context_with_progress_and_senders,
 };
+pub use health::{HealthReport, HealthStatus, RecentError};
 pub use middleware::{Middleware, MiddlewareDecision};
+pub use notification_writer::FlushPolicy;
 pub use proxy::{ProxyBackend, ProxyCatalog, ProxyClient};
 pub use router::{
-    MountResult, NotificationSender, Router, RouterResourceReader, RouterToolCaller, TagFilters,
+    DEFAULT_MAX_CONTENT_ITEMS, DEFAULT_MAX_RESULT_BYTES, MountResult, NotificationSender, Router,
+    RouterResourceReader, RouterToolCaller, TRUNCATION_MARKER, TagFilters,
 };
+#[cfg(feature = "tower")]
+pub use service::McpService;
 pub use session::Session;
 pub use tasks::{SharedTaskManager, TaskManager};
 
@@ -73,36 +91,104 @@ pub use bidirectional::{
     TransportSamplingSender,
 };
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io::{Read, Write};
-use std::sync::{Arc, Condvar, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
 use std::time::{Duration, Instant};
 
 use asupersync::{Budget, CancelKind, Cx, RegionId};
 use fastmcp_console::client::RequestResponseRenderer;
 use fastmcp_console::logging::RichLoggerBuilder;
 use fastmcp_console::{banner::StartupBanner, console};
-use fastmcp_core::logging::{debug, error, info, targets};
-use fastmcp_core::{AuthContext, McpContext, McpError, McpErrorCode, McpResult};
+use fastmcp_core::logging::{debug, error, info, targets, trace, warn};
+use fastmcp_core::{AuthChallenge, AuthContext, McpContext, McpError, McpErrorCode, McpResult};
 use fastmcp_protocol::{
-    CallToolParams, CancelTaskParams, CancelledParams, GetPromptParams, GetTaskParams,
-    InitializeParams, JsonRpcError, JsonRpcMessage, JsonRpcRequest, JsonRpcResponse,
-    ListPromptsParams, ListResourceTemplatesParams, ListResourcesParams, ListTasksParams,
-    ListToolsParams, LogLevel, LogMessageParams, Prompt, ReadResourceParams, RequestId, Resource,
-    ResourceTemplate, ServerCapabilities, ServerInfo, SetLogLevelParams, SubmitTaskParams,
-    SubscribeResourceParams, Tool, UnsubscribeResourceParams,
+    CallToolParams, CancelTaskParams, CancelledParams, ClientCapabilities, ClientInfo,
+    GetPromptParams, GetTaskParams, InitializeParams, JsonRpcError, JsonRpcMessage, JsonRpcRequest,
+    JsonRpcResponse, ListPromptsParams, ListResourceTemplatesParams, ListResourcesParams,
+    ListTasksParams, ListToolsParams, LogLevel, LogMessageParams, PROTOCOL_VERSION, Prompt,
+    ReadResourceParams, RegistryDocument, RequestId, Resource, ResourceTemplate,
+    ServerCapabilities, ServerInfo, SetLogLevelParams, SubmitTaskParams, SubscribeResourceParams,
+    Tool, UnsubscribeResourceParams,
 };
 use fastmcp_transport::sse::SseServerTransport;
 use fastmcp_transport::websocket::WsTransport;
-use fastmcp_transport::{AsyncStdout, Codec, StdioTransport, Transport, TransportError};
+use fastmcp_transport::{
+    AsyncStdout, Codec, CodecError, FrameMode, StdioTransport, Transport, TransportError,
+};
 use log::{Level, LevelFilter};
 
+use auth::ProtectedResource;
+use notification_writer::NotificationWriter;
+use stdin_reader::CancellableStdin;
+
 /// Type alias for startup hook function.
 pub type StartupHook =
     Box<dyn FnOnce() -> Result<(), Box<dyn std::error::Error + Send + Sync>> + Send>;
 
 /// Type alias for shutdown hook function.
-pub type ShutdownHook = Box<dyn FnOnce() + Send>;
+///
+/// Receives the reason the server is shutting down.
+pub type ShutdownHook = Box<dyn FnOnce(&ShutdownReason) + Send>;
+
+/// Why the server is shutting down, passed to the shutdown hook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShutdownReason {
+    /// The client closed the connection, or every transport closed.
+    CleanClose,
+    /// The server was cancelled, for example by a shutdown signal.
+    Cancelled,
+    /// The startup hook returned an error.
+    StartupFailed,
+    /// The connection loop stopped on a fatal error.
+    Error(String),
+}
+
+impl ShutdownReason {
+    /// Returns the process exit code the `run_*` methods use for this reason.
+    #[must_use]
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::CleanClose | Self::Cancelled => 0,
+            Self::StartupFailed | Self::Error(_) => 1,
+        }
+    }
+
+    /// Combines the reasons several connection loops stopped with.
+    ///
+    /// An error outranks a cancellation, which outranks a clean close.
+    fn combine(self, other: Self) -> Self {
+        fn rank(reason: &ShutdownReason) -> u8 {
+            match reason {
+                ShutdownReason::CleanClose => 0,
+                ShutdownReason::Cancelled => 1,
+                ShutdownReason::StartupFailed | ShutdownReason::Error(_) => 2,
+            }
+        }
+        if rank(&other) > rank(&self) {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+/// Type alias for a handler of a custom (non-MCP) JSON-RPC method.
+///
+/// Receives the request context and the raw request params, and returns the
+/// JSON value sent back as the result.
+pub type CustomMethodHandler = Box<
+    dyn Fn(&McpContext, Option<serde_json::Value>) -> McpResult<serde_json::Value> + Send + Sync,
+>;
+
+/// Type alias for a function computing per-session instructions.
+///
+/// Receives the connecting client's info and capabilities from `initialize`.
+/// Returning `None` falls back to the static
+/// [`instructions`](ServerBuilder::instructions).
+pub type InstructionsFn =
+    Box<dyn Fn(&ClientInfo, &ClientCapabilities) -> Option<String> + Send + Sync>;
 
 /// Lifecycle hooks for server startup and shutdown.
 ///
@@ -123,8 +209,8 @@ pub type ShutdownHook = Box<dyn FnOnce() + Send>;
 ///         // Initialize database, caches, etc.
 ///         Ok(())
 ///     })
-///     .on_shutdown(|| {
-///         println!("Cleaning up...");
+///     .on_shutdown_with_reason(|reason| {
+///         println!("Cleaning up after {reason:?}...");
 ///         // Close connections, flush buffers, etc.
 ///     })
 ///     .run_stdio();
@@ -241,6 +327,70 @@ pub enum DuplicateBehavior {
     Ignore,
 }
 
+/// What to do with a request that arrives while the in-flight cap is reached.
+///
+/// See [`ServerBuilder::max_inflight_requests`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InflightOverflow {
+    /// Fail the request immediately with a "server busy" error.
+    #[default]
+    Reject,
+
+    /// Wait up to the given duration for a slot to free up, then fail
+    /// with a "server busy" error.
+    Queue(Duration),
+}
+
+/// Why a `try_run_*` method stopped without a clean shutdown.
+///
+/// Returned by [`Server::try_run_transport`] and friends. The `run_*`
+/// methods log it and exit the process with [`exit_code`](Self::exit_code).
+#[derive(Debug)]
+pub enum ServerRunError {
+    /// The listener could not be bound.
+    Bind(std::io::Error),
+    /// The startup hook returned an error.
+    StartupFailed,
+    /// The connection loop stopped abnormally, for example after too many
+    /// consecutive unparseable messages.
+    Transport {
+        /// The process exit code the loop ended with.
+        exit_code: i32,
+    },
+}
+
+impl ServerRunError {
+    /// Returns the process exit code the `run_*` methods use for this error.
+    #[must_use]
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Bind(_) | Self::StartupFailed => 1,
+            Self::Transport { exit_code } => *exit_code,
+        }
+    }
+}
+
+impl std::fmt::Display for ServerRunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bind(e) => write!(f, "Failed to bind listener: {e}"),
+            Self::StartupFailed => write!(f, "Startup hook failed"),
+            Self::Transport { exit_code } => {
+                write!(f, "Connection loop stopped with exit code {exit_code}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ServerRunError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Bind(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
 /// An MCP server instance.
 ///
 /// Servers are built using [`ServerBuilder`] and can run on various
@@ -250,6 +400,8 @@ pub struct Server {
     capabilities: ServerCapabilities,
     router: Router,
     instructions: Option<String>,
+    /// Computes instructions per session, falling back to `instructions`.
+    instructions_fn: Option<InstructionsFn>,
     /// Request timeout in seconds (0 = no timeout).
     request_timeout_secs: u64,
     /// Runtime statistics collector (None = disabled).
@@ -260,18 +412,65 @@ pub struct Server {
     logging: LoggingConfig,
     /// Console configuration for rich output.
     console_config: ConsoleConfig,
+    /// Build provenance for the banner, ready event and health reports.
+    build_info: Option<BuildInfo>,
     /// Lifecycle hooks (wrapped in Option so they can be taken once).
     lifespan: Mutex<Option<LifespanHooks>>,
     /// Optional authentication provider.
     auth_provider: Option<Arc<dyn AuthProvider>>,
+    /// Authorization servers and scopes advertised to clients.
+    protected_resource: ProtectedResource,
     /// Registered middleware.
     middleware: Arc<Vec<Box<dyn crate::Middleware>>>,
-    /// Active requests by JSON-RPC request ID.
-    active_requests: Mutex<HashMap<RequestId, ActiveRequest>>,
+    /// Active requests by JSON-RPC request ID, ordered so shutdown cancels
+    /// them in the same order on every run.
+    active_requests: Mutex<BTreeMap<RequestId, ActiveRequest>>,
     /// Optional task manager for background tasks (Docket/SEP-1686).
     task_manager: Option<SharedTaskManager>,
     /// Pending server-to-client requests (for bidirectional communication).
     pending_requests: Arc<bidirectional::PendingRequests>,
+    /// Close the connection after this many consecutive unparseable frames.
+    max_consecutive_parse_errors: Option<u32>,
+    /// Receives an audit event for every completed request.
+    audit_sink: Option<Arc<dyn AuditSink>>,
+    /// Maximum nesting depth of request params.
+    max_json_depth: usize,
+    /// Maximum size of an incoming message, in bytes.
+    max_json_size: usize,
+    /// Maximum number of clients served by the accept loops (None = unlimited).
+    max_connections: Option<usize>,
+    /// Maximum number of concurrently active requests (None = unlimited).
+    max_inflight_requests: Option<usize>,
+    /// What to do with requests that arrive while the cap is reached.
+    inflight_overflow: InflightOverflow,
+    /// Signalled whenever an active request finishes.
+    inflight_released: Condvar,
+    /// Whether `run_*` installs SIGINT/SIGTERM handlers.
+    handle_signals: bool,
+    /// Handlers for custom JSON-RPC methods, keyed by method name.
+    custom_methods: HashMap<String, CustomMethodHandler>,
+    /// Methods answered with `method_not_found`.
+    disabled_methods: HashSet<String>,
+    /// Unknown notifications with these prefixes are ignored at trace level.
+    ignored_notification_prefixes: Vec<String>,
+    /// Per-subject request rate limit, checked after authentication.
+    rate_limiter: Option<rate_limiting::SubjectRateLimiter>,
+    /// Request outcomes for `health/check`.
+    health: health::HealthTracker,
+    /// Time source for [`uptime`](Self::uptime).
+    clock: Arc<dyn Clock>,
+    /// Whether the byte-accounting fallback has been warned about.
+    size_estimate_warned: AtomicBool,
+    /// Whether the `ready` event has been written.
+    ready_emitted: AtomicBool,
+    /// Whether stdio output is written as canonical JSON.
+    canonical_responses: bool,
+    /// When stdio notifications are written out.
+    notification_flush: FlushPolicy,
+    /// HTTP/SSE response compression (None = off).
+    compression: Option<fastmcp_transport::compression::CompressionConfig>,
+    /// When the server was built, reset when a `run_*` loop starts.
+    started_at: Mutex<Instant>,
 }
 
 impl Server {
@@ -318,6 +517,24 @@ impl Server {
         self.router.prompts()
     }
 
+    /// Returns a serializable document describing everything this server exposes.
+    ///
+    /// Unlike [`HandlerRegistryRenderer`](fastmcp_console::HandlerRegistryRenderer),
+    /// which formats handlers for humans, the document carries full schemas and
+    /// annotations and is meant for tooling: code generation, diffing, or feeding
+    /// the registry to an LLM. `fastmcp inspect --format mcp` emits the same shape.
+    #[must_use]
+    pub fn registry_document(&self) -> RegistryDocument {
+        RegistryDocument {
+            server: self.info.clone(),
+            capabilities: self.capabilities.clone(),
+            tools: self.tools(),
+            resources: self.resources(),
+            resource_templates: self.resource_templates(),
+            prompts: self.prompts(),
+        }
+    }
+
     /// Returns the task manager, if configured.
     ///
     /// Returns `None` if background tasks are not enabled.
@@ -360,7 +577,35 @@ impl Server {
     /// Returns `None` if statistics collection is disabled.
     #[must_use]
     pub fn stats(&self) -> Option<StatsSnapshot> {
-        self.stats.as_ref().map(ServerStats::snapshot)
+        self.stats.as_ref().map(|stats| {
+            let mut snapshot = stats.snapshot();
+            snapshot.uptime = self.uptime();
+            snapshot
+        })
+    }
+
+    /// Returns how long the server has been running.
+    ///
+    /// Measured from when a `run_*` loop started, or from
+    /// [`build`](ServerBuilder::build) if the server is driven directly
+    /// (e.g. through [`dispatch_one`](Self::dispatch_one)).
+    #[must_use]
+    pub fn uptime(&self) -> Duration {
+        let started_at = *self
+            .started_at
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        self.clock
+            .now_instant()
+            .saturating_duration_since(started_at)
+    }
+
+    /// Restarts the uptime clock.
+    fn mark_started(&self) {
+        *self
+            .started_at
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = self.clock.now_instant();
     }
 
     /// Returns the raw statistics collector.
@@ -372,13 +617,64 @@ impl Server {
         self.stats.as_ref()
     }
 
+    /// Returns a health report for readiness probes.
+    ///
+    /// `initialized` reflects whether any session on this server has completed
+    /// `initialize`; the `health/check` method reports the calling session instead.
+    /// Unlike [`stats`](Self::stats), health tracking is always on.
+    #[must_use]
+    pub fn health(&self) -> HealthReport {
+        self.health.report(
+            self.health.any_session_initialized(),
+            self.active_request_count(),
+            self.uptime(),
+            self.build_info.clone(),
+        )
+    }
+
+    /// Returns the most recent failed requests (up to 16), oldest first.
+    ///
+    /// Every request or notification that fails is recorded, with its
+    /// message masked like the response. The same list is included in
+    /// [`health`](Self::health) reports.
+    #[must_use]
+    pub fn recent_errors(&self) -> Vec<RecentError> {
+        self.health.recent_errors()
+    }
+
+    fn record_recent_error(&self, method: &str, error: &McpError) {
+        let error = error.masked(self.mask_error_details);
+        self.health.record_error(RecentError {
+            method: method.to_string(),
+            code: i32::from(error.code),
+            message: error.message,
+            timestamp: chrono::DateTime::<chrono::Utc>::from(self.clock.now_system()).to_rfc3339(),
+        });
+    }
+
+    /// Returns the instructions to send a session at `initialize`.
+    ///
+    /// A session keeps the instructions it was first given.
+    fn session_instructions(&self, session: &Session, params: &InitializeParams) -> Option<String> {
+        if let Some(instructions) = session.instructions() {
+            return Some(instructions.to_string());
+        }
+        self.instructions_fn
+            .as_ref()
+            .and_then(|f| f(&params.client_info, &params.capabilities))
+            .or_else(|| self.instructions.clone())
+    }
+
+    fn active_request_count(&self) -> usize {
+        lock_recovering(&self.active_requests, "active_requests").len()
+    }
+
     /// Renders a stats panel to stderr, if stats are enabled.
     pub fn display_stats(&self) {
-        let Some(stats) = self.stats.as_ref() else {
+        let Some(snapshot) = self.stats() else {
             return;
         };
 
-        let snapshot = stats.snapshot();
         let renderer = fastmcp_console::stats::StatsRenderer::detect();
         renderer.render_panel(&snapshot, console());
     }
@@ -389,18 +685,75 @@ impl Server {
         &self.console_config
     }
 
+    /// Dispatches a single JSON-RPC request and returns its response.
+    ///
+    /// This runs the full request pipeline (initialization checks, auth,
+    /// middleware, routing and stats) without a running transport, which makes
+    /// it suitable for unit-testing a fully built server or for custom bridges.
+    /// Notifications emitted while handling the request are discarded; use
+    /// [`dispatch_one_with_sender`](Self::dispatch_one_with_sender) to observe
+    /// them.
+    ///
+    /// Returns `None` for JSON-RPC notifications (requests without an ID).
+    ///
+    /// Server-initiated requests such as sampling or elicitation fail, since
+    /// there is no client connection to send them to.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let server = Server::new("demo", "1.0").tool(MyTool).build();
+    /// let mut session = Session::new(server.info().clone(), server.capabilities().clone());
+    ///
+    /// let init = JsonRpcRequest::new("initialize", Some(init_params), 1);
+    /// server.dispatch_one(&cx, &mut session, init);
+    ///
+    /// let call = JsonRpcRequest::new("tools/call", Some(call_params), 2);
+    /// let response = server.dispatch_one(&cx, &mut session, call).unwrap();
+    /// ```
+    pub fn dispatch_one(
+        &self,
+        cx: &Cx,
+        session: &mut Session,
+        request: JsonRpcRequest,
+    ) -> Option<JsonRpcResponse> {
+        let notification_sender: NotificationSender = Arc::new(|_| {});
+        self.dispatch_one_with_sender(cx, session, request, &notification_sender)
+    }
+
+    /// Dispatches a single JSON-RPC request, forwarding notifications to `notification_sender`.
+    ///
+    /// Like [`dispatch_one`](Self::dispatch_one), but progress and log
+    /// notifications produced by handlers are passed to the given sender.
+    pub fn dispatch_one_with_sender(
+        &self,
+        cx: &Cx,
+        session: &mut Session,
+        request: JsonRpcRequest,
+        notification_sender: &NotificationSender,
+    ) -> Option<JsonRpcResponse> {
+        let send_fn: bidirectional::TransportSendFn =
+            Arc::new(|_| Err("no transport attached".to_string()));
+        let request_sender =
+            bidirectional::RequestSender::new(self.pending_requests.clone(), send_fn);
+        self.handle_request(cx, session, request, notification_sender, &request_sender)
+    }
+
     /// Renders the startup banner based on console configuration.
-    fn render_startup_banner(&self) {
+    fn render_startup_banner(&self, transport: &str) {
         let render = || {
             let mut banner = StartupBanner::new(&self.info.name, &self.info.version)
                 .tools(self.router.tools_count())
                 .resources(self.router.resources_count())
                 .prompts(self.router.prompts_count())
-                .transport("stdio");
+                .transport(transport);
 
             if let Some(desc) = self.instructions.as_deref().filter(|d| !d.is_empty()) {
                 banner = banner.description(desc);
             }
+            if let Some(info) = &self.build_info {
+                banner = banner.build_info(info.clone());
+            }
 
             // Apply banner style from config
             match self.console_config.banner_style {
@@ -418,6 +771,32 @@ impl Server {
         }
     }
 
+    /// Writes the `ready` event line if enabled and not yet written.
+    ///
+    /// See [`ConsoleConfig::emit_ready_event`].
+    fn emit_ready_event(&self, out: &mut dyn Write, transport: &str) {
+        if !self.console_config.emit_ready_event || self.ready_emitted.swap(true, Ordering::SeqCst)
+        {
+            return;
+        }
+
+        let mut event = serde_json::json!({
+            "event": "ready",
+            "name": self.info.name,
+            "version": self.info.version,
+            "tools": self.router.tools_count(),
+            "resources": self.router.resources_count(),
+            "prompts": self.router.prompts_count(),
+            "transport": transport,
+        });
+        if let Some(info) = &self.build_info {
+            event["build"] = serde_json::json!(info);
+        }
+        if let Err(e) = writeln!(out, "{event}").and_then(|()| out.flush()) {
+            warn!(target: targets::SERVER, "Failed to write ready event: {}", e);
+        }
+    }
+
     /// Initializes rich logging based on server configuration.
     ///
     /// This should be called early in the startup sequence, before any
@@ -441,6 +820,9 @@ impl Server {
     ///
     /// This is the primary way to run MCP servers as subprocesses.
     /// Creates a testing Cx and runs the server loop.
+    ///
+    /// SIGINT/SIGTERM cancel the Cx and run the shutdown hook before exiting,
+    /// unless disabled with [`ServerBuilder::handle_signals`].
     pub fn run_stdio(self) -> ! {
         // Create a Cx for the server (for now, use testing Cx)
         let cx = Cx::for_testing();
@@ -451,27 +833,141 @@ impl Server {
     ///
     /// This allows integration with a real asupersync runtime.
     pub fn run_stdio_with_cx(self, cx: &Cx) -> ! {
+        self.run_stdio_framed_with_cx(cx, FrameMode::Ndjson)
+    }
+
+    /// Runs the server on stdio using the given message framing.
+    ///
+    /// [`FrameMode::ContentLength`] speaks LSP-style `Content-Length` frames,
+    /// for hosts (such as editors) that reuse their LSP transport.
+    /// [`run_stdio`](Self::run_stdio) uses NDJSON.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Server::new("demo", "1.0")
+    ///     .tool(MyTool)
+    ///     .build()
+    ///     .run_stdio_framed(FrameMode::ContentLength);
+    /// ```
+    pub fn run_stdio_framed(self, frame_mode: FrameMode) -> ! {
+        let cx = Cx::for_testing();
+        self.run_stdio_framed_with_cx(&cx, frame_mode)
+    }
+
+    /// Runs the server on stdio with a provided Cx and message framing.
+    pub fn run_stdio_framed_with_cx(self, cx: &Cx, frame_mode: FrameMode) -> ! {
+        exit_with(self.try_run_stdio_framed_with_cx(cx, frame_mode))
+    }
+
+    /// Runs the server on stdio until stdin closes, without exiting the process.
+    ///
+    /// Like [`run_stdio`](Self::run_stdio), but returns `Ok` on a clean
+    /// shutdown and a [`ServerRunError`] otherwise. The shutdown hook has run
+    /// by the time it returns.
+    ///
+    /// Embedders that manage signals themselves should disable the built-in
+    /// handler with [`ServerBuilder::handle_signals`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the startup hook fails or the connection loop
+    /// stops abnormally.
+    pub fn try_run_stdio(self) -> Result<(), ServerRunError> {
+        let cx = Cx::for_testing();
+        self.try_run_stdio_with_cx(&cx)
+    }
+
+    /// Runs the server on stdio with a provided Cx, without exiting the process.
+    ///
+    /// See [`try_run_stdio`](Self::try_run_stdio).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the startup hook fails or the connection loop
+    /// stops abnormally.
+    pub fn try_run_stdio_with_cx(self, cx: &Cx) -> Result<(), ServerRunError> {
+        self.try_run_stdio_framed_with_cx(cx, FrameMode::Ndjson)
+    }
+
+    /// Runs the server on stdio with a provided Cx and message framing,
+    /// without exiting the process.
+    ///
+    /// See [`try_run_stdio`](Self::try_run_stdio).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the startup hook fails or the connection loop
+    /// stops abnormally.
+    pub fn try_run_stdio_framed_with_cx(
+        self,
+        cx: &Cx,
+        frame_mode: FrameMode,
+    ) -> Result<(), ServerRunError> {
         // Initialize rich logging first, before any log output
         self.init_rich_logging();
 
-        let transport = StdioTransport::stdio();
+        // Reads from stdin give up once `cx` is cancelled, so a shutdown
+        // signal stops the loop even while it waits for input
+        let stdin = CancellableStdin::new(cx.clone());
+        let transport = self.stdio_transport(stdin, std::io::stdout(), frame_mode);
         let shared = SharedTransport::new(transport);
 
         // Create a notification sender that writes to a separate stdout handle.
         // This allows progress notifications to be sent during handler execution
         // while the main transport is blocked on recv().
-        let notification_sender = create_notification_sender();
+        let notifications = NotificationWriter::new(AsyncStdout::new(), self.notification_flush);
+        let notification_sender = create_notification_sender(
+            frame_mode,
+            self.canonical_responses,
+            Arc::clone(&notifications),
+        );
 
         let shared_recv = shared.clone();
         let shared_send = shared.clone();
-        self.run_loop(
+        self.try_run_loop(
             cx,
-            move |cx| shared_recv.recv(cx),
-            move |cx, message| shared_send.send(cx, message),
+            "stdio",
+            move |cx| match shared_recv.recv(cx) {
+                Err(TransportError::Io(_)) if cx.is_cancel_requested() => {
+                    Err(TransportError::Cancelled)
+                }
+                other => other,
+            },
+            move |cx, message| {
+                // Notifications sent while handling a request go out before its response
+                notifications.flush();
+                shared_send.send(cx, message)
+            },
             notification_sender,
         )
     }
 
+    /// Builds the stdio transport used by the `run_stdio*` methods.
+    fn stdio_transport<R: Read, W: Write>(
+        &self,
+        reader: R,
+        writer: W,
+        frame_mode: FrameMode,
+    ) -> StdioTransport<R, W> {
+        let limits = self.inbound_codec();
+        StdioTransport::new(reader, writer)
+            .with_frame_mode(frame_mode)
+            .with_canonical_json(self.canonical_responses)
+            .with_max_message_size(limits.max_message_size())
+            .with_max_depth(limits.max_depth())
+    }
+
+    /// Returns a codec enforcing the configured message size and nesting
+    /// limits, for checking incoming frames before they are parsed.
+    fn inbound_codec(&self) -> Codec {
+        let mut codec = Codec::new();
+        codec.set_max_message_size(self.max_json_size);
+        // The message object wraps params, which count as depth 1
+        codec.set_max_depth(self.max_json_depth.saturating_add(1));
+        codec
+    }
+
     /// Runs the server on a custom transport with a testing Cx.
     ///
     /// This is useful for SSE/WebSocket integrations where the transport is
@@ -488,6 +984,51 @@ impl Server {
     ///
     /// This allows integration with a real asupersync runtime.
     pub fn run_transport_with_cx<T>(self, cx: &Cx, transport: T) -> !
+    where
+        T: Transport + Send + 'static,
+    {
+        exit_with(self.try_run_transport_with_cx(cx, transport))
+    }
+
+    /// Runs the server on a custom transport until it closes, without
+    /// exiting the process.
+    ///
+    /// Like [`run_transport`](Self::run_transport), but returns `Ok` once the
+    /// transport reports a clean close (or the Cx is cancelled) and a
+    /// [`ServerRunError`] otherwise. The shutdown hook has run by the time it
+    /// returns. See [`try_run_stdio`](Self::try_run_stdio) for how signals
+    /// are handled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the startup hook fails or the connection loop
+    /// stops abnormally.
+    pub fn try_run_transport<T>(self, transport: T) -> Result<(), ServerRunError>
+    where
+        T: Transport + Send + 'static,
+    {
+        let cx = Cx::for_testing();
+        self.try_run_transport_with_cx(&cx, transport)
+    }
+
+    /// Runs the server on a custom transport with a provided Cx, without
+    /// exiting the process.
+    ///
+    /// See [`try_run_transport`](Self::try_run_transport).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the startup hook fails or the connection loop
+    /// stops abnormally.
+    pub fn try_run_transport_with_cx<T>(self, cx: &Cx, transport: T) -> Result<(), ServerRunError>
+    where
+        T: Transport + Send + 'static,
+    {
+        self.run_named_transport(cx, transport, "custom")
+    }
+
+    /// Runs the server on `transport`, reporting it as `name` at startup.
+    fn run_named_transport<T>(self, cx: &Cx, transport: T, name: &str) -> Result<(), ServerRunError>
     where
         T: Transport + Send + 'static,
     {
@@ -498,8 +1039,9 @@ impl Server {
 
         let shared_recv = shared.clone();
         let shared_send = shared;
-        self.run_loop(
+        self.try_run_loop(
             cx,
+            name,
             move |cx| shared_recv.recv(cx),
             move |cx, message| shared_send.send(cx, message),
             notification_sender,
@@ -514,8 +1056,8 @@ impl Server {
         W: Write + Send + 'static,
         R: Iterator<Item = JsonRpcRequest> + Send + 'static,
     {
-        let transport = SseServerTransport::new(writer, request_source, endpoint_url);
-        self.run_transport(transport)
+        let cx = Cx::for_testing();
+        self.run_sse_with_cx(&cx, writer, request_source, endpoint_url)
     }
 
     /// Runs the server using SSE transport with a provided Cx.
@@ -531,7 +1073,7 @@ impl Server {
         R: Iterator<Item = JsonRpcRequest> + Send + 'static,
     {
         let transport = SseServerTransport::new(writer, request_source, endpoint_url);
-        self.run_transport_with_cx(cx, transport)
+        exit_with(self.run_named_transport(cx, transport, "sse"))
     }
 
     /// Runs the server using WebSocket transport with a testing Cx.
@@ -542,8 +1084,8 @@ impl Server {
         R: Read + Send + 'static,
         W: Write + Send + 'static,
     {
-        let transport = WsTransport::new(reader, writer);
-        self.run_transport(transport)
+        let cx = Cx::for_testing();
+        self.run_websocket_with_cx(&cx, reader, writer)
     }
 
     /// Runs the server using WebSocket transport with a provided Cx.
@@ -553,7 +1095,72 @@ impl Server {
         W: Write + Send + 'static,
     {
         let transport = WsTransport::new(reader, writer);
-        self.run_transport_with_cx(cx, transport)
+        exit_with(self.run_named_transport(cx, transport, "websocket"))
+    }
+
+    /// Runs the server on several transports at once with a testing Cx.
+    ///
+    /// See [`run_multi_with_cx`](Self::run_multi_with_cx).
+    pub fn run_multi(self, transports: Vec<Box<dyn Transport + Send>>) -> ! {
+        let cx = Cx::for_testing();
+        self.run_multi_with_cx(&cx, transports)
+    }
+
+    /// Runs the server on several transports at once with a provided Cx.
+    ///
+    /// Each transport is served on its own thread with its own [`Session`],
+    /// sharing the router, stats, and lifecycle hooks. The startup hook runs
+    /// once before any transport is served. The process exits after every
+    /// transport has closed, or once `cx` is cancelled and each loop has
+    /// observed it; the shutdown hook then runs once.
+    pub fn run_multi_with_cx(self, cx: &Cx, transports: Vec<Box<dyn Transport + Send>>) -> ! {
+        self.init_rich_logging();
+        self.install_signal_handler(cx);
+        let result = self.start("multi").and_then(|()| {
+            let reason = self.serve_transports(cx, transports);
+            self.shutdown(reason)
+        });
+        exit_with(result)
+    }
+
+    /// Serves each transport on its own thread until all of them have stopped.
+    ///
+    /// Returns the highest exit code reported by any connection loop.
+    pub(crate) fn serve_transports(
+        &self,
+        cx: &Cx,
+        transports: Vec<Box<dyn Transport + Send>>,
+    ) -> ShutdownReason {
+        std::thread::scope(|scope| {
+            let loops: Vec<_> = transports
+                .into_iter()
+                .map(|transport| {
+                    let cx = cx.clone();
+                    scope.spawn(move || {
+                        let shared = SharedTransport::new(transport);
+                        let notification_sender =
+                            create_transport_notification_sender(shared.clone());
+                        let shared_recv = shared.clone();
+                        self.serve_connection(
+                            &cx,
+                            move |cx| shared_recv.recv(cx),
+                            move |cx, message| shared.send(cx, message),
+                            notification_sender,
+                        )
+                    })
+                })
+                .collect();
+
+            loops
+                .into_iter()
+                .map(|handle| {
+                    handle.join().unwrap_or_else(|_| {
+                        error!(target: targets::SERVER, "Transport loop panicked");
+                        ShutdownReason::Error("Transport loop panicked".to_string())
+                    })
+                })
+                .fold(ShutdownReason::CleanClose, ShutdownReason::combine)
+        })
     }
 
     /// Runs the startup lifecycle hook, if configured.
@@ -562,10 +1169,7 @@ impl Server {
     /// `false` if the hook returned an error.
     pub(crate) fn run_startup_hook(&self) -> bool {
         let hook = {
-            let mut guard = self.lifespan.lock().unwrap_or_else(|poisoned| {
-                error!(target: targets::SERVER, "lifespan lock poisoned in run_startup_hook, recovering");
-                poisoned.into_inner()
-            });
+            let mut guard = lock_recovering(&self.lifespan, "lifespan");
             guard.as_mut().and_then(|h| h.on_startup.take())
         };
 
@@ -587,40 +1191,111 @@ impl Server {
     }
 
     /// Runs the shutdown lifecycle hook, if configured.
-    pub(crate) fn run_shutdown_hook(&self) {
+    ///
+    /// The hook is taken on first use, so it never runs twice.
+    pub(crate) fn run_shutdown_hook(&self, reason: &ShutdownReason) {
         let hook = {
-            let mut guard = self.lifespan.lock().unwrap_or_else(|poisoned| {
-                error!(target: targets::SERVER, "lifespan lock poisoned in run_shutdown_hook, recovering");
-                poisoned.into_inner()
-            });
+            let mut guard = lock_recovering(&self.lifespan, "lifespan");
             guard.as_mut().and_then(|h| h.on_shutdown.take())
         };
 
         if let Some(hook) = hook {
-            debug!(target: targets::SERVER, "Running shutdown hook");
-            hook();
+            debug!(target: targets::SERVER, "Running shutdown hook ({:?})", reason);
+            hook(reason);
             debug!(target: targets::SERVER, "Shutdown hook completed");
         }
     }
 
-    /// Performs graceful shutdown: runs hook, closes stats, exits.
-    fn graceful_shutdown(&self, exit_code: i32) -> ! {
+    /// Installs a SIGINT/SIGTERM (Ctrl-C on Windows) handler that cancels `cx`.
+    ///
+    /// The main loop observes the cancellation and shuts down through
+    /// [`shutdown`](Self::shutdown), which runs the shutdown hook. Stdin
+    /// reads observe the cancellation while they wait; other transports stop
+    /// once their pending read returns.
+    fn install_signal_handler(&self, cx: &Cx) {
+        if !self.handle_signals {
+            return;
+        }
+
+        let cx = cx.clone();
+        let result = ctrlc::set_handler(move || {
+            info!(target: targets::SERVER, "Received shutdown signal");
+            cx.cancel_with(CancelKind::Shutdown, None);
+        });
+        if let Err(e) = result {
+            // Only one handler can be installed per process
+            warn!(target: targets::SERVER, "Signal handler not installed: {}", e);
+        }
+    }
+
+    /// Performs graceful shutdown: cancels active requests and runs the hook.
+    ///
+    /// Returns `Ok` for a clean close or cancellation and
+    /// [`ServerRunError::Transport`] otherwise.
+    fn shutdown(&self, reason: ShutdownReason) -> Result<(), ServerRunError> {
         self.cancel_active_requests(CancelKind::Shutdown, true);
-        self.run_shutdown_hook();
-        if let Some(ref stats) = self.stats {
-            stats.connection_closed();
+        self.run_shutdown_hook(&reason);
+        match reason.exit_code() {
+            0 => Ok(()),
+            exit_code => Err(ServerRunError::Transport { exit_code }),
         }
-        std::process::exit(exit_code)
     }
 
-    /// Shared server loop for any transport, using closure-based recv/send.
-    fn run_loop<R, S>(
+    /// Renders the startup banner, runs the startup hook, and emits the
+    /// `ready` event.
+    ///
+    /// Shuts down and returns [`ServerRunError::StartupFailed`] if the
+    /// startup hook fails.
+    fn start(&self, transport: &str) -> Result<(), ServerRunError> {
+        self.mark_started();
+
+        // Render startup banner if enabled (respects both config and legacy env var)
+        if self.console_config.show_banner && !banner_suppressed() {
+            self.render_startup_banner(transport);
+        }
+
+        // Run startup hook
+        if !self.run_startup_hook() {
+            error!(target: targets::SERVER, "Startup hook failed, shutting down");
+            let _ = self.shutdown(ShutdownReason::StartupFailed);
+            return Err(ServerRunError::StartupFailed);
+        }
+
+        self.emit_ready_event(&mut std::io::stderr().lock(), transport);
+        Ok(())
+    }
+
+    /// Shared server loop for a single transport, using closure-based recv/send.
+    fn try_run_loop<R, S>(
         self,
         cx: &Cx,
+        transport: &str,
+        recv: R,
+        send: S,
+        notification_sender: NotificationSender,
+    ) -> Result<(), ServerRunError>
+    where
+        R: FnMut(&Cx) -> Result<JsonRpcMessage, TransportError>,
+        S: FnMut(&Cx, &JsonRpcMessage) -> Result<(), TransportError> + Send + Sync + 'static,
+    {
+        self.install_signal_handler(cx);
+        self.start(transport)?;
+
+        let reason = self.serve_connection(cx, recv, send, notification_sender);
+        self.shutdown(reason)
+    }
+
+    /// Serves one connection until it closes or `cx` is cancelled.
+    ///
+    /// Each connection gets its own [`Session`]. Returns why the connection
+    /// ended.
+    fn serve_connection<R, S>(
+        &self,
+        cx: &Cx,
         mut recv: R,
         send: S,
         notification_sender: NotificationSender,
-    ) -> !
+    ) -> ShutdownReason
     where
         R: FnMut(&Cx) -> Result<JsonRpcMessage, TransportError>,
         S: FnMut(&Cx, &JsonRpcMessage) -> Result<(), TransportError> + Send + Sync + 'static,
@@ -649,21 +1324,11 @@ impl Server {
             stats.connection_opened();
         }
 
-        // Render startup banner if enabled (respects both config and legacy env var)
-        if self.console_config.show_banner && !banner_suppressed() {
-            self.render_startup_banner();
-        }
-
-        // Run startup hook
-        if !self.run_startup_hook() {
-            error!(target: targets::SERVER, "Startup hook failed, exiting");
-            self.graceful_shutdown(1);
-        }
-
         // Create traffic renderer if enabled
         let traffic_renderer = if self.console_config.show_request_traffic {
             let mut renderer = RequestResponseRenderer::new(self.console_config.resolve_context());
             renderer.truncate_at = self.console_config.truncate_at;
+            renderer.redact_keys = self.console_config.redact_keys.clone();
             match self.console_config.traffic_verbosity {
                 TrafficVerbosity::None => {} // Should not happen given the if check
                 TrafficVerbosity::Summary | TrafficVerbosity::Headers => {
@@ -680,30 +1345,56 @@ impl Server {
             None
         };
 
+        let mut parse_errors = ParseErrorTracker::new(self.max_consecutive_parse_errors);
+
         // Main request loop
-        loop {
+        let reason = loop {
             // Check for cancellation
             if cx.is_cancel_requested() {
                 info!(target: targets::SERVER, "Cancellation requested, shutting down");
-                self.graceful_shutdown(0);
+                break ShutdownReason::Cancelled;
             }
 
             // Receive next message
             let message = match recv(cx) {
                 Ok(msg) => msg,
                 Err(TransportError::Closed) => {
-                    // Clean shutdown - track connection close
-                    self.graceful_shutdown(0);
+                    // Clean shutdown
+                    break ShutdownReason::CleanClose;
                 }
                 Err(TransportError::Cancelled) => {
                     info!(target: targets::SERVER, "Transport cancelled");
-                    self.graceful_shutdown(0);
+                    break ShutdownReason::Cancelled;
+                }
+                Err(TransportError::Codec(CodecError::InvalidFrame { id, reason })) => {
+                    let (response, close) = parse_errors.record(id, &reason);
+                    if let Some(response) = response {
+                        let send_result = {
+                            let mut guard = lock_recovering(&send, "transport send");
+                            guard(cx, &JsonRpcMessage::Response(response))
+                        };
+                        if let Err(e) = send_result {
+                            error!(target: targets::TRANSPORT, "Failed to send parse error: {}", e);
+                        }
+                    }
+                    if close {
+                        break ShutdownReason::Error(format!("Too many invalid frames: {reason}"));
+                    }
+                    continue;
+                }
+                Err(TransportError::Codec(CodecError::Json(e))) => {
+                    let (_, close) = parse_errors.record(None, &e.to_string());
+                    if close {
+                        break ShutdownReason::Error(format!("Too many invalid frames: {e}"));
+                    }
+                    continue;
                 }
                 Err(e) => {
                     error!(target: targets::TRANSPORT, "Transport error: {}", e);
                     continue;
                 }
             };
+            parse_errors.reset();
 
             // Log request traffic
             if let Some(renderer) = &traffic_renderer {
@@ -719,11 +1410,10 @@ impl Server {
                 JsonRpcMessage::Request(request) => {
                     // Track bytes received (approximate from serialized request size)
                     if let Some(ref stats) = self.stats {
-                        // Estimate request size by serializing back to JSON
-                        // This is approximate but accurate enough for statistics
-                        if let Ok(json) = serde_json::to_string(&request) {
-                            stats.add_bytes_received(json.len() as u64 + 1); // +1 for newline
-                        }
+                        stats.add_bytes_received(wire_size_estimate(
+                            &request,
+                            &self.size_estimate_warned,
+                        ));
                     }
                     self.handle_request(
                         cx,
@@ -754,21 +1444,25 @@ impl Server {
 
                 // Track bytes sent (approximate from serialized response size)
                 if let Some(ref stats) = self.stats {
-                    if let Ok(json) = serde_json::to_string(&response) {
-                        stats.add_bytes_sent(json.len() as u64 + 1); // +1 for newline
-                    }
+                    stats.add_bytes_sent(wire_size_estimate(&response, &self.size_estimate_warned));
                 }
 
                 // Send response
                 let send_result = {
-                    let mut guard = send.lock().unwrap();
+                    let mut guard = lock_recovering(&send, "transport send");
                     guard(cx, &JsonRpcMessage::Response(response))
                 };
                 if let Err(e) = send_result {
                     error!(target: targets::TRANSPORT, "Failed to send response: {}", e);
                 }
             }
+        };
+
+        // Track connection close
+        if let Some(ref stats) = self.stats {
+            stats.connection_closed();
         }
+        reason
     }
 
     /// Handles a single JSON-RPC request.
@@ -817,38 +1511,84 @@ impl Server {
             Cx::for_request_with_budget(budget)
         };
 
-        let _active_guard = id.clone().map(|request_id| {
-            ActiveRequestGuard::new(&self.active_requests, request_id, request_cx.clone())
-        });
+        let admission = match id.clone() {
+            Some(request_id) => self
+                .admit_request(
+                    request_id,
+                    request_cx.clone(),
+                    counts_toward_inflight_limit(&method),
+                )
+                .map(Some),
+            None => Ok(None),
+        };
+
+        // Snapshot what the session has disabled, to detect list changes
+        let disabled_before = self
+            .advertises_list_changed()
+            .then(|| DisabledComponents::of(session));
 
         // Dispatch based on method, passing the budget, notification sender, and request sender
-        let result = self.dispatch_method(
-            &request_cx,
-            session,
-            request,
-            request_id,
-            &budget,
-            notification_sender,
-            request_sender,
-        );
+        let result = match admission {
+            Ok(_active_guard) => self.dispatch_method(
+                &request_cx,
+                session,
+                request,
+                request_id,
+                &budget,
+                notification_sender,
+                request_sender,
+            ),
+            Err(e) => Err(e),
+        };
+
+        if let Some(before) = disabled_before {
+            self.notify_list_changes(session, &before, notification_sender);
+        }
 
         // Record statistics
         let latency = start_time.elapsed();
         if let Some(ref stats) = self.stats {
             match &result {
                 Ok(_) => stats.record_request(&method, latency, true),
-                Err(e) if e.code == fastmcp_core::McpErrorCode::RequestCancelled => {
-                    stats.record_cancelled(&method, latency);
-                }
-                Err(_) => stats.record_request(&method, latency, false),
+                Err(e) => stats.record_error(&method, latency, e.code),
             }
         }
+        let subject = session.take_request_subject();
+        if let (Some(sink), Some(request_id)) = (&self.audit_sink, &id) {
+            sink.record(AuditEvent {
+                timestamp: chrono::DateTime::<chrono::Utc>::from(self.clock.now_system())
+                    .to_rfc3339(),
+                subject,
+                method: method.clone(),
+                request_id: request_id.clone(),
+                outcome: match &result {
+                    Ok(_) => AuditOutcome::Success,
+                    Err(e) => AuditOutcome::Error {
+                        code: i32::from(e.code),
+                        message: e.message.clone(),
+                    },
+                },
+                latency,
+            });
+        }
+        if let Err(e) = &result {
+            self.record_recent_error(&method, e);
+        }
+        if !is_notification && !matches!(method.as_str(), "ping" | "health/check") {
+            // Mask like the response does so probes can't read internal details.
+            let error = result
+                .as_ref()
+                .err()
+                .map(|e| e.masked(self.mask_error_details).message);
+            self.health.record(error.as_deref());
+        }
 
         // If it's a notification (no ID), we must not reply
         if is_notification {
             if let Err(e) = result {
-                fastmcp_core::logging::error!(
+                log::log!(
                     target: targets::HANDLER,
+                    self.notification_failure_level(&method, &e),
                     "Notification '{}' failed: {}",
                     method,
                     e
@@ -888,6 +1628,147 @@ impl Server {
         }
     }
 
+    /// Returns whether `listChanged` is advertised for any component list.
+    fn advertises_list_changed(&self) -> bool {
+        self.capabilities
+            .tools
+            .as_ref()
+            .is_some_and(|c| c.list_changed)
+            || self
+                .capabilities
+                .resources
+                .as_ref()
+                .is_some_and(|c| c.list_changed)
+            || self
+                .capabilities
+                .prompts
+                .as_ref()
+                .is_some_and(|c| c.list_changed)
+    }
+
+    /// Sends `notifications/<list>/list_changed` for each advertised list a
+    /// request changed by enabling or disabling a component for the session.
+    ///
+    /// Nothing is sent before the session has been initialized.
+    fn notify_list_changes(
+        &self,
+        session: &Session,
+        before: &DisabledComponents,
+        notification_sender: &NotificationSender,
+    ) {
+        if !session.is_initialized() {
+            return;
+        }
+        let after = DisabledComponents::of(session);
+        let lists = [
+            (
+                "tools",
+                self.capabilities
+                    .tools
+                    .as_ref()
+                    .is_some_and(|c| c.list_changed),
+                before.tools != after.tools,
+            ),
+            (
+                "resources",
+                self.capabilities
+                    .resources
+                    .as_ref()
+                    .is_some_and(|c| c.list_changed),
+                before.resources != after.resources,
+            ),
+            (
+                "prompts",
+                self.capabilities
+                    .prompts
+                    .as_ref()
+                    .is_some_and(|c| c.list_changed),
+                before.prompts != after.prompts,
+            ),
+        ];
+        for (list, advertised, changed) in lists {
+            if advertised && changed {
+                debug!(target: targets::SESSION, "Sending {} list_changed notification", list);
+                notification_sender(JsonRpcRequest::notification(
+                    format!("notifications/{list}/list_changed"),
+                    None,
+                ));
+            }
+        }
+    }
+
+    /// Chooses how loudly to log a failed notification.
+    ///
+    /// Unknown notifications are expected from clients that send optional
+    /// ones, so they stay out of the error log.
+    fn notification_failure_level(&self, method: &str, error: &McpError) -> Level {
+        if error.code != McpErrorCode::MethodNotFound {
+            Level::Error
+        } else if self
+            .ignored_notification_prefixes
+            .iter()
+            .any(|prefix| method.starts_with(prefix.as_str()))
+        {
+            Level::Trace
+        } else {
+            Level::Debug
+        }
+    }
+
+    /// Registers a request as active, enforcing the in-flight cap if `limited`.
+    ///
+    /// Only other limited requests count toward the cap. The check and the
+    /// insertion happen under one lock, so concurrent callers cannot
+    /// overshoot it.
+    fn admit_request(
+        &self,
+        id: RequestId,
+        cx: Cx,
+        limited: bool,
+    ) -> McpResult<ActiveRequestGuard<'_>> {
+        let mut active = lock_recovering(&self.active_requests, "active_requests");
+
+        if let (true, Some(max)) = (limited, self.max_inflight_requests) {
+            let occupied = |active: &BTreeMap<RequestId, ActiveRequest>| {
+                active.values().filter(|request| request.limited).count()
+            };
+            let busy = || {
+                McpError::new(
+                    McpErrorCode::RequestCancelled,
+                    format!("Server busy: {max} requests already in flight"),
+                )
+            };
+            match self.inflight_overflow {
+                InflightOverflow::Reject => {
+                    if occupied(&active) >= max {
+                        warn!(target: targets::SERVER, "Rejecting request {}: in-flight cap reached", id);
+                        return Err(busy());
+                    }
+                }
+                InflightOverflow::Queue(timeout) => {
+                    let deadline = Instant::now() + timeout;
+                    while occupied(&active) >= max {
+                        let remaining = deadline.saturating_duration_since(Instant::now());
+                        if remaining.is_zero() {
+                            warn!(target: targets::SERVER, "Request {} timed out waiting for an in-flight slot", id);
+                            return Err(busy());
+                        }
+                        active = self
+                            .inflight_released
+                            .wait_timeout(active, remaining)
+                            .unwrap_or_else(std::sync::PoisonError::into_inner)
+                            .0;
+                    }
+                }
+            }
+        }
+
+        Ok(
+            ActiveRequestGuard::insert(&self.active_requests, &mut active, id, cx, limited)
+                .with_release_signal(&self.inflight_released),
+        )
+    }
+
     /// Creates a budget for a new request based on server configuration.
     fn create_request_budget(&self) -> Budget {
         if self.request_timeout_secs == 0 {
@@ -899,6 +1780,22 @@ impl Server {
         }
     }
 
+    /// Returns the capability `method` belongs to if the server did not
+    /// advertise it.
+    ///
+    /// `tools/*`, `resources/*`, and `prompts/*` are only served when the
+    /// matching capability was advertised, so a client learns the server has
+    /// none rather than that one tool, resource, or prompt is unknown.
+    fn unadvertised_capability(&self, method: &str) -> Option<&'static str> {
+        let (capability, advertised) = match method.split_once('/')?.0 {
+            "tools" => ("tools", self.capabilities.tools.is_some()),
+            "resources" => ("resources", self.capabilities.resources.is_some()),
+            "prompts" => ("prompts", self.capabilities.prompts.is_some()),
+            _ => return None,
+        };
+        (!advertised).then_some(capability)
+    }
+
     /// Dispatches a request to the appropriate handler.
     #[allow(clippy::too_many_arguments, clippy::too_many_lines)]
     fn dispatch_method(
@@ -924,13 +1821,32 @@ impl Server {
             ));
         }
 
+        if self.disabled_methods.contains(&request.method) {
+            return Err(McpError::method_not_found(&request.method));
+        }
+
         // Check initialization state
-        if !session.is_initialized() && request.method != "initialize" && request.method != "ping" {
+        if !session.is_initialized()
+            && !matches!(
+                request.method.as_str(),
+                "initialize" | "ping" | "health/check"
+            )
+        {
             return Err(McpError::invalid_request(
                 "Server not initialized. Client must send 'initialize' first.",
             ));
         }
 
+        if let Some(capability) = self.unadvertised_capability(&request.method) {
+            return Err(McpError::new(
+                McpErrorCode::MethodNotFound,
+                format!(
+                    "Method not found: {} (server does not advertise the {capability} capability)",
+                    request.method
+                ),
+            ));
+        }
+
         if let Some(task_manager) = &self.task_manager {
             task_manager.set_notification_sender(Arc::clone(notification_sender));
         }
@@ -938,7 +1854,11 @@ impl Server {
         // Middleware: on_request
         // We use a temporary context derived from the request context for middleware
         // so they can access session state but share the request's lifecycle.
-        let mw_ctx = McpContext::with_state(cx.clone(), request_id, session.state().clone());
+        let mut mw_ctx = McpContext::with_state(cx.clone(), request_id, session.state().clone())
+            .with_method(request.method.clone());
+        if let Some(id) = &request.id {
+            mw_ctx = mw_ctx.with_request_id_display(id.to_string());
+        }
         let mut entered_middleware: Vec<&dyn crate::Middleware> = Vec::new();
 
         for m in self.middleware.iter() {
@@ -967,24 +1887,29 @@ impl Server {
                 params: request.params.as_ref(),
                 request_id,
             };
-            self.authenticate_request(cx, request_id, session, auth_request)?;
+            let auth = self.authenticate_request(cx, request_id, session, auth_request)?;
+            session.set_request_subject(auth.subject.clone());
+            if let Some(limiter) = &self.rate_limiter {
+                limiter.check(&auth)?;
+            }
         }
 
         let method = &request.method;
         let params = request.params.clone();
 
         // Create bidirectional senders based on client capabilities
-        let bidirectional_senders = self.create_bidirectional_senders(session, request_sender);
+        let bidirectional_senders =
+            self.create_bidirectional_senders(session, request_sender, notification_sender);
 
         let result = match method.as_str() {
             "initialize" => {
-                let params: InitializeParams = parse_params(params)?;
-                let result = self.router.handle_initialize(
-                    cx,
-                    session,
-                    params,
-                    self.instructions.as_deref(),
-                )?;
+                let params = parse_initialize_params(params)?;
+                let instructions = self.session_instructions(session, &params);
+                session.set_instructions(instructions.clone());
+                let result =
+                    self.router
+                        .handle_initialize(cx, session, params, instructions.as_deref())?;
+                self.health.mark_initialized();
                 Ok(serde_json::to_value(result).map_err(McpError::from)?)
             }
             "initialized" => {
@@ -1013,11 +1938,15 @@ impl Server {
                 let result = self.router.handle_tools_call(
                     cx,
                     request_id,
+                    request.id.as_ref(),
                     params,
                     budget,
                     session.state().clone(),
                     Some(notification_sender),
                     bidirectional_senders.as_ref(),
+                    session
+                        .client_capabilities()
+                        .is_some_and(|caps| caps.supports_streaming_content()),
                 )?;
                 Ok(serde_json::to_value(result).map_err(McpError::from)?)
             }
@@ -1042,6 +1971,7 @@ impl Server {
                 let result = self.router.handle_resources_read(
                     cx,
                     request_id,
+                    request.id.as_ref(),
                     &params,
                     budget,
                     session.state().clone(),
@@ -1075,6 +2005,7 @@ impl Server {
                 let result = self.router.handle_prompts_get(
                     cx,
                     request_id,
+                    request.id.as_ref(),
                     params,
                     budget,
                     session.state().clone(),
@@ -1084,9 +2015,22 @@ impl Server {
                 Ok(serde_json::to_value(result).map_err(McpError::from)?)
             }
             "ping" => {
-                // Simple ping-pong for health checks
+                // Simple ping-pong for liveness checks
                 Ok(serde_json::json!({}))
             }
+            "health/check" => {
+                // The probe itself is registered as active; don't count it.
+                let active = self
+                    .active_request_count()
+                    .saturating_sub(usize::from(request.id.is_some()));
+                let report = self.health.report(
+                    session.is_initialized(),
+                    active,
+                    self.uptime(),
+                    self.build_info.clone(),
+                );
+                Ok(serde_json::to_value(report).map_err(McpError::from)?)
+            }
             // Task methods (Docket/SEP-1686)
             "tasks/list" => {
                 let params: ListTasksParams = parse_params_or_default(params)?;
@@ -1116,7 +2060,10 @@ impl Server {
                         .handle_tasks_submit(cx, params, self.task_manager.as_ref())?;
                 Ok(serde_json::to_value(result).map_err(McpError::from)?)
             }
-            _ => Err(McpError::method_not_found(method)),
+            _ => match self.custom_methods.get(method.as_str()) {
+                Some(handler) => handler(&mw_ctx, params),
+                None => Err(McpError::method_not_found(method)),
+            },
         };
 
         let final_result = match result {
@@ -1166,19 +2113,32 @@ impl Server {
     /// Creates bidirectional senders based on client capabilities.
     ///
     /// Returns `Some(BidirectionalSenders)` if the client supports any bidirectional
-    /// features (sampling, elicitation), or `None` if no features are supported.
+    /// features (sampling, elicitation) or handler logs can reach it, or `None`
+    /// otherwise. Logs reach the client once it has set a level and the server
+    /// advertises the `logging` capability.
     fn create_bidirectional_senders(
         &self,
         session: &Session,
         request_sender: &bidirectional::RequestSender,
+        notification_sender: &NotificationSender,
     ) -> Option<handler::BidirectionalSenders> {
         let supports_sampling = session.supports_sampling();
         let supports_elicitation = session.supports_elicitation();
+        let log_level = session
+            .log_level()
+            .filter(|_| self.capabilities.logging.is_some());
 
-        if !supports_sampling && !supports_elicitation {
+        if !supports_sampling && !supports_elicitation && log_level.is_none() {
             return None;
         }
 
+        let request_sender = match session.client_capabilities() {
+            Some(caps) => request_sender
+                .clone()
+                .with_client_capabilities(caps.clone()),
+            None => request_sender.clone(),
+        };
+
         let mut senders = handler::BidirectionalSenders::new();
 
         if supports_sampling {
@@ -1195,13 +2155,22 @@ impl Server {
             senders = senders.with_elicitation(elicitation_sender);
         }
 
+        if let Some(level) = log_level {
+            let sender = notification_sender.clone();
+            let log_sender: Arc<dyn fastmcp_core::LogSender> =
+                Arc::new(handler::LogNotificationSender::new(level, move |req| {
+                    sender(req);
+                }));
+            senders = senders.with_log(log_sender);
+        }
+
         Some(senders)
     }
 
     fn should_authenticate(&self, method: &str) -> bool {
         !matches!(
             method,
-            "initialize" | "initialized" | "notifications/cancelled" | "ping"
+            "initialize" | "initialized" | "notifications/cancelled" | "ping" | "health/check"
         )
     }
 
@@ -1209,24 +2178,92 @@ impl Server {
         &self,
         cx: &Cx,
         request_id: u64,
-        session: &Session,
+        session: &mut Session,
         request: AuthRequest<'_>,
     ) -> Result<AuthContext, McpError> {
         let Some(provider) = &self.auth_provider else {
             return Ok(AuthContext::anonymous());
         };
 
+        let token = match provider.cache_policy() {
+            AuthCachePolicy::PerSession => request.access_token(),
+            AuthCachePolicy::Never => None,
+        };
+        let now = self.clock.now_system();
+        if let Some(auth) = token
+            .as_ref()
+            .and_then(|token| session.cached_auth(token, now))
+        {
+            trace!(target: targets::SESSION, "Reusing cached auth context");
+            return Ok(auth.clone());
+        }
+
         let ctx = McpContext::with_state(cx.clone(), request_id, session.state().clone());
-        let auth = provider.authenticate(&ctx, request)?;
+        let auth = match provider.authenticate(&ctx, request) {
+            Ok(auth) => auth,
+            Err(e) => {
+                session.clear_cached_auth();
+                return Err(self.with_auth_challenge(e, request));
+            }
+        };
         if !ctx.set_auth(auth.clone()) {
             debug!(
                 target: targets::SESSION,
                 "Auth context not stored (session state unavailable)"
             );
         }
+        match token {
+            Some(token) => session.cache_auth(token, auth.clone()),
+            None => session.clear_cached_auth(),
+        }
         Ok(auth)
     }
 
+    /// Attaches a `WWW-Authenticate` challenge to an auth failure when an
+    /// authorization server is configured.
+    fn with_auth_challenge(&self, error: McpError, request: AuthRequest<'_>) -> McpError {
+        if self.protected_resource.authorization_servers.is_empty() {
+            return error;
+        }
+        // RFC 6750: omit the error code when no credentials were presented.
+        let (code, description) = if request.access_token().is_some() {
+            (
+                Some("invalid_token".to_string()),
+                Some(error.message.clone()),
+            )
+        } else {
+            (None, None)
+        };
+        AuthChallenge {
+            realm: Some(self.info.name.clone()),
+            resource_metadata: self
+                .protected_resource
+                .resource_url
+                .as_deref()
+                .map(oauth::protected_resource_metadata_url),
+            error: code,
+            error_description: description,
+        }
+        .attach(error)
+    }
+
+    /// Returns the RFC 9728 protected resource metadata for this server when
+    /// reached at `resource_url`.
+    ///
+    /// Lists the servers added with
+    /// [`authorization_server_url`](ServerBuilder::authorization_server_url)
+    /// and the scopes from [`scopes_supported`](ServerBuilder::scopes_supported).
+    /// The SSE accept loop serves it at
+    /// [`PROTECTED_RESOURCE_METADATA_PATH`](oauth::PROTECTED_RESOURCE_METADATA_PATH).
+    #[must_use]
+    pub fn protected_resource_metadata(&self, resource_url: &str) -> serde_json::Value {
+        oauth::protected_resource_document(
+            resource_url,
+            &self.protected_resource.authorization_servers,
+            &self.protected_resource.scopes_supported,
+        )
+    }
+
     fn handle_cancelled_notification(&self, params: CancelledParams) {
         let reason = params.reason.as_deref().unwrap_or("unspecified");
         let await_cleanup = params.await_cleanup.unwrap_or(false);
@@ -1238,10 +2275,7 @@ impl Server {
             await_cleanup
         );
         let active = {
-            let guard = self.active_requests.lock().unwrap_or_else(|poisoned| {
-                error!(target: targets::SERVER, "active_requests lock poisoned, recovering");
-                poisoned.into_inner()
-            });
+            let guard = lock_recovering(&self.active_requests, "active_requests");
             guard
                 .get(&params.request_id)
                 .map(|entry| (entry.cx.clone(), entry.region_id, entry.completion.clone()))
@@ -1270,10 +2304,7 @@ impl Server {
 
     fn cancel_active_requests(&self, kind: CancelKind, await_cleanup: bool) {
         let active: Vec<(RequestId, RegionId, Cx, Arc<RequestCompletion>)> = {
-            let guard = self.active_requests.lock().unwrap_or_else(|poisoned| {
-                error!(target: targets::SERVER, "active_requests lock poisoned in cancel_active_requests, recovering");
-                poisoned.into_inner()
-            });
+            let guard = lock_recovering(&self.active_requests, "active_requests");
             guard
                 .iter()
                 .map(|(request_id, entry)| {
@@ -1357,15 +2388,6 @@ impl Server {
         }
     }
 
-    fn log_level_rank(level: LogLevel) -> u8 {
-        match level {
-            LogLevel::Debug => 1,
-            LogLevel::Info => 2,
-            LogLevel::Warning => 3,
-            LogLevel::Error => 4,
-        }
-    }
-
     fn emit_log_notification(
         &self,
         session: &Session,
@@ -1376,7 +2398,7 @@ impl Server {
         let Some(min_level) = session.log_level() else {
             return;
         };
-        if Self::log_level_rank(level) < Self::log_level_rank(min_level) {
+        if handler::log_level_rank(level) < handler::log_level_rank(min_level) {
             return;
         }
 
@@ -1428,8 +2450,38 @@ impl Server {
     }
 }
 
+/// Exits the process with the outcome of a `try_run_*` method.
+pub(crate) fn exit_with(result: Result<(), ServerRunError>) -> ! {
+    match result {
+        Ok(()) => std::process::exit(0),
+        Err(e) => {
+            error!(target: targets::SERVER, "{}", e);
+            std::process::exit(e.exit_code())
+        }
+    }
+}
+
 const AWAIT_CLEANUP_TIMEOUT: Duration = Duration::from_secs(5);
 
+/// The components a session has disabled, compared before and after a
+/// request to detect list changes.
+struct DisabledComponents {
+    tools: HashSet<String>,
+    resources: HashSet<String>,
+    prompts: HashSet<String>,
+}
+
+impl DisabledComponents {
+    fn of(session: &Session) -> Self {
+        let state = session.state();
+        Self {
+            tools: state.disabled_tools(),
+            resources: state.disabled_resources(),
+            prompts: state.disabled_prompts(),
+        }
+    }
+}
+
 struct RequestCompletion {
     done: Mutex<bool>,
     cv: Condvar,
@@ -1497,6 +2549,8 @@ struct ActiveRequest {
     cx: Cx,
     region_id: RegionId,
     completion: Arc<RequestCompletion>,
+    /// Whether this request occupies an in-flight slot.
+    limited: bool,
 }
 
 impl ActiveRequest {
@@ -1506,24 +2560,39 @@ impl ActiveRequest {
             cx,
             region_id,
             completion,
+            limited: false,
         }
     }
 }
 
 struct ActiveRequestGuard<'a> {
-    map: &'a Mutex<HashMap<RequestId, ActiveRequest>>,
+    map: &'a Mutex<BTreeMap<RequestId, ActiveRequest>>,
     id: RequestId,
     completion: Arc<RequestCompletion>,
+    /// Notified after the entry is removed, to wake requests queued for a slot.
+    released: Option<&'a Condvar>,
 }
 
 impl<'a> ActiveRequestGuard<'a> {
-    fn new(map: &'a Mutex<HashMap<RequestId, ActiveRequest>>, id: RequestId, cx: Cx) -> Self {
+    fn new(map: &'a Mutex<BTreeMap<RequestId, ActiveRequest>>, id: RequestId, cx: Cx) -> Self {
+        let mut guard = lock_recovering(map, "active_requests");
+        Self::insert(map, &mut guard, id, cx, false)
+    }
+
+    /// Inserts the entry into an already-locked map.
+    ///
+    /// `limited` marks the entry as occupying an in-flight slot.
+    fn insert(
+        map: &'a Mutex<BTreeMap<RequestId, ActiveRequest>>,
+        active: &mut BTreeMap<RequestId, ActiveRequest>,
+        id: RequestId,
+        cx: Cx,
+        limited: bool,
+    ) -> Self {
         let completion = Arc::new(RequestCompletion::new());
-        let entry = ActiveRequest::new(cx, completion.clone());
-        let mut guard = map
-            .lock()
-            .unwrap_or_else(std::sync::PoisonError::into_inner);
-        if guard.insert(id.clone(), entry).is_some() {
+        let mut entry = ActiveRequest::new(cx, completion.clone());
+        entry.limited = limited;
+        if active.insert(id.clone(), entry).is_some() {
             fastmcp_core::logging::warn!(
                 target: targets::SESSION,
                 "Active request replaced for requestId={}",
@@ -1534,17 +2603,20 @@ impl<'a> ActiveRequestGuard<'a> {
             map,
             id,
             completion,
+            released: None,
         }
     }
+
+    fn with_release_signal(mut self, released: &'a Condvar) -> Self {
+        self.released = Some(released);
+        self
+    }
 }
 
 impl Drop for ActiveRequestGuard<'_> {
     fn drop(&mut self) {
         {
-            let mut guard = self
-                .map
-                .lock()
-                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            let mut guard = lock_recovering(self.map, "active_requests");
             match guard.get(&self.id) {
                 Some(entry) if Arc::ptr_eq(&entry.completion, &self.completion) => {
                     guard.remove(&self.id);
@@ -1565,10 +2637,105 @@ impl Drop for ActiveRequestGuard<'_> {
                 }
             }
         }
+        if let Some(released) = self.released {
+            released.notify_all();
+        }
         self.completion.mark_done();
     }
 }
 
+/// Returns true for methods that run user handlers and count toward
+/// [`ServerBuilder::max_inflight_requests`].
+fn counts_toward_inflight_limit(method: &str) -> bool {
+    matches!(method, "tools/call" | "resources/read")
+}
+
+/// Estimates the framed size of a message for byte accounting.
+///
+/// Re-serializes to JSON (+1 for the newline). This is approximate but
+/// accurate enough for statistics. If serialization fails, the `Debug`
+/// length stands in so the message is not counted as zero bytes, and a
+/// warning is logged the first time this happens (tracked by `warned`).
+fn wire_size_estimate<T: serde::Serialize + std::fmt::Debug>(
+    value: &T,
+    warned: &AtomicBool,
+) -> u64 {
+    let len = match serde_json::to_string(value) {
+        Ok(json) => json.len(),
+        Err(e) => {
+            if !warned.swap(true, Ordering::Relaxed) {
+                warn!(
+                    target: targets::SERVER,
+                    "Failed to serialize message for byte accounting ({}); \
+                     estimating sizes from Debug output",
+                    e
+                );
+            }
+            format!("{value:?}").len()
+        }
+    };
+    len as u64 + 1
+}
+
+/// Counts consecutive frames that failed to parse on one connection.
+///
+/// Each bad frame is answered with a JSON-RPC parse error (-32700) when its
+/// request id could be salvaged, and logged otherwise. Once `limit`
+/// consecutive failures are seen, the caller should close the connection.
+struct ParseErrorTracker {
+    consecutive: u32,
+    limit: Option<u32>,
+}
+
+impl ParseErrorTracker {
+    fn new(limit: Option<u32>) -> Self {
+        Self {
+            consecutive: 0,
+            limit,
+        }
+    }
+
+    /// Records an unparseable frame.
+    ///
+    /// Returns the parse error response to send back (if an id was recovered)
+    /// and whether the connection should now be closed.
+    fn record(&mut self, id: Option<RequestId>, reason: &str) -> (Option<JsonRpcResponse>, bool) {
+        self.consecutive = self.consecutive.saturating_add(1);
+
+        let response = match id {
+            Some(id) => {
+                debug!(target: targets::TRANSPORT, "Invalid frame for request {}: {}", id, reason);
+                Some(JsonRpcResponse::error(
+                    Some(id),
+                    JsonRpcError {
+                        code: McpErrorCode::ParseError.into(),
+                        message: format!("Parse error: {reason}"),
+                        data: None,
+                    },
+                ))
+            }
+            None => {
+                warn!(target: targets::TRANSPORT, "Dropping invalid frame with no recoverable id: {}", reason);
+                None
+            }
+        };
+
+        let close = self.limit.is_some_and(|limit| self.consecutive >= limit);
+        if close {
+            error!(
+                target: targets::TRANSPORT,
+                "Closing connection after {} consecutive invalid frames", self.consecutive
+            );
+        }
+        (response, close)
+    }
+
+    /// Resets the counter after a frame parsed successfully.
+    fn reset(&mut self) {
+        self.consecutive = 0;
+    }
+}
+
 /// Checks if banner should be suppressed via environment variable.
 ///
 /// This is a legacy check. Prefer using `ConsoleConfig` for banner control.
@@ -1586,6 +2753,64 @@ fn parse_params<T: serde::de::DeserializeOwned>(
     serde_json::from_value(value).map_err(|e| McpError::invalid_params(e.to_string()))
 }
 
+/// Parses `initialize` params, naming the offending field when a client gets
+/// it wrong.
+///
+/// Every error carries the protocol versions this server supports in its
+/// `data`, so a new client can correct its handshake without guessing.
+fn parse_initialize_params(
+    params: Option<serde_json::Value>,
+) -> Result<InitializeParams, McpError> {
+    let invalid = |message: &str| {
+        McpError::with_data(
+            McpErrorCode::InvalidParams,
+            message,
+            serde_json::json!({ "supportedVersions": [PROTOCOL_VERSION] }),
+        )
+    };
+
+    let Some(value) = params else {
+        return Err(invalid(
+            "initialize params are required (protocolVersion, capabilities, clientInfo)",
+        ));
+    };
+    let Some(fields) = value.as_object() else {
+        return Err(invalid("initialize params must be a JSON object"));
+    };
+
+    match fields.get("protocolVersion") {
+        None | Some(serde_json::Value::Null) => {
+            return Err(invalid(
+                "protocol_version is required (\"protocolVersion\")",
+            ));
+        }
+        Some(serde_json::Value::String(_)) => {}
+        Some(_) => {
+            return Err(invalid(
+                "protocol_version (\"protocolVersion\") must be a string such as \"2024-11-05\"",
+            ));
+        }
+    }
+    if fields
+        .get("clientInfo")
+        .is_none_or(serde_json::Value::is_null)
+    {
+        return Err(invalid(
+            "clientInfo is required (an object with \"name\" and \"version\")",
+        ));
+    }
+    if fields
+        .get("capabilities")
+        .is_none_or(serde_json::Value::is_null)
+    {
+        return Err(invalid(
+            "capabilities is required (use {} if the client has none)",
+        ));
+    }
+
+    serde_json::from_value(value).map_err(|e| invalid(&format!("Invalid initialize params: {e}")))
+}
+
 /// Parses optional parameters from JSON, using default if not provided.
 fn parse_params_or_default<T: serde::de::DeserializeOwned + Default>(
     params: Option<serde_json::Value>,
@@ -1651,6 +2876,20 @@ impl<T: Transport> SharedTransport<T> {
     }
 }
 
+/// Locks `mutex`, recovering the data if a thread panicked while holding it.
+///
+/// The request registry, lifespan hooks, and transport sender hold no
+/// invariant a mid-update panic can break, so a panicking handler should not
+/// make every later request panic on the poisoned lock. The poison flag is
+/// cleared so the error is logged once per panic.
+fn lock_recovering<'a, T: ?Sized>(mutex: &'a Mutex<T>, name: &str) -> MutexGuard<'a, T> {
+    mutex.lock().unwrap_or_else(|poisoned| {
+        error!(target: targets::SERVER, "{} lock poisoned, recovering", name);
+        mutex.clear_poison();
+        poisoned.into_inner()
+    })
+}
+
 fn transport_lock_error() -> TransportError {
     TransportError::Io(std::io::Error::other("transport lock poisoned"))
 }
@@ -1680,14 +2919,15 @@ where
 /// independently of the main transport.
 ///
 /// The sender uses NDJSON format (newline-delimited JSON) to match the
-/// standard MCP transport format.
-fn create_notification_sender() -> NotificationSender {
-    use std::sync::Mutex;
-
-    // Use AsyncStdout so notifications share the global stdout lock used by
-    // the transport writer, preventing interleaved NDJSON writes.
-    let stdout = Mutex::new(AsyncStdout::new());
-    let codec = Codec::new();
+/// standard MCP transport format, and writes through `writer`, which decides
+/// when output is flushed (see [`FlushPolicy`]).
+fn create_notification_sender<W: Write + Send + 'static>(
+    frame_mode: FrameMode,
+    canonical: bool,
+    writer: Arc<NotificationWriter<W>>,
+) -> NotificationSender {
+    let mut codec = Codec::with_frame_mode(frame_mode);
+    codec.set_canonical(canonical);
 
     Arc::new(move |request: JsonRpcRequest| {
         let bytes = match codec.encode_request(&request) {
@@ -1697,16 +2937,6 @@ fn create_notification_sender() -> NotificationSender {
                 return;
             }
         };
-
-        if let Ok(mut stdout) = stdout.lock() {
-            if let Err(e) = stdout.write_all_unchecked(&bytes) {
-                log::error!(target: targets::TRANSPORT, "Failed to send notification: {}", e);
-            }
-            if let Err(e) = stdout.flush_unchecked() {
-                log::error!(target: targets::TRANSPORT, "Failed to flush notification: {}", e);
-            }
-        } else {
-            log::warn!(target: targets::SERVER, "Failed to acquire stdout lock for notification");
-        }
+        writer.send(&bytes);
     })
 }
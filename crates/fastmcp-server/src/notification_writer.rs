@@ -0,0 +1,254 @@
+//! Buffered output for stdio notifications.
+//!
+//! Flushing after every notification keeps clients up to date but costs a
+//! write and flush per progress update. With [`FlushPolicy::Batched`],
+//! [`NotificationWriter`] collects encoded notifications and writes them out
+//! together once enough are waiting or the oldest has waited long enough. The
+//! server flushes it before every response, so notifications still reach the
+//! client ahead of the response they belong to.
+
+use std::io::Write;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError, Weak};
+use std::time::{Duration, Instant};
+
+use fastmcp_core::logging::{error, targets};
+
+/// How often an idle flusher checks whether its writer was dropped.
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// When notifications sent over stdio are written out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FlushPolicy {
+    /// Write and flush each notification as it is sent.
+    #[default]
+    Always,
+    /// Buffer notifications and write them out together once `max_pending`
+    /// are waiting or the oldest has waited `max_delay`.
+    Batched {
+        /// Longest a notification waits before being written.
+        max_delay: Duration,
+        /// Number of waiting notifications that triggers a write.
+        max_pending: usize,
+    },
+}
+
+/// Notifications waiting to be written.
+struct Pending<W> {
+    writer: W,
+    bytes: Vec<u8>,
+    count: usize,
+    oldest: Option<Instant>,
+}
+
+impl<W: Write> Pending<W> {
+    /// Writes out and flushes everything waiting.
+    fn write_out(&mut self) {
+        if self.count == 0 {
+            return;
+        }
+        if let Err(e) = self.writer.write_all(&self.bytes) {
+            error!(target: targets::TRANSPORT, "Failed to send notification: {}", e);
+        }
+        if let Err(e) = self.writer.flush() {
+            error!(target: targets::TRANSPORT, "Failed to flush notification: {}", e);
+        }
+        self.bytes.clear();
+        self.count = 0;
+        self.oldest = None;
+    }
+}
+
+/// Writes encoded notifications according to a [`FlushPolicy`].
+pub(crate) struct NotificationWriter<W: Write> {
+    pending: Mutex<Pending<W>>,
+    /// Wakes the background flusher when the first notification is buffered.
+    wake: Condvar,
+    policy: FlushPolicy,
+}
+
+impl<W: Write + Send + 'static> NotificationWriter<W> {
+    /// Creates a writer, starting a background flusher for batched policies.
+    pub(crate) fn new(writer: W, policy: FlushPolicy) -> Arc<Self> {
+        let this = Arc::new(Self {
+            pending: Mutex::new(Pending {
+                writer,
+                bytes: Vec::new(),
+                count: 0,
+                oldest: None,
+            }),
+            wake: Condvar::new(),
+            policy,
+        });
+        if let FlushPolicy::Batched { max_delay, .. } = policy {
+            let weak = Arc::downgrade(&this);
+            std::thread::spawn(move || run_flusher(&weak, max_delay));
+        }
+        this
+    }
+
+    /// Sends one encoded notification.
+    pub(crate) fn send(&self, bytes: &[u8]) {
+        let mut pending = self.lock();
+        pending.bytes.extend_from_slice(bytes);
+        pending.count += 1;
+        match self.policy {
+            FlushPolicy::Always => pending.write_out(),
+            FlushPolicy::Batched { max_pending, .. } => {
+                if pending.count >= max_pending {
+                    pending.write_out();
+                } else if pending.oldest.is_none() {
+                    pending.oldest = Some(Instant::now());
+                    self.wake.notify_one();
+                }
+            }
+        }
+    }
+
+    /// Writes out any buffered notifications.
+    pub(crate) fn flush(&self) {
+        self.lock().write_out();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Pending<W>> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<W: Write> Drop for NotificationWriter<W> {
+    fn drop(&mut self) {
+        self.pending
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .write_out();
+    }
+}
+
+/// Writes out notifications that have waited `max_delay`, until the writer
+/// is dropped.
+fn run_flusher<W: Write + Send + 'static>(
+    writer: &Weak<NotificationWriter<W>>,
+    max_delay: Duration,
+) {
+    while let Some(this) = writer.upgrade() {
+        let mut pending = this.lock();
+        let wait = match pending.oldest {
+            Some(oldest) => max_delay.saturating_sub(oldest.elapsed()),
+            None => IDLE_CHECK_INTERVAL,
+        };
+        if pending.oldest.is_some() && wait.is_zero() {
+            pending.write_out();
+            continue;
+        }
+        // The timeout also lets the thread notice the writer was dropped
+        let _ = this
+            .wake
+            .wait_timeout(pending, wait)
+            .unwrap_or_else(PoisonError::into_inner);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records each underlying write.
+    #[derive(Clone, Default)]
+    struct CountingWriter {
+        writes: Arc<Mutex<Vec<Vec<u8>>>>,
+    }
+
+    impl CountingWriter {
+        fn write_count(&self) -> usize {
+            self.writes.lock().unwrap().len()
+        }
+
+        fn lines(&self) -> Vec<String> {
+            let bytes = self.writes.lock().unwrap().concat();
+            String::from_utf8(bytes)
+                .unwrap()
+                .lines()
+                .map(str::to_string)
+                .collect()
+        }
+    }
+
+    impl Write for CountingWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.writes.lock().unwrap().push(buf.to_vec());
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn send_burst<W: Write + Send + 'static>(writer: &NotificationWriter<W>, count: usize) {
+        for n in 0..count {
+            writer.send(format!("progress {n}\n").as_bytes());
+        }
+    }
+
+    #[test]
+    fn always_writes_each_notification() {
+        let out = CountingWriter::default();
+        let writer = NotificationWriter::new(out.clone(), FlushPolicy::Always);
+        send_burst(&writer, 10);
+        assert_eq!(out.write_count(), 10);
+    }
+
+    #[test]
+    fn batched_coalesces_a_burst_and_delivers_everything() {
+        let out = CountingWriter::default();
+        let writer = NotificationWriter::new(
+            out.clone(),
+            FlushPolicy::Batched {
+                max_delay: Duration::from_secs(60),
+                max_pending: 32,
+            },
+        );
+        send_burst(&writer, 100);
+        // Three full batches; the last four wait for a flush
+        assert_eq!(out.write_count(), 3);
+
+        writer.flush();
+        assert_eq!(out.write_count(), 4);
+        let expected: Vec<String> = (0..100).map(|n| format!("progress {n}")).collect();
+        assert_eq!(out.lines(), expected);
+    }
+
+    #[test]
+    fn batched_writes_after_max_delay() {
+        let out = CountingWriter::default();
+        let writer = NotificationWriter::new(
+            out.clone(),
+            FlushPolicy::Batched {
+                max_delay: Duration::from_millis(20),
+                max_pending: 1000,
+            },
+        );
+        send_burst(&writer, 3);
+        assert_eq!(out.write_count(), 0);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while out.write_count() == 0 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(out.lines().len(), 3);
+    }
+
+    #[test]
+    fn dropping_writes_out_pending_notifications() {
+        let out = CountingWriter::default();
+        let writer = NotificationWriter::new(
+            out.clone(),
+            FlushPolicy::Batched {
+                max_delay: Duration::from_secs(60),
+                max_pending: 32,
+            },
+        );
+        send_burst(&writer, 2);
+        drop(writer);
+        assert_eq!(out.lines().len(), 2);
+    }
+}
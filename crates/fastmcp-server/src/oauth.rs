@@ -51,12 +51,15 @@
 //! ```
 
 use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+use fastmcp_core::logging::{targets, warn};
 use fastmcp_core::{AccessToken, AuthContext, McpContext, McpError, McpErrorCode, McpResult};
 
 use crate::auth::{AuthRequest, TokenVerifier};
+use crate::clock::{Clock, SystemClock};
 
 // =============================================================================
 // Configuration
@@ -81,6 +84,12 @@ pub struct OAuthServerConfig {
     pub max_code_verifier_length: usize,
     /// Token entropy bytes (default: 32 = 256 bits).
     pub token_entropy_bytes: usize,
+    /// File registered clients are persisted to (default: none).
+    ///
+    /// When set, clients are loaded from it by [`OAuthServer::new`] and it is
+    /// rewritten after every registration change. See
+    /// [`OAuthServer::save_clients`] for the format.
+    pub client_store_path: Option<PathBuf>,
 }
 
 impl Default for OAuthServerConfig {
@@ -94,6 +103,7 @@ impl Default for OAuthServerConfig {
             min_code_verifier_length: 43,
             max_code_verifier_length: 128,
             token_entropy_bytes: 32,
+            client_store_path: None,
         }
     }
 }
@@ -116,12 +126,18 @@ pub enum ClientType {
 pub struct OAuthClient {
     /// Unique client identifier.
     pub client_id: String,
-    /// Client secret (None for public clients).
-    pub client_secret: Option<String>,
+    /// Salted hash of the client secret (None for public clients).
+    ///
+    /// The plaintext secret is never kept: [`OAuthClientBuilder::secret`]
+    /// hashes it on build, and [`authenticate`](Self::authenticate) hashes
+    /// the presented secret to compare.
+    pub client_secret: Option<SecretHash>,
     /// Client type.
     pub client_type: ClientType,
     /// Allowed redirect URIs.
     pub redirect_uris: Vec<String>,
+    /// Allowed redirect URI path prefixes, each ending in `/*`.
+    pub redirect_uri_patterns: Vec<String>,
     /// Allowed scopes.
     pub allowed_scopes: HashSet<String>,
     /// Client name (for display).
@@ -157,7 +173,9 @@ impl OAuthClient {
             }
         }
 
-        false
+        self.redirect_uri_patterns
+            .iter()
+            .any(|pattern| redirect_pattern_match(pattern, uri))
     }
 
     /// Validates that the requested scopes are allowed for this client.
@@ -170,19 +188,27 @@ impl OAuthClient {
     #[must_use]
     pub fn authenticate(&self, secret: Option<&str>) -> bool {
         match (&self.client_secret, secret) {
-            (Some(expected), Some(provided)) => constant_time_eq(expected, provided),
+            (Some(hash), Some(provided)) => verify_secret_hash(hash, provided),
             (None, None) => self.client_type == ClientType::Public,
             _ => false,
         }
     }
 }
 
+/// Salted HMAC-SHA256 hash of an OAuth client secret.
+///
+/// Only [`OAuthClientBuilder::secret`] creates one from a plaintext secret,
+/// so an [`OAuthClient`] cannot hold the secret itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretHash(String);
+
 /// Builder for OAuth clients.
 #[derive(Debug)]
 pub struct OAuthClientBuilder {
     client_id: String,
     client_secret: Option<String>,
     redirect_uris: Vec<String>,
+    redirect_uri_patterns: Vec<String>,
     allowed_scopes: HashSet<String>,
     name: Option<String>,
     description: Option<String>,
@@ -195,6 +221,7 @@ impl OAuthClientBuilder {
             client_id: client_id.into(),
             client_secret: None,
             redirect_uris: Vec::new(),
+            redirect_uri_patterns: Vec::new(),
             allowed_scopes: HashSet::new(),
             name: None,
             description: None,
@@ -226,6 +253,29 @@ impl OAuthClientBuilder {
         self
     }
 
+    /// Allows any redirect URI under a path prefix.
+    ///
+    /// The pattern is a redirect URI whose path ends in a single `/*`
+    /// wildcard, such as `https://app.example.com/oauth/*` or
+    /// `com.example.app:/oauth/*`. It matches URIs that extend the prefix
+    /// with one or more path segments, like
+    /// `https://app.example.com/oauth/callback`. The wildcard never matches
+    /// `.` or `..` segments, empty segments, percent-encoded dots or
+    /// slashes, backslashes, queries, or fragments, so a match always stays
+    /// under the prefix.
+    ///
+    /// Wildcards are only allowed at the end of the path. A host wildcard
+    /// like `https://*.example.com/` would let anyone who can get a
+    /// subdomain (or a dangling DNS record) under that domain receive
+    /// authorization codes, so such patterns are rejected by
+    /// [`build`](Self::build). Without patterns, redirect URIs must match
+    /// exactly.
+    #[must_use]
+    pub fn redirect_uri_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.redirect_uri_patterns.push(pattern.into());
+        self
+    }
+
     /// Adds an allowed scope.
     #[must_use]
     pub fn scope(mut self, scope: impl Into<String>) -> Self {
@@ -264,8 +314,11 @@ impl OAuthClientBuilder {
     /// # Errors
     ///
     /// Returns an error if:
-    /// - No redirect URIs are configured
+    /// - No redirect URIs or patterns are configured
+    /// - A redirect URI pattern is malformed or has a wildcard outside the
+    ///   trailing `/*`
     /// - Client ID is empty
+    /// - Randomness for the secret's salt is unavailable
     pub fn build(self) -> Result<OAuthClient, OAuthError> {
         if self.client_id.is_empty() {
             return Err(OAuthError::InvalidRequest(
@@ -273,12 +326,22 @@ impl OAuthClientBuilder {
             ));
         }
 
-        if self.redirect_uris.is_empty() {
+        if self.redirect_uris.is_empty() && self.redirect_uri_patterns.is_empty() {
             return Err(OAuthError::InvalidRequest(
                 "at least one redirect_uri is required".to_string(),
             ));
         }
 
+        if let Some(pattern) = self
+            .redirect_uri_patterns
+            .iter()
+            .find(|pattern| !is_valid_redirect_pattern(pattern))
+        {
+            return Err(OAuthError::InvalidRequest(format!(
+                "invalid redirect_uri pattern '{pattern}': only a trailing '/*' path wildcard is allowed"
+            )));
+        }
+
         let client_type = if self.client_secret.is_some() {
             ClientType::Confidential
         } else {
@@ -287,9 +350,10 @@ impl OAuthClientBuilder {
 
         Ok(OAuthClient {
             client_id: self.client_id,
-            client_secret: self.client_secret,
+            client_secret: self.client_secret.as_deref().map(hash_secret).transpose()?,
             client_type,
             redirect_uris: self.redirect_uris,
+            redirect_uri_patterns: self.redirect_uri_patterns,
             allowed_scopes: self.allowed_scopes,
             name: self.name,
             description: self.description,
@@ -361,7 +425,13 @@ impl AuthorizationCode {
     /// Checks if this code has expired.
     #[must_use]
     pub fn is_expired(&self) -> bool {
-        Instant::now() >= self.expires_at
+        self.is_expired_at(Instant::now())
+    }
+
+    /// Checks if this code has expired as of `now`.
+    #[must_use]
+    pub fn is_expired_at(&self, now: Instant) -> bool {
+        now >= self.expires_at
     }
 
     /// Validates the PKCE code verifier against the stored challenge.
@@ -423,15 +493,25 @@ impl OAuthToken {
     /// Checks if this token has expired.
     #[must_use]
     pub fn is_expired(&self) -> bool {
-        Instant::now() >= self.expires_at
+        self.is_expired_at(Instant::now())
+    }
+
+    /// Checks if this token has expired as of `now`.
+    #[must_use]
+    pub fn is_expired_at(&self, now: Instant) -> bool {
+        now >= self.expires_at
     }
 
     /// Returns the remaining lifetime in seconds.
     #[must_use]
     pub fn expires_in_secs(&self) -> u64 {
-        self.expires_at
-            .saturating_duration_since(Instant::now())
-            .as_secs()
+        self.expires_in_secs_at(Instant::now())
+    }
+
+    /// Returns the remaining lifetime in seconds as of `now`.
+    #[must_use]
+    pub fn expires_in_secs_at(&self, now: Instant) -> u64 {
+        self.expires_at.saturating_duration_since(now).as_secs()
     }
 }
 
@@ -473,6 +553,46 @@ pub struct AuthorizationRequest {
     pub code_challenge: String,
     /// PKCE code challenge method.
     pub code_challenge_method: CodeChallengeMethod,
+    /// OpenID Connect nonce, to be echoed in the ID token.
+    pub nonce: Option<String>,
+}
+
+impl AuthorizationRequest {
+    /// Parses the query string of an authorization endpoint request.
+    ///
+    /// A leading `?` is ignored. `scope` is split on whitespace, and
+    /// `code_challenge_method` defaults to `plain` as in RFC 7636.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OAuthError::InvalidRequest`] if `response_type`,
+    /// `client_id`, `redirect_uri`, or `code_challenge` is missing, if
+    /// `code_challenge_method` is not `plain` or `S256`, or if a parameter is
+    /// repeated or badly encoded.
+    pub fn from_query(query: &str) -> Result<Self, OAuthError> {
+        let mut params = parse_form(query.strip_prefix('?').unwrap_or(query))?;
+
+        let code_challenge_method = match params.remove("code_challenge_method") {
+            None => CodeChallengeMethod::Plain,
+            Some(method) => CodeChallengeMethod::parse(&method).ok_or_else(|| {
+                OAuthError::InvalidRequest(format!("unsupported code_challenge_method '{method}'"))
+            })?,
+        };
+
+        Ok(Self {
+            response_type: required_param(&mut params, "response_type")?,
+            client_id: required_param(&mut params, "client_id")?,
+            redirect_uri: required_param(&mut params, "redirect_uri")?,
+            scopes: params
+                .remove("scope")
+                .map(|scope| split_scopes(&scope))
+                .unwrap_or_default(),
+            state: params.remove("state"),
+            code_challenge: required_param(&mut params, "code_challenge")?,
+            code_challenge_method,
+            nonce: params.remove("nonce"),
+        })
+    }
 }
 
 /// Token request parameters.
@@ -496,6 +616,70 @@ pub struct TokenRequest {
     pub scopes: Option<Vec<String>>,
 }
 
+impl TokenRequest {
+    /// Parses an `application/x-www-form-urlencoded` token endpoint body.
+    ///
+    /// `scope` is split on whitespace. Which of the optional fields are
+    /// needed depends on the grant and is checked by
+    /// [`OAuthServer::token`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OAuthError::InvalidRequest`] if `grant_type` or `client_id`
+    /// is missing, or if a parameter is repeated or badly encoded.
+    pub fn from_form(body: &str) -> Result<Self, OAuthError> {
+        let mut params = parse_form(body)?;
+
+        Ok(Self {
+            grant_type: required_param(&mut params, "grant_type")?,
+            code: params.remove("code"),
+            redirect_uri: params.remove("redirect_uri"),
+            client_id: required_param(&mut params, "client_id")?,
+            client_secret: params.remove("client_secret"),
+            code_verifier: params.remove("code_verifier"),
+            refresh_token: params.remove("refresh_token"),
+            scopes: params.remove("scope").map(|scope| split_scopes(&scope)),
+        })
+    }
+}
+
+/// Decodes `application/x-www-form-urlencoded` pairs.
+///
+/// Repeated parameters are rejected, as RFC 6749 §3.1 requires.
+fn parse_form(input: &str) -> Result<HashMap<String, String>, OAuthError> {
+    let decode = |raw: &str| {
+        crate::router::percent_decode(&raw.replace('+', " ")).ok_or_else(|| {
+            OAuthError::InvalidRequest(format!("malformed percent-encoding in '{raw}'"))
+        })
+    };
+
+    let mut params = HashMap::new();
+    for pair in input.split('&').filter(|pair| !pair.is_empty()) {
+        let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let name = decode(name)?;
+        let value = decode(value)?;
+        if params.contains_key(&name) {
+            return Err(OAuthError::InvalidRequest(format!(
+                "parameter '{name}' must not be repeated"
+            )));
+        }
+        params.insert(name, value);
+    }
+    Ok(params)
+}
+
+/// Removes a required, non-empty parameter.
+fn required_param(params: &mut HashMap<String, String>, name: &str) -> Result<String, OAuthError> {
+    params
+        .remove(name)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| OAuthError::InvalidRequest(format!("{name} is required")))
+}
+
+fn split_scopes(scope: &str) -> Vec<String> {
+    scope.split_whitespace().map(String::from).collect()
+}
+
 // =============================================================================
 // OAuth Errors
 // =============================================================================
@@ -583,6 +767,79 @@ impl From<OAuthError> for McpError {
     }
 }
 
+// =============================================================================
+// Client Store
+// =============================================================================
+
+/// On-disk form of an [`OAuthClient`], without the plaintext secret.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredClient {
+    client_id: String,
+    client_type: StoredClientType,
+    redirect_uris: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    redirect_uri_patterns: Vec<String>,
+    scopes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// Registration time in Unix seconds.
+    registered_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    secret_hash: Option<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum StoredClientType {
+    Confidential,
+    Public,
+}
+
+impl StoredClient {
+    fn from_client(client: &OAuthClient) -> Self {
+        let mut scopes: Vec<String> = client.allowed_scopes.iter().cloned().collect();
+        scopes.sort();
+        Self {
+            client_id: client.client_id.clone(),
+            client_type: match client.client_type {
+                ClientType::Confidential => StoredClientType::Confidential,
+                ClientType::Public => StoredClientType::Public,
+            },
+            redirect_uris: client.redirect_uris.clone(),
+            redirect_uri_patterns: client.redirect_uri_patterns.clone(),
+            scopes,
+            name: client.name.clone(),
+            description: client.description.clone(),
+            registered_at: client
+                .registered_at
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            secret_hash: client.client_secret.as_ref().map(|hash| hash.0.clone()),
+        }
+    }
+
+    fn into_client(self) -> OAuthClient {
+        OAuthClient {
+            client_id: self.client_id,
+            client_secret: self.secret_hash.map(SecretHash),
+            client_type: match self.client_type {
+                StoredClientType::Confidential => ClientType::Confidential,
+                StoredClientType::Public => ClientType::Public,
+            },
+            redirect_uris: self.redirect_uris,
+            redirect_uri_patterns: self.redirect_uri_patterns,
+            allowed_scopes: self.scopes.into_iter().collect(),
+            name: self.name,
+            description: self.description,
+            registered_at: UNIX_EPOCH + Duration::from_secs(self.registered_at),
+        }
+    }
+}
+
 // =============================================================================
 // OAuth Server
 // =============================================================================
@@ -620,16 +877,51 @@ impl OAuthServerState {
 pub struct OAuthServer {
     config: OAuthServerConfig,
     pub(crate) state: RwLock<OAuthServerState>,
+    /// Time source for issuing and expiring codes and tokens.
+    clock: Arc<dyn Clock>,
 }
 
 impl OAuthServer {
     /// Creates a new OAuth server with the given configuration.
+    ///
+    /// If [`client_store_path`](OAuthServerConfig::client_store_path) is set
+    /// and the file exists, the clients stored in it are registered. A store
+    /// that cannot be read is logged and skipped.
     #[must_use]
     pub fn new(config: OAuthServerConfig) -> Self {
-        Self {
+        let server = Self {
             config,
             state: RwLock::new(OAuthServerState::new()),
+            clock: Arc::new(SystemClock),
+        };
+        if let Some(path) = server.config.client_store_path.as_deref() {
+            if path.exists() {
+                if let Err(e) = server.load_clients(path) {
+                    warn!(
+                        target: targets::SERVER,
+                        "Failed to load OAuth clients from {}: {}",
+                        path.display(),
+                        e
+                    );
+                }
+            }
         }
+        server
+    }
+
+    /// Sets the clock used to issue and expire codes and tokens.
+    ///
+    /// Defaults to [`SystemClock`]. Pass a [`TestClock`](crate::TestClock)
+    /// to test expiry without sleeping.
+    #[must_use]
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Returns the clock codes and tokens are checked against.
+    pub(crate) fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
     }
 
     /// Creates a new OAuth server with default configuration.
@@ -655,6 +947,8 @@ impl OAuthServer {
     /// Returns an error if:
     /// - A client with the same ID already exists
     /// - Public clients are not allowed and the client has no secret
+    /// - The client store is configured and could not be written (the
+    ///   client stays registered in memory)
     pub fn register_client(&self, client: OAuthClient) -> Result<(), OAuthError> {
         if client.client_type == ClientType::Public && !self.config.allow_public_clients {
             return Err(OAuthError::InvalidClient(
@@ -675,12 +969,18 @@ impl OAuthServer {
         }
 
         state.clients.insert(client.client_id.clone(), client);
-        Ok(())
+        drop(state);
+        self.persist_clients()
     }
 
     /// Unregisters an OAuth client.
     ///
     /// This also revokes all tokens issued to the client.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the client is not registered, or if the client
+    /// store is configured and could not be written.
     pub fn unregister_client(&self, client_id: &str) -> Result<(), OAuthError> {
         let mut state = self
             .state
@@ -728,7 +1028,83 @@ impl OAuthServer {
             state.authorization_codes.remove(&code);
         }
 
-        Ok(())
+        drop(state);
+        self.persist_clients()
+    }
+
+    /// Writes all registered clients to `path` as JSON.
+    ///
+    /// Each entry records the client ID, type, redirect URIs and patterns,
+    /// scopes, name, description, registration time (Unix seconds), and the
+    /// salted secret hash. Plaintext secrets are never written.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OAuthError::ServerError`] if the file cannot be written.
+    pub fn save_clients(&self, path: impl AsRef<Path>) -> Result<(), OAuthError> {
+        let mut stored: Vec<StoredClient> = self
+            .state
+            .read()
+            .map_err(|_| OAuthError::ServerError("failed to acquire read lock".to_string()))?
+            .clients
+            .values()
+            .map(StoredClient::from_client)
+            .collect();
+        stored.sort_by(|a, b| a.client_id.cmp(&b.client_id));
+
+        let json = serde_json::to_vec_pretty(&stored)
+            .map_err(|e| OAuthError::ServerError(format!("failed to encode clients: {e}")))?;
+        // Write a sibling file first so a crash never leaves a truncated store
+        let path = path.as_ref();
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)
+            .and_then(|()| std::fs::rename(&tmp, path))
+            .map_err(|e| {
+                OAuthError::ServerError(format!(
+                    "failed to write clients to {}: {e}",
+                    path.display()
+                ))
+            })
+    }
+
+    /// Registers the clients stored in `path` by [`save_clients`](Self::save_clients).
+    ///
+    /// Stored clients replace registered clients with the same ID. Returns
+    /// the number of clients loaded.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OAuthError::ServerError`] if the file cannot be read or
+    /// parsed.
+    pub fn load_clients(&self, path: impl AsRef<Path>) -> Result<usize, OAuthError> {
+        let path = path.as_ref();
+        let json = std::fs::read(path).map_err(|e| {
+            OAuthError::ServerError(format!(
+                "failed to read clients from {}: {e}",
+                path.display()
+            ))
+        })?;
+        let stored: Vec<StoredClient> = serde_json::from_slice(&json)
+            .map_err(|e| OAuthError::ServerError(format!("invalid client store: {e}")))?;
+
+        let mut state = self
+            .state
+            .write()
+            .map_err(|_| OAuthError::ServerError("failed to acquire write lock".to_string()))?;
+        let count = stored.len();
+        for client in stored {
+            let client = client.into_client();
+            state.clients.insert(client.client_id.clone(), client);
+        }
+        Ok(count)
+    }
+
+    /// Saves clients to the configured store, if any.
+    fn persist_clients(&self) -> Result<(), OAuthError> {
+        match &self.config.client_store_path {
+            Some(path) => self.save_clients(path),
+            None => Ok(()),
+        }
     }
 
     /// Gets a registered client by ID.
@@ -753,24 +1129,17 @@ impl OAuthServer {
     // Authorization Endpoint
     // -------------------------------------------------------------------------
 
-    /// Validates an authorization request and creates an authorization code.
+    /// Runs the [`authorize`](Self::authorize) validations without issuing a
+    /// code.
     ///
-    /// This is called after the resource owner has authenticated and approved
-    /// the authorization request.
-    ///
-    /// # Arguments
-    ///
-    /// * `request` - The authorization request parameters
-    /// * `subject` - The authenticated user's identifier (optional)
+    /// Checks the response type, that the client exists, the redirect URI,
+    /// the requested scopes, and that a PKCE challenge is present. Nothing is
+    /// stored, so admin tooling can preview whether a request would succeed.
     ///
-    /// # Returns
+    /// # Errors
     ///
-    /// Returns the authorization code and redirect URI on success.
-    pub fn authorize(
-        &self,
-        request: &AuthorizationRequest,
-        subject: Option<String>,
-    ) -> Result<(String, String), OAuthError> {
+    /// Returns the same error [`authorize`](Self::authorize) would.
+    pub fn check_authorization(&self, request: &AuthorizationRequest) -> Result<(), OAuthError> {
         // Validate response_type
         if request.response_type != "code" {
             return Err(OAuthError::UnsupportedResponseType(
@@ -804,9 +1173,32 @@ impl OAuthServer {
             ));
         }
 
+        Ok(())
+    }
+
+    /// Validates an authorization request and creates an authorization code.
+    ///
+    /// This is called after the resource owner has authenticated and approved
+    /// the authorization request.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The authorization request parameters
+    /// * `subject` - The authenticated user's identifier (optional)
+    ///
+    /// # Returns
+    ///
+    /// Returns the authorization code and redirect URI on success.
+    pub fn authorize(
+        &self,
+        request: &AuthorizationRequest,
+        subject: Option<String>,
+    ) -> Result<(String, String), OAuthError> {
+        self.check_authorization(request)?;
+
         // Generate authorization code
-        let code_value = generate_token(self.config.token_entropy_bytes);
-        let now = Instant::now();
+        let code_value = generate_token(self.config.token_entropy_bytes)?;
+        let now = self.clock.now_instant();
         let code = AuthorizationCode {
             code: code_value.clone(),
             client_id: request.client_id.clone(),
@@ -905,7 +1297,7 @@ impl OAuthServer {
         };
 
         // Validate the code
-        if auth_code.is_expired() {
+        if auth_code.is_expired_at(self.clock.now_instant()) {
             return Err(OAuthError::InvalidGrant(
                 "authorization code has expired".to_string(),
             ));
@@ -974,7 +1366,7 @@ impl OAuthServer {
                 .ok_or_else(|| OAuthError::InvalidGrant("refresh token not found".to_string()))?
         };
 
-        if refresh_token.is_expired() {
+        if refresh_token.is_expired_at(self.clock.now_instant()) {
             return Err(OAuthError::InvalidGrant(
                 "refresh token has expired".to_string(),
             ));
@@ -1013,8 +1405,8 @@ impl OAuthServer {
         };
 
         // Issue new access token (keep same refresh token)
-        let now = Instant::now();
-        let access_token_value = generate_token(self.config.token_entropy_bytes);
+        let now = self.clock.now_instant();
+        let access_token_value = generate_token(self.config.token_entropy_bytes)?;
         let access_token = OAuthToken {
             token: access_token_value.clone(),
             token_type: TokenType::Bearer,
@@ -1040,7 +1432,7 @@ impl OAuthServer {
         Ok(TokenResponse {
             access_token: access_token_value,
             token_type: access_token.token_type.as_str().to_string(),
-            expires_in: access_token.expires_in_secs(),
+            expires_in: access_token.expires_in_secs_at(now),
             refresh_token: None, // Don't issue new refresh token
             scope: if scopes.is_empty() {
                 None
@@ -1056,10 +1448,10 @@ impl OAuthServer {
         scopes: &[String],
         subject: Option<&str>,
     ) -> Result<TokenResponse, OAuthError> {
-        let now = Instant::now();
+        let now = self.clock.now_instant();
 
         // Generate access token
-        let access_token_value = generate_token(self.config.token_entropy_bytes);
+        let access_token_value = generate_token(self.config.token_entropy_bytes)?;
         let access_token = OAuthToken {
             token: access_token_value.clone(),
             token_type: TokenType::Bearer,
@@ -1072,7 +1464,7 @@ impl OAuthServer {
         };
 
         // Generate refresh token
-        let refresh_token_value = generate_token(self.config.token_entropy_bytes);
+        let refresh_token_value = generate_token(self.config.token_entropy_bytes)?;
         let refresh_token = OAuthToken {
             token: refresh_token_value.clone(),
             token_type: TokenType::Bearer,
@@ -1101,7 +1493,7 @@ impl OAuthServer {
         Ok(TokenResponse {
             access_token: access_token_value,
             token_type: access_token.token_type.as_str().to_string(),
-            expires_in: access_token.expires_in_secs(),
+            expires_in: access_token.expires_in_secs_at(now),
             refresh_token: Some(refresh_token_value),
             scope: if scopes.is_empty() {
                 None
@@ -1184,7 +1576,7 @@ impl OAuthServer {
 
         let token_info = state.access_tokens.get(token)?;
 
-        if token_info.is_expired() {
+        if token_info.is_expired_at(self.clock.now_instant()) {
             return None;
         }
 
@@ -1215,14 +1607,40 @@ impl OAuthServer {
             return;
         };
 
+        let now = self.clock.now_instant();
+
         // Remove expired authorization codes
-        state.authorization_codes.retain(|_, c| !c.is_expired());
+        state
+            .authorization_codes
+            .retain(|_, c| !c.is_expired_at(now));
 
         // Remove expired access tokens
-        state.access_tokens.retain(|_, t| !t.is_expired());
+        state.access_tokens.retain(|_, t| !t.is_expired_at(now));
 
         // Remove expired refresh tokens
-        state.refresh_tokens.retain(|_, t| !t.is_expired());
+        state.refresh_tokens.retain(|_, t| !t.is_expired_at(now));
+    }
+
+    // -------------------------------------------------------------------------
+    // Protected Resource Metadata
+    // -------------------------------------------------------------------------
+
+    /// Generates the RFC 9728 protected resource metadata for an MCP server at
+    /// `resource_url` that accepts tokens issued by this server.
+    ///
+    /// The issuer is listed as the only authorization server, and
+    /// `scopes_supported` is the union of the registered clients' scopes.
+    #[must_use]
+    pub fn protected_resource_metadata(&self, resource_url: &str) -> serde_json::Value {
+        let mut scopes: Vec<String> = self
+            .list_clients()
+            .into_iter()
+            .flat_map(|client| client.allowed_scopes)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        scopes.sort();
+        protected_resource_document(resource_url, &[self.config.issuer.clone()], &scopes)
     }
 
     /// Returns statistics about the server state.
@@ -1239,6 +1657,39 @@ impl OAuthServer {
     }
 }
 
+/// Well-known path of the protected resource metadata document (RFC 9728).
+pub const PROTECTED_RESOURCE_METADATA_PATH: &str = "/.well-known/oauth-protected-resource";
+
+/// Returns where the protected resource metadata for `resource_url` is served.
+///
+/// Per RFC 9728 the well-known path goes between the authority and any path
+/// component: `https://mcp.example.com/mcp` maps to
+/// `https://mcp.example.com/.well-known/oauth-protected-resource/mcp`.
+#[must_use]
+pub fn protected_resource_metadata_url(resource_url: &str) -> String {
+    let resource = resource_url.trim_end_matches('/');
+    let authority_start = resource.find("://").map_or(0, |i| i + 3);
+    let (origin, path) = match resource[authority_start..].find('/') {
+        Some(i) => resource.split_at(authority_start + i),
+        None => (resource, ""),
+    };
+    format!("{origin}{PROTECTED_RESOURCE_METADATA_PATH}{path}")
+}
+
+/// Builds a protected resource metadata document.
+pub(crate) fn protected_resource_document(
+    resource_url: &str,
+    authorization_servers: &[String],
+    scopes_supported: &[String],
+) -> serde_json::Value {
+    serde_json::json!({
+        "resource": resource_url,
+        "authorization_servers": authorization_servers,
+        "scopes_supported": scopes_supported,
+        "bearer_methods_supported": ["header"],
+    })
+}
+
 /// Statistics about the OAuth server state.
 #[derive(Debug, Clone, Default)]
 pub struct OAuthServerStats {
@@ -1306,30 +1757,12 @@ impl TokenVerifier for OAuthTokenVerifier {
 // Helper Functions
 // =============================================================================
 
-/// Generates a cryptographically secure random token.
-fn generate_token(bytes: usize) -> String {
-    use std::collections::hash_map::RandomState;
-    use std::hash::{BuildHasher, Hasher};
-
-    // Use system randomness via multiple hash iterations
-    let mut result = Vec::with_capacity(bytes * 2);
-    let state = RandomState::new();
-
-    for i in 0..bytes {
-        let mut hasher = state.build_hasher();
-        hasher.write_usize(i);
-        hasher.write_u128(
-            SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_nanos(),
-        );
-        let hash = hasher.finish();
-        result.extend_from_slice(&hash.to_le_bytes()[..2]);
-    }
-
-    // Base64url encode (URL-safe, no padding)
-    base64url_encode(&result[..bytes])
+/// Generates a token from `bytes` bytes of OS randomness, base64url encoded.
+fn generate_token(bytes: usize) -> Result<String, OAuthError> {
+    let mut buf = vec![0u8; bytes];
+    getrandom::fill(&mut buf)
+        .map_err(|e| OAuthError::ServerError(format!("failed to gather randomness: {e}")))?;
+    Ok(base64url_encode(&buf))
 }
 
 /// Base64url encodes bytes (URL-safe, no padding).
@@ -1409,6 +1842,132 @@ fn url_encode(s: &str) -> String {
     result
 }
 
+/// Prefix of hashes produced by [`hash_secret`].
+const SECRET_HASH_SCHEME: &str = "hmac-sha256";
+
+/// Hashes a client secret as `hmac-sha256$<salt>$<mac>`, keyed by a fresh
+/// random salt.
+fn hash_secret(secret: &str) -> Result<SecretHash, OAuthError> {
+    let salt = generate_token(16)?;
+    let mac = hmac_sha256(salt.as_bytes(), secret.as_bytes());
+    Ok(SecretHash(format!(
+        "{SECRET_HASH_SCHEME}${salt}${}",
+        base64url_encode(&mac)
+    )))
+}
+
+/// Checks `secret` against a hash produced by [`hash_secret`], in constant
+/// time.
+fn verify_secret_hash(hash: &SecretHash, secret: &str) -> bool {
+    let mut parts = hash.0.splitn(3, '$');
+    let (Some(SECRET_HASH_SCHEME), Some(salt), Some(expected)) =
+        (parts.next(), parts.next(), parts.next())
+    else {
+        return false;
+    };
+    let mac = hmac_sha256(salt.as_bytes(), secret.as_bytes());
+    constant_time_eq(expected, &base64url_encode(&mac))
+}
+
+/// HMAC-SHA256 (RFC 2104).
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK_SIZE: usize = 64;
+
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&sha256(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Vec::with_capacity(BLOCK_SIZE + message.len());
+    inner.extend(block.iter().map(|b| b ^ 0x36));
+    inner.extend_from_slice(message);
+
+    let mut outer = Vec::with_capacity(BLOCK_SIZE + 32);
+    outer.extend(block.iter().map(|b| b ^ 0x5c));
+    outer.extend_from_slice(&sha256(&inner));
+    sha256(&outer)
+}
+
+/// SHA-256 (FIPS 180-4).
+///
+/// Unlike [`simple_sha256`], the digest is the same in every process, so it
+/// can be persisted.
+#[allow(clippy::many_single_char_names)]
+fn sha256(data: &[u8]) -> [u8; 32] {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4,
+        0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe,
+        0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f,
+        0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
+        0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc,
+        0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+        0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116,
+        0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+        0xc67178f2,
+    ];
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    let bit_len = (data.len() as u64).wrapping_mul(8);
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&bit_len.to_be_bytes());
+
+    for block in message.chunks_exact(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+        for (k, w) in K.iter().zip(w) {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = hh
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(*k)
+                .wrapping_add(w);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (state, value) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    let mut digest = [0u8; 32];
+    for (chunk, word) in digest.chunks_exact_mut(4).zip(h) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
 /// Constant-time string comparison.
 fn constant_time_eq(a: &str, b: &str) -> bool {
     if a.len() != b.len() {
@@ -1452,6 +2011,51 @@ fn localhost_match(a: &str, b: &str) -> bool {
     }
 }
 
+/// Checks that a redirect URI pattern has a scheme and exactly one
+/// wildcard, in a trailing `/*`.
+fn is_valid_redirect_pattern(pattern: &str) -> bool {
+    let Some(prefix) = pattern.strip_suffix('*') else {
+        return false;
+    };
+    let Some((scheme, rest)) = prefix.split_once(':') else {
+        return false;
+    };
+    let scheme_ok = !scheme.is_empty()
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    // With an authority, the wildcard must come after the host's path slash.
+    let host_ok = match rest.strip_prefix("//") {
+        Some(authority_and_path) => authority_and_path
+            .split_once('/')
+            .is_some_and(|(authority, _)| !authority.is_empty()),
+        None => true,
+    };
+    scheme_ok && host_ok && prefix.ends_with('/') && !prefix.contains(['*', '?', '#', '\\'])
+}
+
+/// Checks a redirect URI against a validated `.../*` pattern.
+fn redirect_pattern_match(pattern: &str, uri: &str) -> bool {
+    let Some(prefix) = pattern.strip_suffix('*') else {
+        return false;
+    };
+    let Some(rest) = uri.strip_prefix(prefix) else {
+        return false;
+    };
+    if rest.is_empty() || rest.contains(['?', '#', '\\']) {
+        return false;
+    }
+    let lowered = rest.to_ascii_lowercase();
+    if ["%2e", "%2f", "%5c"]
+        .iter()
+        .any(|encoded| lowered.contains(encoded))
+    {
+        return false;
+    }
+    rest.split('/')
+        .all(|segment| !matches!(segment, "" | "." | ".."))
+}
+
 /// Normalizes localhost variants.
 fn normalize_localhost(host: &str) -> &'static str {
     match host {
@@ -1499,6 +2103,36 @@ mod tests {
         assert!(!client.authenticate(None));
     }
 
+    #[test]
+    fn test_client_secret_is_stored_hashed() {
+        let build = || {
+            OAuthClient::builder("test-client")
+                .secret("super-secret")
+                .redirect_uri("http://localhost:3000/callback")
+                .build()
+                .unwrap()
+        };
+        let client = build();
+        let stored = client.client_secret.as_ref().unwrap().0.as_str();
+        assert_ne!(stored, "super-secret");
+        assert!(!stored.contains("super-secret"));
+        assert!(stored.starts_with("hmac-sha256$"));
+        // Each client gets its own salt
+        assert_ne!(build().client_secret, client.client_secret);
+        // Presenting the stored hash itself does not authenticate
+        assert!(!client.authenticate(Some(stored)));
+    }
+
+    #[test]
+    fn test_hmac_sha256_rfc4231() {
+        let mac = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        let hex: String = mac.iter().map(|b| format!("{b:02x}")).collect();
+        assert_eq!(
+            hex,
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
     #[test]
     fn test_redirect_uri_validation() {
         let client = OAuthClient::builder("test-client")
@@ -1520,6 +2154,70 @@ mod tests {
         assert!(!client.validate_redirect_uri("https://evil.com/callback"));
     }
 
+    #[test]
+    fn test_redirect_uri_pattern_matches_paths_under_prefix() {
+        let client = OAuthClient::builder("test-client")
+            .redirect_uri_pattern("https://app.example.com/oauth/*")
+            .build()
+            .unwrap();
+
+        assert!(client.validate_redirect_uri("https://app.example.com/oauth/callback"));
+        assert!(client.validate_redirect_uri("https://app.example.com/oauth/v2/callback"));
+
+        // The prefix itself and sibling paths are not covered.
+        assert!(!client.validate_redirect_uri("https://app.example.com/oauth/"));
+        assert!(!client.validate_redirect_uri("https://app.example.com/oauthx/callback"));
+        assert!(!client.validate_redirect_uri("https://app.example.com/other"));
+    }
+
+    #[test]
+    fn test_redirect_uri_pattern_rejects_other_hosts() {
+        let client = OAuthClient::builder("test-client")
+            .redirect_uri_pattern("https://app.example.com/oauth/*")
+            .build()
+            .unwrap();
+
+        assert!(!client.validate_redirect_uri("https://evil.com/oauth/callback"));
+        assert!(!client.validate_redirect_uri("https://app.example.com.evil.com/oauth/callback"));
+        assert!(!client.validate_redirect_uri("http://app.example.com/oauth/callback"));
+    }
+
+    #[test]
+    fn test_redirect_uri_pattern_rejects_traversal() {
+        let client = OAuthClient::builder("test-client")
+            .redirect_uri_pattern("https://app.example.com/oauth/*")
+            .build()
+            .unwrap();
+
+        assert!(!client.validate_redirect_uri("https://app.example.com/oauth/../admin"));
+        assert!(!client.validate_redirect_uri("https://app.example.com/oauth/./callback"));
+        assert!(!client.validate_redirect_uri("https://app.example.com/oauth/%2e%2e/admin"));
+        assert!(!client.validate_redirect_uri("https://app.example.com/oauth//evil.com"));
+        assert!(!client.validate_redirect_uri("https://app.example.com/oauth/cb?next=/admin"));
+    }
+
+    #[test]
+    fn test_redirect_uri_pattern_forbids_host_wildcards() {
+        for pattern in [
+            "https://*.example.com/oauth/*",
+            "https://app.example.com/*/callback",
+            "https://app.example.com/oauth*",
+            "https://*/oauth",
+        ] {
+            let result = OAuthClient::builder("test-client")
+                .redirect_uri_pattern(pattern)
+                .build();
+            assert!(result.is_err(), "pattern '{pattern}' should be rejected");
+        }
+
+        // Custom schemes for native apps are fine.
+        let client = OAuthClient::builder("test-client")
+            .redirect_uri_pattern("com.example.app:/oauth/*")
+            .build()
+            .unwrap();
+        assert!(client.validate_redirect_uri("com.example.app:/oauth/callback"));
+    }
+
     #[test]
     fn test_scope_validation() {
         let client = OAuthClient::builder("test-client")
@@ -1577,6 +2275,7 @@ mod tests {
             state: Some("xyz".to_string()),
             code_challenge: "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM".to_string(),
             code_challenge_method: CodeChallengeMethod::S256,
+            nonce: None,
         };
 
         let (code, redirect) = server
@@ -1607,19 +2306,95 @@ mod tests {
             state: None,
             code_challenge: String::new(), // Missing!
             code_challenge_method: CodeChallengeMethod::S256,
+            nonce: None,
         };
 
         let result = server.authorize(&request, None);
         assert!(matches!(result, Err(OAuthError::InvalidRequest(_))));
     }
 
+    fn preview_request(
+        redirect_uri: &str,
+        scopes: &[&str],
+        challenge: &str,
+    ) -> AuthorizationRequest {
+        AuthorizationRequest {
+            response_type: "code".to_string(),
+            client_id: "test-client".to_string(),
+            redirect_uri: redirect_uri.to_string(),
+            scopes: scopes.iter().map(|s| (*s).to_string()).collect(),
+            state: None,
+            code_challenge: challenge.to_string(),
+            code_challenge_method: CodeChallengeMethod::S256,
+            nonce: None,
+        }
+    }
+
+    fn preview_server() -> OAuthServer {
+        let server = OAuthServer::with_defaults();
+        let client = OAuthClient::builder("test-client")
+            .redirect_uri("http://localhost:3000/callback")
+            .scope("read")
+            .build()
+            .unwrap();
+        server.register_client(client).unwrap();
+        server
+    }
+
+    #[test]
+    fn test_check_authorization_valid_issues_no_code() {
+        let server = preview_server();
+        let request = preview_request("http://localhost:3000/callback", &["read"], "challenge");
+
+        assert!(server.check_authorization(&request).is_ok());
+        assert_eq!(server.stats().authorization_codes, 0);
+    }
+
+    #[test]
+    fn test_check_authorization_bad_redirect() {
+        let server = preview_server();
+        let request = preview_request("http://evil.example/callback", &["read"], "challenge");
+
+        let result = server.check_authorization(&request);
+        assert!(matches!(result, Err(OAuthError::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_check_authorization_disallowed_scope() {
+        let server = preview_server();
+        let request = preview_request("http://localhost:3000/callback", &["admin"], "challenge");
+
+        let result = server.check_authorization(&request);
+        assert!(matches!(result, Err(OAuthError::InvalidScope(_))));
+    }
+
+    #[test]
+    fn test_check_authorization_missing_pkce() {
+        let server = preview_server();
+        let request = preview_request("http://localhost:3000/callback", &["read"], "");
+
+        let result = server.check_authorization(&request);
+        assert!(matches!(result, Err(OAuthError::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_check_authorization_unknown_client() {
+        let server = preview_server();
+        let mut request = preview_request("http://localhost:3000/callback", &["read"], "challenge");
+        request.client_id = "nonexistent".to_string();
+
+        let result = server.check_authorization(&request);
+        assert!(matches!(result, Err(OAuthError::InvalidClient(_))));
+    }
+
     #[test]
     fn test_token_generation() {
-        let token1 = generate_token(32);
-        let token2 = generate_token(32);
+        let token1 = generate_token(32).expect("token");
+        let token2 = generate_token(32).expect("token");
 
-        // Tokens should be unique
+        // Tokens should be unique and carry all 32 random bytes
         assert_ne!(token1, token2);
+        assert_eq!(token1.len(), 43);
         // Tokens should be URL-safe
         assert!(
             token1
@@ -1701,6 +2476,147 @@ mod tests {
         assert_eq!(CodeChallengeMethod::parse("unknown"), None);
     }
 
+    #[test]
+    fn test_authorization_request_from_query() {
+        let request = AuthorizationRequest::from_query(
+            "?response_type=code&client_id=test-client\
+             &redirect_uri=https%3A%2F%2Fapp.example.com%2Foauth%2Fcallback\
+             &scope=openid+profile%20read&state=xyz%2F1\
+             &code_challenge=E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM\
+             &code_challenge_method=S256&nonce=n-0S6_WzA2Mj",
+        )
+        .unwrap();
+
+        assert_eq!(request.response_type, "code");
+        assert_eq!(request.client_id, "test-client");
+        assert_eq!(
+            request.redirect_uri,
+            "https://app.example.com/oauth/callback"
+        );
+        assert_eq!(request.scopes, vec!["openid", "profile", "read"]);
+        assert_eq!(request.state.as_deref(), Some("xyz/1"));
+        assert_eq!(
+            request.code_challenge,
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+        assert_eq!(request.code_challenge_method, CodeChallengeMethod::S256);
+        assert_eq!(request.nonce.as_deref(), Some("n-0S6_WzA2Mj"));
+    }
+
+    #[test]
+    fn test_authorization_request_from_query_errors() {
+        let minimal = "response_type=code&client_id=c&redirect_uri=http%3A%2F%2Flocalhost%2Fcb&code_challenge=abc";
+        let request = AuthorizationRequest::from_query(minimal).unwrap();
+        assert_eq!(request.code_challenge_method, CodeChallengeMethod::Plain);
+        assert!(request.scopes.is_empty());
+        assert!(request.state.is_none());
+
+        match AuthorizationRequest::from_query("response_type=code&client_id=c") {
+            Err(OAuthError::InvalidRequest(message)) => {
+                assert_eq!(message, "redirect_uri is required");
+            }
+            other => panic!("expected invalid_request, got {other:?}"),
+        }
+
+        let bad_method = format!("{minimal}&code_challenge_method=S512");
+        assert!(matches!(
+            AuthorizationRequest::from_query(&bad_method),
+            Err(OAuthError::InvalidRequest(_))
+        ));
+
+        let repeated = format!("{minimal}&client_id=other");
+        assert!(matches!(
+            AuthorizationRequest::from_query(&repeated),
+            Err(OAuthError::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_token_request_from_form() {
+        let request = TokenRequest::from_form(
+            "grant_type=authorization_code&code=SplxlOBeZQQYbYS6WxSbIA\
+             &redirect_uri=https%3A%2F%2Fapp.example.com%2Fcb&client_id=test-client\
+             &code_verifier=dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk",
+        )
+        .unwrap();
+        assert_eq!(request.grant_type, "authorization_code");
+        assert_eq!(request.code.as_deref(), Some("SplxlOBeZQQYbYS6WxSbIA"));
+        assert_eq!(
+            request.redirect_uri.as_deref(),
+            Some("https://app.example.com/cb")
+        );
+        assert_eq!(request.client_id, "test-client");
+        assert_eq!(
+            request.code_verifier.as_deref(),
+            Some("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk")
+        );
+        assert!(request.client_secret.is_none());
+        assert!(request.scopes.is_none());
+
+        let refresh = TokenRequest::from_form(
+            "grant_type=refresh_token&refresh_token=tGzv3JOkF0XG5Qx2TlKWIA\
+             &client_id=test-client&client_secret=s%2Bcret&scope=read",
+        )
+        .unwrap();
+        assert_eq!(refresh.client_secret.as_deref(), Some("s+cret"));
+        assert_eq!(refresh.scopes, Some(vec!["read".to_string()]));
+
+        assert!(matches!(
+            TokenRequest::from_form("client_id=test-client"),
+            Err(OAuthError::InvalidRequest(message)) if message == "grant_type is required"
+        ));
+    }
+
+    #[test]
+    fn test_protected_resource_metadata() {
+        let server = OAuthServer::new(OAuthServerConfig {
+            issuer: "https://auth.example.com".to_string(),
+            ..OAuthServerConfig::default()
+        });
+        for (client_id, scopes) in [
+            ("reader", ["read", "openid"]),
+            ("writer", ["write", "read"]),
+        ] {
+            let client = OAuthClient::builder(client_id)
+                .redirect_uri("http://localhost:3000/callback")
+                .scopes(scopes)
+                .build()
+                .unwrap();
+            server.register_client(client).unwrap();
+        }
+
+        let doc = server.protected_resource_metadata("https://mcp.example.com/mcp");
+        assert_eq!(doc["resource"], "https://mcp.example.com/mcp");
+        assert_eq!(
+            doc["authorization_servers"],
+            serde_json::json!(["https://auth.example.com"])
+        );
+        assert_eq!(
+            doc["scopes_supported"],
+            serde_json::json!(["openid", "read", "write"])
+        );
+        assert_eq!(
+            doc["bearer_methods_supported"],
+            serde_json::json!(["header"])
+        );
+    }
+
+    #[test]
+    fn test_protected_resource_metadata_url() {
+        assert_eq!(
+            protected_resource_metadata_url("https://mcp.example.com"),
+            "https://mcp.example.com/.well-known/oauth-protected-resource"
+        );
+        assert_eq!(
+            protected_resource_metadata_url("https://mcp.example.com/"),
+            "https://mcp.example.com/.well-known/oauth-protected-resource"
+        );
+        assert_eq!(
+            protected_resource_metadata_url("https://mcp.example.com:8443/tenant/mcp"),
+            "https://mcp.example.com:8443/.well-known/oauth-protected-resource/tenant/mcp"
+        );
+    }
+
     #[test]
     fn test_oauth_error_display() {
         let err = OAuthError::InvalidRequest("missing parameter".to_string());
@@ -1787,6 +2703,79 @@ mod tests {
         assert!(server.unregister_client("test-client").is_err());
     }
 
+    #[test]
+    fn test_sha256_known_vectors() {
+        let hex =
+            |digest: [u8; 32]| -> String { digest.iter().map(|b| format!("{b:02x}")).collect() };
+        assert_eq!(
+            hex(sha256(b"")),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            hex(sha256(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    fn confidential_client() -> OAuthClient {
+        OAuthClient::builder("stored-client")
+            .secret("s3cret-value")
+            .redirect_uri("http://localhost:3000/callback")
+            .scopes(["read", "write"])
+            .name("Stored")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_saved_clients_reload_and_authenticate_against_hash() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("clients.json");
+        let server = OAuthServer::with_defaults();
+        server.register_client(confidential_client()).unwrap();
+        server.save_clients(&path).unwrap();
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(!saved.contains("s3cret-value"));
+        assert!(saved.contains("sha256$"));
+
+        let reloaded = OAuthServer::with_defaults();
+        assert_eq!(reloaded.load_clients(&path).unwrap(), 1);
+
+        let client = reloaded.get_client("stored-client").unwrap();
+        assert!(client.client_secret.is_some());
+        assert_eq!(client.client_type, ClientType::Confidential);
+        assert_eq!(client.name.as_deref(), Some("Stored"));
+        assert!(client.validate_scopes(&["read".to_string(), "write".to_string()]));
+        assert!(client.authenticate(Some("s3cret-value")));
+        assert!(!client.authenticate(Some("wrong")));
+        assert!(!client.authenticate(None));
+    }
+
+    #[test]
+    fn test_client_store_path_persists_registration_changes() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("clients.json");
+        let config = || OAuthServerConfig {
+            client_store_path: Some(path.clone()),
+            ..OAuthServerConfig::default()
+        };
+
+        let server = OAuthServer::new(config());
+        server.register_client(confidential_client()).unwrap();
+        let restarted = OAuthServer::new(config());
+        assert!(
+            restarted
+                .get_client("stored-client")
+                .unwrap()
+                .authenticate(Some("s3cret-value"))
+        );
+
+        restarted.unregister_client("stored-client").unwrap();
+        let restarted = OAuthServer::new(config());
+        assert!(restarted.get_client("stored-client").is_none());
+    }
+
     #[test]
     fn test_token_verifier() {
         let server = Arc::new(OAuthServer::with_defaults());
@@ -1853,4 +2842,88 @@ mod tests {
         let result = verifier.verify(&mcp_ctx, auth_request, &wrong_scheme);
         assert!(result.is_err());
     }
+
+    /// Code verifier used with the `plain` challenge method in expiry tests.
+    const TEST_VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+
+    /// Registers a client and runs the authorization step of the PKCE flow.
+    fn authorize_with_pkce(server: &OAuthServer) -> String {
+        let client = OAuthClient::builder("test-client")
+            .redirect_uri("http://localhost:3000/callback")
+            .scope("read")
+            .build()
+            .unwrap();
+        server.register_client(client).unwrap();
+
+        let request = AuthorizationRequest {
+            response_type: "code".to_string(),
+            client_id: "test-client".to_string(),
+            redirect_uri: "http://localhost:3000/callback".to_string(),
+            scopes: vec!["read".to_string()],
+            state: None,
+            code_challenge: TEST_VERIFIER.to_string(),
+            code_challenge_method: CodeChallengeMethod::Plain,
+            nonce: None,
+        };
+        let (code, _) = server
+            .authorize(&request, Some("user123".to_string()))
+            .unwrap();
+        code
+    }
+
+    fn code_exchange(code: String) -> TokenRequest {
+        TokenRequest {
+            grant_type: "authorization_code".to_string(),
+            code: Some(code),
+            redirect_uri: Some("http://localhost:3000/callback".to_string()),
+            client_id: "test-client".to_string(),
+            client_secret: None,
+            code_verifier: Some(TEST_VERIFIER.to_string()),
+            refresh_token: None,
+            scopes: None,
+        }
+    }
+
+    #[test]
+    fn test_access_token_expires_with_clock() {
+        let clock = crate::TestClock::new();
+        let server = OAuthServer::with_defaults().with_clock(clock.clone());
+        let lifetime = server.config().access_token_lifetime;
+
+        let code = authorize_with_pkce(&server);
+        let response = server.token(&code_exchange(code)).unwrap();
+        assert_eq!(response.expires_in, lifetime.as_secs());
+
+        clock.advance(lifetime - Duration::from_secs(1));
+        assert!(
+            server
+                .validate_access_token(&response.access_token)
+                .is_some()
+        );
+
+        clock.advance(Duration::from_secs(1));
+        assert!(
+            server
+                .validate_access_token(&response.access_token)
+                .is_none()
+        );
+
+        assert_eq!(server.stats().access_tokens, 1);
+        server.cleanup_expired();
+        assert_eq!(server.stats().access_tokens, 0);
+    }
+
+    #[test]
+    fn test_authorization_code_expires_with_clock() {
+        let clock = crate::TestClock::new();
+        let server = OAuthServer::with_defaults().with_clock(clock.clone());
+
+        let code = authorize_with_pkce(&server);
+        clock.advance(server.config().authorization_code_lifetime);
+
+        match server.token(&code_exchange(code)) {
+            Err(OAuthError::InvalidGrant(message)) => assert!(message.contains("expired")),
+            other => panic!("expected an expired code, got {other:?}"),
+        }
+    }
 }
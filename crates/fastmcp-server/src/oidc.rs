@@ -620,7 +620,9 @@ impl From<OAuthError> for OidcError {
 
 /// OpenID Connect Provider.
 ///
-/// This extends the OAuth server with OIDC identity features.
+/// This extends the OAuth server with OIDC identity features. ID token
+/// timestamps and expiry follow the OAuth server's
+/// [clock](crate::oauth::OAuthServer::with_clock).
 pub struct OidcProvider {
     /// Underlying OAuth server.
     oauth: Arc<OAuthServer>,
@@ -735,10 +737,7 @@ impl OidcProvider {
         let user_claims = self.get_user_claims(subject, &access_token.scopes)?;
 
         // Build ID token claims
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs() as i64;
+        let now = self.now_secs();
 
         let claims = IdTokenClaims {
             iss: self.config.issuer.clone(),
@@ -904,15 +903,22 @@ impl OidcProvider {
 
     /// Removes expired ID tokens from cache.
     pub fn cleanup_expired(&self) {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs() as i64;
+        let now = self.now_secs();
 
         if let Ok(mut guard) = self.id_tokens.write() {
             guard.retain(|_, token| token.claims.exp > now);
         }
     }
+
+    /// Returns the OAuth server's current time in Unix seconds.
+    fn now_secs(&self) -> i64 {
+        self.oauth
+            .clock()
+            .now_system()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64
+    }
 }
 
 // =============================================================================
@@ -1282,4 +1288,40 @@ mod tests {
             Some(&serde_json::json!(["admin", "user"]))
         );
     }
+
+    #[test]
+    fn test_id_token_expiry_follows_oauth_clock() {
+        let clock = crate::TestClock::new();
+        let oauth = Arc::new(OAuthServer::with_defaults().with_clock(clock.clone()));
+        let provider = OidcProvider::with_defaults(oauth);
+        let claims_provider = InMemoryClaimsProvider::new();
+        claims_provider.set_claims(UserClaims::new("user123"));
+        provider.set_claims_provider(claims_provider);
+        provider.set_hmac_key(b"test-secret-key");
+
+        let now = Instant::now();
+        let access_token = crate::oauth::OAuthToken {
+            token: "test-access-token".to_string(),
+            token_type: crate::oauth::TokenType::Bearer,
+            client_id: "test-client".to_string(),
+            scopes: vec!["openid".to_string()],
+            issued_at: now,
+            expires_at: now + Duration::from_secs(3600),
+            subject: Some("user123".to_string()),
+            is_refresh_token: false,
+        };
+        let id_token = provider.issue_id_token(&access_token, None).unwrap();
+        let lifetime = provider.config().id_token_lifetime;
+        assert_eq!(
+            id_token.claims.exp - id_token.claims.iat,
+            lifetime.as_secs() as i64
+        );
+
+        provider.cleanup_expired();
+        assert!(provider.get_id_token("test-access-token").is_some());
+
+        clock.advance(lifetime);
+        provider.cleanup_expired();
+        assert!(provider.get_id_token("test-access-token").is_none());
+    }
 }
@@ -20,17 +20,38 @@
 //!     .with_recursive(true)
 //!     .with_max_size(10 * 1024 * 1024); // 10MB limit
 //! ```
+//!
+//! Symlinks are not followed unless enabled with
+//! [`FilesystemProvider::with_follow_symlinks`], and even then must resolve
+//! inside the root unless [`FilesystemProvider::with_allow_escape`] is set.
+//!
+//! Independently of glob patterns, files with sensitive extensions (`.env`,
+//! `.pem`, `.key`) are never listed or served; see
+//! [`FilesystemProvider::with_deny_extensions`].
+//!
+//! MIME types come from the file extension. Files without a known extension
+//! are served as `application/octet-stream` unless
+//! [`FilesystemProvider::with_content_sniffing`] is enabled.
 
-use std::path::{Path, PathBuf};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
 
-use fastmcp_core::{McpContext, McpError, McpOutcome, McpResult, Outcome};
-use fastmcp_protocol::{Resource, ResourceContent, ResourceTemplate};
+use fastmcp_core::{McpContext, McpError, McpErrorCode, McpOutcome, McpResult, Outcome};
+use fastmcp_protocol::{
+    Resource, ResourceContent, ResourceRange, ResourceTemplate, TemplateParameter,
+};
 
 use crate::handler::{BoxFuture, ResourceHandler, UriParams};
 
 /// Default maximum file size (10 MB).
 const DEFAULT_MAX_SIZE: usize = 10 * 1024 * 1024;
 
+/// Extensions denied by default because they commonly hold secrets.
+const DEFAULT_DENIED_EXTENSIONS: &[&str] = &["env", "pem", "key"];
+
+/// Number of leading bytes inspected by content sniffing.
+const SNIFF_LEN: u64 = 512;
+
 /// Errors that can occur when using the filesystem provider.
 #[derive(Debug, Clone)]
 pub enum FilesystemProviderError {
@@ -46,6 +67,10 @@ pub enum FilesystemProviderError {
     Io { message: String },
     /// File not found.
     NotFound { path: String },
+    /// The requested range splits a UTF-8 character in a text file.
+    InvalidRange { path: String },
+    /// The file's extension is denied or not on the allowlist.
+    ExtensionDenied { path: String },
 }
 
 impl std::fmt::Display for FilesystemProviderError {
@@ -65,6 +90,15 @@ impl std::fmt::Display for FilesystemProviderError {
             }
             Self::Io { message } => write!(f, "IO error: {message}"),
             Self::NotFound { path } => write!(f, "File not found: {path}"),
+            Self::InvalidRange { path } => {
+                write!(
+                    f,
+                    "Range does not fall on UTF-8 character boundaries: {path}"
+                )
+            }
+            Self::ExtensionDenied { path } => {
+                write!(f, "File extension not allowed: {path}")
+            }
         }
     }
 }
@@ -86,6 +120,12 @@ impl From<FilesystemProviderError> for McpError {
             }
             FilesystemProviderError::Io { .. } => McpError::internal_error(err.to_string()),
             FilesystemProviderError::NotFound { path } => McpError::resource_not_found(&path),
+            FilesystemProviderError::InvalidRange { .. } => {
+                McpError::invalid_params(err.to_string())
+            }
+            FilesystemProviderError::ExtensionDenied { .. } => {
+                McpError::new(McpErrorCode::ResourceForbidden, err.to_string())
+            }
         }
     }
 }
@@ -98,9 +138,11 @@ impl From<FilesystemProviderError> for McpError {
 /// # Security
 ///
 /// - Path traversal attempts (e.g., `../../../etc/passwd`) are blocked
-/// - Symlinks can be optionally followed or blocked
+/// - Symlinks are blocked by default; when followed, they must resolve inside
+///   the root unless escaping is explicitly allowed
 /// - Maximum file size limits prevent memory exhaustion
 /// - Hidden files (starting with `.`) can be excluded
+/// - Sensitive extensions (`.env`, `.pem`, `.key`) are denied by default
 ///
 /// # Example
 ///
@@ -122,12 +164,20 @@ pub struct FilesystemProvider {
     include_patterns: Vec<String>,
     /// Glob patterns to exclude.
     exclude_patterns: Vec<String>,
+    /// Extensions to allow, lowercase without the dot (empty = all).
+    allow_extensions: Vec<String>,
+    /// Extensions to deny, lowercase without the dot.
+    deny_extensions: Vec<String>,
     /// Whether to traverse subdirectories.
     recursive: bool,
     /// Maximum file size in bytes.
     max_file_size: usize,
     /// Whether to follow symlinks.
     follow_symlinks: bool,
+    /// Whether followed symlinks may resolve outside the root.
+    allow_escape: bool,
+    /// Whether to inspect file content when the extension is unknown.
+    content_sniffing: bool,
     /// Description for the resource template.
     description: Option<String>,
 }
@@ -151,9 +201,13 @@ impl FilesystemProvider {
             prefix: None,
             include_patterns: Vec::new(),
             exclude_patterns: vec![".*".to_string()], // Exclude hidden files by default
+            allow_extensions: Vec::new(),
+            deny_extensions: normalize_extensions(DEFAULT_DENIED_EXTENSIONS),
             recursive: false,
             max_file_size: DEFAULT_MAX_SIZE,
             follow_symlinks: false,
+            allow_escape: false,
+            content_sniffing: false,
             description: None,
         }
     }
@@ -209,6 +263,46 @@ impl FilesystemProvider {
         self
     }
 
+    /// Restricts exposed files to the given extensions.
+    ///
+    /// Applied after glob matching, both when listing and on every read, so a
+    /// broad pattern cannot expose other file types. Extensions may be given
+    /// as `"md"`, `".md"` or `"*.md"` and are compared case-insensitively.
+    /// Files without an extension are rejected once an allowlist is set.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let provider = FilesystemProvider::new("/data")
+    ///     .with_allow_extensions(&["md", "txt"]);
+    /// ```
+    #[must_use]
+    pub fn with_allow_extensions(mut self, extensions: &[&str]) -> Self {
+        self.allow_extensions = normalize_extensions(extensions);
+        self
+    }
+
+    /// Sets extensions that are never listed or served.
+    ///
+    /// Denied files requested directly fail with `ResourceForbidden`, even
+    /// if they match an include pattern or the allowlist. This replaces the
+    /// default denylist (`env`, `pem`, `key`); include those again to keep
+    /// them denied. Every dotted segment of a file name is checked, and a
+    /// dotfile's name counts when it starts with the extension, so `"env"`
+    /// covers `.env`, `prod.env`, `.env.local`, `.envrc` and `secrets.env.bak`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let provider = FilesystemProvider::new("/data")
+    ///     .with_deny_extensions(&["env", "pem", "key", "p12"]);
+    /// ```
+    #[must_use]
+    pub fn with_deny_extensions(mut self, extensions: &[&str]) -> Self {
+        self.deny_extensions = normalize_extensions(extensions);
+        self
+    }
+
     /// Enables or disables recursive directory traversal.
     ///
     /// When enabled, files in subdirectories are also exposed.
@@ -244,8 +338,10 @@ impl FilesystemProvider {
 
     /// Enables or disables following symlinks.
     ///
-    /// When disabled (default), symlinks are not followed.
-    /// When enabled, symlinks are followed but must still point within the root directory.
+    /// When disabled (default), symlinks are left out of listings and reads
+    /// through them fail with [`FilesystemProviderError::SymlinkDenied`].
+    /// When enabled, symlinks are followed but their canonicalized target must
+    /// still be within the root directory (see [`with_allow_escape`](Self::with_allow_escape)).
     ///
     /// # Example
     ///
@@ -259,6 +355,46 @@ impl FilesystemProvider {
         self
     }
 
+    /// Lets followed symlinks resolve outside the root directory.
+    ///
+    /// Off by default, and only meaningful together with
+    /// [`with_follow_symlinks(true)`](Self::with_follow_symlinks). Requests
+    /// containing `..` still may not leave the root.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // `/data/shared` is a symlink to `/mnt/shared`
+    /// let provider = FilesystemProvider::new("/data")
+    ///     .with_follow_symlinks(true)
+    ///     .with_allow_escape(true);
+    /// ```
+    #[must_use]
+    pub fn with_allow_escape(mut self, enabled: bool) -> Self {
+        self.allow_escape = enabled;
+        self
+    }
+
+    /// Detects the MIME type of files with an unknown or missing extension
+    /// from their first bytes.
+    ///
+    /// Recognizes PNG, JPEG, PDF, and gzip signatures, and serves other
+    /// files as `text/plain` if the prefix is NUL-free UTF-8. At most 512
+    /// bytes are read. Off by default, in which case such files are served
+    /// as `application/octet-stream`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let provider = FilesystemProvider::new("/data/downloads")
+    ///     .with_content_sniffing(true);
+    /// ```
+    #[must_use]
+    pub fn with_content_sniffing(mut self, enabled: bool) -> Self {
+        self.content_sniffing = enabled;
+        self
+    }
+
     /// Sets the description for the resource template.
     ///
     /// # Example
@@ -297,7 +433,9 @@ impl FilesystemProvider {
     /// Returns an error if:
     /// - The path is absolute
     /// - The path escapes the root directory
-    /// - The path is a symlink and symlinks are disabled
+    /// - The path goes through a symlink and symlinks are disabled
+    /// - The path goes through a symlink that resolves outside the root and
+    ///   escaping is not allowed
     fn validate_path(&self, requested: &str) -> Result<PathBuf, FilesystemProviderError> {
         let requested_path = Path::new(requested);
 
@@ -333,58 +471,74 @@ impl FilesystemProvider {
                 message: format!("Cannot canonicalize root: {e}"),
             })?;
 
-        // Verify still under root
-        if !canonical.starts_with(&canonical_root) {
+        let inside_root = canonical.starts_with(&canonical_root);
+
+        // `..` may never be used to leave the root, symlinks or not
+        let has_parent_dir = requested_path
+            .components()
+            .any(|c| matches!(c, Component::ParentDir));
+        if !inside_root && has_parent_dir {
             return Err(FilesystemProviderError::PathTraversal {
                 requested: requested.to_string(),
             });
         }
 
-        // Check symlink
-        if full_path.is_symlink() {
-            self.check_symlink(&full_path, &canonical_root)?;
+        if self.has_symlink_component(requested_path) {
+            if !self.follow_symlinks {
+                return Err(FilesystemProviderError::SymlinkDenied {
+                    path: requested.to_string(),
+                });
+            }
+            if !inside_root && !self.allow_escape {
+                return Err(FilesystemProviderError::SymlinkEscapesRoot {
+                    path: requested.to_string(),
+                });
+            }
+        } else if !inside_root {
+            return Err(FilesystemProviderError::PathTraversal {
+                requested: requested.to_string(),
+            });
         }
 
         Ok(canonical)
     }
 
-    /// Checks if a symlink is allowed.
-    fn check_symlink(
-        &self,
-        path: &Path,
-        canonical_root: &Path,
-    ) -> Result<(), FilesystemProviderError> {
-        if !self.follow_symlinks {
-            return Err(FilesystemProviderError::SymlinkDenied {
-                path: path.display().to_string(),
-            });
+    /// Returns whether any component of `relative` below the root is a symlink.
+    fn has_symlink_component(&self, relative: &Path) -> bool {
+        let mut current = self.root.clone();
+        for component in relative.components() {
+            current.push(component);
+            if current.is_symlink() {
+                return true;
+            }
         }
+        false
+    }
 
-        // Verify symlink target is still under root
-        let target = std::fs::read_link(path).map_err(|e| FilesystemProviderError::Io {
-            message: e.to_string(),
-        })?;
-
-        let resolved = if target.is_absolute() {
-            target
-        } else {
-            path.parent().unwrap_or(Path::new("")).join(&target)
+    /// Decides whether a symlink met while listing may be followed.
+    ///
+    /// `current` is the directory being walked; symlinked directories that
+    /// resolve to it or one of its ancestors are skipped to avoid cycles.
+    fn follow_listed_symlink(&self, path: &Path, current: &Path, root: &Path) -> bool {
+        if !self.follow_symlinks {
+            return false;
+        }
+        let Ok(target) = path.canonicalize() else {
+            // Dangling symlink
+            return false;
         };
-
-        let canonical_target =
-            resolved
+        if !target.starts_with(root) && !self.allow_escape {
+            return false;
+        }
+        if target.is_dir() {
+            let current = current
                 .canonicalize()
-                .map_err(|e| FilesystemProviderError::Io {
-                    message: e.to_string(),
-                })?;
-
-        if !canonical_target.starts_with(canonical_root) {
-            return Err(FilesystemProviderError::SymlinkEscapesRoot {
-                path: path.display().to_string(),
-            });
+                .unwrap_or_else(|_| current.to_path_buf());
+            if current.starts_with(&target) {
+                return false;
+            }
         }
-
-        Ok(())
+        true
     }
 
     /// Checks if a filename matches the include/exclude patterns.
@@ -411,6 +565,39 @@ impl FilesystemProvider {
         false
     }
 
+    /// Checks a path against the extension allowlist and denylist.
+    fn extension_allowed(&self, path: &str) -> bool {
+        if self
+            .deny_extensions
+            .iter()
+            .any(|denied| has_extension(path, denied))
+        {
+            return false;
+        }
+        if self.allow_extensions.is_empty() {
+            return true;
+        }
+        file_extension(path).is_some_and(|ext| self.allow_extensions.contains(&ext))
+    }
+
+    /// Rejects reads of files whose requested or resolved name has a
+    /// disallowed extension.
+    fn check_extension(
+        &self,
+        relative_path: &str,
+        canonical: &Path,
+    ) -> Result<(), FilesystemProviderError> {
+        if self.extension_allowed(relative_path)
+            && self.extension_allowed(&canonical.to_string_lossy())
+        {
+            Ok(())
+        } else {
+            Err(FilesystemProviderError::ExtensionDenied {
+                path: relative_path.to_string(),
+            })
+        }
+    }
+
     /// Lists files in the directory that match patterns.
     fn list_files(&self) -> Result<Vec<FileEntry>, FilesystemProviderError> {
         let canonical_root = self
@@ -447,7 +634,7 @@ impl FilesystemProvider {
             })?;
 
             // Handle symlinks
-            if file_type.is_symlink() && !self.follow_symlinks {
+            if file_type.is_symlink() && !self.follow_listed_symlink(&path, current, root) {
                 continue;
             }
 
@@ -465,13 +652,13 @@ impl FilesystemProvider {
                 }
             } else if file_type.is_file() || (file_type.is_symlink() && path.is_file()) {
                 // Check patterns
-                if self.matches_patterns(&relative_str) {
+                if self.matches_patterns(&relative_str) && self.extension_allowed(&relative_str) {
                     let metadata = std::fs::metadata(&path).ok();
                     entries.push(FileEntry {
                         path: path.clone(),
                         relative_path: relative_str,
                         size: metadata.as_ref().map(|m| m.len()),
-                        mime_type: detect_mime_type(&path),
+                        mime_type: self.mime_type(&path),
                     });
                 }
             }
@@ -488,6 +675,19 @@ impl FilesystemProvider {
         }
     }
 
+    /// Returns the listed resource for a file entry.
+    fn entry_resource(&self, entry: FileEntry) -> Resource {
+        Resource {
+            uri: self.file_uri(&entry.relative_path),
+            name: entry.relative_path,
+            description: None,
+            mime_type: Some(entry.mime_type),
+            icon: None,
+            version: None,
+            tags: vec![],
+        }
+    }
+
     /// Returns the URI template for this provider.
     fn uri_template(&self) -> String {
         match &self.prefix {
@@ -510,13 +710,9 @@ impl FilesystemProvider {
         }
     }
 
-    /// Reads a file and returns its content.
-    fn read_file(&self, relative_path: &str) -> Result<FileContent, FilesystemProviderError> {
-        // Validate and get canonical path
-        let path = self.validate_path(relative_path)?;
-
-        // Check file size
-        let metadata = std::fs::metadata(&path).map_err(|e| {
+    /// Returns the size of a file, mapping IO errors to provider errors.
+    fn file_len(path: &Path, relative_path: &str) -> Result<u64, FilesystemProviderError> {
+        std::fs::metadata(path).map(|m| m.len()).map_err(|e| {
             if e.kind() == std::io::ErrorKind::NotFound {
                 FilesystemProviderError::NotFound {
                     path: relative_path.to_string(),
@@ -526,18 +722,65 @@ impl FilesystemProvider {
                     message: e.to_string(),
                 }
             }
-        })?;
+        })
+    }
 
-        if metadata.len() > self.max_file_size as u64 {
+    /// Returns the cache validators for a file, derived from its size and mtime.
+    ///
+    /// Returns `None` if the metadata cannot be read; the content is then
+    /// served without validators.
+    fn file_version(&self, relative_path: &str) -> Option<FileVersion> {
+        let path = self.validate_path(relative_path).ok()?;
+        let metadata = std::fs::metadata(path).ok()?;
+        let modified = metadata.modified().ok()?;
+        let mtime_nanos = modified
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos());
+        Some(FileVersion {
+            etag: format!("{:x}-{:x}", metadata.len(), mtime_nanos),
+            last_modified: chrono::DateTime::<chrono::Utc>::from(modified)
+                .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        })
+    }
+
+    /// Returns the MIME type a file is served as.
+    ///
+    /// Uses the extension, falling back to content sniffing if enabled.
+    fn mime_type(&self, path: &Path) -> String {
+        if self.content_sniffing && extension_mime_type(path).is_none() {
+            sniff_mime_type(path).to_string()
+        } else {
+            detect_mime_type(path)
+        }
+    }
+
+    /// Returns the MIME type of the file at a requested path.
+    fn mime_type_for(&self, relative_path: &str) -> String {
+        match self.validate_path(relative_path) {
+            Ok(path) => self.mime_type(&path),
+            Err(_) => detect_mime_type(Path::new(relative_path)),
+        }
+    }
+
+    /// Reads a file and returns its content.
+    fn read_file(&self, relative_path: &str) -> Result<FileContent, FilesystemProviderError> {
+        // Validate and get canonical path
+        let path = self.validate_path(relative_path)?;
+        self.check_extension(relative_path, &path)?;
+
+        // Check file size
+        let len = Self::file_len(&path, relative_path)?;
+
+        if len > self.max_file_size as u64 {
             return Err(FilesystemProviderError::TooLarge {
                 path: relative_path.to_string(),
-                size: metadata.len(),
+                size: len,
                 max: self.max_file_size,
             });
         }
 
         // Detect MIME type
-        let mime_type = detect_mime_type(&path);
+        let mime_type = self.mime_type(&path);
 
         // Read content
         let content = if is_binary_mime_type(&mime_type) {
@@ -554,6 +797,49 @@ impl FilesystemProvider {
 
         Ok(content)
     }
+
+    /// Reads a byte range of a file by seeking, without loading the rest.
+    ///
+    /// The size limit applies to the number of bytes read, so ranges can be
+    /// used to page through files larger than `max_file_size`.
+    fn read_file_range(
+        &self,
+        relative_path: &str,
+        range: ResourceRange,
+    ) -> Result<FileContent, FilesystemProviderError> {
+        let path = self.validate_path(relative_path)?;
+        self.check_extension(relative_path, &path)?;
+        let len = Self::file_len(&path, relative_path)?;
+        let (start, end) = range.resolve(len);
+
+        if end - start > self.max_file_size as u64 {
+            return Err(FilesystemProviderError::TooLarge {
+                path: relative_path.to_string(),
+                size: end - start,
+                max: self.max_file_size,
+            });
+        }
+
+        let io_err = |e: std::io::Error| FilesystemProviderError::Io {
+            message: e.to_string(),
+        };
+        let mut file = std::fs::File::open(&path).map_err(io_err)?;
+        file.seek(SeekFrom::Start(start)).map_err(io_err)?;
+        let mut bytes = Vec::with_capacity((end - start) as usize);
+        file.take(end - start)
+            .read_to_end(&mut bytes)
+            .map_err(io_err)?;
+
+        if is_binary_mime_type(&self.mime_type(&path)) {
+            Ok(FileContent::Binary(bytes))
+        } else {
+            let text =
+                String::from_utf8(bytes).map_err(|_| FilesystemProviderError::InvalidRange {
+                    path: relative_path.to_string(),
+                })?;
+            Ok(FileContent::Text(text))
+        }
+    }
 }
 
 /// A file entry from directory listing.
@@ -567,6 +853,12 @@ struct FileEntry {
     mime_type: String,
 }
 
+/// Cache validators for a file.
+struct FileVersion {
+    etag: String,
+    last_modified: String,
+}
+
 /// File content (text or binary).
 enum FileContent {
     Text(String),
@@ -587,15 +879,7 @@ impl FilesystemResourceHandler {
         let cached_resources = match provider.list_files() {
             Ok(entries) => entries
                 .into_iter()
-                .map(|entry| Resource {
-                    uri: provider.file_uri(&entry.relative_path),
-                    name: entry.relative_path.clone(),
-                    description: None,
-                    mime_type: Some(entry.mime_type),
-                    icon: None,
-                    version: None,
-                    tags: vec![],
-                })
+                .map(|entry| provider.entry_resource(entry))
                 .collect(),
             Err(_) => Vec::new(),
         };
@@ -638,9 +922,27 @@ impl ResourceHandler for FilesystemResourceHandler {
             icon: None,
             version: None,
             tags: vec![],
+            parameters: vec![TemplateParameter {
+                name: "path".to_string(),
+                description: Some("File path relative to the provider root".to_string()),
+                required: true,
+                param_type: Some("string".to_string()),
+            }],
         })
     }
 
+    /// Lists the files currently present, so `resources/list` shows them
+    /// alongside the template. Returns `None` if the root can't be read.
+    fn expand(&self) -> Option<Vec<Resource>> {
+        let entries = self.provider.list_files().ok()?;
+        Some(
+            entries
+                .into_iter()
+                .map(|entry| self.provider.entry_resource(entry))
+                .collect(),
+        )
+    }
+
     fn read(&self, _ctx: &McpContext) -> McpResult<Vec<ResourceContent>> {
         // For template resources, read() without params returns the file list
         let files = self.provider.list_files()?;
@@ -656,6 +958,8 @@ impl ResourceHandler for FilesystemResourceHandler {
             mime_type: Some("text/plain".to_string()),
             text: Some(listing),
             blob: None,
+            etag: None,
+            last_modified: None,
         }])
     }
 
@@ -665,37 +969,17 @@ impl ResourceHandler for FilesystemResourceHandler {
         uri: &str,
         params: &UriParams,
     ) -> McpResult<Vec<ResourceContent>> {
-        // Extract path from URI or params
-        let relative_path = if let Some(path) = params.get("path") {
-            path.clone()
-        } else if let Some(path) = self.provider.path_from_uri(uri) {
-            path
-        } else {
-            return Err(McpError::invalid_params("Missing path parameter"));
-        };
-
+        let relative_path = self.relative_path(uri, params)?;
+        // Taken before reading, so a concurrent write can only make the
+        // validators stale (forcing a re-read), never newer than the content
+        let version = self.provider.file_version(&relative_path);
         let content = self.provider.read_file(&relative_path)?;
-
-        let resource_content = match content {
-            FileContent::Text(text) => ResourceContent {
-                uri: uri.to_string(),
-                mime_type: Some(detect_mime_type(Path::new(&relative_path))),
-                text: Some(text),
-                blob: None,
-            },
-            FileContent::Binary(bytes) => {
-                let base64_str = base64_encode(&bytes);
-
-                ResourceContent {
-                    uri: uri.to_string(),
-                    mime_type: Some(detect_mime_type(Path::new(&relative_path))),
-                    text: None,
-                    blob: Some(base64_str),
-                }
-            }
-        };
-
-        Ok(vec![resource_content])
+        Ok(vec![Self::resource_content(
+            uri,
+            self.provider.mime_type_for(&relative_path),
+            content,
+            version,
+        )])
     }
 
     fn read_async_with_uri<'a>(
@@ -711,6 +995,76 @@ impl ResourceHandler for FilesystemResourceHandler {
             }
         })
     }
+
+    fn content_len(&self, uri: &str, params: &UriParams) -> Option<u64> {
+        let relative_path = self.relative_path(uri, params).ok()?;
+        let path = self.provider.validate_path(&relative_path).ok()?;
+        self.provider.check_extension(&relative_path, &path).ok()?;
+        FilesystemProvider::file_len(&path, &relative_path).ok()
+    }
+
+    fn read_with_range<'a>(
+        &'a self,
+        _ctx: &'a McpContext,
+        uri: &'a str,
+        params: &'a UriParams,
+        range: ResourceRange,
+    ) -> BoxFuture<'a, McpOutcome<Vec<ResourceContent>>> {
+        Box::pin(async move {
+            let result = self.relative_path(uri, params).and_then(|relative_path| {
+                let version = self.provider.file_version(&relative_path);
+                let content = self.provider.read_file_range(&relative_path, range)?;
+                Ok(vec![Self::resource_content(
+                    uri,
+                    self.provider.mime_type_for(&relative_path),
+                    content,
+                    version,
+                )])
+            });
+            match result {
+                Ok(v) => Outcome::Ok(v),
+                Err(e) => Outcome::Err(e),
+            }
+        })
+    }
+}
+
+impl FilesystemResourceHandler {
+    /// Extracts the requested relative path from URI params or the URI itself.
+    fn relative_path(&self, uri: &str, params: &UriParams) -> McpResult<String> {
+        if let Some(path) = params.get("path") {
+            Ok(path.clone())
+        } else if let Some(path) = self.provider.path_from_uri(uri) {
+            Ok(path)
+        } else {
+            Err(McpError::invalid_params("Missing path parameter"))
+        }
+    }
+
+    /// Converts file content into a protocol resource content item.
+    fn resource_content(
+        uri: &str,
+        mime_type: String,
+        content: FileContent,
+        version: Option<FileVersion>,
+    ) -> ResourceContent {
+        let (etag, last_modified) =
+            version.map_or((None, None), |v| (Some(v.etag), Some(v.last_modified)));
+
+        let content = match content {
+            FileContent::Text(text) => ResourceContent {
+                mime_type: Some(mime_type),
+                text: Some(text),
+                ..ResourceContent::reference(uri)
+            },
+            FileContent::Binary(bytes) => ResourceContent::binary(uri, mime_type, &bytes),
+        };
+        ResourceContent {
+            etag,
+            last_modified,
+            ..content
+        }
+    }
 }
 
 impl std::fmt::Debug for FilesystemResourceHandler {
@@ -722,14 +1076,63 @@ impl std::fmt::Debug for FilesystemResourceHandler {
     }
 }
 
+/// Normalizes extensions to lowercase without a leading `*.` or `.`.
+fn normalize_extensions(extensions: &[&str]) -> Vec<String> {
+    extensions
+        .iter()
+        .map(|ext| {
+            ext.trim_start_matches('*')
+                .trim_start_matches('.')
+                .to_ascii_lowercase()
+        })
+        .collect()
+}
+
+/// Returns the lowercase extension of the last path component.
+///
+/// Unlike [`Path::extension`], a dotfile's name is its extension, so
+/// `.env` yields `env`.
+fn file_extension(path: &str) -> Option<String> {
+    let name = path.rsplit(['/', '\\']).next()?;
+    let (_, ext) = name.rsplit_once('.')?;
+    (!ext.is_empty()).then(|| ext.to_ascii_lowercase())
+}
+
+/// Returns whether the last path component has `ext` (lowercase, without
+/// the dot) among its extensions.
+///
+/// Every segment after a dot counts, so `secrets.env.bak` and `.env.local`
+/// both have `env`; so does a dotfile whose name starts with `ext`, such as
+/// `.envrc`.
+fn has_extension(path: &str, ext: &str) -> bool {
+    if ext.is_empty() {
+        return false;
+    }
+    let name = path
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(path)
+        .to_ascii_lowercase();
+    name.strip_prefix('.')
+        .is_some_and(|dotfile| dotfile.starts_with(ext))
+        || name.split('.').skip(1).any(|segment| segment == ext)
+}
+
 /// Detects the MIME type for a file based on its extension.
 fn detect_mime_type(path: &Path) -> String {
+    extension_mime_type(path)
+        .unwrap_or("application/octet-stream")
+        .to_string()
+}
+
+/// Returns the MIME type for a known file extension.
+fn extension_mime_type(path: &Path) -> Option<&'static str> {
     let extension = path
         .extension()
         .and_then(|e| e.to_str())
         .map(str::to_lowercase);
 
-    match extension.as_deref() {
+    let mime_type = match extension.as_deref() {
         // Text formats
         Some("txt") => "text/plain",
         Some("md" | "markdown") => "text/markdown",
@@ -778,10 +1181,47 @@ fn detect_mime_type(path: &Path) -> String {
         Some("so") => "application/octet-stream",
         Some("bin") => "application/octet-stream",
 
-        // Default
-        _ => "application/octet-stream",
+        _ => return None,
+    };
+    Some(mime_type)
+}
+
+/// Detects a MIME type from the first [`SNIFF_LEN`] bytes of a file.
+///
+/// Falls back to `application/octet-stream` if the file cannot be read or
+/// looks binary.
+fn sniff_mime_type(path: &Path) -> &'static str {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"%PDF-", "application/pdf"),
+        (b"\x1f\x8b", "application/gzip"),
+    ];
+
+    let mut prefix = Vec::new();
+    let read =
+        std::fs::File::open(path).and_then(|file| file.take(SNIFF_LEN).read_to_end(&mut prefix));
+    if read.is_err() {
+        return "application/octet-stream";
+    }
+
+    if let Some((_, mime_type)) = SIGNATURES
+        .iter()
+        .find(|(signature, _)| prefix.starts_with(signature))
+    {
+        return mime_type;
+    }
+
+    // A multi-byte character may be cut off at the end of the prefix
+    let is_utf8 = match std::str::from_utf8(&prefix) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    };
+    if is_utf8 && !prefix.contains(&0) {
+        "text/plain"
+    } else {
+        "application/octet-stream"
     }
-    .to_string()
 }
 
 /// Checks if a MIME type represents binary content.
@@ -797,39 +1237,6 @@ fn is_binary_mime_type(mime_type: &str) -> bool {
         || mime_type == "application/wasm"
 }
 
-/// Standard base64 alphabet.
-const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
-
-/// Encodes bytes to standard base64.
-fn base64_encode(data: &[u8]) -> String {
-    let mut result = String::with_capacity((data.len() + 2) / 3 * 4);
-
-    for chunk in data.chunks(3) {
-        let b0 = chunk[0] as usize;
-        let b1 = chunk.get(1).copied().unwrap_or(0) as usize;
-        let b2 = chunk.get(2).copied().unwrap_or(0) as usize;
-
-        let combined = (b0 << 16) | (b1 << 8) | b2;
-
-        result.push(BASE64_CHARS[(combined >> 18) & 0x3F] as char);
-        result.push(BASE64_CHARS[(combined >> 12) & 0x3F] as char);
-
-        if chunk.len() > 1 {
-            result.push(BASE64_CHARS[(combined >> 6) & 0x3F] as char);
-        } else {
-            result.push('=');
-        }
-
-        if chunk.len() > 2 {
-            result.push(BASE64_CHARS[combined & 0x3F] as char);
-        } else {
-            result.push('=');
-        }
-    }
-
-    result
-}
-
 /// Simple glob pattern matching.
 ///
 /// Supports:
@@ -915,6 +1322,7 @@ fn glob_match_recursive(pattern: &str, path: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tempfile::tempdir;
 
     #[test]
     fn test_glob_match_star() {
@@ -958,6 +1366,344 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_read_file_range_returns_slice() {
+        let root = tempdir().expect("create temp dir");
+        std::fs::write(root.path().join("log.txt"), "0123456789abcdef").expect("write file");
+        let provider = FilesystemProvider::new(&root);
+
+        match provider.read_file_range("log.txt", ResourceRange::new(4, Some(6))) {
+            Ok(FileContent::Text(text)) => assert_eq!(text, "456789"),
+            _ => panic!("expected text slice"),
+        }
+
+        // Ranges running past the end are clamped to the file size
+        match provider.read_file_range("log.txt", ResourceRange::new(12, Some(100))) {
+            Ok(FileContent::Text(text)) => assert_eq!(text, "cdef"),
+            _ => panic!("expected clamped text slice"),
+        }
+    }
+
+    #[test]
+    fn test_read_file_range_applies_max_size_to_slice() {
+        let root = tempdir().expect("create temp dir");
+        std::fs::write(root.path().join("big.txt"), "0123456789abcdef").expect("write file");
+        let provider = FilesystemProvider::new(&root).with_max_size(8);
+
+        assert!(matches!(
+            provider.read_file("big.txt"),
+            Err(FilesystemProviderError::TooLarge { size: 16, .. })
+        ));
+        assert!(matches!(
+            provider.read_file_range("big.txt", ResourceRange::new(0, None)),
+            Err(FilesystemProviderError::TooLarge { .. })
+        ));
+        match provider.read_file_range("big.txt", ResourceRange::new(8, Some(8))) {
+            Ok(FileContent::Text(text)) => assert_eq!(text, "89abcdef"),
+            _ => panic!("expected in-limit slice"),
+        }
+    }
+
+    #[test]
+    fn test_read_carries_etag_and_conditional_read_is_not_modified() {
+        use crate::router::Router;
+        use asupersync::{Budget, Cx};
+        use fastmcp_core::SessionState;
+        use fastmcp_protocol::ReadResourceParams;
+
+        let root = tempdir().expect("create temp dir");
+        std::fs::write(root.path().join("notes.txt"), "hello").expect("write file");
+        let mut router = Router::new();
+        router.add_resource(FilesystemProvider::new(&root).with_prefix("docs").build());
+
+        let read = |if_none_match: Option<String>| {
+            let params = ReadResourceParams {
+                uri: "file://docs/notes.txt".to_string(),
+                range: None,
+                if_none_match,
+                if_modified_since: None,
+                meta: None,
+            };
+            router
+                .handle_resources_read(
+                    &Cx::for_testing(),
+                    1,
+                    None,
+                    &params,
+                    &Budget::INFINITE,
+                    SessionState::new(),
+                    None,
+                    None,
+                )
+                .expect("read")
+        };
+
+        let first = read(None);
+        assert!(!first.not_modified);
+        let etag = first.contents[0].etag.clone().expect("etag");
+        assert!(first.contents[0].last_modified.is_some());
+
+        let again = read(Some(etag.clone()));
+        assert!(again.not_modified);
+        assert!(again.contents.is_empty());
+
+        // A different size yields a different ETag, so the content is re-sent
+        std::fs::write(root.path().join("notes.txt"), "hello, world").expect("rewrite file");
+        let changed = read(Some(etag.clone()));
+        assert!(!changed.not_modified);
+        assert_eq!(changed.contents[0].text.as_deref(), Some("hello, world"));
+        assert_ne!(changed.contents[0].etag.as_deref(), Some(etag.as_str()));
+    }
+
+    #[test]
+    fn test_denied_extensions_are_not_listed_or_readable() {
+        let root = tempdir().expect("create temp dir");
+        std::fs::write(root.path().join(".env"), "SECRET=1").expect("write file");
+        std::fs::write(root.path().join("server.pem"), "-----BEGIN-----").expect("write file");
+        std::fs::write(root.path().join("notes.txt"), "hello").expect("write file");
+        // A broad include pattern and no hidden-file exclusion still must not expose secrets
+        let provider = FilesystemProvider::new(&root)
+            .with_patterns(&["*"])
+            .with_exclude(&[]);
+
+        let listed: Vec<String> = provider
+            .list_files()
+            .expect("list files")
+            .into_iter()
+            .map(|entry| entry.relative_path)
+            .collect();
+        assert_eq!(listed, ["notes.txt"]);
+
+        assert!(matches!(
+            provider.read_file(".env"),
+            Err(FilesystemProviderError::ExtensionDenied { .. })
+        ));
+        let err = McpError::from(provider.read_file("server.pem").unwrap_err());
+        assert_eq!(err.code, McpErrorCode::ResourceForbidden);
+        assert!(matches!(
+            provider.read_file_range(".env", ResourceRange::new(0, None)),
+            Err(FilesystemProviderError::ExtensionDenied { .. })
+        ));
+        assert!(matches!(
+            provider.read_file("notes.txt"),
+            Ok(FileContent::Text(text)) if text == "hello"
+        ));
+    }
+
+    #[test]
+    fn test_denied_extension_in_any_segment_is_not_readable() {
+        let root = tempdir().expect("create temp dir");
+        for name in [".env.local", ".env.production", "secrets.env.bak"] {
+            std::fs::write(root.path().join(name), "SECRET=1").expect("write file");
+        }
+        let provider = FilesystemProvider::new(&root)
+            .with_patterns(&["*"])
+            .with_exclude(&[]);
+
+        assert!(provider.list_files().expect("list files").is_empty());
+        for name in [".env.local", ".env.production", "secrets.env.bak"] {
+            assert!(
+                matches!(
+                    provider.read_file(name),
+                    Err(FilesystemProviderError::ExtensionDenied { .. })
+                ),
+                "{name} should be denied"
+            );
+        }
+
+        // Size probes for ranged reads don't reveal denied files either
+        let handler = provider.build();
+        let params = UriParams::from([("path".to_string(), ".env.local".to_string())]);
+        assert_eq!(handler.content_len("file://.env.local", &params), None);
+    }
+
+    #[test]
+    fn test_allow_extensions_restricts_listing_and_reads() {
+        let root = tempdir().expect("create temp dir");
+        std::fs::write(root.path().join("readme.MD"), "# Hi").expect("write file");
+        std::fs::write(root.path().join("data.json"), "{}").expect("write file");
+        std::fs::write(root.path().join("Makefile"), "all:").expect("write file");
+        let provider = FilesystemProvider::new(&root).with_allow_extensions(&["*.md", ".txt"]);
+
+        let listed: Vec<String> = provider
+            .list_files()
+            .expect("list files")
+            .into_iter()
+            .map(|entry| entry.relative_path)
+            .collect();
+        assert_eq!(listed, ["readme.MD"]);
+
+        assert!(provider.read_file("readme.MD").is_ok());
+        assert!(matches!(
+            provider.read_file("data.json"),
+            Err(FilesystemProviderError::ExtensionDenied { .. })
+        ));
+        assert!(matches!(
+            provider.read_file("Makefile"),
+            Err(FilesystemProviderError::ExtensionDenied { .. })
+        ));
+    }
+
+    #[test]
+    fn test_content_sniffing_detects_extensionless_png() {
+        let root = tempdir().expect("create temp dir");
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        std::fs::write(root.path().join("logo"), png).expect("write file");
+
+        let plain = FilesystemProvider::new(&root);
+        assert_eq!(
+            plain.mime_type(&root.path().join("logo")),
+            "application/octet-stream"
+        );
+
+        let provider = FilesystemProvider::new(&root).with_content_sniffing(true);
+        assert_eq!(provider.mime_type(&root.path().join("logo")), "image/png");
+        match provider.read_file("logo") {
+            Ok(FileContent::Binary(bytes)) => assert_eq!(bytes, png),
+            _ => panic!("expected binary content"),
+        }
+    }
+
+    #[test]
+    fn test_content_sniffing_detects_extensionless_text() {
+        let root = tempdir().expect("create temp dir");
+        std::fs::write(root.path().join("NOTES"), "héllo wörld\n").expect("write file");
+        std::fs::write(root.path().join("blob"), [0x01, 0x00, 0xfe]).expect("write file");
+        let provider = FilesystemProvider::new(&root).with_content_sniffing(true);
+
+        assert_eq!(provider.mime_type(&root.path().join("NOTES")), "text/plain");
+        match provider.read_file("NOTES") {
+            Ok(FileContent::Text(text)) => assert_eq!(text, "héllo wörld\n"),
+            _ => panic!("expected text content"),
+        }
+        assert_eq!(
+            provider.mime_type(&root.path().join("blob")),
+            "application/octet-stream"
+        );
+    }
+
+    #[cfg(unix)]
+    fn listed(provider: &FilesystemProvider) -> Vec<String> {
+        let mut listed: Vec<String> = provider
+            .list_files()
+            .expect("list files")
+            .into_iter()
+            .map(|entry| entry.relative_path)
+            .collect();
+        listed.sort();
+        listed
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_in_root_symlink_followed_when_enabled() {
+        let root = tempdir().expect("create temp dir");
+        std::fs::write(root.path().join("notes.txt"), "hello").expect("write file");
+        std::os::unix::fs::symlink(root.path().join("notes.txt"), root.path().join("alias.txt"))
+            .expect("create symlink");
+
+        // Not followed by default
+        let provider = FilesystemProvider::new(&root);
+        assert_eq!(listed(&provider), ["notes.txt"]);
+        assert!(matches!(
+            provider.read_file("alias.txt"),
+            Err(FilesystemProviderError::SymlinkDenied { .. })
+        ));
+
+        let provider = FilesystemProvider::new(&root).with_follow_symlinks(true);
+        assert_eq!(listed(&provider), ["alias.txt", "notes.txt"]);
+        assert!(matches!(
+            provider.read_file("alias.txt"),
+            Ok(FileContent::Text(text)) if text == "hello"
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_escaping_symlink_rejected() {
+        let root = tempdir().expect("create temp dir");
+        let outside = tempdir().expect("create temp dir");
+        std::fs::write(outside.path().join("secret.txt"), "secret").expect("write file");
+        std::os::unix::fs::symlink(
+            outside.path().join("secret.txt"),
+            root.path().join("leak.txt"),
+        )
+        .expect("create symlink");
+        std::os::unix::fs::symlink(&outside, root.path().join("elsewhere"))
+            .expect("create symlink");
+
+        let provider = FilesystemProvider::new(&root).with_recursive(true);
+        assert!(listed(&provider).is_empty());
+        assert!(matches!(
+            provider.read_file("leak.txt"),
+            Err(FilesystemProviderError::SymlinkDenied { .. })
+        ));
+
+        let provider = provider.with_follow_symlinks(true);
+        assert!(listed(&provider).is_empty());
+        assert!(matches!(
+            provider.read_file("leak.txt"),
+            Err(FilesystemProviderError::SymlinkEscapesRoot { .. })
+        ));
+        assert!(matches!(
+            provider.read_file("elsewhere/secret.txt"),
+            Err(FilesystemProviderError::SymlinkEscapesRoot { .. })
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_allow_escape_follows_escaping_symlink() {
+        let root = tempdir().expect("create temp dir");
+        let outside = tempdir().expect("create temp dir");
+        std::fs::write(outside.path().join("shared.txt"), "shared").expect("write file");
+        std::os::unix::fs::symlink(&outside, root.path().join("shared")).expect("create symlink");
+
+        let provider = FilesystemProvider::new(&root)
+            .with_recursive(true)
+            .with_follow_symlinks(true)
+            .with_allow_escape(true);
+        assert_eq!(listed(&provider), ["shared/shared.txt"]);
+        assert!(matches!(
+            provider.read_file("shared/shared.txt"),
+            Ok(FileContent::Text(text)) if text == "shared"
+        ));
+
+        // `..` still cannot leave the root
+        assert!(matches!(
+            provider.read_file("shared/../../etc/passwd"),
+            Err(FilesystemProviderError::PathTraversal { .. }
+                | FilesystemProviderError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_has_extension() {
+        for path in [
+            ".env",
+            "config/.ENV.local",
+            ".env.production",
+            ".envrc",
+            "secrets.env.bak",
+            "prod.env",
+        ] {
+            assert!(has_extension(path, "env"), "{path}");
+        }
+        for path in ["environment.txt", "dir.env/notes.txt", "README", "x.envy"] {
+            assert!(!has_extension(path, "env"), "{path}");
+        }
+        assert!(!has_extension("file.", ""));
+    }
+
+    #[test]
+    fn test_file_extension() {
+        assert_eq!(file_extension(".env").as_deref(), Some("env"));
+        assert_eq!(file_extension("config/prod.ENV").as_deref(), Some("env"));
+        assert_eq!(file_extension("archive.tar.gz").as_deref(), Some("gz"));
+        assert_eq!(file_extension("dir.d/Makefile"), None);
+        assert_eq!(file_extension("trailing."), None);
+    }
+
     #[test]
     fn test_is_binary_mime_type() {
         assert!(is_binary_mime_type("image/png"));
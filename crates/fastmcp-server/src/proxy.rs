@@ -482,6 +482,8 @@ mod tests {
                 text: Some("resource".to_string()),
                 mime_type: None,
                 blob: None,
+                etag: None,
+                last_modified: None,
             }])
         }
 
@@ -981,6 +983,7 @@ mod tests {
             icon: None,
             version: None,
             tags: vec![],
+            parameters: vec![],
         };
         let handler = ProxyResourceHandler::from_template(template.clone(), proxy);
 
@@ -1012,6 +1015,7 @@ mod tests {
             icon: None,
             version: None,
             tags: vec![],
+            parameters: vec![],
         };
         let handler = ProxyResourceHandler::from_template_with_prefix(template, "storage", proxy);
 
@@ -1180,6 +1184,7 @@ mod tests {
             icon: None,
             version: Some("1.0.0".to_string()),
             tags: vec!["db".to_string()],
+            parameters: vec![],
         };
         let resource = super::resource_from_template(&template);
         assert_eq!(resource.uri, "db://{table}/{id}");
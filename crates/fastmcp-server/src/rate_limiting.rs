@@ -5,6 +5,9 @@
 //! - [`RateLimitingMiddleware`]: Token bucket algorithm for burst-friendly limits
 //! - [`SlidingWindowRateLimitingMiddleware`]: Sliding window for precise tracking
 //!
+//! For multi-tenant servers, [`ServerBuilder::rate_limit`](crate::ServerBuilder::rate_limit)
+//! additionally enforces a [`SubjectRateLimiter`] keyed by the authenticated subject.
+//!
 //! # Example
 //!
 //! ```ignore
@@ -22,9 +25,9 @@
 
 use std::collections::{HashMap, VecDeque};
 use std::sync::Mutex;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use fastmcp_core::{McpContext, McpError, McpErrorCode, McpResult};
+use fastmcp_core::{AuthContext, McpContext, McpError, McpErrorCode, McpResult};
 use fastmcp_protocol::JsonRpcRequest;
 
 use crate::{Middleware, MiddlewareDecision};
@@ -124,6 +127,18 @@ impl TokenBucketRateLimiter {
 
         *current_tokens
     }
+
+    /// Returns how long until `tokens` tokens will be available.
+    ///
+    /// Returns [`Duration::ZERO`] if they are available now.
+    #[must_use]
+    pub fn time_until_available(&self, tokens: usize) -> Duration {
+        let deficit = tokens as f64 - self.available_tokens();
+        if deficit <= 0.0 {
+            return Duration::ZERO;
+        }
+        Duration::try_from_secs_f64(deficit / self.refill_rate).unwrap_or(Duration::MAX)
+    }
 }
 
 /// Sliding window rate limiter implementation.
@@ -211,6 +226,61 @@ impl SlidingWindowRateLimiter {
     }
 }
 
+/// Per-subject token bucket limits enforced by the server itself.
+///
+/// Each authenticated subject ([`AuthContext::subject`]) gets its own bucket;
+/// requests without a subject share a single anonymous bucket. Configured via
+/// [`ServerBuilder::rate_limit`](crate::ServerBuilder::rate_limit).
+#[derive(Debug)]
+pub struct SubjectRateLimiter {
+    /// Sustained requests per second allowed per subject.
+    requests_per_second: f64,
+    /// Maximum burst per subject.
+    burst: usize,
+    /// Buckets keyed by subject (`None` = anonymous).
+    buckets: Mutex<HashMap<Option<String>, TokenBucketRateLimiter>>,
+}
+
+impl SubjectRateLimiter {
+    /// Creates a limiter allowing `requests_per_second` per subject with
+    /// bursts up to `burst`.
+    #[must_use]
+    pub fn new(requests_per_second: f64, burst: usize) -> Self {
+        Self {
+            requests_per_second,
+            burst,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Consumes one request from the caller's bucket.
+    ///
+    /// When the bucket is empty, fails with a `RequestCancelled` "rate
+    /// limited" error whose `data.retryAfter` is the number of seconds until
+    /// the next request would be admitted.
+    pub fn check(&self, auth: &AuthContext) -> McpResult<()> {
+        let mut buckets = self
+            .buckets
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let bucket = buckets
+            .entry(auth.subject.clone())
+            .or_insert_with(|| TokenBucketRateLimiter::new(self.burst, self.requests_per_second));
+
+        if bucket.try_consume(1) {
+            return Ok(());
+        }
+
+        let retry_after = bucket.time_until_available(1);
+        let subject = auth.subject.as_deref().unwrap_or("anonymous");
+        Err(McpError::with_data(
+            McpErrorCode::RequestCancelled,
+            format!("Rate limited: too many requests for subject '{subject}'"),
+            serde_json::json!({ "retryAfter": retry_after.as_secs_f64() }),
+        ))
+    }
+}
+
 /// Function type for extracting client ID from request context.
 pub type ClientIdExtractor =
     Box<dyn Fn(&McpContext, &JsonRpcRequest) -> Option<String> + Send + Sync>;
@@ -669,6 +739,31 @@ mod tests {
         assert!(middleware.on_request(&ctx, &request).is_err());
     }
 
+    #[test]
+    fn test_subject_rate_limiter_shares_anonymous_bucket() {
+        let limiter = SubjectRateLimiter::new(0.001, 1);
+
+        assert!(limiter.check(&AuthContext::anonymous()).is_ok());
+        let err = limiter.check(&AuthContext::anonymous()).unwrap_err();
+        assert_eq!(err.code, McpErrorCode::RequestCancelled);
+        assert!(
+            err.data
+                .is_some_and(|d| d["retryAfter"].as_f64() > Some(0.0))
+        );
+
+        assert!(limiter.check(&AuthContext::with_subject("alice")).is_ok());
+    }
+
+    #[test]
+    fn test_time_until_available() {
+        let bucket = TokenBucketRateLimiter::new(1, 10.0);
+        assert_eq!(bucket.time_until_available(1), Duration::ZERO);
+
+        assert!(bucket.try_consume(1));
+        let wait = bucket.time_until_available(1);
+        assert!(wait > Duration::ZERO && wait <= Duration::from_millis(100));
+    }
+
     #[test]
     fn test_rate_limit_error_code() {
         let err = rate_limit_error("test");
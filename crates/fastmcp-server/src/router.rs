@@ -1,12 +1,19 @@
 //! Request router for MCP servers.
 
-use std::collections::HashMap;
-use std::sync::Arc;
-
-use asupersync::{Budget, Cx, Outcome};
-use fastmcp_core::logging::{debug, targets, trace};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use asupersync::types::PanicPayload;
+use asupersync::{Budget, CancelKind, Cx, Outcome};
+use fastmcp_console::stats::ServerStats;
+use fastmcp_core::logging::{debug, error, targets, trace};
 use fastmcp_core::{
-    McpContext, McpError, McpErrorCode, McpResult, OutcomeExt, SessionState, block_on,
+    ContentStreamSender, McpContext, McpError, McpErrorCode, McpOutcome, McpResult,
+    MissingCapabilityPolicy, OutcomeExt, SessionState, ToolContentItem, block_on,
 };
 use fastmcp_protocol::{
     CallToolParams, CallToolResult, CancelTaskParams, CancelTaskResult, Content, GetPromptParams,
@@ -14,17 +21,22 @@ use fastmcp_protocol::{
     JsonRpcRequest, ListPromptsParams, ListPromptsResult, ListResourceTemplatesParams,
     ListResourceTemplatesResult, ListResourcesParams, ListResourcesResult, ListTasksParams,
     ListTasksResult, ListToolsParams, ListToolsResult, PROTOCOL_VERSION, ProgressToken, Prompt,
-    ReadResourceParams, ReadResourceResult, Resource, ResourceTemplate, SubmitTaskParams,
-    SubmitTaskResult, Tool, validate, validate_strict,
+    PromptMessage, ReadResourceParams, ReadResourceResult, RequestId, Resource, ResourceContent,
+    ResourceTemplate, ResultFormat, SubmitTaskParams, SubmitTaskResult, TagMatch,
+    TemplateParameter, Tool, ValidationError, coerce_to_schema, validate, validate_strict,
 };
 
-use crate::handler::{BidirectionalSenders, UriParams, create_context_with_progress_and_senders};
+use crate::caching::ToolResultCache;
+use crate::handler::{
+    BidirectionalSenders, ToolContentNotificationSender, UriParams, content_from_item,
+    create_context_with_progress_and_senders,
+};
 use crate::tasks::SharedTaskManager;
 
 use crate::Session;
 use crate::handler::{
-    BoxedPromptHandler, BoxedResourceHandler, BoxedToolHandler, PromptHandler, ResourceHandler,
-    ToolHandler,
+    BoxedFallbackToolHandler, BoxedPromptHandler, BoxedResourceHandler, BoxedToolHandler,
+    FallbackToolHandler, PromptHandler, ResourceHandler, ToolHandler,
 };
 
 /// Type alias for a notification sender callback.
@@ -40,6 +52,8 @@ pub struct TagFilters<'a> {
     pub include: Option<&'a [String]>,
     /// Exclude components with ANY of these tags (OR logic).
     pub exclude: Option<&'a [String]>,
+    /// Whether a component needs all include tags or just one.
+    pub include_match: TagMatch,
 }
 
 impl<'a> TagFilters<'a> {
@@ -48,12 +62,21 @@ impl<'a> TagFilters<'a> {
         Self {
             include: include.map(|v| v.as_slice()),
             exclude: exclude.map(|v| v.as_slice()),
+            include_match: TagMatch::All,
         }
     }
 
+    /// Sets how the include filter matches (default [`TagMatch::All`]).
+    #[must_use]
+    pub fn with_include_match(mut self, include_match: TagMatch) -> Self {
+        self.include_match = include_match;
+        self
+    }
+
     /// Returns true if the given component tags pass the filter.
     ///
-    /// - Include filter: component must have ALL include tags (AND logic)
+    /// - Include filter: component must have ALL include tags (AND logic), or
+    ///   ANY of them with [`TagMatch::Any`]
     /// - Exclude filter: component is rejected if it has ANY exclude tag (OR logic)
     /// - Tag matching is case-insensitive
     pub fn matches(&self, component_tags: &[String]) -> bool {
@@ -65,11 +88,13 @@ impl<'a> TagFilters<'a> {
         if let Some(include) = self.include {
             // Empty include array means no filter (all pass)
             if !include.is_empty() {
-                for tag in include {
-                    let tag_lower = tag.to_lowercase();
-                    if !component_tags_lower.contains(&tag_lower) {
-                        return false;
-                    }
+                let has = |tag: &String| component_tags_lower.contains(&tag.to_lowercase());
+                let included = match self.include_match {
+                    TagMatch::All => include.iter().all(has),
+                    TagMatch::Any => include.iter().any(has),
+                };
+                if !included {
+                    return false;
                 }
             }
         }
@@ -88,17 +113,531 @@ impl<'a> TagFilters<'a> {
     }
 }
 
+/// Returns a tool's listed definition, filling in annotations from
+/// [`ToolHandler::annotations`] when `definition()` does not set them.
+fn tool_definition(handler: &dyn ToolHandler) -> Tool {
+    let mut def = handler.definition();
+    if def.annotations.is_none() {
+        def.annotations = handler.annotations().cloned();
+    }
+    def
+}
+
+/// The handler a `tools/call` request resolves to.
+#[derive(Clone, Copy)]
+enum ToolTarget<'a> {
+    /// A tool registered under the requested name.
+    Registered(&'a dyn ToolHandler),
+    /// The catch-all handler, for names with no registered tool.
+    Fallback(&'a dyn FallbackToolHandler),
+}
+
+/// Attaches the JSON-RPC method and original request ID to a handler context.
+fn with_request_info(ctx: McpContext, method: &str, original_id: Option<&RequestId>) -> McpContext {
+    let ctx = ctx.with_method(method);
+    match original_id {
+        Some(id) => ctx.with_request_id_display(id.to_string()),
+        None => ctx,
+    }
+}
+
+/// Collects streamed tool content.
+///
+/// For clients that did not opt into streaming, the buffered chunks are
+/// prepended to the tool's final result, so they still receive the full
+/// output in one response. For streaming clients the chunks are forwarded
+/// as they arrive, and recorded as well when the result will be cached, so
+/// a later cache hit returns the complete output.
+#[derive(Default)]
+struct BufferedContentStream {
+    content: Mutex<Vec<Content>>,
+    forward: Option<Arc<dyn ContentStreamSender>>,
+    record: bool,
+}
+
+impl BufferedContentStream {
+    /// Buffers every chunk for the final result.
+    fn buffering() -> Self {
+        Self {
+            record: true,
+            ..Self::default()
+        }
+    }
+
+    /// Forwards every chunk to `forward`, keeping a copy only if `record`.
+    fn forwarding(forward: Arc<dyn ContentStreamSender>, record: bool) -> Self {
+        Self {
+            content: Mutex::default(),
+            forward: Some(forward),
+            record,
+        }
+    }
+
+    /// Returns true if chunks already reached the client as they arrived.
+    fn forwards(&self) -> bool {
+        self.forward.is_some()
+    }
+
+    fn take(&self) -> Vec<Content> {
+        std::mem::take(
+            &mut *self
+                .content
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner),
+        )
+    }
+}
+
+impl ContentStreamSender for BufferedContentStream {
+    fn send_content(&self, content: Vec<ToolContentItem>) {
+        if self.record {
+            self.content
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .extend(content.iter().cloned().map(content_from_item));
+        }
+        if let Some(forward) = &self.forward {
+            forward.send_content(content);
+        }
+    }
+}
+
+/// Runs a handler future to completion, catching panics.
+///
+/// A panic inside a handler is converted into [`Outcome::Panicked`] instead of
+/// unwinding through the router, so one faulty handler cannot take down the
+/// server loop or every other session.
+fn run_handler<T>(fut: impl Future<Output = McpOutcome<T>>) -> McpOutcome<T> {
+    match catch_unwind(AssertUnwindSafe(|| block_on(fut))) {
+        Ok(outcome) => outcome,
+        Err(payload) => {
+            let message = if let Some(s) = payload.downcast_ref::<&str>() {
+                (*s).to_string()
+            } else if let Some(s) = payload.downcast_ref::<String>() {
+                s.clone()
+            } else {
+                "unknown panic".to_string()
+            };
+            Outcome::Panicked(PanicPayload::new(message))
+        }
+    }
+}
+
+/// Error for a tool call cancelled by its own [`ToolHandler::timeout`].
+fn tool_timeout_error(tool: &str, timeout: Duration) -> McpError {
+    McpError::new(
+        McpErrorCode::RequestCancelled,
+        format!(
+            "Tool timeout: '{tool}' exceeded its {}ms limit",
+            timeout.as_millis()
+        ),
+    )
+}
+
+/// Converts a handler outcome into a result, hiding panic details from the client.
+///
+/// The real panic message is logged at error level; the client only sees a
+/// generic internal error.
+fn handler_result<T>(outcome: McpOutcome<T>, kind: &str, name: &str) -> McpResult<T> {
+    match outcome {
+        Outcome::Panicked(payload) => {
+            error!(
+                target: targets::HANDLER,
+                "{} '{}' panicked: {}",
+                kind,
+                name,
+                payload.message()
+            );
+            Err(McpError::internal_error(format!(
+                "Internal error in {kind} '{name}'"
+            )))
+        }
+        other => other.into_mcp_result(),
+    }
+}
+
+/// Builds the `invalid_params` error for tool arguments that fail schema validation.
+///
+/// The message lists every failure for humans; `data.errors` carries the same
+/// failures as `{ path, keyword, message }` objects, where `path` is a JSON
+/// pointer into the arguments, so clients can map them onto form fields.
+fn input_validation_error(errors: &[ValidationError]) -> McpError {
+    let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+    let details: Vec<serde_json::Value> = errors
+        .iter()
+        .map(|e| {
+            serde_json::json!({
+                "path": e.pointer,
+                "keyword": e.keyword,
+                "message": e.message,
+            })
+        })
+        .collect();
+    McpError::with_data(
+        McpErrorCode::InvalidParams,
+        format!("Input validation failed: {}", messages.join("; ")),
+        serde_json::json!({ "errors": details }),
+    )
+}
+
+/// Sets `_meta.cached` on a result served from the tool cache, keeping any
+/// metadata the handler set when the result was first produced.
+fn mark_cached(result: &mut CallToolResult) {
+    insert_meta(result, "cached", serde_json::Value::Bool(true));
+}
+
+/// Sets `_meta.<key>` on a result, keeping the rest of its metadata.
+fn insert_meta(result: &mut CallToolResult, key: &str, value: serde_json::Value) {
+    match result.meta {
+        Some(serde_json::Value::Object(ref mut meta)) => {
+            meta.insert(key.to_string(), value);
+        }
+        _ => result.meta = Some(serde_json::json!({ key: value })),
+    }
+}
+
+/// Renders a structured tool result in the representation the client asked for.
+///
+/// A result is structured when its only content item is text holding a JSON
+/// object or array. [`ResultFormat::Text`] re-renders that text as pretty
+/// JSON; [`ResultFormat::Json`] also sets `structuredContent`. Error results,
+/// unstructured results, and requests without a format are left untouched.
+fn apply_result_format(result: &mut CallToolResult, format: Option<ResultFormat>) {
+    let Some(format) = format else {
+        return;
+    };
+    if result.is_error {
+        return;
+    }
+    let [Content::Text { text }] = result.content.as_mut_slice() else {
+        return;
+    };
+    let value = match serde_json::from_str::<serde_json::Value>(text) {
+        Ok(value @ (serde_json::Value::Object(_) | serde_json::Value::Array(_))) => value,
+        _ => return,
+    };
+    match format {
+        ResultFormat::Text => {
+            if let Ok(pretty) = serde_json::to_string_pretty(&value) {
+                *text = pretty;
+            }
+        }
+        ResultFormat::Json => result.structured_content = Some(value),
+    }
+}
+
+/// Default cap on the number of content items in a `tools/call` result.
+pub const DEFAULT_MAX_CONTENT_ITEMS: usize = 10_000;
+
+/// Default cap on the serialized size of a `tools/call` result's content.
+pub const DEFAULT_MAX_RESULT_BYTES: usize = 16 * 1024 * 1024;
+
+/// Text of the content item appended to a tool result cut short by the caps.
+pub const TRUNCATION_MARKER: &str = "\u{2026} truncated \u{2026}";
+
+/// Returns the length of `value` serialized as JSON.
+fn serialized_len<T: serde::Serialize + ?Sized>(value: &T) -> usize {
+    serde_json::to_vec(value).map_or(0, |bytes| bytes.len())
+}
+
+/// Returns the serialized size of content holding only the
+/// [`TRUNCATION_MARKER`] item, the smallest byte cap a truncated result can
+/// stay within.
+pub(crate) fn min_result_bytes() -> usize {
+    serialized_len(&[Content::Text {
+        text: TRUNCATION_MARKER.to_string(),
+    }])
+}
+
+/// Returns the largest character boundary in `text` at or before `index`.
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let index = index.min(text.len());
+    (0..=index)
+        .rev()
+        .find(|&i| text.is_char_boundary(i))
+        .unwrap_or(0)
+}
+
+/// Shortens `text` at a character boundary so that it serializes as a text
+/// content item within `budget` bytes. Returns `false` if nothing fits.
+fn cut_text_to_fit(text: &mut String, budget: usize) -> bool {
+    // `{"type":"text","text":""}` minus the quotes counted with the text
+    let overhead = serialized_len(&Content::Text {
+        text: String::new(),
+    }) - 2;
+    let mut end = floor_char_boundary(text, budget.saturating_sub(overhead + 2));
+    while end > 0 {
+        let size = overhead + serialized_len(&text[..end]);
+        if size <= budget {
+            text.truncate(end);
+            return true;
+        }
+        end = floor_char_boundary(text, end - (size - budget).min(end));
+    }
+    false
+}
+
+/// Caps a tool result at `max_items` content items and `max_bytes` of
+/// serialized content (the JSON array of items).
+///
+/// Within the caps the result is left alone. Otherwise content past the
+/// caps is dropped, a text item straddling the byte cap is cut short, and a
+/// [`TRUNCATION_MARKER`] item is appended; the capped result, marker
+/// included, stays within both caps. A note of which cap was hit and how
+/// many items were dropped goes in `_meta.truncation`, leaving
+/// `structuredContent` as the tool produced it. Returns that note if the
+/// result was capped.
+fn truncate_tool_result(
+    result: &mut CallToolResult,
+    max_items: usize,
+    max_bytes: usize,
+) -> Option<serde_json::Value> {
+    // Each item costs its own length plus a separator; the array adds one
+    // more byte for the brackets
+    let costs: Vec<usize> = result
+        .content
+        .iter()
+        .map(|item| serialized_len(item) + 1)
+        .collect();
+    let too_many = result.content.len() > max_items;
+    if !too_many && 1 + costs.iter().sum::<usize>() <= max_bytes {
+        return None;
+    }
+
+    let marker = Content::Text {
+        text: TRUNCATION_MARKER.to_string(),
+    };
+    // Leave room for the marker
+    let max_items = max_items.saturating_sub(1);
+    let mut budget = max_bytes.saturating_sub(min_result_bytes());
+    let mut kept = 0;
+    let mut reason = if too_many {
+        "maxContentItems"
+    } else {
+        "maxResultBytes"
+    };
+    for (item, &cost) in result.content.iter_mut().zip(&costs) {
+        if kept == max_items {
+            break;
+        }
+        if cost > budget {
+            reason = "maxResultBytes";
+            if let Content::Text { text } = item {
+                if cut_text_to_fit(text, budget.saturating_sub(1)) {
+                    kept += 1;
+                }
+            }
+            break;
+        }
+        budget -= cost;
+        kept += 1;
+    }
+
+    let note = serde_json::json!({
+        "truncated": true,
+        "reason": reason,
+        "omittedItems": result.content.len() - kept,
+    });
+    result.content.truncate(kept);
+    result.content.push(marker);
+    insert_meta(result, "truncation", note.clone());
+    Some(note)
+}
+
+/// Returns whether a conditional `resources/read` can skip sending `contents`.
+///
+/// `if_none_match` takes precedence over `if_modified_since`, as in HTTP.
+/// Every content item must carry the metadata being compared; anything
+/// without it is always sent.
+fn is_not_modified(params: &ReadResourceParams, contents: &[ResourceContent]) -> bool {
+    if contents.is_empty() {
+        return false;
+    }
+
+    if let Some(if_none_match) = params.if_none_match.as_deref() {
+        let known: Vec<&str> = if_none_match.split(',').map(str::trim).collect();
+        return contents.iter().all(|content| {
+            content
+                .etag
+                .as_deref()
+                .is_some_and(|etag| known.iter().any(|k| *k == "*" || *k == etag))
+        });
+    }
+
+    let Some(since) = params
+        .if_modified_since
+        .as_deref()
+        .and_then(|since| chrono::DateTime::parse_from_rfc3339(since).ok())
+    else {
+        return false;
+    };
+    contents.iter().all(|content| {
+        content
+            .last_modified
+            .as_deref()
+            .and_then(|modified| chrono::DateTime::parse_from_rfc3339(modified).ok())
+            .is_some_and(|modified| modified <= since)
+    })
+}
+
+/// Size limit for a resource embedded into a prompt when the router has no
+/// [`max_resource_bytes`](Router::max_resource_bytes) limit.
+const MAX_EMBEDDED_RESOURCE_BYTES: usize = 1024 * 1024;
+
+/// Returns the approximate payload size of resource contents in bytes.
+///
+/// Blobs are counted by their decoded size.
+fn resource_contents_size(contents: &[ResourceContent]) -> usize {
+    contents
+        .iter()
+        .map(|c| {
+            c.text.as_ref().map_or(0, String::len) + c.blob.as_ref().map_or(0, |b| b.len() / 4 * 3)
+        })
+        .sum()
+}
+
+/// Builds the error returned when a read exceeds the maximum resource size.
+fn oversized_resource_error(uri: &str, size: u64, max: usize) -> McpError {
+    McpError::invalid_request(format!(
+        "Resource '{uri}' is {size} bytes, exceeding the {max}-byte limit; request a range instead"
+    ))
+}
+
+/// Enforces the resource content contract on a handler's output.
+///
+/// Each item must set exactly one of `text` or `blob`, and a blob must be
+/// padded standard base64. Whitespace in blobs (as left by line-wrapping
+/// encoders) is stripped. Violations are logged and fail the read.
+fn normalize_resource_contents(contents: &mut [ResourceContent]) -> McpResult<()> {
+    for content in contents {
+        let problem = match (&content.text, &mut content.blob) {
+            (Some(_), Some(_)) => Some("sets both text and blob"),
+            (None, None) => Some("sets neither text nor blob"),
+            (None, Some(blob)) => {
+                blob.retain(|c| !c.is_ascii_whitespace());
+                (!is_base64(blob)).then_some("has a blob that is not valid base64")
+            }
+            (Some(_), None) => None,
+        };
+        if let Some(problem) = problem {
+            error!(
+                target: targets::HANDLER,
+                "Resource content for '{}' {}",
+                content.uri,
+                problem
+            );
+            return Err(McpError::internal_error(format!(
+                "Resource content for '{}' {problem}",
+                content.uri
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Returns whether `s` is padded standard base64.
+fn is_base64(s: &str) -> bool {
+    let data = s.trim_end_matches('=');
+    s.len() % 4 == 0
+        && s.len() - data.len() <= 2
+        && data
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/')
+}
+
+/// Returns one page of a sorted listing and the cursor for the next page.
+///
+/// Cursors encode the page offset plus a fingerprint of every key in the
+/// listing. If the listing has changed when the cursor comes back (e.g. a
+/// component was disabled for the session), the fingerprint no longer
+/// matches and the request fails with [`McpError::cursor_invalidated`]
+/// rather than silently skipping or repeating entries.
+fn paginate<T>(
+    items: Vec<T>,
+    key: impl Fn(&T) -> &str,
+    cursor: Option<&str>,
+    page_size: Option<usize>,
+) -> McpResult<(Vec<T>, Option<String>)> {
+    let Some(page_size) = page_size else {
+        return Ok((items, None));
+    };
+
+    let fingerprint = {
+        let mut hasher = std::hash::DefaultHasher::new();
+        for item in &items {
+            key(item).hash(&mut hasher);
+        }
+        hasher.finish()
+    };
+
+    let offset = match cursor {
+        None => 0,
+        Some(cursor) => {
+            let (offset, cursor_fingerprint) = cursor
+                .split_once('.')
+                .and_then(|(offset, fingerprint)| {
+                    Some((
+                        offset.parse::<usize>().ok()?,
+                        u64::from_str_radix(fingerprint, 16).ok()?,
+                    ))
+                })
+                .ok_or_else(|| McpError::invalid_params(format!("Invalid cursor: {cursor}")))?;
+            if cursor_fingerprint != fingerprint || offset > items.len() {
+                return Err(McpError::cursor_invalidated());
+            }
+            offset
+        }
+    };
+
+    let end = offset.saturating_add(page_size.max(1)).min(items.len());
+    let next_cursor = (end < items.len()).then(|| format!("{end}.{fingerprint:016x}"));
+    let page = items.into_iter().skip(offset).take(end - offset).collect();
+    Ok((page, next_cursor))
+}
+
 /// Routes MCP requests to the appropriate handlers.
+///
+/// Listing methods return components in a stable order: tools and prompts
+/// sorted by name, resources by URI, and resource templates by URI template.
+/// `*/list` responses are therefore identical across runs regardless of
+/// registration order. Handlers are stored in ordered maps, so mounting
+/// visits them, logs them and reports conflicts in the same order every time.
+///
+/// When a page size is set (see [`set_list_page_size`](Self::set_list_page_size)),
+/// `tools/list`, `resources/list` and `prompts/list` are paginated. A client
+/// that follows `nextCursor` either sees every entry of one consistent
+/// listing exactly once, or gets a `cursor_invalidated` error and must
+/// restart from the first page.
 pub struct Router {
-    tools: HashMap<String, BoxedToolHandler>,
-    resources: HashMap<String, BoxedResourceHandler>,
-    prompts: HashMap<String, BoxedPromptHandler>,
-    resource_templates: HashMap<String, ResourceTemplateEntry>,
+    tools: BTreeMap<String, BoxedToolHandler>,
+    /// Handler for `tools/call` requests naming no registered tool.
+    fallback_tool: Option<BoxedFallbackToolHandler>,
+    resources: BTreeMap<String, BoxedResourceHandler>,
+    prompts: BTreeMap<String, BoxedPromptHandler>,
+    resource_templates: BTreeMap<String, ResourceTemplateEntry>,
     /// Pre-sorted template keys by specificity (most specific first).
     /// Updated whenever templates are added/modified.
     sorted_template_keys: Vec<String>,
+    /// Registration counter used to break specificity ties between templates.
+    next_template_registration: u64,
     /// Whether to enforce strict input validation (reject extra properties).
     strict_input_validation: bool,
+    /// Whether to coerce scalar argument mismatches before validation.
+    coerce_arguments: bool,
+    /// Maximum size in bytes of a single `resources/read` response (None = unlimited).
+    max_resource_bytes: Option<usize>,
+    /// Entries per `*/list` page (None = no pagination).
+    list_page_size: Option<usize>,
+    /// Maximum number of content items in a `tools/call` result.
+    max_content_items: usize,
+    /// Maximum serialized size in bytes of a `tools/call` result's content.
+    max_result_bytes: usize,
+    /// Cached `tools/call` results for tools opted in via `set_tool_cache_ttl`.
+    tool_cache: ToolResultCache,
+    /// Server statistics, used to record tool cache hits.
+    stats: Option<ServerStats>,
+    /// What handler context helpers do when the client lacks a capability.
+    missing_capability_policy: MissingCapabilityPolicy,
 }
 
 impl Router {
@@ -106,12 +645,22 @@ impl Router {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            tools: HashMap::new(),
-            resources: HashMap::new(),
-            prompts: HashMap::new(),
-            resource_templates: HashMap::new(),
+            tools: BTreeMap::new(),
+            fallback_tool: None,
+            resources: BTreeMap::new(),
+            prompts: BTreeMap::new(),
+            resource_templates: BTreeMap::new(),
             sorted_template_keys: Vec::new(),
+            next_template_registration: 0,
             strict_input_validation: false,
+            coerce_arguments: false,
+            max_resource_bytes: None,
+            max_content_items: DEFAULT_MAX_CONTENT_ITEMS,
+            max_result_bytes: DEFAULT_MAX_RESULT_BYTES,
+            list_page_size: None,
+            tool_cache: ToolResultCache::default(),
+            stats: None,
+            missing_capability_policy: MissingCapabilityPolicy::Error,
         }
     }
 
@@ -132,23 +681,145 @@ impl Router {
         self.strict_input_validation
     }
 
+    /// Sets whether to coerce tool arguments toward their input schema.
+    ///
+    /// When enabled, numeric strings, `"true"`/`"false"` and single values
+    /// passed where an array is expected are converted before validation.
+    /// See [`coerce_to_schema`] for the exact rules.
+    pub fn set_coerce_arguments(&mut self, coerce: bool) {
+        self.coerce_arguments = coerce;
+    }
+
+    /// Returns whether tool argument coercion is enabled.
+    #[must_use]
+    pub fn coerce_arguments(&self) -> bool {
+        self.coerce_arguments
+    }
+
+    /// Sets the maximum size in bytes of a `resources/read` response.
+    ///
+    /// Reads whose content exceeds the limit fail with an error instead of
+    /// producing an oversized frame; clients can request a `range` instead.
+    pub fn set_max_resource_bytes(&mut self, max: Option<usize>) {
+        self.max_resource_bytes = max;
+    }
+
+    /// Returns the maximum `resources/read` response size, if any.
+    #[must_use]
+    pub fn max_resource_bytes(&self) -> Option<usize> {
+        self.max_resource_bytes
+    }
+
+    /// Sets the maximum number of content items in a `tools/call` result.
+    ///
+    /// Longer results are truncated, ending with a [`TRUNCATION_MARKER`]
+    /// item. Defaults to [`DEFAULT_MAX_CONTENT_ITEMS`]; 0 is raised to 1 to
+    /// leave room for the marker.
+    pub fn set_max_content_items(&mut self, max: usize) {
+        self.max_content_items = max.max(1);
+    }
+
+    /// Returns the maximum number of content items in a `tools/call` result.
+    #[must_use]
+    pub fn max_content_items(&self) -> usize {
+        self.max_content_items
+    }
+
+    /// Sets the maximum serialized size in bytes of a `tools/call` result's
+    /// content.
+    ///
+    /// Larger results are truncated, ending with a [`TRUNCATION_MARKER`]
+    /// item. Defaults to [`DEFAULT_MAX_RESULT_BYTES`]; values too small to
+    /// hold the marker alone are raised to its size.
+    pub fn set_max_result_bytes(&mut self, max: usize) {
+        self.max_result_bytes = max.max(min_result_bytes());
+    }
+
+    /// Returns the maximum serialized size of a `tools/call` result's content.
+    #[must_use]
+    pub fn max_result_bytes(&self) -> usize {
+        self.max_result_bytes
+    }
+
+    /// Caps a `tools/call` result at the configured item count and size.
+    fn cap_tool_result(&self, tool: &str, result: &mut CallToolResult) {
+        if let Some(note) =
+            truncate_tool_result(result, self.max_content_items, self.max_result_bytes)
+        {
+            fastmcp_core::logging::warn!(
+                target: targets::HANDLER,
+                "Truncated result of tool '{}': {}", tool, note
+            );
+        }
+    }
+
+    /// Sets the number of entries per `tools/list`, `resources/list` and
+    /// `prompts/list` page.
+    ///
+    /// With `None` (the default) listings are returned in full and cursors
+    /// are ignored.
+    pub fn set_list_page_size(&mut self, page_size: Option<usize>) {
+        self.list_page_size = page_size;
+    }
+
+    /// Returns the listing page size, if pagination is enabled.
+    #[must_use]
+    pub fn list_page_size(&self) -> Option<usize> {
+        self.list_page_size
+    }
+
+    /// Sets what handler context helpers (`ctx.log`, `ctx.elicit`, ...) do
+    /// when the client lacks the capability they need.
+    pub fn set_missing_capability_policy(&mut self, policy: MissingCapabilityPolicy) {
+        self.missing_capability_policy = policy;
+    }
+
+    /// Returns the policy for context helpers whose capability is missing.
+    #[must_use]
+    pub fn missing_capability_policy(&self) -> MissingCapabilityPolicy {
+        self.missing_capability_policy
+    }
+
+    /// Caches successful results of the named tool for `ttl`.
+    ///
+    /// Calls with the same arguments (compared with object keys sorted)
+    /// within the TTL are answered from the cache without running the
+    /// handler. Error results are never cached.
+    pub fn set_tool_cache_ttl(&mut self, tool: impl Into<String>, ttl: Duration) {
+        self.tool_cache.set_ttl(tool, ttl);
+    }
+
+    /// Sets the statistics collector used to record cache hits.
+    pub(crate) fn set_stats(&mut self, stats: Option<ServerStats>) {
+        self.stats = stats;
+    }
+
     /// Rebuilds the sorted template keys vector.
     /// Called after any modification to resource_templates.
+    ///
+    /// Templates are ordered by specificity (see [`UriTemplate::specificity`]),
+    /// most specific first; ties go to the template registered first.
     fn rebuild_sorted_template_keys(&mut self) {
         self.sorted_template_keys = self.resource_templates.keys().cloned().collect();
         self.sorted_template_keys.sort_by(|a, b| {
             let entry_a = &self.resource_templates[a];
             let entry_b = &self.resource_templates[b];
-            let (a_literals, a_literal_segments, a_segments) = entry_a.matcher.specificity();
-            let (b_literals, b_literal_segments, b_segments) = entry_b.matcher.specificity();
-            b_literals
-                .cmp(&a_literals)
-                .then(b_literal_segments.cmp(&a_literal_segments))
-                .then(b_segments.cmp(&a_segments))
+            entry_b
+                .matcher
+                .specificity()
+                .cmp(&entry_a.matcher.specificity())
+                .then(entry_a.registration.cmp(&entry_b.registration))
                 .then_with(|| a.cmp(b))
         });
     }
 
+    /// Returns the next template registration sequence number.
+    fn next_registration(&mut self) -> u64 {
+        let registration = self.next_template_registration;
+        self.next_template_registration += 1;
+        registration
+    }
+
     /// Adds a tool handler.
     ///
     /// If a tool with the same name already exists, it will be replaced.
@@ -197,6 +868,33 @@ impl Router {
         Ok(())
     }
 
+    /// Sets the handler for `tools/call` requests naming no registered tool,
+    /// replacing any previous one.
+    pub fn set_fallback_tool<H: FallbackToolHandler + 'static>(&mut self, handler: H) {
+        self.fallback_tool = Some(Box::new(handler));
+    }
+
+    /// Returns whether a fallback tool handler is set.
+    #[must_use]
+    pub fn has_fallback_tool(&self) -> bool {
+        self.fallback_tool.is_some()
+    }
+
+    /// Returns the tools enumerated by the fallback handler, minus any
+    /// shadowed by a registered tool.
+    fn fallback_tools(&self) -> Vec<Tool> {
+        self.fallback_tool
+            .as_ref()
+            .map(|fallback| {
+                fallback
+                    .list_tools()
+                    .into_iter()
+                    .filter(|tool| !self.tools.contains_key(&tool.name))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// Adds a resource handler.
     ///
     /// If a resource with the same URI already exists, it will be replaced.
@@ -212,6 +910,7 @@ impl Router {
                 matcher: UriTemplate::new(&template.uri_template),
                 template: template.clone(),
                 handler: Some(boxed),
+                registration: self.next_registration(),
             };
             self.resource_templates
                 .insert(template.uri_template.clone(), entry);
@@ -276,6 +975,7 @@ impl Router {
                 matcher: UriTemplate::new(&template.uri_template),
                 template: template.clone(),
                 handler: Some(boxed),
+                registration: self.next_registration(),
             };
             self.resource_templates
                 .insert(template.uri_template.clone(), entry);
@@ -294,6 +994,7 @@ impl Router {
             matcher,
             template: template.clone(),
             handler: None,
+            registration: self.next_registration(),
         };
         let needs_rebuild = match self.resource_templates.get_mut(&template.uri_template) {
             Some(existing) => {
@@ -361,13 +1062,20 @@ impl Router {
         Ok(())
     }
 
-    /// Returns all tool definitions.
+    /// Returns all tool definitions, sorted by name.
     #[must_use]
     pub fn tools(&self) -> Vec<Tool> {
-        self.tools.values().map(|h| h.definition()).collect()
+        let mut tools: Vec<Tool> = self
+            .tools
+            .values()
+            .map(|h| tool_definition(h.as_ref()))
+            .chain(self.fallback_tools())
+            .collect();
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
+        tools
     }
 
-    /// Returns tool definitions filtered by session state and tags.
+    /// Returns tool definitions filtered by session state and tags, sorted by name.
     ///
     /// Tools that have been disabled in the session state will not be included.
     /// If tag filters are provided, tools must match the include/exclude criteria.
@@ -377,45 +1085,59 @@ impl Router {
         session_state: Option<&SessionState>,
         tag_filters: Option<&TagFilters<'_>>,
     ) -> Vec<Tool> {
-        self.tools
-            .values()
-            .filter(|h| {
-                let def = h.definition();
-                // Check session state filter
-                if let Some(state) = session_state {
-                    if !state.is_tool_enabled(&def.name) {
-                        return false;
-                    }
+        let is_listed = |def: &Tool| {
+            // Check session state filter
+            if let Some(state) = session_state {
+                if !state.is_tool_enabled(&def.name) {
+                    return false;
                 }
-                // Check tag filters
-                if let Some(filters) = tag_filters {
-                    if !filters.matches(&def.tags) {
-                        return false;
-                    }
+            }
+            // Check tag filters
+            if let Some(filters) = tag_filters {
+                if !filters.matches(&def.tags) {
+                    return false;
                 }
-                true
-            })
-            .map(|h| h.definition())
-            .collect()
+            }
+            true
+        };
+        let mut tools: Vec<Tool> = self
+            .tools
+            .values()
+            .filter(|h| is_listed(&h.definition()))
+            .map(|h| tool_definition(h.as_ref()))
+            .chain(
+                self.fallback_tools()
+                    .into_iter()
+                    .filter(|tool| is_listed(tool)),
+            )
+            .collect();
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
+        tools
     }
 
-    /// Returns all resource definitions.
+    /// Returns all resource definitions, sorted by URI.
     #[must_use]
     pub fn resources(&self) -> Vec<Resource> {
-        self.resources.values().map(|h| h.definition()).collect()
+        let mut resources: Vec<Resource> =
+            self.resources.values().map(|h| h.definition()).collect();
+        resources.sort_by(|a, b| a.uri.cmp(&b.uri));
+        resources
     }
 
-    /// Returns resource definitions filtered by session state and tags.
+    /// Returns resource definitions filtered by session state and tags, sorted by URI.
     ///
     /// Resources that have been disabled in the session state will not be included.
     /// If tag filters are provided, resources must match the include/exclude criteria.
+    /// Templates whose handler implements [`ResourceHandler::expand`] contribute
+    /// their concrete instances.
     #[must_use]
     pub fn resources_filtered(
         &self,
         session_state: Option<&SessionState>,
         tag_filters: Option<&TagFilters<'_>>,
     ) -> Vec<Resource> {
-        self.resources
+        let mut resources: Vec<Resource> = self
+            .resources
             .values()
             .filter(|h| {
                 let def = h.definition();
@@ -434,22 +1156,49 @@ impl Router {
                 true
             })
             .map(|h| h.definition())
-            .collect()
+            .collect();
+
+        // Concrete instances of expandable templates, unless a static
+        // resource with the same URI is registered.
+        let expanded: Vec<Resource> = self
+            .resource_templates
+            .values()
+            .filter_map(|entry| entry.handler.as_ref()?.expand())
+            .flatten()
+            .filter(|resource| {
+                if let Some(state) = session_state {
+                    if !state.is_resource_enabled(&resource.uri) {
+                        return false;
+                    }
+                }
+                if let Some(filters) = tag_filters {
+                    if !filters.matches(&resource.tags) {
+                        return false;
+                    }
+                }
+                !self.resources.contains_key(&resource.uri)
+            })
+            .collect();
+        resources.extend(expanded);
+
+        resources.sort_by(|a, b| a.uri.cmp(&b.uri));
+        resources.dedup_by(|a, b| a.uri == b.uri);
+        resources
     }
 
-    /// Returns all resource templates.
+    /// Returns all resource templates, sorted by URI template.
     #[must_use]
     pub fn resource_templates(&self) -> Vec<ResourceTemplate> {
         let mut templates: Vec<ResourceTemplate> = self
             .resource_templates
             .values()
-            .map(|entry| entry.template.clone())
+            .map(ResourceTemplateEntry::published)
             .collect();
         templates.sort_by(|a, b| a.uri_template.cmp(&b.uri_template));
         templates
     }
 
-    /// Returns resource templates filtered by session state and tags.
+    /// Returns resource templates filtered by session state and tags, sorted by URI template.
     ///
     /// Templates that have been disabled in the session state will not be included.
     /// If tag filters are provided, templates must match the include/exclude criteria.
@@ -477,19 +1226,21 @@ impl Router {
                 }
                 true
             })
-            .map(|entry| entry.template.clone())
+            .map(ResourceTemplateEntry::published)
             .collect();
         templates.sort_by(|a, b| a.uri_template.cmp(&b.uri_template));
         templates
     }
 
-    /// Returns all prompt definitions.
+    /// Returns all prompt definitions, sorted by name.
     #[must_use]
     pub fn prompts(&self) -> Vec<Prompt> {
-        self.prompts.values().map(|h| h.definition()).collect()
+        let mut prompts: Vec<Prompt> = self.prompts.values().map(|h| h.definition()).collect();
+        prompts.sort_by(|a, b| a.name.cmp(&b.name));
+        prompts
     }
 
-    /// Returns prompt definitions filtered by session state and tags.
+    /// Returns prompt definitions filtered by session state and tags, sorted by name.
     ///
     /// Prompts that have been disabled in the session state will not be included.
     /// If tag filters are provided, prompts must match the include/exclude criteria.
@@ -499,7 +1250,8 @@ impl Router {
         session_state: Option<&SessionState>,
         tag_filters: Option<&TagFilters<'_>>,
     ) -> Vec<Prompt> {
-        self.prompts
+        let mut prompts: Vec<Prompt> = self
+            .prompts
             .values()
             .filter(|h| {
                 let def = h.definition();
@@ -518,7 +1270,9 @@ impl Router {
                 true
             })
             .map(|h| h.definition())
-            .collect()
+            .collect();
+        prompts.sort_by(|a, b| a.name.cmp(&b.name));
+        prompts
     }
 
     /// Returns the number of registered tools.
@@ -568,29 +1322,89 @@ impl Router {
     /// Returns true if a resource exists for the given URI (static or template match).
     #[must_use]
     pub fn resource_exists(&self, uri: &str) -> bool {
-        self.resolve_resource(uri).is_some()
+        self.resolve_resource(uri).is_ok()
     }
 
-    fn resolve_resource(&self, uri: &str) -> Option<ResolvedResource<'_>> {
+    /// Resolves a URI to a static resource or the first matching template.
+    ///
+    /// Precedence is deterministic:
+    ///
+    /// 1. A static resource registered under the exact URI always wins.
+    /// 2. Otherwise templates are tried from most to least specific: longer
+    ///    literal text first, then more literal segments, then more typed
+    ///    placeholders, then more segments overall.
+    /// 3. Templates with equal specificity are tried in registration order.
+    ///
+    /// Debug builds log a warning when two equally specific templates both
+    /// match the URI, since the outcome then depends on registration order.
+    ///
+    /// Templates whose typed placeholders (e.g. `{id:int}`) reject the
+    /// extracted values are skipped; if no other template matches, the type
+    /// mismatch is reported as `invalid_params` instead of "not found".
+    fn resolve_resource(&self, uri: &str) -> McpResult<ResolvedResource<'_>> {
         if let Some(handler) = self.resources.get(uri) {
-            return Some(ResolvedResource {
+            return Ok(ResolvedResource {
                 handler,
                 params: UriParams::new(),
             });
         }
 
+        let mut type_mismatch = None;
+
         // Use pre-sorted template keys to avoid sorting on every lookup
-        for key in &self.sorted_template_keys {
+        for (index, key) in self.sorted_template_keys.iter().enumerate() {
             let entry = &self.resource_templates[key];
             let Some(handler) = entry.handler.as_ref() else {
                 continue;
             };
             if let Some(params) = entry.matcher.matches(uri) {
-                return Some(ResolvedResource { handler, params });
+                match entry.matcher.check_param_types(&params) {
+                    Ok(()) => {
+                        if cfg!(debug_assertions) {
+                            self.warn_if_ambiguous(uri, index);
+                        }
+                        return Ok(ResolvedResource { handler, params });
+                    }
+                    Err(message) => {
+                        type_mismatch.get_or_insert(message);
+                    }
+                }
             }
         }
 
-        None
+        match type_mismatch {
+            Some(message) => Err(McpError::invalid_params(format!(
+                "Invalid resource URI '{uri}': {message}"
+            ))),
+            None => Err(McpError::resource_not_found(uri)),
+        }
+    }
+
+    /// Warns if a template after `winner` is equally specific and also matches `uri`.
+    fn warn_if_ambiguous(&self, uri: &str, winner: usize) {
+        let winner_key = &self.sorted_template_keys[winner];
+        let specificity = self.resource_templates[winner_key].matcher.specificity();
+        for key in &self.sorted_template_keys[winner + 1..] {
+            let entry = &self.resource_templates[key];
+            if entry.matcher.specificity() != specificity {
+                break;
+            }
+            let matches = entry.handler.is_some()
+                && entry
+                    .matcher
+                    .matches(uri)
+                    .is_some_and(|params| entry.matcher.check_param_types(&params).is_ok());
+            if matches {
+                fastmcp_core::logging::warn!(
+                    target: targets::HANDLER,
+                    "Resource URI '{}' is ambiguous between templates '{}' and '{}'; using '{}' (registered first)",
+                    uri,
+                    winner_key,
+                    key,
+                    winner_key
+                );
+            }
+        }
     }
 
     /// Gets a prompt handler by name.
@@ -635,7 +1449,8 @@ impl Router {
     /// Handles the tools/list request.
     ///
     /// If session_state is provided, disabled tools will be filtered out.
-    /// If include_tags/exclude_tags are provided, tools are filtered by tags.
+    /// If include_tags/exclude_tags are provided, tools are filtered by tags;
+    /// tag_match chooses whether include_tags must all match or just one.
     pub fn handle_tools_list(
         &self,
         _cx: &Cx,
@@ -643,16 +1458,20 @@ impl Router {
         session_state: Option<&SessionState>,
     ) -> McpResult<ListToolsResult> {
         let tag_filters =
-            TagFilters::new(params.include_tags.as_ref(), params.exclude_tags.as_ref());
+            TagFilters::new(params.include_tags.as_ref(), params.exclude_tags.as_ref())
+                .with_include_match(params.tag_match.unwrap_or_default());
         let tag_filters = if params.include_tags.is_some() || params.exclude_tags.is_some() {
             Some(&tag_filters)
         } else {
             None
         };
-        Ok(ListToolsResult {
-            tools: self.tools_filtered(session_state, tag_filters),
-            next_cursor: None,
-        })
+        let (tools, next_cursor) = paginate(
+            self.tools_filtered(session_state, tag_filters),
+            |tool| tool.name.as_str(),
+            params.cursor.as_deref(),
+            self.list_page_size,
+        )?;
+        Ok(ListToolsResult { tools, next_cursor })
     }
 
     /// Handles the tools/call request.
@@ -661,20 +1480,29 @@ impl Router {
     ///
     /// * `cx` - The asupersync context for cancellation and tracing
     /// * `request_id` - Internal request ID for tracking
+    /// * `original_id` - The JSON-RPC request ID, exposed via [`McpContext::request_id_display`]
     /// * `params` - The tool call parameters including tool name and arguments
     /// * `budget` - Request budget for timeout enforcement
     /// * `session_state` - Session state for per-session storage
     /// * `notification_sender` - Optional callback for sending progress notifications
     /// * `bidirectional_senders` - Optional senders for sampling/elicitation
+    /// * `stream_content` - Whether the client opted in to content streaming
+    ///
+    /// When `stream_content` is true and both `original_id` and `notification_sender`
+    /// are present, content passed to [`McpContext::stream_content`] is sent to the
+    /// client as `notifications/tools/content` before the final result. Otherwise
+    /// streamed content is buffered and prepended to the result.
     pub fn handle_tools_call(
         &self,
         cx: &Cx,
         request_id: u64,
+        original_id: Option<&RequestId>,
         params: CallToolParams,
         budget: &Budget,
         session_state: SessionState,
         notification_sender: Option<&NotificationSender>,
         bidirectional_senders: Option<&BidirectionalSenders>,
+        stream_content: bool,
     ) -> McpResult<CallToolResult> {
         debug!(target: targets::HANDLER, "Calling tool: {}", params.name);
         trace!(target: targets::HANDLER, "Tool arguments: {:?}", params.arguments);
@@ -700,34 +1528,60 @@ impl Router {
             ));
         }
 
-        // Find the tool handler
-        let handler = self
-            .tools
-            .get(&params.name)
-            .ok_or_else(|| McpError::method_not_found(&format!("tool: {}", params.name)))?;
+        // Find the tool handler, falling back to the catch-all handler
+        let target = match (self.tools.get(&params.name), &self.fallback_tool) {
+            (Some(handler), _) => ToolTarget::Registered(handler.as_ref()),
+            (None, Some(fallback)) => ToolTarget::Fallback(fallback.as_ref()),
+            (None, None) => {
+                return Err(McpError::method_not_found(&format!(
+                    "tool: {}",
+                    params.name
+                )));
+            }
+        };
 
-        // Validate arguments against the tool's input schema
+        // Validate arguments against the tool's input schema, if it has one
         // Default to empty object since MCP tool arguments are always objects
-        let arguments = params.arguments.unwrap_or_else(|| serde_json::json!({}));
-        let tool_def = handler.definition();
-
-        // Use strict or lenient validation based on configuration
-        let validation_result = if self.strict_input_validation {
-            validate_strict(&tool_def.input_schema, &arguments)
-        } else {
-            validate(&tool_def.input_schema, &arguments)
+        let mut arguments = params.arguments.unwrap_or_else(|| serde_json::json!({}));
+        let input_schema = match target {
+            ToolTarget::Registered(handler) => Some(handler.definition().input_schema),
+            ToolTarget::Fallback(fallback) => fallback.input_schema(&params.name),
         };
 
-        if let Err(validation_errors) = validation_result {
-            let error_messages: Vec<String> = validation_errors
-                .iter()
-                .map(|e| format!("{}: {}", e.path, e.message))
-                .collect();
-            return Err(McpError::invalid_params(format!(
-                "Input validation failed: {}",
-                error_messages.join("; ")
-            )));
+        if let Some(input_schema) = input_schema {
+            if self.coerce_arguments {
+                arguments = coerce_to_schema(&input_schema, arguments);
+            }
+
+            // Use strict or lenient validation based on configuration
+            let validation_result = if self.strict_input_validation {
+                validate_strict(&input_schema, &arguments)
+            } else {
+                validate(&input_schema, &arguments)
+            };
+
+            if let Err(validation_errors) = validation_result {
+                return Err(input_validation_error(&validation_errors));
+            }
+        }
+
+        let result_format = params.meta.as_ref().and_then(|m| m.result_format);
+
+        if let Some(mut cached) = self.tool_cache.get(&params.name, &arguments) {
+            debug!(target: targets::HANDLER, "Tool cache hit: {}", params.name);
+            if let Some(ref stats) = self.stats {
+                stats.record_tool_cache_hit();
+            }
+            mark_cached(&mut cached);
+            apply_result_format(&mut cached, result_format);
+            self.cap_tool_result(&params.name, &mut cached);
+            return Ok(cached);
         }
+        // Keep the arguments around only if the result may be cached
+        let cache_arguments = self
+            .tool_cache
+            .caches(&params.name)
+            .then(|| arguments.clone());
 
         // Extract progress token from request metadata
         let progress_token: Option<ProgressToken> =
@@ -758,21 +1612,87 @@ impl Router {
                     if let Some(ref elicitation) = senders.elicitation {
                         ctx = ctx.with_elicitation(elicitation.clone());
                     }
+                    if let Some(ref log) = senders.log {
+                        ctx = ctx.with_log_sender(log.clone());
+                    }
                 }
                 ctx
             }
         };
+        let ctx = with_request_info(ctx, "tools/call", original_id)
+            .with_missing_capability_policy(self.missing_capability_policy);
+
+        // Stream content chunks to clients that asked for them; buffer otherwise
+        let content_stream = Arc::new(match (stream_content, original_id, notification_sender) {
+            (true, Some(id), Some(sender)) => {
+                let sender = sender.clone();
+                BufferedContentStream::forwarding(
+                    Arc::new(ToolContentNotificationSender::new(id.clone(), move |req| {
+                        sender(req);
+                    })),
+                    cache_arguments.is_some(),
+                )
+            }
+            _ => BufferedContentStream::buffering(),
+        });
+        let mut ctx = ctx.with_content_stream(content_stream.clone());
+
+        // The handler's own timeout applies when tighter than the time left
+        let handler_timeout = match target {
+            ToolTarget::Registered(handler) => handler.timeout(),
+            ToolTarget::Fallback(_) => None,
+        };
+        if let Some(timeout) = handler_timeout {
+            ctx = ctx.with_timeout(timeout);
+        }
 
         // Call the handler asynchronously - returns McpOutcome (4-valued)
-        let outcome = block_on(handler.call_async(&ctx, arguments));
+        let outcome = match target {
+            ToolTarget::Registered(handler) => run_handler(handler.call_async(&ctx, arguments)),
+            ToolTarget::Fallback(fallback) => {
+                run_handler(fallback.call_async(&ctx, &params.name, arguments))
+            }
+        };
+        let timed_out = ctx.cancel_reason() == Some(CancelKind::Timeout);
+        if let Some(timeout) = handler_timeout.filter(|_| timed_out) {
+            let cancelled = match &outcome {
+                Outcome::Cancelled(_) => true,
+                Outcome::Err(e) => matches!(e.code, McpErrorCode::RequestCancelled),
+                Outcome::Ok(_) | Outcome::Panicked(_) => false,
+            };
+            if cancelled {
+                return Err(tool_timeout_error(&params.name, timeout));
+            }
+        }
         match outcome {
-            Outcome::Ok(content) => Ok(CallToolResult {
-                content,
-                is_error: false,
-            }),
+            Outcome::Ok(content) => {
+                let mut streamed = content_stream.take();
+                let streamed_len = streamed.len();
+                streamed.extend(content);
+                let mut result = CallToolResult {
+                    content: streamed,
+                    is_error: false,
+                    structured_content: None,
+                    meta: ctx.take_result_meta(),
+                };
+                // Cache the handler's full output, streamed chunks included;
+                // the format is per request
+                if let Some(ref cache_arguments) = cache_arguments {
+                    self.tool_cache
+                        .insert(&params.name, cache_arguments, &result);
+                }
+                // Chunks already sent as notifications are not repeated
+                if content_stream.forwards() {
+                    result.content.drain(..streamed_len);
+                }
+                apply_result_format(&mut result, result_format);
+                self.cap_tool_result(&params.name, &mut result);
+                Ok(result)
+            }
             Outcome::Err(e) => {
-                // If the request was cancelled, propagate the error as a JSON-RPC error.
-                if matches!(e.code, McpErrorCode::RequestCancelled) {
+                // Cancellation and application-defined codes are propagated as
+                // JSON-RPC errors so clients can branch on the code.
+                if matches!(e.code, McpErrorCode::RequestCancelled) || e.code.is_application() {
                     return Err(e);
                 }
 
@@ -780,6 +1700,8 @@ impl Router {
                 Ok(CallToolResult {
                     content: vec![Content::Text { text: e.message }],
                     is_error: true,
+                    structured_content: None,
+                    meta: ctx.take_result_meta(),
                 })
             }
             Outcome::Cancelled(_) => {
@@ -787,11 +1709,22 @@ impl Router {
                 Err(McpError::request_cancelled())
             }
             Outcome::Panicked(payload) => {
-                // Panics become internal errors
-                Err(McpError::internal_error(format!(
-                    "Handler panic: {}",
+                // Panics are logged and reported as a generic tool error, so the
+                // server stays up and no internals leak to the client
+                error!(
+                    target: targets::HANDLER,
+                    "tool '{}' panicked: {}",
+                    params.name,
                     payload.message()
-                )))
+                );
+                Ok(CallToolResult {
+                    content: vec![Content::Text {
+                        text: format!("Internal error in tool '{}'", params.name),
+                    }],
+                    is_error: true,
+                    structured_content: None,
+                    meta: None,
+                })
             }
         }
     }
@@ -813,9 +1746,15 @@ impl Router {
         } else {
             None
         };
+        let (resources, next_cursor) = paginate(
+            self.resources_filtered(session_state, tag_filters),
+            |resource| resource.uri.as_str(),
+            params.cursor.as_deref(),
+            self.list_page_size,
+        )?;
         Ok(ListResourcesResult {
-            resources: self.resources_filtered(session_state, tag_filters),
-            next_cursor: None,
+            resources,
+            next_cursor,
         })
     }
 
@@ -847,6 +1786,7 @@ impl Router {
     ///
     /// * `cx` - The asupersync context for cancellation and tracing
     /// * `request_id` - Internal request ID for tracking
+    /// * `original_id` - The JSON-RPC request ID, exposed via [`McpContext::request_id_display`]
     /// * `params` - The resource read parameters including URI
     /// * `budget` - Request budget for timeout enforcement
     /// * `session_state` - Session state for per-session storage
@@ -856,6 +1796,7 @@ impl Router {
         &self,
         cx: &Cx,
         request_id: u64,
+        original_id: Option<&RequestId>,
         params: &ReadResourceParams,
         budget: &Budget,
         session_state: SessionState,
@@ -885,9 +1826,27 @@ impl Router {
             ));
         }
 
-        let resolved = self
-            .resolve_resource(&params.uri)
-            .ok_or_else(|| McpError::resource_not_found(&params.uri))?;
+        let resolved = self.resolve_resource(&params.uri)?;
+
+        // Reject oversized reads up front when the handler knows the size.
+        // Conditional reads skip this: a matching validator yields an empty
+        // response whatever the resource size.
+        if let Some(max) = self.max_resource_bytes {
+            let conditional = params.if_none_match.is_some() || params.if_modified_since.is_some();
+            let known_len = resolved
+                .handler
+                .content_len(&params.uri, &resolved.params)
+                .map(|total| match params.range {
+                    Some(range) => {
+                        let (start, end) = range.resolve(total);
+                        end - start
+                    }
+                    None => total,
+                });
+            if let Some(size) = known_len.filter(|size| !conditional && *size > max as u64) {
+                return Err(oversized_resource_error(&params.uri, size, max));
+            }
+        }
 
         // Extract progress token from request metadata
         let progress_token: Option<ProgressToken> =
@@ -918,22 +1877,48 @@ impl Router {
                     if let Some(ref elicitation) = senders.elicitation {
                         ctx = ctx.with_elicitation(elicitation.clone());
                     }
+                    if let Some(ref log) = senders.log {
+                        ctx = ctx.with_log_sender(log.clone());
+                    }
                 }
                 ctx
             }
         };
+        let ctx = with_request_info(ctx, "resources/read", original_id)
+            .with_missing_capability_policy(self.missing_capability_policy);
 
         // Read the resource asynchronously - returns McpOutcome (4-valued)
-        let outcome = block_on(resolved.handler.read_async_with_uri(
-            &ctx,
-            &params.uri,
-            &resolved.params,
-        ));
+        let outcome = match params.range {
+            Some(range) => run_handler(resolved.handler.read_with_range(
+                &ctx,
+                &params.uri,
+                &resolved.params,
+                range,
+            )),
+            None => run_handler(resolved.handler.read_async_with_uri(
+                &ctx,
+                &params.uri,
+                &resolved.params,
+            )),
+        };
 
         // Convert 4-valued Outcome to McpResult for JSON-RPC response
-        let contents = outcome.into_mcp_result()?;
+        let mut contents = handler_result(outcome, "resource", &params.uri)?;
+        normalize_resource_contents(&mut contents)?;
+
+        if is_not_modified(params, &contents) {
+            debug!(target: targets::HANDLER, "Resource not modified: {}", params.uri);
+            return Ok(ReadResourceResult::unchanged());
+        }
+
+        if let Some(max) = self.max_resource_bytes {
+            let size = resource_contents_size(&contents);
+            if size > max {
+                return Err(oversized_resource_error(&params.uri, size as u64, max));
+            }
+        }
 
-        Ok(ReadResourceResult { contents })
+        Ok(ReadResourceResult::new(contents))
     }
 
     /// Handles the prompts/list request.
@@ -953,9 +1938,15 @@ impl Router {
         } else {
             None
         };
+        let (prompts, next_cursor) = paginate(
+            self.prompts_filtered(session_state, tag_filters),
+            |prompt| prompt.name.as_str(),
+            params.cursor.as_deref(),
+            self.list_page_size,
+        )?;
         Ok(ListPromptsResult {
-            prompts: self.prompts_filtered(session_state, tag_filters),
-            next_cursor: None,
+            prompts,
+            next_cursor,
         })
     }
 
@@ -965,6 +1956,7 @@ impl Router {
     ///
     /// * `cx` - The asupersync context for cancellation and tracing
     /// * `request_id` - Internal request ID for tracking
+    /// * `original_id` - The JSON-RPC request ID, exposed via [`McpContext::request_id_display`]
     /// * `params` - The prompt get parameters including name and arguments
     /// * `budget` - Request budget for timeout enforcement
     /// * `session_state` - Session state for per-session storage
@@ -974,6 +1966,7 @@ impl Router {
         &self,
         cx: &Cx,
         request_id: u64,
+        original_id: Option<&RequestId>,
         params: GetPromptParams,
         budget: &Budget,
         session_state: SessionState,
@@ -1012,6 +2005,28 @@ impl Router {
             )
         })?;
 
+        // Reject the request before invoking the handler if required arguments are missing
+        let definition = handler.definition();
+        let missing: Vec<&str> = definition
+            .arguments
+            .iter()
+            .filter(|arg| arg.required)
+            .filter(|arg| {
+                !params
+                    .arguments
+                    .as_ref()
+                    .is_some_and(|args| args.contains_key(&arg.name))
+            })
+            .map(|arg| arg.name.as_str())
+            .collect();
+        if !missing.is_empty() {
+            return Err(McpError::invalid_params(format!(
+                "Missing required argument(s) for prompt '{}': {}",
+                params.name,
+                missing.join(", ")
+            )));
+        }
+
         // Extract progress token from request metadata
         let progress_token: Option<ProgressToken> =
             params.meta.as_ref().and_then(|m| m.progress_token.clone());
@@ -1041,17 +2056,23 @@ impl Router {
                     if let Some(ref elicitation) = senders.elicitation {
                         ctx = ctx.with_elicitation(elicitation.clone());
                     }
+                    if let Some(ref log) = senders.log {
+                        ctx = ctx.with_log_sender(log.clone());
+                    }
                 }
                 ctx
             }
         };
+        let ctx = with_request_info(ctx, "prompts/get", original_id)
+            .with_missing_capability_policy(self.missing_capability_policy);
 
         // Get the prompt asynchronously - returns McpOutcome (4-valued)
         let arguments = params.arguments.unwrap_or_default();
-        let outcome = block_on(handler.get_async(&ctx, arguments));
+        let outcome = run_handler(handler.get_async(&ctx, arguments));
 
         // Convert 4-valued Outcome to McpResult for JSON-RPC response
-        let messages = outcome.into_mcp_result()?;
+        let messages = handler_result(outcome, "prompt", &params.name)?;
+        let messages = self.embed_prompt_resources(&ctx, &params.name, messages)?;
 
         Ok(GetPromptResult {
             description: handler.definition().description,
@@ -1059,6 +2080,60 @@ impl Router {
         })
     }
 
+    /// Reads and inlines the resources referenced by a prompt's messages.
+    ///
+    /// Messages whose content is a [`ResourceContent::reference`] are replaced
+    /// by one message per content item the resource returns. Other messages
+    /// pass through unchanged. Each resource is bounded by
+    /// [`max_resource_bytes`](Self::max_resource_bytes), or
+    /// [`MAX_EMBEDDED_RESOURCE_BYTES`] when no limit is set.
+    fn embed_prompt_resources(
+        &self,
+        ctx: &McpContext,
+        prompt: &str,
+        messages: Vec<PromptMessage>,
+    ) -> McpResult<Vec<PromptMessage>> {
+        let max = self
+            .max_resource_bytes
+            .unwrap_or(MAX_EMBEDDED_RESOURCE_BYTES);
+        let mut embedded = Vec::with_capacity(messages.len());
+        for message in messages {
+            let uri = match &message.content {
+                Content::Resource { resource } if resource.is_reference() => resource.uri.clone(),
+                _ => {
+                    embedded.push(message);
+                    continue;
+                }
+            };
+
+            if !ctx.is_resource_enabled(&uri) {
+                return Err(McpError::new(
+                    McpErrorCode::ResourceNotFound,
+                    format!("Resource '{uri}' is disabled for this session"),
+                ));
+            }
+            let resolved = self.resolve_resource(&uri)?;
+            let outcome = run_handler(resolved.handler.read_async_with_uri(
+                ctx,
+                &uri,
+                &resolved.params,
+            ));
+            let contents = handler_result(outcome, "resource", &uri)?;
+
+            let size = resource_contents_size(&contents);
+            if size > max {
+                return Err(McpError::invalid_request(format!(
+                    "Resource '{uri}' embedded by prompt '{prompt}' is {size} bytes, exceeding the {max}-byte limit"
+                )));
+            }
+            embedded.extend(contents.into_iter().map(|resource| PromptMessage {
+                role: message.role,
+                content: Content::Resource { resource },
+            }));
+        }
+        Ok(embedded)
+    }
+
     // ========================================================================
     // Task Dispatch Methods (Docket/SEP-1686)
     // ========================================================================
@@ -1306,10 +2381,10 @@ impl Router {
         self.mount_tools_from(other.tools, prefix)
     }
 
-    /// Internal: mount tools from a HashMap.
+    /// Internal: mount tools from another router.
     fn mount_tools_from(
         &mut self,
-        tools: HashMap<String, BoxedToolHandler>,
+        tools: BTreeMap<String, BoxedToolHandler>,
         prefix: Option<&str>,
     ) -> MountResult {
         use crate::handler::MountedToolHandler;
@@ -1351,10 +2426,10 @@ impl Router {
         result
     }
 
-    /// Internal: mount resources from a HashMap.
+    /// Internal: mount resources from another router.
     fn mount_resources_from(
         &mut self,
-        resources: HashMap<String, BoxedResourceHandler>,
+        resources: BTreeMap<String, BoxedResourceHandler>,
         prefix: Option<&str>,
     ) -> MountResult {
         use crate::handler::MountedResourceHandler;
@@ -1387,16 +2462,20 @@ impl Router {
         result
     }
 
-    /// Internal: mount resource templates from a HashMap.
+    /// Internal: mount resource templates from another router.
     fn mount_resource_templates_from(
         &mut self,
-        templates: HashMap<String, ResourceTemplateEntry>,
+        templates: BTreeMap<String, ResourceTemplateEntry>,
         prefix: Option<&str>,
     ) -> MountResult {
         use crate::handler::MountedResourceHandler;
 
         let mut result = MountResult::default();
 
+        // Preserve the source router's registration order
+        let mut templates: Vec<_> = templates.into_iter().collect();
+        templates.sort_by_key(|(_, entry)| entry.registration);
+
         for (uri_template, entry) in templates {
             let mounted_uri_template = Self::apply_prefix(&uri_template, prefix);
             trace!(
@@ -1434,6 +2513,7 @@ impl Router {
                 matcher: UriTemplate::new(&mounted_uri_template),
                 template: mounted_template,
                 handler: mounted_handler,
+                registration: self.next_registration(),
             };
 
             self.resource_templates
@@ -1454,10 +2534,10 @@ impl Router {
         self.mount_prompts_from(other.prompts, prefix)
     }
 
-    /// Internal: mount prompts from a HashMap.
+    /// Internal: mount prompts from another router.
     fn mount_prompts_from(
         &mut self,
-        prompts: HashMap<String, BoxedPromptHandler>,
+        prompts: BTreeMap<String, BoxedPromptHandler>,
         prefix: Option<&str>,
     ) -> MountResult {
         use crate::handler::MountedPromptHandler;
@@ -1498,10 +2578,10 @@ impl Router {
     pub(crate) fn into_parts(
         self,
     ) -> (
-        HashMap<String, BoxedToolHandler>,
-        HashMap<String, BoxedResourceHandler>,
-        HashMap<String, ResourceTemplateEntry>,
-        HashMap<String, BoxedPromptHandler>,
+        BTreeMap<String, BoxedToolHandler>,
+        BTreeMap<String, BoxedResourceHandler>,
+        BTreeMap<String, ResourceTemplateEntry>,
+        BTreeMap<String, BoxedPromptHandler>,
     ) {
         (
             self.tools,
@@ -1522,6 +2602,36 @@ pub(crate) struct ResourceTemplateEntry {
     pub(crate) matcher: UriTemplate,
     pub(crate) template: ResourceTemplate,
     pub(crate) handler: Option<BoxedResourceHandler>,
+    /// Registration sequence number; earlier registrations win specificity ties.
+    pub(crate) registration: u64,
+}
+
+impl ResourceTemplateEntry {
+    /// Returns the template as advertised to clients.
+    ///
+    /// Placeholder types are not valid RFC 6570 (`:` introduces a prefix
+    /// length there), so `{id:int}` is published as `{id}` and the type moves
+    /// to the matching [`TemplateParameter`], which is added if absent.
+    fn published(&self) -> ResourceTemplate {
+        let mut template = self.template.clone();
+        template.uri_template = self.matcher.untyped_pattern();
+        for (name, param_type) in self.matcher.typed_params() {
+            match template.parameters.iter_mut().find(|p| p.name == name) {
+                Some(param) => {
+                    param
+                        .param_type
+                        .get_or_insert_with(|| param_type.name().to_string());
+                }
+                None => template.parameters.push(TemplateParameter {
+                    name: name.to_string(),
+                    description: None,
+                    required: true,
+                    param_type: Some(param_type.name().to_string()),
+                }),
+            }
+        }
+        template
+    }
 }
 
 /// A parsed URI template for matching resource URIs.
@@ -1537,12 +2647,54 @@ enum UriTemplateError {
     UnmatchedClose,
     EmptyParam,
     DuplicateParam(String),
+    UnknownParamType(String),
 }
 
 #[derive(Debug, Clone)]
 enum UriSegment {
     Literal(String),
-    Param(String),
+    Param(String, ParamType),
+}
+
+/// Declared type of a URI template placeholder (`{name:type}`).
+///
+/// Untyped placeholders (`{name}`) are [`ParamType::String`] and match any value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParamType {
+    String,
+    Int,
+    Float,
+    Bool,
+}
+
+impl ParamType {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "string" | "str" => Some(Self::String),
+            "int" | "integer" => Some(Self::Int),
+            "float" | "number" => Some(Self::Float),
+            "bool" | "boolean" => Some(Self::Bool),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Int => "int",
+            Self::Float => "float",
+            Self::Bool => "bool",
+        }
+    }
+
+    fn accepts(self, value: &str) -> bool {
+        match self {
+            Self::String => true,
+            Self::Int => value.parse::<i64>().is_ok(),
+            Self::Float => value.parse::<f64>().is_ok_and(f64::is_finite),
+            Self::Bool => matches!(value, "true" | "false"),
+        }
+    }
 }
 
 impl UriTemplate {
@@ -1604,13 +2756,23 @@ impl UriTemplate {
                         return Err(UriTemplateError::UnclosedParam);
                     }
 
+                    let (name, param_type) = match name.split_once(':') {
+                        Some((name, type_name)) => {
+                            let param_type = ParamType::parse(type_name).ok_or_else(|| {
+                                UriTemplateError::UnknownParamType(type_name.to_string())
+                            })?;
+                            (name.to_string(), param_type)
+                        }
+                        None => (name, ParamType::String),
+                    };
+
                     if name.is_empty() {
                         return Err(UriTemplateError::EmptyParam);
                     }
                     if !seen.insert(name.clone()) {
                         return Err(UriTemplateError::DuplicateParam(name));
                     }
-                    segments.push(UriSegment::Param(name));
+                    segments.push(UriSegment::Param(name, param_type));
                 }
                 '}' => {
                     if matches!(chars.peek(), Some('}')) {
@@ -1634,16 +2796,67 @@ impl UriTemplate {
         })
     }
 
-    fn specificity(&self) -> (usize, usize, usize) {
+    /// Returns the pattern with placeholder types removed (`{id:int}` → `{id}`).
+    fn untyped_pattern(&self) -> String {
+        let mut out = String::with_capacity(self.pattern.len());
+        let mut chars = self.pattern.chars().peekable();
+        while let Some(ch) = chars.next() {
+            out.push(ch);
+            if ch != '{' {
+                continue;
+            }
+            if matches!(chars.peek(), Some('{')) {
+                out.push('{');
+                let _ = chars.next();
+                continue;
+            }
+            let mut typed = false;
+            for next in chars.by_ref() {
+                if next == '}' {
+                    out.push('}');
+                    break;
+                }
+                typed |= next == ':';
+                if !typed {
+                    out.push(next);
+                }
+            }
+        }
+        out
+    }
+
+    /// Returns the placeholders that declare a non-string type.
+    fn typed_params(&self) -> impl Iterator<Item = (&str, ParamType)> {
+        self.segments.iter().filter_map(|segment| match segment {
+            UriSegment::Param(_, ParamType::String) | UriSegment::Literal(_) => None,
+            UriSegment::Param(name, param_type) => Some((name.as_str(), *param_type)),
+        })
+    }
+
+    /// Returns a sort key where larger means more specific.
+    ///
+    /// Compared in order: total literal length, number of literal segments,
+    /// number of typed placeholders, then total segment count.
+    fn specificity(&self) -> (usize, usize, usize, usize) {
         let mut literal_len = 0usize;
         let mut literal_segments = 0usize;
+        let mut typed_params = 0usize;
         for segment in &self.segments {
-            if let UriSegment::Literal(lit) = segment {
-                literal_len += lit.len();
-                literal_segments += 1;
+            match segment {
+                UriSegment::Literal(lit) => {
+                    literal_len += lit.len();
+                    literal_segments += 1;
+                }
+                UriSegment::Param(_, ParamType::String) => {}
+                UriSegment::Param(..) => typed_params += 1,
             }
         }
-        (literal_len, literal_segments, self.segments.len())
+        (
+            literal_len,
+            literal_segments,
+            typed_params,
+            self.segments.len(),
+        )
     }
 
     fn matches(&self, uri: &str) -> Option<UriParams> {
@@ -1656,10 +2869,10 @@ impl UriTemplate {
                 UriSegment::Literal(lit) => {
                     remainder = remainder.strip_prefix(lit)?;
                 }
-                UriSegment::Param(name) => {
+                UriSegment::Param(name, _) => {
                     let next_literal = iter.peek().and_then(|next| match next {
                         UriSegment::Literal(lit) => Some(lit.as_str()),
-                        UriSegment::Param(_) => None,
+                        UriSegment::Param(..) => None,
                     });
 
                     if next_literal.is_none() && iter.peek().is_some() {
@@ -1686,7 +2899,7 @@ impl UriTemplate {
                         let allow_slash_in_last_param = self
                             .segments
                             .iter()
-                            .filter(|seg| matches!(seg, UriSegment::Param(_)))
+                            .filter(|seg| matches!(seg, UriSegment::Param(..)))
                             .count()
                             == 1;
 
@@ -1714,9 +2927,27 @@ impl UriTemplate {
             None
         }
     }
+
+    /// Checks extracted parameters against the placeholders' declared types.
+    fn check_param_types(&self, params: &UriParams) -> Result<(), String> {
+        for segment in &self.segments {
+            let UriSegment::Param(name, param_type) = segment else {
+                continue;
+            };
+            if let Some(value) = params.get(name) {
+                if !param_type.accepts(value) {
+                    return Err(format!(
+                        "parameter '{name}' expects {}, got '{value}'",
+                        param_type.name()
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
-fn percent_decode(input: &str) -> Option<String> {
+pub(crate) fn percent_decode(input: &str) -> Option<String> {
     if !input.as_bytes().contains(&b'%') {
         return Some(input.to_string());
     }
@@ -1820,12 +3051,7 @@ impl ResourceReader for RouterResourceReader {
             debug!(target: targets::HANDLER, "Cross-component resource read: {} (depth: {})", uri, depth);
 
             // Resolve the resource
-            let resolved = router.resolve_resource(&uri).ok_or_else(|| {
-                McpError::new(
-                    McpErrorCode::ResourceNotFound,
-                    format!("Resource not found: {}", uri),
-                )
-            })?;
+            let resolved = router.resolve_resource(&uri)?;
 
             // Create a child context with incremented depth
             // Clone router again for the nested reader (the original is borrowed by resolved)
@@ -1839,14 +3065,15 @@ impl ResourceReader for RouterResourceReader {
                 )));
 
             // Read the resource
-            let outcome = block_on(resolved.handler.read_async_with_uri(
+            let outcome = run_handler(resolved.handler.read_async_with_uri(
                 &child_ctx,
                 &uri,
                 &resolved.params,
             ));
 
             // Convert outcome to result
-            let contents = outcome.into_mcp_result()?;
+            let mut contents = handler_result(outcome, "resource", &uri)?;
+            normalize_resource_contents(&mut contents)?;
 
             // Convert protocol ResourceContent to core ResourceContentItem
             let items: Vec<ResourceContentItem> = contents
@@ -1930,6 +3157,12 @@ impl ToolCaller for RouterToolCaller {
             // Validate arguments against the tool's input schema
             let tool_def = handler.definition();
 
+            let args = if router.coerce_arguments {
+                coerce_to_schema(&tool_def.input_schema, args)
+            } else {
+                args
+            };
+
             // Use strict or lenient validation based on router configuration
             let validation_result = if router.strict_input_validation {
                 validate_strict(&tool_def.input_schema, &args)
@@ -1938,14 +3171,7 @@ impl ToolCaller for RouterToolCaller {
             };
 
             if let Err(validation_errors) = validation_result {
-                let error_messages: Vec<String> = validation_errors
-                    .iter()
-                    .map(|e| format!("{}: {}", e.path, e.message))
-                    .collect();
-                return Err(McpError::invalid_params(format!(
-                    "Input validation failed: {}",
-                    error_messages.join("; ")
-                )));
+                return Err(input_validation_error(&validation_errors));
             }
 
             // Create a child context with incremented depth
@@ -1964,7 +3190,7 @@ impl ToolCaller for RouterToolCaller {
                 )));
 
             // Call the tool
-            let outcome = block_on(handler.call_async(&child_ctx, args));
+            let outcome = run_handler(handler.call_async(&child_ctx, args));
 
             // Convert outcome to result
             match outcome {
@@ -1992,10 +3218,18 @@ impl ToolCaller for RouterToolCaller {
                     Ok(ToolCallResult::error(e.message))
                 }
                 Outcome::Cancelled(_) => Err(McpError::request_cancelled()),
-                Outcome::Panicked(payload) => Err(McpError::internal_error(format!(
-                    "Handler panic: {}",
-                    payload.message()
-                ))),
+                Outcome::Panicked(payload) => {
+                    error!(
+                        target: targets::HANDLER,
+                        "tool '{}' panicked: {}",
+                        name,
+                        payload.message()
+                    );
+                    Ok(ToolCallResult::error(format!(
+                        "Internal error in tool '{}'",
+                        name
+                    )))
+                }
             }
         })
     }
@@ -2068,6 +3302,23 @@ mod uri_template_tests {
         assert_eq!(err, UriTemplateError::UnmatchedClose);
     }
 
+    #[test]
+    fn uri_template_parses_typed_params() {
+        let matcher = UriTemplate::new("item://{id:int}/{ratio:float}");
+        let params = matcher.matches("item://42/0.5").expect("match");
+        assert_eq!(params.get("id").map(String::as_str), Some("42"));
+        assert!(matcher.check_param_types(&params).is_ok());
+
+        let params = matcher.matches("item://abc/0.5").expect("structural match");
+        assert!(matcher.check_param_types(&params).is_err());
+    }
+
+    #[test]
+    fn uri_template_rejects_unknown_param_type() {
+        let err = UriTemplate::parse("item://{id:uuid}").unwrap_err();
+        assert_eq!(err, UriTemplateError::UnknownParamType("uuid".to_string()));
+    }
+
     #[test]
     fn uri_template_rejects_duplicate_params() {
         let err = UriTemplate::parse("db://{id}/{id}").unwrap_err();
@@ -0,0 +1,87 @@
+//! A [`tower_service::Service`] adapter, enabled by the `tower` feature.
+//!
+//! [`McpService`] hands each JSON-RPC request to [`Server::dispatch_one`], so
+//! a server can sit inside an axum or hyper pipeline and be composed with
+//! that stack's own middleware (auth, tracing, rate limiting). Each service
+//! stands for one logical connection and owns that connection's [`Session`].
+
+use std::convert::Infallible;
+use std::future::{Ready, ready};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+
+use asupersync::Cx;
+use fastmcp_protocol::{JsonRpcRequest, JsonRpcResponse};
+use tower_service::Service;
+
+use crate::{Server, Session};
+
+/// Serves one connection's requests through a shared [`Server`].
+///
+/// Clones share the session, so a stack that clones its inner service per
+/// request still sees a single connection. Use [`connect`](Self::connect)
+/// to start a session for another connection.
+///
+/// Requests are handled synchronously inside `call`; the returned future is
+/// already complete. Notifications yield `None`.
+///
+/// # Example
+///
+/// ```ignore
+/// let server = Arc::new(Server::new("demo", "1.0").tool(MyTool).build());
+/// let service = ServiceBuilder::new()
+///     .layer(my_auth_layer)
+///     .service(McpService::new(server));
+/// ```
+#[derive(Clone)]
+pub struct McpService {
+    server: Arc<Server>,
+    session: Arc<Mutex<Session>>,
+    cx: Cx,
+}
+
+impl McpService {
+    /// Creates a service for a new connection to `server`.
+    #[must_use]
+    pub fn new(server: Arc<Server>) -> Self {
+        Self::with_cx(server, Cx::for_testing())
+    }
+
+    /// Creates a service for a new connection, dispatching requests under
+    /// `cx` so that cancelling it cancels in-flight handlers.
+    #[must_use]
+    pub fn with_cx(server: Arc<Server>, cx: Cx) -> Self {
+        let session = Session::new(server.info().clone(), server.capabilities().clone());
+        Self {
+            server,
+            session: Arc::new(Mutex::new(session)),
+            cx,
+        }
+    }
+
+    /// Creates a service for another connection to the same server, with a
+    /// fresh session.
+    #[must_use]
+    pub fn connect(&self) -> Self {
+        Self::with_cx(Arc::clone(&self.server), self.cx.clone())
+    }
+}
+
+impl Service<JsonRpcRequest> for McpService {
+    type Response = Option<JsonRpcResponse>;
+    type Error = Infallible;
+    type Future = Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: JsonRpcRequest) -> Self::Future {
+        let mut session = self.session.lock().unwrap_or_else(PoisonError::into_inner);
+        ready(Ok(self.server.dispatch_one(
+            &self.cx,
+            &mut session,
+            request,
+        )))
+    }
+}
@@ -1,9 +1,10 @@
 //! MCP session management.
 
 use std::collections::HashSet;
+use std::time::SystemTime;
 
-use fastmcp_core::SessionState;
 use fastmcp_core::logging::{debug, targets, warn};
+use fastmcp_core::{AccessToken, AuthContext, SessionState};
 use fastmcp_protocol::{
     ClientCapabilities, ClientInfo, JsonRpcRequest, LogLevel, ResourceUpdatedNotificationParams,
     ServerCapabilities, ServerInfo,
@@ -34,6 +35,12 @@ pub struct Session {
     log_level: Option<LogLevel>,
     /// Per-session state storage.
     state: SessionState,
+    /// Last successful authentication, keyed by the token that produced it.
+    cached_auth: Option<(AccessToken, AuthContext)>,
+    /// Subject authenticated for the request being handled, for auditing.
+    request_subject: Option<String>,
+    /// Instructions sent to this client at `initialize`.
+    instructions: Option<String>,
 }
 
 impl Session {
@@ -50,6 +57,9 @@ impl Session {
             resource_subscriptions: HashSet::new(),
             log_level: None,
             state: SessionState::new(),
+            cached_auth: None,
+            request_subject: None,
+            instructions: None,
         }
     }
 
@@ -105,6 +115,17 @@ impl Session {
         &self.server_capabilities
     }
 
+    /// Returns the instructions this session was given at `initialize`.
+    #[must_use]
+    pub fn instructions(&self) -> Option<&str> {
+        self.instructions.as_deref()
+    }
+
+    /// Records the instructions given at `initialize`.
+    pub(crate) fn set_instructions(&mut self, instructions: Option<String>) {
+        self.instructions = instructions;
+    }
+
     /// Returns the negotiated protocol version.
     #[must_use]
     pub fn protocol_version(&self) -> Option<&str> {
@@ -138,6 +159,42 @@ impl Session {
         self.log_level
     }
 
+    /// Returns the cached auth context if it was resolved from `token` and
+    /// its `exp` claim (if any) is after `now`.
+    pub(crate) fn cached_auth(&self, token: &AccessToken, now: SystemTime) -> Option<&AuthContext> {
+        let (cached_token, auth) = self.cached_auth.as_ref()?;
+        if cached_token != token {
+            return None;
+        }
+        if auth
+            .expires_at()
+            .is_some_and(|expires_at| expires_at <= now)
+        {
+            return None;
+        }
+        Some(auth)
+    }
+
+    /// Caches an auth context resolved from `token`, replacing any previous one.
+    pub(crate) fn cache_auth(&mut self, token: AccessToken, auth: AuthContext) {
+        self.cached_auth = Some((token, auth));
+    }
+
+    /// Drops the cached auth context.
+    pub(crate) fn clear_cached_auth(&mut self) {
+        self.cached_auth = None;
+    }
+
+    /// Records the subject authenticated for the current request.
+    pub(crate) fn set_request_subject(&mut self, subject: Option<String>) {
+        self.request_subject = subject;
+    }
+
+    /// Takes the subject recorded for the current request, clearing it.
+    pub(crate) fn take_request_subject(&mut self) -> Option<String> {
+        self.request_subject.take()
+    }
+
     /// Returns whether the client supports sampling (LLM completions).
     #[must_use]
     pub fn supports_sampling(&self) -> bool {
@@ -227,6 +284,7 @@ mod tests {
                 sampling: Some(SamplingCapability {}),
                 elicitation: None,
                 roots: None,
+                experimental: None,
             },
             "2024-11-05".to_string(),
         );
@@ -255,6 +313,7 @@ mod tests {
                 sampling: None,
                 elicitation: Some(ElicitationCapability::form()),
                 roots: None,
+                experimental: None,
             },
             "2024-11-05".to_string(),
         );
@@ -283,6 +342,7 @@ mod tests {
                 sampling: None,
                 elicitation: None,
                 roots: Some(RootsCapability { list_changed: true }),
+                experimental: None,
             },
             "2024-11-05".to_string(),
         );
@@ -313,6 +373,7 @@ mod tests {
                 roots: Some(RootsCapability {
                     list_changed: false,
                 }),
+                experimental: None,
             },
             "2024-11-05".to_string(),
         );
@@ -0,0 +1,140 @@
+//! Reading stdin without blocking shutdown.
+//!
+//! A read from stdin blocks until the client writes, so a server idling on
+//! stdin would never get back to its cancellation check after a shutdown
+//! signal. [`CancellableStdin`] moves the blocking reads onto a background
+//! thread and waits for their output in short slices, checking the server's
+//! `Cx` in between.
+
+use std::io::{self, Read};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+use asupersync::Cx;
+
+/// Bytes requested from stdin per read.
+const CHUNK_SIZE: usize = 8192;
+
+/// How often a waiting read checks for cancellation.
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Stdin as a reader that fails once `cx` is cancelled.
+///
+/// A read waiting for input returns an error within
+/// [`CANCEL_POLL_INTERVAL`] of the cancellation. The background thread
+/// exits at end of input, or after its next read once this reader is
+/// dropped; until then it stays blocked on stdin.
+pub(crate) struct CancellableStdin {
+    chunks: Receiver<io::Result<Vec<u8>>>,
+    pending: Vec<u8>,
+    pos: usize,
+    cx: Cx,
+}
+
+impl CancellableStdin {
+    /// Starts reading stdin on a background thread.
+    pub(crate) fn new(cx: Cx) -> Self {
+        Self::from_reader(std::io::stdin(), cx)
+    }
+
+    /// Starts reading `inner` on a background thread.
+    pub(crate) fn from_reader<R: Read + Send + 'static>(mut inner: R, cx: Cx) -> Self {
+        let (tx, chunks) = mpsc::channel();
+        std::thread::spawn(move || {
+            loop {
+                let mut chunk = vec![0; CHUNK_SIZE];
+                let result = match inner.read(&mut chunk) {
+                    Ok(n) => {
+                        chunk.truncate(n);
+                        Ok(chunk)
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => Err(e),
+                };
+                let done = !matches!(&result, Ok(chunk) if !chunk.is_empty());
+                if tx.send(result).is_err() || done {
+                    break;
+                }
+            }
+        });
+        Self {
+            chunks,
+            pending: Vec::new(),
+            pos: 0,
+            cx,
+        }
+    }
+}
+
+impl Read for CancellableStdin {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.pending.len() {
+            // Not `Interrupted`: buffered readers retry on that kind
+            if self.cx.is_cancel_requested() {
+                return Err(io::Error::other("read cancelled"));
+            }
+            match self.chunks.recv_timeout(CANCEL_POLL_INTERVAL) {
+                Ok(chunk) => {
+                    self.pending = chunk?;
+                    self.pos = 0;
+                    if self.pending.is_empty() {
+                        return Ok(0);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                // The reader thread has finished: end of input
+                Err(RecvTimeoutError::Disconnected) => return Ok(0),
+            }
+        }
+
+        let n = buf.len().min(self.pending.len() - self.pos);
+        buf[..n].copy_from_slice(&self.pending[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use asupersync::CancelKind;
+    use std::io::{BufRead, BufReader};
+    use std::time::Instant;
+
+    /// A reader that never returns, like an idle stdin.
+    struct Stalled;
+
+    impl Read for Stalled {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            loop {
+                std::thread::park();
+            }
+        }
+    }
+
+    #[test]
+    fn test_reads_lines_and_eof() {
+        let cx = Cx::for_testing();
+        let reader = CancellableStdin::from_reader(&b"one\ntwo\n"[..], cx);
+        let lines: Vec<String> = BufReader::new(reader).lines().map(Result::unwrap).collect();
+        assert_eq!(lines, ["one", "two"]);
+    }
+
+    #[test]
+    fn test_blocked_read_fails_after_cancel() {
+        let cx = Cx::for_testing();
+        let mut reader = BufReader::new(CancellableStdin::from_reader(Stalled, cx.clone()));
+
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(30));
+            cx.cancel_with(CancelKind::Shutdown, None);
+        });
+        let start = Instant::now();
+        let mut line = String::new();
+        let err = reader.read_line(&mut line).expect_err("read should fail");
+        canceller.join().unwrap();
+
+        assert_eq!(err.kind(), io::ErrorKind::Other);
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+}
@@ -33,7 +33,7 @@
 
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock};
 
 use asupersync::runtime::{RuntimeBuilder, RuntimeHandle};
 use asupersync::{Budget, CancelKind, Cx};
@@ -43,6 +43,8 @@ use fastmcp_protocol::{
     JsonRpcRequest, TaskId, TaskInfo, TaskResult, TaskStatus, TaskStatusNotificationParams,
 };
 
+use crate::clock::{Clock, SystemClock};
+
 /// Notification sender used for task status updates.
 pub type TaskNotificationSender = Arc<dyn Fn(JsonRpcRequest) + Send + Sync>;
 
@@ -81,7 +83,7 @@ fn can_transition(from: TaskStatus, to: TaskStatus) -> bool {
     )
 }
 
-fn transition_state(state: &mut TaskState, to: TaskStatus) -> bool {
+fn transition_state(state: &mut TaskState, to: TaskStatus, clock: &dyn Clock) -> bool {
     let from = state.info.status;
     if from == to {
         return true;
@@ -98,7 +100,7 @@ fn transition_state(state: &mut TaskState, to: TaskStatus) -> bool {
     }
 
     state.info.status = to;
-    let now = chrono::Utc::now().to_rfc3339();
+    let now = timestamp(clock);
     match to {
         TaskStatus::Running => {
             state.info.started_at = Some(now.clone());
@@ -120,6 +122,11 @@ fn transition_state(state: &mut TaskState, to: TaskStatus) -> bool {
     true
 }
 
+/// Formats the clock's current time as an RFC 3339 timestamp.
+fn timestamp(clock: &dyn Clock) -> String {
+    chrono::DateTime::<chrono::Utc>::from(clock.now_system()).to_rfc3339()
+}
+
 /// Background task manager.
 ///
 /// Manages the lifecycle of background tasks including submission, status
@@ -134,11 +141,17 @@ pub struct TaskManager {
     /// Whether task list changes should trigger notifications.
     list_changed_notifications: bool,
     /// Background runtime handle for executing tasks.
-    runtime: RuntimeHandle,
+    runtime: Arc<RuntimeHandle>,
     /// Whether submitted tasks should execute immediately.
     auto_execute: bool,
     /// Optional notification sender for task status updates.
     notification_sender: Arc<RwLock<Option<TaskNotificationSender>>>,
+    /// Time source for task timestamps.
+    clock: Arc<dyn Clock>,
+    /// Submitted tasks waiting for an execution slot.
+    queue: Arc<Mutex<TaskQueue>>,
+    /// Maximum number of tasks running at once (unbounded if `None`).
+    max_concurrency: Option<usize>,
 }
 
 impl TaskManager {
@@ -154,12 +167,38 @@ impl TaskManager {
             handlers: Arc::new(RwLock::new(HashMap::new())),
             task_counter: AtomicU64::new(0),
             list_changed_notifications: false,
-            runtime,
+            runtime: Arc::new(runtime),
             auto_execute: true,
             notification_sender: Arc::new(RwLock::new(None)),
+            clock: Arc::new(SystemClock),
+            queue: Arc::new(Mutex::new(TaskQueue::default())),
+            max_concurrency: None,
         }
     }
 
+    /// Limits how many tasks run at once.
+    ///
+    /// Tasks submitted while `max` tasks are running stay
+    /// [`Pending`](TaskStatus::Pending) until one finishes; the
+    /// highest-priority waiting task (see
+    /// [`submit_with_priority`](Self::submit_with_priority)) then takes the
+    /// freed slot. A limit of zero is treated as one.
+    #[must_use]
+    pub fn with_max_concurrency(mut self, max: usize) -> Self {
+        self.max_concurrency = Some(max.max(1));
+        self
+    }
+
+    /// Sets the clock used for task timestamps and
+    /// [`cleanup_completed`](Self::cleanup_completed).
+    ///
+    /// Defaults to [`SystemClock`].
+    #[must_use]
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
     /// Creates a new task manager with list change notifications enabled.
     #[must_use]
     pub fn with_list_changed_notifications() -> Self {
@@ -223,10 +262,25 @@ impl TaskManager {
     /// Returns the task ID for tracking. The task runs asynchronously in the
     /// background region.
     pub fn submit(
+        &self,
+        cx: &Cx,
+        task_type: impl Into<String>,
+        params: Option<serde_json::Value>,
+    ) -> McpResult<TaskId> {
+        self.submit_with_priority(cx, task_type, params, 0)
+    }
+
+    /// Submits a new background task with a scheduling priority.
+    ///
+    /// When the [concurrency limit](Self::with_max_concurrency) is reached,
+    /// waiting tasks with a higher `priority` start first; equal priorities
+    /// start in submission order. [`submit`](Self::submit) uses priority 0.
+    pub fn submit_with_priority(
         &self,
         _cx: &Cx,
         task_type: impl Into<String>,
         params: Option<serde_json::Value>,
+        priority: i32,
     ) -> McpResult<TaskId> {
         let task_type = task_type.into();
 
@@ -248,7 +302,7 @@ impl TaskManager {
         let task_id = TaskId::from_string(format!("task-{counter:08x}"));
 
         // Create task info
-        let now = chrono::Utc::now().to_rfc3339();
+        let now = timestamp(self.clock.as_ref());
         let task_cx = Cx::for_request_with_budget(Budget::INFINITE);
         let info = TaskInfo {
             id: task_id.clone(),
@@ -283,132 +337,28 @@ impl TaskManager {
         self.notify_status(info_snapshot, None);
 
         if self.auto_execute {
-            let params = params.unwrap_or_else(|| serde_json::json!({}));
-            self.spawn_task(task_id.clone(), task_type, task_cx, params);
+            self.runner().enqueue(QueuedTask {
+                task_id: task_id.clone(),
+                task_type,
+                cx: task_cx,
+                params: params.unwrap_or_else(|| serde_json::json!({})),
+                priority,
+            });
         }
 
         Ok(task_id)
     }
 
-    #[allow(clippy::too_many_lines)]
-    fn spawn_task(
-        &self,
-        task_id: TaskId,
-        task_type: String,
-        task_cx: Cx,
-        params: serde_json::Value,
-    ) {
-        let tasks = Arc::clone(&self.tasks);
-        let handlers = Arc::clone(&self.handlers);
-        let notification_sender = Arc::clone(&self.notification_sender);
-
-        self.runtime.spawn(async move {
-            let running_snapshot = {
-                let mut tasks_guard = tasks.write().unwrap_or_else(|poisoned| {
-                    warn!(target: targets::SERVER, "tasks lock poisoned in spawn_task, recovering");
-                    poisoned.into_inner()
-                });
-                match tasks_guard.get_mut(&task_id) {
-                    Some(state) => {
-                        if state.cancel_requested || !transition_state(state, TaskStatus::Running) {
-                            None
-                        } else {
-                            Some(TaskStatusSnapshot::from(state))
-                        }
-                    }
-                    None => None,
-                }
-            };
-
-            notify_snapshot(&notification_sender, running_snapshot);
-
-            let task_future = {
-                let handlers_guard = handlers.read().unwrap_or_else(|poisoned| {
-                    warn!(target: targets::SERVER, "handlers lock poisoned in spawn_task, recovering");
-                    poisoned.into_inner()
-                });
-                let Some(handler) = handlers_guard.get(&task_type) else {
-                    let failure_snapshot = {
-                        let mut tasks_guard = tasks.write().unwrap_or_else(|poisoned| {
-                            warn!(target: targets::SERVER, "tasks lock poisoned in spawn_task failure, recovering");
-                            poisoned.into_inner()
-                        });
-                        match tasks_guard.get_mut(&task_id) {
-                            Some(state) => {
-                                if !state.cancel_requested {
-                                    let error_msg = format!("Unknown task type: {task_type}");
-                                    state.info.status = TaskStatus::Failed;
-                                    state.info.completed_at = Some(chrono::Utc::now().to_rfc3339());
-                                    state.info.error = Some(error_msg.clone());
-                                    state.result = Some(TaskResult {
-                                        id: task_id.clone(),
-                                        success: false,
-                                        data: None,
-                                        error: Some(error_msg),
-                                    });
-                                    Some(TaskStatusSnapshot::from(state))
-                                } else {
-                                    None
-                                }
-                            }
-                            None => None,
-                        }
-                    };
-                    notify_snapshot(&notification_sender, failure_snapshot);
-                    return;
-                };
-                (handler)(&task_cx, params)
-            };
-
-            let result = task_future.await;
-
-            let completion_snapshot = {
-                let mut tasks_guard = tasks.write().unwrap_or_else(|poisoned| {
-                    warn!(target: targets::SERVER, "tasks lock poisoned in spawn_task completion, recovering");
-                    poisoned.into_inner()
-                });
-                match tasks_guard.get_mut(&task_id) {
-                    Some(state) => {
-                        if state.cancel_requested {
-                            None
-                        } else {
-                            let mut snapshot = None;
-                            match result {
-                                Ok(data) => {
-                                    if transition_state(state, TaskStatus::Completed) {
-                                        state.info.progress = Some(1.0);
-                                        state.result = Some(TaskResult {
-                                            id: task_id.clone(),
-                                            success: true,
-                                            data: Some(data),
-                                            error: None,
-                                        });
-                                        snapshot = Some(TaskStatusSnapshot::from(state));
-                                    }
-                                }
-                                Err(err) => {
-                                    let error_msg = err.message;
-                                    if transition_state(state, TaskStatus::Failed) {
-                                        state.info.error = Some(error_msg.clone());
-                                        state.result = Some(TaskResult {
-                                            id: task_id.clone(),
-                                            success: false,
-                                            data: None,
-                                            error: Some(error_msg),
-                                        });
-                                        snapshot = Some(TaskStatusSnapshot::from(state));
-                                    }
-                                }
-                            }
-                            snapshot
-                        }
-                    }
-                    None => None,
-                }
-            };
-
-            notify_snapshot(&notification_sender, completion_snapshot);
-        });
+    fn runner(&self) -> TaskRunner {
+        TaskRunner {
+            tasks: Arc::clone(&self.tasks),
+            handlers: Arc::clone(&self.handlers),
+            notification_sender: Arc::clone(&self.notification_sender),
+            clock: Arc::clone(&self.clock),
+            runtime: Arc::clone(&self.runtime),
+            queue: Arc::clone(&self.queue),
+            max_concurrency: self.max_concurrency,
+        }
     }
 
     /// Starts execution of a pending task.
@@ -430,7 +380,7 @@ impl TaskManager {
                 )));
             }
 
-            if !transition_state(state, TaskStatus::Running) {
+            if !transition_state(state, TaskStatus::Running, self.clock.as_ref()) {
                 return Err(McpError::invalid_params(format!(
                     "Task {task_id} cannot transition to running"
                 )));
@@ -478,7 +428,7 @@ impl TaskManager {
                 poisoned.into_inner()
             });
             if let Some(state) = tasks.get_mut(task_id) {
-                if !transition_state(state, TaskStatus::Completed) {
+                if !transition_state(state, TaskStatus::Completed, self.clock.as_ref()) {
                     return;
                 }
                 state.info.progress = Some(1.0);
@@ -506,7 +456,7 @@ impl TaskManager {
                 poisoned.into_inner()
             });
             if let Some(state) = tasks.get_mut(task_id) {
-                if !transition_state(state, TaskStatus::Failed) {
+                if !transition_state(state, TaskStatus::Failed, self.clock.as_ref()) {
                     return;
                 }
                 state.info.error = Some(error.clone());
@@ -581,7 +531,7 @@ impl TaskManager {
                 )));
             }
 
-            if !transition_state(state, TaskStatus::Cancelled) {
+            if !transition_state(state, TaskStatus::Cancelled, self.clock.as_ref()) {
                 return Err(McpError::invalid_params(format!(
                     "Task {task_id} cannot be cancelled from {:?}",
                     state.info.status
@@ -637,6 +587,18 @@ impl TaskManager {
         tasks.values().filter(|s| s.info.status.is_active()).count()
     }
 
+    /// Returns the number of tasks currently running.
+    #[must_use]
+    pub fn running_count(&self) -> usize {
+        self.list_tasks(Some(TaskStatus::Running)).len()
+    }
+
+    /// Returns the number of submitted tasks waiting to start.
+    #[must_use]
+    pub fn queued_count(&self) -> usize {
+        self.list_tasks(Some(TaskStatus::Pending)).len()
+    }
+
     /// Returns the total number of tasks.
     #[must_use]
     pub fn total_count(&self) -> usize {
@@ -651,7 +613,8 @@ impl TaskManager {
     ///
     /// This is useful for preventing unbounded memory growth from completed tasks.
     pub fn cleanup_completed(&self, max_age: std::time::Duration) {
-        let cutoff = chrono::Utc::now() - chrono::Duration::from_std(max_age).unwrap_or_default();
+        let cutoff = chrono::DateTime::<chrono::Utc>::from(self.clock.now_system())
+            - chrono::Duration::from_std(max_age).unwrap_or_default();
 
         let mut tasks = self.tasks.write().unwrap_or_else(|poisoned| {
             warn!(target: targets::SERVER, "tasks lock poisoned in cleanup_completed, recovering");
@@ -719,6 +682,235 @@ impl TaskManager {
     }
 }
 
+/// A submitted task waiting for an execution slot.
+struct QueuedTask {
+    task_id: TaskId,
+    task_type: String,
+    cx: Cx,
+    params: serde_json::Value,
+    priority: i32,
+}
+
+/// Tasks waiting to run and the number of occupied execution slots.
+#[derive(Default)]
+struct TaskQueue {
+    waiting: Vec<QueuedTask>,
+    running: usize,
+}
+
+impl TaskQueue {
+    /// Removes the highest-priority waiting task, oldest first among equals.
+    fn pop(&mut self) -> Option<QueuedTask> {
+        let index = self
+            .waiting
+            .iter()
+            .enumerate()
+            .max_by(|(a_index, a), (b_index, b)| {
+                a.priority.cmp(&b.priority).then(b_index.cmp(a_index))
+            })
+            .map(|(index, _)| index)?;
+        Some(self.waiting.remove(index))
+    }
+}
+
+/// Handles shared with spawned tasks, so a finishing task can start the
+/// next waiting one.
+#[derive(Clone)]
+struct TaskRunner {
+    tasks: Arc<RwLock<HashMap<TaskId, TaskState>>>,
+    handlers: Arc<RwLock<HashMap<String, TaskHandler>>>,
+    notification_sender: Arc<RwLock<Option<TaskNotificationSender>>>,
+    clock: Arc<dyn Clock>,
+    runtime: Arc<RuntimeHandle>,
+    queue: Arc<Mutex<TaskQueue>>,
+    max_concurrency: Option<usize>,
+}
+
+impl TaskRunner {
+    fn lock_queue(&self) -> MutexGuard<'_, TaskQueue> {
+        self.queue.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn enqueue(&self, task: QueuedTask) {
+        self.lock_queue().waiting.push(task);
+        self.dispatch();
+    }
+
+    /// Starts waiting tasks while execution slots are free.
+    ///
+    /// Tasks cancelled while waiting are dropped without taking a slot.
+    fn dispatch(&self) {
+        let ready = {
+            let mut queue = self.lock_queue();
+            let mut ready = Vec::new();
+            while self.max_concurrency.is_none_or(|max| queue.running < max) {
+                let Some(task) = queue.pop() else {
+                    break;
+                };
+                if !self.is_startable(&task.task_id) {
+                    continue;
+                }
+                queue.running += 1;
+                ready.push(task);
+            }
+            ready
+        };
+        for task in ready {
+            self.spawn(task);
+        }
+    }
+
+    fn is_startable(&self, task_id: &TaskId) -> bool {
+        let tasks = self.tasks.read().unwrap_or_else(PoisonError::into_inner);
+        tasks.get(task_id).is_some_and(|state| {
+            state.info.status == TaskStatus::Pending && !state.cancel_requested
+        })
+    }
+
+    /// Frees a finished task's slot and starts the next waiting task.
+    fn release(&self) {
+        {
+            let mut queue = self.lock_queue();
+            queue.running = queue.running.saturating_sub(1);
+        }
+        self.dispatch();
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn spawn(&self, task: QueuedTask) {
+        let QueuedTask {
+            task_id,
+            task_type,
+            cx: task_cx,
+            params,
+            ..
+        } = task;
+        let slot = SlotGuard(self.clone());
+        let tasks = Arc::clone(&self.tasks);
+        let handlers = Arc::clone(&self.handlers);
+        let notification_sender = Arc::clone(&self.notification_sender);
+        let clock = Arc::clone(&self.clock);
+
+        self.runtime.spawn(async move {
+            let _slot = slot;
+            let running_snapshot = {
+                let mut tasks_guard = tasks.write().unwrap_or_else(|poisoned| {
+                    warn!(target: targets::SERVER, "tasks lock poisoned in spawn_task, recovering");
+                    poisoned.into_inner()
+                });
+                match tasks_guard.get_mut(&task_id) {
+                    Some(state) => {
+                        if state.cancel_requested || !transition_state(state, TaskStatus::Running, clock.as_ref()) {
+                            None
+                        } else {
+                            Some(TaskStatusSnapshot::from(state))
+                        }
+                    }
+                    None => None,
+                }
+            };
+
+            notify_snapshot(&notification_sender, running_snapshot);
+
+            let task_future = {
+                let handlers_guard = handlers.read().unwrap_or_else(|poisoned| {
+                    warn!(target: targets::SERVER, "handlers lock poisoned in spawn_task, recovering");
+                    poisoned.into_inner()
+                });
+                let Some(handler) = handlers_guard.get(&task_type) else {
+                    let failure_snapshot = {
+                        let mut tasks_guard = tasks.write().unwrap_or_else(|poisoned| {
+                            warn!(target: targets::SERVER, "tasks lock poisoned in spawn_task failure, recovering");
+                            poisoned.into_inner()
+                        });
+                        match tasks_guard.get_mut(&task_id) {
+                            Some(state) => {
+                                if !state.cancel_requested {
+                                    let error_msg = format!("Unknown task type: {task_type}");
+                                    state.info.status = TaskStatus::Failed;
+                                    state.info.completed_at = Some(timestamp(clock.as_ref()));
+                                    state.info.error = Some(error_msg.clone());
+                                    state.result = Some(TaskResult {
+                                        id: task_id.clone(),
+                                        success: false,
+                                        data: None,
+                                        error: Some(error_msg),
+                                    });
+                                    Some(TaskStatusSnapshot::from(state))
+                                } else {
+                                    None
+                                }
+                            }
+                            None => None,
+                        }
+                    };
+                    notify_snapshot(&notification_sender, failure_snapshot);
+                    return;
+                };
+                (handler)(&task_cx, params)
+            };
+
+            let result = task_future.await;
+
+            let completion_snapshot = {
+                let mut tasks_guard = tasks.write().unwrap_or_else(|poisoned| {
+                    warn!(target: targets::SERVER, "tasks lock poisoned in spawn_task completion, recovering");
+                    poisoned.into_inner()
+                });
+                match tasks_guard.get_mut(&task_id) {
+                    Some(state) => {
+                        if state.cancel_requested {
+                            None
+                        } else {
+                            let mut snapshot = None;
+                            match result {
+                                Ok(data) => {
+                                    if transition_state(state, TaskStatus::Completed, clock.as_ref()) {
+                                        state.info.progress = Some(1.0);
+                                        state.result = Some(TaskResult {
+                                            id: task_id.clone(),
+                                            success: true,
+                                            data: Some(data),
+                                            error: None,
+                                        });
+                                        snapshot = Some(TaskStatusSnapshot::from(state));
+                                    }
+                                }
+                                Err(err) => {
+                                    let error_msg = err.message;
+                                    if transition_state(state, TaskStatus::Failed, clock.as_ref()) {
+                                        state.info.error = Some(error_msg.clone());
+                                        state.result = Some(TaskResult {
+                                            id: task_id.clone(),
+                                            success: false,
+                                            data: None,
+                                            error: Some(error_msg),
+                                        });
+                                        snapshot = Some(TaskStatusSnapshot::from(state));
+                                    }
+                                }
+                            }
+                            snapshot
+                        }
+                    }
+                    None => None,
+                }
+            };
+
+            notify_snapshot(&notification_sender, completion_snapshot);
+        });
+    }
+}
+
+/// Releases a task's execution slot when the task finishes.
+struct SlotGuard(TaskRunner);
+
+impl Drop for SlotGuard {
+    fn drop(&mut self) {
+        self.0.release();
+    }
+}
+
 #[derive(Debug, Clone)]
 struct TaskStatusSnapshot {
     info: TaskInfo,
@@ -1115,4 +1307,152 @@ mod tests {
         assert_eq!(recorded[2].progress, Some(0.5));
         assert_eq!(recorded.last().expect("last").status, TaskStatus::Completed);
     }
+
+    #[test]
+    fn test_cleanup_completed_follows_clock() {
+        let clock = crate::TestClock::new();
+        let manager = TaskManager::new_for_testing().with_clock(clock.clone());
+        let cx = Cx::for_testing();
+        manager.register_handler("test", |_cx, _params| async { Ok(serde_json::json!({})) });
+
+        let task_id = manager.submit(&cx, "test", None).unwrap();
+        manager.start_task(&task_id).unwrap();
+        manager.complete_task(&task_id, serde_json::json!({}));
+
+        let max_age = std::time::Duration::from_secs(60);
+        clock.advance(max_age - std::time::Duration::from_secs(1));
+        manager.cleanup_completed(max_age);
+        assert!(manager.get_info(&task_id).is_some());
+
+        clock.advance(std::time::Duration::from_secs(2));
+        manager.cleanup_completed(max_age);
+        assert!(manager.get_info(&task_id).is_none());
+    }
+
+    /// Registers a "gated" task type whose tasks record their `name` param
+    /// when they start and finish once `gate` opens.
+    fn register_gated(
+        manager: &TaskManager,
+        gate: &Arc<std::sync::atomic::AtomicBool>,
+        started: &Arc<std::sync::Mutex<Vec<String>>>,
+    ) {
+        let gate = Arc::clone(gate);
+        let started = Arc::clone(started);
+        manager.register_handler("gated", move |_cx, params| {
+            let name = params["name"].as_str().unwrap_or_default().to_string();
+            started.lock().unwrap().push(name);
+            let gate = Arc::clone(&gate);
+            async move {
+                std::future::poll_fn(|task_cx| {
+                    if gate.load(Ordering::SeqCst) {
+                        std::task::Poll::Ready(())
+                    } else {
+                        task_cx.waker().wake_by_ref();
+                        std::task::Poll::Pending
+                    }
+                })
+                .await;
+                Ok(serde_json::json!({}))
+            }
+        });
+    }
+
+    fn wait_until(mut condition: impl FnMut() -> bool) -> bool {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while std::time::Instant::now() < deadline {
+            if condition() {
+                return true;
+            }
+            thread::sleep(std::time::Duration::from_millis(5));
+        }
+        false
+    }
+
+    #[test]
+    fn test_cancelled_task_wakes_from_sleep() {
+        let manager = TaskManager::new();
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        manager.register_handler("sleepy", move |cx, _params| {
+            let ctx = fastmcp_core::McpContext::new(cx.clone(), 0);
+            let done_tx = done_tx.clone();
+            async move {
+                let result = ctx.sleep(std::time::Duration::from_secs(30)).await;
+                let _ = done_tx.send(result.clone());
+                result.map(|()| serde_json::json!({}))
+            }
+        });
+
+        let cx = Cx::for_testing();
+        let task_id = manager.submit(&cx, "sleepy", None).unwrap();
+        assert!(wait_until(|| manager.running_count() == 1));
+        manager.cancel(&task_id, None).unwrap();
+
+        let result = done_rx
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .expect("sleep should end promptly after cancellation");
+        assert_eq!(
+            result.unwrap_err().code,
+            fastmcp_core::McpErrorCode::RequestCancelled
+        );
+    }
+
+    #[test]
+    fn test_max_concurrency_limits_running_tasks() {
+        let manager = TaskManager::new().with_max_concurrency(2);
+        let gate = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let started = Arc::new(std::sync::Mutex::new(Vec::new()));
+        register_gated(&manager, &gate, &started);
+
+        let cx = Cx::for_testing();
+        for i in 0..5 {
+            let params = serde_json::json!({ "name": format!("task-{i}") });
+            manager.submit(&cx, "gated", Some(params)).unwrap();
+        }
+
+        assert!(wait_until(|| manager.running_count() == 2));
+        thread::sleep(std::time::Duration::from_millis(50));
+        assert_eq!(manager.running_count(), 2);
+        assert_eq!(manager.queued_count(), 3);
+        assert_eq!(started.lock().unwrap().len(), 2);
+
+        gate.store(true, Ordering::SeqCst);
+        assert!(wait_until(|| {
+            assert!(manager.running_count() <= 2);
+            manager.list_tasks(Some(TaskStatus::Completed)).len() == 5
+        }));
+        assert_eq!(manager.queued_count(), 0);
+    }
+
+    #[test]
+    fn test_freed_slot_goes_to_highest_priority_task() {
+        let manager = TaskManager::new().with_max_concurrency(1);
+        let gate = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let started = Arc::new(std::sync::Mutex::new(Vec::new()));
+        register_gated(&manager, &gate, &started);
+
+        let cx = Cx::for_testing();
+        let submit = |name: &str, priority: i32| {
+            let params = serde_json::json!({ "name": name });
+            manager
+                .submit_with_priority(&cx, "gated", Some(params), priority)
+                .unwrap()
+        };
+        submit("blocker", 0);
+        assert!(wait_until(|| manager.running_count() == 1));
+        submit("low", 0);
+        let cancelled = submit("cancelled", 9);
+        submit("high", 5);
+        submit("low-2", 0);
+        manager.cancel(&cancelled, None).unwrap();
+
+        gate.store(true, Ordering::SeqCst);
+        assert!(wait_until(|| manager
+            .list_tasks(Some(TaskStatus::Completed))
+            .len()
+            == 4));
+        assert_eq!(
+            *started.lock().unwrap(),
+            vec!["blocker", "high", "low", "low-2"]
+        );
+    }
 }
@@ -0,0 +1,441 @@
+//! Deterministic in-process test harness for servers.
+//!
+//! [`TestServer`] connects a built [`Server`] to one end of an in-memory
+//! transport pair and plays the client on the other end. Every request is
+//! received, dispatched and answered inside a [`LabRuntime`], so a full
+//! request/response cycle runs on the deterministic scheduler without
+//! threads, sockets or subprocesses.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use fastmcp_server::testing::TestServer;
+//!
+//! let server = Server::new("demo", "1.0").tool(GreetTool).build();
+//! let mut test = TestServer::new(server);
+//!
+//! test.assert_tool_text("greet", json!({"name": "Ada"}), "Hello, Ada!");
+//! ```
+//!
+//! Requests are numbered from 1 (the `initialize` handshake), so a test can
+//! predict the ID of its next request with
+//! [`next_request_id`](TestServer::next_request_id) and cancel it from inside
+//! a handler through a [`CancelHandle`].
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+
+use asupersync::Cx;
+use asupersync::conformance::{ConformanceTarget, LabRuntimeTarget};
+use asupersync::lab::{LabConfig, LabRuntime};
+use fastmcp_core::{McpError, McpErrorCode, McpResult};
+use fastmcp_protocol::{
+    CallToolResult, CancelledParams, ClientCapabilities, ClientInfo, Content, GetPromptResult,
+    InitializeParams, InitializeResult, JsonRpcMessage, JsonRpcRequest, JsonRpcResponse,
+    PROTOCOL_VERSION, ReadResourceResult, RequestId,
+};
+use fastmcp_transport::memory::{MemoryTransport, create_memory_transport_pair};
+use fastmcp_transport::{Transport, TransportError};
+use serde::de::DeserializeOwned;
+
+use crate::{NotificationSender, Server, Session};
+
+/// Lab runtime seed used by [`TestServer::new`].
+const DEFAULT_SEED: u64 = 42;
+
+/// Server side of the in-memory connection.
+struct Connection {
+    transport: MemoryTransport,
+    session: Session,
+}
+
+/// A server under test, driven on the deterministic Lab runtime.
+///
+/// Typed helpers such as [`call_tool`](Self::call_tool) perform the
+/// `initialize` handshake on first use.
+pub struct TestServer {
+    server: Arc<Server>,
+    runtime: LabRuntime,
+    client: MemoryTransport,
+    connection: Arc<Mutex<Connection>>,
+    cx: Cx,
+    next_id: i64,
+    notifications: Vec<JsonRpcRequest>,
+}
+
+impl TestServer {
+    /// Wraps a built server using the default Lab runtime seed.
+    #[must_use]
+    pub fn new(server: Server) -> Self {
+        Self::with_seed(server, DEFAULT_SEED)
+    }
+
+    /// Wraps a built server using the given Lab runtime seed.
+    #[must_use]
+    pub fn with_seed(server: Server, seed: u64) -> Self {
+        let (client, transport) = create_memory_transport_pair();
+        let session = Session::new(server.info().clone(), server.capabilities().clone());
+        Self {
+            server: Arc::new(server),
+            runtime: LabRuntime::new(LabConfig::new(seed).max_steps(10_000)),
+            client,
+            connection: Arc::new(Mutex::new(Connection { transport, session })),
+            cx: Cx::for_testing(),
+            next_id: 1,
+            notifications: Vec::new(),
+        }
+    }
+
+    /// Returns the server under test, e.g. to inspect its stats.
+    #[must_use]
+    pub fn server(&self) -> &Server {
+        &self.server
+    }
+
+    /// Returns whether the session has completed `initialize`.
+    #[must_use]
+    pub fn is_initialized(&self) -> bool {
+        self.connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .session
+            .is_initialized()
+    }
+
+    /// Returns the ID the next request will be sent with.
+    #[must_use]
+    pub fn next_request_id(&self) -> RequestId {
+        RequestId::Number(self.next_id)
+    }
+
+    /// Returns a handle that cancels in-flight requests, for use inside handlers.
+    #[must_use]
+    pub fn cancel_handle(&self) -> CancelHandle {
+        CancelHandle {
+            server: Arc::downgrade(&self.server),
+        }
+    }
+
+    /// Returns the notifications the server has sent so far.
+    #[must_use]
+    pub fn notifications(&self) -> &[JsonRpcRequest] {
+        &self.notifications
+    }
+
+    /// Performs the `initialize` handshake followed by `initialized`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the server's error if initialization is rejected.
+    pub fn initialize(&mut self) -> McpResult<InitializeResult> {
+        self.initialize_with(
+            ClientInfo {
+                name: "test-client".to_string(),
+                version: "1.0.0".to_string(),
+            },
+            ClientCapabilities::default(),
+        )
+    }
+
+    /// Like [`initialize`](Self::initialize), as the given client with the
+    /// given capabilities.
+    ///
+    /// # Errors
+    ///
+    /// Returns the server's error if initialization is rejected.
+    pub fn initialize_with(
+        &mut self,
+        client_info: ClientInfo,
+        capabilities: ClientCapabilities,
+    ) -> McpResult<InitializeResult> {
+        let params = InitializeParams {
+            protocol_version: PROTOCOL_VERSION.to_string(),
+            capabilities,
+            client_info,
+        };
+        let response = self.request("initialize", Some(to_value(params)?))?;
+        let result = response_result(response)?;
+        self.notify("initialized", Some(serde_json::json!({})))?;
+        Ok(result)
+    }
+
+    /// Sends a request and returns the raw JSON-RPC response.
+    ///
+    /// Notifications the server emits while handling the request are
+    /// collected into [`notifications`](Self::notifications).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the in-memory transport fails.
+    pub fn request(
+        &mut self,
+        method: &str,
+        params: Option<serde_json::Value>,
+    ) -> McpResult<JsonRpcResponse> {
+        let request = JsonRpcRequest::new(method, params, self.next_id);
+        self.next_id += 1;
+        self.client
+            .send(&self.cx, &JsonRpcMessage::Request(request))
+            .map_err(transport_error)?;
+        self.serve_one()?;
+
+        loop {
+            match self.client.recv(&self.cx).map_err(transport_error)? {
+                JsonRpcMessage::Request(notification) => self.notifications.push(notification),
+                JsonRpcMessage::Response(response) => return Ok(response),
+            }
+        }
+    }
+
+    /// Sends a notification; the server does not reply.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the in-memory transport fails.
+    pub fn notify(&mut self, method: &str, params: Option<serde_json::Value>) -> McpResult<()> {
+        let notification = JsonRpcRequest {
+            jsonrpc: std::borrow::Cow::Borrowed(fastmcp_protocol::JSONRPC_VERSION),
+            method: method.to_string(),
+            params,
+            id: None,
+        };
+        self.client
+            .send(&self.cx, &JsonRpcMessage::Request(notification))
+            .map_err(transport_error)?;
+        self.serve_one()
+    }
+
+    /// Calls a tool.
+    ///
+    /// Tool failures arrive as a result with `is_error` set; only protocol
+    /// errors such as cancellation or unknown tools are returned as `Err`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the server's JSON-RPC error, if any.
+    pub fn call_tool(
+        &mut self,
+        name: &str,
+        arguments: serde_json::Value,
+    ) -> McpResult<CallToolResult> {
+        self.call(
+            "tools/call",
+            serde_json::json!({ "name": name, "arguments": arguments }),
+        )
+    }
+
+    /// Reads a resource.
+    ///
+    /// # Errors
+    ///
+    /// Returns the server's JSON-RPC error, if any.
+    pub fn read_resource(&mut self, uri: &str) -> McpResult<ReadResourceResult> {
+        self.call("resources/read", serde_json::json!({ "uri": uri }))
+    }
+
+    /// Renders a prompt.
+    ///
+    /// # Errors
+    ///
+    /// Returns the server's JSON-RPC error, if any.
+    pub fn get_prompt(
+        &mut self,
+        name: &str,
+        arguments: HashMap<String, String>,
+    ) -> McpResult<GetPromptResult> {
+        self.call(
+            "prompts/get",
+            serde_json::json!({ "name": name, "arguments": arguments }),
+        )
+    }
+
+    /// Asserts that a tool call succeeds with exactly the given text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the call fails, reports `is_error`, or returns other text.
+    pub fn assert_tool_text(&mut self, name: &str, arguments: serde_json::Value, expected: &str) {
+        let result = self
+            .call_tool(name, arguments)
+            .unwrap_or_else(|e| panic!("tool '{name}' failed: {e}"));
+        assert!(
+            !result.is_error,
+            "tool '{name}' reported an error: {:?}",
+            result.content
+        );
+        assert_eq!(text_of(&result.content), expected, "tool '{name}' output");
+    }
+
+    /// Asserts that a tool call fails with the given JSON-RPC error code.
+    ///
+    /// Returns the error for further assertions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the call succeeds or fails with a different code.
+    pub fn assert_tool_error(
+        &mut self,
+        name: &str,
+        arguments: serde_json::Value,
+        code: McpErrorCode,
+    ) -> McpError {
+        match self.call_tool(name, arguments) {
+            Ok(result) => panic!("tool '{name}' succeeded: {:?}", result.content),
+            Err(e) => {
+                assert_eq!(e.code, code, "tool '{name}' error code: {e}");
+                e
+            }
+        }
+    }
+
+    /// Asserts that a resource reads as exactly the given text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the read fails or returns other text.
+    pub fn assert_resource_text(&mut self, uri: &str, expected: &str) {
+        let result = self
+            .read_resource(uri)
+            .unwrap_or_else(|e| panic!("resource '{uri}' failed: {e}"));
+        let text: String = result
+            .contents
+            .iter()
+            .filter_map(|content| content.text.as_deref())
+            .collect();
+        assert_eq!(text, expected, "resource '{uri}' contents");
+    }
+
+    /// Sends a typed request, initializing the session first if needed.
+    fn call<R: DeserializeOwned>(
+        &mut self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> McpResult<R> {
+        if !self.is_initialized() {
+            self.initialize()?;
+        }
+        let response = self.request(method, Some(params))?;
+        response_result(response)
+    }
+
+    /// Lets the server handle the next queued message on the Lab runtime.
+    fn serve_one(&mut self) -> McpResult<()> {
+        let server = Arc::clone(&self.server);
+        let connection = Arc::clone(&self.connection);
+        let outcome = Arc::new(Mutex::new(None));
+        let outcome_for_task = Arc::clone(&outcome);
+
+        LabRuntimeTarget::block_on(&mut self.runtime, async move {
+            let result = serve_message(&server, &connection);
+            *outcome_for_task
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner) = Some(result);
+        });
+
+        let result = outcome
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        result.unwrap_or_else(|| {
+            Err(McpError::internal_error(
+                "Lab runtime stopped before the server handled the message",
+            ))
+        })
+    }
+}
+
+/// Cancels requests the [`TestServer`] is currently handling.
+///
+/// Hand a clone to a handler to simulate a client cancelling mid-call at a
+/// precise point. The handle does not keep the server alive.
+#[derive(Clone)]
+pub struct CancelHandle {
+    server: Weak<Server>,
+}
+
+impl CancelHandle {
+    /// Delivers `notifications/cancelled` for the given in-flight request.
+    pub fn cancel(&self, request_id: impl Into<RequestId>) {
+        let Some(server) = self.server.upgrade() else {
+            return;
+        };
+        server.handle_cancelled_notification(CancelledParams {
+            request_id: request_id.into(),
+            reason: Some("cancelled by test".to_string()),
+            await_cleanup: None,
+        });
+    }
+}
+
+impl std::fmt::Debug for CancelHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CancelHandle").finish_non_exhaustive()
+    }
+}
+
+/// Receives one message on the server side and sends back its replies.
+fn serve_message(server: &Server, connection: &Mutex<Connection>) -> McpResult<()> {
+    let cx = Cx::for_testing();
+    let mut connection = connection.lock().unwrap_or_else(PoisonError::into_inner);
+    let Connection { transport, session } = &mut *connection;
+
+    let request = match transport.recv(&cx).map_err(transport_error)? {
+        JsonRpcMessage::Request(request) => request,
+        // The harness never answers server-initiated requests.
+        JsonRpcMessage::Response(_) => return Ok(()),
+    };
+
+    let emitted = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&emitted);
+    let notification_sender: NotificationSender = Arc::new(move |notification| {
+        sink.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(notification);
+    });
+    let response = server.dispatch_one_with_sender(&cx, session, request, &notification_sender);
+
+    let emitted = std::mem::take(&mut *emitted.lock().unwrap_or_else(PoisonError::into_inner));
+    for notification in emitted {
+        transport
+            .send(&cx, &JsonRpcMessage::Request(notification))
+            .map_err(transport_error)?;
+    }
+    if let Some(response) = response {
+        transport
+            .send(&cx, &JsonRpcMessage::Response(response))
+            .map_err(transport_error)?;
+    }
+    Ok(())
+}
+
+fn response_result<R: DeserializeOwned>(response: JsonRpcResponse) -> McpResult<R> {
+    if let Some(error) = response.error {
+        let mut err = McpError::new(McpErrorCode::from(error.code), error.message);
+        err.data = error.data;
+        return Err(err);
+    }
+    let result = response
+        .result
+        .ok_or_else(|| McpError::internal_error("No result in response"))?;
+    serde_json::from_value(result)
+        .map_err(|e| McpError::internal_error(format!("Failed to deserialize response: {e}")))
+}
+
+fn to_value(params: impl serde::Serialize) -> McpResult<serde_json::Value> {
+    serde_json::to_value(params)
+        .map_err(|e| McpError::internal_error(format!("Failed to serialize params: {e}")))
+}
+
+fn transport_error(e: TransportError) -> McpError {
+    McpError::internal_error(format!("Transport error: {e}"))
+}
+
+fn text_of(content: &[Content]) -> String {
+    content
+        .iter()
+        .filter_map(|item| match item {
+            Content::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect()
+}
@@ -17,11 +17,11 @@ use fastmcp_core::logging::{info, targets};
 use fastmcp_core::{AuthContext, McpContext, McpError, McpErrorCode, McpResult, SessionState};
 use fastmcp_protocol::{
     CallToolParams, CancelTaskParams, CancelledParams, ClientCapabilities, ClientInfo, Content,
-    GetPromptParams, GetTaskParams, InitializeParams, JsonRpcResponse, ListTasksParams, LogLevel,
-    LogMessageParams, Prompt, PromptArgument, PromptMessage, ReadResourceParams, RequestId,
-    Resource, ResourceContent, ResourceTemplate, ResourceUpdatedNotificationParams, Role,
-    ServerCapabilities, ServerInfo, SetLogLevelParams, SubmitTaskParams, TaskId, TaskStatus,
-    TaskStatusNotificationParams, Tool,
+    GetPromptParams, GetPromptResult, GetTaskParams, InitializeParams, JsonRpcRequest,
+    JsonRpcResponse, ListTasksParams, LogLevel, LogMessageParams, Prompt, PromptArgument,
+    PromptMessage, ReadResourceParams, RequestId, Resource, ResourceContent, ResourceRange,
+    ResourceTemplate, ResourceUpdatedNotificationParams, Role, ServerCapabilities, ServerInfo,
+    SetLogLevelParams, SubmitTaskParams, TaskId, TaskStatus, TaskStatusNotificationParams, Tool,
 };
 
 use crate::bidirectional::{PendingRequests, RequestSender, TransportSendFn};
@@ -30,8 +30,8 @@ use crate::router::Router;
 use crate::session::Session;
 use crate::{
     ActiveRequest, ActiveRequestGuard, AuthRequest, Middleware, MiddlewareDecision,
-    NotificationSender, RequestCompletion, Server, StaticTokenVerifier, TaskManager,
-    TokenAuthProvider,
+    NotificationSender, RequestCompletion, Server, ShutdownReason, StaticTokenVerifier,
+    TaskManager, TokenAuthProvider,
 };
 
 /// Creates a mock request sender for tests that does nothing.
@@ -41,6 +41,69 @@ fn create_test_request_sender() -> RequestSender {
     RequestSender::new(pending, send_fn)
 }
 
+/// Creates an uninitialized session with default capabilities.
+fn create_test_session() -> Session {
+    Session::new(
+        ServerInfo {
+            name: "test-server".to_string(),
+            version: "1.0.0".to_string(),
+        },
+        ServerCapabilities::default(),
+    )
+}
+
+/// Creates a session that has completed the initialize handshake.
+fn initialized_session() -> Session {
+    initialized_session_with(ClientCapabilities::default())
+}
+
+/// Creates an initialized session for a client with the given capabilities.
+fn initialized_session_with(capabilities: ClientCapabilities) -> Session {
+    let mut session = create_test_session();
+    session.initialize(
+        ClientInfo {
+            name: "test-client".to_string(),
+            version: "1.0.0".to_string(),
+        },
+        capabilities,
+        "2024-11-05".to_string(),
+    );
+    session
+}
+
+/// Builds the `initialize` request (ID 1) of a client named `client_name`.
+fn initialize_request(client_name: &str) -> JsonRpcRequest {
+    let init = InitializeParams {
+        protocol_version: "2024-11-05".to_string(),
+        capabilities: ClientCapabilities::default(),
+        client_info: ClientInfo {
+            name: client_name.to_string(),
+            version: "1.0.0".to_string(),
+        },
+    };
+    JsonRpcRequest::new("initialize", Some(serde_json::to_value(init).unwrap()), 1)
+}
+
+/// Builds a `tools/call` request for [`GreetTool`].
+fn greet_request(id: i64, name: &str) -> JsonRpcRequest {
+    let params = CallToolParams {
+        name: "greet".to_string(),
+        arguments: Some(serde_json::json!({ "name": name })),
+        meta: None,
+    };
+    JsonRpcRequest::new(
+        "tools/call",
+        Some(serde_json::to_value(params).unwrap()),
+        id,
+    )
+}
+
+/// Returns the text of a successful [`GreetTool`] response.
+fn greeting(response: &JsonRpcResponse) -> String {
+    let result = response.result.as_ref().expect("successful tool call");
+    result["content"][0]["text"].as_str().unwrap().to_string()
+}
+
 // ============================================================================
 // Test Tool Handlers
 // ============================================================================
@@ -501,6 +564,8 @@ impl ResourceHandler for StaticResource {
             mime_type: Some("text/plain".to_string()),
             text: Some(self.content.clone()),
             blob: None,
+            etag: None,
+            last_modified: None,
         }])
     }
 }
@@ -530,6 +595,8 @@ impl ResourceHandler for CancellableResource {
             mime_type: Some("text/plain".to_string()),
             text: Some("Resource content".to_string()),
             blob: None,
+            etag: None,
+            last_modified: None,
         }])
     }
 }
@@ -559,6 +626,7 @@ impl ResourceHandler for TemplateResource {
             icon: None,
             version: None,
             tags: vec![],
+            parameters: vec![],
         })
     }
 
@@ -582,6 +650,8 @@ impl ResourceHandler for TemplateResource {
             mime_type: Some("text/plain".to_string()),
             text: Some(format!("Template {id}")),
             blob: None,
+            etag: None,
+            last_modified: None,
         }])
     }
 }
@@ -611,6 +681,7 @@ impl ResourceHandler for SpecificTemplateResource {
             icon: None,
             version: None,
             tags: vec![],
+            parameters: vec![],
         })
     }
 
@@ -634,6 +705,8 @@ impl ResourceHandler for SpecificTemplateResource {
             mime_type: Some("text/plain".to_string()),
             text: Some(format!("Specific {id}")),
             blob: None,
+            etag: None,
+            last_modified: None,
         }])
     }
 }
@@ -674,6 +747,7 @@ impl ResourceHandler for LoggingTemplateResource {
             icon: None,
             version: None,
             tags: vec![],
+            parameters: vec![],
         })
     }
 
@@ -716,6 +790,8 @@ impl ResourceHandler for LoggingTemplateResource {
             mime_type: Some("text/plain".to_string()),
             text: Some(response),
             blob: None,
+            etag: None,
+            last_modified: None,
         }])
     }
 }
@@ -758,6 +834,160 @@ impl PromptHandler for GreetingPrompt {
     }
 }
 
+/// A prompt that introduces a referenced resource.
+struct EmbeddingPrompt {
+    uri: &'static str,
+}
+
+impl PromptHandler for EmbeddingPrompt {
+    fn definition(&self) -> Prompt {
+        Prompt {
+            name: "review".to_string(),
+            description: Some("Reviews a resource".to_string()),
+            arguments: vec![],
+            icon: None,
+            version: None,
+            tags: vec![],
+        }
+    }
+
+    fn get(
+        &self,
+        _ctx: &McpContext,
+        _arguments: HashMap<String, String>,
+    ) -> McpResult<Vec<PromptMessage>> {
+        Ok(vec![
+            PromptMessage {
+                role: Role::User,
+                content: Content::Text {
+                    text: "Review this document:".to_string(),
+                },
+            },
+            PromptMessage {
+                role: Role::User,
+                content: Content::Resource {
+                    resource: ResourceContent::reference(self.uri),
+                },
+            },
+        ])
+    }
+}
+
+/// A tool, resource and prompt that always panic.
+struct PanickingHandler;
+
+impl ToolHandler for PanickingHandler {
+    fn definition(&self) -> Tool {
+        Tool {
+            name: "explode".to_string(),
+            description: Some("Panics when called".to_string()),
+            input_schema: serde_json::json!({"type": "object"}),
+            output_schema: None,
+            icon: None,
+            version: None,
+            tags: vec![],
+            annotations: None,
+        }
+    }
+
+    fn call(&self, _ctx: &McpContext, _args: serde_json::Value) -> McpResult<Vec<Content>> {
+        panic!("secret internal detail");
+    }
+}
+
+impl ResourceHandler for PanickingHandler {
+    fn definition(&self) -> Resource {
+        Resource {
+            uri: "resource://explode".to_string(),
+            name: "Panicking Resource".to_string(),
+            description: None,
+            mime_type: None,
+            icon: None,
+            version: None,
+            tags: vec![],
+        }
+    }
+
+    fn read(&self, _ctx: &McpContext) -> McpResult<Vec<ResourceContent>> {
+        panic!("secret internal detail");
+    }
+}
+
+impl PromptHandler for PanickingHandler {
+    fn definition(&self) -> Prompt {
+        Prompt {
+            name: "explode".to_string(),
+            description: None,
+            arguments: vec![],
+            icon: None,
+            version: None,
+            tags: vec![],
+        }
+    }
+
+    fn get(
+        &self,
+        _ctx: &McpContext,
+        _arguments: HashMap<String, String>,
+    ) -> McpResult<Vec<PromptMessage>> {
+        panic!("secret internal detail");
+    }
+}
+
+/// A template resource that echoes its extracted URI parameters.
+struct EchoTemplateResource {
+    uri_template: &'static str,
+}
+
+impl ResourceHandler for EchoTemplateResource {
+    fn definition(&self) -> Resource {
+        Resource {
+            uri: self.uri_template.to_string(),
+            name: "Echo Template".to_string(),
+            description: None,
+            mime_type: Some("text/plain".to_string()),
+            icon: None,
+            version: None,
+            tags: vec![],
+        }
+    }
+
+    fn template(&self) -> Option<ResourceTemplate> {
+        Some(ResourceTemplate {
+            uri_template: self.uri_template.to_string(),
+            name: "Echo Template".to_string(),
+            description: None,
+            mime_type: Some("text/plain".to_string()),
+            icon: None,
+            version: None,
+            tags: vec![],
+            parameters: vec![],
+        })
+    }
+
+    fn read(&self, _ctx: &McpContext) -> McpResult<Vec<ResourceContent>> {
+        Err(McpError::invalid_params("uri parameters required"))
+    }
+
+    fn read_with_uri(
+        &self,
+        _ctx: &McpContext,
+        uri: &str,
+        params: &UriParams,
+    ) -> McpResult<Vec<ResourceContent>> {
+        let mut pairs: Vec<String> = params.iter().map(|(k, v)| format!("{k}={v}")).collect();
+        pairs.sort();
+        Ok(vec![ResourceContent {
+            uri: uri.to_string(),
+            mime_type: Some("text/plain".to_string()),
+            text: Some(pairs.join(",")),
+            blob: None,
+            etag: None,
+            last_modified: None,
+        }])
+    }
+}
+
 // ============================================================================
 // Router Tests
 // ============================================================================
@@ -766,6 +996,7 @@ impl PromptHandler for GreetingPrompt {
 mod router_tests {
     use super::*;
     use fastmcp_protocol::ListResourceTemplatesParams;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     /// Creates a test router with all handlers registered.
     fn create_test_router() -> Router {
@@ -794,6 +1025,7 @@ mod router_tests {
             icon: None,
             version: None,
             tags: vec![],
+            parameters: vec![],
         });
 
         // Register prompts
@@ -802,17 +1034,6 @@ mod router_tests {
         router
     }
 
-    /// Creates a test session.
-    fn create_test_session() -> Session {
-        Session::new(
-            ServerInfo {
-                name: "test-server".to_string(),
-                version: "1.0.0".to_string(),
-            },
-            ServerCapabilities::default(),
-        )
-    }
-
     #[test]
     fn test_middleware_ordering_on_response() {
         let events = Arc::new(std::sync::Mutex::new(Vec::new()));
@@ -858,6 +1079,113 @@ mod router_tests {
         assert_eq!(recorded, vec!["A:req", "B:req", "B:resp", "A:resp"]);
     }
 
+    #[test]
+    fn test_dispatch_one_initializes_and_calls_tool() {
+        let server = Server::new("test-server", "1.0.0").tool(GreetTool).build();
+        let cx = Cx::for_testing();
+        let mut session = Session::new(server.info().clone(), server.capabilities().clone());
+
+        let init = InitializeParams {
+            protocol_version: "2024-11-05".to_string(),
+            capabilities: ClientCapabilities::default(),
+            client_info: ClientInfo {
+                name: "test-client".to_string(),
+                version: "1.0.0".to_string(),
+            },
+        };
+        let response = server
+            .dispatch_one(
+                &cx,
+                &mut session,
+                fastmcp_protocol::JsonRpcRequest::new(
+                    "initialize",
+                    Some(serde_json::to_value(init).expect("params")),
+                    1,
+                ),
+            )
+            .expect("initialize response");
+        assert!(response.error.is_none());
+
+        let params = CallToolParams {
+            name: "greet".to_string(),
+            arguments: Some(serde_json::json!({"name": "Ada"})),
+            meta: None,
+        };
+        let response = server
+            .dispatch_one(
+                &cx,
+                &mut session,
+                fastmcp_protocol::JsonRpcRequest::new(
+                    "tools/call",
+                    Some(serde_json::to_value(params).expect("params")),
+                    2,
+                ),
+            )
+            .expect("tools/call response");
+        assert!(response.error.is_none(), "expected successful response");
+        let result = response.result.expect("result");
+        assert_eq!(result["content"][0]["text"], "Hello, Ada!");
+    }
+
+    /// A tool that reports the method and request ID it is serving.
+    struct WhoAmITool;
+
+    impl ToolHandler for WhoAmITool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: "whoami".to_string(),
+                description: None,
+                input_schema: serde_json::json!({"type": "object"}),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+                annotations: None,
+            }
+        }
+
+        fn call(&self, ctx: &McpContext, _args: serde_json::Value) -> McpResult<Vec<Content>> {
+            Ok(vec![Content::Text {
+                text: format!(
+                    "{}|{}",
+                    ctx.method().unwrap_or("-"),
+                    ctx.request_id_display().unwrap_or("-")
+                ),
+            }])
+        }
+    }
+
+    #[test]
+    fn test_handler_sees_method_and_original_request_id() {
+        let server = Server::new("test-server", "1.0.0").tool(WhoAmITool).build();
+        let cx = Cx::for_testing();
+        let mut session = create_test_session();
+        session.initialize(
+            ClientInfo {
+                name: "test-client".to_string(),
+                version: "1.0.0".to_string(),
+            },
+            ClientCapabilities::default(),
+            "2024-11-05".to_string(),
+        );
+
+        let params = CallToolParams {
+            name: "whoami".to_string(),
+            arguments: None,
+            meta: None,
+        };
+        let request = fastmcp_protocol::JsonRpcRequest::new(
+            "tools/call",
+            Some(serde_json::to_value(params).expect("params")),
+            "req-abc-123",
+        );
+        let response = server
+            .dispatch_one(&cx, &mut session, request)
+            .expect("response");
+        let result = response.result.expect("result");
+        assert_eq!(result["content"][0]["text"], "tools/call|req-abc-123");
+    }
+
     #[test]
     fn test_middleware_short_circuit_runs_response_stack() {
         let events = Arc::new(std::sync::Mutex::new(Vec::new()));
@@ -1173,19 +1501,17 @@ mod router_tests {
     }
 
     #[test]
-    fn test_auth_provider_protects_resource_access() {
-        let verifier = StaticTokenVerifier::new([(
-            "resource-token",
-            AuthContext::with_subject("resource-user"),
-        )]);
-        let provider = TokenAuthProvider::new(verifier);
-
+    fn test_auth_failure_carries_www_authenticate_challenge() {
+        const METADATA_URL: &str = "https://mcp.example.com/.well-known/oauth-protected-resource";
+        let provider = TokenAuthProvider::new(StaticTokenVerifier::new([(
+            "good-token",
+            AuthContext::with_subject("user-1"),
+        )]));
         let server = Server::new("test-server", "1.0.0")
-            .resource(StaticResource {
-                uri: "resource://secure".to_string(),
-                content: "secret".to_string(),
-            })
+            .tool(GreetTool)
             .auth_provider(provider)
+            .authorization_server_url("https://auth.example.com")
+            .resource_url("https://mcp.example.com")
             .build();
         let cx = Cx::for_testing();
         let mut session = create_test_session();
@@ -1197,37 +1523,155 @@ mod router_tests {
             ClientCapabilities::default(),
             "2024-11-05".to_string(),
         );
-
         let sender: NotificationSender = Arc::new(|_| {});
-        let request = fastmcp_protocol::JsonRpcRequest::new(
-            "resources/read",
-            Some(serde_json::json!({"uri": "resource://secure"})),
-            8,
-        );
-        let response = server
-            .handle_request(
-                &cx,
-                &mut session,
-                request,
-                &sender,
-                &create_test_request_sender(),
+
+        let mut call = |id: i64, auth: Option<&str>| {
+            let mut params = serde_json::json!({
+                "name": "greet",
+                "arguments": { "name": "Ada" }
+            });
+            if let Some(auth) = auth {
+                params["auth"] = serde_json::json!(auth);
+            }
+            let request = fastmcp_protocol::JsonRpcRequest::new("tools/call", Some(params), id);
+            server
+                .handle_request(
+                    &cx,
+                    &mut session,
+                    request,
+                    &sender,
+                    &create_test_request_sender(),
+                )
+                .expect("response")
+                .error
+                .expect("auth error")
+        };
+
+        let error = call(1, Some("Bearer bad-token"));
+        assert_eq!(error.code, i32::from(McpErrorCode::ResourceForbidden));
+        let challenge = fastmcp_core::AuthChallenge::from_error_data(error.data.as_ref())
+            .expect("challenge attached");
+        assert_eq!(challenge.realm.as_deref(), Some("test-server"));
+        assert_eq!(challenge.resource_metadata.as_deref(), Some(METADATA_URL));
+        assert_eq!(challenge.error.as_deref(), Some("invalid_token"));
+        assert_eq!(
+            challenge.header_value(),
+            format!(
+                "Bearer realm=\"test-server\", resource_metadata=\"{METADATA_URL}\", \
+                 error=\"invalid_token\", error_description=\"Invalid access token\""
             )
-            .expect("response");
-        assert!(response.is_error(), "expected auth error");
+        );
 
-        let request = fastmcp_protocol::JsonRpcRequest::new(
-            "resources/read",
-            Some(serde_json::json!({
-                "uri": "resource://secure",
-                "auth": "Bearer resource-token"
-            })),
-            9,
+        let error = call(2, None);
+        let challenge = fastmcp_core::AuthChallenge::from_error_data(error.data.as_ref())
+            .expect("challenge attached");
+        assert!(challenge.error.is_none());
+        assert_eq!(
+            challenge.header_value(),
+            format!("Bearer realm=\"test-server\", resource_metadata=\"{METADATA_URL}\"")
         );
-        let response = server
-            .handle_request(
-                &cx,
-                &mut session,
-                request,
+    }
+
+    #[test]
+    fn test_protected_resource_metadata_lists_authorization_servers() {
+        let server = Server::new("test-server", "1.0.0")
+            .authorization_server_url("https://auth.example.com")
+            .authorization_server_url("https://backup-auth.example.com")
+            .scopes_supported(["files:read", "files:write"])
+            .build();
+
+        let doc = server.protected_resource_metadata("https://mcp.example.com/mcp");
+        assert_eq!(doc["resource"], "https://mcp.example.com/mcp");
+        assert_eq!(
+            doc["authorization_servers"],
+            serde_json::json!([
+                "https://auth.example.com",
+                "https://backup-auth.example.com"
+            ])
+        );
+        assert_eq!(
+            doc["scopes_supported"],
+            serde_json::json!(["files:read", "files:write"])
+        );
+        assert_eq!(
+            doc["bearer_methods_supported"],
+            serde_json::json!(["header"])
+        );
+    }
+
+    #[test]
+    fn test_auth_challenge_header_value_escapes_quotes() {
+        let challenge = fastmcp_core::AuthChallenge {
+            realm: Some("say \"hi\"\r\n".to_string()),
+            error_description: Some(r"back\slash".to_string()),
+            ..fastmcp_core::AuthChallenge::default()
+        };
+        assert_eq!(
+            challenge.header_value(),
+            r#"Bearer realm="say \"hi\"", error_description="back\\slash""#
+        );
+        assert_eq!(
+            fastmcp_core::AuthChallenge::default().header_value(),
+            "Bearer"
+        );
+    }
+
+    #[test]
+    fn test_auth_provider_protects_resource_access() {
+        let verifier = StaticTokenVerifier::new([(
+            "resource-token",
+            AuthContext::with_subject("resource-user"),
+        )]);
+        let provider = TokenAuthProvider::new(verifier);
+
+        let server = Server::new("test-server", "1.0.0")
+            .resource(StaticResource {
+                uri: "resource://secure".to_string(),
+                content: "secret".to_string(),
+            })
+            .auth_provider(provider)
+            .build();
+        let cx = Cx::for_testing();
+        let mut session = create_test_session();
+        session.initialize(
+            ClientInfo {
+                name: "test-client".to_string(),
+                version: "1.0.0".to_string(),
+            },
+            ClientCapabilities::default(),
+            "2024-11-05".to_string(),
+        );
+
+        let sender: NotificationSender = Arc::new(|_| {});
+        let request = fastmcp_protocol::JsonRpcRequest::new(
+            "resources/read",
+            Some(serde_json::json!({"uri": "resource://secure"})),
+            8,
+        );
+        let response = server
+            .handle_request(
+                &cx,
+                &mut session,
+                request,
+                &sender,
+                &create_test_request_sender(),
+            )
+            .expect("response");
+        assert!(response.is_error(), "expected auth error");
+
+        let request = fastmcp_protocol::JsonRpcRequest::new(
+            "resources/read",
+            Some(serde_json::json!({
+                "uri": "resource://secure",
+                "auth": "Bearer resource-token"
+            })),
+            9,
+        );
+        let response = server
+            .handle_request(
+                &cx,
+                &mut session,
+                request,
                 &sender,
                 &create_test_request_sender(),
             )
@@ -1881,6 +2325,27 @@ mod router_tests {
         assert!(cx.is_cancel_requested());
     }
 
+    #[test]
+    fn test_cancelled_notification_reports_user_cancel_reason() {
+        let server = Server::new("test-server", "1.0.0").build();
+        let request_id = RequestId::Number(98);
+        let cx = Cx::for_testing();
+        server.active_requests.lock().unwrap().insert(
+            request_id.clone(),
+            ActiveRequest::new(cx.clone(), Arc::new(RequestCompletion::new())),
+        );
+        let ctx = McpContext::new(cx, 1);
+        assert_eq!(ctx.cancel_reason(), None);
+
+        server.handle_cancelled_notification(CancelledParams {
+            request_id,
+            reason: None,
+            await_cleanup: None,
+        });
+
+        assert_eq!(ctx.cancel_reason(), Some(CancelKind::User));
+    }
+
     #[test]
     fn test_cancelled_notification_await_cleanup_waits_for_completion() {
         let server = Server::new("test-server", "1.0.0").build();
@@ -1942,6 +2407,66 @@ mod router_tests {
         assert!(!guard_map.contains_key(&request_id));
     }
 
+    #[test]
+    fn test_requests_answered_after_active_requests_lock_poisoned() {
+        let server = Server::new("test-server", "1.0.0").tool(GreetTool).build();
+        std::thread::scope(|scope| {
+            let poisoner = scope.spawn(|| {
+                let _guard = server.active_requests.lock().unwrap();
+                panic!("handler panicked while holding active_requests");
+            });
+            assert!(poisoner.join().is_err());
+        });
+        assert!(server.active_requests.is_poisoned());
+
+        let cx = Cx::for_testing();
+        let mut session = Session::new(server.info().clone(), server.capabilities().clone());
+        let init = InitializeParams {
+            protocol_version: "2024-11-05".to_string(),
+            capabilities: ClientCapabilities::default(),
+            client_info: ClientInfo {
+                name: "test-client".to_string(),
+                version: "1.0.0".to_string(),
+            },
+        };
+        let response = server
+            .dispatch_one(
+                &cx,
+                &mut session,
+                fastmcp_protocol::JsonRpcRequest::new(
+                    "initialize",
+                    Some(serde_json::to_value(init).unwrap()),
+                    1,
+                ),
+            )
+            .expect("initialize response");
+        assert!(response.error.is_none());
+
+        let params = CallToolParams {
+            name: "greet".to_string(),
+            arguments: Some(serde_json::json!({"name": "Ada"})),
+            meta: None,
+        };
+        let response = server
+            .dispatch_one(
+                &cx,
+                &mut session,
+                fastmcp_protocol::JsonRpcRequest::new(
+                    "tools/call",
+                    Some(serde_json::to_value(params).unwrap()),
+                    2,
+                ),
+            )
+            .expect("tools/call response");
+        assert!(response.error.is_none());
+        assert_eq!(
+            response.result.unwrap()["content"][0]["text"],
+            "Hello, Ada!"
+        );
+        assert!(!server.active_requests.is_poisoned());
+        assert!(server.active_requests.lock().unwrap().is_empty());
+    }
+
     #[test]
     fn test_active_request_registry_concurrent_add_remove() {
         let server = Arc::new(Server::new("test-server", "1.0.0").build());
@@ -2541,8 +3066,17 @@ mod router_tests {
             meta: None,
         };
 
-        let result =
-            router.handle_tools_call(&cx, 1, params, &budget, SessionState::new(), None, None);
+        let result = router.handle_tools_call(
+            &cx,
+            1,
+            None,
+            params,
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+            false,
+        );
 
         assert!(result.is_ok());
         let call_result = result.unwrap();
@@ -2568,8 +3102,17 @@ mod router_tests {
             meta: None,
         };
 
-        let result =
-            router.handle_tools_call(&cx, 1, params, &budget, SessionState::new(), None, None);
+        let result = router.handle_tools_call(
+            &cx,
+            1,
+            None,
+            params,
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+            false,
+        );
 
         assert!(result.is_err());
         let err = result.unwrap_err();
@@ -2588,8 +3131,17 @@ mod router_tests {
             meta: None,
         };
 
-        let result =
-            router.handle_tools_call(&cx, 1, params, &budget, SessionState::new(), None, None);
+        let result = router.handle_tools_call(
+            &cx,
+            1,
+            None,
+            params,
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+            false,
+        );
 
         // Tool errors are returned as content with is_error=true
         assert!(result.is_ok());
@@ -2611,8 +3163,17 @@ mod router_tests {
             meta: None,
         };
 
-        let result =
-            router.handle_tools_call(&cx, 1, params, &budget, SessionState::new(), None, None);
+        let result = router.handle_tools_call(
+            &cx,
+            1,
+            None,
+            params,
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+            false,
+        );
 
         // Request should be cancelled before handler runs
         assert!(result.is_err());
@@ -2630,8 +3191,17 @@ mod router_tests {
             meta: None,
         };
 
-        let result =
-            router.handle_tools_call(&cx, 1, params, &budget, SessionState::new(), None, None);
+        let result = router.handle_tools_call(
+            &cx,
+            1,
+            None,
+            params,
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+            false,
+        );
 
         // Request should fail due to exhausted budget
         assert!(result.is_err());
@@ -2647,11 +3217,22 @@ mod router_tests {
 
         let params = ReadResourceParams {
             uri: "resource://test".to_string(),
+            range: None,
+            if_none_match: None,
+            if_modified_since: None,
             meta: None,
         };
 
-        let result =
-            router.handle_resources_read(&cx, 1, &params, &budget, SessionState::new(), None, None);
+        let result = router.handle_resources_read(
+            &cx,
+            1,
+            None,
+            &params,
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+        );
 
         assert!(result.is_ok());
         let read_result = result.unwrap();
@@ -2663,1324 +3244,1716 @@ mod router_tests {
     }
 
     #[test]
-    fn test_handle_resources_read_template_match() {
-        let router = create_test_router();
+    fn test_handle_tools_call_panicking_tool_returns_error_result() {
+        let mut router = create_test_router();
+        router.add_tool(PanickingHandler);
         let cx = Cx::for_testing();
         let budget = Budget::INFINITE;
 
-        let params = ReadResourceParams {
-            uri: "resource://abc".to_string(),
+        let params = CallToolParams {
+            name: "explode".to_string(),
+            arguments: None,
             meta: None,
         };
+        let result = router
+            .handle_tools_call(
+                &cx,
+                1,
+                None,
+                params,
+                &budget,
+                SessionState::new(),
+                None,
+                None,
+                false,
+            )
+            .expect("panic is reported as a tool error, not a failure");
+        assert!(result.is_error);
+        match &result.content[0] {
+            Content::Text { text } => {
+                assert!(text.contains("Internal error"));
+                assert!(!text.contains("secret"));
+            }
+            _ => panic!("Expected text content"),
+        }
 
-        let result =
-            router.handle_resources_read(&cx, 1, &params, &budget, SessionState::new(), None, None);
-
-        assert!(result.is_ok(), "Expected Ok, got Err: {:?}", result.err());
-        let read_result = result.unwrap();
-        assert_eq!(
-            read_result.contents[0].text,
-            Some("Template abc".to_string())
-        );
+        // The router keeps serving subsequent calls
+        let params = CallToolParams {
+            name: "greet".to_string(),
+            arguments: Some(serde_json::json!({"name": "Alice"})),
+            meta: None,
+        };
+        let result = router
+            .handle_tools_call(
+                &cx,
+                2,
+                None,
+                params,
+                &budget,
+                SessionState::new(),
+                None,
+                None,
+                false,
+            )
+            .expect("subsequent call succeeds");
+        assert!(!result.is_error);
     }
 
     #[test]
-    fn test_handle_resources_read_template_match_percent_decoded() {
-        let router = create_test_router();
+    fn test_panicking_resource_and_prompt_return_internal_error() {
+        let mut router = create_test_router();
+        router.add_resource(PanickingHandler);
+        router.add_prompt(PanickingHandler);
         let cx = Cx::for_testing();
         let budget = Budget::INFINITE;
 
         let params = ReadResourceParams {
-            uri: "resource://hello%20world".to_string(),
+            uri: "resource://explode".to_string(),
+            range: None,
+            if_none_match: None,
+            if_modified_since: None,
             meta: None,
         };
+        let err = router
+            .handle_resources_read(
+                &cx,
+                1,
+                None,
+                &params,
+                &budget,
+                SessionState::new(),
+                None,
+                None,
+            )
+            .unwrap_err();
+        assert_eq!(err.code, McpErrorCode::InternalError);
+        assert!(!err.message.contains("secret"));
 
-        let result =
-            router.handle_resources_read(&cx, 1, &params, &budget, SessionState::new(), None, None);
+        let params = GetPromptParams {
+            name: "explode".to_string(),
+            arguments: None,
+            meta: None,
+        };
+        let err = router
+            .handle_prompts_get(
+                &cx,
+                2,
+                None,
+                params,
+                &budget,
+                SessionState::new(),
+                None,
+                None,
+            )
+            .unwrap_err();
+        assert_eq!(err.code, McpErrorCode::InternalError);
 
-        assert!(result.is_ok(), "Expected Ok, got Err: {:?}", result.err());
-        let read_result = result.unwrap();
-        assert_eq!(
-            read_result.contents[0].text,
-            Some("Template hello world".to_string())
+        // Other resources are still readable
+        let params = ReadResourceParams {
+            uri: "resource://test".to_string(),
+            range: None,
+            if_none_match: None,
+            if_modified_since: None,
+            meta: None,
+        };
+        assert!(
+            router
+                .handle_resources_read(
+                    &cx,
+                    3,
+                    None,
+                    &params,
+                    &budget,
+                    SessionState::new(),
+                    None,
+                    None
+                )
+                .is_ok()
         );
     }
 
-    #[test]
-    fn test_handle_resources_read_template_match_with_slash() {
-        let router = create_test_router();
-        let cx = Cx::for_testing();
-        let budget = Budget::INFINITE;
-
+    fn read_uri(router: &Router, uri: &str) -> McpResult<String> {
         let params = ReadResourceParams {
-            uri: "resource://foo/bar".to_string(),
+            uri: uri.to_string(),
+            range: None,
+            if_none_match: None,
+            if_modified_since: None,
             meta: None,
         };
-
-        let result =
-            router.handle_resources_read(&cx, 1, &params, &budget, SessionState::new(), None, None);
-
-        assert!(result.is_ok(), "Expected Ok, got Err: {:?}", result.err());
-        let read_result = result.unwrap();
-        assert_eq!(
-            read_result.contents[0].text,
-            Some("Template foo/bar".to_string())
-        );
+        let result = router.handle_resources_read(
+            &Cx::for_testing(),
+            1,
+            None,
+            &params,
+            &Budget::INFINITE,
+            SessionState::new(),
+            None,
+            None,
+        )?;
+        Ok(result.contents[0].text.clone().unwrap_or_default())
     }
 
     #[test]
-    fn test_handle_resources_read_template_precedence() {
+    fn test_typed_template_accepts_matching_int() {
         let mut router = Router::new();
-        router.add_resource(TemplateResource);
-        router.add_resource(SpecificTemplateResource);
+        router.add_resource(EchoTemplateResource {
+            uri_template: "item://{id:int}",
+        });
 
-        let cx = Cx::for_testing();
-        let budget = Budget::INFINITE;
+        assert_eq!(read_uri(&router, "item://42").unwrap(), "id=42");
+    }
 
-        let params = ReadResourceParams {
-            uri: "resource://foo/123".to_string(),
-            meta: None,
-        };
+    #[test]
+    fn test_typed_template_rejects_non_numeric() {
+        let mut router = Router::new();
+        router.add_resource(EchoTemplateResource {
+            uri_template: "item://{id:int}",
+        });
 
-        let result =
-            router.handle_resources_read(&cx, 1, &params, &budget, SessionState::new(), None, None);
+        let err = read_uri(&router, "item://abc").unwrap_err();
+        assert_eq!(err.code, McpErrorCode::InvalidParams);
+        assert!(err.message.contains("expects int"));
+    }
+
+    #[test]
+    fn test_typed_template_multiple_params() {
+        let mut router = Router::new();
+        router.add_resource(EchoTemplateResource {
+            uri_template: "db://{table}/{id:int}/{active:bool}",
+        });
 
-        assert!(result.is_ok(), "Expected Ok, got Err: {:?}", result.err());
-        let read_result = result.unwrap();
         assert_eq!(
-            read_result.contents[0].text,
-            Some("Specific 123".to_string())
+            read_uri(&router, "db://users/7/true").unwrap(),
+            "active=true,id=7,table=users"
         );
+        let err = read_uri(&router, "db://users/7/yes").unwrap_err();
+        assert_eq!(err.code, McpErrorCode::InvalidParams);
     }
 
     #[test]
-    fn test_e2e_template_logging() {
-        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+    fn test_typed_template_mismatch_falls_through_to_untyped() {
         let mut router = Router::new();
-        router.add_resource(LoggingTemplateResource {
-            template: "file://{path}",
-            events: events.clone(),
+        router.add_resource(EchoTemplateResource {
+            uri_template: "item://{id:int}",
+        });
+        router.add_resource(EchoTemplateResource {
+            uri_template: "item://{slug}",
         });
 
-        let cx = Cx::for_testing();
-        let budget = Budget::INFINITE;
-        let params = ReadResourceParams {
-            uri: "file://dir%2Ffile.txt".to_string(),
-            meta: None,
-        };
+        assert_eq!(read_uri(&router, "item://42").unwrap(), "id=42");
+        assert_eq!(read_uri(&router, "item://abc").unwrap(), "slug=abc");
+    }
 
-        let result =
-            router.handle_resources_read(&cx, 1, &params, &budget, SessionState::new(), None, None);
+    #[test]
+    fn test_static_resource_takes_precedence_over_template() {
+        let mut router = Router::new();
+        // Register the template first so registration order can't explain the result
+        router.add_resource(EchoTemplateResource {
+            uri_template: "resource://{id}",
+        });
+        router.add_resource(StaticResource {
+            uri: "resource://special".to_string(),
+            content: "static".to_string(),
+        });
+
+        assert_eq!(read_uri(&router, "resource://special").unwrap(), "static");
+        assert_eq!(read_uri(&router, "resource://other").unwrap(), "id=other");
+    }
+
+    #[test]
+    fn test_more_literal_template_wins() {
+        let mut router = Router::new();
+        router.add_resource(EchoTemplateResource {
+            uri_template: "repo://{owner}/{name}",
+        });
+        router.add_resource(EchoTemplateResource {
+            uri_template: "repo://{owner}/issues",
+        });
 
-        assert!(result.is_ok(), "Expected Ok, got Err: {:?}", result.err());
-        let read_result = result.unwrap();
         assert_eq!(
-            read_result.contents[0].text.as_deref(),
-            Some("Logged dir/file.txt")
+            read_uri(&router, "repo://acme/issues").unwrap(),
+            "owner=acme"
         );
-
-        let guard = events.lock().expect("template log lock poisoned");
-        assert_eq!(guard.len(), 1);
-        let entry = &guard[0];
-        assert_eq!(entry.template, "file://{path}");
-        assert_eq!(entry.uri, "file://dir%2Ffile.txt");
         assert_eq!(
-            entry.params.get("path").map(String::as_str),
-            Some("dir/file.txt")
+            read_uri(&router, "repo://acme/widgets").unwrap(),
+            "name=widgets,owner=acme"
         );
-        assert_eq!(entry.response, "Logged dir/file.txt");
     }
 
     #[test]
-    fn test_handle_resources_read_not_found() {
-        let router = create_test_router();
-        let cx = Cx::for_testing();
-        let budget = Budget::INFINITE;
-
-        // Use a scheme that doesn't match any registered resources or templates
-        let params = ReadResourceParams {
-            uri: "file://nonexistent".to_string(),
-            meta: None,
-        };
-
-        let result =
-            router.handle_resources_read(&cx, 1, &params, &budget, SessionState::new(), None, None);
-
-        assert!(result.is_err());
+    fn test_equally_specific_templates_use_registration_order() {
+        for (first, second, expected) in [
+            ("doc://{b}", "doc://{a}", "b=x"),
+            ("doc://{a}", "doc://{b}", "a=x"),
+        ] {
+            let mut router = Router::new();
+            router.add_resource(EchoTemplateResource {
+                uri_template: first,
+            });
+            router.add_resource(EchoTemplateResource {
+                uri_template: second,
+            });
+            assert_eq!(read_uri(&router, "doc://x").unwrap(), expected);
+        }
     }
 
     #[test]
-    fn test_handle_resources_read_with_cancellation() {
+    fn test_handle_resources_read_with_range() {
         let router = create_test_router();
         let cx = Cx::for_testing();
-        cx.set_cancel_requested(true);
         let budget = Budget::INFINITE;
 
         let params = ReadResourceParams {
             uri: "resource://test".to_string(),
+            range: Some(ResourceRange::new(5, Some(4))),
+            if_none_match: None,
+            if_modified_since: None,
             meta: None,
         };
 
-        let result =
-            router.handle_resources_read(&cx, 1, &params, &budget, SessionState::new(), None, None);
+        let result = router.handle_resources_read(
+            &cx,
+            1,
+            None,
+            &params,
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+        );
 
-        // Should be cancelled
-        assert!(result.is_err());
+        let read_result = result.expect("ranged read should succeed");
+        assert_eq!(read_result.contents[0].text, Some("cont".to_string()));
     }
 
     #[test]
-    fn test_handle_prompts_get_success() {
-        let router = create_test_router();
+    fn test_handle_resources_read_rejects_oversized() {
+        let mut router = create_test_router();
+        router.set_max_resource_bytes(Some(8));
         let cx = Cx::for_testing();
         let budget = Budget::INFINITE;
 
-        let params = GetPromptParams {
-            name: "greeting".to_string(),
-            arguments: Some({
-                let mut map = HashMap::new();
-                map.insert("name".to_string(), "Bob".to_string());
-                map
-            }),
+        let params = ReadResourceParams {
+            uri: "resource://test".to_string(),
+            range: None,
+            if_none_match: None,
+            if_modified_since: None,
             meta: None,
         };
+        let result = router.handle_resources_read(
+            &cx,
+            1,
+            None,
+            &params,
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+        );
+        let err = result.expect_err("oversized read should fail");
+        assert_eq!(err.code, McpErrorCode::InvalidRequest);
+        assert!(err.message.contains("exceeding the 8-byte limit"));
 
-        let result =
-            router.handle_prompts_get(&cx, 1, params, &budget, SessionState::new(), None, None);
-
-        assert!(result.is_ok());
-        let get_result = result.unwrap();
-        assert_eq!(get_result.messages.len(), 1);
-
-        assert!(matches!(
-            get_result.messages[0].content,
-            Content::Text { .. }
-        ));
-        let Content::Text { text } = &get_result.messages[0].content else {
-            return;
+        // A range within the limit still succeeds
+        let params = ReadResourceParams {
+            uri: "resource://test".to_string(),
+            range: Some(ResourceRange::new(0, Some(8))),
+            if_none_match: None,
+            if_modified_since: None,
+            meta: None,
         };
-        assert!(text.contains("Bob"));
+        let result = router.handle_resources_read(
+            &cx,
+            1,
+            None,
+            &params,
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+        );
+        assert_eq!(
+            result.expect("ranged read").contents[0].text,
+            Some("Test con".to_string())
+        );
     }
 
-    #[test]
-    fn test_handle_prompts_get_not_found() {
-        let router = create_test_router();
-        let cx = Cx::for_testing();
-        let budget = Budget::INFINITE;
+    /// A resource that reports its size up front and counts reads.
+    struct SizedResource {
+        reads: Arc<AtomicUsize>,
+    }
 
-        let params = GetPromptParams {
-            name: "nonexistent".to_string(),
-            arguments: None,
-            meta: None,
-        };
+    impl ResourceHandler for SizedResource {
+        fn definition(&self) -> Resource {
+            Resource {
+                uri: "resource://sized".to_string(),
+                name: "Sized Resource".to_string(),
+                description: None,
+                mime_type: Some("text/plain".to_string()),
+                icon: None,
+                version: None,
+                tags: vec![],
+            }
+        }
 
-        let result =
-            router.handle_prompts_get(&cx, 1, params, &budget, SessionState::new(), None, None);
+        fn content_len(&self, _uri: &str, _params: &UriParams) -> Option<u64> {
+            Some(1024)
+        }
 
-        assert!(result.is_err());
+        fn read(&self, _ctx: &McpContext) -> McpResult<Vec<ResourceContent>> {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![ResourceContent {
+                uri: "resource://sized".to_string(),
+                mime_type: Some("text/plain".to_string()),
+                text: Some("x".repeat(1024)),
+                blob: None,
+                etag: None,
+                last_modified: None,
+            }])
+        }
     }
 
     #[test]
-    fn test_handle_tools_call_validation_missing_required() {
-        let router = create_test_router();
+    fn test_handle_resources_read_rejects_oversized_before_reading() {
+        let reads = Arc::new(AtomicUsize::new(0));
+        let mut router = Router::new();
+        router.add_resource(SizedResource {
+            reads: Arc::clone(&reads),
+        });
+        router.set_max_resource_bytes(Some(100));
         let cx = Cx::for_testing();
         let budget = Budget::INFINITE;
 
-        // greet tool requires 'name' field, so passing empty object should fail validation
-        let params = CallToolParams {
-            name: "greet".to_string(),
-            arguments: Some(serde_json::json!({})),
-            meta: None,
+        let read = |range| {
+            let params = ReadResourceParams {
+                uri: "resource://sized".to_string(),
+                range,
+                if_none_match: None,
+                if_modified_since: None,
+                meta: None,
+            };
+            router.handle_resources_read(
+                &cx,
+                1,
+                None,
+                &params,
+                &budget,
+                SessionState::new(),
+                None,
+                None,
+            )
         };
 
-        let result =
-            router.handle_tools_call(&cx, 1, params, &budget, SessionState::new(), None, None);
+        let err = read(None).expect_err("oversized read should fail");
+        assert!(err.message.contains("1024 bytes"), "{}", err.message);
+        let err = read(Some(ResourceRange::new(0, Some(500)))).expect_err("oversized range");
+        assert!(err.message.contains("500 bytes"), "{}", err.message);
+        assert_eq!(reads.load(Ordering::SeqCst), 0, "handler must not be read");
 
-        assert!(result.is_err());
-        let err = result.unwrap_err();
-        assert!(err.message.contains("validation") || err.message.contains("required"));
+        // A range near the end resolves to fewer bytes than requested
+        let result = read(Some(ResourceRange::new(1000, Some(500)))).expect("tail range");
+        assert_eq!(result.contents[0].text.as_deref().map(str::len), Some(24));
+        assert_eq!(reads.load(Ordering::SeqCst), 1);
     }
 
     #[test]
-    fn test_handle_tools_call_validation_wrong_type() {
+    fn test_handle_resources_read_template_match() {
         let router = create_test_router();
         let cx = Cx::for_testing();
         let budget = Budget::INFINITE;
 
-        // greet tool expects 'name' to be a string, not a number
-        let params = CallToolParams {
-            name: "greet".to_string(),
-            arguments: Some(serde_json::json!({"name": 123})),
+        let params = ReadResourceParams {
+            uri: "resource://abc".to_string(),
+            range: None,
+            if_none_match: None,
+            if_modified_since: None,
             meta: None,
         };
 
-        let result =
-            router.handle_tools_call(&cx, 1, params, &budget, SessionState::new(), None, None);
+        let result = router.handle_resources_read(
+            &cx,
+            1,
+            None,
+            &params,
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+        );
 
-        assert!(result.is_err());
-        let err = result.unwrap_err();
-        assert!(err.message.contains("validation") || err.message.contains("type"));
+        assert!(result.is_ok(), "Expected Ok, got Err: {:?}", result.err());
+        let read_result = result.unwrap();
+        assert_eq!(
+            read_result.contents[0].text,
+            Some("Template abc".to_string())
+        );
     }
 
     #[test]
-    fn test_handle_tools_call_validation_passes() {
+    fn test_handle_resources_read_template_match_percent_decoded() {
         let router = create_test_router();
         let cx = Cx::for_testing();
         let budget = Budget::INFINITE;
 
-        // Valid arguments that satisfy the schema
-        let params = CallToolParams {
-            name: "greet".to_string(),
-            arguments: Some(serde_json::json!({"name": "Alice"})),
+        let params = ReadResourceParams {
+            uri: "resource://hello%20world".to_string(),
+            range: None,
+            if_none_match: None,
+            if_modified_since: None,
             meta: None,
         };
 
-        let result =
-            router.handle_tools_call(&cx, 1, params, &budget, SessionState::new(), None, None);
+        let result = router.handle_resources_read(
+            &cx,
+            1,
+            None,
+            &params,
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+        );
 
-        assert!(result.is_ok());
-        let call_result = result.unwrap();
-        assert!(!call_result.is_error);
+        assert!(result.is_ok(), "Expected Ok, got Err: {:?}", result.err());
+        let read_result = result.unwrap();
+        assert_eq!(
+            read_result.contents[0].text,
+            Some("Template hello world".to_string())
+        );
     }
 
     #[test]
-    fn test_handle_tools_call_lenient_validation_allows_extra_properties() {
-        // Default (lenient) mode allows extra properties
+    fn test_handle_resources_read_template_match_with_slash() {
         let router = create_test_router();
         let cx = Cx::for_testing();
         let budget = Budget::INFINITE;
 
-        // Include an extra property not in the schema
-        let params = CallToolParams {
-            name: "greet".to_string(),
-            arguments: Some(serde_json::json!({"name": "Alice", "extra": "ignored"})),
+        let params = ReadResourceParams {
+            uri: "resource://foo/bar".to_string(),
+            range: None,
+            if_none_match: None,
+            if_modified_since: None,
             meta: None,
         };
 
-        let result =
-            router.handle_tools_call(&cx, 1, params, &budget, SessionState::new(), None, None);
+        let result = router.handle_resources_read(
+            &cx,
+            1,
+            None,
+            &params,
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+        );
 
-        // Should pass in lenient mode
-        assert!(result.is_ok());
-        let call_result = result.unwrap();
-        assert!(!call_result.is_error);
+        assert!(result.is_ok(), "Expected Ok, got Err: {:?}", result.err());
+        let read_result = result.unwrap();
+        assert_eq!(
+            read_result.contents[0].text,
+            Some("Template foo/bar".to_string())
+        );
     }
 
     #[test]
-    fn test_handle_tools_call_strict_validation_rejects_extra_properties() {
-        // Enable strict validation mode
-        let mut router = create_test_router();
-        router.set_strict_input_validation(true);
+    fn test_handle_resources_read_template_precedence() {
+        let mut router = Router::new();
+        router.add_resource(TemplateResource);
+        router.add_resource(SpecificTemplateResource);
+
         let cx = Cx::for_testing();
         let budget = Budget::INFINITE;
 
-        // Include an extra property not in the schema
-        let params = CallToolParams {
-            name: "greet".to_string(),
-            arguments: Some(serde_json::json!({"name": "Alice", "extra": "should_fail"})),
+        let params = ReadResourceParams {
+            uri: "resource://foo/123".to_string(),
+            range: None,
+            if_none_match: None,
+            if_modified_since: None,
             meta: None,
         };
 
-        let result =
-            router.handle_tools_call(&cx, 1, params, &budget, SessionState::new(), None, None);
+        let result = router.handle_resources_read(
+            &cx,
+            1,
+            None,
+            &params,
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+        );
 
-        // Should fail in strict mode due to extra property
-        assert!(result.is_err());
-        let err = result.unwrap_err();
-        assert!(err.message.contains("validation") || err.message.contains("additional"));
+        assert!(result.is_ok(), "Expected Ok, got Err: {:?}", result.err());
+        let read_result = result.unwrap();
+        assert_eq!(
+            read_result.contents[0].text,
+            Some("Specific 123".to_string())
+        );
     }
 
     #[test]
-    fn test_handle_tools_call_strict_validation_passes_valid_input() {
-        // Enable strict validation mode
-        let mut router = create_test_router();
-        router.set_strict_input_validation(true);
+    fn test_e2e_template_logging() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut router = Router::new();
+        router.add_resource(LoggingTemplateResource {
+            template: "file://{path}",
+            events: events.clone(),
+        });
+
         let cx = Cx::for_testing();
         let budget = Budget::INFINITE;
-
-        // Only include defined properties
-        let params = CallToolParams {
-            name: "greet".to_string(),
-            arguments: Some(serde_json::json!({"name": "Alice"})),
+        let params = ReadResourceParams {
+            uri: "file://dir%2Ffile.txt".to_string(),
+            range: None,
+            if_none_match: None,
+            if_modified_since: None,
             meta: None,
         };
 
-        let result =
-            router.handle_tools_call(&cx, 1, params, &budget, SessionState::new(), None, None);
+        let result = router.handle_resources_read(
+            &cx,
+            1,
+            None,
+            &params,
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+        );
 
-        // Should pass in strict mode with valid input
-        assert!(result.is_ok());
-        let call_result = result.unwrap();
-        assert!(!call_result.is_error);
+        assert!(result.is_ok(), "Expected Ok, got Err: {:?}", result.err());
+        let read_result = result.unwrap();
+        assert_eq!(
+            read_result.contents[0].text.as_deref(),
+            Some("Logged dir/file.txt")
+        );
+
+        let guard = events.lock().expect("template log lock poisoned");
+        assert_eq!(guard.len(), 1);
+        let entry = &guard[0];
+        assert_eq!(entry.template, "file://{path}");
+        assert_eq!(entry.uri, "file://dir%2Ffile.txt");
+        assert_eq!(
+            entry.params.get("path").map(String::as_str),
+            Some("dir/file.txt")
+        );
+        assert_eq!(entry.response, "Logged dir/file.txt");
     }
-}
 
-// ============================================================================
-// Session Tests
-// ============================================================================
+    #[test]
+    fn test_handle_resources_read_not_found() {
+        let router = create_test_router();
+        let cx = Cx::for_testing();
+        let budget = Budget::INFINITE;
 
-#[cfg(test)]
-mod session_tests {
-    use super::*;
+        // Use a scheme that doesn't match any registered resources or templates
+        let params = ReadResourceParams {
+            uri: "file://nonexistent".to_string(),
+            range: None,
+            if_none_match: None,
+            if_modified_since: None,
+            meta: None,
+        };
 
-    #[test]
-    fn test_session_creation() {
-        let session = Session::new(
-            ServerInfo {
-                name: "test".to_string(),
-                version: "1.0".to_string(),
-            },
-            ServerCapabilities::default(),
+        let result = router.handle_resources_read(
+            &cx,
+            1,
+            None,
+            &params,
+            &budget,
+            SessionState::new(),
+            None,
+            None,
         );
 
-        assert!(!session.is_initialized());
-        assert!(session.client_info().is_none());
-        assert!(session.client_capabilities().is_none());
-        assert!(session.protocol_version().is_none());
+        assert!(result.is_err());
     }
 
     #[test]
-    fn test_session_initialization() {
-        let mut session = Session::new(
-            ServerInfo {
-                name: "test".to_string(),
-                version: "1.0".to_string(),
-            },
-            ServerCapabilities::default(),
-        );
+    fn test_handle_resources_read_with_cancellation() {
+        let router = create_test_router();
+        let cx = Cx::for_testing();
+        cx.set_cancel_requested(true);
+        let budget = Budget::INFINITE;
 
-        session.initialize(
-            ClientInfo {
-                name: "client".to_string(),
-                version: "2.0".to_string(),
-            },
-            ClientCapabilities::default(),
-            "2024-11-05".to_string(),
+        let params = ReadResourceParams {
+            uri: "resource://test".to_string(),
+            range: None,
+            if_none_match: None,
+            if_modified_since: None,
+            meta: None,
+        };
+
+        let result = router.handle_resources_read(
+            &cx,
+            1,
+            None,
+            &params,
+            &budget,
+            SessionState::new(),
+            None,
+            None,
         );
 
-        assert!(session.is_initialized());
-        assert_eq!(session.client_info().unwrap().name, "client");
-        assert_eq!(session.protocol_version(), Some("2024-11-05"));
+        // Should be cancelled
+        assert!(result.is_err());
     }
-}
-
-// ============================================================================
-// Cancellation Tests
-// ============================================================================
-
-#[cfg(test)]
-mod cancellation_tests {
-    use super::*;
 
     #[test]
-    fn test_tool_observes_cancellation() {
+    fn test_handle_prompts_get_success() {
+        let router = create_test_router();
         let cx = Cx::for_testing();
-        let ctx = McpContext::new(cx.clone(), 1);
+        let budget = Budget::INFINITE;
 
-        // Initially not cancelled
-        assert!(!ctx.is_cancelled());
+        let params = GetPromptParams {
+            name: "greeting".to_string(),
+            arguments: Some({
+                let mut map = HashMap::new();
+                map.insert("name".to_string(), "Bob".to_string());
+                map
+            }),
+            meta: None,
+        };
 
-        // Set cancellation
-        cx.set_cancel_requested(true);
+        let result = router.handle_prompts_get(
+            &cx,
+            1,
+            None,
+            params,
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+        );
 
-        // Now tool should observe cancellation
-        assert!(ctx.is_cancelled());
+        assert!(result.is_ok());
+        let get_result = result.unwrap();
+        assert_eq!(get_result.messages.len(), 1);
+
+        assert!(matches!(
+            get_result.messages[0].content,
+            Content::Text { .. }
+        ));
+        let Content::Text { text } = &get_result.messages[0].content else {
+            return;
+        };
+        assert!(text.contains("Bob"));
     }
 
     #[test]
-    fn test_checkpoint_fails_when_cancelled() {
+    fn test_handle_prompts_get_not_found() {
+        let router = create_test_router();
         let cx = Cx::for_testing();
-        let ctx = McpContext::new(cx.clone(), 1);
+        let budget = Budget::INFINITE;
 
-        // Checkpoint succeeds initially
-        assert!(ctx.checkpoint().is_ok());
+        let params = GetPromptParams {
+            name: "nonexistent".to_string(),
+            arguments: None,
+            meta: None,
+        };
 
-        // Set cancellation
-        cx.set_cancel_requested(true);
+        let result = router.handle_prompts_get(
+            &cx,
+            1,
+            None,
+            params,
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+        );
 
-        // Checkpoint now fails
-        assert!(ctx.checkpoint().is_err());
+        assert!(result.is_err());
     }
 
     #[test]
-    fn test_masked_section_defers_cancellation() {
+    fn test_handle_prompts_get_missing_required_argument() {
+        let router = create_test_router();
         let cx = Cx::for_testing();
-        let ctx = McpContext::new(cx.clone(), 1);
+        let budget = Budget::INFINITE;
 
-        cx.set_cancel_requested(true);
+        let params = GetPromptParams {
+            name: "greeting".to_string(),
+            arguments: Some(HashMap::new()),
+            meta: None,
+        };
 
-        // Inside masked section, checkpoint should succeed
-        ctx.masked(|| {
-            assert!(ctx.checkpoint().is_ok());
-        });
+        let err = router
+            .handle_prompts_get(
+                &cx,
+                1,
+                None,
+                params,
+                &budget,
+                SessionState::new(),
+                None,
+                None,
+            )
+            .unwrap_err();
 
-        // Outside masked section, checkpoint should fail
-        assert!(ctx.checkpoint().is_err());
+        assert_eq!(err.code, McpErrorCode::InvalidParams);
+        assert!(err.message.contains("'greeting'"), "{}", err.message);
+        assert!(err.message.contains("name"), "{}", err.message);
     }
-}
-
-// ============================================================================
-// Budget Tests
-// ============================================================================
-
-#[cfg(test)]
-mod budget_tests {
-    use super::*;
 
     #[test]
-    fn test_infinite_budget_not_exhausted() {
+    fn test_handle_prompts_get_missing_arguments_map() {
+        let router = create_test_router();
+        let cx = Cx::for_testing();
         let budget = Budget::INFINITE;
-        assert!(!budget.is_exhausted());
+
+        let params = GetPromptParams {
+            name: "greeting".to_string(),
+            arguments: None,
+            meta: None,
+        };
+
+        let err = router
+            .handle_prompts_get(
+                &cx,
+                1,
+                None,
+                params,
+                &budget,
+                SessionState::new(),
+                None,
+                None,
+            )
+            .unwrap_err();
+
+        assert_eq!(err.code, McpErrorCode::InvalidParams);
     }
 
-    #[test]
-    fn test_exhausted_budget() {
-        let budget = Budget::unlimited().with_poll_quota(0);
-        assert!(budget.is_exhausted());
+    fn get_review_prompt(router: &Router) -> McpResult<GetPromptResult> {
+        let params = GetPromptParams {
+            name: "review".to_string(),
+            arguments: None,
+            meta: None,
+        };
+        router.handle_prompts_get(
+            &Cx::for_testing(),
+            1,
+            None,
+            params,
+            &Budget::INFINITE,
+            SessionState::new(),
+            None,
+            None,
+        )
     }
 
     #[test]
-    fn test_deadline_budget() {
-        // A budget with a deadline far in the future
-        let budget = Budget::with_deadline_secs(3600);
-        assert!(!budget.is_exhausted());
-    }
-}
+    fn test_handle_prompts_get_embeds_referenced_resource() {
+        let mut router = create_test_router();
+        router.add_prompt(EmbeddingPrompt {
+            uri: "resource://test",
+        });
 
-// ============================================================================
-// Handler Definition Tests
-// ============================================================================
+        let result = get_review_prompt(&router).unwrap();
+        assert_eq!(result.messages.len(), 2);
+        assert!(matches!(
+            &result.messages[0].content,
+            Content::Text { text } if text == "Review this document:"
+        ));
+        let Content::Resource { resource } = &result.messages[1].content else {
+            panic!(
+                "expected embedded resource, got {:?}",
+                result.messages[1].content
+            );
+        };
+        assert_eq!(result.messages[1].role, Role::User);
+        assert_eq!(resource.uri, "resource://test");
+        assert_eq!(resource.text.as_deref(), Some("Test content"));
+        assert_eq!(resource.mime_type.as_deref(), Some("text/plain"));
 
-#[cfg(test)]
-mod handler_definition_tests {
-    use super::*;
-    use crate::router::TagFilters;
-    use fastmcp_protocol::ListToolsParams;
+        router.set_max_resource_bytes(Some(4));
+        let err = get_review_prompt(&router).unwrap_err();
+        assert_eq!(err.code, McpErrorCode::InvalidRequest);
+        assert!(err.message.contains("'review'"), "{}", err.message);
+    }
 
     #[test]
-    fn test_tool_definition() {
-        let tool = GreetTool;
-        let def = tool.definition();
+    fn test_handle_prompts_get_missing_embedded_resource() {
+        let mut router = create_test_router();
+        router.add_prompt(EmbeddingPrompt {
+            uri: "file:///missing.txt",
+        });
 
-        assert_eq!(def.name, "greet");
-        assert!(def.description.is_some());
-        assert!(def.input_schema["type"] == "object");
+        let err = get_review_prompt(&router).unwrap_err();
+        assert_eq!(err.code, McpErrorCode::ResourceNotFound);
+        assert!(
+            err.message.contains("file:///missing.txt"),
+            "{}",
+            err.message
+        );
     }
 
     #[test]
-    fn test_resource_definition() {
-        let resource = StaticResource {
-            uri: "resource://foo".to_string(),
-            content: "bar".to_string(),
+    fn test_handle_tools_call_validation_missing_required() {
+        let router = create_test_router();
+        let cx = Cx::for_testing();
+        let budget = Budget::INFINITE;
+
+        // greet tool requires 'name' field, so passing empty object should fail validation
+        let params = CallToolParams {
+            name: "greet".to_string(),
+            arguments: Some(serde_json::json!({})),
+            meta: None,
         };
-        let def = resource.definition();
 
-        assert_eq!(def.uri, "resource://foo");
-        assert_eq!(def.mime_type, Some("text/plain".to_string()));
+        let result = router.handle_tools_call(
+            &cx,
+            1,
+            None,
+            params,
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+            false,
+        );
+
+        assert!(result.is_err());
+        let err = result.unwrap_err();
+        assert!(err.message.contains("validation") || err.message.contains("required"));
     }
 
     #[test]
-    fn test_prompt_definition() {
-        let prompt = GreetingPrompt;
-        let def = prompt.definition();
-
-        assert_eq!(def.name, "greeting");
-        assert!(!def.arguments.is_empty());
-        assert_eq!(def.arguments.len(), 1);
-    }
+    fn test_handle_tools_call_validation_wrong_type() {
+        let router = create_test_router();
+        let cx = Cx::for_testing();
+        let budget = Budget::INFINITE;
 
-    // ========================================================================
-    // Tag Filtering Tests
-    // ========================================================================
+        // greet tool expects 'name' to be a string, not a number
+        let params = CallToolParams {
+            name: "greet".to_string(),
+            arguments: Some(serde_json::json!({"name": 123})),
+            meta: None,
+        };
 
-    /// A tool with tags for testing tag filtering.
-    struct TaggedTool {
-        name: String,
-        tags: Vec<String>,
-    }
+        let result = router.handle_tools_call(
+            &cx,
+            1,
+            None,
+            params,
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+            false,
+        );
 
-    impl TaggedTool {
-        fn new(name: &str, tags: &[&str]) -> Self {
-            Self {
-                name: name.to_string(),
-                tags: tags.iter().map(|s| s.to_string()).collect(),
-            }
-        }
+        assert!(result.is_err());
+        let err = result.unwrap_err();
+        assert!(err.message.contains("validation") || err.message.contains("type"));
     }
 
-    impl ToolHandler for TaggedTool {
-        fn definition(&self) -> Tool {
-            Tool {
-                name: self.name.clone(),
-                description: Some(format!("Tool with tags: {:?}", self.tags)),
-                input_schema: serde_json::json!({"type": "object"}),
-                output_schema: None,
-                icon: None,
-                version: None,
-                tags: self.tags.clone(),
-                annotations: None,
-            }
-        }
+    #[test]
+    fn test_handle_tools_call_validation_data_missing_required() {
+        let router = create_test_router();
+        let cx = Cx::for_testing();
+        let params = CallToolParams {
+            name: "greet".to_string(),
+            arguments: Some(serde_json::json!({})),
+            meta: None,
+        };
 
-        fn call(&self, _ctx: &McpContext, _args: serde_json::Value) -> McpResult<Vec<Content>> {
-            Ok(vec![Content::Text {
-                text: "ok".to_string(),
-            }])
-        }
-    }
+        let err = router
+            .handle_tools_call(
+                &cx,
+                1,
+                None,
+                params,
+                &Budget::INFINITE,
+                SessionState::new(),
+                None,
+                None,
+                false,
+            )
+            .unwrap_err();
 
-    fn create_tagged_tools_router() -> Router {
-        let mut router = Router::new();
-        // Tools with various tag combinations
-        router.add_tool(TaggedTool::new("search", &["api", "public", "read"]));
-        router.add_tool(TaggedTool::new("create", &["api", "public", "write"]));
-        router.add_tool(TaggedTool::new("admin", &["api", "private", "admin"]));
-        router.add_tool(TaggedTool::new("debug", &["internal", "debug"]));
-        router.add_tool(TaggedTool::new("untagged", &[]));
-        router
+        assert_eq!(err.code, McpErrorCode::InvalidParams);
+        assert!(err.message.contains("missing required field: name"));
+        let data = err.data.expect("validation errors carry data");
+        let errors = data["errors"].as_array().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0]["path"], "/name");
+        assert_eq!(errors[0]["keyword"], "required");
+        assert_eq!(errors[0]["message"], "missing required field: name");
     }
 
     #[test]
-    fn test_tag_filters_include_single_tag() {
-        let router = create_tagged_tools_router();
-        let include = vec!["api".to_string()];
-        let filters = TagFilters::new(Some(&include), None);
-        let tools = router.tools_filtered(None, Some(&filters));
-        assert_eq!(tools.len(), 3, "Expected search, create, admin");
-        assert!(tools.iter().any(|t| t.name == "search"));
-        assert!(tools.iter().any(|t| t.name == "create"));
-        assert!(tools.iter().any(|t| t.name == "admin"));
-    }
+    fn test_handle_tools_call_validation_data_wrong_type() {
+        let router = create_test_router();
+        let cx = Cx::for_testing();
+        let params = CallToolParams {
+            name: "greet".to_string(),
+            arguments: Some(serde_json::json!({"name": 123})),
+            meta: None,
+        };
 
-    #[test]
-    fn test_tag_filters_include_multiple_tags_and_logic() {
-        let router = create_tagged_tools_router();
-        let include = vec!["api".to_string(), "public".to_string()];
-        let filters = TagFilters::new(Some(&include), None);
-        let tools = router.tools_filtered(None, Some(&filters));
-        assert_eq!(
-            tools.len(),
-            2,
-            "Expected search, create (both have api AND public)"
+        let err = router
+            .handle_tools_call(
+                &cx,
+                1,
+                None,
+                params,
+                &Budget::INFINITE,
+                SessionState::new(),
+                None,
+                None,
+                false,
+            )
+            .unwrap_err();
+
+        let data = err.data.expect("validation errors carry data");
+        let errors = data["errors"].as_array().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0]["path"], "/name");
+        assert_eq!(errors[0]["keyword"], "type");
+        assert!(
+            errors[0]["message"]
+                .as_str()
+                .unwrap()
+                .starts_with("expected type string")
         );
-        assert!(tools.iter().any(|t| t.name == "search"));
-        assert!(tools.iter().any(|t| t.name == "create"));
     }
 
     #[test]
-    fn test_tag_filters_exclude_single_tag() {
-        let router = create_tagged_tools_router();
-        let exclude = vec!["private".to_string()];
-        let filters = TagFilters::new(None, Some(&exclude));
-        let tools = router.tools_filtered(None, Some(&filters));
-        assert_eq!(tools.len(), 4, "Expected all except admin");
-        assert!(!tools.iter().any(|t| t.name == "admin"));
-    }
+    fn test_handle_tools_call_validation_passes() {
+        let router = create_test_router();
+        let cx = Cx::for_testing();
+        let budget = Budget::INFINITE;
 
-    #[test]
-    fn test_tag_filters_exclude_multiple_tags_or_logic() {
-        let router = create_tagged_tools_router();
-        let exclude = vec!["private".to_string(), "internal".to_string()];
-        let filters = TagFilters::new(None, Some(&exclude));
-        let tools = router.tools_filtered(None, Some(&filters));
-        assert_eq!(tools.len(), 3, "Expected search, create, untagged");
-        assert!(tools.iter().any(|t| t.name == "search"));
-        assert!(tools.iter().any(|t| t.name == "create"));
-        assert!(tools.iter().any(|t| t.name == "untagged"));
-    }
+        // Valid arguments that satisfy the schema
+        let params = CallToolParams {
+            name: "greet".to_string(),
+            arguments: Some(serde_json::json!({"name": "Alice"})),
+            meta: None,
+        };
 
-    #[test]
-    fn test_tag_filters_include_and_exclude_combined() {
-        let router = create_tagged_tools_router();
-        let include = vec!["api".to_string()];
-        let exclude = vec!["private".to_string()];
-        let filters = TagFilters::new(Some(&include), Some(&exclude));
-        let tools = router.tools_filtered(None, Some(&filters));
-        assert_eq!(
-            tools.len(),
-            2,
-            "Expected search, create (api but not private)"
+        let result = router.handle_tools_call(
+            &cx,
+            1,
+            None,
+            params,
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+            false,
         );
-        assert!(tools.iter().any(|t| t.name == "search"));
-        assert!(tools.iter().any(|t| t.name == "create"));
-    }
-
-    #[test]
-    fn test_tag_filters_case_insensitive() {
-        let router = create_tagged_tools_router();
-        let include = vec!["API".to_string()];
-        let filters = TagFilters::new(Some(&include), None);
-        let tools = router.tools_filtered(None, Some(&filters));
-        assert_eq!(tools.len(), 3, "Should match 'api' tags case-insensitively");
-    }
-
-    #[test]
-    fn test_tag_filters_empty_include_no_filter() {
-        let router = create_tagged_tools_router();
-        let include: Vec<String> = vec![];
-        let filters = TagFilters::new(Some(&include), None);
-        let tools = router.tools_filtered(None, Some(&filters));
-        assert_eq!(tools.len(), 5, "Empty include should not filter");
-    }
 
-    #[test]
-    fn test_tag_filters_no_matches() {
-        let router = create_tagged_tools_router();
-        let include = vec!["nonexistent".to_string()];
-        let filters = TagFilters::new(Some(&include), None);
-        let tools = router.tools_filtered(None, Some(&filters));
-        assert!(tools.is_empty(), "No tools should match nonexistent tag");
+        assert!(result.is_ok());
+        let call_result = result.unwrap();
+        assert!(!call_result.is_error);
     }
 
     #[test]
-    fn test_handle_tools_list_with_include_tags() {
-        let router = create_tagged_tools_router();
+    fn test_handle_tools_call_lenient_validation_allows_extra_properties() {
+        // Default (lenient) mode allows extra properties
+        let router = create_test_router();
         let cx = Cx::for_testing();
-        let params = ListToolsParams {
-            cursor: None,
-            include_tags: Some(vec!["public".to_string()]),
-            exclude_tags: None,
-        };
-        let result = router.handle_tools_list(&cx, params, None);
-        let tools = result.unwrap().tools;
-        assert_eq!(tools.len(), 2, "Expected search, create");
-    }
+        let budget = Budget::INFINITE;
 
-    #[test]
-    fn test_handle_tools_list_with_exclude_tags() {
-        let router = create_tagged_tools_router();
-        let cx = Cx::for_testing();
-        let params = ListToolsParams {
-            cursor: None,
-            include_tags: None,
-            exclude_tags: Some(vec!["private".to_string(), "internal".to_string()]),
+        // Include an extra property not in the schema
+        let params = CallToolParams {
+            name: "greet".to_string(),
+            arguments: Some(serde_json::json!({"name": "Alice", "extra": "ignored"})),
+            meta: None,
         };
-        let result = router.handle_tools_list(&cx, params, None);
-        let tools = result.unwrap().tools;
-        assert_eq!(tools.len(), 3, "Expected search, create, untagged");
-    }
-}
-
-// ============================================================================
-// Multiple Handler Tests
-// ============================================================================
-
-#[cfg(test)]
-mod multi_handler_tests {
-    use super::*;
-
-    /// Second greeting tool with different behavior.
-    struct FormalGreetTool;
 
-    impl ToolHandler for FormalGreetTool {
-        fn definition(&self) -> Tool {
-            Tool {
-                name: "formal_greet".to_string(),
-                description: Some("Formally greets a user".to_string()),
-                input_schema: serde_json::json!({
-                    "type": "object",
-                    "properties": {
-                        "name": {"type": "string"}
-                    }
-                }),
-                output_schema: None,
-                icon: None,
-                version: None,
-                tags: vec![],
-                annotations: None,
-            }
-        }
+        let result = router.handle_tools_call(
+            &cx,
+            1,
+            None,
+            params,
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+            false,
+        );
 
-        fn call(&self, _ctx: &McpContext, arguments: serde_json::Value) -> McpResult<Vec<Content>> {
-            let name = arguments
-                .get("name")
-                .and_then(|v| v.as_str())
-                .unwrap_or("Sir/Madam");
-            Ok(vec![Content::Text {
-                text: format!("Good day, {name}."),
-            }])
-        }
+        // Should pass in lenient mode
+        assert!(result.is_ok());
+        let call_result = result.unwrap();
+        assert!(!call_result.is_error);
     }
 
     #[test]
-    fn test_multiple_tools() {
-        let mut router = Router::new();
-        router.add_tool(GreetTool);
-        router.add_tool(FormalGreetTool);
-
-        let tools = router.tools();
-        assert_eq!(tools.len(), 2);
-
-        // Call both tools
+    fn test_handle_tools_call_strict_validation_rejects_extra_properties() {
+        // Enable strict validation mode
+        let mut router = create_test_router();
+        router.set_strict_input_validation(true);
         let cx = Cx::for_testing();
         let budget = Budget::INFINITE;
 
-        let result1 = router.handle_tools_call(
+        // Include an extra property not in the schema
+        let params = CallToolParams {
+            name: "greet".to_string(),
+            arguments: Some(serde_json::json!({"name": "Alice", "extra": "should_fail"})),
+            meta: None,
+        };
+
+        let result = router.handle_tools_call(
             &cx,
             1,
-            CallToolParams {
-                name: "greet".to_string(),
-                arguments: Some(serde_json::json!({"name": "Alice"})),
-                meta: None,
-            },
-            &budget,
-            SessionState::new(),
             None,
-            None,
-        );
-        assert!(result1.is_ok());
-
-        let result2 = router.handle_tools_call(
-            &cx,
-            2,
-            CallToolParams {
-                name: "formal_greet".to_string(),
-                arguments: Some(serde_json::json!({"name": "Alice"})),
-                meta: None,
-            },
+            params,
             &budget,
             SessionState::new(),
             None,
             None,
+            false,
         );
-        assert!(result2.is_ok());
 
-        // Verify different outputs
-        if let Content::Text { text: text1 } = &result1.unwrap().content[0] {
-            if let Content::Text { text: text2 } = &result2.unwrap().content[0] {
-                assert_eq!(text1, "Hello, Alice!");
-                assert_eq!(text2, "Good day, Alice.");
-            }
-        }
+        // Should fail in strict mode due to extra property
+        assert!(result.is_err());
+        let err = result.unwrap_err();
+        assert!(err.message.contains("validation") || err.message.contains("additional"));
     }
 
     #[test]
-    fn test_multiple_resources() {
-        let mut router = Router::new();
-        router.add_resource(StaticResource {
-            uri: "resource://a".to_string(),
-            content: "Content A".to_string(),
-        });
-        router.add_resource(StaticResource {
-            uri: "resource://b".to_string(),
-            content: "Content B".to_string(),
-        });
-
-        let resources = router.resources();
-        assert_eq!(resources.len(), 2);
-
+    fn test_handle_tools_call_strict_validation_passes_valid_input() {
+        // Enable strict validation mode
+        let mut router = create_test_router();
+        router.set_strict_input_validation(true);
         let cx = Cx::for_testing();
         let budget = Budget::INFINITE;
 
-        let result_a = router.handle_resources_read(
+        // Only include defined properties
+        let params = CallToolParams {
+            name: "greet".to_string(),
+            arguments: Some(serde_json::json!({"name": "Alice"})),
+            meta: None,
+        };
+
+        let result = router.handle_tools_call(
             &cx,
             1,
-            &ReadResourceParams {
-                uri: "resource://a".to_string(),
-                meta: None,
-            },
-            &budget,
-            SessionState::new(),
-            None,
             None,
-        );
-        let result_b = router.handle_resources_read(
-            &cx,
-            2,
-            &ReadResourceParams {
-                uri: "resource://b".to_string(),
-                meta: None,
-            },
+            params,
             &budget,
             SessionState::new(),
             None,
             None,
+            false,
         );
 
-        assert_eq!(
-            result_a.unwrap().contents[0].text,
-            Some("Content A".to_string())
-        );
-        assert_eq!(
-            result_b.unwrap().contents[0].text,
-            Some("Content B".to_string())
-        );
+        // Should pass in strict mode with valid input
+        assert!(result.is_ok());
+        let call_result = result.unwrap();
+        assert!(!call_result.is_error);
     }
 }
 
 // ============================================================================
-// Session State Tests
+// Session Tests
 // ============================================================================
 
-mod session_state_tests {
+#[cfg(test)]
+mod session_tests {
     use super::*;
 
-    /// Tool that increments a counter in session state.
-    struct CounterTool;
-
-    impl ToolHandler for CounterTool {
-        fn definition(&self) -> Tool {
-            Tool {
-                name: "increment".to_string(),
-                description: Some("Increments a counter in session state".to_string()),
-                input_schema: serde_json::json!({"type": "object"}),
-                output_schema: None,
-                icon: None,
-                version: None,
-                tags: vec![],
-                annotations: None,
-            }
-        }
+    #[test]
+    fn test_session_creation() {
+        let session = Session::new(
+            ServerInfo {
+                name: "test".to_string(),
+                version: "1.0".to_string(),
+            },
+            ServerCapabilities::default(),
+        );
 
-        fn call(&self, ctx: &McpContext, _arguments: serde_json::Value) -> McpResult<Vec<Content>> {
-            let count: i32 = ctx.get_state("counter").unwrap_or(0);
-            let new_count = count + 1;
-            ctx.set_state("counter", new_count);
-            Ok(vec![Content::Text {
-                text: format!("Counter: {new_count}"),
-            }])
-        }
+        assert!(!session.is_initialized());
+        assert!(session.client_info().is_none());
+        assert!(session.client_capabilities().is_none());
+        assert!(session.protocol_version().is_none());
     }
 
     #[test]
-    fn test_session_state_persists_across_calls() {
-        let mut router = Router::new();
-        router.add_tool(CounterTool);
+    fn test_session_initialization() {
+        let mut session = Session::new(
+            ServerInfo {
+                name: "test".to_string(),
+                version: "1.0".to_string(),
+            },
+            ServerCapabilities::default(),
+        );
 
-        let cx = Cx::for_testing();
-        let budget = Budget::INFINITE;
+        session.initialize(
+            ClientInfo {
+                name: "client".to_string(),
+                version: "2.0".to_string(),
+            },
+            ClientCapabilities::default(),
+            "2024-11-05".to_string(),
+        );
 
-        // Create a shared session state
-        let state = SessionState::new();
+        assert!(session.is_initialized());
+        assert_eq!(session.client_info().unwrap().name, "client");
+        assert_eq!(session.protocol_version(), Some("2024-11-05"));
+    }
+}
 
-        // First call - counter should be 1
-        let params = CallToolParams {
-            name: "increment".to_string(),
-            arguments: None,
-            meta: None,
-        };
-        let result1 =
-            router.handle_tools_call(&cx, 1, params.clone(), &budget, state.clone(), None, None);
-        assert!(result1.is_ok());
-        if let Content::Text { text } = &result1.unwrap().content[0] {
-            assert_eq!(text, "Counter: 1");
-        }
+// ============================================================================
+// Cancellation Tests
+// ============================================================================
 
-        // Second call with same state - counter should be 2
-        let result2 =
-            router.handle_tools_call(&cx, 2, params.clone(), &budget, state.clone(), None, None);
-        assert!(result2.is_ok());
-        if let Content::Text { text } = &result2.unwrap().content[0] {
-            assert_eq!(text, "Counter: 2");
-        }
+#[cfg(test)]
+mod cancellation_tests {
+    use super::*;
 
-        // Third call - counter should be 3
-        let result3 = router.handle_tools_call(&cx, 3, params, &budget, state.clone(), None, None);
-        assert!(result3.is_ok());
-        if let Content::Text { text } = &result3.unwrap().content[0] {
-            assert_eq!(text, "Counter: 3");
-        }
+    #[test]
+    fn test_tool_observes_cancellation() {
+        let cx = Cx::for_testing();
+        let ctx = McpContext::new(cx.clone(), 1);
+
+        // Initially not cancelled
+        assert!(!ctx.is_cancelled());
+
+        // Set cancellation
+        cx.set_cancel_requested(true);
+
+        // Now tool should observe cancellation
+        assert!(ctx.is_cancelled());
     }
 
     #[test]
-    fn test_different_session_states_are_independent() {
-        let mut router = Router::new();
-        router.add_tool(CounterTool);
-
+    fn test_checkpoint_fails_when_cancelled() {
         let cx = Cx::for_testing();
-        let budget = Budget::INFINITE;
+        let ctx = McpContext::new(cx.clone(), 1);
 
-        // Create two separate session states
-        let state1 = SessionState::new();
-        let state2 = SessionState::new();
+        // Checkpoint succeeds initially
+        assert!(ctx.checkpoint().is_ok());
 
-        let params = CallToolParams {
-            name: "increment".to_string(),
-            arguments: None,
-            meta: None,
-        };
+        // Set cancellation
+        cx.set_cancel_requested(true);
 
-        // Call with state1 twice
-        router
-            .handle_tools_call(&cx, 1, params.clone(), &budget, state1.clone(), None, None)
-            .unwrap();
-        let result1 = router
-            .handle_tools_call(&cx, 2, params.clone(), &budget, state1.clone(), None, None)
-            .unwrap();
+        // Checkpoint now fails
+        assert!(ctx.checkpoint().is_err());
+    }
 
-        // Call with state2 once
-        let result2 = router
-            .handle_tools_call(&cx, 3, params, &budget, state2.clone(), None, None)
-            .unwrap();
+    #[test]
+    fn test_masked_section_defers_cancellation() {
+        let cx = Cx::for_testing();
+        let ctx = McpContext::new(cx.clone(), 1);
 
-        // state1 should have counter=2, state2 should have counter=1
-        if let Content::Text { text } = &result1.content[0] {
-            assert_eq!(text, "Counter: 2");
-        }
-        if let Content::Text { text } = &result2.content[0] {
-            assert_eq!(text, "Counter: 1");
-        }
+        cx.set_cancel_requested(true);
+
+        // Inside masked section, checkpoint should succeed
+        ctx.masked(|| {
+            assert!(ctx.checkpoint().is_ok());
+        });
+
+        // Outside masked section, checkpoint should fail
+        assert!(ctx.checkpoint().is_err());
     }
 }
 
 // ============================================================================
-// Console Config Integration Tests
+// Budget Tests
 // ============================================================================
 
-mod console_config_tests {
-    use crate::{BannerStyle, ConsoleConfig, Server, TrafficVerbosity};
+#[cfg(test)]
+mod budget_tests {
+    use super::*;
 
     #[test]
-    fn test_server_default_console_config() {
-        let server = Server::new("test", "1.0.0").build();
-        let config = server.console_config();
-
-        // Default config should show banner
-        assert!(config.show_banner);
-        assert_eq!(config.banner_style, BannerStyle::Full);
+    fn test_infinite_budget_not_exhausted() {
+        let budget = Budget::INFINITE;
+        assert!(!budget.is_exhausted());
     }
 
     #[test]
-    fn test_server_with_console_config() {
-        let config = ConsoleConfig::new()
-            .with_banner(BannerStyle::Compact)
-            .plain_mode();
-
-        let server = Server::new("test", "1.0.0")
-            .with_console_config(config)
-            .build();
-
-        assert_eq!(server.console_config().banner_style, BannerStyle::Compact);
-        assert!(server.console_config().force_plain);
+    fn test_exhausted_budget() {
+        let budget = Budget::unlimited().with_poll_quota(0);
+        assert!(budget.is_exhausted());
     }
 
     #[test]
-    fn test_server_without_banner() {
-        let server = Server::new("test", "1.0.0").without_banner().build();
-
-        assert!(!server.console_config().show_banner);
-        assert_eq!(server.console_config().banner_style, BannerStyle::None);
+    fn test_deadline_budget() {
+        // A budget with a deadline far in the future
+        let budget = Budget::with_deadline_secs(3600);
+        assert!(!budget.is_exhausted());
     }
+}
 
-    #[test]
-    fn test_server_with_banner_style() {
-        let server = Server::new("test", "1.0.0")
-            .with_banner(BannerStyle::Minimal)
-            .build();
+// ============================================================================
+// Handler Definition Tests
+// ============================================================================
 
-        assert!(server.console_config().show_banner);
-        assert_eq!(server.console_config().banner_style, BannerStyle::Minimal);
-    }
+#[cfg(test)]
+mod handler_definition_tests {
+    use super::*;
+    use crate::router::TagFilters;
+    use fastmcp_protocol::{ListToolsParams, TagMatch};
 
     #[test]
-    fn test_server_with_traffic_logging() {
-        let server = Server::new("test", "1.0.0")
-            .with_traffic_logging(TrafficVerbosity::Summary)
-            .build();
+    fn test_tool_definition() {
+        let tool = GreetTool;
+        let def = tool.definition();
 
-        assert!(server.console_config().show_request_traffic);
-        assert_eq!(
-            server.console_config().traffic_verbosity,
-            TrafficVerbosity::Summary
-        );
+        assert_eq!(def.name, "greet");
+        assert!(def.description.is_some());
+        assert!(def.input_schema["type"] == "object");
     }
 
     #[test]
-    fn test_server_with_periodic_stats() {
-        let server = Server::new("test", "1.0.0").with_periodic_stats(30).build();
+    fn test_resource_definition() {
+        let resource = StaticResource {
+            uri: "resource://foo".to_string(),
+            content: "bar".to_string(),
+        };
+        let def = resource.definition();
 
-        assert!(server.console_config().show_stats_periodic);
-        assert_eq!(server.console_config().stats_interval_secs, 30);
+        assert_eq!(def.uri, "resource://foo");
+        assert_eq!(def.mime_type, Some("text/plain".to_string()));
     }
 
     #[test]
-    fn test_server_plain_mode() {
-        let server = Server::new("test", "1.0.0").plain_mode().build();
+    fn test_prompt_definition() {
+        let prompt = GreetingPrompt;
+        let def = prompt.definition();
 
-        assert!(server.console_config().force_plain);
+        assert_eq!(def.name, "greeting");
+        assert!(!def.arguments.is_empty());
+        assert_eq!(def.arguments.len(), 1);
     }
 
-    #[test]
-    fn test_server_force_color() {
-        let server = Server::new("test", "1.0.0").force_color().build();
+    // ========================================================================
+    // Tag Filtering Tests
+    // ========================================================================
 
-        assert_eq!(server.console_config().force_color, Some(true));
+    /// A tool with tags for testing tag filtering.
+    struct TaggedTool {
+        name: String,
+        tags: Vec<String>,
     }
 
-    #[test]
-    fn test_console_config_chaining() {
-        let server = Server::new("test", "1.0.0")
-            .with_banner(BannerStyle::Compact)
-            .with_traffic_logging(TrafficVerbosity::Headers)
-            .with_periodic_stats(60)
-            .plain_mode()
-            .build();
-
-        let config = server.console_config();
-        assert_eq!(config.banner_style, BannerStyle::Compact);
-        assert_eq!(config.traffic_verbosity, TrafficVerbosity::Headers);
-        assert!(config.show_stats_periodic);
-        assert_eq!(config.stats_interval_secs, 60);
-        assert!(config.force_plain);
+    impl TaggedTool {
+        fn new(name: &str, tags: &[&str]) -> Self {
+            Self {
+                name: name.to_string(),
+                tags: tags.iter().map(|s| s.to_string()).collect(),
+            }
+        }
     }
-}
 
-/// Tests for lifecycle hooks (on_startup, on_shutdown).
-mod lifespan_tests {
-    use super::*;
-    use std::sync::Arc;
-    use std::sync::atomic::{AtomicBool, Ordering};
+    impl ToolHandler for TaggedTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: self.name.clone(),
+                description: Some(format!("Tool with tags: {:?}", self.tags)),
+                input_schema: serde_json::json!({"type": "object"}),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: self.tags.clone(),
+                annotations: None,
+            }
+        }
+
+        fn call(&self, _ctx: &McpContext, _args: serde_json::Value) -> McpResult<Vec<Content>> {
+            Ok(vec![Content::Text {
+                text: "ok".to_string(),
+            }])
+        }
+    }
+
+    fn create_tagged_tools_router() -> Router {
+        let mut router = Router::new();
+        // Tools with various tag combinations
+        router.add_tool(TaggedTool::new("search", &["api", "public", "read"]));
+        router.add_tool(TaggedTool::new("create", &["api", "public", "write"]));
+        router.add_tool(TaggedTool::new("admin", &["api", "private", "admin"]));
+        router.add_tool(TaggedTool::new("debug", &["internal", "debug"]));
+        router.add_tool(TaggedTool::new("untagged", &[]));
+        router
+    }
 
     #[test]
-    fn test_on_startup_hook_builder() {
-        let startup_called = Arc::new(AtomicBool::new(false));
-        let startup_called_clone = startup_called.clone();
+    fn test_listings_are_sorted_regardless_of_registration_order() {
+        let mut router = Router::new();
+        for name in ["zeta", "alpha", "mu", "beta", "omega", "gamma"] {
+            router.add_tool(TaggedTool::new(name, &[]));
+        }
+        for uri in ["file://c", "file://a", "file://b"] {
+            router.add_resource(StaticResource {
+                uri: uri.to_string(),
+                content: String::new(),
+            });
+        }
 
-        let server = Server::new("test", "1.0.0")
-            .on_startup(move || {
-                startup_called_clone.store(true, Ordering::SeqCst);
-                Ok::<(), std::io::Error>(())
-            })
-            .build();
+        let expected = ["alpha", "beta", "gamma", "mu", "omega", "zeta"];
+        for _ in 0..5 {
+            let names: Vec<String> = router.tools().into_iter().map(|t| t.name).collect();
+            assert_eq!(names, expected);
 
-        // The hook is stored but not called until run
-        // Verify that the lifespan is stored (we can't call run_startup_hook directly
-        // since it's private, but we verify the builder works)
-        assert!(!startup_called.load(Ordering::SeqCst));
+            let filtered: Vec<String> = router
+                .tools_filtered(None, None)
+                .into_iter()
+                .map(|t| t.name)
+                .collect();
+            assert_eq!(filtered, expected);
 
-        // Manually trigger the startup hook via the public interface
-        // (In production, this would be called by run_loop)
-        let startup_success = server.run_startup_hook();
-        assert!(startup_success);
-        assert!(startup_called.load(Ordering::SeqCst));
+            let uris: Vec<String> = router.resources().into_iter().map(|r| r.uri).collect();
+            assert_eq!(uris, ["file://a", "file://b", "file://c"]);
+        }
     }
 
     #[test]
-    fn test_on_shutdown_hook_builder() {
-        let shutdown_called = Arc::new(AtomicBool::new(false));
-        let shutdown_called_clone = shutdown_called.clone();
-
-        let server = Server::new("test", "1.0.0")
-            .on_shutdown(move || {
-                shutdown_called_clone.store(true, Ordering::SeqCst);
-            })
-            .build();
+    fn test_tag_filters_include_single_tag() {
+        let router = create_tagged_tools_router();
+        let include = vec!["api".to_string()];
+        let filters = TagFilters::new(Some(&include), None);
+        let tools = router.tools_filtered(None, Some(&filters));
+        assert_eq!(tools.len(), 3, "Expected search, create, admin");
+        assert!(tools.iter().any(|t| t.name == "search"));
+        assert!(tools.iter().any(|t| t.name == "create"));
+        assert!(tools.iter().any(|t| t.name == "admin"));
+    }
 
-        // The hook is stored but not called until shutdown
-        assert!(!shutdown_called.load(Ordering::SeqCst));
+    #[test]
+    fn test_tag_filters_include_multiple_tags_and_logic() {
+        let router = create_tagged_tools_router();
+        let include = vec!["api".to_string(), "public".to_string()];
+        let filters = TagFilters::new(Some(&include), None);
+        let tools = router.tools_filtered(None, Some(&filters));
+        assert_eq!(
+            tools.len(),
+            2,
+            "Expected search, create (both have api AND public)"
+        );
+        assert!(tools.iter().any(|t| t.name == "search"));
+        assert!(tools.iter().any(|t| t.name == "create"));
+    }
 
-        // Manually trigger the shutdown hook
-        server.run_shutdown_hook();
-        assert!(shutdown_called.load(Ordering::SeqCst));
+    #[test]
+    fn test_tag_filters_exclude_single_tag() {
+        let router = create_tagged_tools_router();
+        let exclude = vec!["private".to_string()];
+        let filters = TagFilters::new(None, Some(&exclude));
+        let tools = router.tools_filtered(None, Some(&filters));
+        assert_eq!(tools.len(), 4, "Expected all except admin");
+        assert!(!tools.iter().any(|t| t.name == "admin"));
     }
 
     #[test]
-    fn test_startup_hook_failure() {
-        let server = Server::new("test", "1.0.0")
-            .on_startup(|| Err(std::io::Error::other("startup failed")))
-            .build();
+    fn test_tag_filters_exclude_multiple_tags_or_logic() {
+        let router = create_tagged_tools_router();
+        let exclude = vec!["private".to_string(), "internal".to_string()];
+        let filters = TagFilters::new(None, Some(&exclude));
+        let tools = router.tools_filtered(None, Some(&filters));
+        assert_eq!(tools.len(), 3, "Expected search, create, untagged");
+        assert!(tools.iter().any(|t| t.name == "search"));
+        assert!(tools.iter().any(|t| t.name == "create"));
+        assert!(tools.iter().any(|t| t.name == "untagged"));
+    }
 
-        // Startup should return false on failure
-        let startup_success = server.run_startup_hook();
-        assert!(!startup_success);
+    #[test]
+    fn test_tag_filters_include_and_exclude_combined() {
+        let router = create_tagged_tools_router();
+        let include = vec!["api".to_string()];
+        let exclude = vec!["private".to_string()];
+        let filters = TagFilters::new(Some(&include), Some(&exclude));
+        let tools = router.tools_filtered(None, Some(&filters));
+        assert_eq!(
+            tools.len(),
+            2,
+            "Expected search, create (api but not private)"
+        );
+        assert!(tools.iter().any(|t| t.name == "search"));
+        assert!(tools.iter().any(|t| t.name == "create"));
     }
 
     #[test]
-    fn test_no_hooks_is_ok() {
-        let server = Server::new("test", "1.0.0").build();
+    fn test_tag_filters_case_insensitive() {
+        let router = create_tagged_tools_router();
+        let include = vec!["API".to_string()];
+        let filters = TagFilters::new(Some(&include), None);
+        let tools = router.tools_filtered(None, Some(&filters));
+        assert_eq!(tools.len(), 3, "Should match 'api' tags case-insensitively");
+    }
 
-        // No hooks configured should be fine
-        let startup_success = server.run_startup_hook();
-        assert!(startup_success);
+    #[test]
+    fn test_tag_filters_empty_include_no_filter() {
+        let router = create_tagged_tools_router();
+        let include: Vec<String> = vec![];
+        let filters = TagFilters::new(Some(&include), None);
+        let tools = router.tools_filtered(None, Some(&filters));
+        assert_eq!(tools.len(), 5, "Empty include should not filter");
+    }
 
-        // Shutdown hook should also be a no-op
-        server.run_shutdown_hook();
+    #[test]
+    fn test_tag_filters_no_matches() {
+        let router = create_tagged_tools_router();
+        let include = vec!["nonexistent".to_string()];
+        let filters = TagFilters::new(Some(&include), None);
+        let tools = router.tools_filtered(None, Some(&filters));
+        assert!(tools.is_empty(), "No tools should match nonexistent tag");
     }
 
     #[test]
-    fn test_hooks_only_run_once() {
-        let startup_count = Arc::new(std::sync::atomic::AtomicU32::new(0));
-        let startup_count_clone = startup_count.clone();
+    fn test_handle_tools_list_with_include_tags() {
+        let router = create_tagged_tools_router();
+        let cx = Cx::for_testing();
+        let params = ListToolsParams {
+            cursor: None,
+            include_tags: Some(vec!["public".to_string()]),
+            exclude_tags: None,
+            tag_match: None,
+        };
+        let result = router.handle_tools_list(&cx, params, None);
+        let tools = result.unwrap().tools;
+        assert_eq!(tools.len(), 2, "Expected search, create");
+    }
 
-        let shutdown_count = Arc::new(std::sync::atomic::AtomicU32::new(0));
-        let shutdown_count_clone = shutdown_count.clone();
+    #[test]
+    fn test_handle_tools_list_with_exclude_tags() {
+        let router = create_tagged_tools_router();
+        let cx = Cx::for_testing();
+        let params = ListToolsParams {
+            cursor: None,
+            include_tags: None,
+            exclude_tags: Some(vec!["private".to_string(), "internal".to_string()]),
+            tag_match: None,
+        };
+        let result = router.handle_tools_list(&cx, params, None);
+        let tools = result.unwrap().tools;
+        assert_eq!(tools.len(), 3, "Expected search, create, untagged");
+    }
 
-        let server = Server::new("test", "1.0.0")
-            .on_startup(move || {
-                startup_count_clone.fetch_add(1, Ordering::SeqCst);
-                Ok::<(), std::io::Error>(())
-            })
-            .on_shutdown(move || {
-                shutdown_count_clone.fetch_add(1, Ordering::SeqCst);
-            })
-            .build();
+    fn listed_names(router: &Router, tags: &[&str], tag_match: Option<TagMatch>) -> Vec<String> {
+        let params = ListToolsParams {
+            include_tags: Some(tags.iter().map(|t| t.to_string()).collect()),
+            tag_match,
+            ..ListToolsParams::default()
+        };
+        let result = router.handle_tools_list(&Cx::for_testing(), params, None);
+        result.unwrap().tools.into_iter().map(|t| t.name).collect()
+    }
 
-        // Call startup multiple times
-        server.run_startup_hook();
-        server.run_startup_hook();
-        server.run_startup_hook();
+    #[test]
+    fn test_handle_tools_list_multiple_tags_match_all_by_default() {
+        let router = create_tagged_tools_router();
+        assert_eq!(listed_names(&router, &["api", "write"], None), ["create"]);
+        assert_eq!(
+            listed_names(&router, &["api", "write"], Some(TagMatch::All)),
+            ["create"]
+        );
+    }
 
-        // Should only have run once (hook is taken)
-        assert_eq!(startup_count.load(Ordering::SeqCst), 1);
+    #[test]
+    fn test_handle_tools_list_tag_match_any() {
+        let router = create_tagged_tools_router();
+        assert_eq!(
+            listed_names(&router, &["write", "debug"], Some(TagMatch::Any)),
+            ["create", "debug"]
+        );
+    }
 
-        // Same for shutdown
-        server.run_shutdown_hook();
-        server.run_shutdown_hook();
-        server.run_shutdown_hook();
+    #[test]
+    fn test_handle_tools_list_tag_filter_paginates() {
+        let mut router = create_tagged_tools_router();
+        router.set_list_page_size(Some(2));
+        let cx = Cx::for_testing();
 
-        assert_eq!(shutdown_count.load(Ordering::SeqCst), 1);
+        let mut names = Vec::new();
+        let mut cursor = None;
+        loop {
+            let params = ListToolsParams {
+                cursor,
+                include_tags: Some(vec!["api".to_string()]),
+                ..ListToolsParams::default()
+            };
+            let result = router.handle_tools_list(&cx, params, None).unwrap();
+            names.extend(result.tools.into_iter().map(|t| t.name));
+            cursor = result.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(names, ["admin", "create", "search"]);
     }
 }
 
-/// Deterministic LabRuntime tests for cancel/timeout handling.
-mod lab_runtime_tests {
+// ============================================================================
+// Multiple Handler Tests
+// ============================================================================
+
+#[cfg(test)]
+mod multi_handler_tests {
     use super::*;
-    use asupersync::conformance::{ConformanceTarget, LabRuntimeTarget};
-    use asupersync::lab::{LabConfig, LabRuntime};
-    use std::sync::{Arc, Mutex};
 
-    fn with_lab_runtime<T>(f: impl FnOnce(&mut LabRuntime) -> T) -> T {
-        let mut runtime = LabRuntime::new(LabConfig::new(42).max_steps(2000));
-        f(&mut runtime)
+    /// Second greeting tool with different behavior.
+    struct FormalGreetTool;
+
+    impl ToolHandler for FormalGreetTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: "formal_greet".to_string(),
+                description: Some("Formally greets a user".to_string()),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "name": {"type": "string"}
+                    }
+                }),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+                annotations: None,
+            }
+        }
+
+        fn call(&self, _ctx: &McpContext, arguments: serde_json::Value) -> McpResult<Vec<Content>> {
+            let name = arguments
+                .get("name")
+                .and_then(|v| v.as_str())
+                .unwrap_or("Sir/Madam");
+            Ok(vec![Content::Text {
+                text: format!("Good day, {name}."),
+            }])
+        }
     }
 
     #[test]
-    fn test_lab_runtime_cancelled_tool_call() {
-        with_lab_runtime(|runtime| {
-            let events = Arc::new(Mutex::new(Vec::new()));
-            let events_for_task = Arc::clone(&events);
-
-            LabRuntimeTarget::block_on(runtime, async move {
-                let mut router = Router::new();
-                router.add_tool(CancellationCheckTool);
+    fn test_multiple_tools() {
+        let mut router = Router::new();
+        router.add_tool(GreetTool);
+        router.add_tool(FormalGreetTool);
 
-                let cx = Cx::for_testing();
-                cx.cancel_with(CancelKind::User, None);
+        let tools = router.tools();
+        assert_eq!(tools.len(), 2);
 
-                let params = CallToolParams {
-                    name: "cancellation_check".to_string(),
-                    arguments: Some(serde_json::json!({})),
-                    meta: None,
-                };
-                let result = router.handle_tools_call(
-                    &cx,
-                    1,
-                    params,
-                    &Budget::INFINITE,
-                    SessionState::new(),
-                    None,
-                    None,
-                );
+        // Call both tools
+        let cx = Cx::for_testing();
+        let budget = Budget::INFINITE;
 
-                let err = result.as_ref().err().map(|e| e.message.clone());
-                events_for_task
-                    .lock()
-                    .expect("events lock poisoned")
-                    .push(format!("cancelled_result={err:?}"));
-                info!(
-                    target: targets::SESSION,
-                    "lab cancel outcome ts={} err={:?}",
-                    chrono::Utc::now().to_rfc3339(),
-                    err
-                );
+        let result1 = router.handle_tools_call(
+            &cx,
+            1,
+            None,
+            CallToolParams {
+                name: "greet".to_string(),
+                arguments: Some(serde_json::json!({"name": "Alice"})),
+                meta: None,
+            },
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+            false,
+        );
+        assert!(result1.is_ok());
 
-                assert!(result.is_err());
-            });
+        let result2 = router.handle_tools_call(
+            &cx,
+            2,
+            None,
+            CallToolParams {
+                name: "formal_greet".to_string(),
+                arguments: Some(serde_json::json!({"name": "Alice"})),
+                meta: None,
+            },
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+            false,
+        );
+        assert!(result2.is_ok());
 
-            assert_eq!(events.lock().expect("events lock poisoned").len(), 1);
-        });
+        // Verify different outputs
+        if let Content::Text { text: text1 } = &result1.unwrap().content[0] {
+            if let Content::Text { text: text2 } = &result2.unwrap().content[0] {
+                assert_eq!(text1, "Hello, Alice!");
+                assert_eq!(text2, "Good day, Alice.");
+            }
+        }
     }
 
     #[test]
-    fn test_lab_runtime_budget_exhaustion_resource_read() {
-        with_lab_runtime(|runtime| {
-            let events = Arc::new(Mutex::new(Vec::new()));
-            let events_for_task = Arc::clone(&events);
-
-            LabRuntimeTarget::block_on(runtime, async move {
-                let mut router = Router::new();
-                router.add_resource(StaticResource {
-                    uri: "resource://test".to_string(),
-                    content: "Test content".to_string(),
-                });
-
-                let cx = Cx::for_testing();
-                let budget = Budget::unlimited().with_poll_quota(0);
-                let params = ReadResourceParams {
-                    uri: "resource://test".to_string(),
-                    meta: None,
-                };
-
-                let result = router.handle_resources_read(
-                    &cx,
-                    1,
-                    &params,
-                    &budget,
-                    SessionState::new(),
-                    None,
-                    None,
-                );
-
-                let err = result.as_ref().err().map(|e| e.message.clone());
-                events_for_task
-                    .lock()
-                    .expect("events lock poisoned")
-                    .push(format!("budget_result={err:?}"));
-                info!(
-                    target: targets::SESSION,
-                    "lab budget outcome ts={} err={:?}",
-                    chrono::Utc::now().to_rfc3339(),
-                    err
-                );
+    fn test_multiple_resources() {
+        let mut router = Router::new();
+        router.add_resource(StaticResource {
+            uri: "resource://a".to_string(),
+            content: "Content A".to_string(),
+        });
+        router.add_resource(StaticResource {
+            uri: "resource://b".to_string(),
+            content: "Content B".to_string(),
+        });
 
-                assert!(result.is_err());
-            });
+        let resources = router.resources();
+        assert_eq!(resources.len(), 2);
 
-            assert_eq!(events.lock().expect("events lock poisoned").len(), 1);
-        });
-    }
+        let cx = Cx::for_testing();
+        let budget = Budget::INFINITE;
 
-    #[test]
-    fn test_lab_runtime_deadline_progression() {
-        with_lab_runtime(|runtime| {
-            let budget = Budget::with_deadline_secs(1);
-            let start = runtime.now();
-            assert!(!budget.is_past_deadline(start));
+        let result_a = router.handle_resources_read(
+            &cx,
+            1,
+            None,
+            &ReadResourceParams {
+                uri: "resource://a".to_string(),
+                range: None,
+                if_none_match: None,
+                if_modified_since: None,
+                meta: None,
+            },
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+        );
+        let result_b = router.handle_resources_read(
+            &cx,
+            2,
+            None,
+            &ReadResourceParams {
+                uri: "resource://b".to_string(),
+                range: None,
+                if_none_match: None,
+                if_modified_since: None,
+                meta: None,
+            },
+            &budget,
+            SessionState::new(),
+            None,
+            None,
+        );
 
-            runtime.advance_time(Duration::from_secs(2).as_nanos() as u64);
-            let end = runtime.now();
-            assert!(budget.is_past_deadline(end));
-            info!(
-                target: targets::SESSION,
-                "lab deadline progression start={:?} end={:?}",
-                start,
-                end
-            );
-        });
+        assert_eq!(
+            result_a.unwrap().contents[0].text,
+            Some("Content A".to_string())
+        );
+        assert_eq!(
+            result_b.unwrap().contents[0].text,
+            Some("Content B".to_string())
+        );
     }
 }
 
 // ============================================================================
-// Mount/Composition Tests
+// Session State Tests
 // ============================================================================
 
-mod mount_tests {
+mod session_state_tests {
     use super::*;
-    use crate::Router;
 
-    /// A simple tool for mount tests.
-    struct QueryTool;
+    /// Tool that increments a counter in session state.
+    struct CounterTool;
 
-    impl ToolHandler for QueryTool {
+    impl ToolHandler for CounterTool {
         fn definition(&self) -> Tool {
             Tool {
-                name: "query".to_string(),
-                description: Some("Executes a query".to_string()),
-                input_schema: serde_json::json!({
-                    "type": "object",
-                    "properties": {
-                        "sql": {"type": "string"}
-                    }
-                }),
+                name: "increment".to_string(),
+                description: Some("Increments a counter in session state".to_string()),
+                input_schema: serde_json::json!({"type": "object"}),
                 output_schema: None,
                 icon: None,
                 version: None,
@@ -3989,376 +4962,3709 @@ mod mount_tests {
             }
         }
 
-        fn call(&self, _ctx: &McpContext, arguments: serde_json::Value) -> McpResult<Vec<Content>> {
-            let sql = arguments.get("sql").and_then(|v| v.as_str()).unwrap_or("");
+        fn call(&self, ctx: &McpContext, _arguments: serde_json::Value) -> McpResult<Vec<Content>> {
+            let count: i32 = ctx.get_state("counter").unwrap_or(0);
+            let new_count = count + 1;
+            ctx.set_state("counter", new_count);
             Ok(vec![Content::Text {
-                text: format!("Query result: {sql}"),
+                text: format!("Counter: {new_count}"),
             }])
         }
     }
 
-    /// Another tool for mount tests.
-    struct InsertTool;
+    #[test]
+    fn test_session_state_persists_across_calls() {
+        let mut router = Router::new();
+        router.add_tool(CounterTool);
 
-    impl ToolHandler for InsertTool {
-        fn definition(&self) -> Tool {
-            Tool {
-                name: "insert".to_string(),
-                description: Some("Inserts data".to_string()),
-                input_schema: serde_json::json!({"type": "object"}),
-                output_schema: None,
-                icon: None,
-                version: None,
-                tags: vec![],
-                annotations: None,
-            }
+        let cx = Cx::for_testing();
+        let budget = Budget::INFINITE;
+
+        // Create a shared session state
+        let state = SessionState::new();
+
+        // First call - counter should be 1
+        let params = CallToolParams {
+            name: "increment".to_string(),
+            arguments: None,
+            meta: None,
+        };
+        let result1 = router.handle_tools_call(
+            &cx,
+            1,
+            None,
+            params.clone(),
+            &budget,
+            state.clone(),
+            None,
+            None,
+            false,
+        );
+        assert!(result1.is_ok());
+        if let Content::Text { text } = &result1.unwrap().content[0] {
+            assert_eq!(text, "Counter: 1");
         }
 
-        fn call(
-            &self,
-            _ctx: &McpContext,
-            _arguments: serde_json::Value,
-        ) -> McpResult<Vec<Content>> {
-            Ok(vec![Content::Text {
-                text: "Inserted".to_string(),
-            }])
+        // Second call with same state - counter should be 2
+        let result2 = router.handle_tools_call(
+            &cx,
+            2,
+            None,
+            params.clone(),
+            &budget,
+            state.clone(),
+            None,
+            None,
+            false,
+        );
+        assert!(result2.is_ok());
+        if let Content::Text { text } = &result2.unwrap().content[0] {
+            assert_eq!(text, "Counter: 2");
+        }
+
+        // Third call - counter should be 3
+        let result3 = router.handle_tools_call(
+            &cx,
+            3,
+            None,
+            params,
+            &budget,
+            state.clone(),
+            None,
+            None,
+            false,
+        );
+        assert!(result3.is_ok());
+        if let Content::Text { text } = &result3.unwrap().content[0] {
+            assert_eq!(text, "Counter: 3");
         }
     }
 
-    /// A resource for mount tests.
-    struct ConfigResource;
+    #[test]
+    fn test_different_session_states_are_independent() {
+        let mut router = Router::new();
+        router.add_tool(CounterTool);
 
-    impl ResourceHandler for ConfigResource {
-        fn definition(&self) -> Resource {
-            Resource {
-                uri: "config://app".to_string(),
-                name: "App Config".to_string(),
-                description: Some("Application configuration".to_string()),
-                mime_type: Some("application/json".to_string()),
-                icon: None,
-                version: None,
-                tags: vec![],
-            }
-        }
+        let cx = Cx::for_testing();
+        let budget = Budget::INFINITE;
 
-        fn read(&self, _ctx: &McpContext) -> McpResult<Vec<ResourceContent>> {
-            Ok(vec![ResourceContent {
-                uri: "config://app".to_string(),
-                text: Some(r#"{"debug": true}"#.to_string()),
-                mime_type: Some("application/json".to_string()),
-                blob: None,
-            }])
+        // Create two separate session states
+        let state1 = SessionState::new();
+        let state2 = SessionState::new();
+
+        let params = CallToolParams {
+            name: "increment".to_string(),
+            arguments: None,
+            meta: None,
+        };
+
+        // Call with state1 twice
+        router
+            .handle_tools_call(
+                &cx,
+                1,
+                None,
+                params.clone(),
+                &budget,
+                state1.clone(),
+                None,
+                None,
+                false,
+            )
+            .unwrap();
+        let result1 = router
+            .handle_tools_call(
+                &cx,
+                2,
+                None,
+                params.clone(),
+                &budget,
+                state1.clone(),
+                None,
+                None,
+                false,
+            )
+            .unwrap();
+
+        // Call with state2 once
+        let result2 = router
+            .handle_tools_call(
+                &cx,
+                3,
+                None,
+                params,
+                &budget,
+                state2.clone(),
+                None,
+                None,
+                false,
+            )
+            .unwrap();
+
+        // state1 should have counter=2, state2 should have counter=1
+        if let Content::Text { text } = &result1.content[0] {
+            assert_eq!(text, "Counter: 2");
+        }
+        if let Content::Text { text } = &result2.content[0] {
+            assert_eq!(text, "Counter: 1");
         }
     }
+}
 
-    /// A prompt for mount tests.
-    struct GreetingPrompt;
+// ============================================================================
+// Console Config Integration Tests
+// ============================================================================
 
-    impl PromptHandler for GreetingPrompt {
-        fn definition(&self) -> Prompt {
-            Prompt {
-                name: "greeting".to_string(),
-                description: Some("A greeting prompt".to_string()),
-                arguments: Vec::new(),
-                icon: None,
-                version: None,
-                tags: vec![],
-            }
-        }
+mod console_config_tests {
+    use crate::{BannerStyle, ConsoleConfig, Server, TrafficVerbosity};
 
-        fn get(
-            &self,
-            _ctx: &McpContext,
-            _arguments: HashMap<String, String>,
-        ) -> McpResult<Vec<PromptMessage>> {
-            Ok(vec![PromptMessage {
-                role: Role::User,
-                content: Content::Text {
-                    text: "Hello!".to_string(),
-                },
-            }])
-        }
+    #[test]
+    fn test_server_default_console_config() {
+        let server = Server::new("test", "1.0.0").build();
+        let config = server.console_config();
+
+        // Default config should show banner
+        assert!(config.show_banner);
+        assert_eq!(config.banner_style, BannerStyle::Full);
+    }
+
+    #[test]
+    fn test_server_with_console_config() {
+        let config = ConsoleConfig::new()
+            .with_banner(BannerStyle::Compact)
+            .plain_mode();
+
+        let server = Server::new("test", "1.0.0")
+            .with_console_config(config)
+            .build();
+
+        assert_eq!(server.console_config().banner_style, BannerStyle::Compact);
+        assert!(server.console_config().force_plain);
+    }
+
+    #[test]
+    fn test_server_without_banner() {
+        let server = Server::new("test", "1.0.0").without_banner().build();
+
+        assert!(!server.console_config().show_banner);
+        assert_eq!(server.console_config().banner_style, BannerStyle::None);
+    }
+
+    #[test]
+    fn test_server_with_banner_style() {
+        let server = Server::new("test", "1.0.0")
+            .with_banner(BannerStyle::Minimal)
+            .build();
+
+        assert!(server.console_config().show_banner);
+        assert_eq!(server.console_config().banner_style, BannerStyle::Minimal);
+    }
+
+    #[test]
+    fn test_server_with_traffic_logging() {
+        let server = Server::new("test", "1.0.0")
+            .with_traffic_logging(TrafficVerbosity::Summary)
+            .build();
+
+        assert!(server.console_config().show_request_traffic);
+        assert_eq!(
+            server.console_config().traffic_verbosity,
+            TrafficVerbosity::Summary
+        );
+    }
+
+    #[test]
+    fn test_server_with_periodic_stats() {
+        let server = Server::new("test", "1.0.0").with_periodic_stats(30).build();
+
+        assert!(server.console_config().show_stats_periodic);
+        assert_eq!(server.console_config().stats_interval_secs, 30);
+    }
+
+    #[test]
+    fn test_server_plain_mode() {
+        let server = Server::new("test", "1.0.0").plain_mode().build();
+
+        assert!(server.console_config().force_plain);
+    }
+
+    #[test]
+    fn test_server_force_color() {
+        let server = Server::new("test", "1.0.0").force_color().build();
+
+        assert_eq!(server.console_config().force_color, Some(true));
+    }
+
+    #[test]
+    fn test_console_config_chaining() {
+        let server = Server::new("test", "1.0.0")
+            .with_banner(BannerStyle::Compact)
+            .with_traffic_logging(TrafficVerbosity::Headers)
+            .with_periodic_stats(60)
+            .plain_mode()
+            .build();
+
+        let config = server.console_config();
+        assert_eq!(config.banner_style, BannerStyle::Compact);
+        assert_eq!(config.traffic_verbosity, TrafficVerbosity::Headers);
+        assert!(config.show_stats_periodic);
+        assert_eq!(config.stats_interval_secs, 60);
+        assert!(config.force_plain);
+    }
+
+    #[test]
+    fn test_ready_event_emitted_once_with_counts() {
+        let server = Server::new("ready-server", "2.1.0")
+            .tool(super::GreetTool)
+            .resource(super::StaticResource {
+                uri: "file://ready".to_string(),
+                content: "ready".to_string(),
+            })
+            .prompt(super::GreetingPrompt)
+            .with_console_config(ConsoleConfig::new().emit_ready_event(true))
+            .build();
+
+        let mut out = Vec::new();
+        server.emit_ready_event(&mut out, "stdio");
+        server.emit_ready_event(&mut out, "stdio");
+
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 1);
+        let event: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(
+            event,
+            serde_json::json!({
+                "event": "ready",
+                "name": "ready-server",
+                "version": "2.1.0",
+                "tools": 1,
+                "resources": 1,
+                "prompts": 1,
+                "transport": "stdio",
+            })
+        );
+    }
+
+    #[test]
+    fn test_build_info_in_ready_event_and_health() {
+        let info = crate::BuildInfo {
+            git_sha: Some("1a2b3c4".to_string()),
+            build_time: None,
+            rust_version: None,
+        };
+        let server = Server::new("ready-server", "2.1.0")
+            .with_build_info(info.clone())
+            .with_console_config(ConsoleConfig::new().emit_ready_event(true))
+            .build();
+
+        let mut out = Vec::new();
+        server.emit_ready_event(&mut out, "stdio");
+        let event: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(event["build"], serde_json::json!({"gitSha": "1a2b3c4"}));
+        assert_eq!(server.health().build, Some(info));
+    }
+
+    #[test]
+    fn test_ready_event_disabled_by_default() {
+        let server = Server::new("test", "1.0.0").build();
+
+        let mut out = Vec::new();
+        server.emit_ready_event(&mut out, "stdio");
+        assert!(out.is_empty());
+    }
+}
+
+/// Tests for lifecycle hooks (on_startup, on_shutdown).
+mod lifespan_tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[test]
+    fn test_on_startup_hook_builder() {
+        let startup_called = Arc::new(AtomicBool::new(false));
+        let startup_called_clone = startup_called.clone();
+
+        let server = Server::new("test", "1.0.0")
+            .on_startup(move || {
+                startup_called_clone.store(true, Ordering::SeqCst);
+                Ok::<(), std::io::Error>(())
+            })
+            .build();
+
+        // The hook is stored but not called until run
+        // Verify that the lifespan is stored (we can't call run_startup_hook directly
+        // since it's private, but we verify the builder works)
+        assert!(!startup_called.load(Ordering::SeqCst));
+
+        // Manually trigger the startup hook via the public interface
+        // (In production, this would be called by run_loop)
+        let startup_success = server.run_startup_hook();
+        assert!(startup_success);
+        assert!(startup_called.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_on_shutdown_hook_builder() {
+        let shutdown_called = Arc::new(AtomicBool::new(false));
+        let shutdown_called_clone = shutdown_called.clone();
+
+        let server = Server::new("test", "1.0.0")
+            .on_shutdown(move || {
+                shutdown_called_clone.store(true, Ordering::SeqCst);
+            })
+            .build();
+
+        // The hook is stored but not called until shutdown
+        assert!(!shutdown_called.load(Ordering::SeqCst));
+
+        // Manually trigger the shutdown hook
+        server.run_shutdown_hook(&ShutdownReason::CleanClose);
+        assert!(shutdown_called.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_startup_hook_failure() {
+        let server = Server::new("test", "1.0.0")
+            .on_startup(|| Err(std::io::Error::other("startup failed")))
+            .build();
+
+        // Startup should return false on failure
+        let startup_success = server.run_startup_hook();
+        assert!(!startup_success);
+    }
+
+    #[test]
+    fn test_no_hooks_is_ok() {
+        let server = Server::new("test", "1.0.0").build();
+
+        // No hooks configured should be fine
+        let startup_success = server.run_startup_hook();
+        assert!(startup_success);
+
+        // Shutdown hook should also be a no-op
+        server.run_shutdown_hook(&ShutdownReason::CleanClose);
+    }
+
+    #[test]
+    fn test_hooks_only_run_once() {
+        let startup_count = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let startup_count_clone = startup_count.clone();
+
+        let shutdown_count = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let shutdown_count_clone = shutdown_count.clone();
+
+        let server = Server::new("test", "1.0.0")
+            .on_startup(move || {
+                startup_count_clone.fetch_add(1, Ordering::SeqCst);
+                Ok::<(), std::io::Error>(())
+            })
+            .on_shutdown(move || {
+                shutdown_count_clone.fetch_add(1, Ordering::SeqCst);
+            })
+            .build();
+
+        // Call startup multiple times
+        server.run_startup_hook();
+        server.run_startup_hook();
+        server.run_startup_hook();
+
+        // Should only have run once (hook is taken)
+        assert_eq!(startup_count.load(Ordering::SeqCst), 1);
+
+        // Same for shutdown
+        server.run_shutdown_hook(&ShutdownReason::CleanClose);
+        server.run_shutdown_hook(&ShutdownReason::CleanClose);
+        server.run_shutdown_hook(&ShutdownReason::CleanClose);
+
+        assert_eq!(shutdown_count.load(Ordering::SeqCst), 1);
+    }
+}
+
+/// Deterministic LabRuntime tests for cancel/timeout handling.
+mod lab_runtime_tests {
+    use super::*;
+    use asupersync::conformance::{ConformanceTarget, LabRuntimeTarget};
+    use asupersync::lab::{LabConfig, LabRuntime};
+    use std::sync::{Arc, Mutex};
+
+    fn with_lab_runtime<T>(f: impl FnOnce(&mut LabRuntime) -> T) -> T {
+        let mut runtime = LabRuntime::new(LabConfig::new(42).max_steps(2000));
+        f(&mut runtime)
+    }
+
+    #[test]
+    fn test_lab_runtime_cancelled_tool_call() {
+        with_lab_runtime(|runtime| {
+            let events = Arc::new(Mutex::new(Vec::new()));
+            let events_for_task = Arc::clone(&events);
+
+            LabRuntimeTarget::block_on(runtime, async move {
+                let mut router = Router::new();
+                router.add_tool(CancellationCheckTool);
+
+                let cx = Cx::for_testing();
+                cx.cancel_with(CancelKind::User, None);
+
+                let params = CallToolParams {
+                    name: "cancellation_check".to_string(),
+                    arguments: Some(serde_json::json!({})),
+                    meta: None,
+                };
+                let result = router.handle_tools_call(
+                    &cx,
+                    1,
+                    None,
+                    params,
+                    &Budget::INFINITE,
+                    SessionState::new(),
+                    None,
+                    None,
+                    false,
+                );
+
+                let err = result.as_ref().err().map(|e| e.message.clone());
+                events_for_task
+                    .lock()
+                    .expect("events lock poisoned")
+                    .push(format!("cancelled_result={err:?}"));
+                info!(
+                    target: targets::SESSION,
+                    "lab cancel outcome ts={} err={:?}",
+                    chrono::Utc::now().to_rfc3339(),
+                    err
+                );
+
+                assert!(result.is_err());
+            });
+
+            assert_eq!(events.lock().expect("events lock poisoned").len(), 1);
+        });
+    }
+
+    #[test]
+    fn test_lab_runtime_budget_exhaustion_resource_read() {
+        with_lab_runtime(|runtime| {
+            let events = Arc::new(Mutex::new(Vec::new()));
+            let events_for_task = Arc::clone(&events);
+
+            LabRuntimeTarget::block_on(runtime, async move {
+                let mut router = Router::new();
+                router.add_resource(StaticResource {
+                    uri: "resource://test".to_string(),
+                    content: "Test content".to_string(),
+                });
+
+                let cx = Cx::for_testing();
+                let budget = Budget::unlimited().with_poll_quota(0);
+                let params = ReadResourceParams {
+                    uri: "resource://test".to_string(),
+                    range: None,
+                    if_none_match: None,
+                    if_modified_since: None,
+                    meta: None,
+                };
+
+                let result = router.handle_resources_read(
+                    &cx,
+                    1,
+                    None,
+                    &params,
+                    &budget,
+                    SessionState::new(),
+                    None,
+                    None,
+                );
+
+                let err = result.as_ref().err().map(|e| e.message.clone());
+                events_for_task
+                    .lock()
+                    .expect("events lock poisoned")
+                    .push(format!("budget_result={err:?}"));
+                info!(
+                    target: targets::SESSION,
+                    "lab budget outcome ts={} err={:?}",
+                    chrono::Utc::now().to_rfc3339(),
+                    err
+                );
+
+                assert!(result.is_err());
+            });
+
+            assert_eq!(events.lock().expect("events lock poisoned").len(), 1);
+        });
+    }
+
+    #[test]
+    fn test_lab_runtime_deadline_progression() {
+        with_lab_runtime(|runtime| {
+            let budget = Budget::with_deadline_secs(1);
+            let start = runtime.now();
+            assert!(!budget.is_past_deadline(start));
+
+            runtime.advance_time(Duration::from_secs(2).as_nanos() as u64);
+            let end = runtime.now();
+            assert!(budget.is_past_deadline(end));
+            info!(
+                target: targets::SESSION,
+                "lab deadline progression start={:?} end={:?}",
+                start,
+                end
+            );
+        });
+    }
+}
+
+// ============================================================================
+// Mount/Composition Tests
+// ============================================================================
+
+mod mount_tests {
+    use super::*;
+    use crate::Router;
+
+    /// A simple tool for mount tests.
+    struct QueryTool;
+
+    impl ToolHandler for QueryTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: "query".to_string(),
+                description: Some("Executes a query".to_string()),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "sql": {"type": "string"}
+                    }
+                }),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+                annotations: None,
+            }
+        }
+
+        fn call(&self, _ctx: &McpContext, arguments: serde_json::Value) -> McpResult<Vec<Content>> {
+            let sql = arguments.get("sql").and_then(|v| v.as_str()).unwrap_or("");
+            Ok(vec![Content::Text {
+                text: format!("Query result: {sql}"),
+            }])
+        }
+    }
+
+    /// Another tool for mount tests.
+    struct InsertTool;
+
+    impl ToolHandler for InsertTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: "insert".to_string(),
+                description: Some("Inserts data".to_string()),
+                input_schema: serde_json::json!({"type": "object"}),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+                annotations: None,
+            }
+        }
+
+        fn call(
+            &self,
+            _ctx: &McpContext,
+            _arguments: serde_json::Value,
+        ) -> McpResult<Vec<Content>> {
+            Ok(vec![Content::Text {
+                text: "Inserted".to_string(),
+            }])
+        }
+    }
+
+    /// A resource for mount tests.
+    struct ConfigResource;
+
+    impl ResourceHandler for ConfigResource {
+        fn definition(&self) -> Resource {
+            Resource {
+                uri: "config://app".to_string(),
+                name: "App Config".to_string(),
+                description: Some("Application configuration".to_string()),
+                mime_type: Some("application/json".to_string()),
+                icon: None,
+                version: None,
+                tags: vec![],
+            }
+        }
+
+        fn read(&self, _ctx: &McpContext) -> McpResult<Vec<ResourceContent>> {
+            Ok(vec![ResourceContent {
+                uri: "config://app".to_string(),
+                text: Some(r#"{"debug": true}"#.to_string()),
+                mime_type: Some("application/json".to_string()),
+                blob: None,
+                etag: None,
+                last_modified: None,
+            }])
+        }
+    }
+
+    /// A prompt for mount tests.
+    struct GreetingPrompt;
+
+    impl PromptHandler for GreetingPrompt {
+        fn definition(&self) -> Prompt {
+            Prompt {
+                name: "greeting".to_string(),
+                description: Some("A greeting prompt".to_string()),
+                arguments: Vec::new(),
+                icon: None,
+                version: None,
+                tags: vec![],
+            }
+        }
+
+        fn get(
+            &self,
+            _ctx: &McpContext,
+            _arguments: HashMap<String, String>,
+        ) -> McpResult<Vec<PromptMessage>> {
+            Ok(vec![PromptMessage {
+                role: Role::User,
+                content: Content::Text {
+                    text: "Hello!".to_string(),
+                },
+            }])
+        }
+    }
+
+    #[test]
+    fn test_mount_with_prefix_renames_tools() {
+        let mut main_router = Router::new();
+        let mut db_router = Router::new();
+        db_router.add_tool(QueryTool);
+        db_router.add_tool(InsertTool);
+
+        let result = main_router.mount(db_router, Some("db"));
+
+        assert_eq!(result.tools, 2);
+        assert!(main_router.get_tool("db/query").is_some());
+        assert!(main_router.get_tool("db/insert").is_some());
+        assert!(main_router.get_tool("query").is_none());
+        assert!(main_router.get_tool("insert").is_none());
+    }
+
+    #[test]
+    fn test_mount_without_prefix_keeps_names() {
+        let mut main_router = Router::new();
+        let mut other_router = Router::new();
+        other_router.add_tool(QueryTool);
+
+        let result = main_router.mount(other_router, None);
+
+        assert_eq!(result.tools, 1);
+        assert!(main_router.get_tool("query").is_some());
+    }
+
+    #[test]
+    fn test_mount_resources_with_prefix() {
+        let mut main_router = Router::new();
+        let mut other_router = Router::new();
+        other_router.add_resource(ConfigResource);
+
+        let result = main_router.mount(other_router, Some("service"));
+
+        assert_eq!(result.resources, 1);
+        assert!(main_router.get_resource("service/config://app").is_some());
+        assert!(main_router.get_resource("config://app").is_none());
+    }
+
+    #[test]
+    fn test_mount_prompts_with_prefix() {
+        let mut main_router = Router::new();
+        let mut other_router = Router::new();
+        other_router.add_prompt(GreetingPrompt);
+
+        let result = main_router.mount(other_router, Some("templates"));
+
+        assert_eq!(result.prompts, 1);
+        assert!(main_router.get_prompt("templates/greeting").is_some());
+        assert!(main_router.get_prompt("greeting").is_none());
+    }
+
+    #[test]
+    fn test_mount_conflict_generates_warning() {
+        let mut main_router = Router::new();
+        main_router.add_tool(QueryTool);
+
+        let mut other_router = Router::new();
+        other_router.add_tool(QueryTool);
+
+        // Mount without prefix, causing a conflict
+        let result = main_router.mount(other_router, None);
+
+        assert_eq!(result.tools, 1);
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].contains("already exists"));
+    }
+
+    #[test]
+    fn test_mount_preserves_tool_definition() {
+        let mut main_router = Router::new();
+        let mut db_router = Router::new();
+        db_router.add_tool(QueryTool);
+
+        main_router.mount(db_router, Some("db"));
+
+        let tools = main_router.tools();
+        let tool = tools.iter().find(|t| t.name == "db/query").unwrap();
+        assert_eq!(tool.description, Some("Executes a query".to_string()));
+    }
+
+    #[test]
+    fn test_mount_all_components() {
+        let mut main_router = Router::new();
+        let mut other_router = Router::new();
+        other_router.add_tool(QueryTool);
+        other_router.add_resource(ConfigResource);
+        other_router.add_prompt(GreetingPrompt);
+
+        let result = main_router.mount(other_router, Some("sub"));
+
+        assert_eq!(result.tools, 1);
+        assert_eq!(result.resources, 1);
+        assert_eq!(result.prompts, 1);
+        assert!(result.has_components());
+    }
+
+    #[test]
+    fn test_selective_mount_tools_only() {
+        let db_server = Server::new("db", "1.0")
+            .tool(QueryTool)
+            .resource(ConfigResource)
+            .prompt(GreetingPrompt)
+            .build();
+
+        let main = Server::new("main", "1.0")
+            .mount_tools(db_server, Some("db"))
+            .build();
+
+        let tools = main.tools();
+        let resources = main.resources();
+        let prompts = main.prompts();
+
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "db/query");
+        assert!(resources.is_empty());
+        assert!(prompts.is_empty());
+    }
+
+    #[test]
+    fn test_selective_mount_resources_only() {
+        let data_server = Server::new("data", "1.0")
+            .tool(QueryTool)
+            .resource(ConfigResource)
+            .build();
+
+        let main = Server::new("main", "1.0")
+            .mount_resources(data_server, Some("data"))
+            .build();
+
+        let tools = main.tools();
+        let resources = main.resources();
+
+        assert!(tools.is_empty());
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].uri, "data/config://app");
+    }
+
+    #[test]
+    fn test_selective_mount_prompts_only() {
+        let templates_server = Server::new("templates", "1.0")
+            .tool(QueryTool)
+            .prompt(GreetingPrompt)
+            .build();
+
+        let main = Server::new("main", "1.0")
+            .mount_prompts(templates_server, Some("tmpl"))
+            .build();
+
+        let tools = main.tools();
+        let prompts = main.prompts();
+
+        assert!(tools.is_empty());
+        assert_eq!(prompts.len(), 1);
+        assert_eq!(prompts[0].name, "tmpl/greeting");
+    }
+
+    #[test]
+    fn test_full_mount_via_server_builder() {
+        let db_server = Server::new("db", "1.0")
+            .tool(QueryTool)
+            .tool(InsertTool)
+            .build();
+
+        let api_server = Server::new("api", "1.0").prompt(GreetingPrompt).build();
+
+        let main = Server::new("main", "1.0")
+            .tool(GreetTool)
+            .mount(db_server, Some("db"))
+            .mount(api_server, Some("api"))
+            .build();
+
+        let tools = main.tools();
+        let prompts = main.prompts();
+
+        // Should have original greet + mounted db/query and db/insert
+        assert_eq!(tools.len(), 3);
+        let tool_names: Vec<_> = tools.iter().map(|t| t.name.as_str()).collect();
+        assert!(tool_names.contains(&"greet"));
+        assert!(tool_names.contains(&"db/query"));
+        assert!(tool_names.contains(&"db/insert"));
+
+        // Should have api/greeting
+        assert_eq!(prompts.len(), 1);
+        assert_eq!(prompts[0].name, "api/greeting");
+    }
+
+    #[test]
+    fn test_nested_mounting() {
+        // Create inner server
+        let inner = Server::new("inner", "1.0").tool(QueryTool).build();
+
+        // Mount inner into middle
+        let middle = Server::new("middle", "1.0")
+            .mount(inner, Some("inner"))
+            .build();
+
+        // Mount middle into outer
+        let outer = Server::new("outer", "1.0")
+            .mount(middle, Some("middle"))
+            .build();
+
+        let tools = outer.tools();
+        assert_eq!(tools.len(), 1);
+        // Tool should be at middle/inner/query
+        assert_eq!(tools[0].name, "middle/inner/query");
+    }
+
+    #[test]
+    fn test_prefix_validation_rejects_slashes() {
+        let mut router = Router::new();
+        let mut other = Router::new();
+        other.add_tool(QueryTool);
+
+        let result = router.mount(other, Some("bad/prefix"));
+
+        // Should still mount but generate a warning
+        assert!(!result.warnings.is_empty());
+        assert!(result.warnings[0].contains("slash"));
+    }
+
+    #[test]
+    fn test_mounted_tool_can_be_called() {
+        let db_server = Server::new("db", "1.0").tool(QueryTool).build();
+
+        let main = Server::new("main", "1.0")
+            .mount(db_server, Some("db"))
+            .build();
+
+        // Get the tool handler
+        let tools = main.tools();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "db/query");
+
+        // Verify the definition is correct
+        let tool = &tools[0];
+        assert_eq!(tool.description, Some("Executes a query".to_string()));
+    }
+
+    #[test]
+    fn test_mount_empty_router() {
+        let mut main_router = Router::new();
+        let empty_router = Router::new();
+
+        let result = main_router.mount(empty_router, Some("empty"));
+
+        assert_eq!(result.tools, 0);
+        assert_eq!(result.resources, 0);
+        assert_eq!(result.prompts, 0);
+        assert!(!result.has_components());
+    }
+}
+
+// ============================================================================
+// Duplicate Behavior Tests
+// ============================================================================
+
+mod duplicate_behavior_tests {
+    use super::*;
+    use crate::{DuplicateBehavior, Router};
+
+    /// A simple tool for duplicate tests.
+    struct DupTool {
+        id: u32,
+    }
+
+    impl DupTool {
+        fn new(id: u32) -> Self {
+            Self { id }
+        }
+    }
+
+    impl ToolHandler for DupTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: "dup_tool".to_string(),
+                description: Some(format!("Tool #{}", self.id)),
+                input_schema: serde_json::json!({"type": "object"}),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+                annotations: None,
+            }
+        }
+
+        fn call(
+            &self,
+            _ctx: &McpContext,
+            _arguments: serde_json::Value,
+        ) -> McpResult<Vec<Content>> {
+            Ok(vec![Content::Text {
+                text: format!("Tool #{}", self.id),
+            }])
+        }
+    }
+
+    #[test]
+    fn test_duplicate_behavior_error_returns_error() {
+        let mut router = Router::new();
+        router.add_tool(DupTool::new(1));
+
+        let result = router.add_tool_with_behavior(DupTool::new(2), DuplicateBehavior::Error);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().message.contains("already exists"));
+    }
+
+    #[test]
+    fn test_duplicate_behavior_warn_keeps_original() {
+        let mut router = Router::new();
+        router.add_tool(DupTool::new(1));
+
+        let result = router.add_tool_with_behavior(DupTool::new(2), DuplicateBehavior::Warn);
+        assert!(result.is_ok());
+
+        // Original should be kept
+        let tool = router.get_tool("dup_tool").unwrap();
+        assert_eq!(tool.definition().description, Some("Tool #1".to_string()));
+    }
+
+    #[test]
+    fn test_duplicate_behavior_replace_replaces() {
+        let mut router = Router::new();
+        router.add_tool(DupTool::new(1));
+
+        let result = router.add_tool_with_behavior(DupTool::new(2), DuplicateBehavior::Replace);
+        assert!(result.is_ok());
+
+        // New one should replace original
+        let tool = router.get_tool("dup_tool").unwrap();
+        assert_eq!(tool.definition().description, Some("Tool #2".to_string()));
+    }
+
+    #[test]
+    fn test_duplicate_behavior_ignore_keeps_original() {
+        let mut router = Router::new();
+        router.add_tool(DupTool::new(1));
+
+        let result = router.add_tool_with_behavior(DupTool::new(2), DuplicateBehavior::Ignore);
+        assert!(result.is_ok());
+
+        // Original should be kept
+        let tool = router.get_tool("dup_tool").unwrap();
+        assert_eq!(tool.definition().description, Some("Tool #1".to_string()));
+    }
+
+    #[test]
+    fn test_duplicate_behavior_default_is_warn() {
+        assert_eq!(DuplicateBehavior::default(), DuplicateBehavior::Warn);
+    }
+
+    #[test]
+    fn test_no_duplicate_succeeds_for_all_behaviors() {
+        for behavior in [
+            DuplicateBehavior::Error,
+            DuplicateBehavior::Warn,
+            DuplicateBehavior::Replace,
+            DuplicateBehavior::Ignore,
+        ] {
+            let mut router = Router::new();
+            let result = router.add_tool_with_behavior(DupTool::new(1), behavior);
+            assert!(result.is_ok(), "Failed for {:?}", behavior);
+        }
+    }
+
+    #[test]
+    fn test_server_builder_on_duplicate() {
+        // Create server with strict duplicate checking
+        let server = Server::new("test", "1.0")
+            .on_duplicate(DuplicateBehavior::Replace)
+            .tool(DupTool::new(1))
+            .tool(DupTool::new(2)) // Should replace
+            .build();
+
+        let tools = server.tools();
+        assert_eq!(tools.len(), 1);
+        // The replaced tool should have id 2
+        assert_eq!(tools[0].description, Some("Tool #2".to_string()));
+    }
+
+    #[test]
+    fn test_server_builder_error_behavior_logs_but_continues() {
+        // Create server with error behavior
+        let server = Server::new("test", "1.0")
+            .on_duplicate(DuplicateBehavior::Error)
+            .tool(DupTool::new(1))
+            .tool(DupTool::new(2)) // Should fail silently in builder
+            .build();
+
+        let tools = server.tools();
+        // Only first tool should be registered
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].description, Some("Tool #1".to_string()));
+    }
+
+    #[test]
+    fn test_lenient_registration_keeps_first_duplicate_tool() {
+        let server = Server::new("test", "1.0")
+            .tool(DupTool::new(1))
+            .tool(DupTool::new(2))
+            .try_build()
+            .expect("duplicates are not fatal by default");
+
+        let tools = server.tools();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].description, Some("Tool #1".to_string()));
+    }
+
+    #[test]
+    fn test_strict_registration_rejects_duplicate_tool() {
+        let err = Server::new("test", "1.0")
+            .strict_registration(true)
+            .tool(DupTool::new(1))
+            .tool(DupTool::new(2))
+            .try_build()
+            .unwrap_err();
+
+        assert_eq!(err.code, McpErrorCode::InvalidRequest);
+        assert!(err.message.contains("tool 'dup_tool'"), "{}", err.message);
+    }
+
+    #[test]
+    fn test_strict_registration_applies_whatever_the_duplicate_behavior() {
+        for behavior in [DuplicateBehavior::Replace, DuplicateBehavior::Ignore] {
+            let result = Server::new("test", "1.0")
+                .strict_registration(true)
+                .on_duplicate(behavior)
+                .tool(DupTool::new(1))
+                .tool(DupTool::new(2))
+                .try_build();
+            assert!(result.is_err(), "Built with duplicates under {behavior:?}");
+        }
+    }
+
+    #[test]
+    fn test_strict_registration_rejects_duplicate_resources_and_prompts() {
+        let resource = |content: &str| StaticResource {
+            uri: "resource://dup".to_string(),
+            content: content.to_string(),
+        };
+        let err = Server::new("test", "1.0")
+            .strict_registration(true)
+            .resource(resource("first"))
+            .resource(resource("second"))
+            .prompt(GreetingPrompt)
+            .prompt(GreetingPrompt)
+            .try_build()
+            .unwrap_err();
+
+        assert!(
+            err.message.contains("resource 'resource://dup'"),
+            "{}",
+            err.message
+        );
+        assert!(err.message.contains("prompt '"), "{}", err.message);
+    }
+
+    #[test]
+    fn test_strict_registration_allows_distinct_components() {
+        let server = Server::new("test", "1.0")
+            .strict_registration(true)
+            .tool(DupTool::new(1))
+            .tool(GreetTool)
+            .try_build()
+            .expect("no duplicates");
+        assert_eq!(server.tools().len(), 2);
+    }
+}
+
+// ============================================================================
+// Cross-Component Resource Reading Tests (ctx.read_resource)
+// ============================================================================
+
+mod ctx_read_resource_tests {
+    use super::*;
+    use crate::RouterResourceReader;
+    use fastmcp_core::{
+        MAX_RESOURCE_READ_DEPTH, ResourceContentItem, ResourceReadResult, ResourceReader,
+    };
+
+    /// A simple resource that returns static config data.
+    struct ConfigResource {
+        config_json: String,
+    }
+
+    impl ConfigResource {
+        fn new(json: &str) -> Self {
+            Self {
+                config_json: json.to_string(),
+            }
+        }
+    }
+
+    impl ResourceHandler for ConfigResource {
+        fn definition(&self) -> Resource {
+            Resource {
+                uri: "config://app".to_string(),
+                name: "app_config".to_string(),
+                description: Some("Application configuration".to_string()),
+                mime_type: Some("application/json".to_string()),
+                icon: None,
+                version: None,
+                tags: vec![],
+            }
+        }
+
+        fn template(&self) -> Option<ResourceTemplate> {
+            None
+        }
+
+        fn read(&self, _ctx: &McpContext) -> McpResult<Vec<ResourceContent>> {
+            Ok(vec![ResourceContent {
+                uri: "config://app".to_string(),
+                mime_type: Some("application/json".to_string()),
+                text: Some(self.config_json.clone()),
+                blob: None,
+                etag: None,
+                last_modified: None,
+            }])
+        }
+    }
+
+    /// A resource that reads another resource.
+    struct NestedResource {
+        inner_uri: String,
+    }
+
+    impl NestedResource {
+        fn new(inner_uri: &str) -> Self {
+            Self {
+                inner_uri: inner_uri.to_string(),
+            }
+        }
+    }
+
+    impl ResourceHandler for NestedResource {
+        fn definition(&self) -> Resource {
+            Resource {
+                uri: "nested://wrapper".to_string(),
+                name: "nested_wrapper".to_string(),
+                description: Some("Wraps another resource".to_string()),
+                mime_type: Some("text/plain".to_string()),
+                icon: None,
+                version: None,
+                tags: vec![],
+            }
+        }
+
+        fn template(&self) -> Option<ResourceTemplate> {
+            None
+        }
+
+        fn read(&self, ctx: &McpContext) -> McpResult<Vec<ResourceContent>> {
+            // Read the inner resource using ctx
+            let inner_uri = self.inner_uri.clone();
+            let inner_result = fastmcp_core::block_on(ctx.read_resource(&inner_uri))?;
+
+            let text = inner_result.first_text().unwrap_or("(no content)");
+            Ok(vec![ResourceContent {
+                uri: "nested://wrapper".to_string(),
+                mime_type: Some("text/plain".to_string()),
+                text: Some(format!("Wrapped: {}", text)),
+                blob: None,
+                etag: None,
+                last_modified: None,
+            }])
+        }
+    }
+
+    #[test]
+    fn test_resource_content_item_constructors() {
+        let text_item = ResourceContentItem::text("file://test", "hello world");
+        assert_eq!(text_item.uri, "file://test");
+        assert_eq!(text_item.as_text(), Some("hello world"));
+        assert!(text_item.is_text());
+        assert!(!text_item.is_blob());
+
+        let json_item = ResourceContentItem::json("config://app", r#"{"key": "value"}"#);
+        assert_eq!(json_item.mime_type, Some("application/json".to_string()));
+
+        let blob_item = ResourceContentItem::blob("image://test", "image/png", "base64data");
+        assert_eq!(blob_item.as_blob(), Some("base64data"));
+        assert!(blob_item.is_blob());
+        assert!(!blob_item.is_text());
+    }
+
+    #[test]
+    fn test_resource_read_result_constructors() {
+        let result = ResourceReadResult::text("file://test", "content");
+        assert_eq!(result.first_text(), Some("content"));
+        assert_eq!(result.contents.len(), 1);
+
+        let multi = ResourceReadResult::new(vec![
+            ResourceContentItem::text("file://a", "A"),
+            ResourceContentItem::text("file://b", "B"),
+        ]);
+        assert_eq!(multi.contents.len(), 2);
+        assert_eq!(multi.first_text(), Some("A"));
+    }
+
+    #[test]
+    fn test_ctx_read_resource_without_reader_fails() {
+        let cx = Cx::for_testing();
+        let ctx = McpContext::new(cx, 1);
+
+        // Without a resource reader, should fail
+        assert!(!ctx.can_read_resources());
+
+        let result = fastmcp_core::block_on(ctx.read_resource("config://app"));
+        assert!(result.is_err());
+        let err = result.unwrap_err();
+        assert!(err.message.contains("no router"));
+    }
+
+    #[test]
+    fn test_router_resource_reader_reads_resource() {
+        let mut router = Router::new();
+        router.add_resource(ConfigResource::new(r#"{"db": "postgres"}"#));
+
+        let router_arc = Arc::new(router);
+        let reader = RouterResourceReader::new(router_arc, SessionState::new());
+
+        let cx = Cx::for_testing();
+        let result = fastmcp_core::block_on(reader.read_resource(&cx, "config://app", 0));
+
+        assert!(result.is_ok());
+        let read_result = result.unwrap();
+        assert_eq!(read_result.first_text(), Some(r#"{"db": "postgres"}"#));
+    }
+
+    #[test]
+    fn test_router_resource_reader_not_found() {
+        let router = Router::new(); // No resources
+        let router_arc = Arc::new(router);
+        let reader = RouterResourceReader::new(router_arc, SessionState::new());
+
+        let cx = Cx::for_testing();
+        let result = fastmcp_core::block_on(reader.read_resource(&cx, "config://missing", 0));
+
+        assert!(result.is_err());
+        let err = result.unwrap_err();
+        assert!(err.message.contains("not found"));
+    }
+
+    #[test]
+    fn test_router_resource_reader_depth_limit() {
+        let router = Router::new();
+        let router_arc = Arc::new(router);
+        let reader = RouterResourceReader::new(router_arc, SessionState::new());
+
+        let cx = Cx::for_testing();
+        // Call with depth at limit
+        let result = fastmcp_core::block_on(reader.read_resource(
+            &cx,
+            "any://uri",
+            MAX_RESOURCE_READ_DEPTH + 1,
+        ));
+
+        assert!(result.is_err());
+        let err = result.unwrap_err();
+        assert!(err.message.contains("depth"));
+    }
+
+    #[test]
+    fn test_ctx_with_resource_reader() {
+        let mut router = Router::new();
+        router.add_resource(ConfigResource::new(r#"{"name": "test"}"#));
+
+        let router_arc = Arc::new(router);
+        let reader: Arc<dyn ResourceReader> =
+            Arc::new(RouterResourceReader::new(router_arc, SessionState::new()));
+
+        let cx = Cx::for_testing();
+        let ctx = McpContext::new(cx, 1).with_resource_reader(reader);
+
+        assert!(ctx.can_read_resources());
+        assert_eq!(ctx.resource_read_depth(), 0);
+
+        // Read the resource
+        let result = fastmcp_core::block_on(ctx.read_resource("config://app"));
+        assert!(result.is_ok());
+        let read_result = result.unwrap();
+        assert!(read_result.first_text().unwrap().contains("test"));
+    }
+
+    #[test]
+    fn test_ctx_read_resource_text() {
+        let mut router = Router::new();
+        router.add_resource(ConfigResource::new(r#"{"value": 42}"#));
+
+        let router_arc = Arc::new(router);
+        let reader: Arc<dyn ResourceReader> =
+            Arc::new(RouterResourceReader::new(router_arc, SessionState::new()));
+
+        let cx = Cx::for_testing();
+        let ctx = McpContext::new(cx, 1).with_resource_reader(reader);
+
+        let result = fastmcp_core::block_on(ctx.read_resource_text("config://app"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), r#"{"value": 42}"#);
+    }
+
+    #[test]
+    fn test_ctx_read_resource_json() {
+        let mut router = Router::new();
+        router.add_resource(ConfigResource::new(
+            r#"{"database": "postgres", "port": 5432}"#,
+        ));
+
+        let router_arc = Arc::new(router);
+        let reader: Arc<dyn ResourceReader> =
+            Arc::new(RouterResourceReader::new(router_arc, SessionState::new()));
+
+        let cx = Cx::for_testing();
+        let ctx = McpContext::new(cx, 1).with_resource_reader(reader);
+
+        #[derive(Debug, serde::Deserialize)]
+        struct DbConfig {
+            database: String,
+            port: u16,
+        }
+
+        let result: McpResult<DbConfig> =
+            fastmcp_core::block_on(ctx.read_resource_json("config://app"));
+        assert!(result.is_ok());
+        let config = result.unwrap();
+        assert_eq!(config.database, "postgres");
+        assert_eq!(config.port, 5432);
+    }
+
+    #[test]
+    fn test_ctx_read_resource_json_parse_error() {
+        let mut router = Router::new();
+        router.add_resource(ConfigResource::new("not valid json"));
+
+        let router_arc = Arc::new(router);
+        let reader: Arc<dyn ResourceReader> =
+            Arc::new(RouterResourceReader::new(router_arc, SessionState::new()));
+
+        let cx = Cx::for_testing();
+        let ctx = McpContext::new(cx, 1).with_resource_reader(reader);
+
+        #[derive(Debug, serde::Deserialize)]
+        struct Config {
+            value: i32,
+        }
+
+        let result: McpResult<Config> =
+            fastmcp_core::block_on(ctx.read_resource_json("config://app"));
+        assert!(result.is_err());
+        let err = result.unwrap_err();
+        assert!(err.message.contains("JSON"));
+    }
+
+    #[test]
+    fn test_resource_read_depth_increments() {
+        let cx = Cx::for_testing();
+        let ctx = McpContext::new(cx, 1).with_resource_read_depth(5);
+
+        assert_eq!(ctx.resource_read_depth(), 5);
+    }
+
+    #[test]
+    fn test_max_resource_read_depth_constant() {
+        // Verify the constant is reasonable
+        assert_eq!(MAX_RESOURCE_READ_DEPTH, 10);
+    }
+
+    /// A resource that reads session state and then reads another resource.
+    struct SessionStateResource;
+
+    impl ResourceHandler for SessionStateResource {
+        fn definition(&self) -> Resource {
+            Resource {
+                uri: "session://state".to_string(),
+                name: "session_state".to_string(),
+                description: Some("Returns session state value".to_string()),
+                mime_type: Some("text/plain".to_string()),
+                icon: None,
+                version: None,
+                tags: vec![],
+            }
+        }
+
+        fn template(&self) -> Option<ResourceTemplate> {
+            None
+        }
+
+        fn read(&self, ctx: &McpContext) -> McpResult<Vec<ResourceContent>> {
+            let value: Option<String> = ctx.get_state("test_key");
+            Ok(vec![ResourceContent {
+                uri: "session://state".to_string(),
+                mime_type: Some("text/plain".to_string()),
+                text: Some(value.unwrap_or_else(|| "no_value".to_string())),
+                blob: None,
+                etag: None,
+                last_modified: None,
+            }])
+        }
+    }
+
+    /// A resource that sets session state and reads another resource to verify propagation.
+    struct NestedSessionResource;
+
+    impl ResourceHandler for NestedSessionResource {
+        fn definition(&self) -> Resource {
+            Resource {
+                uri: "nested://session".to_string(),
+                name: "nested_session".to_string(),
+                description: Some("Sets state then reads another resource".to_string()),
+                mime_type: Some("text/plain".to_string()),
+                icon: None,
+                version: None,
+                tags: vec![],
+            }
+        }
+
+        fn template(&self) -> Option<ResourceTemplate> {
+            None
+        }
+
+        fn read(&self, ctx: &McpContext) -> McpResult<Vec<ResourceContent>> {
+            // Set a value in session state
+            ctx.set_state("test_key", "propagated_value");
+
+            // Read another resource - it should see our session state
+            let inner_result = fastmcp_core::block_on(ctx.read_resource("session://state"))?;
+            let text = inner_result.first_text().unwrap_or("(no content)");
+
+            Ok(vec![ResourceContent {
+                uri: "nested://session".to_string(),
+                mime_type: Some("text/plain".to_string()),
+                text: Some(format!("Inner saw: {}", text)),
+                blob: None,
+                etag: None,
+                last_modified: None,
+            }])
+        }
+    }
+
+    #[test]
+    fn test_session_state_propagates_through_nested_reads() {
+        let mut router = Router::new();
+        router.add_resource(SessionStateResource);
+        router.add_resource(NestedSessionResource);
+
+        let router_arc = Arc::new(router);
+        let session_state = SessionState::new();
+        let reader: Arc<dyn ResourceReader> =
+            Arc::new(RouterResourceReader::new(router_arc, session_state.clone()));
+
+        let cx = Cx::for_testing();
+        let ctx = McpContext::with_state(cx, 1, session_state).with_resource_reader(reader);
+
+        // Read the nested resource - it sets state then reads another resource
+        let result = fastmcp_core::block_on(ctx.read_resource("nested://session"));
+        assert!(result.is_ok());
+        let read_result = result.unwrap();
+
+        // The inner resource should have seen the propagated value
+        let text = read_result.first_text().unwrap();
+        assert!(
+            text.contains("propagated_value"),
+            "Expected session state to propagate, got: {}",
+            text
+        );
+    }
+}
+
+// ============================================================================
+// Cross-Component Tool Calling Tests (ctx.call_tool)
+// ============================================================================
+
+mod ctx_call_tool_tests {
+    use super::*;
+    use crate::RouterToolCaller;
+    use fastmcp_core::{MAX_TOOL_CALL_DEPTH, ToolCallResult, ToolCaller, ToolContentItem};
+
+    /// A simple tool that adds two numbers.
+    struct AddTool;
+
+    impl ToolHandler for AddTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: "add".to_string(),
+                description: Some("Adds two numbers".to_string()),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "a": {"type": "integer"},
+                        "b": {"type": "integer"}
+                    },
+                    "required": ["a", "b"]
+                }),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+                annotations: None,
+            }
+        }
+
+        fn call(&self, _ctx: &McpContext, args: serde_json::Value) -> McpResult<Vec<Content>> {
+            let a = args["a"].as_i64().unwrap_or(0);
+            let b = args["b"].as_i64().unwrap_or(0);
+            let sum = a + b;
+            Ok(vec![Content::Text {
+                text: sum.to_string(),
+            }])
+        }
+    }
+
+    /// A tool that returns JSON.
+    struct JsonTool;
+
+    impl ToolHandler for JsonTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: "compute".to_string(),
+                description: Some("Returns a JSON result".to_string()),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {}
+                }),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+                annotations: None,
+            }
+        }
+
+        fn call(&self, _ctx: &McpContext, _args: serde_json::Value) -> McpResult<Vec<Content>> {
+            Ok(vec![Content::Text {
+                text: r#"{"value": 42}"#.to_string(),
+            }])
+        }
+    }
+
+    /// A tool that always fails.
+    struct FailingTool;
+
+    impl ToolHandler for FailingTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: "failing".to_string(),
+                description: Some("Always fails".to_string()),
+                input_schema: serde_json::json!({"type": "object"}),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+                annotations: None,
+            }
+        }
+
+        fn call(&self, _ctx: &McpContext, _args: serde_json::Value) -> McpResult<Vec<Content>> {
+            Err(McpError::new(
+                McpErrorCode::InternalError,
+                "Something went wrong",
+            ))
+        }
+    }
+
+    #[test]
+    fn test_tool_content_item_constructors() {
+        let text_item = ToolContentItem::text("hello world");
+        assert_eq!(text_item.as_text(), Some("hello world"));
+        assert!(text_item.is_text());
+    }
+
+    #[test]
+    fn test_tool_call_result_constructors() {
+        let success = ToolCallResult::text("result");
+        assert!(!success.is_error);
+        assert_eq!(success.first_text(), Some("result"));
+
+        let error = ToolCallResult::error("failed");
+        assert!(error.is_error);
+        assert_eq!(error.first_text(), Some("failed"));
+
+        let multi =
+            ToolCallResult::success(vec![ToolContentItem::text("a"), ToolContentItem::text("b")]);
+        assert_eq!(multi.content.len(), 2);
+        assert_eq!(multi.first_text(), Some("a"));
+    }
+
+    #[test]
+    fn test_ctx_call_tool_without_caller_fails() {
+        let cx = Cx::for_testing();
+        let ctx = McpContext::new(cx, 1);
+
+        // Without a tool caller, should fail
+        assert!(!ctx.can_call_tools());
+
+        let result =
+            fastmcp_core::block_on(ctx.call_tool("add", serde_json::json!({"a": 1, "b": 2})));
+        assert!(result.is_err());
+        let err = result.unwrap_err();
+        assert!(err.message.contains("no router"));
+    }
+
+    #[test]
+    fn test_router_tool_caller_calls_tool() {
+        let mut router = Router::new();
+        router.add_tool(AddTool);
+
+        let router_arc = Arc::new(router);
+        let caller = RouterToolCaller::new(router_arc, SessionState::new());
+
+        let cx = Cx::for_testing();
+        let result = fastmcp_core::block_on(caller.call_tool(
+            &cx,
+            "add",
+            serde_json::json!({"a": 5, "b": 3}),
+            0,
+        ));
+
+        assert!(result.is_ok());
+        let call_result = result.unwrap();
+        assert!(!call_result.is_error);
+        assert_eq!(call_result.first_text(), Some("8"));
+    }
+
+    #[test]
+    fn test_router_tool_caller_not_found() {
+        let router = Router::new(); // No tools
+        let router_arc = Arc::new(router);
+        let caller = RouterToolCaller::new(router_arc, SessionState::new());
+
+        let cx = Cx::for_testing();
+        let result =
+            fastmcp_core::block_on(caller.call_tool(&cx, "nonexistent", serde_json::json!({}), 0));
+
+        assert!(result.is_err());
+        let err = result.unwrap_err();
+        assert!(err.message.contains("not found"));
+    }
+
+    #[test]
+    fn test_router_tool_caller_depth_limit() {
+        let router = Router::new();
+        let router_arc = Arc::new(router);
+        let caller = RouterToolCaller::new(router_arc, SessionState::new());
+
+        let cx = Cx::for_testing();
+        // Call with depth at limit
+        let result = fastmcp_core::block_on(caller.call_tool(
+            &cx,
+            "any_tool",
+            serde_json::json!({}),
+            MAX_TOOL_CALL_DEPTH + 1,
+        ));
+
+        assert!(result.is_err());
+        let err = result.unwrap_err();
+        assert!(err.message.contains("depth"));
+    }
+
+    #[test]
+    fn test_ctx_with_tool_caller() {
+        let mut router = Router::new();
+        router.add_tool(AddTool);
+
+        let router_arc = Arc::new(router);
+        let caller: Arc<dyn ToolCaller> =
+            Arc::new(RouterToolCaller::new(router_arc, SessionState::new()));
+
+        let cx = Cx::for_testing();
+        let ctx = McpContext::new(cx, 1).with_tool_caller(caller);
+
+        assert!(ctx.can_call_tools());
+        assert_eq!(ctx.tool_call_depth(), 0);
+
+        // Call the tool
+        let result =
+            fastmcp_core::block_on(ctx.call_tool("add", serde_json::json!({"a": 10, "b": 5})));
+        assert!(result.is_ok());
+        let call_result = result.unwrap();
+        assert_eq!(call_result.first_text(), Some("15"));
+    }
+
+    #[test]
+    fn test_ctx_call_tool_text() {
+        let mut router = Router::new();
+        router.add_tool(AddTool);
+
+        let router_arc = Arc::new(router);
+        let caller: Arc<dyn ToolCaller> =
+            Arc::new(RouterToolCaller::new(router_arc, SessionState::new()));
+
+        let cx = Cx::for_testing();
+        let ctx = McpContext::new(cx, 1).with_tool_caller(caller);
+
+        let result =
+            fastmcp_core::block_on(ctx.call_tool_text("add", serde_json::json!({"a": 7, "b": 3})));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "10");
+    }
+
+    #[test]
+    fn test_ctx_call_tool_json() {
+        let mut router = Router::new();
+        router.add_tool(JsonTool);
+
+        let router_arc = Arc::new(router);
+        let caller: Arc<dyn ToolCaller> =
+            Arc::new(RouterToolCaller::new(router_arc, SessionState::new()));
+
+        let cx = Cx::for_testing();
+        let ctx = McpContext::new(cx, 1).with_tool_caller(caller);
+
+        #[derive(Debug, serde::Deserialize)]
+        struct Result {
+            value: i32,
+        }
+
+        let result: McpResult<Result> =
+            fastmcp_core::block_on(ctx.call_tool_json("compute", serde_json::json!({})));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().value, 42);
+    }
+
+    #[test]
+    fn test_ctx_call_tool_returns_error_result() {
+        let mut router = Router::new();
+        router.add_tool(FailingTool);
+
+        let router_arc = Arc::new(router);
+        let caller: Arc<dyn ToolCaller> =
+            Arc::new(RouterToolCaller::new(router_arc, SessionState::new()));
+
+        let cx = Cx::for_testing();
+        let ctx = McpContext::new(cx, 1).with_tool_caller(caller);
+
+        // call_tool returns the error as is_error=true
+        let result = fastmcp_core::block_on(ctx.call_tool("failing", serde_json::json!({})));
+        assert!(result.is_ok());
+        let call_result = result.unwrap();
+        assert!(call_result.is_error);
+    }
+
+    #[test]
+    fn test_ctx_call_tool_text_propagates_error() {
+        let mut router = Router::new();
+        router.add_tool(FailingTool);
+
+        let router_arc = Arc::new(router);
+        let caller: Arc<dyn ToolCaller> =
+            Arc::new(RouterToolCaller::new(router_arc, SessionState::new()));
+
+        let cx = Cx::for_testing();
+        let ctx = McpContext::new(cx, 1).with_tool_caller(caller);
+
+        // call_tool_text converts is_error=true to Err
+        let result = fastmcp_core::block_on(ctx.call_tool_text("failing", serde_json::json!({})));
+        assert!(result.is_err());
+        let err = result.unwrap_err();
+        assert!(err.message.contains("failed"));
+    }
+
+    #[test]
+    fn test_tool_call_depth_increments() {
+        let cx = Cx::for_testing();
+        let ctx = McpContext::new(cx, 1).with_tool_call_depth(5);
+
+        assert_eq!(ctx.tool_call_depth(), 5);
+    }
+
+    #[test]
+    fn test_max_tool_call_depth_constant() {
+        // Verify the constant is reasonable
+        assert_eq!(MAX_TOOL_CALL_DEPTH, 10);
+    }
+
+    #[test]
+    fn test_tool_validation_error() {
+        let mut router = Router::new();
+        router.add_tool(AddTool);
+
+        let router_arc = Arc::new(router);
+        let caller = RouterToolCaller::new(router_arc, SessionState::new());
+
+        let cx = Cx::for_testing();
+        // Missing required parameters
+        let result = fastmcp_core::block_on(caller.call_tool(
+            &cx,
+            "add",
+            serde_json::json!({}), // Missing a and b
+            0,
+        ));
+
+        assert!(result.is_err());
+        let err = result.unwrap_err();
+        assert!(err.message.contains("validation"));
+    }
+
+    #[test]
+    fn test_tools_call_coerces_numeric_strings_when_enabled() {
+        let mut router = Router::new();
+        router.add_tool(AddTool);
+        router.set_coerce_arguments(true);
+
+        let cx = Cx::for_testing();
+        let params = CallToolParams {
+            name: "add".to_string(),
+            arguments: Some(serde_json::json!({"a": "5", "b": 3})),
+            meta: None,
+        };
+        let result = router
+            .handle_tools_call(
+                &cx,
+                1,
+                None,
+                params,
+                &Budget::INFINITE,
+                SessionState::new(),
+                None,
+                None,
+                false,
+            )
+            .expect("coerced call succeeds");
+        assert!(!result.is_error);
+        assert!(matches!(&result.content[0], Content::Text { text } if text == "8"));
+
+        // Same coercion applies to cross-component calls
+        let caller = RouterToolCaller::new(Arc::new(router), SessionState::new());
+        let nested = fastmcp_core::block_on(caller.call_tool(
+            &cx,
+            "add",
+            serde_json::json!({"a": "2", "b": "2"}),
+            0,
+        ))
+        .expect("coerced nested call succeeds");
+        assert_eq!(nested.first_text(), Some("4"));
+    }
+
+    #[test]
+    fn test_tools_call_coercion_rejects_non_numeric_string() {
+        let mut router = Router::new();
+        router.add_tool(AddTool);
+        router.set_coerce_arguments(true);
+
+        let cx = Cx::for_testing();
+        let params = CallToolParams {
+            name: "add".to_string(),
+            arguments: Some(serde_json::json!({"a": "five", "b": 3})),
+            meta: None,
+        };
+        let err = router
+            .handle_tools_call(
+                &cx,
+                1,
+                None,
+                params,
+                &Budget::INFINITE,
+                SessionState::new(),
+                None,
+                None,
+                false,
+            )
+            .unwrap_err();
+        assert!(err.message.contains("Input validation failed"));
+    }
+
+    #[test]
+    fn test_tools_call_without_coercion_rejects_numeric_string() {
+        let mut router = Router::new();
+        router.add_tool(AddTool);
+
+        let cx = Cx::for_testing();
+        let params = CallToolParams {
+            name: "add".to_string(),
+            arguments: Some(serde_json::json!({"a": "5", "b": 3})),
+            meta: None,
+        };
+        let result = router.handle_tools_call(
+            &cx,
+            1,
+            None,
+            params,
+            &Budget::INFINITE,
+            SessionState::new(),
+            None,
+            None,
+            false,
+        );
+        assert!(result.is_err());
+    }
+
+    /// A tool that reads session state.
+    struct GetStateTool;
+
+    impl ToolHandler for GetStateTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: "get_state".to_string(),
+                description: Some("Returns session state value".to_string()),
+                input_schema: serde_json::json!({"type": "object"}),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+                annotations: None,
+            }
+        }
+
+        fn call(&self, ctx: &McpContext, _args: serde_json::Value) -> McpResult<Vec<Content>> {
+            let value: Option<String> = ctx.get_state("tool_test_key");
+            Ok(vec![Content::Text {
+                text: value.unwrap_or_else(|| "no_value".to_string()),
+            }])
+        }
+    }
+
+    /// A tool that sets session state and calls another tool to verify propagation.
+    struct NestedStateTool;
+
+    impl ToolHandler for NestedStateTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: "nested_state".to_string(),
+                description: Some("Sets state then calls another tool".to_string()),
+                input_schema: serde_json::json!({"type": "object"}),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+                annotations: None,
+            }
+        }
+
+        fn call(&self, ctx: &McpContext, _args: serde_json::Value) -> McpResult<Vec<Content>> {
+            // Set a value in session state
+            ctx.set_state("tool_test_key", "tool_propagated_value");
+
+            // Call another tool - it should see our session state
+            let inner_result =
+                fastmcp_core::block_on(ctx.call_tool("get_state", serde_json::json!({})))?;
+            let text = inner_result.first_text().unwrap_or("(no content)");
+
+            Ok(vec![Content::Text {
+                text: format!("Inner tool saw: {}", text),
+            }])
+        }
+    }
+
+    #[test]
+    fn test_session_state_propagates_through_nested_tool_calls() {
+        use crate::RouterResourceReader;
+
+        let mut router = Router::new();
+        router.add_tool(GetStateTool);
+        router.add_tool(NestedStateTool);
+
+        let router_arc = Arc::new(router);
+        let session_state = SessionState::new();
+        let caller: Arc<dyn ToolCaller> = Arc::new(RouterToolCaller::new(
+            router_arc.clone(),
+            session_state.clone(),
+        ));
+        let reader: Arc<dyn fastmcp_core::ResourceReader> =
+            Arc::new(RouterResourceReader::new(router_arc, session_state.clone()));
+
+        let cx = Cx::for_testing();
+        let ctx = McpContext::with_state(cx, 1, session_state)
+            .with_tool_caller(caller)
+            .with_resource_reader(reader);
+
+        // Call the nested tool - it sets state then calls another tool
+        let result = fastmcp_core::block_on(ctx.call_tool("nested_state", serde_json::json!({})));
+        assert!(result.is_ok());
+        let call_result = result.unwrap();
+
+        // The inner tool should have seen the propagated value
+        let text = call_result.first_text().unwrap();
+        assert!(
+            text.contains("tool_propagated_value"),
+            "Expected session state to propagate through tool calls, got: {}",
+            text
+        );
+    }
+}
+
+// ============================================================================
+// Handler Direct Tests
+// ============================================================================
+
+mod handler_direct_tests {
+    use super::*;
+    use crate::handler::{
+        BidirectionalSenders, MountedPromptHandler, MountedResourceHandler, MountedToolHandler,
+        ProgressNotificationSender, UriParams,
+    };
+    use fastmcp_protocol::{Icon, ToolAnnotations};
+
+    /// Helper: create a test McpContext.
+    fn test_ctx() -> McpContext {
+        let cx = Cx::for_testing();
+        McpContext::new(cx, 1)
+    }
+
+    // ── ToolHandler direct call ──────────────────────────────────────
+
+    #[test]
+    fn tool_handler_call_returns_content() {
+        let tool = GreetTool;
+        let ctx = test_ctx();
+        let result = tool.call(&ctx, serde_json::json!({"name": "Alice"}));
+        assert!(result.is_ok());
+        let contents = result.unwrap();
+        assert_eq!(contents.len(), 1);
+        match &contents[0] {
+            Content::Text { text } => assert_eq!(text, "Hello, Alice!"),
+            _ => panic!("Expected text content"),
+        }
+    }
+
+    #[test]
+    fn tool_handler_call_default_arg() {
+        let tool = GreetTool;
+        let ctx = test_ctx();
+        let result = tool.call(&ctx, serde_json::json!({}));
+        assert!(result.is_ok());
+        match &result.unwrap()[0] {
+            Content::Text { text } => assert_eq!(text, "Hello, World!"),
+            _ => panic!("Expected text content"),
+        }
+    }
+
+    #[test]
+    fn tool_handler_error_returns_mcp_error() {
+        let tool = ErrorTool;
+        let ctx = test_ctx();
+        let result = tool.call(&ctx, serde_json::json!({}));
+        assert!(result.is_err());
+        let err = result.unwrap_err();
+        assert_eq!(err.code, McpErrorCode::InternalError);
+    }
+
+    #[test]
+    fn tool_handler_definition_has_expected_fields() {
+        let tool = GreetTool;
+        let def = tool.definition();
+        assert_eq!(def.name, "greet");
+        assert!(def.description.is_some());
+        assert_eq!(def.input_schema["type"], "object");
+        assert!(def.input_schema["properties"]["name"].is_object());
+    }
+
+    #[test]
+    fn tool_handler_default_icon_is_none() {
+        let tool = GreetTool;
+        assert!(tool.icon().is_none());
+    }
+
+    #[test]
+    fn tool_handler_default_version_is_none() {
+        let tool = GreetTool;
+        assert!(tool.version().is_none());
+    }
+
+    #[test]
+    fn tool_handler_default_tags_is_empty() {
+        let tool = GreetTool;
+        assert!(tool.tags().is_empty());
+    }
+
+    #[test]
+    fn tool_handler_default_annotations_is_none() {
+        let tool = GreetTool;
+        assert!(tool.annotations().is_none());
+    }
+
+    #[test]
+    fn tool_handler_default_output_schema_is_none() {
+        let tool = GreetTool;
+        assert!(tool.output_schema().is_none());
+    }
+
+    #[test]
+    fn tool_handler_default_timeout_is_none() {
+        let tool = GreetTool;
+        assert!(tool.timeout().is_none());
+    }
+
+    // ── Custom tool with overrides ───────────────────────────────────
+
+    struct RichTool {
+        icon: Icon,
+        version: String,
+        tags: Vec<String>,
+        annotations: ToolAnnotations,
+        output_schema: serde_json::Value,
+        timeout: Duration,
+    }
+
+    impl Default for RichTool {
+        fn default() -> Self {
+            Self {
+                icon: Icon {
+                    src: Some("https://example.com/icon.png".to_string()),
+                    mime_type: None,
+                    sizes: None,
+                },
+                version: "2.0.0".to_string(),
+                tags: vec!["api".to_string(), "read".to_string()],
+                annotations: ToolAnnotations {
+                    title: Some("Rich Tool".to_string()),
+                    read_only_hint: Some(true),
+                    destructive_hint: Some(false),
+                    idempotent_hint: Some(true),
+                    open_world_hint: Some(false),
+                },
+                output_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "result": {"type": "string"}
+                    }
+                }),
+                timeout: Duration::from_secs(60),
+            }
+        }
+    }
+
+    impl ToolHandler for RichTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: "rich".to_string(),
+                description: Some("A fully configured tool".to_string()),
+                input_schema: serde_json::json!({"type": "object"}),
+                output_schema: Some(self.output_schema.clone()),
+                icon: Some(self.icon.clone()),
+                version: Some(self.version.clone()),
+                tags: self.tags.clone(),
+                annotations: Some(self.annotations.clone()),
+            }
+        }
+
+        fn icon(&self) -> Option<&Icon> {
+            Some(&self.icon)
+        }
+
+        fn version(&self) -> Option<&str> {
+            Some(&self.version)
+        }
+
+        fn tags(&self) -> &[String] {
+            &self.tags
+        }
+
+        fn annotations(&self) -> Option<&ToolAnnotations> {
+            Some(&self.annotations)
+        }
+
+        fn output_schema(&self) -> Option<serde_json::Value> {
+            Some(self.output_schema.clone())
+        }
+
+        fn timeout(&self) -> Option<Duration> {
+            Some(self.timeout)
+        }
+
+        fn call(
+            &self,
+            _ctx: &McpContext,
+            _arguments: serde_json::Value,
+        ) -> McpResult<Vec<Content>> {
+            Ok(vec![Content::Text {
+                text: "rich result".to_string(),
+            }])
+        }
+    }
+
+    #[test]
+    fn tool_handler_custom_icon() {
+        let tool = RichTool::default();
+        assert!(tool.icon().is_some());
+    }
+
+    #[test]
+    fn tool_handler_custom_version() {
+        let tool = RichTool::default();
+        assert_eq!(tool.version(), Some("2.0.0"));
+    }
+
+    #[test]
+    fn tool_handler_custom_tags() {
+        let tool = RichTool::default();
+        assert_eq!(tool.tags().len(), 2);
+        assert_eq!(tool.tags()[0], "api");
+    }
+
+    #[test]
+    fn tool_handler_custom_annotations() {
+        let tool = RichTool::default();
+        let ann = tool.annotations().unwrap();
+        assert_eq!(ann.read_only_hint, Some(true));
+        assert_eq!(ann.destructive_hint, Some(false));
+        assert_eq!(ann.idempotent_hint, Some(true));
+    }
+
+    #[test]
+    fn tool_handler_custom_output_schema() {
+        let tool = RichTool::default();
+        let schema = tool.output_schema().unwrap();
+        assert_eq!(schema["type"], "object");
+    }
+
+    #[test]
+    fn tool_handler_custom_timeout() {
+        let tool = RichTool::default();
+        assert_eq!(tool.timeout(), Some(Duration::from_secs(60)));
+    }
+
+    // ── ResourceHandler direct read ──────────────────────────────────
+
+    #[test]
+    fn resource_handler_read_returns_content() {
+        let resource = StaticResource {
+            uri: "test://hello".to_string(),
+            content: "world".to_string(),
+        };
+        let ctx = test_ctx();
+        let result = resource.read(&ctx);
+        assert!(result.is_ok());
+        let contents = result.unwrap();
+        assert_eq!(contents.len(), 1);
+        assert_eq!(contents[0].uri, "test://hello");
+        assert_eq!(contents[0].text, Some("world".to_string()));
+    }
+
+    #[test]
+    fn resource_handler_definition_fields() {
+        let resource = StaticResource {
+            uri: "test://data".to_string(),
+            content: "content".to_string(),
+        };
+        let def = resource.definition();
+        assert_eq!(def.uri, "test://data");
+        assert_eq!(def.name, "Static Resource");
+        assert_eq!(def.mime_type, Some("text/plain".to_string()));
+    }
+
+    #[test]
+    fn resource_handler_default_template_is_none() {
+        let resource = StaticResource {
+            uri: "test://data".to_string(),
+            content: "content".to_string(),
+        };
+        assert!(resource.template().is_none());
+    }
+
+    #[test]
+    fn resource_handler_default_icon_is_none() {
+        let resource = StaticResource {
+            uri: "test://data".to_string(),
+            content: "".to_string(),
+        };
+        assert!(resource.icon().is_none());
+    }
+
+    #[test]
+    fn resource_handler_default_version_is_none() {
+        let resource = StaticResource {
+            uri: "test://data".to_string(),
+            content: "".to_string(),
+        };
+        assert!(resource.version().is_none());
+    }
+
+    #[test]
+    fn resource_handler_default_tags_is_empty() {
+        let resource = StaticResource {
+            uri: "test://data".to_string(),
+            content: "".to_string(),
+        };
+        assert!(resource.tags().is_empty());
+    }
+
+    #[test]
+    fn resource_handler_default_timeout_is_none() {
+        let resource = StaticResource {
+            uri: "test://data".to_string(),
+            content: "".to_string(),
+        };
+        assert!(resource.timeout().is_none());
+    }
+
+    #[test]
+    fn resource_handler_read_with_uri_delegates_to_read() {
+        let resource = StaticResource {
+            uri: "test://data".to_string(),
+            content: "delegated".to_string(),
+        };
+        let ctx = test_ctx();
+        let params = UriParams::new();
+        let result = resource.read_with_uri(&ctx, "test://data", &params);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap()[0].text, Some("delegated".to_string()));
+    }
+
+    #[test]
+    fn resource_handler_template_resource_read_with_uri() {
+        let resource = TemplateResource;
+        let ctx = test_ctx();
+        let mut params = UriParams::new();
+        params.insert("id".to_string(), "42".to_string());
+        let result = resource.read_with_uri(&ctx, "resource://42", &params);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap()[0].text, Some("Template 42".to_string()));
+    }
+
+    #[test]
+    fn resource_handler_template_resource_has_template() {
+        let resource = TemplateResource;
+        let tmpl = resource.template();
+        assert!(tmpl.is_some());
+        assert_eq!(tmpl.unwrap().uri_template, "resource://{id}");
+    }
+
+    #[test]
+    fn resource_handler_template_resource_read_without_params_errors() {
+        let resource = TemplateResource;
+        let ctx = test_ctx();
+        let result = resource.read(&ctx);
+        assert!(result.is_err());
+    }
+
+    // ── PromptHandler direct get ─────────────────────────────────────
+
+    #[test]
+    fn prompt_handler_get_returns_messages() {
+        let prompt = GreetingPrompt;
+        let ctx = test_ctx();
+        let mut args = HashMap::new();
+        args.insert("name".to_string(), "Bob".to_string());
+        let result = prompt.get(&ctx, args);
+        assert!(result.is_ok());
+        let messages = result.unwrap();
+        assert_eq!(messages.len(), 1);
+        match &messages[0].content {
+            Content::Text { text } => assert!(text.contains("Bob")),
+            _ => panic!("Expected text content"),
+        }
+    }
+
+    #[test]
+    fn prompt_handler_definition_fields() {
+        let prompt = GreetingPrompt;
+        let def = prompt.definition();
+        assert_eq!(def.name, "greeting");
+        assert!(def.description.is_some());
+        assert_eq!(def.arguments.len(), 1);
+        assert_eq!(def.arguments[0].name, "name");
+        assert!(def.arguments[0].required);
+    }
+
+    #[test]
+    fn prompt_handler_default_icon_is_none() {
+        let prompt = GreetingPrompt;
+        assert!(prompt.icon().is_none());
+    }
+
+    #[test]
+    fn prompt_handler_default_version_is_none() {
+        let prompt = GreetingPrompt;
+        assert!(prompt.version().is_none());
+    }
+
+    #[test]
+    fn prompt_handler_default_tags_is_empty() {
+        let prompt = GreetingPrompt;
+        assert!(prompt.tags().is_empty());
+    }
+
+    #[test]
+    fn prompt_handler_default_timeout_is_none() {
+        let prompt = GreetingPrompt;
+        assert!(prompt.timeout().is_none());
+    }
+
+    #[test]
+    fn prompt_handler_get_with_missing_arg_uses_default() {
+        let prompt = GreetingPrompt;
+        let ctx = test_ctx();
+        let args = HashMap::new(); // no "name" argument
+        let result = prompt.get(&ctx, args);
+        assert!(result.is_ok());
+        match &result.unwrap()[0].content {
+            Content::Text { text } => assert!(text.contains("User")),
+            _ => panic!("Expected text content"),
+        }
+    }
+
+    // ── MountedToolHandler ───────────────────────────────────────────
+
+    #[test]
+    fn mounted_tool_handler_overrides_name() {
+        let inner: Box<dyn ToolHandler> = Box::new(GreetTool);
+        let mounted = MountedToolHandler::new(inner, "ns/greet".to_string());
+        let def = mounted.definition();
+        assert_eq!(def.name, "ns/greet");
+        // Other fields preserved
+        assert!(def.description.is_some());
+    }
+
+    #[test]
+    fn mounted_tool_handler_delegates_call() {
+        let inner: Box<dyn ToolHandler> = Box::new(GreetTool);
+        let mounted = MountedToolHandler::new(inner, "ns/greet".to_string());
+        let ctx = test_ctx();
+        let result = mounted.call(&ctx, serde_json::json!({"name": "Mounted"}));
+        assert!(result.is_ok());
+        match &result.unwrap()[0] {
+            Content::Text { text } => assert_eq!(text, "Hello, Mounted!"),
+            _ => panic!("Expected text content"),
+        }
+    }
+
+    #[test]
+    fn mounted_tool_handler_delegates_timeout() {
+        let inner: Box<dyn ToolHandler> = Box::new(RichTool::default());
+        let mounted = MountedToolHandler::new(inner, "ns/rich".to_string());
+        assert_eq!(mounted.timeout(), Some(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn mounted_tool_handler_delegates_annotations() {
+        let inner: Box<dyn ToolHandler> = Box::new(RichTool::default());
+        let mounted = MountedToolHandler::new(inner, "ns/rich".to_string());
+        let ann = mounted.annotations().unwrap();
+        assert_eq!(ann.read_only_hint, Some(true));
+    }
+
+    #[test]
+    fn mounted_tool_handler_delegates_output_schema() {
+        let inner: Box<dyn ToolHandler> = Box::new(RichTool::default());
+        let mounted = MountedToolHandler::new(inner, "ns/rich".to_string());
+        assert!(mounted.output_schema().is_some());
+    }
+
+    // ── MountedResourceHandler ───────────────────────────────────────
+
+    #[test]
+    fn mounted_resource_handler_overrides_uri() {
+        let inner: Box<dyn ResourceHandler> = Box::new(StaticResource {
+            uri: "test://orig".to_string(),
+            content: "data".to_string(),
+        });
+        let mounted = MountedResourceHandler::new(inner, "ns/test://orig".to_string());
+        let def = mounted.definition();
+        assert_eq!(def.uri, "ns/test://orig");
+        // Other fields preserved
+        assert_eq!(def.name, "Static Resource");
+    }
+
+    #[test]
+    fn mounted_resource_handler_delegates_read() {
+        let inner: Box<dyn ResourceHandler> = Box::new(StaticResource {
+            uri: "test://data".to_string(),
+            content: "mounted_data".to_string(),
+        });
+        let mounted = MountedResourceHandler::new(inner, "ns/test://data".to_string());
+        let ctx = test_ctx();
+        let result = mounted.read(&ctx);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap()[0].text, Some("mounted_data".to_string()));
+    }
+
+    #[test]
+    fn mounted_resource_handler_with_template() {
+        let inner: Box<dyn ResourceHandler> = Box::new(TemplateResource);
+        let tmpl = ResourceTemplate {
+            uri_template: "ns/resource://{id}".to_string(),
+            name: "Mounted Template".to_string(),
+            description: None,
+            mime_type: None,
+            icon: None,
+            version: None,
+            tags: vec![],
+            parameters: vec![],
+        };
+        let mounted =
+            MountedResourceHandler::with_template(inner, "ns/resource://{id}".to_string(), tmpl);
+        let template = mounted.template();
+        assert!(template.is_some());
+        assert_eq!(template.unwrap().uri_template, "ns/resource://{id}");
+    }
+
+    // ── MountedPromptHandler ─────────────────────────────────────────
+
+    #[test]
+    fn mounted_prompt_handler_overrides_name() {
+        let inner: Box<dyn PromptHandler> = Box::new(GreetingPrompt);
+        let mounted = MountedPromptHandler::new(inner, "ns/greeting".to_string());
+        let def = mounted.definition();
+        assert_eq!(def.name, "ns/greeting");
+        // Arguments preserved
+        assert_eq!(def.arguments.len(), 1);
+    }
+
+    #[test]
+    fn mounted_prompt_handler_delegates_get() {
+        let inner: Box<dyn PromptHandler> = Box::new(GreetingPrompt);
+        let mounted = MountedPromptHandler::new(inner, "ns/greeting".to_string());
+        let ctx = test_ctx();
+        let mut args = HashMap::new();
+        args.insert("name".to_string(), "MountedUser".to_string());
+        let result = mounted.get(&ctx, args);
+        assert!(result.is_ok());
+        match &result.unwrap()[0].content {
+            Content::Text { text } => assert!(text.contains("MountedUser")),
+            _ => panic!("Expected text content"),
+        }
+    }
+
+    // ── ProgressNotificationSender ───────────────────────────────────
+
+    #[test]
+    fn progress_notification_sender_sends_notification() {
+        let sent = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sent_clone = sent.clone();
+        let sender = ProgressNotificationSender::new(
+            fastmcp_protocol::ProgressToken::String("tok".to_string()),
+            move |req: fastmcp_protocol::JsonRpcRequest| {
+                sent_clone.lock().unwrap().push(req);
+            },
+        );
+
+        use fastmcp_core::NotificationSender;
+        sender.send_progress(0.5, Some(1.0), Some("half done"));
+
+        let notifications = sent.lock().unwrap();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].method, "notifications/progress");
+    }
+
+    #[test]
+    fn progress_notification_sender_without_total() {
+        let sent = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sent_clone = sent.clone();
+        let sender = ProgressNotificationSender::new(
+            fastmcp_protocol::ProgressToken::Number(99),
+            move |req: fastmcp_protocol::JsonRpcRequest| {
+                sent_clone.lock().unwrap().push(req);
+            },
+        );
+
+        use fastmcp_core::NotificationSender;
+        sender.send_progress(1.0, None, None);
+
+        let notifications = sent.lock().unwrap();
+        assert_eq!(notifications.len(), 1);
+    }
+
+    #[test]
+    fn progress_notification_sender_debug_format() {
+        let sender = ProgressNotificationSender::new(
+            fastmcp_protocol::ProgressToken::String("debug-test".to_string()),
+            |_: fastmcp_protocol::JsonRpcRequest| {},
+        );
+        let debug = format!("{sender:?}");
+        assert!(debug.contains("ProgressNotificationSender"));
+    }
+
+    // ── BidirectionalSenders ─────────────────────────────────────────
+
+    #[test]
+    fn bidirectional_senders_default_is_empty() {
+        let senders = BidirectionalSenders::new();
+        assert!(senders.sampling.is_none());
+        assert!(senders.elicitation.is_none());
+    }
+
+    #[test]
+    fn bidirectional_senders_debug_format() {
+        let senders = BidirectionalSenders::new();
+        let debug = format!("{senders:?}");
+        assert!(debug.contains("BidirectionalSenders"));
+        assert!(debug.contains("sampling: false"));
+        assert!(debug.contains("elicitation: false"));
+    }
+
+    // ── Router registration and lookup via direct handler ────────────
+
+    #[test]
+    fn router_registers_tool_and_lists_it() {
+        let mut router = Router::new();
+        router.add_tool(GreetTool);
+        let tools = router.tools();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "greet");
+    }
+
+    /// Tool that declares annotations only through `ToolHandler::annotations()`.
+    struct DeleteTool {
+        annotations: ToolAnnotations,
+    }
+
+    impl ToolHandler for DeleteTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: "delete".to_string(),
+                description: Some("Deletes a record".to_string()),
+                input_schema: serde_json::json!({"type": "object"}),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+                annotations: None,
+            }
+        }
+
+        fn annotations(&self) -> Option<&ToolAnnotations> {
+            Some(&self.annotations)
+        }
+
+        fn call(&self, _ctx: &McpContext, _args: serde_json::Value) -> McpResult<Vec<Content>> {
+            Ok(vec![])
+        }
+    }
+
+    #[test]
+    fn router_tools_list_surfaces_annotations() {
+        let mut router = Router::new();
+        router.add_tool(DeleteTool {
+            annotations: ToolAnnotations::new()
+                .title("Delete Record")
+                .destructive(true)
+                .idempotent(true)
+                .open_world(false),
+        });
+        router.add_tool(RichTool::default());
+
+        let cx = Cx::for_testing();
+        let result = router
+            .handle_tools_list(&cx, fastmcp_protocol::ListToolsParams::default(), None)
+            .unwrap();
+        let json = serde_json::to_value(&result).unwrap();
+        let tools = json["tools"].as_array().unwrap();
+
+        let delete = tools.iter().find(|t| t["name"] == "delete").unwrap();
+        assert_eq!(delete["annotations"]["title"], "Delete Record");
+        assert_eq!(delete["annotations"]["destructiveHint"], true);
+        assert_eq!(delete["annotations"]["idempotentHint"], true);
+        assert_eq!(delete["annotations"]["openWorldHint"], false);
+        assert!(delete["annotations"].get("readOnlyHint").is_none());
+
+        let rich = tools.iter().find(|t| t["name"] == "rich").unwrap();
+        assert_eq!(rich["annotations"]["readOnlyHint"], true);
+        assert_eq!(rich["annotations"]["destructiveHint"], false);
+    }
+
+    #[test]
+    fn router_registers_resource_and_lists_it() {
+        let mut router = Router::new();
+        router.add_resource(StaticResource {
+            uri: "test://r1".to_string(),
+            content: "c1".to_string(),
+        });
+        let resources = router.resources();
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].uri, "test://r1");
+    }
+
+    #[test]
+    fn router_registers_prompt_and_lists_it() {
+        let mut router = Router::new();
+        router.add_prompt(GreetingPrompt);
+        let prompts = router.prompts();
+        assert_eq!(prompts.len(), 1);
+        assert_eq!(prompts[0].name, "greeting");
+    }
+
+    #[test]
+    fn router_counts_match_registrations() {
+        let mut router = Router::new();
+        router.add_tool(GreetTool);
+        router.add_tool(ErrorTool);
+        router.add_resource(StaticResource {
+            uri: "test://a".to_string(),
+            content: "".to_string(),
+        });
+        router.add_prompt(GreetingPrompt);
+
+        assert_eq!(router.tools_count(), 2);
+        assert_eq!(router.resources_count(), 1);
+        assert_eq!(router.prompts_count(), 1);
+    }
+
+    #[test]
+    fn router_resource_template_count() {
+        let mut router = Router::new();
+        router.add_resource(TemplateResource);
+        // TemplateResource returns a template, so it should count as a template
+        assert_eq!(router.resource_templates_count(), 1);
+    }
+
+    #[test]
+    fn router_strict_input_validation_default_is_false() {
+        let router = Router::new();
+        assert!(!router.strict_input_validation());
+    }
+
+    #[test]
+    fn router_strict_input_validation_can_be_set() {
+        let mut router = Router::new();
+        router.set_strict_input_validation(true);
+        assert!(router.strict_input_validation());
+    }
+}
+
+// ============================================================================
+// ServerBuilder Tests
+// ============================================================================
+
+mod builder_tests {
+    use super::*;
+    use crate::{DuplicateBehavior, LoggingConfig, ServerBuilder};
+    use fastmcp_console::config::{BannerStyle, ConsoleConfig, TrafficVerbosity};
+    use fastmcp_protocol::ResourceTemplate;
+    use log::Level;
+
+    // ── Minimal handler stubs ────────────────────────────────────────
+
+    struct StubTool {
+        name: &'static str,
+    }
+
+    impl StubTool {
+        fn named(name: &'static str) -> Self {
+            Self { name }
+        }
+    }
+
+    impl ToolHandler for StubTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: self.name.to_string(),
+                description: Some(format!("Stub tool {}", self.name)),
+                input_schema: serde_json::json!({"type": "object"}),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+                annotations: None,
+            }
+        }
+
+        fn call(
+            &self,
+            _ctx: &McpContext,
+            _arguments: serde_json::Value,
+        ) -> McpResult<Vec<Content>> {
+            Ok(vec![Content::Text {
+                text: format!("stub:{}", self.name),
+            }])
+        }
+    }
+
+    struct StubResource {
+        name: &'static str,
+        uri: String,
+    }
+
+    impl StubResource {
+        fn named(name: &'static str) -> Self {
+            Self {
+                name,
+                uri: format!("test://{name}"),
+            }
+        }
+    }
+
+    impl ResourceHandler for StubResource {
+        fn definition(&self) -> Resource {
+            Resource {
+                uri: self.uri.clone(),
+                name: self.name.to_string(),
+                description: Some(format!("Stub resource {}", self.name)),
+                mime_type: Some("text/plain".to_string()),
+                icon: None,
+                version: None,
+                tags: vec![],
+            }
+        }
+
+        fn read(&self, _ctx: &McpContext) -> McpResult<Vec<ResourceContent>> {
+            Ok(vec![ResourceContent {
+                uri: self.uri.clone(),
+                mime_type: Some("text/plain".to_string()),
+                text: Some(format!("content:{}", self.name)),
+                blob: None,
+                etag: None,
+                last_modified: None,
+            }])
+        }
+    }
+
+    struct StubPrompt {
+        name: &'static str,
+    }
+
+    impl StubPrompt {
+        fn named(name: &'static str) -> Self {
+            Self { name }
+        }
+    }
+
+    impl PromptHandler for StubPrompt {
+        fn definition(&self) -> Prompt {
+            Prompt {
+                name: self.name.to_string(),
+                description: Some(format!("Stub prompt {}", self.name)),
+                arguments: vec![],
+                icon: None,
+                version: None,
+                tags: vec![],
+            }
+        }
+
+        fn get(
+            &self,
+            _ctx: &McpContext,
+            _arguments: HashMap<String, String>,
+        ) -> McpResult<Vec<PromptMessage>> {
+            Ok(vec![PromptMessage {
+                role: Role::User,
+                content: Content::Text {
+                    text: format!("prompt:{}", self.name),
+                },
+            }])
+        }
+    }
+
+    // ── Basic Construction ───────────────────────────────────────────
+
+    #[test]
+    fn builder_new_sets_name_and_version() {
+        let server = ServerBuilder::new("test-server", "1.2.3").build();
+        assert_eq!(server.info().name, "test-server");
+        assert_eq!(server.info().version, "1.2.3");
+    }
+
+    #[test]
+    fn builder_default_capabilities_include_logging() {
+        let server = ServerBuilder::new("s", "0.1").build();
+        assert!(server.capabilities().logging.is_some());
+        assert!(server.capabilities().tools.is_none());
+        assert!(server.capabilities().resources.is_none());
+        assert!(server.capabilities().prompts.is_none());
+        assert!(server.capabilities().tasks.is_none());
+    }
+
+    #[test]
+    fn builder_server_new_delegates_to_builder() {
+        // Server::new returns a ServerBuilder, not a Server
+        let server = Server::new("srv", "0.1").build();
+        assert_eq!(server.info().name, "srv");
+    }
+
+    // ── Tool Registration ────────────────────────────────────────────
+
+    #[test]
+    fn builder_tool_enables_tools_capability() {
+        let server = ServerBuilder::new("s", "0.1")
+            .tool(StubTool::named("alpha"))
+            .build();
+        assert!(server.capabilities().tools.is_some());
+        assert!(server.has_tools());
+    }
+
+    #[test]
+    fn builder_registers_multiple_tools() {
+        let server = ServerBuilder::new("s", "0.1")
+            .tool(StubTool::named("a"))
+            .tool(StubTool::named("b"))
+            .tool(StubTool::named("c"))
+            .build();
+        let tools = server.tools();
+        assert_eq!(tools.len(), 3);
+        let names: Vec<_> = tools.iter().map(|t| t.name.as_str()).collect();
+        assert!(names.contains(&"a"));
+        assert!(names.contains(&"b"));
+        assert!(names.contains(&"c"));
+    }
+
+    // ── Resource Registration ────────────────────────────────────────
+
+    #[test]
+    fn builder_resource_enables_resources_capability() {
+        let server = ServerBuilder::new("s", "0.1")
+            .resource(StubResource::named("data"))
+            .build();
+        assert!(server.capabilities().resources.is_some());
+        assert!(server.has_resources());
+    }
+
+    #[test]
+    fn builder_registers_multiple_resources() {
+        let server = ServerBuilder::new("s", "0.1")
+            .resource(StubResource::named("r1"))
+            .resource(StubResource::named("r2"))
+            .build();
+        let resources = server.resources();
+        assert_eq!(resources.len(), 2);
+    }
+
+    #[test]
+    fn builder_resource_template_enables_resources_capability() {
+        let template = ResourceTemplate {
+            uri_template: "file://{path}".to_string(),
+            name: "file".to_string(),
+            description: None,
+            mime_type: None,
+            icon: None,
+            version: None,
+            tags: vec![],
+            parameters: vec![],
+        };
+        let server = ServerBuilder::new("s", "0.1")
+            .resource_template(template)
+            .build();
+        assert!(server.capabilities().resources.is_some());
+        assert!(server.has_resources());
+        let templates = server.resource_templates();
+        assert_eq!(templates.len(), 1);
+        assert_eq!(templates[0].name, "file");
+    }
+
+    // ── Prompt Registration ──────────────────────────────────────────
+
+    #[test]
+    fn builder_prompt_enables_prompts_capability() {
+        let server = ServerBuilder::new("s", "0.1")
+            .prompt(StubPrompt::named("hello"))
+            .build();
+        assert!(server.capabilities().prompts.is_some());
+        assert!(server.has_prompts());
+    }
+
+    #[test]
+    fn builder_registers_multiple_prompts() {
+        let server = ServerBuilder::new("s", "0.1")
+            .prompt(StubPrompt::named("p1"))
+            .prompt(StubPrompt::named("p2"))
+            .prompt(StubPrompt::named("p3"))
+            .build();
+        let prompts = server.prompts();
+        assert_eq!(prompts.len(), 3);
+    }
+
+    // ── Mixed Registration ───────────────────────────────────────────
+
+    #[test]
+    fn builder_mixed_handlers_enable_all_capabilities() {
+        let server = ServerBuilder::new("s", "0.1")
+            .tool(StubTool::named("t"))
+            .resource(StubResource::named("r"))
+            .prompt(StubPrompt::named("p"))
+            .build();
+        assert!(server.has_tools());
+        assert!(server.has_resources());
+        assert!(server.has_prompts());
+    }
+
+    #[test]
+    fn builder_no_handlers_means_no_capabilities() {
+        let server = ServerBuilder::new("empty", "0.1").build();
+        assert!(!server.has_tools());
+        assert!(!server.has_resources());
+        assert!(!server.has_prompts());
+    }
+
+    // ── Request Timeout ──────────────────────────────────────────────
+
+    #[test]
+    fn builder_default_request_timeout_is_30() {
+        // Build and check internal state via the server
+        let server = ServerBuilder::new("s", "0.1").build();
+        // Default is 30 seconds; verified via internal state
+        // We can't directly read request_timeout_secs, but we verify the builder
+        // accepted the default without panicking
+        assert_eq!(server.info().name, "s");
+    }
+
+    #[test]
+    fn builder_custom_request_timeout() {
+        let server = ServerBuilder::new("s", "0.1").request_timeout(60).build();
+        // Builder accepted custom timeout without error
+        assert_eq!(server.info().name, "s");
+    }
+
+    #[test]
+    fn builder_zero_timeout_disables_enforcement() {
+        let server = ServerBuilder::new("s", "0.1").request_timeout(0).build();
+        assert_eq!(server.info().name, "s");
+    }
+
+    // ── Stats ────────────────────────────────────────────────────────
+
+    #[test]
+    fn builder_stats_enabled_by_default() {
+        let server = ServerBuilder::new("s", "0.1").build();
+        assert!(server.stats().is_some());
+    }
+
+    #[test]
+    fn builder_without_stats_disables_collection() {
+        let server = ServerBuilder::new("s", "0.1").without_stats().build();
+        assert!(server.stats().is_none());
+        assert!(server.stats_collector().is_none());
+    }
+
+    // ── Error Masking ────────────────────────────────────────────────
+
+    #[test]
+    fn builder_error_masking_disabled_by_default() {
+        let builder = ServerBuilder::new("s", "0.1");
+        assert!(!builder.is_error_masking_enabled());
+    }
+
+    #[test]
+    fn builder_mask_error_details_enables_masking() {
+        let builder = ServerBuilder::new("s", "0.1").mask_error_details(true);
+        assert!(builder.is_error_masking_enabled());
+    }
+
+    #[test]
+    fn builder_mask_error_details_toggle() {
+        let builder = ServerBuilder::new("s", "0.1")
+            .mask_error_details(true)
+            .mask_error_details(false);
+        assert!(!builder.is_error_masking_enabled());
+    }
+
+    // ── Strict Input Validation ──────────────────────────────────────
+
+    #[test]
+    fn builder_strict_input_validation_disabled_by_default() {
+        let builder = ServerBuilder::new("s", "0.1");
+        assert!(!builder.is_strict_input_validation_enabled());
+    }
+
+    #[test]
+    fn builder_strict_input_validation_enable() {
+        let builder = ServerBuilder::new("s", "0.1").strict_input_validation(true);
+        assert!(builder.is_strict_input_validation_enabled());
+    }
+
+    #[test]
+    fn builder_strict_input_validation_toggle() {
+        let builder = ServerBuilder::new("s", "0.1")
+            .strict_input_validation(true)
+            .strict_input_validation(false);
+        assert!(!builder.is_strict_input_validation_enabled());
+    }
+
+    // ── Instructions ─────────────────────────────────────────────────
+
+    #[test]
+    fn builder_instructions_set() {
+        // Instructions are stored internally; verify the builder accepts them.
+        let server = ServerBuilder::new("s", "0.1")
+            .instructions("Use this server for math operations")
+            .build();
+        assert_eq!(server.info().name, "s");
+    }
+
+    // ── Logging Configuration ────────────────────────────────────────
+
+    #[test]
+    fn builder_log_level() {
+        let server = ServerBuilder::new("s", "0.1")
+            .log_level(Level::Debug)
+            .build();
+        assert_eq!(server.info().name, "s");
+    }
+
+    #[test]
+    fn builder_log_level_filter() {
+        let server = ServerBuilder::new("s", "0.1")
+            .log_level_filter(log::LevelFilter::Warn)
+            .build();
+        assert_eq!(server.info().name, "s");
+    }
+
+    #[test]
+    fn builder_log_timestamps_and_targets() {
+        let server = ServerBuilder::new("s", "0.1")
+            .log_timestamps(false)
+            .log_targets(false)
+            .build();
+        assert_eq!(server.info().name, "s");
+    }
+
+    #[test]
+    fn builder_full_logging_config() {
+        let config = LoggingConfig {
+            level: Level::Trace,
+            timestamps: false,
+            targets: false,
+            file_line: true,
+        };
+        let server = ServerBuilder::new("s", "0.1").logging(config).build();
+        assert_eq!(server.info().name, "s");
+    }
+
+    // ── Console Configuration ────────────────────────────────────────
+
+    #[test]
+    fn builder_console_config_full() {
+        let config = ConsoleConfig::new()
+            .with_banner(BannerStyle::Compact)
+            .plain_mode();
+        let server = ServerBuilder::new("s", "0.1")
+            .with_console_config(config)
+            .build();
+        let cc = server.console_config();
+        assert!(cc.force_plain);
+    }
+
+    #[test]
+    fn builder_without_banner() {
+        let server = ServerBuilder::new("s", "0.1").without_banner().build();
+        let cc = server.console_config();
+        assert!(!cc.show_banner);
+        assert_eq!(cc.banner_style, BannerStyle::None);
+    }
+
+    #[test]
+    fn builder_with_banner_compact() {
+        let server = ServerBuilder::new("s", "0.1")
+            .with_banner(BannerStyle::Compact)
+            .build();
+        assert_eq!(server.info().name, "s");
+    }
+
+    #[test]
+    fn builder_traffic_logging() {
+        let server = ServerBuilder::new("s", "0.1")
+            .with_traffic_logging(TrafficVerbosity::Full)
+            .build();
+        assert_eq!(server.info().name, "s");
+    }
+
+    #[test]
+    fn builder_plain_mode() {
+        let server = ServerBuilder::new("s", "0.1").plain_mode().build();
+        assert!(server.console_config().force_plain);
+    }
+
+    #[test]
+    fn builder_force_color() {
+        let server = ServerBuilder::new("s", "0.1").force_color().build();
+        assert_eq!(server.info().name, "s");
+    }
+
+    #[test]
+    fn builder_periodic_stats() {
+        let server = ServerBuilder::new("s", "0.1")
+            .with_periodic_stats(10)
+            .build();
+        assert_eq!(server.info().name, "s");
+    }
+
+    // ── DuplicateBehavior ────────────────────────────────────────────
+
+    #[test]
+    fn builder_on_duplicate_default_is_warn() {
+        // Default behavior is Warn (keeps original, logs warning)
+        let server = ServerBuilder::new("s", "0.1")
+            .tool(StubTool::named("dup"))
+            .tool(StubTool::named("dup"))
+            .build();
+        // With Warn default, the second registration keeps original
+        let tools = server.tools();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "dup");
+    }
+
+    #[test]
+    fn builder_on_duplicate_ignore_keeps_original() {
+        let server = ServerBuilder::new("s", "0.1")
+            .on_duplicate(DuplicateBehavior::Ignore)
+            .tool(StubTool::named("dup"))
+            .tool(StubTool::named("dup"))
+            .build();
+        let tools = server.tools();
+        assert_eq!(tools.len(), 1);
+    }
+
+    #[test]
+    fn builder_on_duplicate_replace() {
+        let server = ServerBuilder::new("s", "0.1")
+            .on_duplicate(DuplicateBehavior::Replace)
+            .tool(StubTool::named("dup"))
+            .tool(StubTool::named("dup"))
+            .build();
+        let tools = server.tools();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "dup");
+    }
+
+    #[test]
+    fn builder_on_duplicate_error_logs_and_skips() {
+        // With Error behavior, duplicate registration fails but builder doesn't panic
+        let server = ServerBuilder::new("s", "0.1")
+            .on_duplicate(DuplicateBehavior::Error)
+            .tool(StubTool::named("dup"))
+            .tool(StubTool::named("dup"))
+            .build();
+        // Only the first registration succeeds
+        let tools = server.tools();
+        assert_eq!(tools.len(), 1);
     }
 
     #[test]
-    fn test_mount_with_prefix_renames_tools() {
-        let mut main_router = Router::new();
-        let mut db_router = Router::new();
-        db_router.add_tool(QueryTool);
-        db_router.add_tool(InsertTool);
-
-        let result = main_router.mount(db_router, Some("db"));
+    fn builder_on_duplicate_applies_to_resources() {
+        let server = ServerBuilder::new("s", "0.1")
+            .on_duplicate(DuplicateBehavior::Ignore)
+            .resource(StubResource::named("r"))
+            .resource(StubResource::named("r"))
+            .build();
+        let resources = server.resources();
+        assert_eq!(resources.len(), 1);
+    }
 
-        assert_eq!(result.tools, 2);
-        assert!(main_router.get_tool("db/query").is_some());
-        assert!(main_router.get_tool("db/insert").is_some());
-        assert!(main_router.get_tool("query").is_none());
-        assert!(main_router.get_tool("insert").is_none());
+    #[test]
+    fn builder_on_duplicate_applies_to_prompts() {
+        let server = ServerBuilder::new("s", "0.1")
+            .on_duplicate(DuplicateBehavior::Ignore)
+            .prompt(StubPrompt::named("p"))
+            .prompt(StubPrompt::named("p"))
+            .build();
+        let prompts = server.prompts();
+        assert_eq!(prompts.len(), 1);
     }
 
+    // ── Middleware Registration ───────────────────────────────────────
+
     #[test]
-    fn test_mount_without_prefix_keeps_names() {
-        let mut main_router = Router::new();
-        let mut other_router = Router::new();
-        other_router.add_tool(QueryTool);
+    fn builder_middleware_registration() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+        let server = ServerBuilder::new("s", "0.1")
+            .middleware(RecordingMiddleware::new("m1", events.clone()))
+            .middleware(RecordingMiddleware::new("m2", events.clone()))
+            .build();
+        // Server builds successfully with middleware
+        assert_eq!(server.info().name, "s");
+    }
 
-        let result = main_router.mount(other_router, None);
+    // ── Auth Provider ────────────────────────────────────────────────
 
-        assert_eq!(result.tools, 1);
-        assert!(main_router.get_tool("query").is_some());
+    #[test]
+    fn builder_auth_provider_static_token() {
+        let ctx = fastmcp_core::AuthContext::with_subject("test-user");
+        let server = ServerBuilder::new("s", "0.1")
+            .auth_provider(TokenAuthProvider::new(StaticTokenVerifier::new(vec![(
+                "secret-token".to_string(),
+                ctx,
+            )])))
+            .build();
+        assert_eq!(server.info().name, "s");
     }
 
     #[test]
-    fn test_mount_resources_with_prefix() {
-        let mut main_router = Router::new();
-        let mut other_router = Router::new();
-        other_router.add_resource(ConfigResource);
+    fn builder_auth_provider_allow_all() {
+        let server = ServerBuilder::new("s", "0.1")
+            .auth_provider(crate::AllowAllAuthProvider)
+            .build();
+        assert_eq!(server.info().name, "s");
+    }
 
-        let result = main_router.mount(other_router, Some("service"));
+    // ── Lifecycle Hooks ──────────────────────────────────────────────
 
-        assert_eq!(result.resources, 1);
-        assert!(main_router.get_resource("service/config://app").is_some());
-        assert!(main_router.get_resource("config://app").is_none());
+    #[test]
+    fn builder_on_startup_hook() {
+        let server = ServerBuilder::new("s", "0.1")
+            .on_startup(|| -> Result<(), std::io::Error> { Ok(()) })
+            .build();
+        assert_eq!(server.info().name, "s");
     }
 
     #[test]
-    fn test_mount_prompts_with_prefix() {
-        let mut main_router = Router::new();
-        let mut other_router = Router::new();
-        other_router.add_prompt(GreetingPrompt);
-
-        let result = main_router.mount(other_router, Some("templates"));
+    fn builder_on_shutdown_hook() {
+        let server = ServerBuilder::new("s", "0.1")
+            .on_shutdown(|| {
+                // cleanup
+            })
+            .build();
+        assert_eq!(server.info().name, "s");
+    }
 
-        assert_eq!(result.prompts, 1);
-        assert!(main_router.get_prompt("templates/greeting").is_some());
-        assert!(main_router.get_prompt("greeting").is_none());
+    #[test]
+    fn builder_both_lifecycle_hooks() {
+        let server = ServerBuilder::new("s", "0.1")
+            .on_startup(|| -> Result<(), std::io::Error> { Ok(()) })
+            .on_shutdown(|| {})
+            .build();
+        assert_eq!(server.info().name, "s");
     }
 
+    // ── Mount ────────────────────────────────────────────────────────
+
     #[test]
-    fn test_mount_conflict_generates_warning() {
-        let mut main_router = Router::new();
-        main_router.add_tool(QueryTool);
+    fn builder_mount_server_with_prefix() {
+        let child = ServerBuilder::new("child", "0.1")
+            .tool(StubTool::named("do_thing"))
+            .resource(StubResource::named("data"))
+            .prompt(StubPrompt::named("ask"))
+            .build();
 
-        let mut other_router = Router::new();
-        other_router.add_tool(QueryTool);
+        let parent = ServerBuilder::new("parent", "0.1")
+            .mount(child, Some("child"))
+            .build();
 
-        // Mount without prefix, causing a conflict
-        let result = main_router.mount(other_router, None);
+        assert!(parent.has_tools());
+        assert!(parent.has_resources());
+        assert!(parent.has_prompts());
 
-        assert_eq!(result.tools, 1);
-        assert_eq!(result.warnings.len(), 1);
-        assert!(result.warnings[0].contains("already exists"));
+        let tools = parent.tools();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "child/do_thing");
     }
 
     #[test]
-    fn test_mount_preserves_tool_definition() {
-        let mut main_router = Router::new();
-        let mut db_router = Router::new();
-        db_router.add_tool(QueryTool);
+    fn builder_mount_server_without_prefix() {
+        let child = ServerBuilder::new("child", "0.1")
+            .tool(StubTool::named("alpha"))
+            .build();
 
-        main_router.mount(db_router, Some("db"));
+        let parent = ServerBuilder::new("parent", "0.1")
+            .mount(child, None)
+            .build();
 
-        let tools = main_router.tools();
-        let tool = tools.iter().find(|t| t.name == "db/query").unwrap();
-        assert_eq!(tool.description, Some("Executes a query".to_string()));
+        let tools = parent.tools();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "alpha");
     }
 
     #[test]
-    fn test_mount_all_components() {
-        let mut main_router = Router::new();
-        let mut other_router = Router::new();
-        other_router.add_tool(QueryTool);
-        other_router.add_resource(ConfigResource);
-        other_router.add_prompt(GreetingPrompt);
+    fn builder_mount_tools_only() {
+        let child = ServerBuilder::new("child", "0.1")
+            .tool(StubTool::named("t"))
+            .resource(StubResource::named("r"))
+            .prompt(StubPrompt::named("p"))
+            .build();
 
-        let result = main_router.mount(other_router, Some("sub"));
+        let parent = ServerBuilder::new("parent", "0.1")
+            .mount_tools(child, Some("ns"))
+            .build();
 
-        assert_eq!(result.tools, 1);
-        assert_eq!(result.resources, 1);
-        assert_eq!(result.prompts, 1);
-        assert!(result.has_components());
+        assert!(parent.has_tools());
+        assert!(!parent.has_resources());
+        assert!(!parent.has_prompts());
     }
 
     #[test]
-    fn test_selective_mount_tools_only() {
-        let db_server = Server::new("db", "1.0")
-            .tool(QueryTool)
-            .resource(ConfigResource)
-            .prompt(GreetingPrompt)
+    fn builder_mount_resources_only() {
+        let child = ServerBuilder::new("child", "0.1")
+            .tool(StubTool::named("t"))
+            .resource(StubResource::named("r"))
             .build();
 
-        let main = Server::new("main", "1.0")
-            .mount_tools(db_server, Some("db"))
+        let parent = ServerBuilder::new("parent", "0.1")
+            .mount_resources(child, Some("ns"))
             .build();
 
-        let tools = main.tools();
-        let resources = main.resources();
-        let prompts = main.prompts();
+        assert!(!parent.has_tools());
+        assert!(parent.has_resources());
+    }
 
-        assert_eq!(tools.len(), 1);
-        assert_eq!(tools[0].name, "db/query");
-        assert!(resources.is_empty());
-        assert!(prompts.is_empty());
+    #[test]
+    fn builder_mount_prompts_only() {
+        let child = ServerBuilder::new("child", "0.1")
+            .tool(StubTool::named("t"))
+            .prompt(StubPrompt::named("p"))
+            .build();
+
+        let parent = ServerBuilder::new("parent", "0.1")
+            .mount_prompts(child, Some("ns"))
+            .build();
+
+        assert!(!parent.has_tools());
+        assert!(parent.has_prompts());
     }
 
     #[test]
-    fn test_selective_mount_resources_only() {
-        let data_server = Server::new("data", "1.0")
-            .tool(QueryTool)
-            .resource(ConfigResource)
+    fn builder_mount_multiple_servers() {
+        let db = ServerBuilder::new("db", "0.1")
+            .tool(StubTool::named("query"))
+            .build();
+        let api = ServerBuilder::new("api", "0.1")
+            .tool(StubTool::named("fetch"))
             .build();
 
-        let main = Server::new("main", "1.0")
-            .mount_resources(data_server, Some("data"))
+        let main = ServerBuilder::new("main", "0.1")
+            .mount(db, Some("db"))
+            .mount(api, Some("api"))
             .build();
 
         let tools = main.tools();
-        let resources = main.resources();
+        assert_eq!(tools.len(), 2);
+        let names: Vec<_> = tools.iter().map(|t| t.name.as_str()).collect();
+        assert!(names.contains(&"db/query"));
+        assert!(names.contains(&"api/fetch"));
+    }
+
+    // ── Fluent Chaining ──────────────────────────────────────────────
+
+    #[test]
+    fn builder_full_fluent_chain() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+        let server = ServerBuilder::new("full-server", "2.0.0")
+            .instructions("A fully configured server")
+            .on_duplicate(DuplicateBehavior::Error)
+            .request_timeout(120)
+            .mask_error_details(true)
+            .strict_input_validation(true)
+            .log_level(Level::Debug)
+            .log_timestamps(false)
+            .without_banner()
+            .plain_mode()
+            .tool(StubTool::named("tool_a"))
+            .resource(StubResource::named("res_a"))
+            .prompt(StubPrompt::named("prompt_a"))
+            .middleware(RecordingMiddleware::new("audit", events))
+            .auth_provider(crate::AllowAllAuthProvider)
+            .on_startup(|| -> Result<(), std::io::Error> { Ok(()) })
+            .on_shutdown(|| {})
+            .build();
 
-        assert!(tools.is_empty());
-        assert_eq!(resources.len(), 1);
-        assert_eq!(resources[0].uri, "data/config://app");
+        assert_eq!(server.info().name, "full-server");
+        assert_eq!(server.info().version, "2.0.0");
+        assert!(server.has_tools());
+        assert!(server.has_resources());
+        assert!(server.has_prompts());
+        assert_eq!(server.tools().len(), 1);
+        assert_eq!(server.resources().len(), 1);
+        assert_eq!(server.prompts().len(), 1);
     }
 
+    // ── into_router ──────────────────────────────────────────────────
+
     #[test]
-    fn test_selective_mount_prompts_only() {
-        let templates_server = Server::new("templates", "1.0")
-            .tool(QueryTool)
-            .prompt(GreetingPrompt)
+    fn builder_into_router_preserves_components() {
+        let server = ServerBuilder::new("s", "0.1")
+            .tool(StubTool::named("t1"))
+            .tool(StubTool::named("t2"))
+            .resource(StubResource::named("r1"))
+            .prompt(StubPrompt::named("p1"))
             .build();
 
-        let main = Server::new("main", "1.0")
-            .mount_prompts(templates_server, Some("tmpl"))
-            .build();
+        let router = server.into_router();
+        assert_eq!(router.tools_count(), 2);
+        assert_eq!(router.resources_count(), 1);
+        assert_eq!(router.prompts_count(), 1);
+    }
 
-        let tools = main.tools();
-        let prompts = main.prompts();
+    // ── Task Manager ─────────────────────────────────────────────────
 
-        assert!(tools.is_empty());
-        assert_eq!(prompts.len(), 1);
-        assert_eq!(prompts[0].name, "tmpl/greeting");
+    #[test]
+    fn builder_without_task_manager() {
+        let server = ServerBuilder::new("s", "0.1").build();
+        assert!(server.task_manager().is_none());
+        assert!(server.capabilities().tasks.is_none());
     }
 
     #[test]
-    fn test_full_mount_via_server_builder() {
-        let db_server = Server::new("db", "1.0")
-            .tool(QueryTool)
-            .tool(InsertTool)
+    fn builder_with_task_manager_enables_tasks_capability() {
+        let tm = TaskManager::new();
+        let server = ServerBuilder::new("s", "0.1")
+            .with_task_manager(tm.into_shared())
             .build();
+        assert!(server.task_manager().is_some());
+        assert!(server.capabilities().tasks.is_some());
+    }
 
-        let api_server = Server::new("api", "1.0").prompt(GreetingPrompt).build();
-
-        let main = Server::new("main", "1.0")
-            .tool(GreetTool)
-            .mount(db_server, Some("db"))
-            .mount(api_server, Some("api"))
-            .build();
+    // ── Tool schema validation ───────────────────────────────────────
 
-        let tools = main.tools();
-        let prompts = main.prompts();
+    struct SchemaTool {
+        schema: serde_json::Value,
+    }
 
-        // Should have original greet + mounted db/query and db/insert
-        assert_eq!(tools.len(), 3);
-        let tool_names: Vec<_> = tools.iter().map(|t| t.name.as_str()).collect();
-        assert!(tool_names.contains(&"greet"));
-        assert!(tool_names.contains(&"db/query"));
-        assert!(tool_names.contains(&"db/insert"));
+    impl ToolHandler for SchemaTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: "shaped".to_string(),
+                description: None,
+                input_schema: self.schema.clone(),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+                annotations: None,
+            }
+        }
+    }
 
-        // Should have api/greeting
-        assert_eq!(prompts.len(), 1);
-        assert_eq!(prompts[0].name, "api/greeting");
+    #[test]
+    fn build_accepts_well_formed_tool_schema() {
+        let server = ServerBuilder::new("s", "0.1")
+            .tool(SchemaTool {
+                schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {"name": {"type": "string", "minLength": 1}},
+                    "required": ["name"]
+                }),
+            })
+            .try_build();
+        assert!(server.is_ok());
     }
 
     #[test]
-    fn test_nested_mounting() {
-        // Create inner server
-        let inner = Server::new("inner", "1.0").tool(QueryTool).build();
+    fn try_build_reports_tool_with_malformed_schema() {
+        let err = ServerBuilder::new("s", "0.1")
+            .tool(StubTool::named("fine"))
+            .tool(SchemaTool {
+                schema: serde_json::json!({"type": 123}),
+            })
+            .try_build()
+            .err()
+            .expect("malformed schema is rejected");
+        assert_eq!(err.code, McpErrorCode::InvalidParams);
+        assert!(err.message.contains("Tool 'shaped'"), "{}", err.message);
+        assert!(err.message.contains("root.type"), "{}", err.message);
+    }
 
-        // Mount inner into middle
-        let middle = Server::new("middle", "1.0")
-            .mount(inner, Some("inner"))
+    #[test]
+    #[should_panic(expected = "Tool 'shaped' has an invalid input_schema")]
+    fn build_panics_on_malformed_schema() {
+        let _ = ServerBuilder::new("s", "0.1")
+            .tool(SchemaTool {
+                schema: serde_json::json!({"type": 123}),
+            })
             .build();
+    }
 
-        // Mount middle into outer
-        let outer = Server::new("outer", "1.0")
-            .mount(middle, Some("middle"))
+    #[test]
+    fn schema_validation_can_be_disabled() {
+        let server = ServerBuilder::new("s", "0.1")
+            .validate_tool_schemas(false)
+            .tool(SchemaTool {
+                schema: serde_json::json!({"type": 123}),
+            })
             .build();
-
-        let tools = outer.tools();
-        assert_eq!(tools.len(), 1);
-        // Tool should be at middle/inner/query
-        assert_eq!(tools[0].name, "middle/inner/query");
+        assert_eq!(server.tools().len(), 1);
     }
+}
+
+mod parse_error_tests {
+    use super::*;
+    use crate::ParseErrorTracker;
 
     #[test]
-    fn test_prefix_validation_rejects_slashes() {
-        let mut router = Router::new();
-        let mut other = Router::new();
-        other.add_tool(QueryTool);
+    fn test_recoverable_id_gets_parse_error_response() {
+        let mut tracker = ParseErrorTracker::new(None);
 
-        let result = router.mount(other, Some("bad/prefix"));
+        let (response, close) = tracker.record(
+            Some(RequestId::String("req-7".to_string())),
+            "EOF while parsing",
+        );
+        assert!(!close);
 
-        // Should still mount but generate a warning
-        assert!(!result.warnings.is_empty());
-        assert!(result.warnings[0].contains("slash"));
+        let response = response.expect("parse error response");
+        assert_eq!(response.id, Some(RequestId::String("req-7".to_string())));
+        let error = response.error.expect("error payload");
+        assert_eq!(error.code, -32700);
+        assert!(error.message.contains("EOF while parsing"));
     }
 
     #[test]
-    fn test_mounted_tool_can_be_called() {
-        let db_server = Server::new("db", "1.0").tool(QueryTool).build();
-
-        let main = Server::new("main", "1.0")
-            .mount(db_server, Some("db"))
-            .build();
+    fn test_unrecoverable_id_is_only_logged() {
+        let mut tracker = ParseErrorTracker::new(None);
+        let (response, close) = tracker.record(None, "invalid utf-8");
+        assert!(response.is_none());
+        assert!(!close);
+    }
 
-        // Get the tool handler
-        let tools = main.tools();
-        assert_eq!(tools.len(), 1);
-        assert_eq!(tools[0].name, "db/query");
+    #[test]
+    fn test_closes_after_consecutive_threshold() {
+        let mut tracker = ParseErrorTracker::new(Some(3));
 
-        // Verify the definition is correct
-        let tool = &tools[0];
-        assert_eq!(tool.description, Some("Executes a query".to_string()));
+        assert!(!tracker.record(None, "bad").1);
+        assert!(!tracker.record(Some(RequestId::Number(1)), "bad").1);
+        // A good frame resets the streak.
+        tracker.reset();
+        assert!(!tracker.record(None, "bad").1);
+        assert!(!tracker.record(None, "bad").1);
+        assert!(tracker.record(None, "bad").1);
     }
 
     #[test]
-    fn test_mount_empty_router() {
-        let mut main_router = Router::new();
-        let empty_router = Router::new();
+    fn test_builder_threads_parse_error_limit() {
+        let server = Server::new("test", "1.0").build();
+        assert_eq!(server.max_consecutive_parse_errors, None);
 
-        let result = main_router.mount(empty_router, Some("empty"));
-
-        assert_eq!(result.tools, 0);
-        assert_eq!(result.resources, 0);
-        assert_eq!(result.prompts, 0);
-        assert!(!result.has_components());
+        let server = Server::new("test", "1.0")
+            .max_consecutive_parse_errors(5)
+            .build();
+        assert_eq!(server.max_consecutive_parse_errors, Some(5));
     }
 }
 
-// ============================================================================
-// Duplicate Behavior Tests
-// ============================================================================
-
-mod duplicate_behavior_tests {
+mod content_streaming_tests {
     use super::*;
-    use crate::{DuplicateBehavior, Router};
-
-    /// A simple tool for duplicate tests.
-    struct DupTool {
-        id: u32,
-    }
-
-    impl DupTool {
-        fn new(id: u32) -> Self {
-            Self { id }
-        }
-    }
-
-    impl ToolHandler for DupTool {
+    use crate::testing::TestServer;
+    use fastmcp_core::ToolContentItem;
+    use fastmcp_protocol::{CallToolResult, JsonRpcMessage};
+    use fastmcp_transport::Transport;
+    use fastmcp_transport::sse::SseServerTransport;
+    use std::io::Write;
+    use std::sync::Mutex;
+
+    /// A tool that streams two chunks before returning its final content.
+    struct ChunkedBlobTool;
+
+    impl ToolHandler for ChunkedBlobTool {
         fn definition(&self) -> Tool {
             Tool {
-                name: "dup_tool".to_string(),
-                description: Some(format!("Tool #{}", self.id)),
+                name: "blob".to_string(),
+                description: None,
                 input_schema: serde_json::json!({"type": "object"}),
                 output_schema: None,
                 icon: None,
@@ -4368,563 +8674,719 @@ mod duplicate_behavior_tests {
             }
         }
 
-        fn call(
-            &self,
-            _ctx: &McpContext,
-            _arguments: serde_json::Value,
-        ) -> McpResult<Vec<Content>> {
+        fn call(&self, ctx: &McpContext, _arguments: serde_json::Value) -> McpResult<Vec<Content>> {
+            ctx.stream_content(vec![ToolContentItem::text("part-1")])?;
+            ctx.stream_content(vec![ToolContentItem::text("part-2")])?;
             Ok(vec![Content::Text {
-                text: format!("Tool #{}", self.id),
+                text: "done".to_string(),
             }])
         }
     }
 
-    #[test]
-    fn test_duplicate_behavior_error_returns_error() {
-        let mut router = Router::new();
-        router.add_tool(DupTool::new(1));
-
-        let result = router.add_tool_with_behavior(DupTool::new(2), DuplicateBehavior::Error);
-        assert!(result.is_err());
-        assert!(result.unwrap_err().message.contains("already exists"));
-    }
-
-    #[test]
-    fn test_duplicate_behavior_warn_keeps_original() {
-        let mut router = Router::new();
-        router.add_tool(DupTool::new(1));
+    /// SSE response body shared between the notification sender and the test.
+    #[derive(Clone, Default)]
+    struct SharedBody(Arc<Mutex<Vec<u8>>>);
 
-        let result = router.add_tool_with_behavior(DupTool::new(2), DuplicateBehavior::Warn);
-        assert!(result.is_ok());
+    impl Write for SharedBody {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
 
-        // Original should be kept
-        let tool = router.get_tool("dup_tool").unwrap();
-        assert_eq!(tool.definition().description, Some("Tool #1".to_string()));
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
     }
 
-    #[test]
-    fn test_duplicate_behavior_replace_replaces() {
-        let mut router = Router::new();
-        router.add_tool(DupTool::new(1));
-
-        let result = router.add_tool_with_behavior(DupTool::new(2), DuplicateBehavior::Replace);
-        assert!(result.is_ok());
-
-        // New one should replace original
-        let tool = router.get_tool("dup_tool").unwrap();
-        assert_eq!(tool.definition().description, Some("Tool #2".to_string()));
+    /// Returns the JSON payloads of all SSE message events, in order.
+    fn sse_messages(body: &SharedBody) -> Vec<serde_json::Value> {
+        let bytes = body.0.lock().unwrap().clone();
+        String::from_utf8(bytes)
+            .unwrap()
+            .lines()
+            .filter_map(|line| line.strip_prefix("data: "))
+            .filter_map(|data| serde_json::from_str(data).ok())
+            .collect()
     }
 
-    #[test]
-    fn test_duplicate_behavior_ignore_keeps_original() {
-        let mut router = Router::new();
-        router.add_tool(DupTool::new(1));
-
-        let result = router.add_tool_with_behavior(DupTool::new(2), DuplicateBehavior::Ignore);
-        assert!(result.is_ok());
+    /// Capabilities of a client that accepts streamed tool content.
+    fn streaming_capabilities() -> ClientCapabilities {
+        let mut experimental = HashMap::new();
+        experimental.insert(
+            fastmcp_protocol::STREAMING_CONTENT_CAPABILITY.to_string(),
+            serde_json::json!({}),
+        );
+        ClientCapabilities {
+            experimental: Some(experimental),
+            ..ClientCapabilities::default()
+        }
+    }
 
-        // Original should be kept
-        let tool = router.get_tool("dup_tool").unwrap();
-        assert_eq!(tool.definition().description, Some("Tool #1".to_string()));
+    fn texts(result: &CallToolResult) -> Vec<&str> {
+        result
+            .content
+            .iter()
+            .map(|content| match content {
+                Content::Text { text } => text.as_str(),
+                other => panic!("expected text content, got {other:?}"),
+            })
+            .collect()
     }
 
-    #[test]
-    fn test_duplicate_behavior_default_is_warn() {
-        assert_eq!(DuplicateBehavior::default(), DuplicateBehavior::Warn);
+    fn call_blob(id: i64) -> JsonRpcRequest {
+        let params = CallToolParams {
+            name: "blob".to_string(),
+            arguments: None,
+            meta: None,
+        };
+        JsonRpcRequest::new(
+            "tools/call",
+            Some(serde_json::to_value(params).unwrap()),
+            id,
+        )
     }
 
     #[test]
-    fn test_no_duplicate_succeeds_for_all_behaviors() {
-        for behavior in [
-            DuplicateBehavior::Error,
-            DuplicateBehavior::Warn,
-            DuplicateBehavior::Replace,
-            DuplicateBehavior::Ignore,
-        ] {
-            let mut router = Router::new();
-            let result = router.add_tool_with_behavior(DupTool::new(1), behavior);
-            assert!(result.is_ok(), "Failed for {:?}", behavior);
+    fn test_streaming_client_receives_chunks_before_response() {
+        let server = Server::new("test", "1.0").tool(ChunkedBlobTool).build();
+        let cx = Cx::for_testing();
+        let mut session = initialized_session_with(streaming_capabilities());
+
+        let body = SharedBody::default();
+        let transport = Arc::new(Mutex::new(SseServerTransport::new(
+            body.clone(),
+            std::iter::empty::<JsonRpcRequest>(),
+            "/messages",
+        )));
+        let notify_transport = transport.clone();
+        let sender: NotificationSender = Arc::new(move |notification| {
+            notify_transport
+                .lock()
+                .unwrap()
+                .send(&Cx::for_testing(), &JsonRpcMessage::Request(notification))
+                .unwrap();
+        });
+
+        let response = server
+            .dispatch_one_with_sender(&cx, &mut session, call_blob(7), &sender)
+            .unwrap();
+        transport
+            .lock()
+            .unwrap()
+            .send(&cx, &JsonRpcMessage::Response(response))
+            .unwrap();
+
+        let messages = sse_messages(&body);
+        assert_eq!(messages.len(), 3);
+        for (sequence, text) in [(0, "part-1"), (1, "part-2")] {
+            let chunk = &messages[sequence];
+            assert_eq!(chunk["method"], "notifications/tools/content");
+            assert_eq!(chunk["params"]["requestId"], 7);
+            assert_eq!(chunk["params"]["sequence"], sequence);
+            assert_eq!(chunk["params"]["content"][0]["text"], text);
         }
+
+        // The final response carries only what the tool returned
+        let last = &messages[2];
+        assert_eq!(last["id"], 7);
+        let content = last["result"]["content"].as_array().unwrap();
+        assert_eq!(content.len(), 1);
+        assert_eq!(content[0]["text"], "done");
     }
 
     #[test]
-    fn test_server_builder_on_duplicate() {
-        // Create server with strict duplicate checking
-        let server = Server::new("test", "1.0")
-            .on_duplicate(DuplicateBehavior::Replace)
-            .tool(DupTool::new(1))
-            .tool(DupTool::new(2)) // Should replace
-            .build();
+    fn test_non_streaming_client_gets_aggregated_response() {
+        let server = Server::new("test", "1.0").tool(ChunkedBlobTool).build();
+        let mut test = TestServer::new(server);
 
-        let tools = server.tools();
-        assert_eq!(tools.len(), 1);
-        // The replaced tool should have id 2
-        assert_eq!(tools[0].description, Some("Tool #2".to_string()));
+        let result = test.call_tool("blob", serde_json::json!({})).unwrap();
+
+        assert!(test.notifications().is_empty());
+        assert_eq!(texts(&result), ["part-1", "part-2", "done"]);
     }
 
     #[test]
-    fn test_server_builder_error_behavior_logs_but_continues() {
-        // Create server with error behavior
+    fn test_cache_hit_after_streamed_call_returns_full_output() {
         let server = Server::new("test", "1.0")
-            .on_duplicate(DuplicateBehavior::Error)
-            .tool(DupTool::new(1))
-            .tool(DupTool::new(2)) // Should fail silently in builder
+            .tool(ChunkedBlobTool)
+            .cache_tool("blob", Duration::from_secs(60))
             .build();
+        let mut test = TestServer::new(server);
+        test.initialize_with(
+            ClientInfo {
+                name: "test-client".to_string(),
+                version: "1.0.0".to_string(),
+            },
+            streaming_capabilities(),
+        )
+        .unwrap();
 
-        let tools = server.tools();
-        // Only first tool should be registered
-        assert_eq!(tools.len(), 1);
-        assert_eq!(tools[0].description, Some("Tool #1".to_string()));
+        let first = test.call_tool("blob", serde_json::json!({})).unwrap();
+        assert_eq!(test.notifications().len(), 2);
+        assert_eq!(texts(&first), ["done"]);
+
+        // The cached result carries the streamed chunks too
+        let second = test.call_tool("blob", serde_json::json!({})).unwrap();
+        assert_eq!(test.notifications().len(), 2);
+        assert_eq!(texts(&second), ["part-1", "part-2", "done"]);
+        assert_eq!(test.server().stats().expect("stats").tool_cache_hits, 1);
     }
-}
 
-// ============================================================================
-// Cross-Component Resource Reading Tests (ctx.read_resource)
-// ============================================================================
+    #[test]
+    fn test_stream_content_requires_attached_stream() {
+        let ctx = McpContext::new(Cx::for_testing(), 1);
+        assert!(!ctx.can_stream_content());
+        assert!(
+            ctx.stream_content(vec![ToolContentItem::text("x")])
+                .is_err()
+        );
+    }
+}
 
-mod ctx_read_resource_tests {
+mod inflight_limit_tests {
     use super::*;
-    use crate::RouterResourceReader;
-    use fastmcp_core::{
-        MAX_RESOURCE_READ_DEPTH, ResourceContentItem, ResourceReadResult, ResourceReader,
-    };
+    use crate::InflightOverflow;
 
-    /// A simple resource that returns static config data.
-    struct ConfigResource {
-        config_json: String,
+    fn call(server: &Server, id: i64) -> JsonRpcResponse {
+        let sender: NotificationSender = Arc::new(|_| {});
+        server
+            .handle_request(
+                &Cx::for_testing(),
+                &mut initialized_session(),
+                greet_request(id, "Ada"),
+                &sender,
+                &create_test_request_sender(),
+            )
+            .expect("response")
     }
 
-    impl ConfigResource {
-        fn new(json: &str) -> Self {
-            Self {
-                config_json: json.to_string(),
-            }
-        }
+    fn assert_busy(response: &JsonRpcResponse) {
+        let error = response.error.as_ref().expect("busy error");
+        assert_eq!(error.code, i32::from(McpErrorCode::RequestCancelled));
+        assert!(error.message.contains("Server busy"), "{}", error.message);
     }
 
-    impl ResourceHandler for ConfigResource {
-        fn definition(&self) -> Resource {
-            Resource {
-                uri: "config://app".to_string(),
-                name: "app_config".to_string(),
-                description: Some("Application configuration".to_string()),
-                mime_type: Some("application/json".to_string()),
-                icon: None,
-                version: None,
-                tags: vec![],
-            }
-        }
+    #[test]
+    fn test_reject_mode_fails_fast_at_cap() {
+        let server = Server::new("test", "1.0")
+            .tool(GreetTool)
+            .max_inflight_requests(1)
+            .build();
 
-        fn template(&self) -> Option<ResourceTemplate> {
-            None
-        }
+        let occupant = server
+            .admit_request(RequestId::Number(100), Cx::for_testing(), true)
+            .expect("first slot");
+        assert_busy(&call(&server, 1));
 
-        fn read(&self, _ctx: &McpContext) -> McpResult<Vec<ResourceContent>> {
-            Ok(vec![ResourceContent {
-                uri: "config://app".to_string(),
-                mime_type: Some("application/json".to_string()),
-                text: Some(self.config_json.clone()),
-                blob: None,
-            }])
-        }
+        // Once the slot frees up, requests go through again.
+        drop(occupant);
+        assert!(call(&server, 2).error.is_none());
     }
 
-    /// A resource that reads another resource.
-    struct NestedResource {
-        inner_uri: String,
-    }
+    #[test]
+    fn test_non_handler_methods_are_not_capped() {
+        let server = Server::new("test", "1.0")
+            .tool(GreetTool)
+            .max_inflight_requests(1)
+            .build();
+        let _occupant = server
+            .admit_request(RequestId::Number(100), Cx::for_testing(), true)
+            .expect("first slot");
 
-    impl NestedResource {
-        fn new(inner_uri: &str) -> Self {
-            Self {
-                inner_uri: inner_uri.to_string(),
-            }
-        }
+        let sender: NotificationSender = Arc::new(|_| {});
+        let response = server
+            .handle_request(
+                &Cx::for_testing(),
+                &mut initialized_session(),
+                fastmcp_protocol::JsonRpcRequest::new("tools/list", None, 3),
+                &sender,
+                &create_test_request_sender(),
+            )
+            .expect("response");
+        assert!(response.error.is_none());
     }
 
-    impl ResourceHandler for NestedResource {
-        fn definition(&self) -> Resource {
-            Resource {
-                uri: "nested://wrapper".to_string(),
-                name: "nested_wrapper".to_string(),
-                description: Some("Wraps another resource".to_string()),
-                mime_type: Some("text/plain".to_string()),
-                icon: None,
-                version: None,
-                tags: vec![],
-            }
-        }
-
-        fn template(&self) -> Option<ResourceTemplate> {
-            None
-        }
-
-        fn read(&self, ctx: &McpContext) -> McpResult<Vec<ResourceContent>> {
-            // Read the inner resource using ctx
-            let inner_uri = self.inner_uri.clone();
-            let inner_result = fastmcp_core::block_on(ctx.read_resource(&inner_uri))?;
+    #[test]
+    fn test_unlimited_requests_do_not_occupy_slots() {
+        let server = Server::new("test", "1.0")
+            .tool(GreetTool)
+            .max_inflight_requests(1)
+            .build();
 
-            let text = inner_result.first_text().unwrap_or("(no content)");
-            Ok(vec![ResourceContent {
-                uri: "nested://wrapper".to_string(),
-                mime_type: Some("text/plain".to_string()),
-                text: Some(format!("Wrapped: {}", text)),
-                blob: None,
-            }])
-        }
+        // In-flight prompts/get and tools/list requests leave the slot free.
+        let _prompt = server
+            .admit_request(RequestId::Number(100), Cx::for_testing(), false)
+            .expect("unlimited request");
+        let _list = server
+            .admit_request(RequestId::Number(101), Cx::for_testing(), false)
+            .expect("unlimited request");
+        assert!(call(&server, 1).error.is_none());
+
+        // A limited request takes the slot; unlimited ones are still admitted.
+        let _tool = server
+            .admit_request(RequestId::Number(102), Cx::for_testing(), true)
+            .expect("first slot");
+        assert_busy(&call(&server, 2));
+        assert!(
+            server
+                .admit_request(RequestId::Number(103), Cx::for_testing(), false)
+                .is_ok()
+        );
     }
 
     #[test]
-    fn test_resource_content_item_constructors() {
-        let text_item = ResourceContentItem::text("file://test", "hello world");
-        assert_eq!(text_item.uri, "file://test");
-        assert_eq!(text_item.as_text(), Some("hello world"));
-        assert!(text_item.is_text());
-        assert!(!text_item.is_blob());
+    fn test_queue_mode_waits_for_free_slot() {
+        let server = Server::new("test", "1.0")
+            .tool(GreetTool)
+            .max_inflight_requests(1)
+            .inflight_overflow(InflightOverflow::Queue(Duration::from_secs(5)))
+            .build();
 
-        let json_item = ResourceContentItem::json("config://app", r#"{"key": "value"}"#);
-        assert_eq!(json_item.mime_type, Some("application/json".to_string()));
+        let occupant = server
+            .admit_request(RequestId::Number(100), Cx::for_testing(), true)
+            .expect("first slot");
 
-        let blob_item = ResourceContentItem::blob("image://test", "image/png", "base64data");
-        assert_eq!(blob_item.as_blob(), Some("base64data"));
-        assert!(blob_item.is_blob());
-        assert!(!blob_item.is_text());
+        thread::scope(|scope| {
+            scope.spawn(move || {
+                thread::sleep(Duration::from_millis(25));
+                drop(occupant);
+            });
+
+            let start = Instant::now();
+            let response = call(&server, 1);
+            assert!(response.error.is_none(), "queued request should succeed");
+            assert!(start.elapsed() >= Duration::from_millis(20));
+        });
     }
 
     #[test]
-    fn test_resource_read_result_constructors() {
-        let result = ResourceReadResult::text("file://test", "content");
-        assert_eq!(result.first_text(), Some("content"));
-        assert_eq!(result.contents.len(), 1);
+    fn test_queue_mode_times_out_when_slot_stays_busy() {
+        let server = Server::new("test", "1.0")
+            .tool(GreetTool)
+            .max_inflight_requests(1)
+            .inflight_overflow(InflightOverflow::Queue(Duration::from_millis(20)))
+            .build();
 
-        let multi = ResourceReadResult::new(vec![
-            ResourceContentItem::text("file://a", "A"),
-            ResourceContentItem::text("file://b", "B"),
-        ]);
-        assert_eq!(multi.contents.len(), 2);
-        assert_eq!(multi.first_text(), Some("A"));
+        let _occupant = server
+            .admit_request(RequestId::Number(100), Cx::for_testing(), true)
+            .expect("first slot");
+        assert_busy(&call(&server, 1));
     }
+}
 
-    #[test]
-    fn test_ctx_read_resource_without_reader_fails() {
-        let cx = Cx::for_testing();
-        let ctx = McpContext::new(cx, 1);
+mod initialize_params_tests {
+    use super::*;
 
-        // Without a resource reader, should fail
-        assert!(!ctx.can_read_resources());
+    fn initialize_error(params: Option<serde_json::Value>) -> fastmcp_protocol::JsonRpcError {
+        let server = Server::new("test", "1.0").build();
+        let mut session = create_test_session();
+        let response = server
+            .dispatch_one(
+                &Cx::for_testing(),
+                &mut session,
+                fastmcp_protocol::JsonRpcRequest::new("initialize", params, 1),
+            )
+            .expect("response");
+        assert!(!session.is_initialized());
+        response.error.expect("initialize should fail")
+    }
 
-        let result = fastmcp_core::block_on(ctx.read_resource("config://app"));
-        assert!(result.is_err());
-        let err = result.unwrap_err();
-        assert!(err.message.contains("no router"));
+    fn assert_supported_versions(error: &fastmcp_protocol::JsonRpcError) {
+        assert_eq!(error.code, i32::from(McpErrorCode::InvalidParams));
+        let data = error.data.as_ref().expect("error data");
+        assert_eq!(
+            data["supportedVersions"],
+            serde_json::json!([fastmcp_protocol::PROTOCOL_VERSION])
+        );
     }
 
     #[test]
-    fn test_router_resource_reader_reads_resource() {
-        let mut router = Router::new();
-        router.add_resource(ConfigResource::new(r#"{"db": "postgres"}"#));
-
-        let router_arc = Arc::new(router);
-        let reader = RouterResourceReader::new(router_arc, SessionState::new());
-
-        let cx = Cx::for_testing();
-        let result = fastmcp_core::block_on(reader.read_resource(&cx, "config://app", 0));
-
-        assert!(result.is_ok());
-        let read_result = result.unwrap();
-        assert_eq!(read_result.first_text(), Some(r#"{"db": "postgres"}"#));
+    fn test_missing_protocol_version() {
+        let error = initialize_error(Some(serde_json::json!({
+            "capabilities": {},
+            "clientInfo": {"name": "c", "version": "1"}
+        })));
+        assert!(
+            error.message.contains("protocol_version is required"),
+            "{}",
+            error.message
+        );
+        assert_supported_versions(&error);
     }
 
     #[test]
-    fn test_router_resource_reader_not_found() {
-        let router = Router::new(); // No resources
-        let router_arc = Arc::new(router);
-        let reader = RouterResourceReader::new(router_arc, SessionState::new());
+    fn test_malformed_protocol_version() {
+        let error = initialize_error(Some(serde_json::json!({
+            "protocolVersion": 20241105,
+            "capabilities": {},
+            "clientInfo": {"name": "c", "version": "1"}
+        })));
+        assert!(
+            error.message.contains("must be a string"),
+            "{}",
+            error.message
+        );
+        assert_supported_versions(&error);
+    }
 
-        let cx = Cx::for_testing();
-        let result = fastmcp_core::block_on(reader.read_resource(&cx, "config://missing", 0));
+    #[test]
+    fn test_missing_client_info() {
+        let error = initialize_error(Some(serde_json::json!({
+            "protocolVersion": "2024-11-05",
+            "capabilities": {}
+        })));
+        assert!(
+            error.message.contains("clientInfo is required"),
+            "{}",
+            error.message
+        );
+        assert_supported_versions(&error);
+    }
 
-        assert!(result.is_err());
-        let err = result.unwrap_err();
-        assert!(err.message.contains("not found"));
+    #[test]
+    fn test_missing_capabilities() {
+        let error = initialize_error(Some(serde_json::json!({
+            "protocolVersion": "2024-11-05",
+            "clientInfo": {"name": "c", "version": "1"}
+        })));
+        assert!(
+            error.message.contains("capabilities is required"),
+            "{}",
+            error.message
+        );
+        assert_supported_versions(&error);
     }
 
     #[test]
-    fn test_router_resource_reader_depth_limit() {
-        let router = Router::new();
-        let router_arc = Arc::new(router);
-        let reader = RouterResourceReader::new(router_arc, SessionState::new());
+    fn test_missing_params() {
+        let error = initialize_error(None);
+        assert!(
+            error.message.contains("protocolVersion"),
+            "{}",
+            error.message
+        );
+        assert_supported_versions(&error);
+    }
+}
 
-        let cx = Cx::for_testing();
-        // Call with depth at limit
-        let result = fastmcp_core::block_on(reader.read_resource(
-            &cx,
-            "any://uri",
-            MAX_RESOURCE_READ_DEPTH + 1,
-        ));
+mod tool_cache_tests {
+    use super::*;
+    use fastmcp_protocol::CallToolResult;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
-        assert!(result.is_err());
-        let err = result.unwrap_err();
-        assert!(err.message.contains("depth"));
+    /// A tool that counts invocations and fails when asked to.
+    struct CountingTool {
+        calls: Arc<AtomicUsize>,
     }
 
-    #[test]
-    fn test_ctx_with_resource_reader() {
-        let mut router = Router::new();
-        router.add_resource(ConfigResource::new(r#"{"name": "test"}"#));
+    impl ToolHandler for CountingTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: "fetch".to_string(),
+                description: None,
+                input_schema: serde_json::json!({"type": "object"}),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+                annotations: None,
+            }
+        }
 
-        let router_arc = Arc::new(router);
-        let reader: Arc<dyn ResourceReader> =
-            Arc::new(RouterResourceReader::new(router_arc, SessionState::new()));
+        fn call(&self, _ctx: &McpContext, arguments: serde_json::Value) -> McpResult<Vec<Content>> {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            if arguments.get("fail").is_some() {
+                return Err(McpError::tool_error("upstream unavailable"));
+            }
+            Ok(vec![Content::Text {
+                text: format!("call #{n}"),
+            }])
+        }
+    }
 
-        let cx = Cx::for_testing();
-        let ctx = McpContext::new(cx, 1).with_resource_reader(reader);
+    fn server_with_cache(ttl: Duration) -> (Server, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let server = Server::new("test", "1.0")
+            .tool(CountingTool {
+                calls: calls.clone(),
+            })
+            .cache_tool("fetch", ttl)
+            .build();
+        (server, calls)
+    }
 
-        assert!(ctx.can_read_resources());
-        assert_eq!(ctx.resource_read_depth(), 0);
+    fn call(server: &Server, arguments: serde_json::Value) -> CallToolResult {
+        let params = CallToolParams {
+            name: "fetch".to_string(),
+            arguments: Some(arguments),
+            meta: None,
+        };
+        server
+            .router
+            .handle_tools_call(
+                &Cx::for_testing(),
+                1,
+                None,
+                params,
+                &Budget::INFINITE,
+                SessionState::new(),
+                None,
+                None,
+                false,
+            )
+            .expect("tools/call")
+    }
 
-        // Read the resource
-        let result = fastmcp_core::block_on(ctx.read_resource("config://app"));
-        assert!(result.is_ok());
-        let read_result = result.unwrap();
-        assert!(read_result.first_text().unwrap().contains("test"));
+    fn text(result: &CallToolResult) -> &str {
+        match &result.content[0] {
+            Content::Text { text } => text,
+            other => panic!("expected text, got {other:?}"),
+        }
     }
 
     #[test]
-    fn test_ctx_read_resource_text() {
-        let mut router = Router::new();
-        router.add_resource(ConfigResource::new(r#"{"value": 42}"#));
+    fn test_cache_hit_skips_handler_regardless_of_key_order() {
+        let (server, calls) = server_with_cache(Duration::from_secs(60));
 
-        let router_arc = Arc::new(router);
-        let reader: Arc<dyn ResourceReader> =
-            Arc::new(RouterResourceReader::new(router_arc, SessionState::new()));
+        let first = call(&server, serde_json::json!({"a": 1, "b": {"x": 1, "y": 2}}));
+        let second = call(&server, serde_json::json!({"b": {"y": 2, "x": 1}, "a": 1}));
 
-        let cx = Cx::for_testing();
-        let ctx = McpContext::new(cx, 1).with_resource_reader(reader);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(text(&first), "call #1");
+        assert_eq!(text(&second), "call #1");
+        assert!(first.meta.is_none());
+        assert_eq!(second.meta, Some(serde_json::json!({"cached": true})));
+        assert_eq!(server.stats().expect("stats").tool_cache_hits, 1);
 
-        let result = fastmcp_core::block_on(ctx.read_resource_text("config://app"));
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), r#"{"value": 42}"#);
+        // Different arguments are a different entry
+        let third = call(&server, serde_json::json!({"a": 2}));
+        assert_eq!(text(&third), "call #2");
     }
 
     #[test]
-    fn test_ctx_read_resource_json() {
-        let mut router = Router::new();
-        router.add_resource(ConfigResource::new(
-            r#"{"database": "postgres", "port": 5432}"#,
-        ));
+    fn test_cache_misses_after_ttl() {
+        let (server, calls) = server_with_cache(Duration::from_millis(20));
 
-        let router_arc = Arc::new(router);
-        let reader: Arc<dyn ResourceReader> =
-            Arc::new(RouterResourceReader::new(router_arc, SessionState::new()));
-
-        let cx = Cx::for_testing();
-        let ctx = McpContext::new(cx, 1).with_resource_reader(reader);
-
-        #[derive(Debug, serde::Deserialize)]
-        struct DbConfig {
-            database: String,
-            port: u16,
-        }
+        call(&server, serde_json::json!({"a": 1}));
+        thread::sleep(Duration::from_millis(40));
+        let result = call(&server, serde_json::json!({"a": 1}));
 
-        let result: McpResult<DbConfig> =
-            fastmcp_core::block_on(ctx.read_resource_json("config://app"));
-        assert!(result.is_ok());
-        let config = result.unwrap();
-        assert_eq!(config.database, "postgres");
-        assert_eq!(config.port, 5432);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(text(&result), "call #2");
     }
 
     #[test]
-    fn test_ctx_read_resource_json_parse_error() {
-        let mut router = Router::new();
-        router.add_resource(ConfigResource::new("not valid json"));
-
-        let router_arc = Arc::new(router);
-        let reader: Arc<dyn ResourceReader> =
-            Arc::new(RouterResourceReader::new(router_arc, SessionState::new()));
+    fn test_error_results_are_not_cached() {
+        let (server, calls) = server_with_cache(Duration::from_secs(60));
 
-        let cx = Cx::for_testing();
-        let ctx = McpContext::new(cx, 1).with_resource_reader(reader);
+        let first = call(&server, serde_json::json!({"fail": true}));
+        let second = call(&server, serde_json::json!({"fail": true}));
 
-        #[derive(Debug, serde::Deserialize)]
-        struct Config {
-            value: i32,
-        }
+        assert!(first.is_error);
+        assert!(second.is_error);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(server.stats().expect("stats").tool_cache_hits, 0);
+    }
+}
 
-        let result: McpResult<Config> =
-            fastmcp_core::block_on(ctx.read_resource_json("config://app"));
-        assert!(result.is_err());
-        let err = result.unwrap_err();
-        assert!(err.message.contains("JSON"));
+mod custom_method_tests {
+    use super::*;
+    use crate::testing::TestServer;
+
+    fn server() -> Server {
+        Server::new("test", "1.0")
+            .experimental_capability("x-search", serde_json::json!({"version": 1}))
+            .custom_method("x-search/query", |ctx, params| {
+                Ok(serde_json::json!({
+                    "method": ctx.method(),
+                    "query": params.and_then(|p| p.get("q").cloned()),
+                }))
+            })
+            .build()
     }
 
     #[test]
-    fn test_resource_read_depth_increments() {
-        let cx = Cx::for_testing();
-        let ctx = McpContext::new(cx, 1).with_resource_read_depth(5);
-
-        assert_eq!(ctx.resource_read_depth(), 5);
+    fn test_experimental_capability_in_initialize() {
+        let mut test = TestServer::new(server());
+        let result = test.initialize().expect("initialize");
+        let experimental = result.capabilities.experimental.expect("experimental");
+        assert_eq!(experimental["x-search"], serde_json::json!({"version": 1}));
     }
 
     #[test]
-    fn test_max_resource_read_depth_constant() {
-        // Verify the constant is reasonable
-        assert_eq!(MAX_RESOURCE_READ_DEPTH, 10);
-    }
+    fn test_custom_method_dispatches_to_handler() {
+        let mut test = TestServer::new(server());
+        test.initialize().expect("initialize");
 
-    /// A resource that reads session state and then reads another resource.
-    struct SessionStateResource;
+        let response = test
+            .request("x-search/query", Some(serde_json::json!({"q": "rust"})))
+            .expect("response");
 
-    impl ResourceHandler for SessionStateResource {
-        fn definition(&self) -> Resource {
-            Resource {
-                uri: "session://state".to_string(),
-                name: "session_state".to_string(),
-                description: Some("Returns session state value".to_string()),
-                mime_type: Some("text/plain".to_string()),
-                icon: None,
-                version: None,
-                tags: vec![],
-            }
-        }
+        assert!(response.error.is_none(), "{:?}", response.error);
+        assert_eq!(
+            response.result.expect("result"),
+            serde_json::json!({"method": "x-search/query", "query": "rust"})
+        );
+    }
 
-        fn template(&self) -> Option<ResourceTemplate> {
-            None
-        }
+    #[test]
+    fn test_unregistered_method_still_not_found() {
+        let mut test = TestServer::new(server());
+        test.initialize().expect("initialize");
 
-        fn read(&self, ctx: &McpContext) -> McpResult<Vec<ResourceContent>> {
-            let value: Option<String> = ctx.get_state("test_key");
-            Ok(vec![ResourceContent {
-                uri: "session://state".to_string(),
-                mime_type: Some("text/plain".to_string()),
-                text: Some(value.unwrap_or_else(|| "no_value".to_string())),
-                blob: None,
-            }])
-        }
+        let response = test.request("x-search/other", None).expect("response");
+
+        let error = response.error.expect("error");
+        assert_eq!(error.code, i32::from(McpErrorCode::MethodNotFound));
     }
+}
 
-    /// A resource that sets session state and reads another resource to verify propagation.
-    struct NestedSessionResource;
+mod registry_document_tests {
+    use super::*;
 
-    impl ResourceHandler for NestedSessionResource {
-        fn definition(&self) -> Resource {
-            Resource {
-                uri: "nested://session".to_string(),
-                name: "nested_session".to_string(),
-                description: Some("Sets state then reads another resource".to_string()),
-                mime_type: Some("text/plain".to_string()),
-                icon: None,
-                version: None,
-                tags: vec![],
-            }
-        }
+    #[test]
+    fn test_registry_document_includes_every_registered_item() {
+        let server = Server::new("registry-server", "2.0.0")
+            .tool(GreetTool)
+            .resource(StaticResource {
+                uri: "resource://test".to_string(),
+                content: "Test content".to_string(),
+            })
+            .resource(TemplateResource)
+            .prompt(GreetingPrompt)
+            .build();
 
-        fn template(&self) -> Option<ResourceTemplate> {
-            None
-        }
+        let document = server.registry_document();
 
-        fn read(&self, ctx: &McpContext) -> McpResult<Vec<ResourceContent>> {
-            // Set a value in session state
-            ctx.set_state("test_key", "propagated_value");
+        assert_eq!(document.server.name, "registry-server");
+        assert_eq!(document.server.version, "2.0.0");
+        assert!(document.capabilities.tools.is_some());
 
-            // Read another resource - it should see our session state
-            let inner_result = fastmcp_core::block_on(ctx.read_resource("session://state"))?;
-            let text = inner_result.first_text().unwrap_or("(no content)");
+        assert_eq!(document.tools.len(), 1);
+        assert_eq!(document.tools[0].name, "greet");
+        assert_eq!(
+            document.tools[0].input_schema,
+            GreetTool.definition().input_schema
+        );
 
-            Ok(vec![ResourceContent {
-                uri: "nested://session".to_string(),
-                mime_type: Some("text/plain".to_string()),
-                text: Some(format!("Inner saw: {}", text)),
-                blob: None,
-            }])
-        }
+        assert!(
+            document
+                .resources
+                .iter()
+                .any(|r| r.uri == "resource://test")
+        );
+        assert!(
+            document
+                .resource_templates
+                .iter()
+                .any(|t| t.uri_template == "resource://{id}")
+        );
+
+        assert_eq!(document.prompts.len(), 1);
+        assert_eq!(document.prompts[0].name, "greeting");
+        assert_eq!(document.prompts[0].arguments[0].name, "name");
+        assert!(document.prompts[0].arguments[0].required);
     }
 
     #[test]
-    fn test_session_state_propagates_through_nested_reads() {
-        let mut router = Router::new();
-        router.add_resource(SessionStateResource);
-        router.add_resource(NestedSessionResource);
-
-        let router_arc = Arc::new(router);
-        let session_state = SessionState::new();
-        let reader: Arc<dyn ResourceReader> =
-            Arc::new(RouterResourceReader::new(router_arc, session_state.clone()));
-
-        let cx = Cx::for_testing();
-        let ctx = McpContext::with_state(cx, 1, session_state).with_resource_reader(reader);
+    fn test_registry_document_serializes_schemas() {
+        let server = Server::new("registry-server", "2.0.0")
+            .tool(GreetTool)
+            .build();
 
-        // Read the nested resource - it sets state then reads another resource
-        let result = fastmcp_core::block_on(ctx.read_resource("nested://session"));
-        assert!(result.is_ok());
-        let read_result = result.unwrap();
+        let json = serde_json::to_value(server.registry_document()).expect("serialize");
 
-        // The inner resource should have seen the propagated value
-        let text = read_result.first_text().unwrap();
-        assert!(
-            text.contains("propagated_value"),
-            "Expected session state to propagate, got: {}",
-            text
+        assert_eq!(json["server"]["name"], "registry-server");
+        assert_eq!(
+            json["tools"][0]["inputSchema"]["required"],
+            serde_json::json!(["name"])
         );
+        assert!(json["resourceTemplates"].is_array());
+        assert!(json["prompts"].is_array());
     }
 }
 
-// ============================================================================
-// Cross-Component Tool Calling Tests (ctx.call_tool)
-// ============================================================================
-
-mod ctx_call_tool_tests {
+mod subject_rate_limit_tests {
     use super::*;
-    use crate::RouterToolCaller;
-    use fastmcp_core::{MAX_TOOL_CALL_DEPTH, ToolCallResult, ToolCaller, ToolContentItem};
 
-    /// A simple tool that adds two numbers.
-    struct AddTool;
+    fn rate_limited_server() -> Server {
+        let verifier = StaticTokenVerifier::new([
+            ("alice-token", AuthContext::with_subject("alice")),
+            ("bob-token", AuthContext::with_subject("bob")),
+        ]);
+        Server::new("test-server", "1.0.0")
+            .tool(GreetTool)
+            .auth_provider(TokenAuthProvider::new(verifier))
+            .rate_limit(0.001, 2)
+            .build()
+    }
 
-    impl ToolHandler for AddTool {
-        fn definition(&self) -> Tool {
-            Tool {
-                name: "add".to_string(),
-                description: Some("Adds two numbers".to_string()),
-                input_schema: serde_json::json!({
-                    "type": "object",
-                    "properties": {
-                        "a": {"type": "integer"},
-                        "b": {"type": "integer"}
-                    },
-                    "required": ["a", "b"]
-                }),
-                output_schema: None,
-                icon: None,
-                version: None,
-                tags: vec![],
-                annotations: None,
-            }
-        }
+    fn list_tools(server: &Server, session: &mut Session, token: &str, id: i64) -> JsonRpcResponse {
+        let sender: NotificationSender = Arc::new(|_| {});
+        let request = JsonRpcRequest::new(
+            "tools/list",
+            Some(serde_json::json!({ "auth": format!("Bearer {token}") })),
+            id,
+        );
+        server
+            .handle_request(
+                &Cx::for_testing(),
+                session,
+                request,
+                &sender,
+                &create_test_request_sender(),
+            )
+            .expect("response")
+    }
 
-        fn call(&self, _ctx: &McpContext, args: serde_json::Value) -> McpResult<Vec<Content>> {
-            let a = args["a"].as_i64().unwrap_or(0);
-            let b = args["b"].as_i64().unwrap_or(0);
-            let sum = a + b;
-            Ok(vec![Content::Text {
-                text: sum.to_string(),
-            }])
+    #[test]
+    fn test_subject_over_limit_is_throttled_with_retry_hint() {
+        let server = rate_limited_server();
+        let mut session = initialized_session();
+
+        for id in 1..=2 {
+            let response = list_tools(&server, &mut session, "alice-token", id);
+            assert!(response.error.is_none(), "{:?}", response.error);
+        }
+
+        let response = list_tools(&server, &mut session, "alice-token", 3);
+        let error = response.error.expect("rate limit error");
+        assert_eq!(error.code, i32::from(McpErrorCode::RequestCancelled));
+        assert!(error.message.contains("Rate limited"), "{}", error.message);
+        let retry_after = error.data.expect("error data")["retryAfter"]
+            .as_f64()
+            .expect("retryAfter");
+        assert!(retry_after > 0.0);
+    }
+
+    #[test]
+    fn test_other_subject_is_unaffected() {
+        let server = rate_limited_server();
+        let mut session = initialized_session();
+
+        for id in 1..=3 {
+            list_tools(&server, &mut session, "alice-token", id);
         }
+
+        let response = list_tools(&server, &mut session, "bob-token", 4);
+        assert!(response.error.is_none(), "{:?}", response.error);
     }
+}
 
-    /// A tool that returns JSON.
-    struct JsonTool;
+mod sampling_capability_tests {
+    use super::*;
+    use fastmcp_core::{McpOutcome, Outcome};
+    use fastmcp_protocol::{CallToolResult, JsonRpcMessage, SamplingCapability};
 
-    impl ToolHandler for JsonTool {
+    /// A tool that asks the client to sample a completion.
+    struct HaikuTool;
+
+    impl ToolHandler for HaikuTool {
         fn definition(&self) -> Tool {
             Tool {
-                name: "compute".to_string(),
-                description: Some("Returns a JSON result".to_string()),
-                input_schema: serde_json::json!({
-                    "type": "object",
-                    "properties": {}
-                }),
+                name: "haiku".to_string(),
+                description: None,
+                input_schema: serde_json::json!({"type": "object"}),
                 output_schema: None,
                 icon: None,
                 version: None,
@@ -4934,20 +9396,114 @@ mod ctx_call_tool_tests {
         }
 
         fn call(&self, _ctx: &McpContext, _args: serde_json::Value) -> McpResult<Vec<Content>> {
-            Ok(vec![Content::Text {
-                text: r#"{"value": 42}"#.to_string(),
-            }])
+            unreachable!("haiku is only called asynchronously")
+        }
+
+        fn call_async<'a>(
+            &'a self,
+            ctx: &'a McpContext,
+            _args: serde_json::Value,
+        ) -> crate::handler::BoxFuture<'a, McpOutcome<Vec<Content>>> {
+            Box::pin(async move {
+                match ctx.sample("Write a haiku", 50).await {
+                    Ok(response) => Outcome::Ok(vec![Content::Text {
+                        text: response.text,
+                    }]),
+                    Err(e) => Outcome::Err(e),
+                }
+            })
         }
     }
 
-    /// A tool that always fails.
-    struct FailingTool;
+    /// A request sender whose client answers every request with a completion.
+    fn answering_request_sender() -> RequestSender {
+        let pending = Arc::new(PendingRequests::new());
+        let responder = Arc::clone(&pending);
+        let send_fn: TransportSendFn = Arc::new(move |message| {
+            if let JsonRpcMessage::Request(request) = message {
+                let id = request.id.clone().expect("request id");
+                responder.route_response(&JsonRpcResponse::success(
+                    id,
+                    serde_json::json!({
+                        "role": "assistant",
+                        "content": {"type": "text", "text": "autumn moonlight"},
+                        "model": "test-model",
+                        "stopReason": "endTurn"
+                    }),
+                ));
+            }
+            Ok(())
+        });
+        RequestSender::new(pending, send_fn)
+    }
 
-    impl ToolHandler for FailingTool {
+    fn call_haiku(capabilities: ClientCapabilities) -> CallToolResult {
+        let server = Server::new("test-server", "1.0.0").tool(HaikuTool).build();
+        let mut session = initialized_session_with(capabilities);
+        let sender: NotificationSender = Arc::new(|_| {});
+        let request = JsonRpcRequest::new(
+            "tools/call",
+            Some(serde_json::json!({"name": "haiku", "arguments": {}})),
+            1,
+        );
+        let response = server
+            .handle_request(
+                &Cx::for_testing(),
+                &mut session,
+                request,
+                &sender,
+                &answering_request_sender(),
+            )
+            .expect("response");
+        serde_json::from_value(response.result.expect("result")).expect("tool result")
+    }
+
+    fn text(result: &CallToolResult) -> &str {
+        match &result.content[0] {
+            Content::Text { text } => text,
+            other => panic!("expected text, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_sampling_fails_fast_without_client_capability() {
+        let result = call_haiku(ClientCapabilities::default());
+
+        assert!(result.is_error);
+        assert!(
+            text(&result).contains("Sampling not available"),
+            "{}",
+            text(&result)
+        );
+    }
+
+    #[test]
+    fn test_sampling_reaches_client_that_advertised_it() {
+        let result = call_haiku(ClientCapabilities {
+            sampling: Some(SamplingCapability {}),
+            ..ClientCapabilities::default()
+        });
+
+        assert!(!result.is_error, "{}", text(&result));
+        assert_eq!(text(&result), "autumn moonlight");
+    }
+}
+
+mod elicitation_tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use fastmcp_core::{ElicitationAction, McpOutcome, Outcome};
+    use fastmcp_protocol::{CallToolResult, ElicitationCapability, JsonRpcMessage};
+
+    /// A tool that asks the user for a name and reports the outcome.
+    struct AskNameTool;
+
+    impl ToolHandler for AskNameTool {
         fn definition(&self) -> Tool {
             Tool {
-                name: "failing".to_string(),
-                description: Some("Always fails".to_string()),
+                name: "ask_name".to_string(),
+                description: None,
                 input_schema: serde_json::json!({"type": "object"}),
                 output_schema: None,
                 icon: None,
@@ -4958,254 +9514,385 @@ mod ctx_call_tool_tests {
         }
 
         fn call(&self, _ctx: &McpContext, _args: serde_json::Value) -> McpResult<Vec<Content>> {
-            Err(McpError::new(
-                McpErrorCode::InternalError,
-                "Something went wrong",
-            ))
+            unreachable!("ask_name is only called asynchronously")
         }
-    }
 
-    #[test]
-    fn test_tool_content_item_constructors() {
-        let text_item = ToolContentItem::text("hello world");
-        assert_eq!(text_item.as_text(), Some("hello world"));
-        assert!(text_item.is_text());
+        fn call_async<'a>(
+            &'a self,
+            ctx: &'a McpContext,
+            _args: serde_json::Value,
+        ) -> crate::handler::BoxFuture<'a, McpOutcome<Vec<Content>>> {
+            Box::pin(async move {
+                let schema = serde_json::json!({
+                    "type": "object",
+                    "properties": {"name": {"type": "string"}},
+                    "required": ["name"]
+                });
+                let response = match ctx.elicit("What is your name?", schema).await {
+                    Ok(response) => response,
+                    Err(e) => return Outcome::Err(e),
+                };
+                let text = match response.action {
+                    ElicitationAction::Accept => {
+                        format!("hello {}", response.get_string("name").unwrap_or("?"))
+                    }
+                    ElicitationAction::Decline => "declined".to_string(),
+                    ElicitationAction::Cancel => "cancelled".to_string(),
+                };
+                Outcome::Ok(vec![Content::Text { text }])
+            })
+        }
     }
 
-    #[test]
-    fn test_tool_call_result_constructors() {
-        let success = ToolCallResult::text("result");
-        assert!(!success.is_error);
-        assert_eq!(success.first_text(), Some("result"));
+    /// Calls `ask_name` against a mock client that answers with `answer`.
+    ///
+    /// Returns the tool result and the methods the server sent to the client.
+    fn call_ask_name(answer: serde_json::Value) -> (CallToolResult, Vec<String>) {
+        let pending = Arc::new(PendingRequests::new());
+        let responder = Arc::clone(&pending);
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let recorder = Arc::clone(&sent);
+        let send_fn: TransportSendFn = Arc::new(move |message| {
+            if let JsonRpcMessage::Request(request) = message {
+                recorder.lock().unwrap().push(request.method.clone());
+                let id = request.id.clone().expect("request id");
+                responder.route_response(&JsonRpcResponse::success(id, answer.clone()));
+            }
+            Ok(())
+        });
 
-        let error = ToolCallResult::error("failed");
-        assert!(error.is_error);
-        assert_eq!(error.first_text(), Some("failed"));
+        let server = Server::new("test-server", "1.0.0")
+            .tool(AskNameTool)
+            .build();
+        let mut session = initialized_session_with(ClientCapabilities {
+            elicitation: Some(ElicitationCapability::default()),
+            ..ClientCapabilities::default()
+        });
+        let sender: NotificationSender = Arc::new(|_| {});
+        let request = JsonRpcRequest::new(
+            "tools/call",
+            Some(serde_json::json!({"name": "ask_name", "arguments": {}})),
+            1,
+        );
+        let response = server
+            .handle_request(
+                &Cx::for_testing(),
+                &mut session,
+                request,
+                &sender,
+                &RequestSender::new(pending, send_fn),
+            )
+            .expect("response");
+        let result = serde_json::from_value(response.result.expect("result")).expect("tool result");
+        let sent = sent.lock().unwrap().clone();
+        (result, sent)
+    }
 
-        let multi =
-            ToolCallResult::success(vec![ToolContentItem::text("a"), ToolContentItem::text("b")]);
-        assert_eq!(multi.content.len(), 2);
-        assert_eq!(multi.first_text(), Some("a"));
+    fn text(result: &CallToolResult) -> &str {
+        match &result.content[0] {
+            Content::Text { text } => text,
+            other => panic!("expected text, got {other:?}"),
+        }
     }
 
     #[test]
-    fn test_ctx_call_tool_without_caller_fails() {
-        let cx = Cx::for_testing();
-        let ctx = McpContext::new(cx, 1);
-
-        // Without a tool caller, should fail
-        assert!(!ctx.can_call_tools());
+    fn test_elicit_accept_returns_content() {
+        let (result, sent) = call_ask_name(serde_json::json!({
+            "action": "accept",
+            "content": {"name": "Ada"}
+        }));
 
-        let result =
-            fastmcp_core::block_on(ctx.call_tool("add", serde_json::json!({"a": 1, "b": 2})));
-        assert!(result.is_err());
-        let err = result.unwrap_err();
-        assert!(err.message.contains("no router"));
+        assert!(!result.is_error, "{}", text(&result));
+        assert_eq!(text(&result), "hello Ada");
+        assert_eq!(sent, ["elicitation/create"]);
     }
 
     #[test]
-    fn test_router_tool_caller_calls_tool() {
-        let mut router = Router::new();
-        router.add_tool(AddTool);
+    fn test_elicit_decline_is_not_an_error() {
+        let (result, _) = call_ask_name(serde_json::json!({"action": "decline"}));
 
-        let router_arc = Arc::new(router);
-        let caller = RouterToolCaller::new(router_arc, SessionState::new());
+        assert!(!result.is_error, "{}", text(&result));
+        assert_eq!(text(&result), "declined");
+    }
+}
 
-        let cx = Cx::for_testing();
-        let result = fastmcp_core::block_on(caller.call_tool(
-            &cx,
-            "add",
-            serde_json::json!({"a": 5, "b": 3}),
-            0,
-        ));
+mod budget_remaining_tests {
+    use super::*;
+    use fastmcp_protocol::CallToolResult;
 
-        assert!(result.is_ok());
-        let call_result = result.unwrap();
-        assert!(!call_result.is_error);
-        assert_eq!(call_result.first_text(), Some("8"));
-    }
+    /// A tool that only runs its expensive path when time allows.
+    struct AdaptiveTool;
 
-    #[test]
-    fn test_router_tool_caller_not_found() {
-        let router = Router::new(); // No tools
-        let router_arc = Arc::new(router);
-        let caller = RouterToolCaller::new(router_arc, SessionState::new());
+    impl ToolHandler for AdaptiveTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: "adaptive".to_string(),
+                description: None,
+                input_schema: serde_json::json!({"type": "object"}),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+                annotations: None,
+            }
+        }
 
-        let cx = Cx::for_testing();
-        let result =
-            fastmcp_core::block_on(caller.call_tool(&cx, "nonexistent", serde_json::json!({}), 0));
+        fn call(&self, ctx: &McpContext, _args: serde_json::Value) -> McpResult<Vec<Content>> {
+            let thorough = ctx
+                .budget_remaining()
+                .deadline
+                .is_none_or(|left| left > Duration::from_secs(5));
+            let text = if thorough { "thorough" } else { "fast" };
+            Ok(vec![Content::Text {
+                text: text.to_string(),
+            }])
+        }
+    }
 
-        assert!(result.is_err());
-        let err = result.unwrap_err();
-        assert!(err.message.contains("not found"));
+    fn call_adaptive(request_timeout_secs: u64) -> String {
+        let server = Server::new("test-server", "1.0.0")
+            .tool(AdaptiveTool)
+            .request_timeout(request_timeout_secs)
+            .build();
+        let mut session = initialized_session();
+        let sender: NotificationSender = Arc::new(|_| {});
+        let request = JsonRpcRequest::new(
+            "tools/call",
+            Some(serde_json::json!({"name": "adaptive", "arguments": {}})),
+            1,
+        );
+        let response = server
+            .handle_request(
+                &Cx::for_testing(),
+                &mut session,
+                request,
+                &sender,
+                &create_test_request_sender(),
+            )
+            .expect("response");
+        let result: CallToolResult =
+            serde_json::from_value(response.result.expect("result")).expect("tool result");
+        match &result.content[0] {
+            Content::Text { text } => text.clone(),
+            other => panic!("expected text, got {other:?}"),
+        }
     }
 
     #[test]
-    fn test_router_tool_caller_depth_limit() {
-        let router = Router::new();
-        let router_arc = Arc::new(router);
-        let caller = RouterToolCaller::new(router_arc, SessionState::new());
-
-        let cx = Cx::for_testing();
-        // Call with depth at limit
-        let result = fastmcp_core::block_on(caller.call_tool(
-            &cx,
-            "any_tool",
-            serde_json::json!({}),
-            MAX_TOOL_CALL_DEPTH + 1,
-        ));
-
-        assert!(result.is_err());
-        let err = result.unwrap_err();
-        assert!(err.message.contains("depth"));
+    fn test_tool_takes_fast_path_near_deadline() {
+        assert_eq!(call_adaptive(1), "fast");
     }
 
     #[test]
-    fn test_ctx_with_tool_caller() {
-        let mut router = Router::new();
-        router.add_tool(AddTool);
+    fn test_tool_takes_thorough_path_without_deadline() {
+        assert_eq!(call_adaptive(0), "thorough");
+    }
+}
 
-        let router_arc = Arc::new(router);
-        let caller: Arc<dyn ToolCaller> =
-            Arc::new(RouterToolCaller::new(router_arc, SessionState::new()));
+#[cfg(feature = "arbitrary-precision")]
+mod arbitrary_precision_tests {
+    use super::*;
 
-        let cx = Cx::for_testing();
-        let ctx = McpContext::new(cx, 1).with_tool_caller(caller);
+    /// A tool that echoes its `value` argument back as JSON text.
+    struct EchoNumberTool;
 
-        assert!(ctx.can_call_tools());
-        assert_eq!(ctx.tool_call_depth(), 0);
+    impl ToolHandler for EchoNumberTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: "echo_number".to_string(),
+                description: None,
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {"value": {"type": "number"}},
+                    "required": ["value"]
+                }),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+                annotations: None,
+            }
+        }
 
-        // Call the tool
-        let result =
-            fastmcp_core::block_on(ctx.call_tool("add", serde_json::json!({"a": 10, "b": 5})));
-        assert!(result.is_ok());
-        let call_result = result.unwrap();
-        assert_eq!(call_result.first_text(), Some("15"));
+        fn call(&self, _ctx: &McpContext, arguments: serde_json::Value) -> McpResult<Vec<Content>> {
+            Ok(vec![Content::Text {
+                text: arguments["value"].to_string(),
+            }])
+        }
     }
 
-    #[test]
-    fn test_ctx_call_tool_text() {
-        let mut router = Router::new();
-        router.add_tool(AddTool);
-
-        let router_arc = Arc::new(router);
-        let caller: Arc<dyn ToolCaller> =
-            Arc::new(RouterToolCaller::new(router_arc, SessionState::new()));
-
-        let cx = Cx::for_testing();
-        let ctx = McpContext::new(cx, 1).with_tool_caller(caller);
+    /// Sends `number` (as raw JSON text) through the echo tool and returns
+    /// the serialized response.
+    fn echo(number: &str) -> String {
+        let server = Server::new("test-server", "1.0.0")
+            .tool(EchoNumberTool)
+            .arbitrary_precision(true)
+            .build();
+        let mut session = initialized_session();
+        let raw = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{{"name":"echo_number","arguments":{{"value":{number}}}}}}}"#
+        );
+        let request: JsonRpcRequest = serde_json::from_str(&raw).expect("parse request");
+        let sender: NotificationSender = Arc::new(|_| {});
+        let response = server
+            .handle_request(
+                &Cx::for_testing(),
+                &mut session,
+                request,
+                &sender,
+                &create_test_request_sender(),
+            )
+            .expect("response");
+        serde_json::to_string(&response).expect("serialize response")
+    }
 
-        let result =
-            fastmcp_core::block_on(ctx.call_tool_text("add", serde_json::json!({"a": 7, "b": 3})));
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "10");
+    #[test]
+    fn test_big_integer_round_trips() {
+        let number = "123456789012345678901234567890";
+        let response = echo(number);
+        assert!(response.contains(number), "{response}");
+        assert!(!response.contains("isError"), "{response}");
     }
 
     #[test]
-    fn test_ctx_call_tool_json() {
-        let mut router = Router::new();
-        router.add_tool(JsonTool);
+    fn test_high_precision_decimal_round_trips() {
+        let number = "0.10000000000000000555111512312578270211815834045";
+        let response = echo(number);
+        assert!(response.contains(number), "{response}");
+    }
+}
 
-        let router_arc = Arc::new(router);
-        let caller: Arc<dyn ToolCaller> =
-            Arc::new(RouterToolCaller::new(router_arc, SessionState::new()));
+mod health_check_tests {
+    use super::*;
+    use crate::HealthStatus;
+    use crate::testing::TestServer;
 
-        let cx = Cx::for_testing();
-        let ctx = McpContext::new(cx, 1).with_tool_caller(caller);
+    fn test_server() -> TestServer {
+        TestServer::new(Server::new("test-server", "1.0.0").tool(GreetTool).build())
+    }
 
-        #[derive(Debug, serde::Deserialize)]
-        struct Result {
-            value: i32,
-        }
+    fn health_check(test: &mut TestServer) -> serde_json::Value {
+        test.request("health/check", None)
+            .expect("response")
+            .result
+            .expect("result")
+    }
 
-        let result: McpResult<Result> =
-            fastmcp_core::block_on(ctx.call_tool_json("compute", serde_json::json!({})));
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap().value, 42);
+    fn call_missing_tool(test: &mut TestServer, name: &str) {
+        let response = test
+            .request("tools/call", Some(serde_json::json!({ "name": name })))
+            .expect("response");
+        assert!(response.error.is_some());
     }
 
     #[test]
-    fn test_ctx_call_tool_returns_error_result() {
-        let mut router = Router::new();
-        router.add_tool(FailingTool);
+    fn test_health_check_available_before_initialize() {
+        let mut test = test_server();
 
-        let router_arc = Arc::new(router);
-        let caller: Arc<dyn ToolCaller> =
-            Arc::new(RouterToolCaller::new(router_arc, SessionState::new()));
+        let response = test.request("health/check", None).expect("response");
+        assert!(response.error.is_none(), "{:?}", response.error);
+        let report = response.result.expect("result");
+        assert_eq!(report["status"], "ok");
+        assert_eq!(report["initialized"], false);
+        assert_eq!(report["activeRequests"], 0);
+        assert_eq!(report["totalRequests"], 0);
+        assert!(report["uptimeSecs"].as_f64().expect("uptime") >= 0.0);
+    }
 
-        let cx = Cx::for_testing();
-        let ctx = McpContext::new(cx, 1).with_tool_caller(caller);
+    #[test]
+    fn test_health_check_reports_initialized_session() {
+        let mut test = test_server();
+        test.initialize().expect("initialize");
 
-        // call_tool returns the error as is_error=true
-        let result = fastmcp_core::block_on(ctx.call_tool("failing", serde_json::json!({})));
-        assert!(result.is_ok());
-        let call_result = result.unwrap();
-        assert!(call_result.is_error);
+        let report = health_check(&mut test);
+        assert_eq!(report["initialized"], true);
+        assert_eq!(report["totalRequests"], 1);
+        assert!(test.server().health().initialized);
     }
 
     #[test]
-    fn test_ctx_call_tool_text_propagates_error() {
-        let mut router = Router::new();
-        router.add_tool(FailingTool);
+    fn test_health_check_degrades_after_errors() {
+        let mut test = test_server();
+        test.initialize().expect("initialize");
 
-        let router_arc = Arc::new(router);
-        let caller: Arc<dyn ToolCaller> =
-            Arc::new(RouterToolCaller::new(router_arc, SessionState::new()));
+        for _ in 0..10 {
+            call_missing_tool(&mut test, "missing_tool");
+        }
 
-        let cx = Cx::for_testing();
-        let ctx = McpContext::new(cx, 1).with_tool_caller(caller);
+        let report = health_check(&mut test);
+        assert_eq!(report["status"], "degraded");
+        assert_eq!(report["totalRequests"], 11);
+        assert!(report["recentErrorRate"].as_f64().expect("rate") > 0.5);
+        assert!(
+            report["lastError"]
+                .as_str()
+                .expect("last error")
+                .contains("missing_tool")
+        );
 
-        // call_tool_text converts is_error=true to Err
-        let result = fastmcp_core::block_on(ctx.call_tool_text("failing", serde_json::json!({})));
-        assert!(result.is_err());
-        let err = result.unwrap_err();
-        assert!(err.message.contains("failed"));
+        let health = test.server().health();
+        assert_eq!(health.status, HealthStatus::Degraded);
+        assert_eq!(health.total_requests, 11);
     }
 
     #[test]
-    fn test_tool_call_depth_increments() {
-        let cx = Cx::for_testing();
-        let ctx = McpContext::new(cx, 1).with_tool_call_depth(5);
-
-        assert_eq!(ctx.tool_call_depth(), 5);
-    }
+    fn test_probes_do_not_count_toward_health() {
+        let mut test = TestServer::new(Server::new("test-server", "1.0.0").build());
 
-    #[test]
-    fn test_max_tool_call_depth_constant() {
-        // Verify the constant is reasonable
-        assert_eq!(MAX_TOOL_CALL_DEPTH, 10);
+        for _ in 0..4 {
+            test.request("ping", None).expect("ping");
+            health_check(&mut test);
+        }
+        assert_eq!(test.server().health().total_requests, 0);
     }
 
     #[test]
-    fn test_tool_validation_error() {
-        let mut router = Router::new();
-        router.add_tool(AddTool);
+    fn test_recent_errors_keep_newest_failures_in_order() {
+        let mut test = test_server();
+        test.initialize().expect("initialize");
+        assert!(test.server().recent_errors().is_empty());
 
-        let router_arc = Arc::new(router);
-        let caller = RouterToolCaller::new(router_arc, SessionState::new());
+        let capacity = crate::health::RECENT_ERRORS_CAPACITY;
+        for n in 0..capacity + 4 {
+            call_missing_tool(&mut test, &format!("missing_{n}"));
+        }
 
-        let cx = Cx::for_testing();
-        // Missing required parameters
-        let result = fastmcp_core::block_on(caller.call_tool(
-            &cx,
-            "add",
-            serde_json::json!({}), // Missing a and b
-            0,
-        ));
+        let errors = test.server().recent_errors();
+        assert_eq!(errors.len(), capacity);
+        for (i, error) in errors.iter().enumerate() {
+            assert_eq!(error.method, "tools/call");
+            assert_eq!(error.code, i32::from(McpErrorCode::MethodNotFound));
+            assert!(
+                error.message.contains(&format!("missing_{}", i + 4)),
+                "{}",
+                error.message
+            );
+            assert!(!error.timestamp.is_empty());
+        }
 
-        assert!(result.is_err());
-        let err = result.unwrap_err();
-        assert!(err.message.contains("validation"));
+        let report = health_check(&mut test);
+        let reported = report["recentErrors"].as_array().expect("recent errors");
+        assert_eq!(reported.len(), capacity);
+        assert_eq!(reported[capacity - 1]["method"], "tools/call");
+        assert_eq!(test.server().health().recent_errors, errors);
     }
+}
 
-    /// A tool that reads session state.
-    struct GetStateTool;
+mod tool_timeout_tests {
+    use super::*;
+    use fastmcp_protocol::CallToolResult;
 
-    impl ToolHandler for GetStateTool {
+    /// A tool that waits for cancellation (or a safety cap) and reports how
+    /// much of its budget it could see.
+    struct PatientTool {
+        timeout: Duration,
+    }
+
+    impl ToolHandler for PatientTool {
         fn definition(&self) -> Tool {
             Tool {
-                name: "get_state".to_string(),
-                description: Some("Returns session state value".to_string()),
+                name: "patient".to_string(),
+                description: None,
                 input_schema: serde_json::json!({"type": "object"}),
                 output_schema: None,
                 icon: None,
@@ -5215,779 +9902,1383 @@ mod ctx_call_tool_tests {
             }
         }
 
-        fn call(&self, ctx: &McpContext, _args: serde_json::Value) -> McpResult<Vec<Content>> {
-            let value: Option<String> = ctx.get_state("tool_test_key");
-            Ok(vec![Content::Text {
-                text: value.unwrap_or_else(|| "no_value".to_string()),
-            }])
+        fn timeout(&self) -> Option<Duration> {
+            Some(self.timeout)
         }
-    }
 
-    /// A tool that sets session state and calls another tool to verify propagation.
-    struct NestedStateTool;
-
-    impl ToolHandler for NestedStateTool {
-        fn definition(&self) -> Tool {
-            Tool {
-                name: "nested_state".to_string(),
-                description: Some("Sets state then calls another tool".to_string()),
-                input_schema: serde_json::json!({"type": "object"}),
-                output_schema: None,
-                icon: None,
-                version: None,
-                tags: vec![],
-                annotations: None,
+        fn call(&self, ctx: &McpContext, args: serde_json::Value) -> McpResult<Vec<Content>> {
+            if args.get("wait").and_then(serde_json::Value::as_bool) == Some(true) {
+                let started = Instant::now();
+                while started.elapsed() < Duration::from_secs(5) {
+                    if ctx.checkpoint().is_err() {
+                        return Err(McpError::request_cancelled());
+                    }
+                    thread::sleep(Duration::from_millis(5));
+                }
             }
+            let left = ctx
+                .budget_remaining()
+                .deadline
+                .map_or_else(|| "unbounded".to_string(), |d| d.as_millis().to_string());
+            Ok(vec![Content::Text { text: left }])
         }
+    }
 
-        fn call(&self, ctx: &McpContext, _args: serde_json::Value) -> McpResult<Vec<Content>> {
-            // Set a value in session state
-            ctx.set_state("tool_test_key", "tool_propagated_value");
-
-            // Call another tool - it should see our session state
-            let inner_result =
-                fastmcp_core::block_on(ctx.call_tool("get_state", serde_json::json!({})))?;
-            let text = inner_result.first_text().unwrap_or("(no content)");
-
-            Ok(vec![Content::Text {
-                text: format!("Inner tool saw: {}", text),
-            }])
-        }
+    fn call(
+        timeout: Duration,
+        budget: &Budget,
+        wait: bool,
+    ) -> (McpResult<CallToolResult>, Duration) {
+        let mut router = Router::new();
+        router.add_tool(PatientTool { timeout });
+        let params = CallToolParams {
+            name: "patient".to_string(),
+            arguments: Some(serde_json::json!({ "wait": wait })),
+            meta: None,
+        };
+        let started = Instant::now();
+        let result = router.handle_tools_call(
+            &Cx::for_testing_with_budget(*budget),
+            1,
+            None,
+            params,
+            budget,
+            SessionState::new(),
+            None,
+            None,
+            false,
+        );
+        (result, started.elapsed())
     }
 
     #[test]
-    fn test_session_state_propagates_through_nested_tool_calls() {
-        use crate::RouterResourceReader;
-
-        let mut router = Router::new();
-        router.add_tool(GetStateTool);
-        router.add_tool(NestedStateTool);
+    fn test_tool_timeout_fires_before_request_budget() {
+        let (result, elapsed) = call(
+            Duration::from_millis(50),
+            &Budget::with_deadline_secs(30),
+            true,
+        );
 
-        let router_arc = Arc::new(router);
-        let session_state = SessionState::new();
-        let caller: Arc<dyn ToolCaller> = Arc::new(RouterToolCaller::new(
-            router_arc.clone(),
-            session_state.clone(),
-        ));
-        let reader: Arc<dyn fastmcp_core::ResourceReader> =
-            Arc::new(RouterResourceReader::new(router_arc, session_state.clone()));
+        let err = result.expect_err("tool should time out");
+        assert_eq!(err.code, McpErrorCode::RequestCancelled);
+        assert!(err.message.contains("Tool timeout"), "{}", err.message);
+        assert!(err.message.contains("50ms"), "{}", err.message);
+        assert!(elapsed < Duration::from_secs(2), "took {elapsed:?}");
+    }
 
-        let cx = Cx::for_testing();
-        let ctx = McpContext::with_state(cx, 1, session_state)
-            .with_tool_caller(caller)
-            .with_resource_reader(reader);
+    #[test]
+    fn test_tool_timeout_applies_without_request_deadline() {
+        let (result, _) = call(Duration::from_millis(20), &Budget::INFINITE, true);
+        let err = result.expect_err("tool should time out");
+        assert!(err.message.contains("Tool timeout"), "{}", err.message);
+    }
 
-        // Call the nested tool - it sets state then calls another tool
-        let result = fastmcp_core::block_on(ctx.call_tool("nested_state", serde_json::json!({})));
-        assert!(result.is_ok());
-        let call_result = result.unwrap();
+    #[test]
+    fn test_request_budget_tighter_than_tool_timeout() {
+        let (result, _) = call(
+            Duration::from_secs(60),
+            &Budget::with_deadline_secs(1),
+            false,
+        );
+        let result = result.expect("tool should run under the request budget");
+        assert!(!result.is_error);
+        let Content::Text { text } = &result.content[0] else {
+            panic!("expected text content");
+        };
+        let left_ms: u64 = text.parse().expect("bounded deadline");
+        assert!(left_ms <= 1000, "saw {left_ms}ms of budget");
 
-        // The inner tool should have seen the propagated value
-        let text = call_result.first_text().unwrap();
-        assert!(
-            text.contains("tool_propagated_value"),
-            "Expected session state to propagate through tool calls, got: {}",
-            text
+        let (result, _) = call(
+            Duration::from_secs(60),
+            &Budget::unlimited().with_poll_quota(0),
+            true,
         );
+        let err = result.expect_err("exhausted request budget");
+        assert!(err.message.contains("budget exhausted"), "{}", err.message);
     }
-}
 
-// ============================================================================
-// Handler Direct Tests
-// ============================================================================
+    #[test]
+    fn test_fast_tool_unaffected_by_timeout() {
+        let (result, _) = call(Duration::from_millis(500), &Budget::INFINITE, false);
+        let result = result.expect("fast tool succeeds");
+        assert!(!result.is_error);
+    }
+}
 
-mod handler_direct_tests {
+mod conditional_read_tests {
     use super::*;
-    use crate::handler::{
-        BidirectionalSenders, MountedPromptHandler, MountedResourceHandler, MountedToolHandler,
-        ProgressNotificationSender, UriParams,
-    };
-    use fastmcp_protocol::{Icon, ToolAnnotations};
-
-    /// Helper: create a test McpContext.
-    fn test_ctx() -> McpContext {
-        let cx = Cx::for_testing();
-        McpContext::new(cx, 1)
-    }
+    use fastmcp_protocol::ReadResourceResult;
 
-    // ── ToolHandler direct call ──────────────────────────────────────
+    /// A resource with fixed cache validators.
+    struct VersionedResource;
 
-    #[test]
-    fn tool_handler_call_returns_content() {
-        let tool = GreetTool;
-        let ctx = test_ctx();
-        let result = tool.call(&ctx, serde_json::json!({"name": "Alice"}));
-        assert!(result.is_ok());
-        let contents = result.unwrap();
-        assert_eq!(contents.len(), 1);
-        match &contents[0] {
-            Content::Text { text } => assert_eq!(text, "Hello, Alice!"),
-            _ => panic!("Expected text content"),
+    impl ResourceHandler for VersionedResource {
+        fn definition(&self) -> Resource {
+            Resource {
+                uri: "resource://versioned".to_string(),
+                name: "Versioned".to_string(),
+                description: None,
+                mime_type: Some("text/plain".to_string()),
+                icon: None,
+                version: None,
+                tags: vec![],
+            }
         }
-    }
 
-    #[test]
-    fn tool_handler_call_default_arg() {
-        let tool = GreetTool;
-        let ctx = test_ctx();
-        let result = tool.call(&ctx, serde_json::json!({}));
-        assert!(result.is_ok());
-        match &result.unwrap()[0] {
-            Content::Text { text } => assert_eq!(text, "Hello, World!"),
-            _ => panic!("Expected text content"),
+        fn read(&self, _ctx: &McpContext) -> McpResult<Vec<ResourceContent>> {
+            Ok(vec![ResourceContent {
+                uri: "resource://versioned".to_string(),
+                mime_type: Some("text/plain".to_string()),
+                text: Some("v2 body".to_string()),
+                blob: None,
+                etag: Some("v2".to_string()),
+                last_modified: Some("2024-06-01T12:00:00Z".to_string()),
+            }])
         }
     }
 
-    #[test]
-    fn tool_handler_error_returns_mcp_error() {
-        let tool = ErrorTool;
-        let ctx = test_ctx();
-        let result = tool.call(&ctx, serde_json::json!({}));
-        assert!(result.is_err());
-        let err = result.unwrap_err();
-        assert_eq!(err.code, McpErrorCode::InternalError);
+    fn read(
+        router: &Router,
+        uri: &str,
+        if_none_match: Option<&str>,
+        if_modified_since: Option<&str>,
+    ) -> ReadResourceResult {
+        let params = ReadResourceParams {
+            uri: uri.to_string(),
+            range: None,
+            if_none_match: if_none_match.map(str::to_string),
+            if_modified_since: if_modified_since.map(str::to_string),
+            meta: None,
+        };
+        router
+            .handle_resources_read(
+                &Cx::for_testing(),
+                1,
+                None,
+                &params,
+                &Budget::INFINITE,
+                SessionState::new(),
+                None,
+                None,
+            )
+            .expect("read")
     }
 
-    #[test]
-    fn tool_handler_definition_has_expected_fields() {
-        let tool = GreetTool;
-        let def = tool.definition();
-        assert_eq!(def.name, "greet");
-        assert!(def.description.is_some());
-        assert_eq!(def.input_schema["type"], "object");
-        assert!(def.input_schema["properties"]["name"].is_object());
+    fn router() -> Router {
+        let mut router = Router::new();
+        router.add_resource(VersionedResource);
+        router.add_resource(StaticResource {
+            uri: "resource://plain".to_string(),
+            content: "no validators".to_string(),
+        });
+        router
     }
 
     #[test]
-    fn tool_handler_default_icon_is_none() {
-        let tool = GreetTool;
-        assert!(tool.icon().is_none());
+    fn test_first_read_returns_validators() {
+        let result = read(&router(), "resource://versioned", None, None);
+        assert!(!result.not_modified);
+        assert_eq!(result.contents[0].etag.as_deref(), Some("v2"));
+
+        let value = serde_json::to_value(&result).expect("serialize");
+        assert_eq!(value["contents"][0]["etag"], "v2");
+        assert_eq!(value["contents"][0]["lastModified"], "2024-06-01T12:00:00Z");
+        assert!(value.get("notModified").is_none());
     }
 
     #[test]
-    fn tool_handler_default_version_is_none() {
-        let tool = GreetTool;
-        assert!(tool.version().is_none());
+    fn test_if_none_match() {
+        let router = router();
+        for tag in ["v2", "v1, v2", "*"] {
+            let result = read(&router, "resource://versioned", Some(tag), None);
+            assert!(result.not_modified, "{tag}");
+            assert!(result.contents.is_empty());
+            let value = serde_json::to_value(&result).expect("serialize");
+            assert_eq!(value["notModified"], true);
+        }
+
+        let result = read(&router, "resource://versioned", Some("v1"), None);
+        assert!(!result.not_modified);
+        assert_eq!(result.contents[0].text.as_deref(), Some("v2 body"));
+
+        // ETag mismatch wins over a matching If-Modified-Since
+        let result = read(
+            &router,
+            "resource://versioned",
+            Some("v1"),
+            Some("2030-01-01T00:00:00Z"),
+        );
+        assert!(!result.not_modified);
     }
 
     #[test]
-    fn tool_handler_default_tags_is_empty() {
-        let tool = GreetTool;
-        assert!(tool.tags().is_empty());
+    fn test_if_modified_since() {
+        let router = router();
+        let unchanged = read(
+            &router,
+            "resource://versioned",
+            None,
+            Some("2024-06-01T12:00:00Z"),
+        );
+        assert!(unchanged.not_modified);
+
+        let stale = read(
+            &router,
+            "resource://versioned",
+            None,
+            Some("2024-05-01T00:00:00Z"),
+        );
+        assert!(!stale.not_modified);
+        assert_eq!(stale.contents.len(), 1);
     }
 
     #[test]
-    fn tool_handler_default_annotations_is_none() {
-        let tool = GreetTool;
-        assert!(tool.annotations().is_none());
+    fn test_content_without_validators_is_always_sent() {
+        let router = router();
+        let result = read(
+            &router,
+            "resource://plain",
+            Some("*"),
+            Some("2030-01-01T00:00:00Z"),
+        );
+        assert!(!result.not_modified);
+        assert_eq!(result.contents[0].text.as_deref(), Some("no validators"));
     }
+}
 
-    #[test]
-    fn tool_handler_default_output_schema_is_none() {
-        let tool = GreetTool;
-        assert!(tool.output_schema().is_none());
+mod multi_transport_tests {
+    use super::*;
+    use fastmcp_protocol::JsonRpcMessage;
+    use fastmcp_transport::Transport;
+    use fastmcp_transport::memory::{MemoryTransport, create_memory_transport_pair};
+
+    fn roundtrip(client: &mut MemoryTransport, request: JsonRpcRequest) -> JsonRpcResponse {
+        let cx = Cx::for_testing();
+        client
+            .send(&cx, &JsonRpcMessage::Request(request))
+            .expect("send request");
+        match client.recv(&cx).expect("receive response") {
+            JsonRpcMessage::Response(response) => response,
+            other => panic!("expected a response, got {other:?}"),
+        }
     }
 
     #[test]
-    fn tool_handler_default_timeout_is_none() {
-        let tool = GreetTool;
-        assert!(tool.timeout().is_none());
+    fn two_transports_serve_independent_sessions() {
+        let server = Server::new("multi", "1.0.0").tool(GreetTool).build();
+        let (mut client_a, server_a) = create_memory_transport_pair();
+        let (mut client_b, server_b) = create_memory_transport_pair();
+        let transports: Vec<Box<dyn Transport + Send>> =
+            vec![Box::new(server_a), Box::new(server_b)];
+        let cx = Cx::for_testing();
+
+        let reason = thread::scope(|scope| {
+            let serving = scope.spawn(|| server.serve_transports(&cx, transports));
+
+            let response = roundtrip(&mut client_a, initialize_request("client-a"));
+            assert!(response.error.is_none());
+
+            // Initializing one transport's session does not initialize the other.
+            let response = roundtrip(&mut client_b, greet_request(2, "Bob"));
+            assert!(response.error.is_some());
+
+            let response = roundtrip(&mut client_b, initialize_request("client-b"));
+            assert!(response.error.is_none());
+
+            assert_eq!(
+                greeting(&roundtrip(&mut client_a, greet_request(2, "Alice"))),
+                "Hello, Alice!"
+            );
+            assert_eq!(
+                greeting(&roundtrip(&mut client_b, greet_request(2, "Bob"))),
+                "Hello, Bob!"
+            );
+
+            drop(client_a);
+            drop(client_b);
+            serving.join().expect("serving thread")
+        });
+        assert_eq!(reason, ShutdownReason::CleanClose);
+
+        let stats = server.stats().expect("stats enabled by default");
+        assert_eq!(stats.total_connections, 2);
+        assert_eq!(stats.active_connections, 0);
+        // Both initializes and all three tool calls, including the rejected one.
+        assert_eq!(stats.total_requests, 5);
+        assert_eq!(stats.tool_calls, 3);
     }
 
-    // ── Custom tool with overrides ───────────────────────────────────
+    #[test]
+    fn cancelling_cx_stops_every_transport_loop() {
+        let server = Server::new("multi", "1.0.0").tool(GreetTool).build();
+        let (_client_a, server_a) = create_memory_transport_pair();
+        let (_client_b, server_b) = create_memory_transport_pair();
+        let transports: Vec<Box<dyn Transport + Send>> =
+            vec![Box::new(server_a), Box::new(server_b)];
+        let cx = Cx::for_testing();
 
-    struct RichTool {
-        icon: Icon,
-        version: String,
-        tags: Vec<String>,
-        annotations: ToolAnnotations,
-        output_schema: serde_json::Value,
-        timeout: Duration,
+        let reason = thread::scope(|scope| {
+            let serving = scope.spawn(|| server.serve_transports(&cx, transports));
+            thread::sleep(Duration::from_millis(50));
+            cx.cancel_with(CancelKind::Shutdown, None);
+            serving.join().expect("serving thread")
+        });
+        assert_eq!(reason, ShutdownReason::Cancelled);
+        assert_eq!(server.stats().unwrap().active_connections, 0);
     }
+}
 
-    impl Default for RichTool {
-        fn default() -> Self {
-            Self {
-                icon: Icon {
-                    src: Some("https://example.com/icon.png".to_string()),
-                    mime_type: None,
-                    sizes: None,
-                },
-                version: "2.0.0".to_string(),
-                tags: vec!["api".to_string(), "read".to_string()],
-                annotations: ToolAnnotations {
-                    destructive: Some(false),
-                    idempotent: Some(true),
-                    read_only: Some(true),
-                    open_world_hint: Some("none".to_string()),
-                },
-                output_schema: serde_json::json!({
-                    "type": "object",
-                    "properties": {
-                        "result": {"type": "string"}
-                    }
-                }),
-                timeout: Duration::from_secs(60),
-            }
-        }
+/// Deterministic end-to-end tests driven through `testing::TestServer`.
+mod test_server_tests {
+    use super::*;
+    use crate::testing::{CancelHandle, TestServer};
+    use fastmcp_protocol::RequestId;
+    use std::sync::OnceLock;
+
+    /// Cancels its own request through the harness, then reports whether it saw it.
+    struct SelfCancellingTool {
+        target: Arc<OnceLock<(CancelHandle, RequestId)>>,
     }
 
-    impl ToolHandler for RichTool {
+    impl ToolHandler for SelfCancellingTool {
         fn definition(&self) -> Tool {
             Tool {
-                name: "rich".to_string(),
-                description: Some("A fully configured tool".to_string()),
+                name: "self_cancel".to_string(),
+                description: Some("Cancels the armed request ID".to_string()),
                 input_schema: serde_json::json!({"type": "object"}),
-                output_schema: Some(self.output_schema.clone()),
-                icon: Some(self.icon.clone()),
-                version: Some(self.version.clone()),
-                tags: self.tags.clone(),
-                annotations: Some(self.annotations.clone()),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+                annotations: None,
             }
         }
 
-        fn icon(&self) -> Option<&Icon> {
-            Some(&self.icon)
-        }
-
-        fn version(&self) -> Option<&str> {
-            Some(&self.version)
-        }
-
-        fn tags(&self) -> &[String] {
-            &self.tags
-        }
-
-        fn annotations(&self) -> Option<&ToolAnnotations> {
-            Some(&self.annotations)
-        }
-
-        fn output_schema(&self) -> Option<serde_json::Value> {
-            Some(self.output_schema.clone())
-        }
-
-        fn timeout(&self) -> Option<Duration> {
-            Some(self.timeout)
-        }
-
-        fn call(
-            &self,
-            _ctx: &McpContext,
-            _arguments: serde_json::Value,
-        ) -> McpResult<Vec<Content>> {
+        fn call(&self, ctx: &McpContext, _args: serde_json::Value) -> McpResult<Vec<Content>> {
+            if let Some((handle, request_id)) = self.target.get() {
+                handle.cancel(request_id.clone());
+            }
+            if ctx.is_cancelled() {
+                return Err(McpError::request_cancelled());
+            }
             Ok(vec![Content::Text {
-                text: "rich result".to_string(),
+                text: "finished".to_string(),
             }])
         }
     }
 
-    #[test]
-    fn tool_handler_custom_icon() {
-        let tool = RichTool::default();
-        assert!(tool.icon().is_some());
+    fn demo_server() -> Server {
+        Server::new("lab-server", "1.0.0")
+            .tool(GreetTool)
+            .resource(StaticResource {
+                uri: "resource://greeting".to_string(),
+                content: "Hello from a resource".to_string(),
+            })
+            .prompt(GreetingPrompt)
+            .build()
     }
 
     #[test]
-    fn tool_handler_custom_version() {
-        let tool = RichTool::default();
-        assert_eq!(tool.version(), Some("2.0.0"));
-    }
+    fn drives_tool_resource_and_prompt_requests() {
+        let mut test = TestServer::new(demo_server());
+        assert!(!test.is_initialized());
 
-    #[test]
-    fn tool_handler_custom_tags() {
-        let tool = RichTool::default();
-        assert_eq!(tool.tags().len(), 2);
-        assert_eq!(tool.tags()[0], "api");
-    }
+        test.assert_tool_text("greet", serde_json::json!({"name": "Ada"}), "Hello, Ada!");
+        assert!(test.is_initialized());
+        test.assert_resource_text("resource://greeting", "Hello from a resource");
 
-    #[test]
-    fn tool_handler_custom_annotations() {
-        let tool = RichTool::default();
-        let ann = tool.annotations().unwrap();
-        assert_eq!(ann.read_only, Some(true));
-        assert_eq!(ann.destructive, Some(false));
-        assert_eq!(ann.idempotent, Some(true));
+        let arguments = HashMap::from([("name".to_string(), "Grace".to_string())]);
+        let prompt = test.get_prompt("greeting", arguments).unwrap();
+        assert!(matches!(
+            &prompt.messages[0].content,
+            Content::Text { text } if text == "Please greet Grace warmly."
+        ));
+
+        let err = test.assert_tool_error(
+            "missing",
+            serde_json::json!({}),
+            McpErrorCode::MethodNotFound,
+        );
+        assert!(err.message.contains("missing"));
+
+        let stats = test.server().stats().expect("stats enabled by default");
+        assert_eq!(stats.tool_calls, 2);
     }
 
     #[test]
-    fn tool_handler_custom_output_schema() {
-        let tool = RichTool::default();
-        let schema = tool.output_schema().unwrap();
-        assert_eq!(schema["type"], "object");
+    fn uninitialized_requests_are_rejected() {
+        let mut test = TestServer::new(demo_server());
+        let response = test.request("tools/list", None).unwrap();
+        assert!(response.error.is_some());
+        assert_eq!(test.next_request_id(), RequestId::Number(2));
     }
 
     #[test]
-    fn tool_handler_custom_timeout() {
-        let tool = RichTool::default();
-        assert_eq!(tool.timeout(), Some(Duration::from_secs(60)));
-    }
+    fn cancellation_mid_call_is_reproducible() {
+        for seed in [7, 7, 1234] {
+            let target = Arc::new(OnceLock::new());
+            let server = Server::new("lab-server", "1.0.0")
+                .tool(SelfCancellingTool {
+                    target: Arc::clone(&target),
+                })
+                .build();
+            let mut test = TestServer::with_seed(server, seed);
+            test.initialize().unwrap();
 
-    // ── ResourceHandler direct read ──────────────────────────────────
+            assert!(
+                target
+                    .set((test.cancel_handle(), test.next_request_id()))
+                    .is_ok()
+            );
+            test.assert_tool_error(
+                "self_cancel",
+                serde_json::json!({}),
+                McpErrorCode::RequestCancelled,
+            );
 
-    #[test]
-    fn resource_handler_read_returns_content() {
-        let resource = StaticResource {
-            uri: "test://hello".to_string(),
-            content: "world".to_string(),
-        };
-        let ctx = test_ctx();
-        let result = resource.read(&ctx);
-        assert!(result.is_ok());
-        let contents = result.unwrap();
-        assert_eq!(contents.len(), 1);
-        assert_eq!(contents[0].uri, "test://hello");
-        assert_eq!(contents[0].text, Some("world".to_string()));
+            // The armed ID is no longer in flight, so the next call completes.
+            test.assert_tool_text("self_cancel", serde_json::json!({}), "finished");
+        }
     }
+}
 
-    #[test]
-    fn resource_handler_definition_fields() {
-        let resource = StaticResource {
-            uri: "test://data".to_string(),
-            content: "content".to_string(),
-        };
-        let def = resource.definition();
-        assert_eq!(def.uri, "test://data");
-        assert_eq!(def.name, "Static Resource");
-        assert_eq!(def.mime_type, Some("text/plain".to_string()));
+mod unknown_notification_tests {
+    use super::*;
+    use log::Level;
+
+    fn notification(method: &str) -> JsonRpcRequest {
+        JsonRpcRequest {
+            jsonrpc: std::borrow::Cow::Borrowed(fastmcp_protocol::JSONRPC_VERSION),
+            method: method.to_string(),
+            params: None,
+            id: None,
+        }
     }
 
     #[test]
-    fn resource_handler_default_template_is_none() {
-        let resource = StaticResource {
-            uri: "test://data".to_string(),
-            content: "content".to_string(),
-        };
-        assert!(resource.template().is_none());
+    fn unknown_notification_gets_no_response() {
+        let server = Server::new("test-server", "1.0.0").build();
+        let cx = Cx::for_testing();
+        let mut session = initialized_session();
+
+        let response = server.dispatch_one(&cx, &mut session, notification("notifications/x-hint"));
+        assert!(response.is_none());
     }
 
     #[test]
-    fn resource_handler_default_icon_is_none() {
-        let resource = StaticResource {
-            uri: "test://data".to_string(),
-            content: "".to_string(),
-        };
-        assert!(resource.icon().is_none());
+    fn unknown_request_method_still_errors() {
+        let server = Server::new("test-server", "1.0.0").build();
+        let cx = Cx::for_testing();
+        let mut session = initialized_session();
+
+        let request = JsonRpcRequest::new("notifications/x-hint", None, 1);
+        let response = server
+            .dispatch_one(&cx, &mut session, request)
+            .expect("requests are answered");
+        let error = response.error.expect("unknown method");
+        assert_eq!(error.code, i32::from(McpErrorCode::MethodNotFound));
     }
 
     #[test]
-    fn resource_handler_default_version_is_none() {
-        let resource = StaticResource {
-            uri: "test://data".to_string(),
-            content: "".to_string(),
-        };
-        assert!(resource.version().is_none());
+    fn unknown_notifications_log_below_error() {
+        let server = Server::new("test-server", "1.0.0").build();
+        let unknown = McpError::method_not_found("notifications/x-hint");
+
+        assert_eq!(
+            server.notification_failure_level("notifications/x-hint", &unknown),
+            Level::Trace
+        );
+        assert_eq!(
+            server.notification_failure_level("x-vendor/hint", &unknown),
+            Level::Debug
+        );
+        assert_eq!(
+            server.notification_failure_level(
+                "notifications/cancelled",
+                &McpError::invalid_params("missing requestId")
+            ),
+            Level::Error
+        );
     }
 
     #[test]
-    fn resource_handler_default_tags_is_empty() {
-        let resource = StaticResource {
-            uri: "test://data".to_string(),
-            content: "".to_string(),
-        };
-        assert!(resource.tags().is_empty());
+    fn ignored_notification_prefixes_are_configurable() {
+        let server = Server::new("test-server", "1.0.0")
+            .ignored_notification_prefixes(["x-vendor/"])
+            .build();
+        let unknown = McpError::method_not_found("x-vendor/hint");
+
+        assert_eq!(
+            server.notification_failure_level("x-vendor/hint", &unknown),
+            Level::Trace
+        );
+        assert_eq!(
+            server.notification_failure_level("notifications/x-hint", &unknown),
+            Level::Debug
+        );
     }
+}
 
-    #[test]
-    fn resource_handler_default_timeout_is_none() {
-        let resource = StaticResource {
-            uri: "test://data".to_string(),
-            content: "".to_string(),
-        };
-        assert!(resource.timeout().is_none());
+mod auth_cache_tests {
+    use super::*;
+    use crate::{AuthCachePolicy, AuthProvider, Clock, TestClock, TokenVerifier};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Token provider that counts how often it is consulted.
+    struct CountingProvider {
+        inner: TokenAuthProvider,
+        calls: Arc<AtomicUsize>,
+        policy: AuthCachePolicy,
     }
 
-    #[test]
-    fn resource_handler_read_with_uri_delegates_to_read() {
-        let resource = StaticResource {
-            uri: "test://data".to_string(),
-            content: "delegated".to_string(),
-        };
-        let ctx = test_ctx();
-        let params = UriParams::new();
-        let result = resource.read_with_uri(&ctx, "test://data", &params);
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap()[0].text, Some("delegated".to_string()));
+    impl AuthProvider for CountingProvider {
+        fn authenticate(
+            &self,
+            ctx: &McpContext,
+            request: AuthRequest<'_>,
+        ) -> McpResult<AuthContext> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.inner.authenticate(ctx, request)
+        }
+
+        fn cache_policy(&self) -> AuthCachePolicy {
+            self.policy
+        }
     }
 
-    #[test]
-    fn resource_handler_template_resource_read_with_uri() {
-        let resource = TemplateResource;
-        let ctx = test_ctx();
-        let mut params = UriParams::new();
-        params.insert("id".to_string(), "42".to_string());
-        let result = resource.read_with_uri(&ctx, "resource://42", &params);
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap()[0].text, Some("Template 42".to_string()));
+    /// Verifier whose contexts carry the given `exp` claim (Unix seconds).
+    struct ExpiringVerifier(u64);
+
+    impl TokenVerifier for ExpiringVerifier {
+        fn verify(
+            &self,
+            _ctx: &McpContext,
+            _request: AuthRequest<'_>,
+            token: &fastmcp_core::AccessToken,
+        ) -> McpResult<AuthContext> {
+            Ok(AuthContext {
+                subject: Some("expiring".to_string()),
+                token: Some(token.clone()),
+                claims: Some(serde_json::json!({ "exp": self.0 })),
+                ..AuthContext::default()
+            })
+        }
     }
 
-    #[test]
-    fn resource_handler_template_resource_has_template() {
-        let resource = TemplateResource;
-        let tmpl = resource.template();
-        assert!(tmpl.is_some());
-        assert_eq!(tmpl.unwrap().uri_template, "resource://{id}");
+    fn static_provider() -> TokenAuthProvider {
+        TokenAuthProvider::new(StaticTokenVerifier::new([
+            ("alice-token", AuthContext::with_subject("alice")),
+            ("bob-token", AuthContext::with_subject("bob")),
+        ]))
     }
 
-    #[test]
-    fn resource_handler_template_resource_read_without_params_errors() {
-        let resource = TemplateResource;
-        let ctx = test_ctx();
-        let result = resource.read(&ctx);
-        assert!(result.is_err());
+    fn counting_server(
+        inner: TokenAuthProvider,
+        policy: AuthCachePolicy,
+    ) -> (Server, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let server = Server::new("test-server", "1.0.0")
+            .tool(GreetTool)
+            .auth_provider(CountingProvider {
+                inner,
+                calls: Arc::clone(&calls),
+                policy,
+            })
+            .build();
+        (server, calls)
     }
 
-    // ── PromptHandler direct get ─────────────────────────────────────
+    fn list_tools(server: &Server, session: &mut Session, token: &str, id: i64) -> JsonRpcResponse {
+        let sender: NotificationSender = Arc::new(|_| {});
+        let request = JsonRpcRequest::new(
+            "tools/list",
+            Some(serde_json::json!({ "auth": format!("Bearer {token}") })),
+            id,
+        );
+        server
+            .handle_request(
+                &Cx::for_testing(),
+                session,
+                request,
+                &sender,
+                &create_test_request_sender(),
+            )
+            .expect("response")
+    }
 
     #[test]
-    fn prompt_handler_get_returns_messages() {
-        let prompt = GreetingPrompt;
-        let ctx = test_ctx();
-        let mut args = HashMap::new();
-        args.insert("name".to_string(), "Bob".to_string());
-        let result = prompt.get(&ctx, args);
-        assert!(result.is_ok());
-        let messages = result.unwrap();
-        assert_eq!(messages.len(), 1);
-        match &messages[0].content {
-            Content::Text { text } => assert!(text.contains("Bob")),
-            _ => panic!("Expected text content"),
+    fn second_request_reuses_cached_context() {
+        let (server, calls) = counting_server(static_provider(), AuthCachePolicy::PerSession);
+        let mut session = initialized_session();
+
+        for id in 1..=3 {
+            let response = list_tools(&server, &mut session, "alice-token", id);
+            assert!(response.error.is_none(), "{:?}", response.error);
         }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let auth: AuthContext = session
+            .state()
+            .get(fastmcp_core::AUTH_STATE_KEY)
+            .expect("auth stored");
+        assert_eq!(auth.subject.as_deref(), Some("alice"));
     }
 
     #[test]
-    fn prompt_handler_definition_fields() {
-        let prompt = GreetingPrompt;
-        let def = prompt.definition();
-        assert_eq!(def.name, "greeting");
-        assert!(def.description.is_some());
-        assert_eq!(def.arguments.len(), 1);
-        assert_eq!(def.arguments[0].name, "name");
-        assert!(def.arguments[0].required);
+    fn changed_token_reauthenticates() {
+        let (server, calls) = counting_server(static_provider(), AuthCachePolicy::PerSession);
+        let mut session = initialized_session();
+
+        list_tools(&server, &mut session, "alice-token", 1);
+        let response = list_tools(&server, &mut session, "bob-token", 2);
+        assert!(response.error.is_none(), "{:?}", response.error);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let response = list_tools(&server, &mut session, "revoked-token", 3);
+        let error = response.error.expect("auth error");
+        assert_eq!(error.code, i32::from(McpErrorCode::ResourceForbidden));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // A failed attempt drops the cache, so the old token is checked again.
+        list_tools(&server, &mut session, "bob-token", 4);
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
     }
 
     #[test]
-    fn prompt_handler_default_icon_is_none() {
-        let prompt = GreetingPrompt;
-        assert!(prompt.icon().is_none());
+    fn never_policy_authenticates_every_request() {
+        let (server, calls) = counting_server(static_provider(), AuthCachePolicy::Never);
+        let mut session = initialized_session();
+
+        list_tools(&server, &mut session, "alice-token", 1);
+        list_tools(&server, &mut session, "alice-token", 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
     }
 
     #[test]
-    fn prompt_handler_default_version_is_none() {
-        let prompt = GreetingPrompt;
-        assert!(prompt.version().is_none());
+    fn expired_context_is_not_reused() {
+        let (server, calls) = counting_server(
+            TokenAuthProvider::new(ExpiringVerifier(1)),
+            AuthCachePolicy::PerSession,
+        );
+        let mut session = initialized_session();
+
+        list_tools(&server, &mut session, "old-token", 1);
+        list_tools(&server, &mut session, "old-token", 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
     }
 
     #[test]
-    fn prompt_handler_default_tags_is_empty() {
-        let prompt = GreetingPrompt;
-        assert!(prompt.tags().is_empty());
+    fn cached_context_expires_by_server_clock() {
+        let clock = TestClock::new();
+        let exp = clock
+            .now_system()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("after epoch")
+            .as_secs()
+            + 60;
+        let calls = Arc::new(AtomicUsize::new(0));
+        let server = Server::new("test-server", "1.0.0")
+            .tool(GreetTool)
+            .clock(clock.clone())
+            .auth_provider(CountingProvider {
+                inner: TokenAuthProvider::new(ExpiringVerifier(exp)),
+                calls: Arc::clone(&calls),
+                policy: AuthCachePolicy::PerSession,
+            })
+            .build();
+        let mut session = initialized_session();
+
+        list_tools(&server, &mut session, "token", 1);
+        list_tools(&server, &mut session, "token", 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        clock.advance(Duration::from_secs(120));
+        list_tools(&server, &mut session, "token", 3);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
     }
+}
+
+mod uptime_tests {
+    use super::*;
+    use crate::TestClock;
 
     #[test]
-    fn prompt_handler_default_timeout_is_none() {
-        let prompt = GreetingPrompt;
-        assert!(prompt.timeout().is_none());
+    fn uptime_advances_with_clock_and_is_reported() {
+        let clock = TestClock::new();
+        let server = Server::new("test-server", "1.0.0")
+            .clock(clock.clone())
+            .build();
+        assert_eq!(server.uptime(), Duration::ZERO);
+
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(server.uptime(), Duration::from_secs(5));
+        assert_eq!(
+            server.stats().expect("stats enabled").uptime,
+            Duration::from_secs(5)
+        );
+        assert!((server.health().uptime_secs - 5.0).abs() < f64::EPSILON);
+
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(server.uptime(), Duration::from_secs(7));
     }
 
     #[test]
-    fn prompt_handler_get_with_missing_arg_uses_default() {
-        let prompt = GreetingPrompt;
-        let ctx = test_ctx();
-        let args = HashMap::new(); // no "name" argument
-        let result = prompt.get(&ctx, args);
-        assert!(result.is_ok());
-        match &result.unwrap()[0].content {
-            Content::Text { text } => assert!(text.contains("User")),
-            _ => panic!("Expected text content"),
-        }
-    }
+    fn starting_resets_uptime() {
+        let clock = TestClock::new();
+        let server = Server::new("test-server", "1.0.0")
+            .without_stats()
+            .clock(clock.clone())
+            .build();
 
-    // ── MountedToolHandler ───────────────────────────────────────────
+        clock.advance(Duration::from_secs(30));
+        server.mark_started();
+        assert_eq!(server.uptime(), Duration::ZERO);
 
-    #[test]
-    fn mounted_tool_handler_overrides_name() {
-        let inner: Box<dyn ToolHandler> = Box::new(GreetTool);
-        let mounted = MountedToolHandler::new(inner, "ns/greet".to_string());
-        let def = mounted.definition();
-        assert_eq!(def.name, "ns/greet");
-        // Other fields preserved
-        assert!(def.description.is_some());
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(server.uptime(), Duration::from_secs(1));
+        assert!(server.stats().is_none());
     }
+}
+
+mod disabled_method_tests {
+    use super::*;
+    use crate::testing::TestServer;
 
     #[test]
-    fn mounted_tool_handler_delegates_call() {
-        let inner: Box<dyn ToolHandler> = Box::new(GreetTool);
-        let mounted = MountedToolHandler::new(inner, "ns/greet".to_string());
-        let ctx = test_ctx();
-        let result = mounted.call(&ctx, serde_json::json!({"name": "Mounted"}));
-        assert!(result.is_ok());
-        match &result.unwrap()[0] {
-            Content::Text { text } => assert_eq!(text, "Hello, Mounted!"),
-            _ => panic!("Expected text content"),
-        }
+    fn disabled_set_level_is_rejected_and_not_advertised() {
+        let server = Server::new("test-server", "1.0.0")
+            .tool(GreetTool)
+            .disable_method("logging/setLevel")
+            .build();
+        let mut harness = TestServer::new(server);
+
+        let init = harness.initialize().expect("initialize");
+        assert!(init.capabilities.logging.is_none());
+
+        let response = harness
+            .request(
+                "logging/setLevel",
+                Some(serde_json::json!({ "level": "debug" })),
+            )
+            .expect("response");
+        let error = response.error.expect("method disabled");
+        assert_eq!(error.code, i32::from(McpErrorCode::MethodNotFound));
+        assert!(
+            error.message.contains("logging/setLevel"),
+            "{}",
+            error.message
+        );
+
+        harness.assert_tool_text("greet", serde_json::json!({ "name": "Ada" }), "Hello, Ada!");
     }
 
     #[test]
-    fn mounted_tool_handler_delegates_timeout() {
-        let inner: Box<dyn ToolHandler> = Box::new(RichTool::default());
-        let mounted = MountedToolHandler::new(inner, "ns/rich".to_string());
-        assert_eq!(mounted.timeout(), Some(Duration::from_secs(60)));
+    fn lifecycle_methods_cannot_be_disabled() {
+        let server = Server::new("test-server", "1.0.0")
+            .disable_method("initialize")
+            .disable_method("ping")
+            .build();
+        let mut harness = TestServer::new(server);
+
+        harness.initialize().expect("initialize");
+        let response = harness.request("ping", None).expect("response");
+        assert!(response.error.is_none(), "{:?}", response.error);
     }
 
     #[test]
-    fn mounted_tool_handler_delegates_annotations() {
-        let inner: Box<dyn ToolHandler> = Box::new(RichTool::default());
-        let mounted = MountedToolHandler::new(inner, "ns/rich".to_string());
-        let ann = mounted.annotations().unwrap();
-        assert_eq!(ann.read_only, Some(true));
+    fn capabilities_are_kept_when_nothing_is_disabled() {
+        let server = Server::new("test-server", "1.0.0").build();
+        assert!(server.capabilities().logging.is_some());
     }
+}
 
-    #[test]
-    fn mounted_tool_handler_delegates_output_schema() {
-        let inner: Box<dyn ToolHandler> = Box::new(RichTool::default());
-        let mounted = MountedToolHandler::new(inner, "ns/rich".to_string());
-        assert!(mounted.output_schema().is_some());
+mod list_pagination_tests {
+    use super::*;
+    use fastmcp_protocol::ListToolsParams;
+
+    struct NamedTool(&'static str);
+
+    impl ToolHandler for NamedTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: self.0.to_string(),
+                description: None,
+                input_schema: serde_json::json!({"type": "object"}),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+                annotations: None,
+            }
+        }
+
+        fn call(&self, _ctx: &McpContext, _args: serde_json::Value) -> McpResult<Vec<Content>> {
+            Ok(vec![])
+        }
     }
 
-    // ── MountedResourceHandler ───────────────────────────────────────
+    fn paged_router(page_size: usize) -> Router {
+        let mut router = Router::new();
+        for name in ["a", "b", "c", "d", "e"] {
+            router.add_tool(NamedTool(name));
+        }
+        router.set_list_page_size(Some(page_size));
+        router
+    }
 
-    #[test]
-    fn mounted_resource_handler_overrides_uri() {
-        let inner: Box<dyn ResourceHandler> = Box::new(StaticResource {
-            uri: "test://orig".to_string(),
-            content: "data".to_string(),
-        });
-        let mounted = MountedResourceHandler::new(inner, "ns/test://orig".to_string());
-        let def = mounted.definition();
-        assert_eq!(def.uri, "ns/test://orig");
-        // Other fields preserved
-        assert_eq!(def.name, "Static Resource");
+    fn list_page(
+        router: &Router,
+        state: &SessionState,
+        cursor: Option<String>,
+    ) -> McpResult<(Vec<String>, Option<String>)> {
+        let params = ListToolsParams {
+            cursor,
+            ..ListToolsParams::default()
+        };
+        let result = router.handle_tools_list(&Cx::for_testing(), params, Some(state))?;
+        let names = result.tools.into_iter().map(|tool| tool.name).collect();
+        Ok((names, result.next_cursor))
     }
 
     #[test]
-    fn mounted_resource_handler_delegates_read() {
-        let inner: Box<dyn ResourceHandler> = Box::new(StaticResource {
-            uri: "test://data".to_string(),
-            content: "mounted_data".to_string(),
-        });
-        let mounted = MountedResourceHandler::new(inner, "ns/test://data".to_string());
-        let ctx = test_ctx();
-        let result = mounted.read(&ctx);
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap()[0].text, Some("mounted_data".to_string()));
+    fn pages_cover_every_tool_once() {
+        let router = paged_router(2);
+        let state = SessionState::new();
+
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        loop {
+            let (names, next) = list_page(&router, &state, cursor).expect("page");
+            assert!(names.len() <= 2);
+            seen.extend(names);
+            match next {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(seen, ["a", "b", "c", "d", "e"]);
     }
 
     #[test]
-    fn mounted_resource_handler_with_template() {
-        let inner: Box<dyn ResourceHandler> = Box::new(TemplateResource);
-        let tmpl = ResourceTemplate {
-            uri_template: "ns/resource://{id}".to_string(),
-            name: "Mounted Template".to_string(),
-            description: None,
-            mime_type: None,
-            icon: None,
-            version: None,
-            tags: vec![],
-        };
-        let mounted =
-            MountedResourceHandler::with_template(inner, "ns/resource://{id}".to_string(), tmpl);
-        let template = mounted.template();
-        assert!(template.is_some());
-        assert_eq!(template.unwrap().uri_template, "ns/resource://{id}");
-    }
+    fn mutation_between_pages_invalidates_cursor() {
+        let router = paged_router(2);
+        let state = SessionState::new();
 
-    // ── MountedPromptHandler ─────────────────────────────────────────
+        let (first, cursor) = list_page(&router, &state, None).expect("first page");
+        assert_eq!(first, ["a", "b"]);
 
-    #[test]
-    fn mounted_prompt_handler_overrides_name() {
-        let inner: Box<dyn PromptHandler> = Box::new(GreetingPrompt);
-        let mounted = MountedPromptHandler::new(inner, "ns/greeting".to_string());
-        let def = mounted.definition();
-        assert_eq!(def.name, "ns/greeting");
-        // Arguments preserved
-        assert_eq!(def.arguments.len(), 1);
+        // Removing an already-listed tool would shift "c" onto the first
+        // page and skip it; the server must report that instead.
+        let ctx = McpContext::with_state(Cx::for_testing(), 1, state.clone());
+        assert!(ctx.disable_tool("a"));
+
+        let error = list_page(&router, &state, cursor).expect_err("stale cursor");
+        assert!(error.is_cursor_invalidated(), "{error:?}");
+        assert_eq!(error.code, McpErrorCode::InvalidParams);
+
+        let (restarted, _) = list_page(&router, &state, None).expect("restart");
+        assert_eq!(restarted, ["b", "c"]);
     }
 
     #[test]
-    fn mounted_prompt_handler_delegates_get() {
-        let inner: Box<dyn PromptHandler> = Box::new(GreetingPrompt);
-        let mounted = MountedPromptHandler::new(inner, "ns/greeting".to_string());
-        let ctx = test_ctx();
-        let mut args = HashMap::new();
-        args.insert("name".to_string(), "MountedUser".to_string());
-        let result = mounted.get(&ctx, args);
-        assert!(result.is_ok());
-        match &result.unwrap()[0].content {
-            Content::Text { text } => assert!(text.contains("MountedUser")),
-            _ => panic!("Expected text content"),
-        }
+    fn malformed_cursor_is_invalid_params() {
+        let router = paged_router(2);
+        let error = list_page(&router, &SessionState::new(), Some("garbage".to_string()))
+            .expect_err("bad cursor");
+        assert_eq!(error.code, McpErrorCode::InvalidParams);
+        assert!(!error.is_cursor_invalidated());
     }
 
-    // ── ProgressNotificationSender ───────────────────────────────────
-
     #[test]
-    fn progress_notification_sender_sends_notification() {
-        let sent = Arc::new(std::sync::Mutex::new(Vec::new()));
-        let sent_clone = sent.clone();
-        let sender = ProgressNotificationSender::new(
-            fastmcp_protocol::ProgressToken::String("tok".to_string()),
-            move |req: fastmcp_protocol::JsonRpcRequest| {
-                sent_clone.lock().unwrap().push(req);
-            },
-        );
+    fn listing_is_unpaged_by_default() {
+        let mut router = paged_router(2);
+        router.set_list_page_size(None);
+        let (names, next) = list_page(&router, &SessionState::new(), None).expect("list");
+        assert_eq!(names.len(), 5);
+        assert!(next.is_none());
+    }
+}
 
-        use fastmcp_core::NotificationSender;
-        sender.send_progress(0.5, Some(1.0), Some("half done"));
+mod template_expansion_tests {
+    use super::*;
+    use crate::providers::FilesystemProvider;
+    use fastmcp_protocol::ListResourcesParams;
 
-        let notifications = sent.lock().unwrap();
-        assert_eq!(notifications.len(), 1);
-        assert_eq!(notifications[0].method, "notifications/progress");
+    fn list_uris(router: &Router) -> Vec<String> {
+        router
+            .handle_resources_list(&Cx::for_testing(), ListResourcesParams::default(), None)
+            .expect("list")
+            .resources
+            .into_iter()
+            .map(|resource| resource.uri)
+            .collect()
     }
 
     #[test]
-    fn progress_notification_sender_without_total() {
-        let sent = Arc::new(std::sync::Mutex::new(Vec::new()));
-        let sent_clone = sent.clone();
-        let sender = ProgressNotificationSender::new(
-            fastmcp_protocol::ProgressToken::Number(99),
-            move |req: fastmcp_protocol::JsonRpcRequest| {
-                sent_clone.lock().unwrap().push(req);
-            },
-        );
+    fn filesystem_template_expands_to_present_files() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let root = dir.path();
+        std::fs::write(root.join("a.md"), "# A").expect("write a");
 
-        use fastmcp_core::NotificationSender;
-        sender.send_progress(1.0, None, None);
+        let mut router = Router::new();
+        router.add_resource(StaticResource {
+            uri: "resource://static".to_string(),
+            content: "static".to_string(),
+        });
+        router.add_resource(FilesystemProvider::new(root).with_prefix("docs").build());
+        assert_eq!(
+            list_uris(&router),
+            ["file://docs/a.md", "resource://static"]
+        );
 
-        let notifications = sent.lock().unwrap();
-        assert_eq!(notifications.len(), 1);
+        // Expansion reflects the directory at listing time.
+        std::fs::write(root.join("b.txt"), "b").expect("write b");
+        std::fs::write(root.join(".hidden"), "h").expect("write hidden");
+        assert_eq!(
+            list_uris(&router),
+            ["file://docs/a.md", "file://docs/b.txt", "resource://static"]
+        );
     }
 
     #[test]
-    fn progress_notification_sender_debug_format() {
-        let sender = ProgressNotificationSender::new(
-            fastmcp_protocol::ProgressToken::String("debug-test".to_string()),
-            |_: fastmcp_protocol::JsonRpcRequest| {},
-        );
-        let debug = format!("{sender:?}");
-        assert!(debug.contains("ProgressNotificationSender"));
+    fn non_expandable_template_lists_no_instances() {
+        let mut router = Router::new();
+        router.add_resource(TemplateResource);
+        assert!(TemplateResource.expand().is_none());
+        assert!(list_uris(&router).is_empty());
+        assert_eq!(router.resource_templates().len(), 1);
     }
+}
 
-    // ── BidirectionalSenders ─────────────────────────────────────────
+mod byte_accounting_tests {
+    use super::*;
+    use crate::wire_size_estimate;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Debug-printable value whose JSON serialization always fails.
+    #[derive(Debug)]
+    struct Unserializable {
+        method: &'static str,
+    }
+
+    impl serde::Serialize for Unserializable {
+        fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("forced failure"))
+        }
+    }
 
     #[test]
-    fn bidirectional_senders_default_is_empty() {
-        let senders = BidirectionalSenders::new();
-        assert!(senders.sampling.is_none());
-        assert!(senders.elicitation.is_none());
+    fn serializable_message_counts_json_length() {
+        let warned = AtomicBool::new(false);
+        let request = fastmcp_protocol::JsonRpcRequest::new("ping", None, 1);
+        let json = serde_json::to_string(&request).expect("serialize");
+
+        assert_eq!(wire_size_estimate(&request, &warned), json.len() as u64 + 1);
+        assert!(!warned.load(Ordering::Relaxed));
     }
 
     #[test]
-    fn bidirectional_senders_debug_format() {
-        let senders = BidirectionalSenders::new();
-        let debug = format!("{senders:?}");
-        assert!(debug.contains("BidirectionalSenders"));
-        assert!(debug.contains("sampling: false"));
-        assert!(debug.contains("elicitation: false"));
+    fn failed_serialization_estimates_and_warns_once() {
+        let warned = AtomicBool::new(false);
+        let value = Unserializable {
+            method: "tools/call",
+        };
+
+        let estimate = wire_size_estimate(&value, &warned);
+        assert_eq!(estimate, format!("{value:?}").len() as u64 + 1);
+        assert!(estimate > 1);
+        assert!(warned.load(Ordering::Relaxed), "fallback should warn");
+
+        // Later failures keep estimating without re-arming the warning.
+        assert_eq!(wire_size_estimate(&value, &warned), estimate);
+        assert!(warned.load(Ordering::Relaxed));
     }
+}
 
-    // ── Router registration and lookup via direct handler ────────────
+mod accept_loop_tests {
+    use super::*;
+    use fastmcp_protocol::JsonRpcMessage;
+    use fastmcp_transport::Transport;
+    use fastmcp_transport::sse::SseReader;
+    use fastmcp_transport::websocket::WsClientTransport;
+    use std::io::{Read, Write};
+    use std::net::{SocketAddr, TcpListener, TcpStream};
+
+    const UPGRADE_REQUEST: &str = "GET /mcp HTTP/1.1\r\nHost: localhost\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n";
+
+    /// Reads an HTTP response head without consuming anything after it.
+    fn read_head(stream: &mut TcpStream) -> String {
+        let mut head = Vec::new();
+        let mut byte = [0u8; 1];
+        while !head.ends_with(b"\r\n\r\n") {
+            if stream.read(&mut byte).expect("read response head") == 0 {
+                break;
+            }
+            head.push(byte[0]);
+        }
+        String::from_utf8(head).unwrap()
+    }
 
-    #[test]
-    fn router_registers_tool_and_lists_it() {
-        let mut router = Router::new();
-        router.add_tool(GreetTool);
-        let tools = router.tools();
-        assert_eq!(tools.len(), 1);
-        assert_eq!(tools[0].name, "greet");
+    fn ws_connect(addr: SocketAddr) -> WsClientTransport<TcpStream, TcpStream> {
+        let mut stream = TcpStream::connect(addr).expect("connect");
+        stream.write_all(UPGRADE_REQUEST.as_bytes()).unwrap();
+        let head = read_head(&mut stream);
+        assert!(
+            head.starts_with("HTTP/1.1 101"),
+            "unexpected response: {head}"
+        );
+        assert!(head.contains("Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo="));
+        WsClientTransport::new(stream.try_clone().unwrap(), stream)
+    }
+
+    fn ws_roundtrip(
+        client: &mut WsClientTransport<TcpStream, TcpStream>,
+        request: JsonRpcRequest,
+    ) -> JsonRpcResponse {
+        let cx = Cx::for_testing();
+        client
+            .send(&cx, &JsonRpcMessage::Request(request))
+            .expect("send request");
+        match client.recv(&cx).expect("receive response") {
+            JsonRpcMessage::Response(response) => response,
+            other => panic!("expected a response, got {other:?}"),
+        }
+    }
+
+    /// Opens an event stream and returns it with the advertised POST path.
+    fn sse_connect(addr: SocketAddr) -> (SseReader<TcpStream>, String) {
+        let mut stream = TcpStream::connect(addr).expect("connect");
+        stream
+            .write_all(b"GET /sse HTTP/1.1\r\nHost: localhost\r\nAccept: text/event-stream\r\n\r\n")
+            .unwrap();
+        let head = read_head(&mut stream);
+        assert!(
+            head.starts_with("HTTP/1.1 200"),
+            "unexpected response: {head}"
+        );
+        let mut events = SseReader::new(stream);
+        let endpoint = events
+            .read_endpoint(&Cx::for_testing())
+            .expect("read endpoint")
+            .expect("endpoint event");
+        (events, endpoint)
+    }
+
+    /// POSTs a message and returns the status line of the reply.
+    fn sse_post(addr: SocketAddr, endpoint: &str, message: &impl serde::Serialize) -> String {
+        let body = serde_json::to_string(message).unwrap();
+        let mut stream = TcpStream::connect(addr).expect("connect");
+        write!(
+            stream,
+            "POST {endpoint} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+        read_head(&mut stream)
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    fn sse_roundtrip(
+        addr: SocketAddr,
+        events: &mut SseReader<TcpStream>,
+        endpoint: &str,
+        request: JsonRpcRequest,
+    ) -> JsonRpcResponse {
+        assert_eq!(sse_post(addr, endpoint, &request), "HTTP/1.1 202 Accepted");
+        match events.read_message(&Cx::for_testing()).expect("read event") {
+            Some(JsonRpcMessage::Response(response)) => response,
+            other => panic!("expected a response, got {other:?}"),
+        }
+    }
+
+    fn listener() -> (TcpListener, SocketAddr) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let addr = listener.local_addr().unwrap();
+        (listener, addr)
     }
 
     #[test]
-    fn router_registers_resource_and_lists_it() {
-        let mut router = Router::new();
-        router.add_resource(StaticResource {
-            uri: "test://r1".to_string(),
-            content: "c1".to_string(),
+    fn ws_accept_serves_independent_sessions() {
+        let server = Server::new("accept", "1.0.0").tool(GreetTool).build();
+        let (listener, addr) = listener();
+        let cx = Cx::for_testing();
+
+        let reason = thread::scope(|scope| {
+            let serving = scope.spawn(|| server.serve_ws_listener(&cx, &listener));
+
+            let mut client_a = ws_connect(addr);
+            let mut client_b = ws_connect(addr);
+
+            let response = ws_roundtrip(&mut client_a, initialize_request("client-a"));
+            assert!(response.error.is_none());
+
+            // Initializing one client's session does not initialize the other.
+            let response = ws_roundtrip(&mut client_b, greet_request(2, "Bob"));
+            assert!(response.error.is_some());
+
+            let response = ws_roundtrip(&mut client_b, initialize_request("client-b"));
+            assert!(response.error.is_none());
+
+            assert_eq!(
+                greeting(&ws_roundtrip(&mut client_a, greet_request(2, "Alice"))),
+                "Hello, Alice!"
+            );
+            assert_eq!(
+                greeting(&ws_roundtrip(&mut client_b, greet_request(2, "Bob"))),
+                "Hello, Bob!"
+            );
+            assert_eq!(server.stats().unwrap().active_connections, 2);
+
+            cx.cancel_with(CancelKind::Shutdown, None);
+            serving.join().expect("serving thread")
         });
-        let resources = router.resources();
-        assert_eq!(resources.len(), 1);
-        assert_eq!(resources[0].uri, "test://r1");
+        assert_eq!(reason, ShutdownReason::Cancelled);
+        assert_eq!(server.stats().unwrap().active_connections, 0);
     }
 
     #[test]
-    fn router_registers_prompt_and_lists_it() {
-        let mut router = Router::new();
-        router.add_prompt(GreetingPrompt);
-        let prompts = router.prompts();
-        assert_eq!(prompts.len(), 1);
-        assert_eq!(prompts[0].name, "greeting");
+    fn ws_accept_rejects_clients_over_max_connections() {
+        let server = Server::new("accept", "1.0.0").max_connections(1).build();
+        let (listener, addr) = listener();
+        let cx = Cx::for_testing();
+
+        thread::scope(|scope| {
+            let serving = scope.spawn(|| server.serve_ws_listener(&cx, &listener));
+
+            let mut client = ws_connect(addr);
+            let response = ws_roundtrip(&mut client, initialize_request("client-a"));
+            assert!(response.error.is_none());
+
+            let mut rejected = TcpStream::connect(addr).expect("connect");
+            rejected.write_all(UPGRADE_REQUEST.as_bytes()).unwrap();
+            let head = read_head(&mut rejected);
+            assert!(
+                head.starts_with("HTTP/1.1 503"),
+                "unexpected response: {head}"
+            );
+
+            cx.cancel_with(CancelKind::Shutdown, None);
+            serving.join().expect("serving thread");
+        });
     }
 
     #[test]
-    fn router_counts_match_registrations() {
-        let mut router = Router::new();
-        router.add_tool(GreetTool);
-        router.add_tool(ErrorTool);
-        router.add_resource(StaticResource {
-            uri: "test://a".to_string(),
-            content: "".to_string(),
-        });
-        router.add_prompt(GreetingPrompt);
+    fn sse_accept_serves_independent_sessions() {
+        let server = Server::new("accept", "1.0.0").tool(GreetTool).build();
+        let (listener, addr) = listener();
+        let cx = Cx::for_testing();
 
-        assert_eq!(router.tools_count(), 2);
-        assert_eq!(router.resources_count(), 1);
-        assert_eq!(router.prompts_count(), 1);
+        let reason = thread::scope(|scope| {
+            let serving = scope.spawn(|| server.serve_sse_listener(&cx, &listener));
+
+            let (mut events_a, endpoint_a) = sse_connect(addr);
+            let (mut events_b, endpoint_b) = sse_connect(addr);
+            assert_ne!(endpoint_a, endpoint_b);
+
+            let response = sse_roundtrip(
+                addr,
+                &mut events_a,
+                &endpoint_a,
+                initialize_request("client-a"),
+            );
+            assert!(response.error.is_none());
+
+            let response = sse_roundtrip(addr, &mut events_b, &endpoint_b, greet_request(2, "Bob"));
+            assert!(response.error.is_some());
+
+            let response = sse_roundtrip(
+                addr,
+                &mut events_b,
+                &endpoint_b,
+                initialize_request("client-b"),
+            );
+            assert!(response.error.is_none());
+
+            assert_eq!(
+                greeting(&sse_roundtrip(
+                    addr,
+                    &mut events_a,
+                    &endpoint_a,
+                    greet_request(2, "Alice")
+                )),
+                "Hello, Alice!"
+            );
+
+            // Messages for a session that does not exist are refused.
+            assert_eq!(
+                sse_post(addr, "/messages?session=unknown", &greet_request(2, "Eve")),
+                "HTTP/1.1 404 Not Found"
+            );
+
+            cx.cancel_with(CancelKind::Shutdown, None);
+            serving.join().expect("serving thread")
+        });
+        assert_eq!(reason, ShutdownReason::Cancelled);
     }
 
     #[test]
-    fn router_resource_template_count() {
-        let mut router = Router::new();
-        router.add_resource(TemplateResource);
-        // TemplateResource returns a template, so it should count as a template
-        assert_eq!(router.resource_templates_count(), 1);
+    fn sse_accept_routes_posted_responses_to_pending_requests() {
+        let server = Server::new("accept", "1.0.0").build();
+        let (listener, addr) = listener();
+        let cx = Cx::for_testing();
+
+        thread::scope(|scope| {
+            let serving = scope.spawn(|| server.serve_sse_listener(&cx, &listener));
+
+            let (_events, endpoint) = sse_connect(addr);
+            let id = server.pending_requests.next_request_id();
+            let pending = server.pending_requests.register(id.clone());
+
+            let response = JsonRpcResponse::success(id, serde_json::json!({"roots": []}));
+            assert_eq!(
+                sse_post(addr, &endpoint, &response),
+                "HTTP/1.1 202 Accepted"
+            );
+            let result = pending
+                .recv_timeout(Duration::from_secs(5))
+                .expect("response routed");
+            assert_eq!(result.unwrap(), serde_json::json!({"roots": []}));
+
+            cx.cancel_with(CancelKind::Shutdown, None);
+            serving.join().expect("serving thread");
+        });
     }
 
     #[test]
-    fn router_strict_input_validation_default_is_false() {
-        let router = Router::new();
-        assert!(!router.strict_input_validation());
+    fn sse_accept_rejects_streams_over_max_connections_but_takes_posts() {
+        let server = Server::new("accept", "1.0.0")
+            .tool(GreetTool)
+            .max_connections(1)
+            .build();
+        let (listener, addr) = listener();
+        let cx = Cx::for_testing();
+
+        thread::scope(|scope| {
+            let serving = scope.spawn(|| server.serve_sse_listener(&cx, &listener));
+
+            let (mut events, endpoint) = sse_connect(addr);
+
+            let mut rejected = TcpStream::connect(addr).expect("connect");
+            rejected
+                .write_all(b"GET /sse HTTP/1.1\r\nHost: localhost\r\n\r\n")
+                .unwrap();
+            let head = read_head(&mut rejected);
+            assert!(
+                head.starts_with("HTTP/1.1 503"),
+                "unexpected response: {head}"
+            );
+
+            let response =
+                sse_roundtrip(addr, &mut events, &endpoint, initialize_request("client-a"));
+            assert!(response.error.is_none());
+
+            cx.cancel_with(CancelKind::Shutdown, None);
+            serving.join().expect("serving thread");
+        });
     }
 
     #[test]
-    fn router_strict_input_validation_can_be_set() {
-        let mut router = Router::new();
-        router.set_strict_input_validation(true);
-        assert!(router.strict_input_validation());
+    fn sse_accept_serves_protected_resource_metadata() {
+        let server = Server::new("accept", "1.0.0")
+            .authorization_server_url("https://auth.example.com")
+            .scopes_supported(["read"])
+            .build();
+        let (listener, addr) = listener();
+        let cx = Cx::for_testing();
+
+        thread::scope(|scope| {
+            let serving = scope.spawn(|| server.serve_sse_listener(&cx, &listener));
+
+            let mut stream = TcpStream::connect(addr).expect("connect");
+            stream
+                .write_all(
+                    b"GET /.well-known/oauth-protected-resource HTTP/1.1\r\nHost: mcp.local:8080\r\n\r\n",
+                )
+                .unwrap();
+            let head = read_head(&mut stream);
+            assert!(
+                head.starts_with("HTTP/1.1 200"),
+                "unexpected response: {head}"
+            );
+            let mut body = String::new();
+            stream.read_to_string(&mut body).unwrap();
+            let doc: serde_json::Value = serde_json::from_str(&body).unwrap();
+            assert_eq!(doc["resource"], "http://mcp.local:8080");
+            assert_eq!(
+                doc["authorization_servers"],
+                serde_json::json!(["https://auth.example.com"])
+            );
+            assert_eq!(doc["scopes_supported"], serde_json::json!(["read"]));
+
+            cx.cancel_with(CancelKind::Shutdown, None);
+            serving.join().expect("serving thread");
+        });
     }
-}
 
-// ============================================================================
-// ServerBuilder Tests
-// ============================================================================
+    #[test]
+    fn sse_accept_compresses_responses_when_configured() {
+        use fastmcp_transport::compression::{self, CompressionConfig, ContentEncoding};
 
-mod builder_tests {
-    use super::*;
-    use crate::{DuplicateBehavior, LoggingConfig, ServerBuilder};
-    use fastmcp_console::config::{BannerStyle, ConsoleConfig, TrafficVerbosity};
-    use fastmcp_protocol::ResourceTemplate;
-    use log::Level;
+        let server = Server::new("accept", "1.0.0")
+            .authorization_server_url("https://auth.example.com")
+            .compression(CompressionConfig {
+                min_size: 16,
+                level: 6,
+            })
+            .build();
+        let (listener, addr) = listener();
+        let cx = Cx::for_testing();
 
-    // ── Minimal handler stubs ────────────────────────────────────────
+        let fetch = |accept_encoding: &str| {
+            let mut stream = TcpStream::connect(addr).expect("connect");
+            write!(
+                stream,
+                "GET /.well-known/oauth-protected-resource HTTP/1.1\r\nHost: mcp.local\r\n{accept_encoding}\r\n"
+            )
+            .unwrap();
+            let head = read_head(&mut stream);
+            let mut body = Vec::new();
+            stream.read_to_end(&mut body).unwrap();
+            (head, body)
+        };
 
-    struct StubTool {
-        name: &'static str,
+        thread::scope(|scope| {
+            let serving = scope.spawn(|| server.serve_sse_listener(&cx, &listener));
+
+            let (head, body) = fetch("Accept-Encoding: gzip\r\n");
+            assert!(head.contains("content-encoding: gzip"), "{head}");
+            let body = compression::decompress(&body, ContentEncoding::Gzip, 1 << 20).unwrap();
+            let doc: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(doc["resource"], "http://mcp.local");
+
+            let (head, body) = fetch("");
+            assert!(!head.contains("content-encoding"), "{head}");
+            let doc: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(doc["resource"], "http://mcp.local");
+
+            cx.cancel_with(CancelKind::Shutdown, None);
+            serving.join().expect("serving thread");
+        });
     }
+}
 
-    impl StubTool {
-        fn named(name: &'static str) -> Self {
-            Self { name }
-        }
+mod application_error_code_tests {
+    use super::*;
+    use fastmcp_protocol::CallToolResult;
+
+    /// Fails with `McpError::with_code(code, ..)`.
+    struct CodedErrorTool {
+        code: i64,
     }
 
-    impl ToolHandler for StubTool {
+    impl ToolHandler for CodedErrorTool {
         fn definition(&self) -> Tool {
-            Tool {
-                name: self.name.to_string(),
-                description: Some(format!("Stub tool {}", self.name)),
+            Tool {
+                name: "reserve".to_string(),
+                description: Some("Fails with a domain error code".to_string()),
                 input_schema: serde_json::json!({"type": "object"}),
                 output_schema: None,
                 icon: None,
@@ -6002,725 +11293,1641 @@ mod builder_tests {
             _ctx: &McpContext,
             _arguments: serde_json::Value,
         ) -> McpResult<Vec<Content>> {
-            Ok(vec![Content::Text {
-                text: format!("stub:{}", self.name),
-            }])
+            Err(McpError::with_code(self.code, "Seat already taken"))
         }
     }
 
-    struct StubResource {
-        name: &'static str,
-        uri: String,
+    fn call_reserve(code: i64) -> JsonRpcResponse {
+        let server = Server::new("test-server", "1.0.0")
+            .tool(CodedErrorTool { code })
+            .build();
+        let mut session = initialized_session();
+        let params = CallToolParams {
+            name: "reserve".to_string(),
+            arguments: None,
+            meta: None,
+        };
+        server
+            .dispatch_one(
+                &Cx::for_testing(),
+                &mut session,
+                JsonRpcRequest::new("tools/call", Some(serde_json::to_value(params).unwrap()), 1),
+            )
+            .expect("response")
     }
 
-    impl StubResource {
-        fn named(name: &'static str) -> Self {
-            Self {
-                name,
-                uri: format!("test://{name}"),
-            }
-        }
+    #[test]
+    fn application_code_surfaces_in_jsonrpc_error() {
+        let response = call_reserve(4009);
+
+        assert!(response.result.is_none());
+        let error = response.error.expect("JSON-RPC error");
+        assert_eq!(error.code, 4009);
+        assert_eq!(error.message, "Seat already taken");
     }
 
-    impl ResourceHandler for StubResource {
-        fn definition(&self) -> Resource {
-            Resource {
-                uri: self.uri.clone(),
-                name: self.name.to_string(),
-                description: Some(format!("Stub resource {}", self.name)),
-                mime_type: Some("text/plain".to_string()),
+    #[test]
+    fn reserved_code_is_not_passed_through() {
+        let response = call_reserve(-32601);
+
+        // Rejected codes become an internal error, reported like any other
+        // tool failure rather than as `MethodNotFound`.
+        assert!(response.error.is_none());
+        let result: CallToolResult = serde_json::from_value(response.result.unwrap()).unwrap();
+        assert!(result.is_error);
+    }
+}
+
+mod result_meta_tests {
+    use super::*;
+
+    /// Records the model it "used" in the result's `_meta`.
+    struct ModelTool;
+
+    impl ToolHandler for ModelTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: "summarize".to_string(),
+                description: None,
+                input_schema: serde_json::json!({"type": "object"}),
+                output_schema: None,
                 icon: None,
                 version: None,
                 tags: vec![],
+                annotations: None,
             }
         }
 
-        fn read(&self, _ctx: &McpContext) -> McpResult<Vec<ResourceContent>> {
-            Ok(vec![ResourceContent {
-                uri: self.uri.clone(),
-                mime_type: Some("text/plain".to_string()),
-                text: Some(format!("content:{}", self.name)),
-                blob: None,
+        fn call(&self, ctx: &McpContext, arguments: serde_json::Value) -> McpResult<Vec<Content>> {
+            if arguments.get("meta").is_some() {
+                ctx.set_result_meta("model", serde_json::json!("small"));
+                ctx.set_result_meta("model", serde_json::json!("large"));
+                ctx.set_result_meta("tokens", serde_json::json!(42));
+            }
+            Ok(vec![Content::Text {
+                text: "summary".to_string(),
             }])
         }
     }
 
-    struct StubPrompt {
-        name: &'static str,
+    fn call(arguments: serde_json::Value) -> serde_json::Value {
+        let server = Server::new("test-server", "1.0.0").tool(ModelTool).build();
+        let mut session = initialized_session();
+        let params = CallToolParams {
+            name: "summarize".to_string(),
+            arguments: Some(arguments),
+            meta: None,
+        };
+        let response = server
+            .dispatch_one(
+                &Cx::for_testing(),
+                &mut session,
+                JsonRpcRequest::new("tools/call", Some(serde_json::to_value(params).unwrap()), 1),
+            )
+            .expect("response");
+        serde_json::to_value(response).unwrap()
     }
 
-    impl StubPrompt {
-        fn named(name: &'static str) -> Self {
-            Self { name }
-        }
+    #[test]
+    fn handler_meta_is_serialized_as_underscore_meta() {
+        let response = call(serde_json::json!({"meta": true}));
+
+        assert_eq!(
+            response["result"]["_meta"],
+            serde_json::json!({"model": "large", "tokens": 42})
+        );
+        assert_eq!(response["result"]["content"][0]["text"], "summary");
     }
 
-    impl PromptHandler for StubPrompt {
-        fn definition(&self) -> Prompt {
-            Prompt {
-                name: self.name.to_string(),
-                description: Some(format!("Stub prompt {}", self.name)),
-                arguments: vec![],
-                icon: None,
-                version: None,
-                tags: vec![],
-            }
-        }
+    #[test]
+    fn result_without_meta_omits_field() {
+        let response = call(serde_json::json!({}));
 
-        fn get(
-            &self,
-            _ctx: &McpContext,
-            _arguments: HashMap<String, String>,
-        ) -> McpResult<Vec<PromptMessage>> {
-            Ok(vec![PromptMessage {
-                role: Role::User,
-                content: Content::Text {
-                    text: format!("prompt:{}", self.name),
-                },
-            }])
-        }
+        assert!(response["result"].get("_meta").is_none());
+        assert!(response["result"].get("meta").is_none());
     }
+}
 
-    // ── Basic Construction ───────────────────────────────────────────
+mod capability_gating_tests {
+    use super::*;
+
+    fn request(server: &Server, method: &str, params: serde_json::Value) -> JsonRpcResponse {
+        let mut session = initialized_session();
+        server
+            .dispatch_one(
+                &Cx::for_testing(),
+                &mut session,
+                JsonRpcRequest::new(method, Some(params), 1),
+            )
+            .expect("response")
+    }
 
     #[test]
-    fn builder_new_sets_name_and_version() {
-        let server = ServerBuilder::new("test-server", "1.2.3").build();
-        assert_eq!(server.info().name, "test-server");
-        assert_eq!(server.info().version, "1.2.3");
+    fn tools_call_without_tools_capability_is_method_not_found() {
+        let server = Server::new("test-server", "1.0.0")
+            .prompt(GreetingPrompt)
+            .build();
+        assert!(!server.has_tools());
+
+        let response = request(&server, "tools/call", serde_json::json!({"name": "greet"}));
+
+        let error = response.error.expect("error");
+        assert_eq!(error.code, i32::from(McpErrorCode::MethodNotFound));
+        assert!(error.message.contains("tools/call"), "{}", error.message);
+        assert!(
+            error.message.contains("tools capability"),
+            "{}",
+            error.message
+        );
     }
 
     #[test]
-    fn builder_default_capabilities_include_logging() {
-        let server = ServerBuilder::new("s", "0.1").build();
-        assert!(server.capabilities().logging.is_some());
-        assert!(server.capabilities().tools.is_none());
-        assert!(server.capabilities().resources.is_none());
-        assert!(server.capabilities().prompts.is_none());
-        assert!(server.capabilities().tasks.is_none());
+    fn unknown_tool_on_tools_server_names_the_tool() {
+        let server = Server::new("test-server", "1.0.0").tool(GreetTool).build();
+
+        let response = request(
+            &server,
+            "tools/call",
+            serde_json::json!({"name": "missing"}),
+        );
+
+        let error = response.error.expect("error");
+        assert_eq!(error.code, i32::from(McpErrorCode::MethodNotFound));
+        assert!(error.message.contains("tool: missing"), "{}", error.message);
+        assert!(!error.message.contains("capability"), "{}", error.message);
     }
 
     #[test]
-    fn builder_server_new_delegates_to_builder() {
-        // Server::new returns a ServerBuilder, not a Server
-        let server = Server::new("srv", "0.1").build();
-        assert_eq!(server.info().name, "srv");
+    fn resource_and_prompt_methods_follow_their_capabilities() {
+        let server = Server::new("test-server", "1.0.0").tool(GreetTool).build();
+
+        for method in [
+            "resources/list",
+            "resources/read",
+            "prompts/list",
+            "prompts/get",
+        ] {
+            let response = request(&server, method, serde_json::json!({}));
+            let error = response.error.expect("error");
+            assert_eq!(
+                error.code,
+                i32::from(McpErrorCode::MethodNotFound),
+                "{method}"
+            );
+        }
+
+        let response = request(&server, "tools/list", serde_json::json!({}));
+        assert!(response.error.is_none());
     }
+}
 
-    // ── Tool Registration ────────────────────────────────────────────
+mod canonical_response_tests {
+    use super::*;
+    use fastmcp_protocol::JsonRpcMessage;
+    use fastmcp_transport::{FrameMode, Transport};
+
+    fn call_greet(server: &Server) -> JsonRpcResponse {
+        let mut session = initialized_session();
+        server
+            .dispatch_one(
+                &Cx::for_testing(),
+                &mut session,
+                JsonRpcRequest::new(
+                    "tools/call",
+                    Some(serde_json::json!({"name": "greet", "arguments": {"name": "Ada"}})),
+                    1,
+                ),
+            )
+            .expect("response")
+    }
+
+    fn wire_bytes(server: &Server, response: JsonRpcResponse) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut transport = server.stdio_transport(&[][..], &mut out, FrameMode::Ndjson);
+        transport
+            .send(&Cx::for_testing(), &JsonRpcMessage::Response(response))
+            .expect("send");
+        drop(transport);
+        out
+    }
 
     #[test]
-    fn builder_tool_enables_tools_capability() {
-        let server = ServerBuilder::new("s", "0.1")
-            .tool(StubTool::named("alpha"))
+    fn canonical_mode_writes_identical_bytes_for_equal_responses() {
+        let server = Server::new("test-server", "1.0.0")
+            .tool(GreetTool)
+            .canonical_responses(true)
             .build();
-        assert!(server.capabilities().tools.is_some());
-        assert!(server.has_tools());
+        let dispatched = call_greet(&server);
+
+        // The same response, received with its keys in a different order.
+        let reordered: JsonRpcResponse = serde_json::from_str(&format!(
+            r#"{{"result":{},"id":1,"jsonrpc":"2.0"}}"#,
+            dispatched.result.as_ref().expect("result")
+        ))
+        .expect("parse");
+
+        let a = wire_bytes(&server, dispatched);
+        let b = wire_bytes(&server, reordered);
+        assert_eq!(a, b);
+        assert!(
+            a.starts_with(br#"{"id":1,"jsonrpc":"2.0","result":{"#),
+            "{a:?}"
+        );
     }
 
     #[test]
-    fn builder_registers_multiple_tools() {
-        let server = ServerBuilder::new("s", "0.1")
-            .tool(StubTool::named("a"))
-            .tool(StubTool::named("b"))
-            .tool(StubTool::named("c"))
-            .build();
-        let tools = server.tools();
-        assert_eq!(tools.len(), 3);
-        let names: Vec<_> = tools.iter().map(|t| t.name.as_str()).collect();
-        assert!(names.contains(&"a"));
-        assert!(names.contains(&"b"));
-        assert!(names.contains(&"c"));
+    fn default_mode_keeps_struct_field_order() {
+        let server = Server::new("test-server", "1.0.0").tool(GreetTool).build();
+        let bytes = wire_bytes(&server, call_greet(&server));
+        let line = String::from_utf8(bytes).expect("utf8");
+        assert!(line.starts_with(r#"{"jsonrpc":"2.0","result":"#), "{line}");
+        assert!(line.trim_end().ends_with(r#""id":1}"#), "{line}");
     }
+}
 
-    // ── Resource Registration ────────────────────────────────────────
+mod instructions_fn_tests {
+    use super::*;
+    use crate::testing::TestServer;
+
+    fn initialize(test: &mut TestServer, client_name: &str) -> Option<String> {
+        let client = ClientInfo {
+            name: client_name.to_string(),
+            version: "1.0.0".to_string(),
+        };
+        test.initialize_with(client, ClientCapabilities::default())
+            .expect("initialize")
+            .instructions
+    }
+
+    fn adaptive_server() -> Server {
+        Server::new("test-server", "1.0.0")
+            .instructions("Use the tools.")
+            .instructions_fn(|client, _capabilities| {
+                client
+                    .name
+                    .starts_with("legacy-")
+                    .then(|| format!("Hello {}: call `search` first.", client.name))
+            })
+            .build()
+    }
 
     #[test]
-    fn builder_resource_enables_resources_capability() {
-        let server = ServerBuilder::new("s", "0.1")
-            .resource(StubResource::named("data"))
+    fn instructions_vary_with_client_name() {
+        let mut legacy = TestServer::new(adaptive_server());
+        assert_eq!(
+            initialize(&mut legacy, "legacy-client").as_deref(),
+            Some("Hello legacy-client: call `search` first.")
+        );
+
+        // Clients the function declines get the static instructions
+        let mut modern = TestServer::new(adaptive_server());
+        assert_eq!(
+            initialize(&mut modern, "modern-client").as_deref(),
+            Some("Use the tools.")
+        );
+    }
+
+    #[test]
+    fn session_keeps_its_first_instructions() {
+        let mut test = TestServer::new(adaptive_server());
+
+        initialize(&mut test, "legacy-client");
+        assert_eq!(
+            initialize(&mut test, "modern-client").as_deref(),
+            Some("Hello legacy-client: call `search` first.")
+        );
+    }
+}
+
+mod try_run_tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use fastmcp_protocol::JsonRpcMessage;
+    use fastmcp_transport::Transport;
+    use fastmcp_transport::memory::create_memory_transport_pair;
+
+    use crate::ServerRunError;
+
+    #[test]
+    fn try_run_transport_returns_ok_after_clean_close() {
+        let shutdown_called = Arc::new(AtomicBool::new(false));
+        let shutdown_flag = Arc::clone(&shutdown_called);
+        let server = Server::new("try-run", "1.0.0")
+            .without_banner()
+            .handle_signals(false)
+            .on_shutdown(move || shutdown_flag.store(true, Ordering::SeqCst))
             .build();
-        assert!(server.capabilities().resources.is_some());
-        assert!(server.has_resources());
+        let (mut client, server_transport) = create_memory_transport_pair();
+
+        let client_thread = thread::spawn(move || {
+            let cx = Cx::for_testing();
+            let request = JsonRpcRequest::new("ping", None, 1);
+            client
+                .send(&cx, &JsonRpcMessage::Request(request))
+                .expect("send ping");
+            let reply = client.recv(&cx).expect("receive pong");
+            assert!(matches!(reply, JsonRpcMessage::Response(_)));
+            // Dropping the client closes the server side cleanly.
+        });
+
+        let result = server.try_run_transport(server_transport);
+        client_thread.join().expect("client thread");
+
+        assert!(result.is_ok(), "unexpected error: {result:?}");
+        assert!(shutdown_called.load(Ordering::SeqCst));
     }
 
     #[test]
-    fn builder_registers_multiple_resources() {
-        let server = ServerBuilder::new("s", "0.1")
-            .resource(StubResource::named("r1"))
-            .resource(StubResource::named("r2"))
+    fn try_run_transport_reports_startup_failure() {
+        let server = Server::new("try-run", "1.0.0")
+            .without_banner()
+            .handle_signals(false)
+            .on_startup(|| Err(std::io::Error::other("no database")))
             .build();
-        let resources = server.resources();
-        assert_eq!(resources.len(), 2);
+        let (_client, server_transport) = create_memory_transport_pair();
+
+        let err = server
+            .try_run_transport(server_transport)
+            .expect_err("startup hook failed");
+        assert!(matches!(err, ServerRunError::StartupFailed));
+        assert_eq!(err.exit_code(), 1);
+    }
+}
+
+mod result_format_tests {
+    use super::*;
+    use fastmcp_protocol::{RequestMeta, ResultFormat};
+
+    /// A tool returning a JSON object as compact text.
+    struct WeatherTool;
+
+    impl ToolHandler for WeatherTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: "weather".to_string(),
+                description: None,
+                input_schema: serde_json::json!({"type": "object"}),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+                annotations: None,
+            }
+        }
+
+        fn call(
+            &self,
+            _ctx: &McpContext,
+            _arguments: serde_json::Value,
+        ) -> McpResult<Vec<Content>> {
+            Ok(vec![Content::Text {
+                text: r#"{"city":"Oslo","tempC":4}"#.to_string(),
+            }])
+        }
+    }
+
+    fn call(server: &Server, tool: &str, format: Option<ResultFormat>) -> CallToolResult {
+        let params = CallToolParams {
+            name: tool.to_string(),
+            arguments: Some(serde_json::json!({"name": "Ada"})),
+            meta: format.map(|format| RequestMeta {
+                progress_token: None,
+                result_format: Some(format),
+            }),
+        };
+        server
+            .router
+            .handle_tools_call(
+                &Cx::for_testing(),
+                1,
+                None,
+                params,
+                &Budget::INFINITE,
+                SessionState::new(),
+                None,
+                None,
+                false,
+            )
+            .expect("tools/call")
+    }
+
+    fn text(result: &CallToolResult) -> &str {
+        match &result.content[..] {
+            [Content::Text { text }] => text,
+            other => panic!("expected one text item, got {other:?}"),
+        }
+    }
+
+    fn server() -> Server {
+        Server::new("test", "1.0")
+            .tool(WeatherTool)
+            .tool(GreetTool)
+            .build()
     }
 
     #[test]
-    fn builder_resource_template_enables_resources_capability() {
-        let template = ResourceTemplate {
-            uri_template: "file://{path}".to_string(),
-            name: "file".to_string(),
-            description: None,
-            mime_type: None,
-            icon: None,
-            version: None,
-            tags: vec![],
-        };
-        let server = ServerBuilder::new("s", "0.1")
-            .resource_template(template)
-            .build();
-        assert!(server.capabilities().resources.is_some());
-        assert!(server.has_resources());
-        let templates = server.resource_templates();
-        assert_eq!(templates.len(), 1);
-        assert_eq!(templates[0].name, "file");
+    fn default_format_leaves_result_untouched() {
+        let result = call(&server(), "weather", None);
+        assert_eq!(text(&result), r#"{"city":"Oslo","tempC":4}"#);
+        assert!(result.structured_content.is_none());
     }
 
-    // ── Prompt Registration ──────────────────────────────────────────
+    #[test]
+    fn text_format_pretty_prints_structured_content() {
+        let result = call(&server(), "weather", Some(ResultFormat::Text));
+        assert_eq!(text(&result), "{\n  \"city\": \"Oslo\",\n  \"tempC\": 4\n}");
+        assert!(result.structured_content.is_none());
+    }
 
     #[test]
-    fn builder_prompt_enables_prompts_capability() {
-        let server = ServerBuilder::new("s", "0.1")
-            .prompt(StubPrompt::named("hello"))
-            .build();
-        assert!(server.capabilities().prompts.is_some());
-        assert!(server.has_prompts());
+    fn json_format_sets_structured_content() {
+        let result = call(&server(), "weather", Some(ResultFormat::Json));
+        assert_eq!(
+            result.structured_content,
+            Some(serde_json::json!({"city": "Oslo", "tempC": 4}))
+        );
+        // Text-only clients still get the original content
+        assert_eq!(text(&result), r#"{"city":"Oslo","tempC":4}"#);
+
+        let value = serde_json::to_value(&result).unwrap();
+        assert_eq!(value["structuredContent"]["city"], "Oslo");
     }
 
     #[test]
-    fn builder_registers_multiple_prompts() {
-        let server = ServerBuilder::new("s", "0.1")
-            .prompt(StubPrompt::named("p1"))
-            .prompt(StubPrompt::named("p2"))
-            .prompt(StubPrompt::named("p3"))
-            .build();
-        let prompts = server.prompts();
-        assert_eq!(prompts.len(), 3);
+    fn unstructured_results_ignore_the_format() {
+        for format in [ResultFormat::Text, ResultFormat::Json] {
+            let result = call(&server(), "greet", Some(format));
+            assert_eq!(text(&result), "Hello, Ada!");
+            assert!(result.structured_content.is_none());
+        }
     }
+}
 
-    // ── Mixed Registration ───────────────────────────────────────────
+mod list_changed_tests {
+    use super::*;
+    use std::sync::Mutex;
 
-    #[test]
-    fn builder_mixed_handlers_enable_all_capabilities() {
-        let server = ServerBuilder::new("s", "0.1")
-            .tool(StubTool::named("t"))
-            .resource(StubResource::named("r"))
-            .prompt(StubPrompt::named("p"))
-            .build();
-        assert!(server.has_tools());
-        assert!(server.has_resources());
-        assert!(server.has_prompts());
+    /// A tool that disables `greet` for the calling session.
+    struct LockGreetTool;
+
+    impl ToolHandler for LockGreetTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: "lock_greet".to_string(),
+                description: None,
+                input_schema: serde_json::json!({"type": "object"}),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+                annotations: None,
+            }
+        }
+
+        fn call(&self, ctx: &McpContext, _arguments: serde_json::Value) -> McpResult<Vec<Content>> {
+            ctx.disable_tool("greet");
+            Ok(vec![Content::Text {
+                text: "locked".to_string(),
+            }])
+        }
     }
 
-    #[test]
-    fn builder_no_handlers_means_no_capabilities() {
-        let server = ServerBuilder::new("empty", "0.1").build();
-        assert!(!server.has_tools());
-        assert!(!server.has_resources());
-        assert!(!server.has_prompts());
+    fn server(list_changed: bool) -> Server {
+        Server::new("dynamic", "1.0.0")
+            .tool(GreetTool)
+            .tool(LockGreetTool)
+            .resource(StaticResource {
+                uri: "file://static".to_string(),
+                content: "static".to_string(),
+            })
+            .list_changed_notifications(list_changed)
+            .build()
     }
 
-    // ── Request Timeout ──────────────────────────────────────────────
+    /// Initializes a session and calls `lock_greet`, returning the
+    /// initialize result and the notification methods sent.
+    fn lock_greet(server: &Server) -> (serde_json::Value, Vec<String>) {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let sent_clone = Arc::clone(&sent);
+        let sender: NotificationSender = Arc::new(move |request: JsonRpcRequest| {
+            sent_clone.lock().unwrap().push(request.method);
+        });
+        let cx = Cx::for_testing();
+        let mut session = create_test_session();
 
-    #[test]
-    fn builder_default_request_timeout_is_30() {
-        // Build and check internal state via the server
-        let server = ServerBuilder::new("s", "0.1").build();
-        // Default is 30 seconds; verified via internal state
-        // We can't directly read request_timeout_secs, but we verify the builder
-        // accepted the default without panicking
-        assert_eq!(server.info().name, "s");
+        let init = InitializeParams {
+            protocol_version: "2024-11-05".to_string(),
+            capabilities: ClientCapabilities::default(),
+            client_info: ClientInfo {
+                name: "client".to_string(),
+                version: "1.0.0".to_string(),
+            },
+        };
+        let init = JsonRpcRequest::new("initialize", Some(serde_json::to_value(init).unwrap()), 1);
+        let initialized = server
+            .dispatch_one_with_sender(&cx, &mut session, init, &sender)
+            .and_then(|response| response.result)
+            .expect("initialize result");
+
+        let call = CallToolParams {
+            name: "lock_greet".to_string(),
+            arguments: None,
+            meta: None,
+        };
+        let call = JsonRpcRequest::new("tools/call", Some(serde_json::to_value(call).unwrap()), 2);
+        let response = server
+            .dispatch_one_with_sender(&cx, &mut session, call, &sender)
+            .expect("tools/call response");
+        assert!(response.error.is_none());
+        assert!(!session.state().is_tool_enabled("greet"));
+
+        let methods = sent.lock().unwrap().clone();
+        (initialized, methods)
     }
 
     #[test]
-    fn builder_custom_request_timeout() {
-        let server = ServerBuilder::new("s", "0.1").request_timeout(60).build();
-        // Builder accepted custom timeout without error
-        assert_eq!(server.info().name, "s");
+    fn advertises_and_sends_tools_list_changed_when_enabled() {
+        let (initialized, methods) = lock_greet(&server(true));
+        assert_eq!(initialized["capabilities"]["tools"]["listChanged"], true);
+        assert_eq!(
+            initialized["capabilities"]["resources"]["listChanged"],
+            true
+        );
+        assert!(initialized["capabilities"].get("prompts").is_none());
+
+        // Only the list that changed is announced
+        let list_changed: Vec<_> = methods
+            .iter()
+            .filter(|method| method.ends_with("/list_changed"))
+            .collect();
+        assert_eq!(list_changed, ["notifications/tools/list_changed"]);
     }
 
     #[test]
-    fn builder_zero_timeout_disables_enforcement() {
-        let server = ServerBuilder::new("s", "0.1").request_timeout(0).build();
-        assert_eq!(server.info().name, "s");
+    fn suppresses_list_changed_when_not_advertised() {
+        let (initialized, methods) = lock_greet(&server(false));
+        assert!(
+            initialized["capabilities"]["tools"]
+                .get("listChanged")
+                .is_none()
+        );
+        assert!(
+            !methods
+                .iter()
+                .any(|method| method.ends_with("/list_changed")),
+            "unexpected notifications: {methods:?}"
+        );
     }
+}
 
-    // ── Stats ────────────────────────────────────────────────────────
+mod fallback_tool_tests {
+    use super::*;
+    use crate::FallbackToolHandler;
 
-    #[test]
-    fn builder_stats_enabled_by_default() {
-        let server = ServerBuilder::new("s", "0.1").build();
-        assert!(server.stats().is_some());
+    /// Echoes the requested tool name and arguments back.
+    struct ForwardingFallback {
+        schema: Option<serde_json::Value>,
+        listed: Vec<Tool>,
     }
 
-    #[test]
-    fn builder_without_stats_disables_collection() {
-        let server = ServerBuilder::new("s", "0.1").without_stats().build();
-        assert!(server.stats().is_none());
-        assert!(server.stats_collector().is_none());
+    impl ForwardingFallback {
+        fn new() -> Self {
+            Self {
+                schema: None,
+                listed: Vec::new(),
+            }
+        }
     }
 
-    // ── Error Masking ────────────────────────────────────────────────
+    impl FallbackToolHandler for ForwardingFallback {
+        fn input_schema(&self, _name: &str) -> Option<serde_json::Value> {
+            self.schema.clone()
+        }
 
-    #[test]
-    fn builder_error_masking_disabled_by_default() {
-        let builder = ServerBuilder::new("s", "0.1");
-        assert!(!builder.is_error_masking_enabled());
-    }
+        fn list_tools(&self) -> Vec<Tool> {
+            self.listed.clone()
+        }
 
-    #[test]
-    fn builder_mask_error_details_enables_masking() {
-        let builder = ServerBuilder::new("s", "0.1").mask_error_details(true);
-        assert!(builder.is_error_masking_enabled());
+        fn call(
+            &self,
+            _ctx: &McpContext,
+            name: &str,
+            arguments: serde_json::Value,
+        ) -> McpResult<Vec<Content>> {
+            Ok(vec![Content::Text {
+                text: format!("forwarded {name} {arguments}"),
+            }])
+        }
     }
 
-    #[test]
-    fn builder_mask_error_details_toggle() {
-        let builder = ServerBuilder::new("s", "0.1")
-            .mask_error_details(true)
-            .mask_error_details(false);
-        assert!(!builder.is_error_masking_enabled());
+    fn call(server: &Server, tool: &str, arguments: serde_json::Value) -> McpResult<String> {
+        let params = CallToolParams {
+            name: tool.to_string(),
+            arguments: Some(arguments),
+            meta: None,
+        };
+        let result = server.router.handle_tools_call(
+            &Cx::for_testing(),
+            1,
+            None,
+            params,
+            &Budget::INFINITE,
+            SessionState::new(),
+            None,
+            None,
+            false,
+        )?;
+        match &result.content[..] {
+            [Content::Text { text }] => Ok(text.clone()),
+            other => panic!("expected one text item, got {other:?}"),
+        }
     }
 
-    // ── Strict Input Validation ──────────────────────────────────────
+    fn listed_names(server: &Server) -> Vec<String> {
+        server
+            .router
+            .handle_tools_list(
+                &Cx::for_testing(),
+                fastmcp_protocol::ListToolsParams::default(),
+                None,
+            )
+            .expect("tools/list")
+            .tools
+            .into_iter()
+            .map(|tool| tool.name)
+            .collect()
+    }
 
     #[test]
-    fn builder_strict_input_validation_disabled_by_default() {
-        let builder = ServerBuilder::new("s", "0.1");
-        assert!(!builder.is_strict_input_validation_enabled());
+    fn unknown_tool_is_routed_to_fallback() {
+        let server = Server::new("test", "1.0")
+            .tool(GreetTool)
+            .fallback_tool(ForwardingFallback::new())
+            .build();
+
+        let text = call(&server, "backend/search", serde_json::json!({"q": 1})).expect("call");
+        assert_eq!(text, r#"forwarded backend/search {"q":1}"#);
+        assert!(server.has_tools());
     }
 
     #[test]
-    fn builder_strict_input_validation_enable() {
-        let builder = ServerBuilder::new("s", "0.1").strict_input_validation(true);
-        assert!(builder.is_strict_input_validation_enabled());
+    fn registered_tool_takes_precedence_over_fallback() {
+        let server = Server::new("test", "1.0")
+            .tool(GreetTool)
+            .fallback_tool(ForwardingFallback::new())
+            .build();
+
+        let text = call(&server, "greet", serde_json::json!({"name": "Ada"})).expect("call");
+        assert_eq!(text, "Hello, Ada!");
     }
 
     #[test]
-    fn builder_strict_input_validation_toggle() {
-        let builder = ServerBuilder::new("s", "0.1")
-            .strict_input_validation(true)
-            .strict_input_validation(false);
-        assert!(!builder.is_strict_input_validation_enabled());
+    fn unknown_tool_without_fallback_is_not_found() {
+        let server = Server::new("test", "1.0").tool(GreetTool).build();
+
+        let err = call(&server, "backend/search", serde_json::json!({})).unwrap_err();
+        assert_eq!(err.code, McpErrorCode::MethodNotFound);
     }
 
-    // ── Instructions ─────────────────────────────────────────────────
+    #[test]
+    fn fallback_arguments_are_validated_only_against_supplied_schema() {
+        let unvalidated = Server::new("test", "1.0")
+            .fallback_tool(ForwardingFallback::new())
+            .build();
+        assert!(call(&unvalidated, "anything", serde_json::json!({"q": 1})).is_ok());
+
+        let mut fallback = ForwardingFallback::new();
+        fallback.schema = Some(serde_json::json!({
+            "type": "object",
+            "properties": {"q": {"type": "string"}},
+            "required": ["q"]
+        }));
+        let validated = Server::new("test", "1.0").fallback_tool(fallback).build();
+        let err = call(&validated, "anything", serde_json::json!({"q": 1})).unwrap_err();
+        assert_eq!(err.code, McpErrorCode::InvalidParams);
+    }
 
     #[test]
-    fn builder_instructions_set() {
-        // Instructions are stored internally; verify the builder accepts them.
-        let server = ServerBuilder::new("s", "0.1")
-            .instructions("Use this server for math operations")
+    fn fallback_tools_are_listed_only_when_enumerated() {
+        let server = Server::new("test", "1.0")
+            .tool(GreetTool)
+            .fallback_tool(ForwardingFallback::new())
             .build();
-        assert_eq!(server.info().name, "s");
+        assert_eq!(listed_names(&server), ["greet"]);
+
+        let mut fallback = ForwardingFallback::new();
+        let mut remote = GreetTool.definition();
+        remote.name = "backend/search".to_string();
+        // Shadowed by the registered tool, so listed once
+        fallback.listed = vec![remote, GreetTool.definition()];
+        let server = Server::new("test", "1.0")
+            .tool(GreetTool)
+            .fallback_tool(fallback)
+            .build();
+        assert_eq!(listed_names(&server), ["backend/search", "greet"]);
     }
+}
 
-    // ── Logging Configuration ────────────────────────────────────────
+mod shutdown_reason_tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    use fastmcp_transport::memory::create_memory_transport_pair;
+
+    /// Returns a slot the shutdown hook records its reason into.
+    fn recorded() -> (
+        Arc<Mutex<Option<ShutdownReason>>>,
+        impl FnOnce(&ShutdownReason) + Send + 'static,
+    ) {
+        let slot = Arc::new(Mutex::new(None));
+        let hook_slot = Arc::clone(&slot);
+        let hook = move |reason: &ShutdownReason| {
+            *hook_slot.lock().unwrap() = Some(reason.clone());
+        };
+        (slot, hook)
+    }
 
     #[test]
-    fn builder_log_level() {
-        let server = ServerBuilder::new("s", "0.1")
-            .log_level(Level::Debug)
+    fn hook_receives_clean_close_when_transport_closes() {
+        let (slot, hook) = recorded();
+        let server = Server::new("reason", "1.0.0")
+            .without_banner()
+            .handle_signals(false)
+            .on_shutdown_with_reason(hook)
             .build();
-        assert_eq!(server.info().name, "s");
+        let (client, server_transport) = create_memory_transport_pair();
+        drop(client);
+
+        server
+            .try_run_transport(server_transport)
+            .expect("clean close");
+        assert_eq!(*slot.lock().unwrap(), Some(ShutdownReason::CleanClose));
     }
 
     #[test]
-    fn builder_log_level_filter() {
-        let server = ServerBuilder::new("s", "0.1")
-            .log_level_filter(log::LevelFilter::Warn)
+    fn hook_receives_startup_failed_when_startup_hook_errors() {
+        let (slot, hook) = recorded();
+        let server = Server::new("reason", "1.0.0")
+            .without_banner()
+            .handle_signals(false)
+            .on_startup(|| Err(std::io::Error::other("no database")))
+            .on_shutdown_with_reason(hook)
             .build();
-        assert_eq!(server.info().name, "s");
+        let (_client, server_transport) = create_memory_transport_pair();
+
+        assert!(server.try_run_transport(server_transport).is_err());
+        assert_eq!(*slot.lock().unwrap(), Some(ShutdownReason::StartupFailed));
     }
 
     #[test]
-    fn builder_log_timestamps_and_targets() {
-        let server = ServerBuilder::new("s", "0.1")
-            .log_timestamps(false)
-            .log_targets(false)
+    fn hook_receives_cancelled_when_cx_is_cancelled() {
+        let (slot, hook) = recorded();
+        let server = Server::new("reason", "1.0.0")
+            .without_banner()
+            .handle_signals(false)
+            .on_shutdown_with_reason(hook)
             .build();
-        assert_eq!(server.info().name, "s");
+        let (_client, server_transport) = create_memory_transport_pair();
+        let cx = Cx::for_testing();
+        cx.cancel_with(CancelKind::Shutdown, None);
+
+        server
+            .try_run_transport_with_cx(&cx, server_transport)
+            .expect("cancellation is not an error");
+        assert_eq!(*slot.lock().unwrap(), Some(ShutdownReason::Cancelled));
     }
 
     #[test]
-    fn builder_full_logging_config() {
-        let config = LoggingConfig {
-            level: Level::Trace,
-            timestamps: false,
-            targets: false,
-            file_line: true,
-        };
-        let server = ServerBuilder::new("s", "0.1").logging(config).build();
-        assert_eq!(server.info().name, "s");
+    fn combine_prefers_errors_then_cancellation() {
+        let error = ShutdownReason::Error("boom".to_string());
+        assert_eq!(
+            ShutdownReason::CleanClose.combine(ShutdownReason::Cancelled),
+            ShutdownReason::Cancelled
+        );
+        assert_eq!(ShutdownReason::Cancelled.combine(error.clone()), error);
+        assert_eq!(error.clone().combine(ShutdownReason::CleanClose), error);
+        assert_eq!(error.exit_code(), 1);
+        assert_eq!(ShutdownReason::Cancelled.exit_code(), 0);
     }
+}
 
-    // ── Console Configuration ────────────────────────────────────────
+// ============================================================================
+// Params Limits Tests
+// ============================================================================
+
+mod params_limits_tests {
+    use super::*;
+    use fastmcp_protocol::JsonRpcMessage;
+    use fastmcp_transport::{CodecError, FrameMode, Transport, TransportError};
+
+    /// Receives one message from raw `input` through the server's stdio transport.
+    fn recv_raw(server: &Server, input: &[u8]) -> Result<JsonRpcMessage, TransportError> {
+        let mut out = Vec::new();
+        let mut transport = server.stdio_transport(input, &mut out, FrameMode::Ndjson);
+        transport.recv(&Cx::for_testing())
+    }
+
+    /// A `tools/call` line whose `name` argument is wrapped in `levels` arrays.
+    fn nested_call(levels: usize) -> Vec<u8> {
+        format!(
+            r#"{{"jsonrpc":"2.0","method":"tools/call","id":3,"params":{{"name":"greet","arguments":{{"name":{}"Ada"{}}}}}}}"#,
+            "[".repeat(levels),
+            "]".repeat(levels)
+        )
+        .into_bytes()
+    }
 
-    #[test]
-    fn builder_console_config_full() {
-        let config = ConsoleConfig::new()
-            .with_banner(BannerStyle::Compact)
-            .plain_mode();
-        let server = ServerBuilder::new("s", "0.1")
-            .with_console_config(config)
-            .build();
-        let cc = server.console_config();
-        assert!(cc.force_plain);
+    fn is_too_deep(result: &Result<JsonRpcMessage, TransportError>) -> bool {
+        matches!(
+            result,
+            Err(TransportError::Codec(CodecError::InvalidFrame {
+                id: Some(RequestId::Number(3)),
+                reason,
+            })) if reason.contains("nested too deeply")
+        )
     }
 
     #[test]
-    fn builder_without_banner() {
-        let server = ServerBuilder::new("s", "0.1").without_banner().build();
-        let cc = server.console_config();
-        assert!(!cc.show_banner);
-        assert_eq!(cc.banner_style, BannerStyle::None);
+    fn deeply_nested_messages_are_rejected_before_parsing() {
+        let server = Server::new("test", "1.0").tool(GreetTool).build();
+
+        let result = recv_raw(&server, &nested_call(100_000));
+        assert!(is_too_deep(&result), "unexpected result: {result:?}");
     }
 
     #[test]
-    fn builder_with_banner_compact() {
-        let server = ServerBuilder::new("s", "0.1")
-            .with_banner(BannerStyle::Compact)
+    fn oversized_messages_are_rejected() {
+        let server = Server::new("test", "1.0")
+            .tool(GreetTool)
+            .max_json_size(1024)
             .build();
-        assert_eq!(server.info().name, "s");
+
+        let line = format!(
+            r#"{{"jsonrpc":"2.0","method":"tools/call","id":3,"params":{{"name":"greet","arguments":{{"name":"{}"}}}}}}"#,
+            "x".repeat(4096)
+        );
+        let result = recv_raw(&server, line.as_bytes());
+        assert!(
+            matches!(
+                result,
+                Err(TransportError::Codec(CodecError::MessageTooLarge(_)))
+            ),
+            "unexpected result: {result:?}"
+        );
     }
 
     #[test]
-    fn builder_traffic_logging() {
-        let server = ServerBuilder::new("s", "0.1")
-            .with_traffic_logging(TrafficVerbosity::Full)
+    fn depth_limit_is_configurable() {
+        let server = Server::new("test", "1.0")
+            .tool(GreetTool)
+            .max_json_depth(3)
             .build();
-        assert_eq!(server.info().name, "s");
+
+        // params -> arguments -> one array is three levels deep
+        assert!(recv_raw(&server, &nested_call(1)).is_ok());
+        assert!(is_too_deep(&recv_raw(&server, &nested_call(2))));
     }
+}
 
-    #[test]
-    fn builder_plain_mode() {
-        let server = ServerBuilder::new("s", "0.1").plain_mode().build();
-        assert!(server.console_config().force_plain);
+// ============================================================================
+// Audit Sink Tests
+// ============================================================================
+
+mod audit_sink_tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    use crate::{AuditEvent, AuditOutcome, AuditSink, FileAuditSink};
+
+    #[derive(Default)]
+    struct RecordingSink {
+        events: Mutex<Vec<AuditEvent>>,
     }
 
-    #[test]
-    fn builder_force_color() {
-        let server = ServerBuilder::new("s", "0.1").force_color().build();
-        assert_eq!(server.info().name, "s");
+    impl AuditSink for RecordingSink {
+        fn record(&self, event: AuditEvent) {
+            self.events.lock().unwrap().push(event);
+        }
+    }
+
+    fn greet_with_token(token: &str, id: i64) -> fastmcp_protocol::JsonRpcRequest {
+        fastmcp_protocol::JsonRpcRequest::new(
+            "tools/call",
+            Some(serde_json::json!({
+                "name": "greet",
+                "arguments": { "name": "Ada" },
+                "auth": format!("Bearer {token}")
+            })),
+            id,
+        )
     }
 
     #[test]
-    fn builder_periodic_stats() {
-        let server = ServerBuilder::new("s", "0.1")
-            .with_periodic_stats(10)
+    fn sink_receives_one_event_per_request_with_subject_and_outcome() {
+        let sink = Arc::new(RecordingSink::default());
+        let provider = TokenAuthProvider::new(StaticTokenVerifier::new([(
+            "good-token",
+            AuthContext::with_subject("user-1"),
+        )]));
+        let server = Server::new("test-server", "1.0.0")
+            .tool(GreetTool)
+            .auth_provider(provider)
+            .audit_sink(sink.clone())
             .build();
-        assert_eq!(server.info().name, "s");
-    }
+        let cx = Cx::for_testing();
+        let mut session = initialized_session();
+        let sender: NotificationSender = Arc::new(|_| {});
 
-    // ── DuplicateBehavior ────────────────────────────────────────────
+        let requests = [
+            greet_with_token("good-token", 1),
+            greet_with_token("bad-token", 2),
+            fastmcp_protocol::JsonRpcRequest::new("ping", None, 3),
+            fastmcp_protocol::JsonRpcRequest::notification("notifications/initialized", None),
+        ];
+        for request in requests {
+            server.handle_request(
+                &cx,
+                &mut session,
+                request,
+                &sender,
+                &create_test_request_sender(),
+            );
+        }
+
+        let events = sink.events.lock().unwrap();
+        assert_eq!(events.len(), 3, "notifications are not audited");
+
+        assert_eq!(events[0].subject.as_deref(), Some("user-1"));
+        assert_eq!(events[0].method, "tools/call");
+        assert_eq!(events[0].request_id, RequestId::Number(1));
+        assert_eq!(events[0].outcome, AuditOutcome::Success);
+
+        assert_eq!(events[1].subject, None);
+        assert_eq!(events[1].request_id, RequestId::Number(2));
+        assert!(matches!(
+            events[1].outcome,
+            AuditOutcome::Error { code, .. } if code == i32::from(McpErrorCode::ResourceForbidden)
+        ));
+
+        assert_eq!(events[2].subject, None);
+        assert_eq!(events[2].method, "ping");
+        assert_eq!(events[2].outcome, AuditOutcome::Success);
+    }
 
     #[test]
-    fn builder_on_duplicate_default_is_warn() {
-        // Default behavior is Warn (keeps original, logs warning)
-        let server = ServerBuilder::new("s", "0.1")
-            .tool(StubTool::named("dup"))
-            .tool(StubTool::named("dup"))
+    fn file_sink_appends_json_lines() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("audit.jsonl");
+        let server = Server::new("test-server", "1.0.0")
+            .tool(GreetTool)
+            .audit_sink(Arc::new(
+                FileAuditSink::open(&path).expect("open audit file"),
+            ))
             .build();
-        // With Warn default, the second registration keeps original
-        let tools = server.tools();
-        assert_eq!(tools.len(), 1);
-        assert_eq!(tools[0].name, "dup");
+        let cx = Cx::for_testing();
+        let mut session = initialized_session();
+        let sender: NotificationSender = Arc::new(|_| {});
+
+        for id in [1, 2] {
+            server.handle_request(
+                &cx,
+                &mut session,
+                fastmcp_protocol::JsonRpcRequest::new("ping", None, id),
+                &sender,
+                &create_test_request_sender(),
+            );
+        }
+
+        let contents = std::fs::read_to_string(&path).expect("read audit file");
+        let lines: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("JSON line"))
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["method"], "ping");
+        assert_eq!(lines[0]["requestId"], 1);
+        assert_eq!(lines[0]["outcome"]["status"], "success");
+        assert!(lines[0]["subject"].is_null());
+        assert!(lines[0]["latencyMs"].is_number());
+        assert_eq!(lines[1]["requestId"], 2);
+    }
+}
+
+// ============================================================================
+// Resource Content Contract Tests
+// ============================================================================
+
+mod resource_content_contract_tests {
+    use super::*;
+
+    /// A resource returning fixed content items.
+    struct FixedContentResource {
+        contents: Vec<ResourceContent>,
+    }
+
+    impl ResourceHandler for FixedContentResource {
+        fn definition(&self) -> Resource {
+            Resource {
+                uri: "resource://fixed".to_string(),
+                name: "Fixed".to_string(),
+                description: None,
+                mime_type: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+            }
+        }
+
+        fn read(&self, _ctx: &McpContext) -> McpResult<Vec<ResourceContent>> {
+            Ok(self.contents.clone())
+        }
+    }
+
+    fn read_fixed(contents: Vec<ResourceContent>) -> McpResult<Vec<ResourceContent>> {
+        let mut router = Router::new();
+        router.add_resource(FixedContentResource { contents });
+        let params = ReadResourceParams {
+            uri: "resource://fixed".to_string(),
+            range: None,
+            if_none_match: None,
+            if_modified_since: None,
+            meta: None,
+        };
+        router
+            .handle_resources_read(
+                &Cx::for_testing(),
+                1,
+                None,
+                &params,
+                &Budget::INFINITE,
+                SessionState::new(),
+                None,
+                None,
+            )
+            .map(|result| result.contents)
     }
 
     #[test]
-    fn builder_on_duplicate_ignore_keeps_original() {
-        let server = ServerBuilder::new("s", "0.1")
-            .on_duplicate(DuplicateBehavior::Ignore)
-            .tool(StubTool::named("dup"))
-            .tool(StubTool::named("dup"))
-            .build();
-        let tools = server.tools();
-        assert_eq!(tools.len(), 1);
+    fn binary_resource_reads_back_as_base64_blob() {
+        let bytes = [0x89, b'P', b'N', b'G', 0x00, 0xFF];
+        let contents = read_fixed(vec![ResourceContent::binary(
+            "resource://fixed",
+            "image/png",
+            &bytes,
+        )])
+        .expect("read");
+
+        assert_eq!(contents.len(), 1);
+        assert!(contents[0].text.is_none());
+        assert_eq!(contents[0].blob.as_deref(), Some("iVBORwD/"));
+        assert_eq!(contents[0].mime_type.as_deref(), Some("image/png"));
     }
 
     #[test]
-    fn builder_on_duplicate_replace() {
-        let server = ServerBuilder::new("s", "0.1")
-            .on_duplicate(DuplicateBehavior::Replace)
-            .tool(StubTool::named("dup"))
-            .tool(StubTool::named("dup"))
-            .build();
-        let tools = server.tools();
-        assert_eq!(tools.len(), 1);
-        assert_eq!(tools[0].name, "dup");
+    fn wrapped_blob_is_normalized() {
+        let mut content = ResourceContent::binary("resource://fixed", "image/png", &[0; 60]);
+        let blob = content.blob.take().unwrap();
+        let (head, tail) = blob.split_at(40);
+        content.blob = Some(format!("{head}\r\n{tail}\n"));
+
+        let contents = read_fixed(vec![content]).expect("read");
+        assert_eq!(contents[0].blob.as_deref(), Some(blob.as_str()));
     }
 
     #[test]
-    fn builder_on_duplicate_error_logs_and_skips() {
-        // With Error behavior, duplicate registration fails but builder doesn't panic
-        let server = ServerBuilder::new("s", "0.1")
-            .on_duplicate(DuplicateBehavior::Error)
-            .tool(StubTool::named("dup"))
-            .tool(StubTool::named("dup"))
-            .build();
-        // Only the first registration succeeds
-        let tools = server.tools();
-        assert_eq!(tools.len(), 1);
+    fn content_with_both_text_and_blob_is_rejected() {
+        let content = ResourceContent {
+            text: Some("also text".to_string()),
+            ..ResourceContent::binary("resource://fixed", "image/png", b"png")
+        };
+
+        let err = read_fixed(vec![content]).unwrap_err();
+        assert_eq!(err.code, McpErrorCode::InternalError);
+        assert!(
+            err.message.contains("both text and blob"),
+            "{}",
+            err.message
+        );
     }
 
     #[test]
-    fn builder_on_duplicate_applies_to_resources() {
-        let server = ServerBuilder::new("s", "0.1")
-            .on_duplicate(DuplicateBehavior::Ignore)
-            .resource(StubResource::named("r"))
-            .resource(StubResource::named("r"))
-            .build();
-        let resources = server.resources();
-        assert_eq!(resources.len(), 1);
+    fn content_with_neither_text_nor_blob_is_rejected() {
+        let err = read_fixed(vec![ResourceContent::reference("resource://fixed")]).unwrap_err();
+        assert!(
+            err.message.contains("neither text nor blob"),
+            "{}",
+            err.message
+        );
     }
 
     #[test]
-    fn builder_on_duplicate_applies_to_prompts() {
-        let server = ServerBuilder::new("s", "0.1")
-            .on_duplicate(DuplicateBehavior::Ignore)
-            .prompt(StubPrompt::named("p"))
-            .prompt(StubPrompt::named("p"))
-            .build();
-        let prompts = server.prompts();
-        assert_eq!(prompts.len(), 1);
+    fn invalid_base64_blob_is_rejected() {
+        let content = ResourceContent {
+            blob: Some("not base64!".to_string()),
+            ..ResourceContent::binary("resource://fixed", "image/png", b"")
+        };
+
+        let err = read_fixed(vec![content]).unwrap_err();
+        assert!(err.message.contains("not valid base64"), "{}", err.message);
     }
+}
 
-    // ── Middleware Registration ───────────────────────────────────────
+// ============================================================================
+// Handler Log Message Tests
+// ============================================================================
 
-    #[test]
-    fn builder_middleware_registration() {
-        let events = Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
-        let server = ServerBuilder::new("s", "0.1")
-            .middleware(RecordingMiddleware::new("m1", events.clone()))
-            .middleware(RecordingMiddleware::new("m2", events.clone()))
-            .build();
-        // Server builds successfully with middleware
-        assert_eq!(server.info().name, "s");
+mod context_log_tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    use fastmcp_core::MissingCapabilityPolicy;
+    use fastmcp_core::logging::Level;
+
+    /// Logs at debug through the context, then returns.
+    struct ChattyTool;
+
+    impl ToolHandler for ChattyTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: "chatty".to_string(),
+                description: None,
+                input_schema: serde_json::json!({"type": "object"}),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+                annotations: None,
+            }
+        }
+
+        fn call(&self, ctx: &McpContext, _args: serde_json::Value) -> McpResult<Vec<Content>> {
+            ctx.log_with_logger(Level::Debug, "chatty", serde_json::json!({"step": "start"}))?;
+            Ok(vec![Content::Text {
+                text: "done".to_string(),
+            }])
+        }
     }
 
-    // ── Auth Provider ────────────────────────────────────────────────
+    /// Sets `level` (if any) on `session`, calls `chatty`, and returns the
+    /// response with the log messages the tool sent.
+    fn call_chatty(
+        server: &Server,
+        mut session: Session,
+        level: Option<LogLevel>,
+    ) -> (JsonRpcResponse, Vec<LogMessageParams>) {
+        let cx = Cx::for_testing();
+        let notifications = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&notifications);
+        let sender: NotificationSender = Arc::new(move |req| recorded.lock().unwrap().push(req));
+
+        if let Some(level) = level {
+            let set_level = fastmcp_protocol::JsonRpcRequest::new(
+                "logging/setLevel",
+                Some(serde_json::to_value(SetLogLevelParams { level }).unwrap()),
+                1i64,
+            );
+            server
+                .handle_request(
+                    &cx,
+                    &mut session,
+                    set_level,
+                    &sender,
+                    &create_test_request_sender(),
+                )
+                .unwrap();
+        }
 
-    #[test]
-    fn builder_auth_provider_static_token() {
-        let ctx = fastmcp_core::AuthContext::with_subject("test-user");
-        let server = ServerBuilder::new("s", "0.1")
-            .auth_provider(TokenAuthProvider::new(StaticTokenVerifier::new(vec![(
-                "secret-token".to_string(),
-                ctx,
-            )])))
-            .build();
-        assert_eq!(server.info().name, "s");
+        let call = fastmcp_protocol::JsonRpcRequest::new(
+            "tools/call",
+            Some(serde_json::json!({"name": "chatty", "arguments": {}})),
+            2i64,
+        );
+        let response = server
+            .handle_request(
+                &cx,
+                &mut session,
+                call,
+                &sender,
+                &create_test_request_sender(),
+            )
+            .unwrap();
+
+        let logs = notifications
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|req| req.method == "notifications/message")
+            .map(|req| serde_json::from_value::<LogMessageParams>(req.params.clone().unwrap()))
+            .map(Result::unwrap)
+            .filter(|log| log.logger.as_deref() == Some("chatty"))
+            .collect();
+        (response, logs)
+    }
+
+    /// Whether the call produced a tool error result.
+    fn tool_failed(response: &JsonRpcResponse) -> bool {
+        response.result.as_ref().expect("tool result")["isError"] == true
     }
 
-    #[test]
-    fn builder_auth_provider_allow_all() {
-        let server = ServerBuilder::new("s", "0.1")
-            .auth_provider(crate::AllowAllAuthProvider)
-            .build();
-        assert_eq!(server.info().name, "s");
+    fn debug_server(policy: MissingCapabilityPolicy) -> Server {
+        Server::new("test-server", "1.0.0")
+            .log_level(Level::Debug)
+            .missing_capability_policy(policy)
+            .tool(ChattyTool)
+            .build()
     }
 
-    // ── Lifecycle Hooks ──────────────────────────────────────────────
+    /// A server without the `logging` capability, and a session that would
+    /// otherwise accept debug messages.
+    fn server_without_logging(policy: MissingCapabilityPolicy) -> (Server, Session) {
+        let server = Server::new("test-server", "1.0.0")
+            .log_level(Level::Debug)
+            .disable_method("logging/setLevel")
+            .missing_capability_policy(policy)
+            .tool(ChattyTool)
+            .build();
+        assert!(server.capabilities().logging.is_none());
+        let mut session = initialized_session();
+        session.set_log_level(LogLevel::Debug);
+        (server, session)
+    }
 
     #[test]
-    fn builder_on_startup_hook() {
-        let server = ServerBuilder::new("s", "0.1")
-            .on_startup(|| -> Result<(), std::io::Error> { Ok(()) })
-            .build();
-        assert_eq!(server.info().name, "s");
+    fn debug_log_is_suppressed_at_info() {
+        let server = debug_server(MissingCapabilityPolicy::Error);
+        let (response, logs) = call_chatty(&server, initialized_session(), Some(LogLevel::Info));
+        assert!(!tool_failed(&response), "{response:?}");
+        assert!(logs.is_empty());
     }
 
     #[test]
-    fn builder_on_shutdown_hook() {
-        let server = ServerBuilder::new("s", "0.1")
-            .on_shutdown(|| {
-                // cleanup
-            })
-            .build();
-        assert_eq!(server.info().name, "s");
+    fn debug_log_is_delivered_at_debug() {
+        let server = debug_server(MissingCapabilityPolicy::Error);
+        let (response, logs) = call_chatty(&server, initialized_session(), Some(LogLevel::Debug));
+        assert!(!tool_failed(&response), "{response:?}");
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].level, LogLevel::Debug);
+        assert_eq!(logs[0].data, serde_json::json!({"step": "start"}));
     }
 
     #[test]
-    fn builder_both_lifecycle_hooks() {
-        let server = ServerBuilder::new("s", "0.1")
-            .on_startup(|| -> Result<(), std::io::Error> { Ok(()) })
-            .on_shutdown(|| {})
-            .build();
-        assert_eq!(server.info().name, "s");
+    fn log_errors_before_the_client_sets_a_level() {
+        let server = debug_server(MissingCapabilityPolicy::Error);
+        let (response, logs) = call_chatty(&server, initialized_session(), None);
+        assert!(tool_failed(&response));
+        let text = &response.result.as_ref().unwrap()["content"][0]["text"];
+        assert!(
+            text.as_str().unwrap().contains("Logging not available"),
+            "{text}"
+        );
+        assert!(logs.is_empty());
     }
 
-    // ── Mount ────────────────────────────────────────────────────────
+    #[test]
+    fn log_is_skipped_before_the_client_sets_a_level_when_silent() {
+        let server = debug_server(MissingCapabilityPolicy::SilentNoOp);
+        let (response, logs) = call_chatty(&server, initialized_session(), None);
+        assert!(!tool_failed(&response), "{response:?}");
+        assert!(logs.is_empty());
+    }
 
     #[test]
-    fn builder_mount_server_with_prefix() {
-        let child = ServerBuilder::new("child", "0.1")
-            .tool(StubTool::named("do_thing"))
-            .resource(StubResource::named("data"))
-            .prompt(StubPrompt::named("ask"))
-            .build();
+    fn log_errors_without_the_logging_capability() {
+        let (server, session) = server_without_logging(MissingCapabilityPolicy::Error);
+        let (response, logs) = call_chatty(&server, session, None);
+        assert!(tool_failed(&response));
+        assert!(logs.is_empty());
+    }
 
-        let parent = ServerBuilder::new("parent", "0.1")
-            .mount(child, Some("child"))
-            .build();
+    #[test]
+    fn log_is_skipped_without_the_logging_capability_when_silent() {
+        let (server, session) = server_without_logging(MissingCapabilityPolicy::SilentNoOp);
+        let (response, logs) = call_chatty(&server, session, None);
+        assert!(!tool_failed(&response), "{response:?}");
+        assert!(logs.is_empty());
+    }
+}
 
-        assert!(parent.has_tools());
-        assert!(parent.has_resources());
-        assert!(parent.has_prompts());
+// ============================================================================
+// Resource Template Parameter Tests
+// ============================================================================
 
-        let tools = parent.tools();
-        assert_eq!(tools.len(), 1);
-        assert_eq!(tools[0].name, "child/do_thing");
+mod template_parameter_tests {
+    use super::*;
+    use fastmcp_protocol::{ListResourceTemplatesParams, TemplateParameter};
+
+    fn row_template() -> ResourceTemplate {
+        ResourceTemplate {
+            uri_template: "db://{table}/{id:int}".to_string(),
+            name: "Row".to_string(),
+            description: Some("One row of a table".to_string()),
+            mime_type: Some("application/json".to_string()),
+            icon: None,
+            version: None,
+            tags: vec![],
+            parameters: vec![
+                TemplateParameter {
+                    name: "table".to_string(),
+                    description: Some("Table to read from".to_string()),
+                    required: true,
+                    param_type: Some("string".to_string()),
+                },
+                TemplateParameter {
+                    name: "id".to_string(),
+                    description: Some("Primary key".to_string()),
+                    required: true,
+                    param_type: Some("int".to_string()),
+                },
+            ],
+        }
     }
 
     #[test]
-    fn builder_mount_server_without_prefix() {
-        let child = ServerBuilder::new("child", "0.1")
-            .tool(StubTool::named("alpha"))
-            .build();
+    fn templates_listing_includes_parameter_metadata() {
+        let mut router = Router::new();
+        router.add_resource_template(row_template());
 
-        let parent = ServerBuilder::new("parent", "0.1")
-            .mount(child, None)
-            .build();
+        let result = router
+            .handle_resource_templates_list(
+                &Cx::for_testing(),
+                ListResourceTemplatesParams::default(),
+                None,
+            )
+            .unwrap();
 
-        let tools = parent.tools();
-        assert_eq!(tools.len(), 1);
-        assert_eq!(tools[0].name, "alpha");
+        let [template] = result.resource_templates.as_slice() else {
+            panic!("expected one template, got {:?}", result.resource_templates);
+        };
+        let params: Vec<_> = template
+            .parameters
+            .iter()
+            .map(|p| (p.name.as_str(), p.param_type.as_deref(), p.required))
+            .collect();
+        assert_eq!(
+            params,
+            [("table", Some("string"), true), ("id", Some("int"), true)]
+        );
+        assert_eq!(
+            template.parameters[1].description.as_deref(),
+            Some("Primary key")
+        );
     }
 
     #[test]
-    fn builder_mount_tools_only() {
-        let child = ServerBuilder::new("child", "0.1")
-            .tool(StubTool::named("t"))
-            .resource(StubResource::named("r"))
-            .prompt(StubPrompt::named("p"))
-            .build();
+    fn templates_listing_publishes_untyped_uri_template() {
+        let mut router = Router::new();
+        router.add_resource(EchoTemplateResource {
+            uri_template: "db://{table}/{id:int}/{active:bool}",
+        });
 
-        let parent = ServerBuilder::new("parent", "0.1")
-            .mount_tools(child, Some("ns"))
-            .build();
+        let result = router
+            .handle_resource_templates_list(
+                &Cx::for_testing(),
+                ListResourceTemplatesParams::default(),
+                None,
+            )
+            .unwrap();
 
-        assert!(parent.has_tools());
-        assert!(!parent.has_resources());
-        assert!(!parent.has_prompts());
+        let [template] = result.resource_templates.as_slice() else {
+            panic!("expected one template, got {:?}", result.resource_templates);
+        };
+        assert_eq!(template.uri_template, "db://{table}/{id}/{active}");
+        let params: Vec<_> = template
+            .parameters
+            .iter()
+            .map(|p| (p.name.as_str(), p.param_type.as_deref()))
+            .collect();
+        assert_eq!(params, [("id", Some("int")), ("active", Some("bool"))]);
     }
 
     #[test]
-    fn builder_mount_resources_only() {
-        let child = ServerBuilder::new("child", "0.1")
-            .tool(StubTool::named("t"))
-            .resource(StubResource::named("r"))
+    fn templates_list_response_serializes_parameters() {
+        let server = Server::new("test-server", "1.0.0")
+            .resource_template(row_template())
             .build();
+        let cx = Cx::for_testing();
+        let mut session = initialized_session();
 
-        let parent = ServerBuilder::new("parent", "0.1")
-            .mount_resources(child, Some("ns"))
-            .build();
+        let request = fastmcp_protocol::JsonRpcRequest::new("resources/templates/list", None, 1i64);
+        let response = server.dispatch_one(&cx, &mut session, request).unwrap();
+        let result = response.result.expect("templates list result");
 
-        assert!(!parent.has_tools());
-        assert!(parent.has_resources());
+        assert_eq!(
+            result["resourceTemplates"][0]["parameters"][1],
+            serde_json::json!({
+                "name": "id",
+                "description": "Primary key",
+                "required": true,
+                "type": "int"
+            })
+        );
     }
+}
 
-    #[test]
-    fn builder_mount_prompts_only() {
-        let child = ServerBuilder::new("child", "0.1")
-            .tool(StubTool::named("t"))
-            .prompt(StubPrompt::named("p"))
-            .build();
+// ============================================================================
+// Tool Result Cap Tests
+// ============================================================================
 
-        let parent = ServerBuilder::new("parent", "0.1")
-            .mount_prompts(child, Some("ns"))
-            .build();
+mod result_cap_tests {
+    use super::*;
+    use crate::TRUNCATION_MARKER;
 
-        assert!(!parent.has_tools());
-        assert!(parent.has_prompts());
+    /// Returns `count` text items, each `text` repeated `repeat` times.
+    struct BulkTool {
+        count: usize,
+        text: &'static str,
+        repeat: usize,
     }
 
-    #[test]
-    fn builder_mount_multiple_servers() {
-        let db = ServerBuilder::new("db", "0.1")
-            .tool(StubTool::named("query"))
-            .build();
-        let api = ServerBuilder::new("api", "0.1")
-            .tool(StubTool::named("fetch"))
-            .build();
+    impl ToolHandler for BulkTool {
+        fn definition(&self) -> Tool {
+            Tool {
+                name: "bulk".to_string(),
+                description: None,
+                input_schema: serde_json::json!({"type": "object"}),
+                output_schema: None,
+                icon: None,
+                version: None,
+                tags: vec![],
+                annotations: None,
+            }
+        }
 
-        let main = ServerBuilder::new("main", "0.1")
-            .mount(db, Some("db"))
-            .mount(api, Some("api"))
-            .build();
+        fn call(&self, _ctx: &McpContext, _args: serde_json::Value) -> McpResult<Vec<Content>> {
+            Ok((0..self.count)
+                .map(|_| Content::Text {
+                    text: self.text.repeat(self.repeat),
+                })
+                .collect())
+        }
+    }
 
-        let tools = main.tools();
-        assert_eq!(tools.len(), 2);
-        let names: Vec<_> = tools.iter().map(|t| t.name.as_str()).collect();
-        assert!(names.contains(&"db/query"));
-        assert!(names.contains(&"api/fetch"));
+    fn call_bulk(tool: BulkTool, configure: impl FnOnce(&mut Router)) -> CallToolResult {
+        let mut router = Router::new();
+        router.add_tool(tool);
+        configure(&mut router);
+        router
+            .handle_tools_call(
+                &Cx::for_testing(),
+                1,
+                None,
+                CallToolParams {
+                    name: "bulk".to_string(),
+                    arguments: Some(serde_json::json!({})),
+                    meta: None,
+                },
+                &Budget::INFINITE,
+                SessionState::new(),
+                None,
+                None,
+                false,
+            )
+            .expect("tools/call")
     }
 
-    // ── Fluent Chaining ──────────────────────────────────────────────
+    fn content_bytes(result: &CallToolResult) -> usize {
+        serde_json::to_vec(&result.content).unwrap().len()
+    }
+
+    fn ends_with_marker(result: &CallToolResult) -> bool {
+        matches!(
+            result.content.last(),
+            Some(Content::Text { text }) if text == TRUNCATION_MARKER
+        )
+    }
 
     #[test]
-    fn builder_full_fluent_chain() {
-        let events = Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
-        let server = ServerBuilder::new("full-server", "2.0.0")
-            .instructions("A fully configured server")
-            .on_duplicate(DuplicateBehavior::Error)
-            .request_timeout(120)
-            .mask_error_details(true)
-            .strict_input_validation(true)
-            .log_level(Level::Debug)
-            .log_timestamps(false)
-            .without_banner()
-            .plain_mode()
-            .tool(StubTool::named("tool_a"))
-            .resource(StubResource::named("res_a"))
-            .prompt(StubPrompt::named("prompt_a"))
-            .middleware(RecordingMiddleware::new("audit", events))
-            .auth_provider(crate::AllowAllAuthProvider)
-            .on_startup(|| -> Result<(), std::io::Error> { Ok(()) })
-            .on_shutdown(|| {})
-            .build();
+    fn results_within_the_caps_are_untouched() {
+        let tool = BulkTool {
+            count: 3,
+            text: "x",
+            repeat: 10,
+        };
+        let result = call_bulk(tool, |_| {});
+        assert_eq!(result.content.len(), 3);
+        assert!(!ends_with_marker(&result));
+        assert!(result.meta.is_none());
+    }
 
-        assert_eq!(server.info().name, "full-server");
-        assert_eq!(server.info().version, "2.0.0");
-        assert!(server.has_tools());
-        assert!(server.has_resources());
-        assert!(server.has_prompts());
-        assert_eq!(server.tools().len(), 1);
-        assert_eq!(server.resources().len(), 1);
-        assert_eq!(server.prompts().len(), 1);
+    #[test]
+    fn item_count_cap_truncates_with_marker() {
+        let tool = BulkTool {
+            count: 20,
+            text: "x",
+            repeat: 10,
+        };
+        let result = call_bulk(tool, |router| router.set_max_content_items(5));
+
+        assert_eq!(result.content.len(), 5);
+        assert!(ends_with_marker(&result));
+        assert!(!result.is_error);
+        assert!(result.structured_content.is_none());
+        assert_eq!(
+            result.meta,
+            Some(serde_json::json!({
+                "truncation": {
+                    "truncated": true,
+                    "reason": "maxContentItems",
+                    "omittedItems": 16
+                }
+            }))
+        );
     }
 
-    // ── into_router ──────────────────────────────────────────────────
+    #[test]
+    fn byte_cap_truncates_with_marker() {
+        let tool = BulkTool {
+            count: 10,
+            text: "x",
+            repeat: 300,
+        };
+        let result = call_bulk(tool, |router| router.set_max_result_bytes(1000));
+
+        assert!(content_bytes(&result) <= 1000, "{}", content_bytes(&result));
+        assert!(ends_with_marker(&result));
+        let note = &result.meta.expect("result meta")["truncation"];
+        assert_eq!(note["reason"], "maxResultBytes");
+        // The third item was cut short to fill the remaining space
+        assert_eq!(note["omittedItems"], 7);
+        match &result.content[2] {
+            Content::Text { text } => assert!(text.len() < 300 && !text.is_empty()),
+            other => panic!("expected text, got {other:?}"),
+        }
+    }
 
     #[test]
-    fn builder_into_router_preserves_components() {
-        let server = ServerBuilder::new("s", "0.1")
-            .tool(StubTool::named("t1"))
-            .tool(StubTool::named("t2"))
-            .resource(StubResource::named("r1"))
-            .prompt(StubPrompt::named("p1"))
+    fn byte_cap_cuts_text_at_a_character_boundary() {
+        let tool = BulkTool {
+            count: 1,
+            text: "\u{e9}",
+            repeat: 5000,
+        };
+        let result = call_bulk(tool, |router| router.set_max_result_bytes(1001));
+
+        assert!(content_bytes(&result) <= 1001, "{}", content_bytes(&result));
+        assert_eq!(result.content.len(), 2);
+        match &result.content[0] {
+            Content::Text { text } => {
+                assert!(!text.is_empty());
+                assert!(text.chars().all(|c| c == '\u{e9}'));
+            }
+            other => panic!("expected text, got {other:?}"),
+        }
+        assert!(ends_with_marker(&result));
+    }
+
+    #[test]
+    fn builder_applies_the_caps() {
+        let server = Server::new("test", "1.0")
+            .max_content_items(7)
+            .max_result_bytes(4096)
             .build();
+        assert_eq!(server.router.max_content_items(), 7);
+        assert_eq!(server.router.max_result_bytes(), 4096);
 
-        let router = server.into_router();
-        assert_eq!(router.tools_count(), 2);
-        assert_eq!(router.resources_count(), 1);
-        assert_eq!(router.prompts_count(), 1);
+        let defaults = Server::new("test", "1.0").build();
+        assert_eq!(
+            defaults.router.max_content_items(),
+            crate::DEFAULT_MAX_CONTENT_ITEMS
+        );
+        assert_eq!(
+            defaults.router.max_result_bytes(),
+            crate::DEFAULT_MAX_RESULT_BYTES
+        );
     }
 
-    // ── Task Manager ─────────────────────────────────────────────────
-
     #[test]
-    fn builder_without_task_manager() {
-        let server = ServerBuilder::new("s", "0.1").build();
-        assert!(server.task_manager().is_none());
-        assert!(server.capabilities().tasks.is_none());
+    fn router_raises_caps_too_small_for_the_marker() {
+        let mut router = Router::new();
+        router.set_max_content_items(0);
+        router.set_max_result_bytes(1);
+        assert_eq!(router.max_content_items(), 1);
+        assert_eq!(router.max_result_bytes(), crate::router::min_result_bytes());
     }
 
     #[test]
-    fn builder_with_task_manager_enables_tasks_capability() {
-        let tm = TaskManager::new();
-        let server = ServerBuilder::new("s", "0.1")
-            .with_task_manager(tm.into_shared())
+    fn builder_raises_caps_too_small_for_the_marker() {
+        let server = Server::new("test", "1.0")
+            .max_content_items(0)
+            .max_result_bytes(1)
             .build();
-        assert!(server.task_manager().is_some());
-        assert!(server.capabilities().tasks.is_some());
+        let max_items = server.router.max_content_items();
+        let max_bytes = server.router.max_result_bytes();
+        assert_eq!(max_items, 1);
+
+        let tool = BulkTool {
+            count: 3,
+            text: "x",
+            repeat: 10,
+        };
+        let result = call_bulk(tool, |router| {
+            router.set_max_content_items(max_items);
+            router.set_max_result_bytes(max_bytes);
+        });
+        // Only the marker is left, and it fits
+        assert_eq!(result.content.len(), 1);
+        assert!(ends_with_marker(&result));
+        assert_eq!(content_bytes(&result), max_bytes);
     }
 }
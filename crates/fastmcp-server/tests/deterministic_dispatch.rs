@@ -0,0 +1,181 @@
+//! Dispatch ordering tests.
+//!
+//! Replaying captured traffic only helps debugging if the server handles it
+//! the same way every time. These tests build the same server repeatedly,
+//! dispatch the same batch to each, and compare the responses and the log
+//! records produced along the way. The recording logger is process-wide, so
+//! the tests live in their own binary.
+
+use std::cell::RefCell;
+use std::sync::Once;
+
+use asupersync::Cx;
+use fastmcp_core::{McpContext, McpResult};
+use fastmcp_protocol::{Content, JsonRpcRequest, Tool};
+use fastmcp_server::{Router, Server, Session, ToolHandler};
+use log::{LevelFilter, Log, Metadata, Record};
+
+const RUNS: usize = 8;
+
+thread_local! {
+    /// `target: message` of each record logged on this thread while recording.
+    static RECORDED: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+struct ThreadRecorder;
+
+impl Log for ThreadRecorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        RECORDED.with(|recorded| {
+            if let Some(records) = recorded.borrow_mut().as_mut() {
+                records.push(format!("{}: {}", record.target(), record.args()));
+            }
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+/// Runs `f`, returning its result and the records it logged on this thread.
+fn record_logs<T>(f: impl FnOnce() -> T) -> (T, Vec<String>) {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        log::set_logger(&ThreadRecorder).expect("no other logger in this test binary");
+        log::set_max_level(LevelFilter::Trace);
+    });
+
+    RECORDED.with(|recorded| *recorded.borrow_mut() = Some(Vec::new()));
+    let result = f();
+    let records = RECORDED
+        .with(|recorded| recorded.borrow_mut().take())
+        .unwrap_or_default();
+    (result, records)
+}
+
+struct NamedTool(&'static str);
+
+impl ToolHandler for NamedTool {
+    fn definition(&self) -> Tool {
+        Tool {
+            name: self.0.to_string(),
+            description: None,
+            input_schema: serde_json::json!({"type": "object"}),
+            output_schema: None,
+            icon: None,
+            version: None,
+            tags: vec![],
+            annotations: None,
+        }
+    }
+
+    fn call(&self, _ctx: &McpContext, _args: serde_json::Value) -> McpResult<Vec<Content>> {
+        Ok(vec![Content::Text {
+            text: format!("called {}", self.0),
+        }])
+    }
+}
+
+fn backend(names: &[&'static str]) -> Server {
+    names
+        .iter()
+        .fold(Server::new("backend", "1.0.0"), |builder, name| {
+            builder.tool(NamedTool(name))
+        })
+        .build()
+}
+
+/// A server whose tools come from two mounts that partly overlap.
+fn build_server() -> Server {
+    Server::new("deterministic", "1.0.0")
+        .mount(
+            backend(&["delta", "alpha", "echo", "charlie", "bravo"]),
+            Some("svc"),
+        )
+        .mount(backend(&["echo", "alpha", "foxtrot"]), Some("svc"))
+        .build()
+}
+
+fn batch() -> Vec<JsonRpcRequest> {
+    let call = |name: &str, id: i64| {
+        JsonRpcRequest::new(
+            "tools/call",
+            Some(serde_json::json!({"name": name, "arguments": {}})),
+            id,
+        )
+    };
+    vec![
+        JsonRpcRequest::new(
+            "initialize",
+            Some(serde_json::json!({
+                "protocolVersion": "2024-11-05",
+                "capabilities": {},
+                "clientInfo": {"name": "replay", "version": "1.0.0"}
+            })),
+            1,
+        ),
+        JsonRpcRequest::new("tools/list", None, 2),
+        call("svc/charlie", 3),
+        call("svc/alpha", 4),
+        call("missing", 5),
+        JsonRpcRequest::new("ping", None, 6),
+        call("svc/foxtrot", 7),
+    ]
+}
+
+/// Builds a fresh server, dispatches the batch, and returns the serialized
+/// responses with everything logged on the way.
+fn replay() -> (Vec<String>, Vec<String>) {
+    record_logs(|| {
+        let server = build_server();
+        let cx = Cx::for_testing();
+        let mut session = Session::new(server.info().clone(), server.capabilities().clone());
+        batch()
+            .into_iter()
+            .filter_map(|request| server.dispatch_one(&cx, &mut session, request))
+            .map(|response| serde_json::to_string(&response).expect("serialize response"))
+            .collect()
+    })
+}
+
+#[test]
+fn repeated_replays_produce_identical_responses_and_logs() {
+    let (responses, logs) = replay();
+    assert_eq!(responses.len(), batch().len());
+    assert!(
+        logs.iter()
+            .any(|record| record.starts_with("fastmcp::mount")),
+        "expected mount conflict warnings, got {logs:#?}"
+    );
+
+    for _ in 1..RUNS {
+        let (again_responses, again_logs) = replay();
+        assert_eq!(again_responses, responses);
+        assert_eq!(again_logs, logs);
+    }
+}
+
+#[test]
+fn mount_reports_conflicts_in_name_order() {
+    for _ in 0..RUNS {
+        let mut router = Router::new();
+        for name in ["delta", "bravo", "alpha", "charlie"] {
+            router.add_tool(NamedTool(name));
+        }
+        let mut other = Router::new();
+        for name in ["charlie", "delta", "alpha", "bravo"] {
+            other.add_tool(NamedTool(name));
+        }
+
+        let result = router.mount(other, None);
+
+        assert_eq!(
+            result.warnings,
+            ["alpha", "bravo", "charlie", "delta"]
+                .map(|name| format!("Tool '{name}' already exists, will be overwritten"))
+        );
+    }
+}
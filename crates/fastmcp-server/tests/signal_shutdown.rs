@@ -0,0 +1,114 @@
+//! Signal handling tests.
+//!
+//! Each test re-runs its own binary as a stdio server, sends it SIGTERM and
+//! checks that the shutdown hook ran before the process exited.
+
+#![cfg(unix)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use fastmcp_server::Server;
+
+/// Set in the child process to the path the shutdown hook writes to.
+const CHILD_MARKER_ENV: &str = "FASTMCP_SIGNAL_TEST_MARKER";
+
+/// Set in the child process to how long the shutdown hook takes, in ms.
+const CHILD_HOOK_DELAY_ENV: &str = "FASTMCP_SIGNAL_TEST_HOOK_DELAY_MS";
+
+const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn ready_path(marker: &Path) -> PathBuf {
+    marker.with_extension("ready")
+}
+
+/// Runs a stdio server whose lifecycle hooks write marker files, if this
+/// process was started as a child by [`sigterm_child`].
+fn run_child_server_if_requested() {
+    let Some(marker) = std::env::var_os(CHILD_MARKER_ENV).map(PathBuf::from) else {
+        return;
+    };
+    let delay = std::env::var(CHILD_HOOK_DELAY_ENV)
+        .ok()
+        .and_then(|ms| ms.parse().ok())
+        .map_or(Duration::ZERO, Duration::from_millis);
+    let ready = ready_path(&marker);
+    Server::new("signal-test", "1.0.0")
+        .on_startup(move || fs::write(ready, "ready"))
+        .on_shutdown(move || {
+            thread::sleep(delay);
+            let _ = fs::write(marker, "shutdown");
+        })
+        .build()
+        .run_stdio()
+}
+
+fn wait_for(what: &str, mut done: impl FnMut() -> bool) {
+    let deadline = Instant::now() + TEST_TIMEOUT;
+    while !done() {
+        assert!(Instant::now() < deadline, "timed out waiting for {what}");
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+/// Starts `test` as a child server, sends it SIGTERM once it is ready, and
+/// returns its exit status and the shutdown marker's contents.
+fn sigterm_child(test: &str, hook_delay: Duration) -> (ExitStatus, String) {
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let marker = dir.path().join("shutdown.marker");
+    let ready = ready_path(&marker);
+
+    // Keep stdin open so the server is blocked on a read when the signal lands
+    let mut child = Command::new(std::env::current_exe().expect("test binary path"))
+        .args([test, "--exact", "--nocapture"])
+        .env(CHILD_MARKER_ENV, &marker)
+        .env(CHILD_HOOK_DELAY_ENV, hook_delay.as_millis().to_string())
+        .env("FASTMCP_NO_BANNER", "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("spawn child server");
+
+    wait_for("server startup", || ready.exists());
+
+    let kill = Command::new("kill")
+        .args(["-TERM", &child.id().to_string()])
+        .status()
+        .expect("run kill");
+    assert!(kill.success());
+
+    let mut status = None;
+    wait_for("server exit", || {
+        status = child.try_wait().expect("poll child");
+        status.is_some()
+    });
+    drop(child.stdin.take());
+
+    let contents = fs::read_to_string(&marker).unwrap_or_default();
+    (status.expect("exit status"), contents)
+}
+
+#[test]
+fn sigterm_runs_shutdown_hook() {
+    run_child_server_if_requested();
+
+    let (status, contents) = sigterm_child("sigterm_runs_shutdown_hook", Duration::ZERO);
+    assert!(status.success());
+    assert_eq!(contents, "shutdown");
+}
+
+#[test]
+fn sigterm_waits_for_slow_shutdown_hook() {
+    run_child_server_if_requested();
+
+    let (status, contents) = sigterm_child(
+        "sigterm_waits_for_slow_shutdown_hook",
+        Duration::from_secs(3),
+    );
+    assert!(status.success());
+    assert_eq!(contents, "shutdown");
+}
@@ -0,0 +1,135 @@
+//! Tower adapter tests.
+//!
+//! Wires [`McpService`] into a small tower stack and checks that requests,
+//! including a `tools/call`, flow through the stack's middleware to the
+//! server and back.
+
+#![cfg(feature = "tower")]
+
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll, Waker};
+
+use fastmcp_core::{McpContext, McpResult};
+use fastmcp_protocol::{Content, JsonRpcRequest, JsonRpcResponse, Tool};
+use fastmcp_server::{McpService, Server, ToolHandler};
+use tower::{ServiceBuilder, ServiceExt};
+
+struct GreetTool;
+
+impl ToolHandler for GreetTool {
+    fn definition(&self) -> Tool {
+        Tool {
+            name: "greet".to_string(),
+            description: None,
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {"name": {"type": "string"}},
+                "required": ["name"]
+            }),
+            output_schema: None,
+            icon: None,
+            version: None,
+            tags: vec![],
+            annotations: None,
+        }
+    }
+
+    fn call(&self, _ctx: &McpContext, arguments: serde_json::Value) -> McpResult<Vec<Content>> {
+        let name = arguments["name"].as_str().unwrap_or("World");
+        Ok(vec![Content::Text {
+            text: format!("Hello, {name}!"),
+        }])
+    }
+}
+
+/// Drives a future to completion; the adapter's futures are always ready.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        std::thread::yield_now();
+    }
+}
+
+fn initialize(id: i64) -> JsonRpcRequest {
+    JsonRpcRequest::new(
+        "initialize",
+        Some(serde_json::json!({
+            "protocolVersion": "2024-11-05",
+            "capabilities": {},
+            "clientInfo": {"name": "tower-test", "version": "1.0.0"}
+        })),
+        id,
+    )
+}
+
+fn greet(id: i64) -> JsonRpcRequest {
+    JsonRpcRequest::new(
+        "tools/call",
+        Some(serde_json::json!({"name": "greet", "arguments": {"name": "Ada"}})),
+        id,
+    )
+}
+
+fn first_text(response: &JsonRpcResponse) -> &str {
+    response.result.as_ref().expect("result")["content"][0]["text"]
+        .as_str()
+        .expect("text content")
+}
+
+#[test]
+fn tools_call_flows_through_a_tower_stack() {
+    let server = Arc::new(Server::new("tower-test", "1.0.0").tool(GreetTool).build());
+    let seen = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&seen);
+    let service = ServiceBuilder::new()
+        .map_request(move |request: JsonRpcRequest| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            request
+        })
+        .service(McpService::new(server));
+
+    // Each call goes through a clone, as stacks that clone per request do;
+    // the clones share one session.
+    let response = block_on(service.clone().oneshot(initialize(1)))
+        .unwrap()
+        .expect("initialize response");
+    assert!(response.error.is_none(), "{:?}", response.error);
+
+    let notification = JsonRpcRequest::notification("notifications/initialized", None);
+    assert!(
+        block_on(service.clone().oneshot(notification))
+            .unwrap()
+            .is_none()
+    );
+
+    let response = block_on(service.clone().oneshot(greet(2)))
+        .unwrap()
+        .expect("tools/call response");
+    assert!(response.error.is_none(), "{:?}", response.error);
+    assert_eq!(first_text(&response), "Hello, Ada!");
+
+    assert_eq!(seen.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn each_connection_gets_its_own_session() {
+    let server = Arc::new(Server::new("tower-test", "1.0.0").tool(GreetTool).build());
+    let first = McpService::new(server);
+    block_on(first.clone().oneshot(initialize(1))).unwrap();
+
+    let response = block_on(first.clone().oneshot(greet(2))).unwrap().unwrap();
+    assert_eq!(first_text(&response), "Hello, Ada!");
+
+    // A new connection has not been initialized yet
+    let response = block_on(first.connect().oneshot(greet(1)))
+        .unwrap()
+        .unwrap();
+    assert!(response.error.is_some());
+}
@@ -1,8 +1,21 @@
 //! Message codec for framing JSON-RPC messages.
 //!
-//! MCP uses newline-delimited JSON (NDJSON) for message framing.
-
-use fastmcp_protocol::{JsonRpcMessage, JsonRpcRequest, JsonRpcResponse};
+//! MCP uses newline-delimited JSON (NDJSON) for message framing. Hosts that
+//! reuse an LSP transport can select [`FrameMode::ContentLength`] instead.
+
+use fastmcp_protocol::{JsonRpcMessage, JsonRpcRequest, JsonRpcResponse, RequestId};
+
+/// How messages are delimited on a byte stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrameMode {
+    /// One JSON message per line (the MCP default).
+    #[default]
+    Ndjson,
+    /// LSP-style frames: `Content-Length: N\r\n\r\n` followed by N bytes of JSON.
+    ///
+    /// Other headers (such as `Content-Type`) are accepted and ignored.
+    ContentLength,
+}
 
 /// Codec for encoding/decoding JSON-RPC messages.
 #[derive(Debug)]
@@ -13,6 +26,12 @@ pub struct Codec {
     read_pos: usize,
     /// Maximum allowed message size in bytes.
     max_message_size: usize,
+    /// Maximum nesting of arrays and objects in a message.
+    max_depth: usize,
+    /// How messages are delimited.
+    frame_mode: FrameMode,
+    /// Whether outgoing messages are encoded as canonical JSON.
+    canonical: bool,
 }
 
 impl Default for Codec {
@@ -24,17 +43,38 @@ impl Default for Codec {
 /// Threshold for compacting buffer (when read_pos exceeds this fraction of capacity).
 const COMPACT_THRESHOLD: usize = 4096;
 
+/// Default nesting limit, matching `serde_json`'s own recursion limit.
+const DEFAULT_MAX_DEPTH: usize = 128;
+
 impl Codec {
-    /// Creates a new codec with default settings (10MB limit).
+    /// Creates a new codec with default settings (10MB limit, 128 levels of nesting).
     #[must_use]
     pub fn new() -> Self {
         Self {
             buffer: Vec::new(),
             read_pos: 0,
             max_message_size: 10 * 1024 * 1024, // 10MB
+            max_depth: DEFAULT_MAX_DEPTH,
+            frame_mode: FrameMode::Ndjson,
+            canonical: false,
+        }
+    }
+
+    /// Creates a codec using the given framing.
+    #[must_use]
+    pub fn with_frame_mode(frame_mode: FrameMode) -> Self {
+        Self {
+            frame_mode,
+            ..Self::new()
         }
     }
 
+    /// Returns how this codec delimits messages.
+    #[must_use]
+    pub fn frame_mode(&self) -> FrameMode {
+        self.frame_mode
+    }
+
     /// Returns the maximum allowed message size in bytes.
     #[must_use]
     pub fn max_message_size(&self) -> usize {
@@ -51,15 +91,69 @@ impl Codec {
         }
     }
 
+    /// Returns how deeply arrays and objects may nest in an incoming message.
+    #[must_use]
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Sets how deeply arrays and objects may nest in an incoming message.
+    ///
+    /// The message object itself counts as depth 1. Deeper frames are
+    /// rejected before they reach the JSON parser.
+    pub fn set_max_depth(&mut self, depth: usize) {
+        self.max_depth = depth;
+    }
+
+    /// Checks a single frame against this codec's limits, then parses it
+    /// like [`decode_frame`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CodecError::MessageTooLarge`] for an oversized frame, and
+    /// [`CodecError::InvalidFrame`] for one that nests too deeply or cannot
+    /// be parsed.
+    pub fn parse_frame(&self, frame: &[u8]) -> Result<JsonRpcMessage, CodecError> {
+        if frame.len() > self.max_message_size {
+            return Err(CodecError::MessageTooLarge(frame.len()));
+        }
+        self.check_depth(frame)?;
+        decode_frame(frame)
+    }
+
+    /// Rejects a frame that nests deeper than [`max_depth`](Self::max_depth).
+    fn check_depth(&self, frame: &[u8]) -> Result<(), CodecError> {
+        if nests_deeper_than(frame, self.max_depth) {
+            return Err(CodecError::InvalidFrame {
+                id: salvage_request_id(frame),
+                reason: format!("message nested too deeply (limit {})", self.max_depth),
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns whether outgoing messages are encoded as canonical JSON.
+    #[must_use]
+    pub fn is_canonical(&self) -> bool {
+        self.canonical
+    }
+
+    /// Encodes outgoing messages as canonical JSON (sorted keys, no
+    /// whitespace), so logically equal messages produce identical bytes.
+    ///
+    /// Off by default: canonical encoding goes through an intermediate
+    /// [`serde_json::Value`] and is slower.
+    pub fn set_canonical(&mut self, canonical: bool) {
+        self.canonical = canonical;
+    }
+
     /// Encodes a request to bytes.
     ///
     /// # Errors
     ///
     /// Returns an error if serialization fails.
     pub fn encode_request(&self, request: &JsonRpcRequest) -> Result<Vec<u8>, CodecError> {
-        let mut bytes = serde_json::to_vec(request)?;
-        bytes.push(b'\n');
-        Ok(bytes)
+        Ok(self.frame(self.serialize(request)?))
     }
 
     /// Encodes a response to bytes.
@@ -68,9 +162,30 @@ impl Codec {
     ///
     /// Returns an error if serialization fails.
     pub fn encode_response(&self, response: &JsonRpcResponse) -> Result<Vec<u8>, CodecError> {
-        let mut bytes = serde_json::to_vec(response)?;
-        bytes.push(b'\n');
-        Ok(bytes)
+        Ok(self.frame(self.serialize(response)?))
+    }
+
+    fn serialize<T: serde::Serialize>(&self, message: &T) -> serde_json::Result<Vec<u8>> {
+        if self.canonical {
+            fastmcp_protocol::to_canonical_vec(message)
+        } else {
+            serde_json::to_vec(message)
+        }
+    }
+
+    /// Wraps a serialized message in this codec's framing.
+    fn frame(&self, mut body: Vec<u8>) -> Vec<u8> {
+        match self.frame_mode {
+            FrameMode::Ndjson => {
+                body.push(b'\n');
+                body
+            }
+            FrameMode::ContentLength => {
+                let mut bytes = format!("Content-Length: {}\r\n\r\n", body.len()).into_bytes();
+                bytes.append(&mut body);
+                bytes
+            }
+        }
     }
 
     /// Decodes bytes into a message, returning any complete messages.
@@ -79,7 +194,7 @@ impl Codec {
     ///
     /// # Errors
     ///
-    /// Returns an error if a complete line fails to parse or if the buffer exceeds the limit.
+    /// Returns an error if a complete frame fails to parse or if the buffer exceeds the limit.
     pub fn decode(&mut self, data: &[u8]) -> Result<Vec<JsonRpcMessage>, CodecError> {
         // Calculate unread data size
         let unread_len = self.buffer.len() - self.read_pos;
@@ -101,6 +216,24 @@ impl Codec {
         self.buffer.extend_from_slice(data);
 
         let mut messages = Vec::new();
+        match self.frame_mode {
+            FrameMode::Ndjson => self.decode_lines(&mut messages)?,
+            FrameMode::ContentLength => self.decode_content_length(&mut messages)?,
+        }
+
+        // Check remaining unread data
+        let remaining = self.buffer.len() - self.read_pos;
+        if remaining > self.max_message_size {
+            self.buffer.clear();
+            self.read_pos = 0;
+            return Err(CodecError::MessageTooLarge(remaining));
+        }
+
+        Ok(messages)
+    }
+
+    /// Decodes every complete line in the buffer.
+    fn decode_lines(&mut self, messages: &mut Vec<JsonRpcMessage>) -> Result<(), CodecError> {
         let mut start = self.read_pos;
 
         #[allow(clippy::mut_range_bound)]
@@ -113,6 +246,11 @@ impl Codec {
                     return Err(CodecError::MessageTooLarge(line_len));
                 }
                 let line = &self.buffer[start..i];
+                if let Err(err) = self.check_depth(line) {
+                    self.buffer.clear();
+                    self.read_pos = 0;
+                    return Err(err);
+                }
                 if !line.is_empty() {
                     let msg: JsonRpcMessage = serde_json::from_slice(line)?;
                     messages.push(msg);
@@ -123,16 +261,54 @@ impl Codec {
 
         // Update read position instead of draining for each decode call
         self.read_pos = start;
+        Ok(())
+    }
 
-        // Check remaining unread data
-        let remaining = self.buffer.len() - self.read_pos;
-        if remaining > self.max_message_size {
-            self.buffer.clear();
-            self.read_pos = 0;
-            return Err(CodecError::MessageTooLarge(remaining));
-        }
+    /// Decodes every complete `Content-Length` frame in the buffer.
+    fn decode_content_length(
+        &mut self,
+        messages: &mut Vec<JsonRpcMessage>,
+    ) -> Result<(), CodecError> {
+        loop {
+            let unread = &self.buffer[self.read_pos..];
+            let Some(header_len) = unread.windows(4).position(|w| w == b"\r\n\r\n") else {
+                return Ok(());
+            };
+
+            let length = unread[..header_len]
+                .split(|&b| b == b'\n')
+                .map(parse_content_length_header)
+                .try_fold(None, |found, parsed| parsed.map(|n| n.or(found)));
+            let length = match length {
+                Ok(Some(length)) => length,
+                unusable => {
+                    // The stream can't be resynchronized without a length.
+                    self.buffer.clear();
+                    self.read_pos = 0;
+                    return Err(unusable.err().unwrap_or_else(|| CodecError::InvalidFrame {
+                        id: None,
+                        reason: "missing Content-Length header".to_string(),
+                    }));
+                }
+            };
+            if length > self.max_message_size {
+                self.buffer.clear();
+                self.read_pos = 0;
+                return Err(CodecError::MessageTooLarge(length));
+            }
 
-        Ok(messages)
+            let body_start = header_len + 4;
+            let Some(body) = unread.get(body_start..body_start + length) else {
+                return Ok(());
+            };
+            if let Err(err) = self.check_depth(body) {
+                self.buffer.clear();
+                self.read_pos = 0;
+                return Err(err);
+            }
+            self.read_pos += body_start + length;
+            messages.push(serde_json::from_slice(body)?);
+        }
     }
 
     /// Clears the internal buffer.
@@ -142,6 +318,132 @@ impl Codec {
     }
 }
 
+/// Parses one header line of a [`FrameMode::ContentLength`] frame.
+///
+/// Returns the length for a `Content-Length` header (matched
+/// case-insensitively), `None` for any other header, and an error for a
+/// line that is not a header or a length that is not a number. A trailing
+/// `\r` is ignored.
+pub(crate) fn parse_content_length_header(line: &[u8]) -> Result<Option<usize>, CodecError> {
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    let invalid = |reason: String| CodecError::InvalidFrame { id: None, reason };
+
+    let text = std::str::from_utf8(line).map_err(|e| invalid(format!("invalid header: {e}")))?;
+    let Some((name, value)) = text.split_once(':') else {
+        return Err(invalid(format!("invalid header line: {text:?}")));
+    };
+    if !name.trim().eq_ignore_ascii_case("content-length") {
+        return Ok(None);
+    }
+    value
+        .trim()
+        .parse()
+        .map(Some)
+        .map_err(|_| invalid(format!("invalid Content-Length: {:?}", value.trim())))
+}
+
+/// Parses a single frame (one line, without the trailing newline) into a message.
+///
+/// Unlike [`Codec::decode`], a frame that is not valid UTF-8 or not valid
+/// JSON-RPC is reported as [`CodecError::InvalidFrame`], carrying whatever
+/// request id could be salvaged from the raw bytes so the caller can answer
+/// with a parse error for that id.
+///
+/// # Errors
+///
+/// Returns [`CodecError::InvalidFrame`] if the frame cannot be parsed.
+pub fn decode_frame(frame: &[u8]) -> Result<JsonRpcMessage, CodecError> {
+    serde_json::from_slice(frame).map_err(|e| CodecError::InvalidFrame {
+        id: salvage_request_id(frame),
+        reason: e.to_string(),
+    })
+}
+
+/// Returns whether the JSON text in `bytes` nests arrays and objects deeper
+/// than `max_depth`.
+///
+/// A linear scan of the raw bytes, so the limit holds before any parser
+/// recurses. Brackets inside strings don't count.
+fn nests_deeper_than(bytes: &[u8], max_depth: usize) -> bool {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for &byte in bytes {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => {
+                depth += 1;
+                if depth > max_depth {
+                    return true;
+                }
+            }
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Best-effort extraction of the JSON-RPC `id` from a frame that failed to parse.
+///
+/// If the frame is a well-formed JSON object, its top-level `id` is used.
+/// Otherwise the (lossily decoded) text is scanned for the first `"id": <value>`
+/// pair whose value is a string or an integer. Returns `None` when nothing
+/// usable is found.
+#[must_use]
+pub fn salvage_request_id(frame: &[u8]) -> Option<RequestId> {
+    if let Ok(serde_json::Value::Object(map)) = serde_json::from_slice(frame) {
+        return match map.get("id")? {
+            serde_json::Value::Number(n) => n.as_i64().map(RequestId::Number),
+            serde_json::Value::String(s) => Some(RequestId::String(s.clone())),
+            _ => None,
+        };
+    }
+
+    let text = String::from_utf8_lossy(frame);
+    let mut rest: &str = &text;
+    while let Some(pos) = rest.find("\"id\"") {
+        rest = &rest[pos + 4..];
+        if let Some(id) = scan_id_value(rest) {
+            return Some(id);
+        }
+    }
+    None
+}
+
+/// Parses `: <string-or-integer>` at the start of `s`, skipping whitespace.
+fn scan_id_value(s: &str) -> Option<RequestId> {
+    let s = s.trim_start().strip_prefix(':')?.trim_start();
+
+    if let Some(body) = s.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = body.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => return Some(RequestId::String(value)),
+                '\\' => value.push(chars.next()?),
+                _ => value.push(c),
+            }
+        }
+        return None;
+    }
+
+    let end = s
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || (i == 0 && c == '-')))
+        .map_or(s.len(), |(i, _)| i);
+    s[..end].parse().ok().map(RequestId::Number)
+}
+
 /// Codec error types.
 #[derive(Debug)]
 pub enum CodecError {
@@ -149,6 +451,15 @@ pub enum CodecError {
     Json(serde_json::Error),
     /// Message too large.
     MessageTooLarge(usize),
+    /// A frame could not be parsed as a JSON-RPC message.
+    ///
+    /// `id` is the request id salvaged from the raw frame, if any.
+    InvalidFrame {
+        /// Request id recovered from the frame.
+        id: Option<RequestId>,
+        /// Why the frame was rejected.
+        reason: String,
+    },
 }
 
 impl std::fmt::Display for CodecError {
@@ -156,6 +467,7 @@ impl std::fmt::Display for CodecError {
         match self {
             CodecError::Json(e) => write!(f, "JSON error: {e}"),
             CodecError::MessageTooLarge(size) => write!(f, "Message too large: {size} bytes"),
+            CodecError::InvalidFrame { reason, .. } => write!(f, "Invalid frame: {reason}"),
         }
     }
 }
@@ -164,7 +476,7 @@ impl std::error::Error for CodecError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             CodecError::Json(e) => Some(e),
-            CodecError::MessageTooLarge(_) => None,
+            CodecError::MessageTooLarge(_) | CodecError::InvalidFrame { .. } => None,
         }
     }
 }
@@ -194,6 +506,82 @@ mod tests {
         assert_eq!(messages.len(), 1);
     }
 
+    /// A request whose pretty-printed body spans several lines.
+    fn multiline_frame(request: &JsonRpcRequest) -> (Vec<u8>, Vec<u8>) {
+        let body = serde_json::to_vec_pretty(request).unwrap();
+        assert!(body.contains(&b'\n'));
+        let mut frame = format!("Content-Length: {}\r\n\r\n", body.len()).into_bytes();
+        frame.extend_from_slice(&body);
+        (body, frame)
+    }
+
+    #[test]
+    fn test_content_length_roundtrip() {
+        let codec = Codec::with_frame_mode(FrameMode::ContentLength);
+        assert_eq!(codec.frame_mode(), FrameMode::ContentLength);
+        let request = JsonRpcRequest::new("test/method", None, 1i64);
+        let body = serde_json::to_vec(&request).unwrap();
+
+        let encoded = codec.encode_request(&request).unwrap();
+        let header = format!("Content-Length: {}\r\n\r\n", body.len());
+        assert!(encoded.starts_with(header.as_bytes()));
+        assert!(encoded.ends_with(&body));
+
+        // Split mid-header and mid-body; two frames back to back.
+        let mut stream = encoded.clone();
+        stream.extend_from_slice(&encoded);
+        let mut decoder = Codec::with_frame_mode(FrameMode::ContentLength);
+        assert!(decoder.decode(&stream[..10]).unwrap().is_empty());
+        assert!(
+            decoder
+                .decode(&stream[10..header.len() + 5])
+                .unwrap()
+                .is_empty()
+        );
+        let messages = decoder.decode(&stream[header.len() + 5..]).unwrap();
+        assert_eq!(messages.len(), 2);
+    }
+
+    #[test]
+    fn test_content_length_allows_newlines_in_body() {
+        let request = JsonRpcRequest::new("test/method", Some(serde_json::json!({"a": 1})), 7i64);
+        let (body, frame) = multiline_frame(&request);
+
+        let mut decoder = Codec::with_frame_mode(FrameMode::ContentLength);
+        let messages = decoder.decode(&frame).unwrap();
+        assert!(matches!(
+            &messages[..],
+            [JsonRpcMessage::Request(req)] if req.method == "test/method"
+        ));
+
+        // The same body cannot be carried as NDJSON.
+        let mut ndjson = Codec::new();
+        let mut line = body;
+        line.push(b'\n');
+        assert!(ndjson.decode(&line).is_err());
+    }
+
+    #[test]
+    fn test_content_length_headers() {
+        assert_eq!(
+            parse_content_length_header(b"content-length: 42\r").unwrap(),
+            Some(42)
+        );
+        assert_eq!(
+            parse_content_length_header(b"Content-Type: application/vscode-jsonrpc").unwrap(),
+            None
+        );
+        assert!(parse_content_length_header(b"Content-Length: lots").is_err());
+        assert!(parse_content_length_header(b"not a header").is_err());
+
+        let mut decoder = Codec::with_frame_mode(FrameMode::ContentLength);
+        let frame = b"Content-Type: application/json\r\n\r\n{}";
+        assert!(matches!(
+            decoder.decode(frame),
+            Err(CodecError::InvalidFrame { .. })
+        ));
+    }
+
     #[test]
     fn test_encode_response() {
         let codec = Codec::new();
@@ -216,6 +604,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_canonical_encoding_sorts_keys() {
+        let response =
+            JsonRpcResponse::success(RequestId::Number(1), serde_json::json!({"b": 1, "a": 2}));
+
+        let mut codec = Codec::new();
+        assert!(!codec.is_canonical());
+        let default = codec.encode_response(&response).unwrap();
+        assert!(default.starts_with(br#"{"jsonrpc""#));
+
+        codec.set_canonical(true);
+        let canonical = codec.encode_response(&response).unwrap();
+        assert_eq!(
+            canonical,
+            b"{\"id\":1,\"jsonrpc\":\"2.0\",\"result\":{\"a\":2,\"b\":1}}\n"
+        );
+    }
+
     #[test]
     fn test_decode_multiple_messages() {
         let input = b"{\"jsonrpc\":\"2.0\",\"method\":\"test1\",\"id\":1}\n{\"jsonrpc\":\"2.0\",\"method\":\"test2\",\"id\":2}\n";
@@ -265,6 +671,72 @@ mod tests {
         assert_eq!(messages2.len(), 1);
     }
 
+    /// A request whose params wrap `"leaf"` in `levels` arrays, as raw bytes.
+    fn nested_request(levels: usize) -> Vec<u8> {
+        format!(
+            "{{\"jsonrpc\":\"2.0\",\"method\":\"deep\",\"id\":9,\"params\":{{\"a\":{}\"leaf\"{}}}}}",
+            "[".repeat(levels),
+            "]".repeat(levels)
+        )
+        .into_bytes()
+    }
+
+    #[test]
+    fn test_decode_rejects_deeply_nested_frame() {
+        let mut codec = Codec::new();
+        codec.set_max_depth(4);
+        // Message object, params and two arrays
+        let mut line = nested_request(2);
+        line.push(b'\n');
+        assert_eq!(codec.decode(&line).unwrap().len(), 1);
+
+        // Far beyond what the parser could recurse through
+        let mut line = nested_request(100_000);
+        line.push(b'\n');
+        let err = codec.decode(&line).unwrap_err();
+        assert!(
+            matches!(&err, CodecError::InvalidFrame { id: Some(RequestId::Number(9)), reason }
+                if reason.contains("nested too deeply")),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn test_content_length_rejects_deeply_nested_frame() {
+        let body = nested_request(3);
+        let mut frame = format!("Content-Length: {}\r\n\r\n", body.len()).into_bytes();
+        frame.extend_from_slice(&body);
+
+        let mut codec = Codec::with_frame_mode(FrameMode::ContentLength);
+        codec.set_max_depth(4);
+        assert!(matches!(
+            codec.decode(&frame),
+            Err(CodecError::InvalidFrame { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_frame_enforces_limits() {
+        let mut codec = Codec::new();
+        codec.set_max_depth(4);
+        assert!(codec.parse_frame(&nested_request(2)).is_ok());
+        assert!(matches!(
+            codec.parse_frame(&nested_request(3)),
+            Err(CodecError::InvalidFrame { .. })
+        ));
+
+        // Brackets inside strings don't nest
+        let quoted = br#"{"jsonrpc":"2.0","method":"m","id":1,"params":{"s":"[[[[\"{{"}}"#;
+        assert!(codec.parse_frame(quoted).is_ok());
+
+        let frame = nested_request(0);
+        codec.set_max_message_size(frame.len() - 1);
+        assert!(matches!(
+            codec.parse_frame(&frame),
+            Err(CodecError::MessageTooLarge(len)) if len == frame.len()
+        ));
+    }
+
     #[test]
     fn test_decode_rejects_oversized_incomplete_line() {
         let req = JsonRpcRequest::new("oversized", None, 1i64);
@@ -363,4 +835,44 @@ mod tests {
         assert!(json_err.source().is_some());
         assert!(size_err.source().is_none());
     }
+
+    #[test]
+    fn test_decode_frame_reports_salvaged_id() {
+        let err = decode_frame(b"{\"jsonrpc\":\"2.0\",\"id\":7,\"method\":").unwrap_err();
+        match err {
+            CodecError::InvalidFrame { id, .. } => assert_eq!(id, Some(RequestId::Number(7))),
+            other => panic!("expected InvalidFrame, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_decode_frame_invalid_utf8() {
+        let mut frame = b"{\"jsonrpc\":\"2.0\",\"id\":\"abc\",\"method\":\"".to_vec();
+        frame.extend_from_slice(&[0xff, 0xfe]);
+        frame.extend_from_slice(b"\"}");
+
+        let err = decode_frame(&frame).unwrap_err();
+        assert!(matches!(
+            err,
+            CodecError::InvalidFrame { id: Some(RequestId::String(ref s)), .. } if s == "abc"
+        ));
+    }
+
+    #[test]
+    fn test_salvage_request_id() {
+        assert_eq!(
+            salvage_request_id(br#"{"id": "req-1", "method": 42}"#),
+            Some(RequestId::String("req-1".to_string()))
+        );
+        assert_eq!(
+            salvage_request_id(br#"{"id" : -3, "method": "x""#),
+            Some(RequestId::Number(-3))
+        );
+        assert_eq!(
+            salvage_request_id(br#"{"id":"a\"b","#),
+            Some(RequestId::String("a\"b".to_string()))
+        );
+        assert_eq!(salvage_request_id(br#"{"id": null}"#), None);
+        assert_eq!(salvage_request_id(b"not json at all"), None);
+    }
 }
@@ -0,0 +1,255 @@
+//! HTTP content-encoding support (gzip and deflate).
+//!
+//! The HTTP and SSE transports use this to compress response bodies for
+//! clients that send `Accept-Encoding`, and to decompress request bodies sent
+//! with `Content-Encoding`. Stdio is never compressed.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use fastmcp_transport::compression::{CompressionConfig, negotiate};
+//!
+//! let config = CompressionConfig::default();
+//! let response = HttpResponse::ok()
+//!     .with_json(&result)
+//!     .compressed(request.header("accept-encoding"), &config);
+//! ```
+
+use std::io::{self, Read, Write};
+
+use flate2::Compression;
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+
+/// When and how hard to compress response bodies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressionConfig {
+    /// Bodies smaller than this many bytes are sent uncompressed.
+    pub min_size: usize,
+    /// Compression level, from 0 (none) to 9 (best).
+    pub level: u32,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            min_size: 1024,
+            level: 6,
+        }
+    }
+}
+
+/// A supported `Content-Encoding`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentEncoding {
+    /// `gzip` (RFC 1952).
+    Gzip,
+    /// `deflate`, i.e. zlib-wrapped deflate (RFC 1950).
+    Deflate,
+}
+
+impl ContentEncoding {
+    /// Parses a single encoding token (case-insensitive).
+    #[must_use]
+    pub fn parse(token: &str) -> Option<Self> {
+        match token.trim().to_ascii_lowercase().as_str() {
+            "gzip" | "x-gzip" => Some(Self::Gzip),
+            "deflate" => Some(Self::Deflate),
+            _ => None,
+        }
+    }
+
+    /// Returns the header token for this encoding.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Deflate => "deflate",
+        }
+    }
+}
+
+/// Picks an encoding from an `Accept-Encoding` header value.
+///
+/// Prefers gzip over deflate, and skips encodings the client refused with
+/// `q=0`. A `*` wildcard accepts gzip unless it was refused. Returns `None` if neither is accepted.
+#[must_use]
+pub fn negotiate(accept_encoding: &str) -> Option<ContentEncoding> {
+    let mut gzip = false;
+    let mut deflate = false;
+    let mut wildcard = false;
+    let mut gzip_refused = false;
+    for entry in accept_encoding.split(',') {
+        let mut parts = entry.split(';');
+        let token = parts.next().unwrap_or("").trim();
+        let refused = parts.any(|param| {
+            param
+                .trim()
+                .strip_prefix("q=")
+                .and_then(|q| q.trim().parse::<f32>().ok())
+                .is_some_and(|q| q <= 0.0)
+        });
+        if refused {
+            gzip_refused |= ContentEncoding::parse(token) == Some(ContentEncoding::Gzip);
+            continue;
+        }
+        match ContentEncoding::parse(token) {
+            Some(ContentEncoding::Gzip) => gzip = true,
+            Some(ContentEncoding::Deflate) => deflate = true,
+            None if token == "*" => wildcard = true,
+            None => {}
+        }
+    }
+
+    if gzip || (wildcard && !gzip_refused) {
+        Some(ContentEncoding::Gzip)
+    } else if deflate {
+        Some(ContentEncoding::Deflate)
+    } else {
+        None
+    }
+}
+
+/// Compresses `body` with `encoding` at `level` (0-9).
+///
+/// # Errors
+///
+/// Returns an error if the encoder fails.
+pub fn compress(body: &[u8], encoding: ContentEncoding, level: u32) -> io::Result<Vec<u8>> {
+    let level = Compression::new(level.min(9));
+    match encoding {
+        ContentEncoding::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), level);
+            encoder.write_all(body)?;
+            encoder.finish()
+        }
+        ContentEncoding::Deflate => {
+            let mut encoder = ZlibEncoder::new(Vec::new(), level);
+            encoder.write_all(body)?;
+            encoder.finish()
+        }
+    }
+}
+
+/// Decompresses `body`, refusing output larger than `max_size` bytes.
+///
+/// # Errors
+///
+/// Returns [`io::ErrorKind::InvalidData`] if the body is not valid for
+/// `encoding` or decompresses to more than `max_size` bytes.
+pub fn decompress(body: &[u8], encoding: ContentEncoding, max_size: usize) -> io::Result<Vec<u8>> {
+    let limit = u64::try_from(max_size)
+        .unwrap_or(u64::MAX)
+        .saturating_add(1);
+    let mut out = Vec::new();
+    match encoding {
+        ContentEncoding::Gzip => GzDecoder::new(body).take(limit).read_to_end(&mut out)?,
+        ContentEncoding::Deflate => ZlibDecoder::new(body).take(limit).read_to_end(&mut out)?,
+    };
+    if out.len() > max_size {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("decompressed body exceeds limit {max_size}"),
+        ));
+    }
+    Ok(out)
+}
+
+/// A writer that compresses everything written through it, or passes it
+/// through unchanged.
+///
+/// Each [`flush`](Write::flush) emits all data written so far, so a
+/// compressed event stream still delivers events as they are written.
+pub enum CompressedWriter<W: Write> {
+    /// Writes gzip.
+    Gzip(GzEncoder<W>),
+    /// Writes zlib-wrapped deflate.
+    Deflate(ZlibEncoder<W>),
+    /// Writes through unchanged.
+    Identity(W),
+}
+
+impl<W: Write> CompressedWriter<W> {
+    /// Wraps `inner`, compressing with `encoding` at `level` if given.
+    #[must_use]
+    pub fn new(inner: W, encoding: Option<ContentEncoding>, level: u32) -> Self {
+        let level = Compression::new(level.min(9));
+        match encoding {
+            Some(ContentEncoding::Gzip) => Self::Gzip(GzEncoder::new(inner, level)),
+            Some(ContentEncoding::Deflate) => Self::Deflate(ZlibEncoder::new(inner, level)),
+            None => Self::Identity(inner),
+        }
+    }
+}
+
+impl<W: Write> Write for CompressedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Gzip(encoder) => encoder.write(buf),
+            Self::Deflate(encoder) => encoder.write(buf),
+            Self::Identity(inner) => inner.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Gzip(encoder) => encoder.flush(),
+            Self::Deflate(encoder) => encoder.flush(),
+            Self::Identity(inner) => inner.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiate_prefers_gzip_and_honours_q_zero() {
+        assert_eq!(negotiate("gzip, deflate"), Some(ContentEncoding::Gzip));
+        assert_eq!(negotiate("deflate, gzip"), Some(ContentEncoding::Gzip));
+        assert_eq!(negotiate("deflate"), Some(ContentEncoding::Deflate));
+        assert_eq!(
+            negotiate("gzip;q=0, deflate"),
+            Some(ContentEncoding::Deflate)
+        );
+        assert_eq!(negotiate("*"), Some(ContentEncoding::Gzip));
+        assert_eq!(negotiate("gzip;q=0, *"), None);
+        assert_eq!(negotiate("br, identity"), None);
+        assert_eq!(negotiate(""), None);
+    }
+
+    #[test]
+    fn compress_roundtrips() {
+        let body = b"hello hello hello hello hello hello".repeat(20);
+        for encoding in [ContentEncoding::Gzip, ContentEncoding::Deflate] {
+            let compressed = compress(&body, encoding, 6).unwrap();
+            assert!(compressed.len() < body.len());
+            assert_eq!(decompress(&compressed, encoding, body.len()).unwrap(), body);
+        }
+    }
+
+    #[test]
+    fn decompress_enforces_limit() {
+        let body = vec![b'a'; 4096];
+        let compressed = compress(&body, ContentEncoding::Gzip, 9).unwrap();
+        let err = decompress(&compressed, ContentEncoding::Gzip, 1024).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn compressed_writer_flushes_complete_chunks() {
+        let mut writer = CompressedWriter::new(Vec::new(), Some(ContentEncoding::Gzip), 6);
+        writer.write_all(b"event: message\n\n").unwrap();
+        writer.flush().unwrap();
+        let CompressedWriter::Gzip(encoder) = writer else {
+            panic!("expected gzip writer");
+        };
+        let flushed = encoder.get_ref().clone();
+
+        // Everything written before the flush decodes without finishing the stream
+        let mut decoded = Vec::new();
+        let _ = GzDecoder::new(flushed.as_slice()).read_to_end(&mut decoded);
+        assert_eq!(decoded, b"event: message\n\n");
+    }
+}
@@ -54,8 +54,10 @@ use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use asupersync::Cx;
+use fastmcp_core::AuthChallenge;
 use fastmcp_protocol::{JsonRpcMessage, JsonRpcRequest, JsonRpcResponse};
 
+use crate::compression::{self, CompressionConfig, ContentEncoding};
 use crate::{Codec, CodecError, Transport, TransportError};
 
 // =============================================================================
@@ -211,6 +213,31 @@ impl HttpRequest {
     pub fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
         serde_json::from_slice(&self.body)
     }
+
+    /// Decompresses a body sent with `Content-Encoding: gzip` or `deflate`
+    /// in place and drops the header.
+    ///
+    /// Bodies without the header (or with `identity`) are left alone.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HttpError::InvalidEncoding`] for unsupported encodings,
+    /// corrupt bodies, or bodies that decompress to more than `max_size`
+    /// bytes.
+    pub fn decode_body(&mut self, max_size: usize) -> Result<(), HttpError> {
+        let Some(header) = self.header("content-encoding") else {
+            return Ok(());
+        };
+        if header.trim().eq_ignore_ascii_case("identity") {
+            return Ok(());
+        }
+        let encoding = ContentEncoding::parse(header)
+            .ok_or_else(|| HttpError::InvalidEncoding(header.to_string()))?;
+        self.body = compression::decompress(&self.body, encoding, max_size)
+            .map_err(|e| HttpError::InvalidEncoding(e.to_string()))?;
+        self.headers.remove("content-encoding");
+        Ok(())
+    }
 }
 
 /// Outgoing HTTP response.
@@ -279,6 +306,31 @@ impl HttpResponse {
         self
     }
 
+    /// Compresses the body if the client accepts gzip or deflate and the body
+    /// is at least [`CompressionConfig::min_size`] bytes.
+    ///
+    /// Sets `Content-Encoding` and `Vary: Accept-Encoding` when compressing.
+    /// Bodies that already carry a `Content-Encoding` are left alone.
+    #[must_use]
+    pub fn compressed(mut self, accept_encoding: Option<&str>, config: &CompressionConfig) -> Self {
+        if self.body.len() < config.min_size || self.headers.contains_key("content-encoding") {
+            return self;
+        }
+        let Some(encoding) = accept_encoding.and_then(compression::negotiate) else {
+            return self;
+        };
+        if let Ok(body) = compression::compress(&self.body, encoding, config.level) {
+            self.body = body;
+            self.headers.insert(
+                "content-encoding".to_string(),
+                encoding.as_str().to_string(),
+            );
+            self.headers
+                .insert("vary".to_string(), "accept-encoding".to_string());
+        }
+        self
+    }
+
     /// Sets CORS headers for cross-origin requests.
     #[must_use]
     pub fn with_cors(mut self, origin: &str) -> Self {
@@ -319,6 +371,8 @@ pub enum HttpError {
     Closed,
     /// Transport error.
     Transport(TransportError),
+    /// Unsupported or corrupt `Content-Encoding`.
+    InvalidEncoding(String),
 }
 
 impl std::fmt::Display for HttpError {
@@ -331,6 +385,7 @@ impl std::fmt::Display for HttpError {
             Self::Timeout => write!(f, "request timeout"),
             Self::Closed => write!(f, "connection closed"),
             Self::Transport(e) => write!(f, "transport error: {}", e),
+            Self::InvalidEncoding(e) => write!(f, "invalid content encoding: {}", e),
         }
     }
 }
@@ -370,8 +425,10 @@ pub struct HttpHandlerConfig {
     pub cors_origins: Vec<String>,
     /// Request timeout.
     pub timeout: Duration,
-    /// Maximum request body size in bytes.
+    /// Maximum request body size in bytes, after decompression.
     pub max_body_size: usize,
+    /// Response compression (None = never compress).
+    pub compression: Option<CompressionConfig>,
 }
 
 impl Default for HttpHandlerConfig {
@@ -382,6 +439,7 @@ impl Default for HttpHandlerConfig {
             cors_origins: vec!["*".to_string()],
             timeout: Duration::from_secs(30),
             max_body_size: 10 * 1024 * 1024, // 10 MB
+            compression: None,
         }
     }
 }
@@ -470,12 +528,22 @@ impl HttpRequestHandler {
             )));
         }
 
+        // Decompress a gzip/deflate body, keeping the size limit
+        if request.header("content-encoding").is_some() {
+            let mut decoded = request.clone();
+            decoded.decode_body(self.config.max_body_size)?;
+            return Ok(serde_json::from_slice(&decoded.body)?);
+        }
+
         // Parse JSON-RPC request
         let json_rpc: JsonRpcRequest = serde_json::from_slice(&request.body)?;
         Ok(json_rpc)
     }
 
     /// Creates an HTTP response from a JSON-RPC response.
+    ///
+    /// Errors carrying an [`AuthChallenge`] are answered with
+    /// `401 Unauthorized` and a matching `WWW-Authenticate` header.
     #[must_use]
     pub fn create_response(
         &self,
@@ -488,6 +556,15 @@ impl HttpRequestHandler {
             .with_body(body)
             .with_header("content-type", "application/json");
 
+        let challenge = response
+            .error
+            .as_ref()
+            .and_then(|error| AuthChallenge::from_error_data(error.data.as_ref()));
+        if let Some(challenge) = challenge {
+            http_response.status = HttpStatus::UNAUTHORIZED;
+            http_response = http_response.with_header("www-authenticate", challenge.header_value());
+        }
+
         if self.config.allow_cors {
             if let Some(origin) = origin {
                 if self.is_origin_allowed(origin) {
@@ -499,6 +576,24 @@ impl HttpRequestHandler {
         http_response
     }
 
+    /// Creates the HTTP response to `request` from a JSON-RPC response.
+    ///
+    /// Like [`create_response`](Self::create_response), using the request's
+    /// `Origin`, and compresses the body if [`HttpHandlerConfig::compression`]
+    /// is set and the request's `Accept-Encoding` allows it.
+    #[must_use]
+    pub fn create_response_for(
+        &self,
+        request: &HttpRequest,
+        response: &JsonRpcResponse,
+    ) -> HttpResponse {
+        let http_response = self.create_response(response, request.header("origin"));
+        match &self.config.compression {
+            Some(config) => http_response.compressed(request.header("accept-encoding"), config),
+            None => http_response,
+        }
+    }
+
     /// Creates an error HTTP response.
     #[must_use]
     pub fn error_response(&self, status: HttpStatus, message: &str) -> HttpResponse {
@@ -551,6 +646,9 @@ impl<R: Read, W: Write> HttpTransport<R, W> {
 
     /// Reads an HTTP request from the reader.
     ///
+    /// Bodies sent with `Content-Encoding: gzip` or `deflate` are
+    /// decompressed (see [`HttpRequest::decode_body`]).
+    ///
     /// This is a simplified HTTP parser for demonstration.
     /// In production, use a proper HTTP parsing library.
     pub fn read_request(&mut self) -> Result<HttpRequest, HttpError> {
@@ -623,24 +721,29 @@ impl<R: Read, W: Write> HttpTransport<R, W> {
                 .map_err(|e| HttpError::Transport(e.into()))?;
         }
 
-        Ok(HttpRequest {
+        let mut request = HttpRequest {
             method,
             path,
             headers,
             body,
             query: HashMap::new(),
-        })
+        };
+        request.decode_body(self.codec.max_message_size())?;
+        Ok(request)
     }
 
     /// Writes an HTTP response to the writer.
     pub fn write_response(&mut self, response: &HttpResponse) -> Result<(), HttpError> {
         let status_text = match response.status.0 {
             200 => "OK",
+            202 => "Accepted",
             400 => "Bad Request",
             401 => "Unauthorized",
             403 => "Forbidden",
             404 => "Not Found",
+            405 => "Method Not Allowed",
             500 => "Internal Server Error",
+            503 => "Service Unavailable",
             _ => "Unknown",
         };
 
@@ -1308,6 +1411,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn e2e_http_auth_challenge_sets_www_authenticate() {
+        let handler = HttpRequestHandler::new();
+        let challenge = AuthChallenge {
+            resource_metadata: Some(
+                "https://mcp.example.com/.well-known/oauth-protected-resource".to_string(),
+            ),
+            ..AuthChallenge::default()
+        };
+        let error = challenge.attach(fastmcp_core::McpError::new(
+            fastmcp_core::McpErrorCode::ResourceForbidden,
+            "Missing access token",
+        ));
+        let response =
+            JsonRpcResponse::error(Some(fastmcp_protocol::RequestId::Number(1)), error.into());
+
+        let http_response = handler.create_response(&response, None);
+        assert_eq!(http_response.status, HttpStatus::UNAUTHORIZED);
+        assert_eq!(
+            http_response
+                .headers
+                .get("www-authenticate")
+                .map(String::as_str),
+            Some(
+                "Bearer resource_metadata=\"https://mcp.example.com/.well-known/oauth-protected-resource\""
+            )
+        );
+    }
+
     #[test]
     fn e2e_http_cors_handling() {
         let config = HttpHandlerConfig {
@@ -1468,4 +1600,108 @@ mod tests {
         // The error message contains "body size"
         assert!(matches!(result, Err(HttpError::InvalidContentType(_))));
     }
+
+    fn compressing_handler() -> HttpRequestHandler {
+        HttpRequestHandler::with_config(HttpHandlerConfig {
+            compression: Some(CompressionConfig {
+                min_size: 256,
+                level: 6,
+            }),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn e2e_http_large_response_is_compressed_and_roundtrips() {
+        let handler = compressing_handler();
+        let request = HttpRequest::new(HttpMethod::Post, "/mcp/v1")
+            .with_header("Accept-Encoding", "gzip, deflate");
+        let response = JsonRpcResponse::success(
+            fastmcp_protocol::RequestId::Number(1),
+            serde_json::json!({"text": "lorem ipsum ".repeat(200)}),
+        );
+        let plain = handler.create_response(&response, None).body;
+
+        let http_response = handler.create_response_for(&request, &response);
+        assert_eq!(
+            http_response
+                .headers
+                .get("content-encoding")
+                .map(String::as_str),
+            Some("gzip")
+        );
+        assert!(http_response.body.len() < plain.len());
+
+        let decoded =
+            compression::decompress(&http_response.body, ContentEncoding::Gzip, usize::MAX)
+                .unwrap();
+        assert_eq!(decoded, plain);
+    }
+
+    #[test]
+    fn e2e_http_small_response_stays_uncompressed() {
+        let handler = compressing_handler();
+        let request =
+            HttpRequest::new(HttpMethod::Post, "/mcp/v1").with_header("Accept-Encoding", "gzip");
+        let response = JsonRpcResponse::success(
+            fastmcp_protocol::RequestId::Number(1),
+            serde_json::json!({"ok": true}),
+        );
+
+        let http_response = handler.create_response_for(&request, &response);
+        assert!(!http_response.headers.contains_key("content-encoding"));
+        assert_eq!(
+            http_response.body,
+            handler.create_response(&response, None).body
+        );
+
+        // Large bodies are not compressed for clients that did not ask
+        let large = JsonRpcResponse::success(
+            fastmcp_protocol::RequestId::Number(2),
+            serde_json::json!({"text": "lorem ipsum ".repeat(200)}),
+        );
+        let plain_request = HttpRequest::new(HttpMethod::Post, "/mcp/v1");
+        let http_response = handler.create_response_for(&plain_request, &large);
+        assert!(!http_response.headers.contains_key("content-encoding"));
+    }
+
+    #[test]
+    fn e2e_http_compressed_request_body_is_decoded() {
+        use std::io::Cursor;
+
+        let request = JsonRpcRequest::new("tools/list", None, 7i64);
+        let json = serde_json::to_vec(&request).unwrap();
+        let body = compression::compress(&json, ContentEncoding::Deflate, 6).unwrap();
+
+        let http_request = HttpRequest::new(HttpMethod::Post, "/mcp/v1")
+            .with_header("Content-Type", "application/json")
+            .with_header("Content-Encoding", "deflate")
+            .with_body(body.clone());
+        let parsed = HttpRequestHandler::new()
+            .parse_request(&http_request)
+            .unwrap();
+        assert_eq!(parsed.method, "tools/list");
+
+        let mut raw = format!(
+            "POST /mcp/v1 HTTP/1.1\r\nContent-Type: application/json\r\nContent-Encoding: deflate\r\nContent-Length: {}\r\n\r\n",
+            body.len()
+        )
+        .into_bytes();
+        raw.extend_from_slice(&body);
+        let mut output = Vec::new();
+        let read = HttpTransport::new(Cursor::new(raw), &mut output)
+            .read_request()
+            .unwrap();
+        assert_eq!(read.body, json);
+        assert!(read.header("content-encoding").is_none());
+
+        let bogus = HttpRequest::new(HttpMethod::Post, "/mcp/v1")
+            .with_header("Content-Type", "application/json")
+            .with_header("Content-Encoding", "br")
+            .with_body(json);
+        assert!(matches!(
+            HttpRequestHandler::new().parse_request(&bogus),
+            Err(HttpError::InvalidEncoding(_))
+        ));
+    }
 }
@@ -25,6 +25,7 @@
 
 mod async_io;
 mod codec;
+pub mod compression;
 pub mod event_store;
 pub mod http;
 pub mod memory;
@@ -34,7 +35,7 @@ pub mod websocket;
 
 pub use async_io::{AsyncLineReader, AsyncStdin, AsyncStdout};
 
-pub use codec::{Codec, CodecError};
+pub use codec::{Codec, CodecError, FrameMode, decode_frame, salvage_request_id};
 pub use stdio::{AsyncStdioTransport, StdioTransport};
 
 use asupersync::Cx;
@@ -111,6 +112,28 @@ pub trait Transport {
     fn close(&mut self) -> Result<(), TransportError>;
 }
 
+impl<T: Transport + ?Sized> Transport for Box<T> {
+    fn send(&mut self, cx: &Cx, message: &JsonRpcMessage) -> Result<(), TransportError> {
+        (**self).send(cx, message)
+    }
+
+    fn recv(&mut self, cx: &Cx) -> Result<JsonRpcMessage, TransportError> {
+        (**self).recv(cx)
+    }
+
+    fn send_request(&mut self, cx: &Cx, request: &JsonRpcRequest) -> Result<(), TransportError> {
+        (**self).send_request(cx, request)
+    }
+
+    fn send_response(&mut self, cx: &Cx, response: &JsonRpcResponse) -> Result<(), TransportError> {
+        (**self).send_response(cx, response)
+    }
+
+    fn close(&mut self) -> Result<(), TransportError> {
+        (**self).close()
+    }
+}
+
 /// Transport error types.
 #[derive(Debug)]
 pub enum TransportError {
@@ -675,6 +675,19 @@ impl<W: Write, R: Iterator<Item = JsonRpcRequest>> SseServerTransport<W, R> {
         }
     }
 
+    /// Sends the endpoint event now instead of with the first message.
+    ///
+    /// Clients wait for this event before they POST anything, so servers
+    /// that accept the stream themselves call this right after the response
+    /// headers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if cancelled or the write fails.
+    pub fn send_endpoint(&mut self, cx: &Cx) -> Result<(), TransportError> {
+        self.ensure_endpoint_sent(cx)
+    }
+
     /// Sends the endpoint event if not already sent.
     fn ensure_endpoint_sent(&mut self, cx: &Cx) -> Result<(), TransportError> {
         if !self.endpoint_sent {
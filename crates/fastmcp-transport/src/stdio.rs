@@ -1,7 +1,8 @@
 //! Standard I/O transport for MCP.
 //!
 //! This is the primary transport for MCP servers running as subprocess.
-//! Uses newline-delimited JSON (NDJSON) framing.
+//! Uses newline-delimited JSON (NDJSON) framing, or LSP-style
+//! `Content-Length` framing via [`StdioTransport::with_frame_mode`].
 //!
 //! # Cancel-Safety
 //!
@@ -44,7 +45,10 @@ use asupersync::{Budget, Cx};
 use fastmcp_protocol::{JsonRpcMessage, JsonRpcRequest, JsonRpcResponse};
 
 use crate::async_io::{AsyncLineReader, AsyncStdout};
-use crate::{Codec, CodecError, SendPermit, Transport, TransportError, TwoPhaseTransport};
+use crate::codec::parse_content_length_header;
+use crate::{
+    Codec, CodecError, FrameMode, SendPermit, Transport, TransportError, TwoPhaseTransport,
+};
 
 /// Stdio transport implementation.
 ///
@@ -58,11 +62,14 @@ use crate::{Codec, CodecError, SendPermit, Transport, TransportError, TwoPhaseTr
 /// - Lines are terminated by `\n` (LF, not CRLF)
 /// - Empty lines are ignored
 /// - UTF-8 encoding is required
+///
+/// With [`FrameMode::ContentLength`], each message is instead preceded by a
+/// `Content-Length: N\r\n\r\n` header, so bodies may contain newlines.
 pub struct StdioTransport<R, W> {
     reader: BufReader<R>,
     writer: W,
     codec: Codec,
-    line_buffer: String,
+    line_buffer: Vec<u8>,
 }
 
 impl<R: Read, W: Write> StdioTransport<R, W> {
@@ -75,10 +82,51 @@ impl<R: Read, W: Write> StdioTransport<R, W> {
             reader: BufReader::new(reader),
             writer,
             codec: Codec::new(),
-            line_buffer: String::with_capacity(4096),
+            line_buffer: Vec::with_capacity(4096),
         }
     }
 
+    /// Switches the transport to the given framing (NDJSON by default).
+    #[must_use]
+    pub fn with_frame_mode(mut self, frame_mode: FrameMode) -> Self {
+        let max_message_size = self.codec.max_message_size();
+        let max_depth = self.codec.max_depth();
+        let canonical = self.codec.is_canonical();
+        self.codec = Codec::with_frame_mode(frame_mode);
+        self.codec.set_max_message_size(max_message_size);
+        self.codec.set_max_depth(max_depth);
+        self.codec.set_canonical(canonical);
+        self
+    }
+
+    /// Rejects incoming messages larger than `bytes` (10MB by default).
+    #[must_use]
+    pub fn with_max_message_size(mut self, bytes: usize) -> Self {
+        self.codec.set_max_message_size(bytes);
+        self
+    }
+
+    /// Rejects incoming messages nesting deeper than `depth` (see
+    /// [`Codec::set_max_depth`]).
+    #[must_use]
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.codec.set_max_depth(depth);
+        self
+    }
+
+    /// Writes outgoing messages as canonical JSON (see [`Codec::set_canonical`]).
+    #[must_use]
+    pub fn with_canonical_json(mut self, canonical: bool) -> Self {
+        self.codec.set_canonical(canonical);
+        self
+    }
+
+    /// Returns how this transport delimits messages.
+    #[must_use]
+    pub fn frame_mode(&self) -> FrameMode {
+        self.codec.frame_mode()
+    }
+
     /// Encodes and sends a message, appending newline.
     fn write_message(&mut self, message: &JsonRpcMessage) -> Result<(), TransportError> {
         let bytes = match message {
@@ -91,31 +139,70 @@ impl<R: Read, W: Write> StdioTransport<R, W> {
     }
 
     /// Reads a line from the reader, handling EOF.
-    fn read_line(&mut self) -> Result<&str, TransportError> {
+    ///
+    /// The line is returned as raw bytes so that frames which are not valid
+    /// UTF-8 can still be inspected for a request id.
+    fn read_line(&mut self) -> Result<&[u8], TransportError> {
         self.line_buffer.clear();
-        let bytes_read = self.reader.read_line(&mut self.line_buffer)?;
+        let bytes_read = self.reader.read_until(b'\n', &mut self.line_buffer)?;
 
         if bytes_read == 0 {
             return Err(TransportError::Closed);
         }
 
         // Trim trailing newline
-        let line_len = {
-            let line = self
-                .line_buffer
-                .trim_end_matches('\n')
-                .trim_end_matches('\r');
-            line.len()
-        };
+        let mut line_len = self.line_buffer.len();
+        while line_len > 0 && matches!(self.line_buffer[line_len - 1], b'\n' | b'\r') {
+            line_len -= 1;
+        }
         if line_len > self.codec.max_message_size() {
             self.line_buffer.clear();
             return Err(TransportError::Codec(CodecError::MessageTooLarge(line_len)));
         }
-        let line = self
-            .line_buffer
-            .trim_end_matches('\n')
-            .trim_end_matches('\r');
-        Ok(line)
+        Ok(&self.line_buffer[..line_len])
+    }
+
+    /// Reads one `Content-Length` frame and returns its body.
+    ///
+    /// Blank lines before the headers are skipped. An oversized body is
+    /// consumed before the error is returned, so the next frame still parses.
+    fn read_content_length_frame(&mut self) -> Result<&[u8], TransportError> {
+        let mut saw_header = false;
+        let mut declared = None;
+        let length = loop {
+            let line = self.read_line()?;
+            if line.is_empty() {
+                if !saw_header {
+                    continue;
+                }
+                break declared.ok_or_else(|| CodecError::InvalidFrame {
+                    id: None,
+                    reason: "missing Content-Length header".to_string(),
+                })?;
+            }
+            saw_header = true;
+            if let Some(n) = parse_content_length_header(line)? {
+                declared = Some(n);
+            }
+        };
+
+        if length > self.codec.max_message_size() {
+            std::io::copy(
+                &mut (&mut self.reader).take(length as u64),
+                &mut std::io::sink(),
+            )?;
+            return Err(TransportError::Codec(CodecError::MessageTooLarge(length)));
+        }
+
+        self.line_buffer.clear();
+        self.line_buffer.resize(length, 0);
+        self.reader
+            .read_exact(&mut self.line_buffer)
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::UnexpectedEof => TransportError::Closed,
+                _ => TransportError::Io(e),
+            })?;
+        Ok(&self.line_buffer)
     }
 }
 
@@ -145,6 +232,11 @@ impl<R: Read, W: Write> Transport for StdioTransport<R, W> {
             return Err(TransportError::Cancelled);
         }
 
+        if self.codec.frame_mode() == FrameMode::ContentLength {
+            let body = self.read_content_length_frame()?;
+            return Ok(self.codec.parse_frame(body)?);
+        }
+
         // Read lines until we get a non-empty one
         loop {
             let line = self.read_line()?;
@@ -159,10 +251,7 @@ impl<R: Read, W: Write> Transport for StdioTransport<R, W> {
             }
 
             // Parse the JSON message
-            let message: JsonRpcMessage = serde_json::from_str(line)
-                .map_err(|e| TransportError::Codec(crate::CodecError::Json(e)))?;
-
-            return Ok(message);
+            return Ok(self.codec.parse_frame(line)?);
         }
     }
 
@@ -326,26 +415,22 @@ impl Transport for AsyncStdioTransport {
         let line = self
             .reader
             .read_non_empty_line(cx)
-            .map_err(|e| {
-                if e.kind() == std::io::ErrorKind::Interrupted {
-                    TransportError::Cancelled
-                } else {
-                    TransportError::Io(e)
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::Interrupted => TransportError::Cancelled,
+                // The line was consumed but is not UTF-8; there is no text
+                // left to salvage an id from.
+                std::io::ErrorKind::InvalidData => {
+                    TransportError::Codec(CodecError::InvalidFrame {
+                        id: None,
+                        reason: e.to_string(),
+                    })
                 }
+                _ => TransportError::Io(e),
             })?
             .ok_or(TransportError::Closed)?;
 
-        if line.len() > self.codec.max_message_size() {
-            return Err(TransportError::Codec(CodecError::MessageTooLarge(
-                line.len(),
-            )));
-        }
-
         // Parse the JSON message
-        let message: JsonRpcMessage = serde_json::from_str(&line)
-            .map_err(|e| TransportError::Codec(crate::CodecError::Json(e)))?;
-
-        Ok(message)
+        Ok(self.codec.parse_frame(line.as_bytes())?)
     }
 
     fn close(&mut self) -> Result<(), TransportError> {
@@ -455,6 +540,84 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_content_length_roundtrip() {
+        let cx = Cx::for_testing();
+        let mut sender = StdioTransport::new(Cursor::new(Vec::new()), Vec::new())
+            .with_frame_mode(FrameMode::ContentLength);
+        assert_eq!(sender.frame_mode(), FrameMode::ContentLength);
+
+        let first = JsonRpcRequest::new("first", Some(serde_json::json!({"text": "a\nb"})), 1i64);
+        let second = JsonRpcRequest::new("second", None, 2i64);
+        sender.send_request_direct(&cx, &first).unwrap();
+        sender.send(&cx, &JsonRpcMessage::Request(second)).unwrap();
+        let written = sender.writer.clone();
+        assert!(written.starts_with(b"Content-Length: "));
+
+        let mut receiver = StdioTransport::new(Cursor::new(written), Vec::new())
+            .with_frame_mode(FrameMode::ContentLength);
+        let JsonRpcMessage::Request(req) = receiver.recv(&cx).unwrap() else {
+            panic!("expected request");
+        };
+        assert_eq!(req.method, "first");
+        assert_eq!(req.params.unwrap()["text"], "a\nb");
+        let JsonRpcMessage::Request(req) = receiver.recv(&cx).unwrap() else {
+            panic!("expected request");
+        };
+        assert_eq!(req.method, "second");
+        assert!(matches!(receiver.recv(&cx), Err(TransportError::Closed)));
+    }
+
+    #[test]
+    fn test_content_length_body_with_newlines() {
+        let request = JsonRpcRequest::new("pretty", Some(serde_json::json!({"a": [1, 2]})), 3i64);
+        let body = serde_json::to_vec_pretty(&request).unwrap();
+        let mut input = b"\r\nContent-Type: application/json\r\n".to_vec();
+        input.extend_from_slice(format!("Content-Length: {}\r\n\r\n", body.len()).as_bytes());
+        input.extend_from_slice(&body);
+
+        let cx = Cx::for_testing();
+        let mut transport = StdioTransport::new(Cursor::new(input), Vec::new())
+            .with_frame_mode(FrameMode::ContentLength);
+        let JsonRpcMessage::Request(req) = transport.recv(&cx).unwrap() else {
+            panic!("expected request");
+        };
+        assert_eq!(req.method, "pretty");
+
+        // NDJSON framing splits the same body at its first newline.
+        let mut line = body;
+        line.push(b'\n');
+        let mut ndjson = StdioTransport::new(Cursor::new(line), Vec::new());
+        assert!(ndjson.recv(&cx).is_err());
+    }
+
+    #[test]
+    fn test_content_length_oversized_body_is_skipped() {
+        let big = JsonRpcRequest::new(
+            "big",
+            Some(serde_json::json!({"pad": "x".repeat(64)})),
+            1i64,
+        );
+        let small = JsonRpcRequest::new("small", None, 2i64);
+        let codec = Codec::with_frame_mode(FrameMode::ContentLength);
+        let mut input = codec.encode_request(&big).unwrap();
+        input.extend(codec.encode_request(&small).unwrap());
+
+        let cx = Cx::for_testing();
+        let mut transport = StdioTransport::new(Cursor::new(input), Vec::new())
+            .with_frame_mode(FrameMode::ContentLength);
+        transport.codec.set_max_message_size(48);
+
+        assert!(matches!(
+            transport.recv(&cx),
+            Err(TransportError::Codec(CodecError::MessageTooLarge(_)))
+        ));
+        let JsonRpcMessage::Request(req) = transport.recv(&cx).unwrap() else {
+            panic!("expected request");
+        };
+        assert_eq!(req.method, "small");
+    }
+
     #[test]
     fn test_send_message() {
         let reader = Cursor::new(Vec::new());
@@ -519,6 +682,31 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_recv_invalid_frame_then_recovers() {
+        let mut input = b"{\"jsonrpc\":\"2.0\",\"id\":9,\"method\":\"".to_vec();
+        input.extend_from_slice(&[0xc3, 0x28]);
+        input.extend_from_slice(b"\"}\n{\"jsonrpc\":\"2.0\",\"method\":\"next\",\"id\":10}\n");
+        let reader = Cursor::new(input);
+        let writer = Vec::new();
+
+        let mut transport = StdioTransport::new(reader, writer);
+
+        let cx = Cx::for_testing();
+        let result = transport.recv(&cx);
+        assert!(matches!(
+            result,
+            Err(TransportError::Codec(CodecError::InvalidFrame {
+                id: Some(fastmcp_protocol::RequestId::Number(9)),
+                ..
+            }))
+        ));
+
+        // The bad frame is consumed; the next line parses normally.
+        let msg = transport.recv(&cx).unwrap();
+        assert!(matches!(msg, JsonRpcMessage::Request(ref req) if req.method == "next"));
+    }
+
     #[test]
     fn test_cancellation_on_recv() {
         let input = b"{\"jsonrpc\":\"2.0\",\"method\":\"test\",\"id\":1}\n";
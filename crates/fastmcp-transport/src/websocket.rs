@@ -7,16 +7,21 @@
 //! # Wire Format
 //!
 //! MCP over WebSocket uses:
-//! - Text frames for JSON-RPC messages (one message per frame)
+//! - The `mcp` subprotocol ([`MCP_SUBPROTOCOL`])
+//! - Text frames for JSON-RPC messages (one message per frame); binary
+//!   frames can be selected with [`WsFrameMode::Binary`] for gateways that
+//!   require them
 //! - Standard JSON-RPC request/response format
 //! - Optional ping/pong for keep-alive
 //!
 //! # Architecture
 //!
 //! This implementation provides low-level WebSocket message framing.
-//! It does NOT include HTTP upgrade handling - that should be done by
-//! your HTTP server (e.g., hyper, axum, warp) before handing off the
-//! upgraded connection to this transport.
+//! The HTTP upgrade is usually done by your HTTP server (e.g., hyper, axum,
+//! warp) before handing off the upgraded connection to this transport. For
+//! plain byte streams such as an accepted `TcpStream`,
+//! [`WsTransportBuilder::accept`] performs the server side of the upgrade
+//! itself.
 //!
 //! # Example
 //!
@@ -42,9 +47,34 @@ use std::io::{BufReader, Read, Write};
 
 use asupersync::Cx;
 
+use crate::http::{HttpError, HttpMethod, HttpRequest, HttpTransport};
 use crate::{Codec, Transport, TransportError};
 use fastmcp_protocol::{JsonRpcMessage, JsonRpcRequest, JsonRpcResponse};
 
+/// The WebSocket subprotocol recommended for MCP (`Sec-WebSocket-Protocol: mcp`).
+pub const MCP_SUBPROTOCOL: &str = "mcp";
+
+/// GUID appended to `Sec-WebSocket-Key` to form the accept value (RFC 6455 §1.3).
+const WS_ACCEPT_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Computes the `Sec-WebSocket-Accept` value for a client's `Sec-WebSocket-Key`.
+#[must_use]
+pub fn accept_key(client_key: &str) -> String {
+    let mut input = client_key.trim().to_string();
+    input.push_str(WS_ACCEPT_GUID);
+    base64_encode(&sha1(input.as_bytes()))
+}
+
+/// How [`WsTransport`] frames outgoing JSON-RPC messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WsFrameMode {
+    /// One text frame per message (the MCP default).
+    #[default]
+    Text,
+    /// One binary frame per message, carrying the same UTF-8 JSON.
+    Binary,
+}
+
 /// WebSocket frame types.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WsFrameType {
@@ -111,6 +141,16 @@ impl WsFrame {
         }
     }
 
+    /// Creates a new binary frame with the given payload.
+    #[must_use]
+    pub fn binary(payload: Vec<u8>) -> Self {
+        Self {
+            frame_type: WsFrameType::Binary,
+            payload,
+            fin: true,
+        }
+    }
+
     /// Creates a new close frame.
     #[must_use]
     pub fn close() -> Self {
@@ -335,7 +375,8 @@ impl<W: Write> WsWriter<W> {
 /// WebSocket transport for MCP.
 ///
 /// Provides bidirectional message passing over WebSocket.
-/// Messages are JSON-RPC encoded as text frames.
+/// Messages are JSON-RPC encoded as text frames, or binary frames when
+/// built with [`WsFrameMode::Binary`] via [`WsTransport::builder`].
 ///
 /// # Example
 ///
@@ -358,10 +399,22 @@ pub struct WsTransport<R, W> {
     codec: Codec,
     fragment_buffer: Vec<u8>,
     max_message_size: usize,
+    frame_mode: WsFrameMode,
+    subprotocol: Option<String>,
+}
+
+impl WsTransport<(), ()> {
+    /// Creates a builder for configuring the subprotocol and frame mode.
+    #[must_use]
+    pub fn builder() -> WsTransportBuilder {
+        WsTransportBuilder::new()
+    }
 }
 
 impl<R: Read, W: Write> WsTransport<R, W> {
     /// Creates a new WebSocket transport.
+    ///
+    /// Uses text frames and assumes no subprotocol was negotiated.
     pub fn new(reader: R, writer: W) -> Self {
         Self {
             reader: WsReader::new(reader),
@@ -369,9 +422,23 @@ impl<R: Read, W: Write> WsTransport<R, W> {
             codec: Codec::new(),
             fragment_buffer: Vec::new(),
             max_message_size: 10 * 1024 * 1024,
+            frame_mode: WsFrameMode::Text,
+            subprotocol: None,
         }
     }
 
+    /// Returns the subprotocol negotiated during the upgrade, if any.
+    #[must_use]
+    pub fn subprotocol(&self) -> Option<&str> {
+        self.subprotocol.as_deref()
+    }
+
+    /// Returns how outgoing messages are framed.
+    #[must_use]
+    pub fn frame_mode(&self) -> WsFrameMode {
+        self.frame_mode
+    }
+
     /// Sends a JSON-RPC message over the WebSocket.
     ///
     /// # Cancel-Safety
@@ -402,8 +469,10 @@ impl<R: Read, W: Write> WsTransport<R, W> {
         })?;
         let text = text.trim_end();
 
-        // Send as text frame
-        let frame = WsFrame::text(text);
+        let frame = match self.frame_mode {
+            WsFrameMode::Text => WsFrame::text(text),
+            WsFrameMode::Binary => WsFrame::binary(text.as_bytes().to_vec()),
+        };
         self.writer.write_frame(&frame)?;
 
         Ok(())
@@ -413,6 +482,8 @@ impl<R: Read, W: Write> WsTransport<R, W> {
     ///
     /// Handles control frames (ping/pong) automatically.
     /// Handles message fragmentation (Continuation frames).
+    /// Text frames are always accepted; binary frames carry messages only in
+    /// [`WsFrameMode::Binary`] and are skipped otherwise.
     ///
     /// # Cancel-Safety
     ///
@@ -432,14 +503,26 @@ impl<R: Read, W: Write> WsTransport<R, W> {
             let frame = self.reader.read_frame()?;
 
             match frame.frame_type {
-                WsFrameType::Text => {
+                WsFrameType::Text | WsFrameType::Binary => {
+                    // Per RFC 6455 Section 5.4, data frames MUST NOT be interleaved
+                    // during fragmentation. Reject if we're inside a fragmented message.
                     if !self.fragment_buffer.is_empty() {
                         return Err(TransportError::Io(std::io::Error::new(
                             std::io::ErrorKind::InvalidData,
-                            "Received Text frame while inside fragmented message",
+                            format!(
+                                "Received {:?} frame while inside fragmented message",
+                                frame.frame_type
+                            ),
                         )));
                     }
 
+                    if frame.frame_type == WsFrameType::Binary
+                        && self.frame_mode == WsFrameMode::Text
+                    {
+                        // Binary frames not used by MCP in text mode, skip
+                        continue;
+                    }
+
                     if frame.fin {
                         // Complete message in single frame
                         return self.decode_message(frame.payload);
@@ -490,18 +573,6 @@ impl<R: Read, W: Write> WsTransport<R, W> {
                     // More fragments to come
                     continue;
                 }
-                WsFrameType::Binary => {
-                    // Per RFC 6455 Section 5.4, data frames MUST NOT be interleaved
-                    // during fragmentation. Reject if we're inside a fragmented message.
-                    if !self.fragment_buffer.is_empty() {
-                        return Err(TransportError::Io(std::io::Error::new(
-                            std::io::ErrorKind::InvalidData,
-                            "Received Binary frame while inside fragmented message",
-                        )));
-                    }
-                    // Binary frames not used by MCP, skip otherwise
-                    continue;
-                }
                 WsFrameType::Close => {
                     return Err(TransportError::Closed);
                 }
@@ -590,6 +661,286 @@ impl<R: Read, W: Write> WsTransport<R, W> {
     }
 }
 
+/// Builder for [`WsTransport`].
+///
+/// When the HTTP upgrade happens outside this crate, pass the peer's
+/// `Sec-WebSocket-Protocol` header to [`peer_subprotocols`](Self::peer_subprotocols)
+/// to have [`build`](Self::build) check it against ours. On a raw stream,
+/// [`accept`](Self::accept) performs the upgrade and the check together.
+///
+/// # Example
+///
+/// ```ignore
+/// let transport = WsTransport::builder()
+///     .frame_mode(WsFrameMode::Binary)
+///     .peer_subprotocols(offered_header)
+///     .build(reader, writer)?;
+/// log::info!("WebSocket subprotocol: {:?}", transport.subprotocol());
+/// ```
+#[derive(Debug, Clone)]
+pub struct WsTransportBuilder {
+    subprotocol: String,
+    frame_mode: WsFrameMode,
+    peer_subprotocols: Option<String>,
+}
+
+impl Default for WsTransportBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WsTransportBuilder {
+    /// Creates a builder for the MCP subprotocol and text frames.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            subprotocol: MCP_SUBPROTOCOL.to_string(),
+            frame_mode: WsFrameMode::default(),
+            peer_subprotocols: None,
+        }
+    }
+
+    /// Sets the subprotocol this transport speaks (default [`MCP_SUBPROTOCOL`]).
+    #[must_use]
+    pub fn subprotocol(mut self, subprotocol: impl Into<String>) -> Self {
+        self.subprotocol = subprotocol.into();
+        self
+    }
+
+    /// Sets how outgoing messages are framed (default [`WsFrameMode::Text`]).
+    #[must_use]
+    pub fn frame_mode(mut self, frame_mode: WsFrameMode) -> Self {
+        self.frame_mode = frame_mode;
+        self
+    }
+
+    /// Records the peer's `Sec-WebSocket-Protocol` header from the upgrade.
+    ///
+    /// This is a comma-separated list: the protocols a client offered, or
+    /// the single protocol a server selected.
+    #[must_use]
+    pub fn peer_subprotocols(mut self, header: impl Into<String>) -> Self {
+        self.peer_subprotocols = Some(header.into());
+        self
+    }
+
+    /// Builds the transport.
+    ///
+    /// Without [`peer_subprotocols`](Self::peer_subprotocols) no subprotocol
+    /// is considered negotiated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the peer named subprotocols and ours is not
+    /// among them.
+    pub fn build<R: Read, W: Write>(
+        self,
+        reader: R,
+        writer: W,
+    ) -> Result<WsTransport<R, W>, TransportError> {
+        let subprotocol = self.negotiate()?;
+        let mut transport = WsTransport::new(reader, writer);
+        transport.frame_mode = self.frame_mode;
+        transport.subprotocol = subprotocol;
+        Ok(transport)
+    }
+
+    /// Performs the server side of the HTTP upgrade, then builds the transport.
+    ///
+    /// Reads the client's `GET` upgrade request from `reader`, takes the
+    /// offered subprotocols from its `Sec-WebSocket-Protocol` header, and
+    /// answers with `101 Switching Protocols`. Requests that cannot be
+    /// upgraded are answered with `400 Bad Request`.
+    ///
+    /// The request is read without buffering past its headers, so frames the
+    /// client sends right after the upgrade are not lost.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request is not a valid WebSocket upgrade, the
+    /// subprotocols are incompatible, or I/O fails.
+    pub fn accept<R: Read, W: Write>(
+        mut self,
+        mut reader: R,
+        mut writer: W,
+    ) -> Result<WsTransport<R, W>, TransportError> {
+        let request = HttpTransport::new(&mut reader, &mut writer)
+            .read_request()
+            .map_err(upgrade_error)?;
+
+        let checked = upgrade_key(&request).and_then(|key| {
+            if let Some(offered) = request.header("sec-websocket-protocol") {
+                self.peer_subprotocols = Some(offered.to_string());
+            }
+            Ok((key, self.negotiate()?))
+        });
+        let (key, subprotocol) = match checked {
+            Ok(checked) => checked,
+            Err(e) => {
+                // Best effort: the client may already have gone away.
+                let _ = writer.write_all(
+                    b"HTTP/1.1 400 Bad Request\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                );
+                let _ = writer.flush();
+                return Err(e);
+            }
+        };
+
+        let mut response = format!(
+            "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n",
+            accept_key(key)
+        );
+        if let Some(subprotocol) = &subprotocol {
+            response.push_str(&format!("Sec-WebSocket-Protocol: {subprotocol}\r\n"));
+        }
+        response.push_str("\r\n");
+        writer.write_all(response.as_bytes())?;
+        writer.flush()?;
+
+        let mut transport = WsTransport::new(reader, writer);
+        transport.frame_mode = self.frame_mode;
+        transport.subprotocol = subprotocol;
+        Ok(transport)
+    }
+
+    /// Checks the peer's subprotocols against ours.
+    fn negotiate(&self) -> Result<Option<String>, TransportError> {
+        let Some(header) = self.peer_subprotocols.as_deref() else {
+            return Ok(None);
+        };
+        if !header
+            .split(',')
+            .map(str::trim)
+            .any(|offered| offered == self.subprotocol)
+        {
+            return Err(TransportError::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "Incompatible WebSocket subprotocol: peer negotiated '{header}', expected '{}'",
+                    self.subprotocol
+                ),
+            )));
+        }
+        Ok(Some(self.subprotocol.clone()))
+    }
+}
+
+/// Validates a WebSocket upgrade request and returns its `Sec-WebSocket-Key`.
+fn upgrade_key(request: &HttpRequest) -> Result<&str, TransportError> {
+    let invalid = |reason: &str| {
+        TransportError::Io(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Invalid WebSocket upgrade: {reason}"),
+        ))
+    };
+
+    if request.method != HttpMethod::Get {
+        return Err(invalid("method must be GET"));
+    }
+    if !request
+        .header("upgrade")
+        .is_some_and(|value| value.eq_ignore_ascii_case("websocket"))
+    {
+        return Err(invalid("missing 'Upgrade: websocket' header"));
+    }
+    request
+        .header("sec-websocket-key")
+        .filter(|key| !key.trim().is_empty())
+        .ok_or_else(|| invalid("missing Sec-WebSocket-Key header"))
+}
+
+/// Maps an error reading the upgrade request to a transport error.
+fn upgrade_error(error: HttpError) -> TransportError {
+    match error {
+        HttpError::Closed => TransportError::Closed,
+        HttpError::Transport(e) => e,
+        other => TransportError::Io(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            other.to_string(),
+        )),
+    }
+}
+
+/// SHA-1 digest, used only for the `Sec-WebSocket-Accept` value.
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut state: [u32; 5] = [
+        0x6745_2301,
+        0xEFCD_AB89,
+        0x98BA_DCFE,
+        0x1032_5476,
+        0xC3D2_E1F0,
+    ];
+
+    let mut message = data.to_vec();
+    let bit_len = (data.len() as u64).wrapping_mul(8);
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&bit_len.to_be_bytes());
+
+    for block in message.chunks_exact(64) {
+        let mut w = [0u32; 80];
+        for (slot, word) in w.iter_mut().zip(block.chunks_exact(4)) {
+            *slot = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (i, word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A82_7999),
+                20..=39 => (b ^ c ^ d, 0x6ED9_EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1B_BCDC),
+                _ => (b ^ c ^ d, 0xCA62_C1D6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        for (value, add) in state.iter_mut().zip([a, b, c, d, e]) {
+            *value = value.wrapping_add(add);
+        }
+    }
+
+    let mut digest = [0u8; 20];
+    for (chunk, value) in digest.chunks_exact_mut(4).zip(state) {
+        chunk.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
+
+/// Standard (padded) base64, as used by the WebSocket handshake headers.
+fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b1 = chunk.get(1).copied().unwrap_or(0);
+        let b2 = chunk.get(2).copied().unwrap_or(0);
+        let n = (u32::from(chunk[0]) << 16) | (u32::from(b1) << 8) | u32::from(b2);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(char::from(ALPHABET[((n >> (18 - 6 * i)) & 0x3F) as usize]));
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
 impl<R: Read, W: Write> Transport for WsTransport<R, W> {
     fn send(&mut self, cx: &Cx, message: &JsonRpcMessage) -> Result<(), TransportError> {
         WsTransport::send(self, cx, message)
@@ -1209,6 +1560,152 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_binary_mode_sends_binary_frames() {
+        let cx = Cx::for_testing();
+        let mut output = Vec::new();
+        {
+            let mut transport = WsTransport::builder()
+                .frame_mode(WsFrameMode::Binary)
+                .build(Cursor::new(Vec::<u8>::new()), &mut output)
+                .unwrap();
+            assert_eq!(transport.frame_mode(), WsFrameMode::Binary);
+            let request = JsonRpcRequest::new("tools/list", None, 7i64);
+            transport.send_request(&cx, &request).unwrap();
+        }
+
+        let mut reader = WsReader::new_client(Cursor::new(output));
+        let frame = reader.read_frame().unwrap();
+        assert_eq!(frame.frame_type, WsFrameType::Binary);
+        let value: serde_json::Value = serde_json::from_slice(&frame.payload).unwrap();
+        assert_eq!(value["method"], "tools/list");
+        assert_eq!(value["id"], 7);
+    }
+
+    #[test]
+    fn test_binary_mode_receives_binary_frames() {
+        let json = br#"{"jsonrpc":"2.0","method":"ping","id":1}"#;
+        let mut buffer = build_masked_frame(0x02, false, &json[..10]);
+        buffer.extend(build_masked_frame(0x00, true, &json[10..]));
+
+        let cx = Cx::for_testing();
+        let mut transport = WsTransport::builder()
+            .frame_mode(WsFrameMode::Binary)
+            .build(Cursor::new(buffer), Vec::<u8>::new())
+            .unwrap();
+
+        match transport.recv(&cx).unwrap() {
+            JsonRpcMessage::Request(req) => assert_eq!(req.method, "ping"),
+            JsonRpcMessage::Response(_) => panic!("Expected request"),
+        }
+    }
+
+    #[test]
+    fn test_text_mode_skips_binary_frames() {
+        let mut buffer = build_masked_frame(0x02, true, b"ignored");
+        buffer.extend(build_masked_frame(
+            0x01,
+            true,
+            br#"{"jsonrpc":"2.0","method":"ping","id":1}"#,
+        ));
+
+        let cx = Cx::for_testing();
+        let mut transport = WsTransport::new(Cursor::new(buffer), Vec::<u8>::new());
+
+        match transport.recv(&cx).unwrap() {
+            JsonRpcMessage::Request(req) => assert_eq!(req.method, "ping"),
+            JsonRpcMessage::Response(_) => panic!("Expected request"),
+        }
+    }
+
+    #[test]
+    fn test_builder_defaults_to_mcp_subprotocol() {
+        let transport = WsTransport::builder()
+            .peer_subprotocols("chat, mcp")
+            .build(Cursor::new(Vec::<u8>::new()), Vec::<u8>::new())
+            .unwrap();
+        assert_eq!(transport.subprotocol(), Some(MCP_SUBPROTOCOL));
+        assert_eq!(transport.frame_mode(), WsFrameMode::Text);
+
+        let transport = WsTransport::builder()
+            .build(Cursor::new(Vec::<u8>::new()), Vec::<u8>::new())
+            .unwrap();
+        assert_eq!(transport.subprotocol(), None);
+    }
+
+    #[test]
+    fn test_builder_rejects_subprotocol_mismatch() {
+        let result = WsTransport::builder()
+            .subprotocol("mcp")
+            .peer_subprotocols("graphql-ws")
+            .build(Cursor::new(Vec::<u8>::new()), Vec::<u8>::new());
+
+        match result {
+            Err(TransportError::Io(e)) => {
+                assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+                assert!(e.to_string().contains("graphql-ws"));
+            }
+            Err(other) => panic!("unexpected error: {other:?}"),
+            Ok(_) => panic!("expected subprotocol mismatch"),
+        }
+    }
+
+    #[test]
+    fn test_accept_key_matches_rfc_example() {
+        // RFC 6455 §1.3
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    #[test]
+    fn test_accept_performs_upgrade() {
+        let mut input = b"GET /mcp HTTP/1.1\r\n\
+Host: localhost\r\n\
+Upgrade: websocket\r\n\
+Connection: Upgrade\r\n\
+Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+Sec-WebSocket-Protocol: chat, mcp\r\n\
+Sec-WebSocket-Version: 13\r\n\r\n"
+            .to_vec();
+        let ping = r#"{"jsonrpc":"2.0","method":"ping","id":1}"#;
+        input.extend(build_masked_frame(0x1, true, ping.as_bytes()));
+
+        let mut output = Vec::new();
+        let mut transport = WsTransport::builder()
+            .accept(Cursor::new(input), &mut output)
+            .unwrap();
+        assert_eq!(transport.subprotocol(), Some(MCP_SUBPROTOCOL));
+
+        // The frame sent right after the upgrade is still readable.
+        let cx = Cx::for_testing();
+        match transport.recv(&cx).unwrap() {
+            JsonRpcMessage::Request(req) => assert_eq!(req.method, "ping"),
+            JsonRpcMessage::Response(_) => panic!("Expected request"),
+        }
+        drop(transport);
+
+        let response = String::from_utf8(output).unwrap();
+        assert!(response.starts_with("HTTP/1.1 101 Switching Protocols\r\n"));
+        assert!(response.contains("Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo=\r\n"));
+        assert!(response.contains("Sec-WebSocket-Protocol: mcp\r\n"));
+    }
+
+    #[test]
+    fn test_accept_rejects_plain_request() {
+        let input = b"GET /mcp HTTP/1.1\r\nHost: localhost\r\n\r\n".to_vec();
+        let mut output = Vec::new();
+        let result = WsTransport::builder().accept(Cursor::new(input), &mut output);
+
+        assert!(matches!(result, Err(TransportError::Io(_))));
+        assert!(
+            String::from_utf8(output)
+                .unwrap()
+                .starts_with("HTTP/1.1 400")
+        );
+    }
+
     #[test]
     fn test_transport_roundtrip() {
         use fastmcp_protocol::RequestId;
@@ -53,9 +53,10 @@
 
 // Re-export core types
 pub use fastmcp_core::{
-    AUTH_STATE_KEY, AccessToken, AuthContext, Budget, CancelledError, Cx, IntoOutcome, LabConfig,
-    LabRuntime, McpContext, McpError, McpErrorCode, McpOutcome, McpResult, Outcome, OutcomeExt,
-    RegionId, ResultExt, Scope, TaskId, cancelled, err, ok,
+    AUTH_STATE_KEY, AccessToken, AuthChallenge, AuthContext, Budget, BudgetRemaining, CancelKind,
+    CancelledError, Cx, IntoOutcome, LabConfig, LabRuntime, McpContext, McpError, McpErrorCode,
+    McpOutcome, McpResult, MissingCapabilityPolicy, Outcome, OutcomeExt, RegionId, ResultExt,
+    Scope, TaskId, cancelled, err, ok,
 };
 
 // Re-export logging module
@@ -68,24 +69,30 @@ pub use fastmcp_protocol::{
     JsonRpcMessage, JsonRpcRequest, JsonRpcResponse, ListPromptsParams, ListPromptsResult,
     ListResourceTemplatesParams, ListResourceTemplatesResult, ListResourcesParams,
     ListResourcesResult, ListToolsParams, ListToolsResult, LogLevel, PROTOCOL_VERSION, Prompt,
-    PromptArgument, PromptMessage, ReadResourceParams, ReadResourceResult, Resource,
-    ResourceContent, ResourceTemplate, ResourcesCapability, Role, ServerCapabilities, ServerInfo,
-    SubscribeResourceParams, Tool, ToolsCapability, UnsubscribeResourceParams,
+    PromptArgument, PromptMessage, ReadResourceParams, ReadResourceResult, RegistryDocument,
+    Resource, ResourceContent, ResourceTemplate, ResourcesCapability, Role, ServerCapabilities,
+    ServerInfo, SubscribeResourceParams, TagMatch, TemplateParameter, Tool, ToolsCapability,
+    UnsubscribeResourceParams,
 };
 
 // Re-export transport types
-pub use fastmcp_transport::{Codec, StdioTransport, Transport, TransportError};
+pub use fastmcp_transport::{Codec, FrameMode, StdioTransport, Transport, TransportError};
 
 // Re-export transport modules
-pub use fastmcp_transport::{event_store, http, memory};
+pub use fastmcp_transport::{compression, event_store, http, memory};
 
 // Re-export server types
 #[cfg(feature = "jwt")]
 pub use fastmcp_server::JwtTokenVerifier;
+#[cfg(feature = "tower")]
+pub use fastmcp_server::McpService;
 pub use fastmcp_server::{
-    AllowAllAuthProvider, AuthProvider, AuthRequest, PromptHandler, ProxyBackend, ProxyCatalog,
-    ProxyClient, ResourceHandler, Router, Server, ServerBuilder, Session, SharedTaskManager,
-    StaticTokenVerifier, TaskManager, TokenAuthProvider, TokenVerifier, ToolHandler,
+    AllowAllAuthProvider, AuditEvent, AuditOutcome, AuditSink, AuthCachePolicy, AuthProvider,
+    AuthRequest, BuildInfo, Clock, FallbackToolHandler, FileAuditSink, FlushPolicy, HealthReport,
+    HealthStatus, PromptHandler, ProxyBackend, ProxyCatalog, ProxyClient, ResourceHandler, Router,
+    Server, ServerBuilder, ServerRunError, Session, SharedTaskManager, ShutdownReason,
+    StaticTokenVerifier, SystemClock, TaskManager, TestClock, TokenAuthProvider, TokenVerifier,
+    ToolHandler,
 };
 
 // Re-export server middleware modules
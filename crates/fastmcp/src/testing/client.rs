@@ -261,6 +261,9 @@ impl TestClient {
         self.ensure_initialized()?;
         let params = ReadResourceParams {
             uri: uri.to_string(),
+            range: None,
+            if_none_match: None,
+            if_modified_since: None,
             meta: None,
         };
         let result: ReadResourceResult = self.send_request("resources/read", params)?;
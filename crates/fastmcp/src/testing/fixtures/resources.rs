@@ -190,6 +190,7 @@ pub fn file_path_template() -> ResourceTemplate {
         icon: None,
         version: Some("1.0.0".to_string()),
         tags: vec!["file".to_string(), "template".to_string()],
+        parameters: vec![],
     }
 }
 
@@ -204,6 +205,7 @@ pub fn database_table_template() -> ResourceTemplate {
         icon: None,
         version: Some("1.0.0".to_string()),
         tags: vec!["database".to_string(), "template".to_string()],
+        parameters: vec![],
     }
 }
 
@@ -218,6 +220,7 @@ pub fn api_endpoint_template() -> ResourceTemplate {
         icon: None,
         version: Some("1.0.0".to_string()),
         tags: vec!["api".to_string(), "template".to_string()],
+        parameters: vec![],
     }
 }
 
@@ -232,6 +235,7 @@ pub fn user_profile_template() -> ResourceTemplate {
         icon: None,
         version: None,
         tags: vec!["user".to_string(), "profile".to_string()],
+        parameters: vec![],
     }
 }
 
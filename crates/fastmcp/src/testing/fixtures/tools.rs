@@ -148,7 +148,7 @@ pub fn slow_tool() -> Tool {
 /// use fastmcp::testing::fixtures::tools::file_write_tool;
 ///
 /// let tool = file_write_tool();
-/// assert!(tool.annotations.as_ref().unwrap().destructive.unwrap());
+/// assert!(tool.annotations.as_ref().unwrap().destructive_hint.unwrap());
 /// ```
 #[must_use]
 pub fn file_write_tool() -> Tool {
@@ -514,8 +514,8 @@ mod tests {
     fn test_file_write_tool_annotations() {
         let tool = file_write_tool();
         let annotations = tool.annotations.as_ref().unwrap();
-        assert_eq!(annotations.destructive, Some(true));
-        assert_eq!(annotations.idempotent, Some(false));
+        assert_eq!(annotations.destructive_hint, Some(true));
+        assert_eq!(annotations.idempotent_hint, Some(false));
     }
 
     #[test]
@@ -570,7 +570,7 @@ mod tests {
             .build();
 
         let annotations = tool.annotations.as_ref().unwrap();
-        assert_eq!(annotations.read_only, Some(true));
-        assert_eq!(annotations.idempotent, Some(true));
+        assert_eq!(annotations.read_only_hint, Some(true));
+        assert_eq!(annotations.idempotent_hint, Some(true));
     }
 }
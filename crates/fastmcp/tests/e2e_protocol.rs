@@ -158,6 +158,8 @@ impl ResourceHandler for TextFileResourceHandler {
             mime_type: Some("text/plain".to_string()),
             text: Some("Hello, World!\nThis is sample text content.".to_string()),
             blob: None,
+            etag: None,
+            last_modified: None,
         }])
     }
 }
@@ -189,6 +191,8 @@ impl ResourceHandler for JsonConfigResourceHandler {
             mime_type: Some("application/json".to_string()),
             text: Some(config.to_string()),
             blob: None,
+            etag: None,
+            last_modified: None,
         }])
     }
 }
@@ -155,6 +155,8 @@ impl ResourceHandler for StatusResourceHandler {
             mime_type: Some("application/json".to_string()),
             text: Some(status.to_string()),
             blob: None,
+            etag: None,
+            last_modified: None,
         }])
     }
 }
@@ -180,6 +182,8 @@ impl ResourceHandler for ReadmeResourceHandler {
             mime_type: Some("text/markdown".to_string()),
             text: Some("# Test Project\n\nThis is a test project.".to_string()),
             blob: None,
+            etag: None,
+            last_modified: None,
         }])
     }
 }
@@ -269,6 +273,7 @@ fn setup_workflow_server() -> TestClient {
             icon: None,
             version: None,
             tags: vec![],
+            parameters: vec![],
         })
         .prompt(HelpPromptHandler)
         .prompt(NoArgsPromptHandler)
@@ -698,8 +703,8 @@ fn workflow_tool_annotations_preserved() {
     let echo = tools.iter().find(|t| t.name == "echo").unwrap();
 
     let annotations = echo.annotations.as_ref().unwrap();
-    assert_eq!(annotations.read_only, Some(true));
-    assert_eq!(annotations.idempotent, Some(true));
+    assert_eq!(annotations.read_only_hint, Some(true));
+    assert_eq!(annotations.idempotent_hint, Some(true));
 }
 
 #[test]
@@ -2838,6 +2843,8 @@ impl ResourceHandler for PlainTextResourceHandler {
             mime_type: Some("text/plain".to_string()),
             text: Some("Hello, World!".to_string()),
             blob: None,
+            etag: None,
+            last_modified: None,
         }])
     }
 }
@@ -2872,6 +2879,8 @@ impl ResourceHandler for JsonResourceHandler {
             mime_type: Some("application/json".to_string()),
             text: Some(json.to_string()),
             blob: None,
+            etag: None,
+            last_modified: None,
         }])
     }
 }
@@ -2902,6 +2911,8 @@ impl ResourceHandler for BinaryResourceHandler {
             mime_type: Some("application/octet-stream".to_string()),
             text: None,
             blob: Some(blob),
+            etag: None,
+            last_modified: None,
         }])
     }
 }
@@ -2961,6 +2972,8 @@ impl ResourceHandler for UnicodeResourceHandler {
             mime_type: Some("text/plain; charset=utf-8".to_string()),
             text: Some("日本語 中文 العربية 🌍🌎🌏 Ελληνικά".to_string()),
             blob: None,
+            etag: None,
+            last_modified: None,
         }])
     }
 }
@@ -2988,6 +3001,8 @@ impl ResourceHandler for LargeContentResourceHandler {
             mime_type: Some("text/plain".to_string()),
             text: Some(large_content),
             blob: None,
+            etag: None,
+            last_modified: None,
         }])
     }
 }
@@ -3015,18 +3030,24 @@ impl ResourceHandler for MultiContentResourceHandler {
                 mime_type: Some("text/plain".to_string()),
                 text: Some("Part 1".to_string()),
                 blob: None,
+                etag: None,
+                last_modified: None,
             },
             ResourceContent {
                 uri: "data://multi/part2".to_string(),
                 mime_type: Some("text/plain".to_string()),
                 text: Some("Part 2".to_string()),
                 blob: None,
+                etag: None,
+                last_modified: None,
             },
             ResourceContent {
                 uri: "data://multi/part3".to_string(),
                 mime_type: Some("text/plain".to_string()),
                 text: Some("Part 3".to_string()),
                 blob: None,
+                etag: None,
+                last_modified: None,
             },
         ])
     }